@@ -1,5 +1,11 @@
+use crate::editorconfig::EditorConfigProperties;
 use crate::error::{EditorError, Result};
+use crate::folding::{indentation_fold_ranges, syntax_fold_ranges, FoldRange, FoldState};
+use crate::language::{LanguageDefinition, LanguageRegistry};
+use crate::syntax::{HighlightSpan, SyntaxHighlighter, SyntaxLanguage};
+use crate::text_change::{ChangeLog, TextChange};
 use ropey::Rope;
+use std::cell::RefCell;
 use std::path::PathBuf;
 use std::time::SystemTime;
 
@@ -12,8 +18,13 @@ pub enum LineEnding {
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Encoding {
     Utf8,
+    Utf8Bom,
 }
 
+const BOM: char = '\u{feff}';
+const MAX_HIGHLIGHT_BYTES: u64 = 10_000_000;
+const MODELINE_SAMPLE_LINES: usize = 5;
+
 impl LineEnding {
     pub fn as_str(&self) -> &str {
         match self {
@@ -44,6 +55,19 @@ pub struct Buffer {
     auto_save_enabled: bool,
     file_size: Option<u64>,
     memory_limit: Option<u64>,
+    editorconfig: EditorConfigProperties,
+    changes: ChangeLog,
+    syntax: Option<SyntaxHighlighter>,
+    language: Option<String>,
+    folds: FoldState,
+    fold_ranges: RefCell<Option<FoldRangeCache>>,
+}
+
+#[derive(Debug, Clone)]
+struct FoldRangeCache {
+    revision: u64,
+    tab_size: usize,
+    ranges: Vec<FoldRange>,
 }
 
 impl Buffer {
@@ -60,6 +84,12 @@ impl Buffer {
             auto_save_enabled: false,
             file_size: None,
             memory_limit: Some(500_000_000),
+            editorconfig: EditorConfigProperties::default(),
+            changes: ChangeLog::default(),
+            syntax: None,
+            language: None,
+            folds: FoldState::new(),
+            fold_ranges: RefCell::new(None),
         }
     }
 
@@ -78,10 +108,22 @@ impl Buffer {
             auto_save_enabled: false,
             file_size: Some(content.len() as u64),
             memory_limit: Some(500_000_000),
+            editorconfig: EditorConfigProperties::default(),
+            changes: ChangeLog::default(),
+            syntax: None,
+            language: None,
+            folds: FoldState::new(),
+            fold_ranges: RefCell::new(None),
         }
     }
 
     pub fn from_file(path: PathBuf) -> Result<Self> {
+        Self::from_file_with_languages(path, LanguageRegistry::builtin())
+    }
+
+    // Loads the file and picks its language from `languages`, so the syntax
+    // tree is only built once.
+    pub fn from_file_with_languages(path: PathBuf, languages: &LanguageRegistry) -> Result<Self> {
         let metadata = std::fs::metadata(&path)?;
         let file_size = metadata.len();
 
@@ -128,26 +170,40 @@ impl Buffer {
             return Err(EditorError::BinaryFile(path.to_string_lossy().to_string()));
         }
 
-        let normalized = normalize_line_endings(&content);
-        Ok(Self {
+        let (content, encoding) = match content.strip_prefix(BOM) {
+            Some(stripped) => (stripped, Encoding::Utf8Bom),
+            None => (content.as_str(), Encoding::Utf8),
+        };
+        let normalized = normalize_line_endings(content);
+        let editorconfig = EditorConfigProperties::resolve(&path);
+        let mut buffer = Self {
             rope: Rope::from_str(&normalized),
             file_path: Some(path),
             modified: false,
             line_ending,
-            encoding: Encoding::Utf8,
+            encoding,
             read_only,
             is_binary,
             last_saved: Some(SystemTime::now()),
             auto_save_enabled: false,
             file_size: Some(file_size),
             memory_limit: Some(memory_limit),
-        })
+            editorconfig,
+            changes: ChangeLog::default(),
+            syntax: None,
+            language: None,
+            folds: FoldState::new(),
+            fold_ranges: RefCell::new(None),
+        };
+        buffer.detect_language(languages);
+        Ok(buffer)
     }
 
     pub fn save(&mut self) -> Result<()> {
         self.check_read_only()?;
-        if let Some(path) = &self.file_path {
-            self.write_to_file(path)?;
+        if let Some(path) = self.file_path.clone() {
+            self.apply_editorconfig_on_save()?;
+            self.write_to_file(&path)?;
             self.modified = false;
             self.last_saved = Some(SystemTime::now());
             Ok(())
@@ -159,19 +215,99 @@ impl Buffer {
     }
 
     pub fn save_as(&mut self, path: PathBuf) -> Result<()> {
+        self.editorconfig = EditorConfigProperties::resolve(&path);
+        self.apply_editorconfig_on_save()?;
         self.write_to_file(&path)?;
         self.file_path = Some(path);
+        if self.language.is_none() {
+            self.detect_language(LanguageRegistry::builtin());
+        }
         self.modified = false;
         self.last_saved = Some(SystemTime::now());
         Ok(())
     }
 
+    pub fn editorconfig(&self) -> &EditorConfigProperties {
+        &self.editorconfig
+    }
+
+    pub fn set_editorconfig(&mut self, editorconfig: EditorConfigProperties) {
+        self.editorconfig = editorconfig;
+    }
+
+    fn apply_editorconfig_on_save(&mut self) -> Result<()> {
+        if let Some(line_ending) = self.editorconfig.end_of_line {
+            if line_ending != self.line_ending {
+                self.set_line_ending(line_ending)?;
+            }
+        }
+        if let Some(encoding) = self.editorconfig.charset {
+            self.encoding = encoding;
+        }
+
+        self.normalize_whitespace_for_save();
+        Ok(())
+    }
+
+    // Trims trailing whitespace and fixes the final newline as .editorconfig
+    // asks. Each change is its own small edit, so folds, the syntax tree and
+    // positions elsewhere in the buffer are left alone. Returns whether
+    // anything changed.
+    pub fn normalize_whitespace_for_save(&mut self) -> bool {
+        let mut changed = false;
+        if self.editorconfig.trim_trailing_whitespace == Some(true) {
+            for line_idx in (0..self.rope.len_lines()).rev() {
+                let line = self.rope.line(line_idx);
+                let mut text_len = line.len_chars();
+                if text_len > 0 && line.char(text_len - 1) == '\n' {
+                    text_len -= 1;
+                }
+                let trailing = (0..text_len)
+                    .rev()
+                    .take_while(|&i| matches!(line.char(i), ' ' | '\t'))
+                    .count();
+                if trailing > 0 {
+                    let end = self.rope.line_to_char(line_idx) + text_len;
+                    self.replace_chars(end - trailing, end, "");
+                    changed = true;
+                }
+            }
+        }
+
+        let len = self.rope.len_chars();
+        match self.editorconfig.insert_final_newline {
+            Some(true) if len > 0 && self.rope.char(len - 1) != '\n' => {
+                self.replace_chars(len, len, "\n");
+                changed = true;
+            }
+            Some(false) => {
+                let newlines = (0..len)
+                    .rev()
+                    .take_while(|&i| self.rope.char(i) == '\n')
+                    .count();
+                if newlines > 0 {
+                    self.replace_chars(len - newlines, len, "");
+                    changed = true;
+                }
+            }
+            _ => {}
+        }
+        changed
+    }
+
     fn write_to_file(&self, path: &PathBuf) -> Result<()> {
         use std::io::Write;
 
+        let bom = if self.encoding == Encoding::Utf8Bom {
+            BOM.to_string()
+        } else {
+            String::new()
+        };
+
         let result = if self.rope.len_chars() > 10_000_000 {
             let file = std::fs::File::create(path)?;
             let mut writer = std::io::BufWriter::new(file);
+            writer.write_all(bom.as_bytes())?;
 
             for chunk in self.rope.chunks() {
                 match self.line_ending {
@@ -185,7 +321,7 @@ impl Buffer {
             writer.flush()
         } else {
             let content = self.content_with_line_endings();
-            std::fs::write(path, content)
+            std::fs::write(path, bom + &content)
         };
 
         if let Err(e) = result {
@@ -204,8 +340,7 @@ impl Buffer {
     pub fn insert_char(&mut self, line: usize, column: usize, ch: char) -> Result<()> {
         self.check_read_only()?;
         let char_idx = self.line_col_to_char_idx(line, column)?;
-        self.rope.insert_char(char_idx, ch);
-        self.modified = true;
+        self.replace_chars(char_idx, char_idx, ch.encode_utf8(&mut [0; 4]));
         Ok(())
     }
 
@@ -213,8 +348,7 @@ impl Buffer {
         self.check_read_only()?;
         let char_idx = self.line_col_to_char_idx(line, column)?;
         if char_idx < self.rope.len_chars() {
-            self.rope.remove(char_idx..char_idx + 1);
-            self.modified = true;
+            self.replace_chars(char_idx, char_idx + 1, "");
             Ok(())
         } else {
             Err(EditorError::InvalidPosition { line, column })
@@ -224,8 +358,7 @@ impl Buffer {
     pub fn insert_str(&mut self, line: usize, column: usize, s: &str) -> Result<()> {
         self.check_read_only()?;
         let char_idx = self.line_col_to_char_idx(line, column)?;
-        self.rope.insert(char_idx, s);
-        self.modified = true;
+        self.replace_chars(char_idx, char_idx, s);
         Ok(())
     }
 
@@ -240,8 +373,7 @@ impl Buffer {
         let start_idx = self.line_col_to_char_idx(start_line, start_col)?;
         let end_idx = self.line_col_to_char_idx(end_line, end_col)?;
         if start_idx <= end_idx && end_idx <= self.rope.len_chars() {
-            self.rope.remove(start_idx..end_idx);
-            self.modified = true;
+            self.replace_chars(start_idx, end_idx, "");
             Ok(())
         } else {
             Err(EditorError::InvalidPosition {
@@ -253,11 +385,131 @@ impl Buffer {
 
     pub fn set_content(&mut self, content: String) -> Result<()> {
         self.check_read_only()?;
-        self.rope = Rope::from_str(&content);
-        self.modified = true;
+        self.replace_chars(0, self.rope.len_chars(), &content);
         Ok(())
     }
 
+    fn replace_chars(&mut self, start: usize, end: usize, text: &str) {
+        let change = TextChange::apply(
+            &mut self.rope,
+            self.changes.next_revision(),
+            start,
+            end,
+            text,
+        );
+        if let Some(syntax) = self.syntax.as_mut() {
+            syntax.apply_change(&change, &self.rope);
+        }
+        self.folds.apply_change(&change);
+        self.changes.record(change);
+        self.modified = true;
+    }
+
+    pub fn rope(&self) -> &Rope {
+        &self.rope
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.changes.revision()
+    }
+
+    pub fn changes_since(&self, revision: u64) -> Option<&[TextChange]> {
+        self.changes.changes_since(revision)
+    }
+
+    pub fn syntax_language(&self) -> Option<SyntaxLanguage> {
+        self.syntax.as_ref().map(|syntax| syntax.language())
+    }
+
+    pub fn set_syntax_language(&mut self, language: Option<SyntaxLanguage>) {
+        self.syntax = language.and_then(|language| SyntaxHighlighter::new(language, &self.rope));
+        self.fold_ranges.take();
+    }
+
+    pub fn language(&self) -> Option<&str> {
+        self.language.as_deref()
+    }
+
+    pub fn set_language(&mut self, language: Option<&LanguageDefinition>) {
+        self.language = language.map(|language| language.name.clone());
+        let syntax = language
+            .and_then(|language| language.syntax)
+            .filter(|_| self.file_size.unwrap_or(0) <= MAX_HIGHLIGHT_BYTES);
+        if syntax != self.syntax_language() {
+            self.set_syntax_language(syntax);
+        }
+    }
+
+    pub fn detect_language(&mut self, registry: &LanguageRegistry) {
+        let sample = self.modeline_sample();
+        let language = registry.detect(self.file_path.as_deref(), &sample);
+        self.set_language(language);
+    }
+
+    fn modeline_sample(&self) -> String {
+        let line_count = self.rope.len_lines();
+        let head = line_count.min(MODELINE_SAMPLE_LINES);
+        let tail = line_count.saturating_sub(MODELINE_SAMPLE_LINES).max(head);
+
+        let mut sample = String::new();
+        for line_idx in (0..head).chain(tail..line_count) {
+            sample.extend(self.rope.line(line_idx).chars());
+        }
+        sample
+    }
+
+    pub fn syntax_tree(&self) -> Option<&tree_sitter::Tree> {
+        self.syntax.as_ref().and_then(|syntax| syntax.tree())
+    }
+
+    pub fn highlight_line(&self, line_idx: usize) -> Vec<HighlightSpan> {
+        self.syntax
+            .as_ref()
+            .map(|syntax| syntax.line_spans(&self.rope, line_idx))
+            .unwrap_or_default()
+    }
+
+    pub fn folds(&self) -> &FoldState {
+        &self.folds
+    }
+
+    pub fn folds_mut(&mut self) -> &mut FoldState {
+        &mut self.folds
+    }
+
+    pub fn fold_ranges(&self, tab_size: usize) -> Vec<FoldRange> {
+        self.with_fold_ranges(tab_size, |ranges| ranges.to_vec())
+    }
+
+    pub fn fold_range_at(&self, line_idx: usize, tab_size: usize) -> Option<FoldRange> {
+        self.with_fold_ranges(tab_size, |ranges| {
+            ranges
+                .binary_search_by_key(&line_idx, |range| range.start_line)
+                .ok()
+                .map(|index| ranges[index])
+        })
+    }
+
+    fn with_fold_ranges<R>(&self, tab_size: usize, f: impl FnOnce(&[FoldRange]) -> R) -> R {
+        let revision = self.revision();
+        let mut cache = self.fold_ranges.borrow_mut();
+        let stale = cache
+            .as_ref()
+            .is_none_or(|cache| cache.revision != revision || cache.tab_size != tab_size);
+        if stale {
+            let ranges = match self.syntax_tree() {
+                Some(tree) => syntax_fold_ranges(tree, &self.rope, tab_size),
+                None => indentation_fold_ranges(&self.rope, tab_size),
+            };
+            *cache = Some(FoldRangeCache {
+                revision,
+                tab_size,
+                ranges,
+            });
+        }
+        f(&cache.as_ref().expect("fold range cache is filled").ranges)
+    }
+
     pub fn line_count(&self) -> usize {
         self.rope.len_lines()
     }
@@ -299,6 +551,10 @@ impl Buffer {
         self.file_path.as_ref()
     }
 
+    pub fn set_file_path(&mut self, path: PathBuf) {
+        self.file_path = Some(path);
+    }
+
     pub fn content(&self) -> String {
         self.rope.to_string()
     }
@@ -501,9 +757,14 @@ impl Buffer {
                 (content, line_ending)
             };
 
-            let normalized = normalize_line_endings(&content);
-            self.rope = Rope::from_str(&normalized);
+            let (content, encoding) = match content.strip_prefix(BOM) {
+                Some(stripped) => (stripped, Encoding::Utf8Bom),
+                None => (content.as_str(), Encoding::Utf8),
+            };
+            let normalized = normalize_line_endings(content);
+            self.replace_chars(0, self.rope.len_chars(), &normalized);
             self.line_ending = line_ending;
+            self.encoding = encoding;
             self.modified = false;
             self.last_saved = Some(SystemTime::now());
             Ok(())
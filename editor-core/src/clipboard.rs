@@ -1,7 +1,6 @@
 use crate::error::Result;
-
-#[cfg(not(test))]
-use crate::error::EditorError;
+use serde::{Deserialize, Serialize};
+use std::io;
 
 #[cfg(not(test))]
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -11,6 +10,10 @@ use arboard::Clipboard;
 #[cfg(not(test))]
 use parking_lot::Mutex;
 #[cfg(not(test))]
+use std::io::Write;
+#[cfg(not(test))]
+use std::process::{Command, Stdio};
+#[cfg(not(test))]
 use std::sync::Arc;
 
 #[cfg(not(test))]
@@ -27,6 +30,12 @@ static USE_MOCK: AtomicBool = AtomicBool::new(false);
 static MOCK_CLIPBOARD: once_cell::sync::Lazy<Arc<Mutex<Option<String>>>> =
     once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(None)));
 
+// What was copied last, whatever the backend. It is all the internal
+// backend has, and what the others fall back to when they fail.
+#[cfg(not(test))]
+static INTERNAL_CLIPBOARD: once_cell::sync::Lazy<Mutex<Option<String>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
 #[cfg(test)]
 use std::sync::{Arc, Mutex};
 
@@ -34,12 +43,150 @@ use std::sync::{Arc, Mutex};
 static TEST_CLIPBOARD: once_cell::sync::Lazy<Arc<Mutex<Option<String>>>> =
     once_cell::sync::Lazy::new(|| Arc::new(Mutex::new(None)));
 
-#[derive(Clone)]
-pub struct ClipboardManager;
+type TerminalWriter = Box<dyn Fn(&str) -> io::Result<()> + Send + Sync>;
+
+// Set by a terminal frontend to send OSC 52 sequences to its terminal.
+static TERMINAL_WRITER: once_cell::sync::Lazy<parking_lot::Mutex<Option<TerminalWriter>>> =
+    once_cell::sync::Lazy::new(|| parking_lot::Mutex::new(None));
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipboardBackend {
+    #[default]
+    Auto,
+    Arboard,
+    Osc52,
+    Tmux,
+    Internal,
+}
+
+impl ClipboardBackend {
+    pub fn all() -> &'static [ClipboardBackend] {
+        &[
+            ClipboardBackend::Auto,
+            ClipboardBackend::Arboard,
+            ClipboardBackend::Osc52,
+            ClipboardBackend::Tmux,
+            ClipboardBackend::Internal,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ClipboardBackend::Auto => "auto",
+            ClipboardBackend::Arboard => "arboard",
+            ClipboardBackend::Osc52 => "osc52",
+            ClipboardBackend::Tmux => "tmux",
+            ClipboardBackend::Internal => "internal",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|backend| backend.name() == name)
+    }
+
+    pub fn detect() -> Self {
+        Self::detect_in(&ClipboardEnvironment::current())
+    }
+
+    // Inside tmux its buffers reach the outer terminal as well. Over SSH
+    // the system clipboard belongs to the remote machine, so the terminal
+    // is asked to copy instead. Without a display server only the editor
+    // itself can hold the text.
+    pub fn detect_in(environment: &ClipboardEnvironment) -> Self {
+        if environment.tmux {
+            ClipboardBackend::Tmux
+        } else if environment.ssh && environment.terminal_writer {
+            ClipboardBackend::Osc52
+        } else if environment.system_clipboard {
+            ClipboardBackend::Arboard
+        } else if environment.terminal_writer {
+            ClipboardBackend::Osc52
+        } else {
+            ClipboardBackend::Internal
+        }
+    }
+}
+
+// What `auto` looks at to pick a backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ClipboardEnvironment {
+    pub tmux: bool,
+    pub ssh: bool,
+    pub terminal_writer: bool,
+    pub system_clipboard: bool,
+}
+
+impl ClipboardEnvironment {
+    pub fn current() -> Self {
+        Self {
+            tmux: std::env::var_os("TMUX").is_some(),
+            ssh: is_ssh_session(),
+            terminal_writer: ClipboardManager::has_terminal_writer(),
+            system_clipboard: system_clipboard_available(),
+        }
+    }
+}
+
+pub fn is_ssh_session() -> bool {
+    ["SSH_CONNECTION", "SSH_CLIENT", "SSH_TTY"]
+        .iter()
+        .any(|name| std::env::var_os(name).is_some())
+}
+
+#[cfg(not(test))]
+fn system_clipboard_available() -> bool {
+    GLOBAL_CLIPBOARD.lock().is_some()
+}
+
+#[cfg(test)]
+fn system_clipboard_available() -> bool {
+    false
+}
+
+// Copying never fails: when the chosen backend can't take the text, it is
+// still kept for pasting inside the editor.
+#[derive(Debug, Clone)]
+pub struct ClipboardManager {
+    backend: ClipboardBackend,
+    active: ClipboardBackend,
+}
 
 impl ClipboardManager {
     pub fn new() -> Result<Self> {
-        Ok(Self)
+        Ok(Self::default())
+    }
+
+    // `auto` is resolved here, once, so the backend doesn't change under a
+    // running session.
+    pub fn with_backend(backend: ClipboardBackend) -> Self {
+        let active = match backend {
+            ClipboardBackend::Auto => ClipboardBackend::detect(),
+            backend => backend,
+        };
+        Self { backend, active }
+    }
+
+    pub fn backend(&self) -> ClipboardBackend {
+        self.backend
+    }
+
+    pub fn active_backend(&self) -> ClipboardBackend {
+        self.active
+    }
+
+    pub fn set_terminal_writer<F>(writer: F)
+    where
+        F: Fn(&str) -> io::Result<()> + Send + Sync + 'static,
+    {
+        *TERMINAL_WRITER.lock() = Some(Box::new(writer));
+    }
+
+    pub fn has_terminal_writer() -> bool {
+        TERMINAL_WRITER.lock().is_some()
     }
 
     #[doc(hidden)]
@@ -69,11 +216,26 @@ impl ClipboardManager {
             return Ok(());
         }
 
-        let mut clipboard = GLOBAL_CLIPBOARD.lock();
-        if let Some(cb) = clipboard.as_mut() {
-            cb.set_text(text).map_err(|e| {
-                EditorError::InvalidOperation(format!("Failed to set clipboard text: {}", e))
-            })?;
+        *INTERNAL_CLIPBOARD.lock() = Some(text.to_string());
+        match self.active {
+            ClipboardBackend::Arboard => {
+                let copied = GLOBAL_CLIPBOARD
+                    .lock()
+                    .as_mut()
+                    .is_some_and(|cb| cb.set_text(text).is_ok());
+                if !copied {
+                    let _ = write_osc52(text);
+                }
+            }
+            ClipboardBackend::Osc52 => {
+                let _ = write_osc52(text);
+            }
+            ClipboardBackend::Tmux => {
+                if tmux_load_buffer(text).is_err() {
+                    let _ = write_osc52(text);
+                }
+            }
+            ClipboardBackend::Auto | ClipboardBackend::Internal => {}
         }
         Ok(())
     }
@@ -94,14 +256,19 @@ impl ClipboardManager {
             });
         }
 
-        let mut clipboard = GLOBAL_CLIPBOARD.lock();
-        if let Some(cb) = clipboard.as_mut() {
-            cb.get_text().map_err(|e| {
-                EditorError::InvalidOperation(format!("Failed to get clipboard text: {}", e))
-            })
-        } else {
-            Ok(String::new())
-        }
+        // Terminals seldom let programs read the clipboard back, so OSC 52
+        // pastes what was copied here.
+        let text = match self.active {
+            ClipboardBackend::Arboard => GLOBAL_CLIPBOARD
+                .lock()
+                .as_mut()
+                .and_then(|cb| cb.get_text().ok()),
+            ClipboardBackend::Tmux => tmux_save_buffer().ok(),
+            _ => None,
+        };
+        Ok(text
+            .or_else(|| INTERNAL_CLIPBOARD.lock().clone())
+            .unwrap_or_default())
     }
 
     #[cfg(test)]
@@ -116,6 +283,50 @@ impl ClipboardManager {
 
 impl Default for ClipboardManager {
     fn default() -> Self {
-        Self
+        Self::with_backend(ClipboardBackend::Auto)
+    }
+}
+
+#[cfg(not(test))]
+fn write_osc52(text: &str) -> io::Result<()> {
+    match TERMINAL_WRITER.lock().as_ref() {
+        Some(writer) => writer(text),
+        None => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no terminal to send OSC 52 to",
+        )),
+    }
+}
+
+// `-w` also hands the buffer to the outer terminal's clipboard; tmux
+// before 3.2 doesn't know it, so it is tried without as well.
+#[cfg(not(test))]
+fn tmux_load_buffer(text: &str) -> io::Result<()> {
+    for args in [&["load-buffer", "-w", "-"][..], &["load-buffer", "-"][..]] {
+        let mut child = Command::new("tmux")
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    Err(io::Error::other("tmux load-buffer failed"))
+}
+
+#[cfg(not(test))]
+fn tmux_save_buffer() -> io::Result<String> {
+    let output = Command::new("tmux")
+        .args(["save-buffer", "-"])
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("tmux save-buffer failed"));
     }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
@@ -0,0 +1,287 @@
+use crate::command::Command;
+use crate::fuzzy::fuzzy_match;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+pub const DEFAULT_CLIPBOARD_HISTORY_SIZE: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClipboardEntry {
+    pub text: String,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl ClipboardEntry {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            pinned: false,
+        }
+    }
+}
+
+// Everything copied, cut or killed, newest first. The capacity bounds the
+// unpinned entries only; pinned ones stay until they are unpinned.
+#[derive(Debug, Clone)]
+pub struct ClipboardHistory {
+    entries: VecDeque<ClipboardEntry>,
+    capacity: usize,
+}
+
+impl ClipboardHistory {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CLIPBOARD_HISTORY_SIZE)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.expire();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &ClipboardEntry> {
+        self.entries.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&ClipboardEntry> {
+        self.entries.get(index)
+    }
+
+    pub fn latest(&self) -> Option<&str> {
+        self.entries.front().map(|entry| entry.text.as_str())
+    }
+
+    // Text already in the history moves to the front and keeps its pin.
+    pub fn push(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if text.is_empty() {
+            return;
+        }
+        let entry = self
+            .entries
+            .iter()
+            .position(|entry| entry.text == text)
+            .and_then(|index| self.entries.remove(index))
+            .unwrap_or_else(|| ClipboardEntry::new(text));
+        self.entries.push_front(entry);
+        self.expire();
+    }
+
+    // A run of kills grows one entry; a pinned entry is left as it was and
+    // the grown text goes in front of it.
+    pub fn replace_latest(&mut self, text: impl Into<String>) {
+        match self.entries.front_mut() {
+            Some(entry) if !entry.pinned => entry.text = text.into(),
+            _ => self.push(text),
+        }
+    }
+
+    pub fn toggle_pin(&mut self, index: usize) -> Option<bool> {
+        let entry = self.entries.get_mut(index)?;
+        entry.pinned = !entry.pinned;
+        let pinned = entry.pinned;
+        self.expire();
+        Some(pinned)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.retain(|entry| entry.pinned);
+    }
+
+    pub fn restore(&mut self, entries: impl IntoIterator<Item = ClipboardEntry>) {
+        self.entries = entries
+            .into_iter()
+            .filter(|entry| !entry.text.is_empty())
+            .collect();
+        self.expire();
+    }
+
+    // Drops the oldest unpinned entries beyond the capacity.
+    fn expire(&mut self) {
+        let mut unpinned = self.entries.iter().filter(|entry| !entry.pinned).count();
+        let mut index = self.entries.len();
+        while unpinned > self.capacity && index > 0 {
+            index -= 1;
+            if !self.entries[index].pinned {
+                self.entries.remove(index);
+                unpinned -= 1;
+            }
+        }
+    }
+}
+
+impl Default for ClipboardHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClipboardPickerItem {
+    pub index: usize,
+    pub preview: String,
+    pub pinned: bool,
+    pub indices: Vec<usize>,
+}
+
+// Lists the clipboard history to paste from, filtered by a fuzzy query on
+// each entry's one-line preview.
+#[derive(Debug, Clone)]
+pub struct ClipboardPicker {
+    query: String,
+    candidates: Vec<ClipboardPickerItem>,
+    items: Vec<ClipboardPickerItem>,
+    selected_index: usize,
+}
+
+impl ClipboardPicker {
+    pub fn new(history: &ClipboardHistory) -> Self {
+        let mut picker = Self {
+            query: String::new(),
+            candidates: Vec::new(),
+            items: Vec::new(),
+            selected_index: 0,
+        };
+        picker.refresh(history);
+        picker
+    }
+
+    pub fn title(&self) -> &'static str {
+        "Paste from Clipboard History"
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn items(&self) -> &[ClipboardPickerItem] {
+        &self.items
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    pub fn selected_item(&self) -> Option<&ClipboardPickerItem> {
+        self.items.get(self.selected_index)
+    }
+
+    pub fn set_query(&mut self, query: impl Into<String>) {
+        self.query = query.into();
+        self.update_items();
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.query.push(c);
+        self.update_items();
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.update_items();
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected_index + 1 < self.items.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn select_index(&mut self, index: usize) -> bool {
+        if index < self.items.len() {
+            self.selected_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    // Picks up changes to the history, such as a pin, keeping the query and
+    // the selected entry.
+    pub fn refresh(&mut self, history: &ClipboardHistory) {
+        let selected = self.selected_item().map(|item| item.index);
+        self.candidates = history
+            .entries()
+            .enumerate()
+            .map(|(index, entry)| ClipboardPickerItem {
+                index,
+                preview: preview(&entry.text),
+                pinned: entry.pinned,
+                indices: Vec::new(),
+            })
+            .collect();
+        self.update_items();
+        if let Some(position) =
+            selected.and_then(|selected| self.items.iter().position(|item| item.index == selected))
+        {
+            self.selected_index = position;
+        }
+    }
+
+    pub fn confirm(&self) -> Option<Command> {
+        Some(Command::PasteFromHistory(self.selected_item()?.index))
+    }
+
+    pub fn toggle_pin(&self) -> Option<Command> {
+        Some(Command::ToggleClipboardPin(self.selected_item()?.index))
+    }
+
+    // With an empty query entries stay newest first; otherwise results are
+    // ranked by match score.
+    fn update_items(&mut self) {
+        let mut matches: Vec<(i64, ClipboardPickerItem)> = self
+            .candidates
+            .iter()
+            .filter_map(|candidate| {
+                fuzzy_match(&self.query, &candidate.preview).map(|m| {
+                    (
+                        m.score,
+                        ClipboardPickerItem {
+                            indices: m.indices,
+                            ..candidate.clone()
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.items = matches.into_iter().map(|(_, item)| item).collect();
+        self.selected_index = 0;
+    }
+}
+
+// The entry on one line, with line breaks and tabs shown as arrows.
+fn preview(text: &str) -> String {
+    text.chars()
+        .filter(|&c| c != '\r')
+        .map(|c| match c {
+            '\n' => '↵',
+            '\t' => '→',
+            c => c,
+        })
+        .collect()
+}
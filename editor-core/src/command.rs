@@ -1,14 +1,19 @@
 use crate::cursor::CursorPosition;
+use crate::keymap::KeyPress;
+use crate::split::{PaneDirection, SplitDirection};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CaseMode {
     Upper,
     Lower,
     Title,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Command {
     InsertChar(char),
     DeleteChar,
@@ -44,6 +49,12 @@ pub enum Command {
     AddCursor(CursorPosition),
     RemoveCursor(usize),
     ClearSecondaryCursors,
+    AddNextOccurrence,
+    SkipOccurrence,
+    SelectAllOccurrences,
+    SplitSelectionIntoLines,
+    ExpandSelection,
+    ShrinkSelection,
 
     MouseClick(CursorPosition),
     MouseDragStart(CursorPosition),
@@ -70,6 +81,10 @@ pub enum Command {
     Copy,
     Cut,
     Paste,
+    PasteFromHistory(usize),
+    InsertText(String),
+    ToggleClipboardPin(usize),
+    ClearClipboardHistory,
 
     SelectionStart,
     SelectionEnd,
@@ -94,8 +109,13 @@ pub enum Command {
 
     ToggleLineComment,
     ToggleBlockComment,
+    SetLanguage(String),
     FoldCode,
     UnfoldCode,
+    ToggleFold,
+    FoldAll,
+    UnfoldAll,
+    FoldLevel(usize),
 
     ToggleReadOnly,
 
@@ -145,6 +165,107 @@ pub enum Command {
     ExportHistory(PathBuf),
     ImportHistory(PathBuf),
 
+    ToggleFileTree,
+    FocusFileTree,
+    FocusEditor,
+    FileTreeNavigateNext,
+    FileTreeNavigatePrevious,
+    FileTreeNavigateFirst,
+    FileTreeNavigateLast,
+    FileTreeSelect(usize),
+    FileTreeExpand,
+    FileTreeCollapse,
+    FileTreeActivate,
+    FileTreeToggleHidden,
+    FileTreeRefresh,
+    FileTreeCreateFile(String),
+    FileTreeCreateDirectory(String),
+    FileTreeRename(String),
+    FileTreeDelete,
+
+    ToggleSymbolOutline,
+    FocusSymbolOutline,
+    SymbolOutlineNavigateNext,
+    SymbolOutlineNavigatePrevious,
+    SymbolOutlineNavigateFirst,
+    SymbolOutlineNavigateLast,
+    SymbolOutlineSelect(usize),
+    SymbolOutlineExpand,
+    SymbolOutlineCollapse,
+    SymbolOutlineActivate,
+    JumpToSymbol {
+        path: Option<PathBuf>,
+        line: usize,
+        column: usize,
+    },
+    GotoDefinition,
+    RefreshSymbolIndex,
+
+    TriggerCompletion,
+    CompletionNext,
+    CompletionPrevious,
+    CompletionSelect(usize),
+    CompletionAccept,
+    CompletionCancel,
+    ShowHover,
+    FindReferences,
+    NextReference,
+    PreviousReference,
+    NextDiagnostic,
+    PreviousDiagnostic,
+    RenameSymbol(String),
+    FormatDocument,
+    RestartLanguageServer,
+
+    ExpandSnippet,
+    InsertSnippet,
+    NextTabstop,
+    PreviousTabstop,
+    ExitSnippet,
+
+    Split {
+        direction: SplitDirection,
+    },
+    CloseSplit,
+    FocusSplit {
+        direction: PaneDirection,
+    },
+    FocusNextSplit,
+    FocusSplitPane(usize),
+    ResizeSplit {
+        direction: SplitDirection,
+        delta: i32,
+    },
+    SwapSplit,
+
+    VimKey(KeyPress),
+    ToggleVimMode,
+
+    SetMark,
+    ExchangePointAndMark,
+    KeyboardQuit,
+    KillLine,
+    KillRegion,
+    CopyRegion,
+    KillWord {
+        forward: bool,
+    },
+    Yank,
+    YankPop,
+    IncrementalSearch {
+        forward: bool,
+    },
+
+    StartMacroRecording(String),
+    StopMacroRecording,
+    ToggleMacroRecording,
+    PlayMacro {
+        name: String,
+        count: usize,
+    },
+    PlayMacroAtCursors(String),
+    SaveMacros,
+
     Quit,
 }
 
@@ -172,6 +293,8 @@ impl Command {
                 | Command::SetSoftWrap(_)
                 | Command::TrimTrailingWhitespace
                 | Command::Paste
+                | Command::PasteFromHistory(_)
+                | Command::InsertText(_)
                 | Command::Cut
                 | Command::ReplaceNext { .. }
                 | Command::ReplaceAll { .. }
@@ -180,6 +303,15 @@ impl Command {
                 | Command::Redo
                 | Command::ToggleLineComment
                 | Command::ToggleBlockComment
+                | Command::CompletionAccept
+                | Command::ExpandSnippet
+                | Command::RenameSymbol(_)
+                | Command::FormatDocument
+                | Command::KillLine
+                | Command::KillRegion
+                | Command::KillWord { .. }
+                | Command::Yank
+                | Command::YankPop
         )
     }
 
@@ -201,6 +333,12 @@ impl Command {
                 | Command::AddCursor(_)
                 | Command::RemoveCursor(_)
                 | Command::ClearSecondaryCursors
+                | Command::AddNextOccurrence
+                | Command::SkipOccurrence
+                | Command::SelectAllOccurrences
+                | Command::SplitSelectionIntoLines
+                | Command::ExpandSelection
+                | Command::ShrinkSelection
                 | Command::GotoLine(_)
                 | Command::MouseClick(_)
                 | Command::MouseDragStart(_)
@@ -223,13 +361,27 @@ impl Command {
                 | Command::HistoryNavigateNext
                 | Command::HistoryNavigatePrevious
                 | Command::HistorySelectCommit(_)
+                | Command::JumpToSymbol { .. }
+                | Command::GotoDefinition
+                | Command::NextReference
+                | Command::PreviousReference
+                | Command::NextDiagnostic
+                | Command::PreviousDiagnostic
         )
     }
 
     pub fn is_file_command(&self) -> bool {
         matches!(
             self,
-            Command::Open(_) | Command::Save | Command::SaveAs(_) | Command::Close | Command::New
+            Command::Open(_)
+                | Command::Save
+                | Command::SaveAs(_)
+                | Command::Close
+                | Command::New
+                | Command::FileTreeCreateFile(_)
+                | Command::FileTreeCreateDirectory(_)
+                | Command::FileTreeRename(_)
+                | Command::FileTreeDelete
         )
     }
 }
@@ -0,0 +1,193 @@
+use crate::command_registry::{ArgumentSpec, CommandAction, CommandCategory, CommandRegistry};
+use crate::editor::EditorState;
+use crate::error::{EditorError, Result};
+use crate::fuzzy::fuzzy_match;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteItem {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub category: CommandCategory,
+    pub keybinding: Option<&'static str>,
+    pub indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub enum PaletteOutcome {
+    Continue,
+    Run(CommandAction),
+}
+
+#[derive(Debug, Clone)]
+struct PendingCommand {
+    id: &'static str,
+    title: &'static str,
+    arguments: &'static [ArgumentSpec],
+    values: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CommandPalette {
+    query: String,
+    candidates: Vec<PaletteItem>,
+    items: Vec<PaletteItem>,
+    selected_index: usize,
+    pending: Option<PendingCommand>,
+}
+
+impl CommandPalette {
+    pub fn new(registry: &CommandRegistry, state: &EditorState) -> Self {
+        let candidates: Vec<PaletteItem> = registry
+            .available(state)
+            .into_iter()
+            .filter(|spec| !spec.hidden)
+            .map(|spec| PaletteItem {
+                id: spec.id,
+                title: spec.title,
+                category: spec.category,
+                keybinding: spec.keybinding,
+                indices: Vec::new(),
+            })
+            .collect();
+
+        Self {
+            query: String::new(),
+            items: candidates.clone(),
+            candidates,
+            selected_index: 0,
+            pending: None,
+        }
+    }
+
+    pub fn for_command(registry: &CommandRegistry, id: &str) -> Result<Self> {
+        let spec = registry
+            .get(id)
+            .ok_or_else(|| EditorError::InvalidOperation(format!("Unknown command: {}", id)))?;
+
+        Ok(Self {
+            query: String::new(),
+            candidates: Vec::new(),
+            items: Vec::new(),
+            selected_index: 0,
+            pending: Some(PendingCommand {
+                id: spec.id,
+                title: spec.title,
+                arguments: spec.arguments,
+                values: Vec::new(),
+            }),
+        })
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn items(&self) -> &[PaletteItem] {
+        &self.items
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    pub fn selected_item(&self) -> Option<&PaletteItem> {
+        self.items.get(self.selected_index)
+    }
+
+    pub fn is_prompting(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    pub fn prompt(&self) -> Option<&ArgumentSpec> {
+        self.pending
+            .as_ref()
+            .and_then(|pending| pending.arguments.get(pending.values.len()))
+    }
+
+    pub fn pending_title(&self) -> Option<&str> {
+        self.pending.as_ref().map(|pending| pending.title)
+    }
+
+    pub fn set_query(&mut self, query: impl Into<String>) {
+        self.query = query.into();
+        self.update_items();
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.query.push(c);
+        self.update_items();
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.update_items();
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected_index + 1 < self.items.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn confirm(&mut self, registry: &CommandRegistry) -> Result<PaletteOutcome> {
+        if self.pending.is_none() {
+            let item = self
+                .selected_item()
+                .ok_or_else(|| EditorError::InvalidOperation("No matching command".to_string()))?;
+            let spec = registry.get(item.id).ok_or_else(|| {
+                EditorError::InvalidOperation(format!("Unknown command: {}", item.id))
+            })?;
+
+            self.pending = Some(PendingCommand {
+                id: spec.id,
+                title: spec.title,
+                arguments: spec.arguments,
+                values: Vec::new(),
+            });
+            self.query.clear();
+        } else if let Some(pending) = self.pending.as_mut() {
+            if pending.values.len() < pending.arguments.len() {
+                pending.values.push(std::mem::take(&mut self.query));
+            }
+        }
+
+        match self.pending.as_ref() {
+            Some(pending) if pending.values.len() >= pending.arguments.len() => registry
+                .resolve(pending.id, &pending.values)
+                .map(PaletteOutcome::Run),
+            _ => Ok(PaletteOutcome::Continue),
+        }
+    }
+
+    fn update_items(&mut self) {
+        if self.pending.is_some() {
+            return;
+        }
+
+        let mut matches: Vec<(i64, PaletteItem)> = self
+            .candidates
+            .iter()
+            .filter_map(|candidate| {
+                if let Some(m) = fuzzy_match(&self.query, candidate.title) {
+                    Some((
+                        m.score,
+                        PaletteItem {
+                            indices: m.indices,
+                            ..candidate.clone()
+                        },
+                    ))
+                } else {
+                    fuzzy_match(&self.query, candidate.id).map(|m| (m.score, candidate.clone()))
+                }
+            })
+            .collect();
+
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.items = matches.into_iter().map(|(_, item)| item).collect();
+        self.selected_index = 0;
+    }
+}
@@ -0,0 +1,1368 @@
+use crate::command::{CaseMode, Command};
+use crate::editor::EditorState;
+use crate::error::{EditorError, Result};
+use crate::split::{PaneDirection, SplitDirection};
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandCategory {
+    File,
+    Edit,
+    View,
+    Search,
+    Navigation,
+    Bookmarks,
+    Tools,
+    History,
+    FileTree,
+    Help,
+}
+
+impl CommandCategory {
+    pub fn all() -> Vec<CommandCategory> {
+        vec![
+            CommandCategory::File,
+            CommandCategory::Edit,
+            CommandCategory::View,
+            CommandCategory::Search,
+            CommandCategory::Navigation,
+            CommandCategory::Bookmarks,
+            CommandCategory::Tools,
+            CommandCategory::History,
+            CommandCategory::FileTree,
+            CommandCategory::Help,
+        ]
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            CommandCategory::File => "File",
+            CommandCategory::Edit => "Edit",
+            CommandCategory::View => "View",
+            CommandCategory::Search => "Search",
+            CommandCategory::Navigation => "Navigation",
+            CommandCategory::Bookmarks => "Bookmarks",
+            CommandCategory::Tools => "Tools",
+            CommandCategory::History => "History",
+            CommandCategory::FileTree => "File Tree",
+            CommandCategory::Help => "Help",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgumentKind {
+    Text,
+    LineNumber,
+    Number,
+    Path,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArgumentSpec {
+    pub name: &'static str,
+    pub prompt: &'static str,
+    pub kind: ArgumentKind,
+}
+
+impl ArgumentSpec {
+    pub const fn new(name: &'static str, prompt: &'static str, kind: ArgumentKind) -> Self {
+        Self { name, prompt, kind }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum CommandAction {
+    Execute(Command),
+    Frontend(&'static str),
+}
+
+pub type CommandBuilder = fn(&[String]) -> Result<Command>;
+pub type AvailabilityPredicate = fn(&EditorState) -> bool;
+
+#[derive(Clone)]
+enum CommandTarget {
+    Editor(CommandBuilder),
+    Frontend,
+}
+
+#[derive(Clone)]
+pub struct CommandSpec {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub category: CommandCategory,
+    pub keybinding: Option<&'static str>,
+    pub arguments: &'static [ArgumentSpec],
+    pub menu_group: Option<u8>,
+    pub hidden: bool,
+    target: CommandTarget,
+    available: AvailabilityPredicate,
+}
+
+impl CommandSpec {
+    pub fn new(
+        id: &'static str,
+        title: &'static str,
+        category: CommandCategory,
+        build: CommandBuilder,
+    ) -> Self {
+        Self {
+            id,
+            title,
+            category,
+            keybinding: None,
+            arguments: &[],
+            menu_group: None,
+            hidden: false,
+            target: CommandTarget::Editor(build),
+            available: |_| true,
+        }
+    }
+
+    pub fn frontend(id: &'static str, title: &'static str, category: CommandCategory) -> Self {
+        Self {
+            target: CommandTarget::Frontend,
+            ..Self::new(id, title, category, |_| {
+                Err(EditorError::InvalidOperation(
+                    "Frontend command has no editor command".to_string(),
+                ))
+            })
+        }
+    }
+
+    pub fn with_keybinding(mut self, keybinding: &'static str) -> Self {
+        self.keybinding = Some(keybinding);
+        self
+    }
+
+    pub fn with_arguments(mut self, arguments: &'static [ArgumentSpec]) -> Self {
+        self.arguments = arguments;
+        self
+    }
+
+    pub fn with_menu_group(mut self, group: u8) -> Self {
+        self.menu_group = Some(group);
+        self
+    }
+
+    pub fn hidden(mut self) -> Self {
+        self.hidden = true;
+        self
+    }
+
+    pub fn with_availability(mut self, available: AvailabilityPredicate) -> Self {
+        self.available = available;
+        self
+    }
+
+    pub fn is_frontend(&self) -> bool {
+        matches!(self.target, CommandTarget::Frontend)
+    }
+
+    pub fn is_available(&self, state: &EditorState) -> bool {
+        (self.available)(state)
+    }
+
+    pub fn resolve(&self, args: &[String]) -> Result<CommandAction> {
+        if args.len() != self.arguments.len() {
+            return Err(EditorError::InvalidOperation(format!(
+                "{} expects {} argument(s), got {}",
+                self.id,
+                self.arguments.len(),
+                args.len()
+            )));
+        }
+
+        match self.target {
+            CommandTarget::Editor(build) => build(args).map(CommandAction::Execute),
+            CommandTarget::Frontend => Ok(CommandAction::Frontend(self.id)),
+        }
+    }
+}
+
+impl std::fmt::Debug for CommandSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CommandSpec")
+            .field("id", &self.id)
+            .field("title", &self.title)
+            .field("category", &self.category)
+            .field("keybinding", &self.keybinding)
+            .field("arguments", &self.arguments)
+            .field("menu_group", &self.menu_group)
+            .field("hidden", &self.hidden)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CommandRegistry {
+    commands: Vec<CommandSpec>,
+}
+
+static BUILTIN_REGISTRY: Lazy<CommandRegistry> = Lazy::new(CommandRegistry::new);
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self {
+            commands: builtin_commands(),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn builtin() -> &'static CommandRegistry {
+        &BUILTIN_REGISTRY
+    }
+
+    pub fn register(&mut self, spec: CommandSpec) -> Result<()> {
+        if self.get(spec.id).is_some() {
+            return Err(EditorError::InvalidOperation(format!(
+                "Command already registered: {}",
+                spec.id
+            )));
+        }
+        self.commands.push(spec);
+        Ok(())
+    }
+
+    pub fn get(&self, id: &str) -> Option<&CommandSpec> {
+        self.commands.iter().find(|spec| spec.id == id)
+    }
+
+    pub fn all(&self) -> &[CommandSpec] {
+        &self.commands
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn by_category(&self, category: CommandCategory) -> Vec<&CommandSpec> {
+        self.commands
+            .iter()
+            .filter(|spec| spec.category == category)
+            .collect()
+    }
+
+    pub fn menu_items(&self, category: CommandCategory) -> Vec<&CommandSpec> {
+        let mut items: Vec<&CommandSpec> = self
+            .commands
+            .iter()
+            .filter(|spec| spec.category == category && spec.menu_group.is_some())
+            .collect();
+        items.sort_by_key(|spec| spec.menu_group);
+        items
+    }
+
+    pub fn keybindings_by_category(&self) -> Vec<(CommandCategory, Vec<&CommandSpec>)> {
+        CommandCategory::all()
+            .into_iter()
+            .map(|category| {
+                let specs = self
+                    .commands
+                    .iter()
+                    .filter(|spec| spec.category == category && spec.keybinding.is_some())
+                    .collect::<Vec<_>>();
+                (category, specs)
+            })
+            .filter(|(_, specs)| !specs.is_empty())
+            .collect()
+    }
+
+    pub fn available<'a>(&'a self, state: &EditorState) -> Vec<&'a CommandSpec> {
+        self.commands
+            .iter()
+            .filter(|spec| spec.is_available(state))
+            .collect()
+    }
+
+    pub fn find_by_keybinding(&self, keybinding: &str) -> Option<&CommandSpec> {
+        self.commands
+            .iter()
+            .find(|spec| spec.keybinding == Some(keybinding))
+    }
+
+    pub fn resolve(&self, id: &str, args: &[String]) -> Result<CommandAction> {
+        self.get(id)
+            .ok_or_else(|| EditorError::InvalidOperation(format!("Unknown command: {}", id)))?
+            .resolve(args)
+    }
+}
+
+impl Default for CommandRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_line_number(value: &str) -> Result<usize> {
+    match value.trim().parse::<usize>() {
+        Ok(line) if line > 0 => Ok(line),
+        _ => Err(EditorError::Parse(format!(
+            "Invalid line number: {}",
+            value
+        ))),
+    }
+}
+
+fn parse_number(value: &str) -> Result<usize> {
+    value
+        .trim()
+        .parse::<usize>()
+        .map_err(|_| EditorError::Parse(format!("Invalid number: {}", value)))
+}
+
+fn parse_text(value: &str) -> Result<String> {
+    if value.is_empty() {
+        return Err(EditorError::InvalidOperation(
+            "Argument cannot be empty".to_string(),
+        ));
+    }
+    Ok(value.to_string())
+}
+
+fn parse_path(value: &str) -> Result<PathBuf> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(EditorError::InvalidOperation(
+            "Path cannot be empty".to_string(),
+        ));
+    }
+    Ok(PathBuf::from(value))
+}
+
+fn recording_macro(state: &EditorState) -> bool {
+    state.is_recording_macro()
+}
+
+fn file_tree_open(state: &EditorState) -> bool {
+    state.is_file_tree_open()
+}
+
+fn symbol_outline_open(state: &EditorState) -> bool {
+    state.is_symbol_outline_open()
+}
+
+fn completion_open(state: &EditorState) -> bool {
+    state.is_completion_open()
+}
+
+fn snippet_active(state: &EditorState) -> bool {
+    state.is_snippet_active()
+}
+
+fn has_splits(state: &EditorState) -> bool {
+    state.split_layout().is_split()
+}
+
+const SPLIT_RESIZE_STEP: i32 = 5;
+
+const PATH_ARGUMENT: &[ArgumentSpec] =
+    &[ArgumentSpec::new("path", "File path:", ArgumentKind::Path)];
+const SEARCH_ARGUMENT: &[ArgumentSpec] = &[ArgumentSpec::new(
+    "query",
+    "Search for:",
+    ArgumentKind::Text,
+)];
+const REPLACE_ARGUMENTS: &[ArgumentSpec] = &[
+    ArgumentSpec::new("find", "Search for:", ArgumentKind::Text),
+    ArgumentSpec::new("replace", "Replace with:", ArgumentKind::Text),
+];
+const LINE_ARGUMENT: &[ArgumentSpec] = &[ArgumentSpec::new(
+    "line",
+    "Line number:",
+    ArgumentKind::LineNumber,
+)];
+const WIDTH_ARGUMENT: &[ArgumentSpec] = &[ArgumentSpec::new(
+    "width",
+    "Line width:",
+    ArgumentKind::Number,
+)];
+const BOOKMARK_ARGUMENT: &[ArgumentSpec] = &[ArgumentSpec::new(
+    "name",
+    "Bookmark name:",
+    ArgumentKind::Text,
+)];
+const FILE_NAME_ARGUMENT: &[ArgumentSpec] =
+    &[ArgumentSpec::new("name", "File name:", ArgumentKind::Text)];
+const FOLDER_NAME_ARGUMENT: &[ArgumentSpec] = &[ArgumentSpec::new(
+    "name",
+    "Folder name:",
+    ArgumentKind::Text,
+)];
+const LANGUAGE_ARGUMENT: &[ArgumentSpec] = &[ArgumentSpec::new(
+    "language",
+    "Language:",
+    ArgumentKind::Text,
+)];
+const FOLD_LEVEL_ARGUMENT: &[ArgumentSpec] = &[ArgumentSpec::new(
+    "level",
+    "Fold level:",
+    ArgumentKind::Number,
+)];
+const RENAME_ARGUMENT: &[ArgumentSpec] =
+    &[ArgumentSpec::new("name", "New name:", ArgumentKind::Text)];
+const SYMBOL_NAME_ARGUMENT: &[ArgumentSpec] = &[ArgumentSpec::new(
+    "name",
+    "Rename symbol to:",
+    ArgumentKind::Text,
+)];
+const MACRO_NAME_ARGUMENT: &[ArgumentSpec] = &[ArgumentSpec::new(
+    "name",
+    "Macro name (empty for the last one):",
+    ArgumentKind::Text,
+)];
+const MACRO_REPEAT_ARGUMENTS: &[ArgumentSpec] = &[
+    ArgumentSpec::new(
+        "name",
+        "Macro name (empty for the last one):",
+        ArgumentKind::Text,
+    ),
+    ArgumentSpec::new("count", "Times:", ArgumentKind::Number),
+];
+
+fn builtin_commands() -> Vec<CommandSpec> {
+    use CommandCategory::*;
+
+    vec![
+        CommandSpec::new("file.new", "New File", File, |_| Ok(Command::New))
+            .with_keybinding("Ctrl+N")
+            .with_menu_group(0),
+        CommandSpec::new("file.open", "Open File", File, |args| {
+            Ok(Command::Open(parse_path(&args[0])?))
+        })
+        .with_keybinding("Ctrl+O")
+        .with_arguments(PATH_ARGUMENT)
+        .with_menu_group(0),
+        CommandSpec::new("file.save", "Save", File, |_| Ok(Command::Save))
+            .with_keybinding("Ctrl+S")
+            .with_menu_group(1),
+        CommandSpec::new("file.save_as", "Save As", File, |args| {
+            Ok(Command::SaveAs(parse_path(&args[0])?))
+        })
+        .with_arguments(PATH_ARGUMENT)
+        .with_menu_group(1),
+        CommandSpec::new("file.close", "Close File", File, |_| Ok(Command::Close))
+            .with_keybinding("Ctrl+W")
+            .with_menu_group(2),
+        CommandSpec::frontend("app.quit", "Quit", File)
+            .with_keybinding("Ctrl+Q")
+            .with_menu_group(2),
+        CommandSpec::new("edit.undo", "Undo", Edit, |_| Ok(Command::Undo))
+            .with_keybinding("Ctrl+Z")
+            .with_menu_group(0),
+        CommandSpec::new("edit.redo", "Redo", Edit, |_| Ok(Command::Redo))
+            .with_keybinding("Ctrl+Y")
+            .with_menu_group(0),
+        CommandSpec::new("edit.cut", "Cut", Edit, |_| Ok(Command::Cut))
+            .with_keybinding("Ctrl+X")
+            .with_menu_group(1),
+        CommandSpec::new("edit.copy", "Copy", Edit, |_| Ok(Command::Copy))
+            .with_keybinding("Ctrl+C")
+            .with_menu_group(1),
+        CommandSpec::new("edit.paste", "Paste", Edit, |_| Ok(Command::Paste))
+            .with_keybinding("Ctrl+V")
+            .with_menu_group(1),
+        CommandSpec::frontend(
+            "edit.paste_from_history",
+            "Paste from Clipboard History",
+            Edit,
+        )
+        .with_keybinding("Ctrl+Shift+V")
+        .with_menu_group(1),
+        CommandSpec::new(
+            "edit.clear_clipboard_history",
+            "Clear Clipboard History",
+            Edit,
+            |_| Ok(Command::ClearClipboardHistory),
+        ),
+        CommandSpec::frontend("edit.select_all", "Select All", Edit)
+            .with_keybinding("Ctrl+A")
+            .with_menu_group(2),
+        CommandSpec::new(
+            "edit.toggle_overwrite",
+            "Toggle Overwrite Mode",
+            Edit,
+            |_| Ok(Command::ToggleOverwriteMode),
+        )
+        .with_keybinding("Insert"),
+        CommandSpec::new("edit.toggle_read_only", "Toggle Read-Only", Edit, |_| {
+            Ok(Command::ToggleReadOnly)
+        })
+        .with_keybinding("Ctrl+R"),
+        CommandSpec::new("edit.toggle_vim_mode", "Toggle Vim Mode", Edit, |_| {
+            Ok(Command::ToggleVimMode)
+        }),
+        CommandSpec::new("edit.set_mark", "Set Mark", Edit, |_| Ok(Command::SetMark)),
+        CommandSpec::new(
+            "edit.exchange_point_and_mark",
+            "Exchange Point and Mark",
+            Edit,
+            |_| Ok(Command::ExchangePointAndMark),
+        ),
+        CommandSpec::new("edit.keyboard_quit", "Keyboard Quit", Edit, |_| {
+            Ok(Command::KeyboardQuit)
+        }),
+        CommandSpec::new("edit.kill_line", "Kill Line", Edit, |_| {
+            Ok(Command::KillLine)
+        }),
+        CommandSpec::new("edit.kill_region", "Kill Region", Edit, |_| {
+            Ok(Command::KillRegion)
+        }),
+        CommandSpec::new("edit.copy_region", "Copy Region", Edit, |_| {
+            Ok(Command::CopyRegion)
+        }),
+        CommandSpec::new("edit.kill_word", "Kill Word", Edit, |_| {
+            Ok(Command::KillWord { forward: true })
+        }),
+        CommandSpec::new(
+            "edit.backward_kill_word",
+            "Backward Kill Word",
+            Edit,
+            |_| Ok(Command::KillWord { forward: false }),
+        ),
+        CommandSpec::new("edit.yank", "Yank", Edit, |_| Ok(Command::Yank)),
+        CommandSpec::new("edit.yank_pop", "Yank Pop", Edit, |_| Ok(Command::YankPop)),
+        CommandSpec::new(
+            "edit.trigger_completion",
+            "Trigger Completion",
+            Edit,
+            |_| Ok(Command::TriggerCompletion),
+        )
+        .with_keybinding("Ctrl+Space")
+        .with_menu_group(2),
+        CommandSpec::new("edit.expand_snippet", "Expand Snippet", Edit, |_| {
+            Ok(Command::ExpandSnippet)
+        })
+        .with_keybinding("Alt+J")
+        .with_menu_group(2),
+        CommandSpec::new("edit.insert_snippet", "Insert Snippet", Edit, |_| {
+            Ok(Command::InsertSnippet)
+        })
+        .with_keybinding("Ctrl+Alt+J")
+        .with_menu_group(2),
+        CommandSpec::frontend("view.toggle_line_numbers", "Toggle Line Numbers", View)
+            .with_menu_group(0),
+        CommandSpec::frontend("view.toggle_status_bar", "Toggle Status Bar", View)
+            .with_menu_group(0),
+        CommandSpec::new("view.toggle_file_tree", "Toggle File Tree", View, |_| {
+            Ok(Command::ToggleFileTree)
+        })
+        .with_keybinding("Ctrl+E")
+        .with_menu_group(1),
+        CommandSpec::new(
+            "view.toggle_symbol_outline",
+            "Toggle Symbol Outline",
+            View,
+            |_| Ok(Command::ToggleSymbolOutline),
+        )
+        .with_keybinding("Ctrl+Alt+O")
+        .with_menu_group(1),
+        CommandSpec::new(
+            "view.focus_symbol_outline",
+            "Focus Symbol Outline",
+            View,
+            |_| Ok(Command::FocusSymbolOutline),
+        ),
+        CommandSpec::new("view.set_language", "Set Language Mode", View, |args| {
+            Ok(Command::SetLanguage(parse_text(args[0].trim())?))
+        })
+        .with_arguments(LANGUAGE_ARGUMENT),
+        CommandSpec::frontend("view.command_palette", "Command Palette", View)
+            .with_keybinding("Ctrl+Shift+P")
+            .with_menu_group(2),
+        CommandSpec::new("view.fold", "Fold", View, |_| Ok(Command::FoldCode))
+            .with_keybinding("Ctrl+Shift+[")
+            .with_menu_group(3),
+        CommandSpec::new("view.unfold", "Unfold", View, |_| Ok(Command::UnfoldCode))
+            .with_keybinding("Ctrl+Shift+]")
+            .with_menu_group(3),
+        CommandSpec::new("view.toggle_fold", "Toggle Fold", View, |_| {
+            Ok(Command::ToggleFold)
+        }),
+        CommandSpec::new("view.fold_all", "Fold All", View, |_| Ok(Command::FoldAll))
+            .with_keybinding("Ctrl+Alt+[")
+            .with_menu_group(3),
+        CommandSpec::new("view.unfold_all", "Unfold All", View, |_| {
+            Ok(Command::UnfoldAll)
+        })
+        .with_keybinding("Ctrl+Alt+]")
+        .with_menu_group(3),
+        CommandSpec::new("view.fold_level", "Fold Level", View, |args| {
+            Ok(Command::FoldLevel(parse_number(&args[0])?))
+        })
+        .with_arguments(FOLD_LEVEL_ARGUMENT),
+        CommandSpec::new("view.split_right", "Split Right", View, |_| {
+            Ok(Command::Split {
+                direction: SplitDirection::Vertical,
+            })
+        })
+        .with_keybinding("Alt+\\")
+        .with_menu_group(4),
+        CommandSpec::new("view.split_down", "Split Down", View, |_| {
+            Ok(Command::Split {
+                direction: SplitDirection::Horizontal,
+            })
+        })
+        .with_keybinding("Alt+-")
+        .with_menu_group(4),
+        CommandSpec::new("view.close_split", "Close Split", View, |_| {
+            Ok(Command::CloseSplit)
+        })
+        .with_keybinding("Alt+W")
+        .with_availability(has_splits)
+        .with_menu_group(4),
+        CommandSpec::new("view.focus_next_split", "Focus Next Split", View, |_| {
+            Ok(Command::FocusNextSplit)
+        })
+        .with_keybinding("Alt+O")
+        .with_availability(has_splits),
+        CommandSpec::new("view.focus_split_left", "Focus Split Left", View, |_| {
+            Ok(Command::FocusSplit {
+                direction: PaneDirection::Left,
+            })
+        })
+        .with_keybinding("Alt+Left")
+        .with_availability(has_splits),
+        CommandSpec::new("view.focus_split_right", "Focus Split Right", View, |_| {
+            Ok(Command::FocusSplit {
+                direction: PaneDirection::Right,
+            })
+        })
+        .with_keybinding("Alt+Right")
+        .with_availability(has_splits),
+        CommandSpec::new("view.focus_split_up", "Focus Split Above", View, |_| {
+            Ok(Command::FocusSplit {
+                direction: PaneDirection::Up,
+            })
+        })
+        .with_keybinding("Alt+Up")
+        .with_availability(has_splits),
+        CommandSpec::new("view.focus_split_down", "Focus Split Below", View, |_| {
+            Ok(Command::FocusSplit {
+                direction: PaneDirection::Down,
+            })
+        })
+        .with_keybinding("Alt+Down")
+        .with_availability(has_splits),
+        CommandSpec::new("view.widen_split", "Widen Split", View, |_| {
+            Ok(Command::ResizeSplit {
+                direction: SplitDirection::Vertical,
+                delta: SPLIT_RESIZE_STEP,
+            })
+        })
+        .with_keybinding("Alt+Shift+Right")
+        .with_availability(has_splits),
+        CommandSpec::new("view.narrow_split", "Narrow Split", View, |_| {
+            Ok(Command::ResizeSplit {
+                direction: SplitDirection::Vertical,
+                delta: -SPLIT_RESIZE_STEP,
+            })
+        })
+        .with_keybinding("Alt+Shift+Left")
+        .with_availability(has_splits),
+        CommandSpec::new("view.heighten_split", "Make Split Taller", View, |_| {
+            Ok(Command::ResizeSplit {
+                direction: SplitDirection::Horizontal,
+                delta: SPLIT_RESIZE_STEP,
+            })
+        })
+        .with_keybinding("Alt+Shift+Down")
+        .with_availability(has_splits),
+        CommandSpec::new("view.shorten_split", "Make Split Shorter", View, |_| {
+            Ok(Command::ResizeSplit {
+                direction: SplitDirection::Horizontal,
+                delta: -SPLIT_RESIZE_STEP,
+            })
+        })
+        .with_keybinding("Alt+Shift+Up")
+        .with_availability(has_splits),
+        CommandSpec::new("view.swap_split", "Swap Split", View, |_| {
+            Ok(Command::SwapSplit)
+        })
+        .with_keybinding("Alt+X")
+        .with_availability(has_splits),
+        CommandSpec::new("search.find", "Find", Search, |args| {
+            Ok(Command::Search(parse_text(&args[0])?))
+        })
+        .with_keybinding("Ctrl+F")
+        .with_arguments(SEARCH_ARGUMENT)
+        .with_menu_group(0),
+        CommandSpec::new("search.replace", "Replace All", Search, |args| {
+            Ok(Command::ReplaceAll {
+                find: parse_text(&args[0])?,
+                replace: args[1].clone(),
+            })
+        })
+        .with_keybinding("Ctrl+H")
+        .with_arguments(REPLACE_ARGUMENTS)
+        .with_menu_group(0),
+        CommandSpec::new("search.replace_next", "Replace Next", Search, |args| {
+            Ok(Command::ReplaceNext {
+                find: parse_text(&args[0])?,
+                replace: args[1].clone(),
+            })
+        })
+        .with_arguments(REPLACE_ARGUMENTS),
+        CommandSpec::new("search.goto_line", "Go to Line", Search, |args| {
+            Ok(Command::GotoLine(parse_line_number(&args[0])?))
+        })
+        .with_keybinding("Ctrl+G")
+        .with_arguments(LINE_ARGUMENT)
+        .with_menu_group(1),
+        CommandSpec::new("search.next_match", "Next Match", Search, |_| {
+            Ok(Command::NextMatch)
+        })
+        .with_keybinding("F3")
+        .with_menu_group(1),
+        CommandSpec::new("search.previous_match", "Previous Match", Search, |_| {
+            Ok(Command::PreviousMatch)
+        })
+        .with_keybinding("Shift+F3")
+        .with_menu_group(1),
+        CommandSpec::new(
+            "search.isearch_forward",
+            "Incremental Search Forward",
+            Search,
+            |_| Ok(Command::IncrementalSearch { forward: true }),
+        ),
+        CommandSpec::new(
+            "search.isearch_backward",
+            "Incremental Search Backward",
+            Search,
+            |_| Ok(Command::IncrementalSearch { forward: false }),
+        ),
+        CommandSpec::frontend("search.goto_symbol", "Go to Symbol in File", Search)
+            .with_keybinding("Ctrl+Shift+O")
+            .with_menu_group(1),
+        CommandSpec::frontend(
+            "search.goto_symbol_in_project",
+            "Go to Symbol in Project",
+            Search,
+        )
+        .with_keybinding("Ctrl+Shift+T")
+        .with_menu_group(1),
+        CommandSpec::new(
+            "navigation.matching_bracket",
+            "Jump to Matching Bracket",
+            Navigation,
+            |_| Ok(Command::JumpToMatchingBracket),
+        )
+        .with_keybinding("Ctrl+B"),
+        CommandSpec::new(
+            "navigation.goto_definition",
+            "Go to Definition",
+            Navigation,
+            |_| Ok(Command::GotoDefinition),
+        )
+        .with_keybinding("F12"),
+        CommandSpec::new(
+            "navigation.refresh_symbol_index",
+            "Refresh Symbol Index",
+            Navigation,
+            |_| Ok(Command::RefreshSymbolIndex),
+        ),
+        CommandSpec::new("navigation.show_hover", "Show Hover", Navigation, |_| {
+            Ok(Command::ShowHover)
+        })
+        .with_keybinding("Alt+I"),
+        CommandSpec::new(
+            "navigation.find_references",
+            "Find References",
+            Navigation,
+            |_| Ok(Command::FindReferences),
+        )
+        .with_keybinding("Shift+F12"),
+        CommandSpec::new(
+            "navigation.next_reference",
+            "Next Reference",
+            Navigation,
+            |_| Ok(Command::NextReference),
+        )
+        .with_keybinding("F4"),
+        CommandSpec::new(
+            "navigation.previous_reference",
+            "Previous Reference",
+            Navigation,
+            |_| Ok(Command::PreviousReference),
+        )
+        .with_keybinding("Shift+F4"),
+        CommandSpec::new(
+            "navigation.next_diagnostic",
+            "Next Diagnostic",
+            Navigation,
+            |_| Ok(Command::NextDiagnostic),
+        )
+        .with_keybinding("F8"),
+        CommandSpec::new(
+            "navigation.previous_diagnostic",
+            "Previous Diagnostic",
+            Navigation,
+            |_| Ok(Command::PreviousDiagnostic),
+        )
+        .with_keybinding("Shift+F8"),
+        CommandSpec::new(
+            "navigation.start_of_file",
+            "Go to Start of File",
+            Navigation,
+            |_| Ok(Command::MoveToStartOfFile),
+        )
+        .with_keybinding("Ctrl+Home"),
+        CommandSpec::new(
+            "navigation.end_of_file",
+            "Go to End of File",
+            Navigation,
+            |_| Ok(Command::MoveToEndOfFile),
+        )
+        .with_keybinding("Ctrl+End"),
+        CommandSpec::new(
+            "navigation.clear_cursors",
+            "Clear Secondary Cursors",
+            Navigation,
+            |_| Ok(Command::ClearSecondaryCursors),
+        )
+        .with_keybinding("Esc"),
+        CommandSpec::new(
+            "navigation.add_next_occurrence",
+            "Add Next Occurrence",
+            Navigation,
+            |_| Ok(Command::AddNextOccurrence),
+        )
+        .with_keybinding("Alt+D"),
+        CommandSpec::new(
+            "navigation.skip_occurrence",
+            "Skip Occurrence",
+            Navigation,
+            |_| Ok(Command::SkipOccurrence),
+        )
+        .with_keybinding("Alt+Shift+D"),
+        CommandSpec::new(
+            "navigation.select_all_occurrences",
+            "Select All Occurrences",
+            Navigation,
+            |_| Ok(Command::SelectAllOccurrences),
+        )
+        .with_keybinding("Ctrl+Shift+L"),
+        CommandSpec::new(
+            "navigation.split_selection_into_lines",
+            "Split Selection into Lines",
+            Navigation,
+            |_| Ok(Command::SplitSelectionIntoLines),
+        )
+        .with_keybinding("Alt+Shift+I"),
+        CommandSpec::new(
+            "navigation.expand_selection",
+            "Expand Selection",
+            Navigation,
+            |_| Ok(Command::ExpandSelection),
+        )
+        .with_keybinding("Ctrl+Alt+Up"),
+        CommandSpec::new(
+            "navigation.shrink_selection",
+            "Shrink Selection",
+            Navigation,
+            |_| Ok(Command::ShrinkSelection),
+        )
+        .with_keybinding("Ctrl+Alt+Down"),
+        CommandSpec::new(
+            "navigation.toggle_block_selection",
+            "Toggle Block Selection",
+            Navigation,
+            |_| Ok(Command::ToggleBlockSelection),
+        ),
+        CommandSpec::new("bookmarks.toggle", "Toggle Bookmark", Bookmarks, |_| {
+            Ok(Command::ToggleBookmark)
+        })
+        .with_keybinding("Ctrl+M"),
+        CommandSpec::new("bookmarks.next", "Next Bookmark", Bookmarks, |_| {
+            Ok(Command::NextBookmark)
+        })
+        .with_keybinding("F2"),
+        CommandSpec::new("bookmarks.previous", "Previous Bookmark", Bookmarks, |_| {
+            Ok(Command::PreviousBookmark)
+        })
+        .with_keybinding("Shift+F2"),
+        CommandSpec::new(
+            "bookmarks.add_named",
+            "Add Named Bookmark",
+            Bookmarks,
+            |args| Ok(Command::AddNamedBookmark(parse_text(&args[0])?)),
+        )
+        .with_arguments(BOOKMARK_ARGUMENT),
+        CommandSpec::new(
+            "bookmarks.jump_to_named",
+            "Jump to Named Bookmark",
+            Bookmarks,
+            |args| Ok(Command::JumpToNamedBookmark(parse_text(&args[0])?)),
+        )
+        .with_arguments(BOOKMARK_ARGUMENT),
+        CommandSpec::new("bookmarks.clear", "Clear All Bookmarks", Bookmarks, |_| {
+            Ok(Command::ClearAllBookmarks)
+        }),
+        CommandSpec::new("tools.toggle_comment", "Toggle Comment", Tools, |_| {
+            Ok(Command::ToggleLineComment)
+        })
+        .with_keybinding("Ctrl+/")
+        .with_menu_group(0),
+        CommandSpec::new(
+            "tools.toggle_block_comment",
+            "Toggle Block Comment",
+            Tools,
+            |_| Ok(Command::ToggleBlockComment),
+        )
+        .with_keybinding("Ctrl+Shift+/")
+        .with_menu_group(0),
+        CommandSpec::new("tools.sort_lines", "Sort Lines", Tools, |_| {
+            Ok(Command::SortLines { numerical: false })
+        })
+        .with_menu_group(1),
+        CommandSpec::new(
+            "tools.sort_lines_numerically",
+            "Sort Lines Numerically",
+            Tools,
+            |_| Ok(Command::SortLines { numerical: true }),
+        ),
+        CommandSpec::new("tools.duplicate_line", "Duplicate Line", Tools, |_| {
+            Ok(Command::DuplicateLine)
+        })
+        .with_keybinding("Ctrl+D")
+        .with_menu_group(1),
+        CommandSpec::new("tools.delete_line", "Delete Line", Tools, |_| {
+            Ok(Command::DeleteLine)
+        })
+        .with_keybinding("Ctrl+K")
+        .with_menu_group(1),
+        CommandSpec::new("tools.join_lines", "Join Lines", Tools, |_| {
+            Ok(Command::JoinLines)
+        })
+        .with_keybinding("Ctrl+J")
+        .with_menu_group(1),
+        CommandSpec::new("tools.move_lines_up", "Move Lines Up", Tools, |_| {
+            Ok(Command::MoveLinesUp)
+        })
+        .with_keybinding("Ctrl+Shift+Up"),
+        CommandSpec::new("tools.move_lines_down", "Move Lines Down", Tools, |_| {
+            Ok(Command::MoveLinesDown)
+        })
+        .with_keybinding("Ctrl+Shift+Down"),
+        CommandSpec::new("tools.upper_case", "Convert to Upper Case", Tools, |_| {
+            Ok(Command::ChangeCase {
+                mode: CaseMode::Upper,
+            })
+        })
+        .with_keybinding("Ctrl+U"),
+        CommandSpec::new("tools.lower_case", "Convert to Lower Case", Tools, |_| {
+            Ok(Command::ChangeCase {
+                mode: CaseMode::Lower,
+            })
+        })
+        .with_keybinding("Ctrl+Shift+U"),
+        CommandSpec::new("tools.title_case", "Convert to Title Case", Tools, |_| {
+            Ok(Command::ChangeCase {
+                mode: CaseMode::Title,
+            })
+        }),
+        CommandSpec::new("tools.indent", "Indent", Tools, |_| Ok(Command::Indent))
+            .with_keybinding("Tab"),
+        CommandSpec::new("tools.dedent", "Dedent", Tools, |_| Ok(Command::Dedent))
+            .with_keybinding("Shift+Tab"),
+        CommandSpec::new(
+            "tools.transpose_characters",
+            "Transpose Characters",
+            Tools,
+            |_| Ok(Command::TransposeCharacters),
+        ),
+        CommandSpec::new(
+            "tools.trim_trailing_whitespace",
+            "Trim Trailing Whitespace",
+            Tools,
+            |_| Ok(Command::TrimTrailingWhitespace),
+        ),
+        CommandSpec::new("tools.hard_wrap", "Hard Wrap", Tools, |args| {
+            Ok(Command::HardWrap(parse_number(&args[0])?))
+        })
+        .with_arguments(WIDTH_ARGUMENT),
+        CommandSpec::new("tools.rename_symbol", "Rename Symbol", Tools, |args| {
+            Ok(Command::RenameSymbol(parse_text(&args[0])?))
+        })
+        .with_keybinding("Ctrl+Shift+R")
+        .with_arguments(SYMBOL_NAME_ARGUMENT)
+        .with_menu_group(2),
+        CommandSpec::new("tools.format_document", "Format Document", Tools, |_| {
+            Ok(Command::FormatDocument)
+        })
+        .with_keybinding("Ctrl+Shift+I")
+        .with_menu_group(2),
+        CommandSpec::new(
+            "tools.restart_language_server",
+            "Restart Language Server",
+            Tools,
+            |_| Ok(Command::RestartLanguageServer),
+        ),
+        CommandSpec::new(
+            "tools.toggle_macro_recording",
+            "Start/Stop Recording Macro",
+            Tools,
+            |_| Ok(Command::ToggleMacroRecording),
+        )
+        .with_keybinding("Ctrl+Alt+M"),
+        CommandSpec::new("tools.record_macro", "Record Macro", Tools, |args| {
+            Ok(Command::StartMacroRecording(args[0].trim().to_string()))
+        })
+        .with_arguments(MACRO_NAME_ARGUMENT),
+        CommandSpec::new(
+            "tools.stop_macro_recording",
+            "Stop Recording Macro",
+            Tools,
+            |_| Ok(Command::StopMacroRecording),
+        )
+        .with_availability(recording_macro),
+        CommandSpec::new("tools.play_last_macro", "Play Last Macro", Tools, |_| {
+            Ok(Command::PlayMacro {
+                name: String::new(),
+                count: 1,
+            })
+        })
+        .with_keybinding("Ctrl+Alt+E"),
+        CommandSpec::new("tools.play_macro", "Play Macro", Tools, |args| {
+            Ok(Command::PlayMacro {
+                name: args[0].trim().to_string(),
+                count: 1,
+            })
+        })
+        .with_arguments(MACRO_NAME_ARGUMENT),
+        CommandSpec::new(
+            "tools.play_macro_times",
+            "Play Macro Several Times",
+            Tools,
+            |args| {
+                Ok(Command::PlayMacro {
+                    name: args[0].trim().to_string(),
+                    count: parse_number(&args[1])?,
+                })
+            },
+        )
+        .with_arguments(MACRO_REPEAT_ARGUMENTS),
+        CommandSpec::new(
+            "tools.play_macro_at_cursors",
+            "Play Macro at Each Cursor",
+            Tools,
+            |args| Ok(Command::PlayMacroAtCursors(args[0].trim().to_string())),
+        )
+        .with_arguments(MACRO_NAME_ARGUMENT),
+        CommandSpec::new("tools.save_macros", "Save Macros", Tools, |_| {
+            Ok(Command::SaveMacros)
+        }),
+        CommandSpec::new("history.open", "Open History Browser", History, |_| {
+            Ok(Command::OpenHistoryBrowser)
+        })
+        .with_keybinding("Ctrl+T"),
+        CommandSpec::new("history.stats", "Show History Statistics", History, |_| {
+            Ok(Command::ShowHistoryStats)
+        }),
+        CommandSpec::new("history.cleanup", "Clean Up History", History, |_| {
+            Ok(Command::CleanupHistory)
+        }),
+        CommandSpec::new("history.export", "Export History", History, |args| {
+            Ok(Command::ExportHistory(parse_path(&args[0])?))
+        })
+        .with_arguments(PATH_ARGUMENT),
+        CommandSpec::new("history.import", "Import History", History, |args| {
+            Ok(Command::ImportHistory(parse_path(&args[0])?))
+        })
+        .with_arguments(PATH_ARGUMENT),
+        CommandSpec::new("file_tree.focus", "Focus File Tree", FileTree, |_| {
+            Ok(Command::FocusFileTree)
+        })
+        .with_keybinding("Ctrl+Shift+E"),
+        CommandSpec::new("file_tree.focus_editor", "Focus Editor", FileTree, |_| {
+            Ok(Command::FocusEditor)
+        })
+        .with_availability(file_tree_open),
+        CommandSpec::new("file_tree.new_file", "New File in Tree", FileTree, |args| {
+            Ok(Command::FileTreeCreateFile(parse_text(&args[0])?))
+        })
+        .with_arguments(FILE_NAME_ARGUMENT)
+        .with_availability(file_tree_open),
+        CommandSpec::new(
+            "file_tree.new_folder",
+            "New Folder in Tree",
+            FileTree,
+            |args| Ok(Command::FileTreeCreateDirectory(parse_text(&args[0])?)),
+        )
+        .with_arguments(FOLDER_NAME_ARGUMENT)
+        .with_availability(file_tree_open),
+        CommandSpec::new("file_tree.rename", "Rename Tree Entry", FileTree, |args| {
+            Ok(Command::FileTreeRename(parse_text(&args[0])?))
+        })
+        .with_arguments(RENAME_ARGUMENT)
+        .with_availability(file_tree_open),
+        CommandSpec::new("file_tree.delete", "Delete Tree Entry", FileTree, |_| {
+            Ok(Command::FileTreeDelete)
+        })
+        .with_availability(file_tree_open),
+        CommandSpec::new(
+            "file_tree.toggle_hidden",
+            "Toggle Hidden Files",
+            FileTree,
+            |_| Ok(Command::FileTreeToggleHidden),
+        )
+        .with_availability(file_tree_open),
+        CommandSpec::new("file_tree.refresh", "Refresh File Tree", FileTree, |_| {
+            Ok(Command::FileTreeRefresh)
+        })
+        .with_availability(file_tree_open),
+        CommandSpec::frontend("help.shortcuts", "Keyboard Shortcuts", Help)
+            .with_keybinding("F1")
+            .with_menu_group(0),
+        CommandSpec::frontend("help.about", "About", Help).with_menu_group(1),
+        CommandSpec::new("editor.cursor_up", "Cursor Up", Navigation, |_| {
+            Ok(Command::MoveCursorUp)
+        })
+        .hidden(),
+        CommandSpec::new("editor.cursor_down", "Cursor Down", Navigation, |_| {
+            Ok(Command::MoveCursorDown)
+        })
+        .hidden(),
+        CommandSpec::new("editor.cursor_left", "Cursor Left", Navigation, |_| {
+            Ok(Command::MoveCursorLeft)
+        })
+        .hidden(),
+        CommandSpec::new("editor.cursor_right", "Cursor Right", Navigation, |_| {
+            Ok(Command::MoveCursorRight)
+        })
+        .hidden(),
+        CommandSpec::new("editor.word_left", "Word Left", Navigation, |_| {
+            Ok(Command::MoveCursorWordLeft)
+        })
+        .hidden(),
+        CommandSpec::new("editor.word_right", "Word Right", Navigation, |_| {
+            Ok(Command::MoveCursorWordRight)
+        })
+        .hidden(),
+        CommandSpec::new("editor.line_start", "Start of Line", Navigation, |_| {
+            Ok(Command::MoveToStartOfLine)
+        })
+        .hidden(),
+        CommandSpec::new("editor.line_end", "End of Line", Navigation, |_| {
+            Ok(Command::MoveToEndOfLine)
+        })
+        .hidden(),
+        CommandSpec::new("editor.page_up", "Page Up", Navigation, |_| {
+            Ok(Command::PageUp)
+        })
+        .hidden(),
+        CommandSpec::new("editor.page_down", "Page Down", Navigation, |_| {
+            Ok(Command::PageDown)
+        })
+        .hidden(),
+        CommandSpec::new("editor.backspace", "Backspace", Edit, |_| {
+            Ok(Command::Backspace)
+        })
+        .hidden(),
+        CommandSpec::new("editor.delete", "Delete Character", Edit, |_| {
+            Ok(Command::DeleteChar)
+        })
+        .hidden(),
+        CommandSpec::new("editor.newline", "New Line", Edit, |_| Ok(Command::NewLine)).hidden(),
+        CommandSpec::new("history.navigate_next", "Next Commit", History, |_| {
+            Ok(Command::HistoryNavigateNext)
+        })
+        .hidden(),
+        CommandSpec::new(
+            "history.navigate_previous",
+            "Previous Commit",
+            History,
+            |_| Ok(Command::HistoryNavigatePrevious),
+        )
+        .hidden(),
+        CommandSpec::new("history.navigate_first", "First Commit", History, |_| {
+            Ok(Command::HistoryNavigateFirst)
+        })
+        .hidden(),
+        CommandSpec::new("history.navigate_last", "Last Commit", History, |_| {
+            Ok(Command::HistoryNavigateLast)
+        })
+        .hidden(),
+        CommandSpec::new("history.page_up", "History Page Up", History, |_| {
+            Ok(Command::HistoryPageUp)
+        })
+        .hidden(),
+        CommandSpec::new("history.page_down", "History Page Down", History, |_| {
+            Ok(Command::HistoryPageDown)
+        })
+        .hidden(),
+        CommandSpec::new("history.view_diff", "View Diff", History, |_| {
+            Ok(Command::HistoryViewDiff)
+        })
+        .hidden(),
+        CommandSpec::new(
+            "history.toggle_file_list",
+            "Toggle File List",
+            History,
+            |_| Ok(Command::HistoryToggleFileList),
+        )
+        .hidden(),
+        CommandSpec::new(
+            "history.toggle_side_by_side",
+            "Toggle Side-by-Side Diff",
+            History,
+            |_| Ok(Command::HistoryToggleSideBySide),
+        )
+        .hidden(),
+        CommandSpec::new("history.close", "Close History Browser", History, |_| {
+            Ok(Command::CloseHistoryBrowser)
+        })
+        .hidden(),
+        CommandSpec::frontend("history.set_base_commit", "Set Base Commit", History).hidden(),
+        CommandSpec::new(
+            "history.clear_base_commit",
+            "Clear Base Commit",
+            History,
+            |_| Ok(Command::HistoryClearBaseCommit),
+        )
+        .hidden(),
+        CommandSpec::frontend("history.close_stats", "Close History Statistics", History).hidden(),
+        CommandSpec::new("file_tree.navigate_next", "Next Entry", FileTree, |_| {
+            Ok(Command::FileTreeNavigateNext)
+        })
+        .hidden(),
+        CommandSpec::new(
+            "file_tree.navigate_previous",
+            "Previous Entry",
+            FileTree,
+            |_| Ok(Command::FileTreeNavigatePrevious),
+        )
+        .hidden(),
+        CommandSpec::new("file_tree.navigate_first", "First Entry", FileTree, |_| {
+            Ok(Command::FileTreeNavigateFirst)
+        })
+        .hidden(),
+        CommandSpec::new("file_tree.navigate_last", "Last Entry", FileTree, |_| {
+            Ok(Command::FileTreeNavigateLast)
+        })
+        .hidden(),
+        CommandSpec::new("file_tree.expand", "Expand Entry", FileTree, |_| {
+            Ok(Command::FileTreeExpand)
+        })
+        .hidden(),
+        CommandSpec::new("file_tree.collapse", "Collapse Entry", FileTree, |_| {
+            Ok(Command::FileTreeCollapse)
+        })
+        .hidden(),
+        CommandSpec::new("file_tree.activate", "Open Entry", FileTree, |_| {
+            Ok(Command::FileTreeActivate)
+        })
+        .hidden(),
+        CommandSpec::new("outline.navigate_next", "Next Symbol", View, |_| {
+            Ok(Command::SymbolOutlineNavigateNext)
+        })
+        .with_availability(symbol_outline_open)
+        .hidden(),
+        CommandSpec::new("outline.navigate_previous", "Previous Symbol", View, |_| {
+            Ok(Command::SymbolOutlineNavigatePrevious)
+        })
+        .with_availability(symbol_outline_open)
+        .hidden(),
+        CommandSpec::new("outline.navigate_first", "First Symbol", View, |_| {
+            Ok(Command::SymbolOutlineNavigateFirst)
+        })
+        .with_availability(symbol_outline_open)
+        .hidden(),
+        CommandSpec::new("outline.navigate_last", "Last Symbol", View, |_| {
+            Ok(Command::SymbolOutlineNavigateLast)
+        })
+        .with_availability(symbol_outline_open)
+        .hidden(),
+        CommandSpec::new("outline.expand", "Expand Symbol", View, |_| {
+            Ok(Command::SymbolOutlineExpand)
+        })
+        .with_availability(symbol_outline_open)
+        .hidden(),
+        CommandSpec::new("outline.collapse", "Collapse Symbol", View, |_| {
+            Ok(Command::SymbolOutlineCollapse)
+        })
+        .with_availability(symbol_outline_open)
+        .hidden(),
+        CommandSpec::new("outline.activate", "Go to Symbol", View, |_| {
+            Ok(Command::SymbolOutlineActivate)
+        })
+        .with_availability(symbol_outline_open)
+        .hidden(),
+        CommandSpec::new("outline.focus_editor", "Focus Editor", View, |_| {
+            Ok(Command::FocusEditor)
+        })
+        .with_availability(symbol_outline_open)
+        .hidden(),
+        CommandSpec::new("completion.next", "Next Completion", Edit, |_| {
+            Ok(Command::CompletionNext)
+        })
+        .with_availability(completion_open)
+        .hidden(),
+        CommandSpec::new("completion.previous", "Previous Completion", Edit, |_| {
+            Ok(Command::CompletionPrevious)
+        })
+        .with_availability(completion_open)
+        .hidden(),
+        CommandSpec::new("completion.accept", "Accept Completion", Edit, |_| {
+            Ok(Command::CompletionAccept)
+        })
+        .with_availability(completion_open)
+        .hidden(),
+        CommandSpec::new("completion.cancel", "Cancel Completion", Edit, |_| {
+            Ok(Command::CompletionCancel)
+        })
+        .with_availability(completion_open)
+        .hidden(),
+        CommandSpec::new("snippet.next_tabstop", "Next Tabstop", Edit, |_| {
+            Ok(Command::NextTabstop)
+        })
+        .with_availability(snippet_active)
+        .hidden(),
+        CommandSpec::new("snippet.previous_tabstop", "Previous Tabstop", Edit, |_| {
+            Ok(Command::PreviousTabstop)
+        })
+        .with_availability(snippet_active)
+        .hidden(),
+        CommandSpec::new("snippet.exit", "Exit Snippet", Edit, |_| {
+            Ok(Command::ExitSnippet)
+        })
+        .with_availability(snippet_active)
+        .hidden(),
+        CommandSpec::frontend("menu.activate", "Activate Menu Bar", View).hidden(),
+        CommandSpec::frontend("menu.up", "Menu Up", View).hidden(),
+        CommandSpec::frontend("menu.down", "Menu Down", View).hidden(),
+        CommandSpec::frontend("menu.left", "Menu Left", View).hidden(),
+        CommandSpec::frontend("menu.right", "Menu Right", View).hidden(),
+        CommandSpec::frontend("menu.select", "Menu Select", View).hidden(),
+        CommandSpec::frontend("menu.close", "Close Menu", View).hidden(),
+        CommandSpec::frontend("dialog.confirm", "Confirm Dialog", View).hidden(),
+        CommandSpec::frontend("dialog.cancel", "Cancel Dialog", View).hidden(),
+        CommandSpec::frontend("dialog.switch_field", "Switch Dialog Field", View).hidden(),
+        CommandSpec::frontend("dialog.backspace", "Dialog Backspace", View).hidden(),
+        CommandSpec::frontend("dialog.delete", "Dialog Delete", View).hidden(),
+        CommandSpec::frontend("dialog.cursor_left", "Dialog Cursor Left", View).hidden(),
+        CommandSpec::frontend("dialog.cursor_right", "Dialog Cursor Right", View).hidden(),
+        CommandSpec::frontend("dialog.start", "Dialog Start of Input", View).hidden(),
+        CommandSpec::frontend("dialog.end", "Dialog End of Input", View).hidden(),
+        CommandSpec::frontend("dialog.scroll_up", "Dialog Scroll Up", View).hidden(),
+        CommandSpec::frontend("dialog.scroll_down", "Dialog Scroll Down", View).hidden(),
+        CommandSpec::frontend("dialog.page_up", "Dialog Page Up", View).hidden(),
+        CommandSpec::frontend("dialog.page_down", "Dialog Page Down", View).hidden(),
+        CommandSpec::frontend("palette.next", "Palette Next", View).hidden(),
+        CommandSpec::frontend("palette.previous", "Palette Previous", View).hidden(),
+        CommandSpec::frontend("palette.backspace", "Palette Backspace", View).hidden(),
+        CommandSpec::frontend("palette.confirm", "Palette Confirm", View).hidden(),
+        CommandSpec::frontend("palette.cancel", "Close Palette", View).hidden(),
+        CommandSpec::frontend("palette.toggle_pin", "Palette Toggle Pin", View).hidden(),
+    ]
+}
@@ -0,0 +1,203 @@
+use crate::cursor::CursorPosition;
+use crate::fuzzy::fuzzy_match;
+use crate::snippet::SnippetRegistry;
+use ropey::Rope;
+use std::path::Path;
+
+mod sources;
+
+pub(crate) use sources::snippet_items;
+pub use sources::{BufferWordSource, PathSource, SnippetSource};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionItem {
+    pub label: String,
+    pub kind: Option<String>,
+    pub detail: Option<String>,
+    pub insert_text: String,
+    pub is_snippet: bool,
+    pub filter_text: Option<String>,
+    pub sort_text: Option<String>,
+}
+
+impl CompletionItem {
+    pub fn new(label: impl Into<String>) -> Self {
+        let label = label.into();
+        Self {
+            insert_text: label.clone(),
+            label,
+            is_snippet: false,
+            kind: None,
+            detail: None,
+            filter_text: None,
+            sort_text: None,
+        }
+    }
+
+    pub fn with_kind(mut self, kind: impl Into<String>) -> Self {
+        self.kind = Some(kind.into());
+        self
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn with_insert_text(mut self, insert_text: impl Into<String>) -> Self {
+        self.insert_text = insert_text.into();
+        self
+    }
+
+    pub fn with_snippet(mut self, body: impl Into<String>) -> Self {
+        self.insert_text = body.into();
+        self.is_snippet = true;
+        self
+    }
+
+    fn filter_text(&self) -> &str {
+        self.filter_text.as_deref().unwrap_or(&self.label)
+    }
+
+    fn sort_text(&self) -> &str {
+        self.sort_text.as_deref().unwrap_or(&self.label)
+    }
+}
+
+// What a source gets to look at: `prefix` is the word before the cursor the
+// menu will replace, `buffers` starts with the current buffer.
+pub struct CompletionContext<'a> {
+    pub language: &'a str,
+    pub prefix: &'a str,
+    pub line_before_cursor: &'a str,
+    pub cursor: CursorPosition,
+    pub directory: Option<&'a Path>,
+    pub buffers: &'a [&'a Rope],
+    pub snippets: &'a SnippetRegistry,
+}
+
+pub trait CompletionSource {
+    fn name(&self) -> &str;
+
+    fn complete(&self, context: &CompletionContext) -> Vec<CompletionItem>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompletionMenuItem {
+    pub item: CompletionItem,
+    pub indices: Vec<usize>,
+}
+
+// `start` is where the word being completed begins on the primary cursor's
+// line; the text between it and the cursor is the query.
+#[derive(Debug, Clone)]
+pub struct CompletionMenu {
+    start: CursorPosition,
+    query: String,
+    candidates: Vec<CompletionItem>,
+    items: Vec<CompletionMenuItem>,
+    selected_index: usize,
+}
+
+impl CompletionMenu {
+    pub fn new(
+        start: CursorPosition,
+        query: impl Into<String>,
+        candidates: Vec<CompletionItem>,
+    ) -> Self {
+        let mut menu = Self {
+            start,
+            query: query.into(),
+            candidates,
+            items: Vec::new(),
+            selected_index: 0,
+        };
+        menu.update_items();
+        menu
+    }
+
+    pub fn start(&self) -> CursorPosition {
+        self.start
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn items(&self) -> &[CompletionMenuItem] {
+        &self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    pub fn selected_item(&self) -> Option<&CompletionItem> {
+        self.items.get(self.selected_index).map(|entry| &entry.item)
+    }
+
+    pub fn set_query(&mut self, query: impl Into<String>) {
+        let query = query.into();
+        if query != self.query {
+            self.query = query;
+            self.update_items();
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.items.is_empty() {
+            self.selected_index = (self.selected_index + 1) % self.items.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.items.is_empty() {
+            self.selected_index = (self.selected_index + self.items.len() - 1) % self.items.len();
+        }
+    }
+
+    pub fn select_index(&mut self, index: usize) -> bool {
+        if index < self.items.len() {
+            self.selected_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    // An empty query keeps the source's own order (its sort text); a typed
+    // prefix ranks by match score first.
+    fn update_items(&mut self) {
+        let mut matches: Vec<(i64, CompletionMenuItem)> = self
+            .candidates
+            .iter()
+            .filter_map(|item| {
+                let m = fuzzy_match(&self.query, item.filter_text())?;
+                let indices = if item.filter_text.is_none() {
+                    m.indices
+                } else {
+                    Vec::new()
+                };
+                Some((
+                    m.score,
+                    CompletionMenuItem {
+                        item: item.clone(),
+                        indices,
+                    },
+                ))
+            })
+            .collect();
+
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then_with(|| a.item.sort_text().cmp(b.item.sort_text()))
+        });
+        self.items = matches.into_iter().map(|(_, item)| item).collect();
+        self.selected_index = 0;
+    }
+}
@@ -0,0 +1,161 @@
+use super::{CompletionContext, CompletionItem, CompletionSource};
+use crate::snippet::Snippet;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+const MIN_WORD_LENGTH: usize = 3;
+const MAX_SCANNED_LINES: usize = 50_000;
+const MAX_PATH_ENTRIES: usize = 500;
+const PATH_DELIMITERS: &[char] = &[
+    '"', '\'', '`', '(', ')', '<', '>', '[', ']', '{', '}', ',', ';', '=',
+];
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// Words from every open buffer. Words in the current buffer rank by their
+// distance from the cursor, ahead of words that only appear elsewhere.
+#[derive(Debug, Default)]
+pub struct BufferWordSource;
+
+impl CompletionSource for BufferWordSource {
+    fn name(&self) -> &str {
+        "buffer words"
+    }
+
+    fn complete(&self, context: &CompletionContext) -> Vec<CompletionItem> {
+        let mut distances: HashMap<String, usize> = HashMap::new();
+
+        for (buffer_index, rope) in context.buffers.iter().enumerate() {
+            for (line_index, line) in rope.lines().take(MAX_SCANNED_LINES).enumerate() {
+                let distance = if buffer_index == 0 {
+                    line_index.abs_diff(context.cursor.line)
+                } else {
+                    MAX_SCANNED_LINES + line_index
+                };
+                let mut word = String::new();
+                for c in line.chars().chain(std::iter::once(' ')) {
+                    if is_word_char(c) {
+                        word.push(c);
+                        continue;
+                    }
+                    if word.chars().count() >= MIN_WORD_LENGTH
+                        && word != context.prefix
+                        && !word.starts_with(|c: char| c.is_ascii_digit())
+                    {
+                        let best = distances
+                            .entry(std::mem::take(&mut word))
+                            .or_insert(distance);
+                        *best = (*best).min(distance);
+                    }
+                    word.clear();
+                }
+            }
+        }
+
+        distances
+            .into_iter()
+            .map(|(word, distance)| CompletionItem {
+                sort_text: Some(format!("2{:010}{}", distance, word)),
+                ..CompletionItem::new(word).with_kind("word")
+            })
+            .collect()
+    }
+}
+
+// Entries of the directory named by the path being typed, relative to the
+// buffer's directory unless it starts with `/` or `~/`.
+#[derive(Debug, Default)]
+pub struct PathSource;
+
+impl PathSource {
+    fn directory(context: &CompletionContext) -> Option<PathBuf> {
+        let token_start = context
+            .line_before_cursor
+            .rfind(|c: char| c.is_whitespace() || PATH_DELIMITERS.contains(&c))
+            .map_or(0, |index| {
+                index
+                    + context.line_before_cursor[index..]
+                        .chars()
+                        .next()
+                        .map_or(1, char::len_utf8)
+            });
+        let token = &context.line_before_cursor[token_start..];
+        let directory = &token[..token.rfind('/')? + 1];
+
+        if let Some(rest) = directory.strip_prefix("~/") {
+            Some(dirs::home_dir()?.join(rest))
+        } else if directory.starts_with('/') {
+            Some(PathBuf::from(directory))
+        } else {
+            let base = match context.directory {
+                Some(base) => base.to_path_buf(),
+                None => std::env::current_dir().ok()?,
+            };
+            Some(base.join(directory))
+        }
+    }
+}
+
+impl CompletionSource for PathSource {
+    fn name(&self) -> &str {
+        "paths"
+    }
+
+    fn complete(&self, context: &CompletionContext) -> Vec<CompletionItem> {
+        let Some(directory) = Self::directory(context) else {
+            return Vec::new();
+        };
+        let Ok(entries) = fs::read_dir(&directory) else {
+            return Vec::new();
+        };
+
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if name.starts_with('.') {
+                    return None;
+                }
+                let is_dir = entry.file_type().ok()?.is_dir();
+                let label = if is_dir { format!("{}/", name) } else { name };
+                Some(CompletionItem {
+                    sort_text: Some(format!("0{}{}", if is_dir { 0 } else { 1 }, label)),
+                    ..CompletionItem::new(label).with_kind(if is_dir { "folder" } else { "file" })
+                })
+            })
+            .take(MAX_PATH_ENTRIES)
+            .collect()
+    }
+}
+
+// Snippets for the buffer's language, offered under each of their prefixes
+// and expanded with their tabstops when accepted.
+#[derive(Debug, Default)]
+pub struct SnippetSource;
+
+impl CompletionSource for SnippetSource {
+    fn name(&self) -> &str {
+        "snippets"
+    }
+
+    fn complete(&self, context: &CompletionContext) -> Vec<CompletionItem> {
+        context
+            .snippets
+            .for_language(context.language)
+            .flat_map(snippet_items)
+            .collect()
+    }
+}
+
+pub(crate) fn snippet_items(snippet: &Snippet) -> impl Iterator<Item = CompletionItem> + '_ {
+    snippet.prefixes.iter().map(move |prefix| CompletionItem {
+        sort_text: Some(format!("1{}", prefix)),
+        ..CompletionItem::new(prefix.clone())
+            .with_kind("snippet")
+            .with_detail(snippet.description.as_deref().unwrap_or(&snippet.name))
+            .with_snippet(snippet.body.clone())
+    })
+}
@@ -0,0 +1,383 @@
+use crate::clipboard::ClipboardBackend;
+use crate::clipboard_history::DEFAULT_CLIPBOARD_HISTORY_SIZE;
+use crate::error::{EditorError, Result};
+use crate::git_history::{GcConfig, LargeFileConfig, RetentionPolicy};
+use crate::keymap::KeymapProfile;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+
+const CONFIG_DIR: &str = ".config/editor-rs";
+const USER_CONFIG_FILE: &str = "config.toml";
+pub const PROJECT_CONFIG_FILE: &str = ".editor-rs.toml";
+const MAX_TAB_SIZE: usize = 16;
+const REPLACED_KEYS: &[&str] = &["retention"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct EditorConfig {
+    pub editor: EditorSettings,
+    pub view: ViewSettings,
+    pub history: HistorySettings,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EditorSettings {
+    pub tab_size: usize,
+    pub insert_spaces: bool,
+    pub scroll_offset: usize,
+    pub soft_wrap: usize,
+    pub auto_commit: bool,
+    pub vim_mode: bool,
+    pub keymap: KeymapProfile,
+    pub clipboard: ClipboardBackend,
+    pub clipboard_history: usize,
+    pub persist_clipboard_history: bool,
+}
+
+impl Default for EditorSettings {
+    fn default() -> Self {
+        Self {
+            tab_size: 4,
+            insert_spaces: true,
+            scroll_offset: 5,
+            soft_wrap: 0,
+            auto_commit: true,
+            vim_mode: false,
+            keymap: KeymapProfile::Default,
+            clipboard: ClipboardBackend::Auto,
+            clipboard_history: DEFAULT_CLIPBOARD_HISTORY_SIZE,
+            persist_clipboard_history: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ViewSettings {
+    pub line_numbers: bool,
+    pub status_bar: bool,
+}
+
+impl Default for ViewSettings {
+    fn default() -> Self {
+        Self {
+            line_numbers: true,
+            status_bar: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistorySettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_root: Option<PathBuf>,
+    pub auto_cleanup: bool,
+    pub retention: RetentionPolicy,
+    pub large_files: LargeFileConfig,
+    pub gc: GcConfig,
+}
+
+impl HistorySettings {
+    pub fn resolved_storage_root(&self) -> Option<PathBuf> {
+        let root = self.storage_root.as_ref()?;
+        match root.strip_prefix("~") {
+            Ok(rest) => dirs::home_dir().map(|home| home.join(rest)),
+            Err(_) => Some(root.clone()),
+        }
+    }
+}
+
+impl EditorConfig {
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let config: Self =
+            toml::from_str(content).map_err(|e| EditorError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn to_toml_string(&self) -> Result<String> {
+        toml::to_string_pretty(self).map_err(|e| EditorError::Parse(e.to_string()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_toml_string()?)?;
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.editor.tab_size == 0 || self.editor.tab_size > MAX_TAB_SIZE {
+            return Err(EditorError::Parse(format!(
+                "editor.tab_size must be between 1 and {}, got {}",
+                MAX_TAB_SIZE, self.editor.tab_size
+            )));
+        }
+        if self.editor.clipboard_history == 0 {
+            return Err(EditorError::Parse(
+                "editor.clipboard_history must be greater than 0".to_string(),
+            ));
+        }
+        if self.history.large_files.threshold_mb == 0 {
+            return Err(EditorError::Parse(
+                "history.large_files.threshold_mb must be greater than 0".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn soft_wrap_width(&self) -> Option<usize> {
+        (self.editor.soft_wrap > 0).then_some(self.editor.soft_wrap)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigLayer {
+    User,
+    Project,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ConfigLoader {
+    user_path: Option<PathBuf>,
+    project_path: Option<PathBuf>,
+}
+
+impl ConfigLoader {
+    pub fn new() -> Self {
+        // Without a project config the current directory's is recorded
+        // anyway, so one created later is still picked up.
+        let project_path = std::env::current_dir().ok().map(|dir| {
+            Self::discover_project_config(&dir).unwrap_or_else(|| dir.join(PROJECT_CONFIG_FILE))
+        });
+        Self {
+            user_path: Self::user_config_path().ok(),
+            project_path,
+        }
+    }
+
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub fn with_user_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.user_path = Some(path.into());
+        self
+    }
+
+    pub fn with_project_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.project_path = Some(path.into());
+        self
+    }
+
+    pub fn user_config_path() -> Result<PathBuf> {
+        let home_dir = std::env::var("HOME").map_err(|_| {
+            EditorError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "HOME environment variable not set",
+            ))
+        })?;
+
+        Ok(PathBuf::from(home_dir)
+            .join(CONFIG_DIR)
+            .join(USER_CONFIG_FILE))
+    }
+
+    pub fn discover_project_config(start: &Path) -> Option<PathBuf> {
+        start
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_CONFIG_FILE))
+            .find(|path| path.is_file())
+    }
+
+    pub fn layers(&self) -> Vec<(ConfigLayer, &Path)> {
+        let mut layers = Vec::new();
+        if let Some(path) = &self.user_path {
+            layers.push((ConfigLayer::User, path.as_path()));
+        }
+        if let Some(path) = &self.project_path {
+            layers.push((ConfigLayer::Project, path.as_path()));
+        }
+        layers
+    }
+
+    pub fn load(&self) -> Result<EditorConfig> {
+        let mut merged = toml::Value::try_from(EditorConfig::default())
+            .map_err(|e| EditorError::Parse(e.to_string()))?;
+
+        for (_, path) in self.layers() {
+            if !path.is_file() {
+                continue;
+            }
+            let content = fs::read_to_string(path)?;
+            let layer = Self::parse_layer(&content)
+                .map_err(|e| EditorError::Parse(format!("{}: {}", path.display(), e)))?;
+            merge_values(&mut merged, layer);
+        }
+
+        let config: EditorConfig = merged
+            .try_into()
+            .map_err(|e| EditorError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn parse_layer(content: &str) -> Result<toml::Value> {
+        let value: toml::Value =
+            toml::from_str(content).map_err(|e| EditorError::Parse(e.to_string()))?;
+        let layer: EditorConfig = value
+            .clone()
+            .try_into()
+            .map_err(|e| EditorError::Parse(e.to_string()))?;
+        layer.validate()?;
+        Ok(value)
+    }
+
+    fn is_config_path(&self, path: &Path) -> bool {
+        self.layers().iter().any(|(_, config_path)| {
+            config_path.file_name() == path.file_name()
+                && same_directory(config_path.parent(), path.parent())
+        })
+    }
+}
+
+fn same_directory(a: Option<&Path>, b: Option<&Path>) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => match (a.canonicalize(), b.canonicalize()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => a == b,
+        },
+        _ => false,
+    }
+}
+
+fn merge_values(base: &mut toml::Value, overlay: toml::Value) {
+    match (base, overlay) {
+        (toml::Value::Table(base), toml::Value::Table(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) if !REPLACED_KEYS.contains(&key.as_str()) => {
+                        merge_values(existing, value)
+                    }
+                    _ => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+pub struct ConfigWatcher {
+    loader: ConfigLoader,
+    events: Receiver<notify::Result<notify::Event>>,
+    watcher: RecommendedWatcher,
+    watched: Vec<PathBuf>,
+}
+
+impl ConfigWatcher {
+    pub fn new(loader: ConfigLoader) -> Result<Self> {
+        let (tx, rx) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .map_err(|e| EditorError::InvalidOperation(format!("Cannot watch config: {}", e)))?;
+
+        let mut config_watcher = Self {
+            loader,
+            events: rx,
+            watcher,
+            watched: Vec::new(),
+        };
+        config_watcher.update_watches()?;
+        Ok(config_watcher)
+    }
+
+    // Watches the directory of each config file or, until it exists, the
+    // nearest ancestor that does, moving closer as directories appear.
+    // Returns whether the watched directories changed.
+    fn update_watches(&mut self) -> Result<bool> {
+        let mut wanted: Vec<PathBuf> = Vec::new();
+        for (_, path) in self.loader.layers() {
+            if let Some(dir) = nearest_existing_dir(path) {
+                if !wanted.contains(&dir) {
+                    wanted.push(dir);
+                }
+            }
+        }
+        if wanted == self.watched {
+            return Ok(false);
+        }
+
+        for dir in &self.watched {
+            if !wanted.contains(dir) {
+                let _ = self.watcher.unwatch(dir);
+            }
+        }
+        for dir in &wanted {
+            if !self.watched.contains(dir) {
+                self.watcher
+                    .watch(dir, RecursiveMode::NonRecursive)
+                    .map_err(|e| {
+                        EditorError::InvalidOperation(format!(
+                            "Cannot watch {}: {}",
+                            dir.display(),
+                            e
+                        ))
+                    })?;
+            }
+        }
+        self.watched = wanted;
+        Ok(true)
+    }
+
+    pub fn loader(&self) -> &ConfigLoader {
+        &self.loader
+    }
+
+    pub fn poll(&mut self) -> Option<Result<EditorConfig>> {
+        let mut changed = false;
+        let mut seen_event = false;
+        while let Ok(event) = self.events.try_recv() {
+            let Ok(event) = event else {
+                continue;
+            };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            seen_event = true;
+            if event
+                .paths
+                .iter()
+                .any(|path| self.loader.is_config_path(path))
+            {
+                changed = true;
+            }
+        }
+
+        // A config file may have been written before its new directory was
+        // watched, so moving the watches reloads too.
+        if seen_event {
+            match self.update_watches() {
+                Ok(moved) => changed |= moved,
+                Err(e) => return Some(Err(e)),
+            }
+        }
+
+        changed.then(|| self.loader.load())
+    }
+}
+
+fn nearest_existing_dir(path: &Path) -> Option<PathBuf> {
+    path.parent()?
+        .ancestors()
+        .find(|dir| dir.is_dir())
+        .map(Path::to_path_buf)
+}
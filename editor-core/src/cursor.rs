@@ -1,7 +1,8 @@
 use crate::error::{EditorError, Result};
+use crate::selection::Selection;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct CursorPosition {
     pub line: usize,
     pub column: usize,
@@ -10,6 +11,8 @@ pub struct CursorPosition {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MultiCursor {
     positions: Vec<CursorPosition>,
+    // The other end of each cursor's selection, kept in step with `positions`.
+    anchors: Vec<Option<CursorPosition>>,
 }
 
 impl CursorPosition {
@@ -88,6 +91,7 @@ impl MultiCursor {
     pub fn new() -> Self {
         Self {
             positions: vec![CursorPosition::zero()],
+            anchors: vec![None],
         }
     }
 
@@ -99,41 +103,168 @@ impl MultiCursor {
         &self.positions[0]
     }
 
+    pub fn anchors(&self) -> &[Option<CursorPosition>] {
+        &self.anchors
+    }
+
+    // The selection each cursor carries, or None where it has no anchor or
+    // the anchor sits on the cursor.
+    pub fn selections(&self) -> Vec<Option<Selection>> {
+        self.positions
+            .iter()
+            .zip(&self.anchors)
+            .map(|(position, anchor)| {
+                anchor
+                    .filter(|anchor| anchor != position)
+                    .map(|anchor| Selection::new(anchor, *position))
+            })
+            .collect()
+    }
+
+    pub fn has_selections(&self) -> bool {
+        self.selections().iter().any(Option::is_some)
+    }
+
     pub fn add_cursor(&mut self, position: CursorPosition) {
         self.positions.push(position);
+        self.anchors.push(None);
+        self.merge_overlaps();
+    }
+
+    pub fn add_selection(&mut self, selection: Selection) {
+        self.positions.push(selection.cursor);
+        self.anchors.push(Some(selection.anchor));
         self.merge_overlaps();
     }
 
     pub fn remove_cursor(&mut self, index: usize) {
         if index < self.positions.len() {
             self.positions.remove(index);
+            self.anchors.remove(index);
         }
 
         if self.positions.is_empty() {
             self.positions.push(CursorPosition::zero());
+            self.anchors.push(None);
         }
     }
 
     pub fn set_positions(&mut self, positions: Vec<CursorPosition>) {
         if positions.is_empty() {
             self.positions = vec![CursorPosition::zero()];
+            self.anchors = vec![None];
         } else {
+            self.anchors = vec![None; positions.len()];
             self.positions = positions;
             self.merge_overlaps();
         }
     }
 
+    // Moves the cursors while each keeps its anchor, as when a selection is
+    // extended.
+    pub fn move_positions(&mut self, positions: Vec<CursorPosition>) {
+        if positions.len() != self.positions.len() {
+            self.set_positions(positions);
+            return;
+        }
+        self.positions = positions;
+        self.merge_overlaps();
+    }
+
+    // Moves every cursor and anchor, as when the text around them shifts.
+    pub fn map_positions<F>(&mut self, mut f: F)
+    where
+        F: FnMut(CursorPosition) -> CursorPosition,
+    {
+        for position in &mut self.positions {
+            *position = f(*position);
+        }
+        for anchor in self.anchors.iter_mut().flatten() {
+            *anchor = f(*anchor);
+        }
+        self.merge_overlaps();
+    }
+
+    pub fn set_selections(&mut self, selections: Vec<Selection>) {
+        if selections.is_empty() {
+            self.set_positions(Vec::new());
+            return;
+        }
+        self.positions = selections
+            .iter()
+            .map(|selection| selection.cursor)
+            .collect();
+        self.anchors = selections
+            .iter()
+            .map(|selection| Some(selection.anchor))
+            .collect();
+        self.merge_overlaps();
+    }
+
+    // Anchors every cursor where it stands, starting an empty selection.
+    pub fn set_anchors(&mut self) {
+        self.anchors = self.positions.iter().copied().map(Some).collect();
+    }
+
+    pub fn clear_anchors(&mut self) {
+        self.anchors = vec![None; self.positions.len()];
+    }
+
     pub fn reset_to(&mut self, position: CursorPosition) {
         self.positions = vec![position];
+        self.anchors = vec![None];
     }
 
+    // Sorts the cursors and folds together those whose selections overlap.
+    // Selections that only touch stay apart unless one of them is empty.
     pub fn merge_overlaps(&mut self) {
-        self.positions
-            .sort_by(|a, b| a.line.cmp(&b.line).then(a.column.cmp(&b.column)));
-        self.positions.dedup();
+        let mut cursors: Vec<(CursorPosition, Option<CursorPosition>)> = self
+            .positions
+            .iter()
+            .copied()
+            .zip(self.anchors.iter().copied())
+            .collect();
+        cursors.sort_by_key(|&(position, anchor)| {
+            let start = anchor.map_or(position, |anchor| anchor.min(position));
+            (start, position)
+        });
+
+        let mut merged: Vec<(CursorPosition, Option<CursorPosition>)> = Vec::new();
+        for (position, anchor) in cursors {
+            let (start, end) = span(position, anchor);
+            let Some(last) = merged.last_mut() else {
+                merged.push((position, anchor));
+                continue;
+            };
+            let (last_start, last_end) = span(last.0, last.1);
+            let overlaps =
+                start < last_end || (start == last_end && (start == end || last_start == last_end));
+            if !overlaps {
+                merged.push((position, anchor));
+                continue;
+            }
+
+            let end = end.max(last_end);
+            *last = match last.1 {
+                Some(last_anchor) if last_anchor > last.0 => (last_start, Some(end)),
+                _ if last_start == end => (end, None),
+                _ => (end, Some(last_start)),
+            };
+        }
+
+        self.positions = merged.iter().map(|&(position, _)| position).collect();
+        self.anchors = merged.into_iter().map(|(_, anchor)| anchor).collect();
     }
 }
 
+fn span(
+    position: CursorPosition,
+    anchor: Option<CursorPosition>,
+) -> (CursorPosition, CursorPosition) {
+    let anchor = anchor.unwrap_or(position);
+    (anchor.min(position), anchor.max(position))
+}
+
 impl Default for MultiCursor {
     fn default() -> Self {
         Self::new()
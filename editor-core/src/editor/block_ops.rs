@@ -0,0 +1,191 @@
+use super::state::EditorState;
+use crate::cursor::CursorPosition;
+use crate::error::Result;
+use crate::selection::Selection;
+
+// The rows and columns covered by a block selection. The columns may be
+// the same, which leaves a zero-width block: a column cursor on every row.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct BlockRange {
+    first_line: usize,
+    last_line: usize,
+    from: usize,
+    to: usize,
+}
+
+impl BlockRange {
+    pub(super) fn of(selection: &Selection) -> Self {
+        Self {
+            first_line: selection.start().line,
+            last_line: selection.end().line,
+            from: selection.anchor.column.min(selection.cursor.column),
+            to: selection.anchor.column.max(selection.cursor.column),
+        }
+    }
+}
+
+impl EditorState {
+    // The block selection edits apply to, when there is one and only one
+    // cursor.
+    pub(super) fn block_range(&self) -> Option<BlockRange> {
+        if self.cursors.positions().len() > 1 {
+            return None;
+        }
+        self.selection
+            .filter(|s| s.is_block() && !s.is_empty())
+            .map(|selection| BlockRange::of(&selection))
+    }
+
+    // Each row of the block, padded with spaces to the block's width.
+    pub(super) fn block_rows(&self, block: BlockRange) -> Result<Vec<String>> {
+        let width = block.to - block.from;
+        (block.first_line..=block.last_line)
+            .map(|line| {
+                let row: String = self
+                    .buffer()
+                    .line(line)?
+                    .chars()
+                    .take_while(|&c| c != '\n' && c != '\r')
+                    .skip(block.from)
+                    .take(width)
+                    .collect();
+                Ok(format!("{:width$}", row))
+            })
+            .collect()
+    }
+
+    // Replaces the block's columns with `text` on every row and leaves a
+    // zero-width block after it, so typing carries on down the column.
+    pub(super) fn insert_in_block(&mut self, block: BlockRange, text: &str) -> Result<()> {
+        self.edit_block(block, |state| {
+            for line in block.first_line..=block.last_line {
+                state.replace_block_row(line, block.from, block.to, text)?;
+            }
+            Ok(block.from + text.chars().count())
+        })
+    }
+
+    // Deletes the block's columns on every row, or with a zero-width block
+    // the character before (backspace) or after it.
+    pub(super) fn delete_in_block(&mut self, block: BlockRange, forward: bool) -> Result<()> {
+        let (from, to) = match (block.from < block.to, forward) {
+            (true, _) => (block.from, block.to),
+            (false, true) => (block.from, block.from + 1),
+            (false, false) if block.from > 0 => (block.from - 1, block.from),
+            (false, false) => return Ok(()),
+        };
+        self.edit_block(block, |state| {
+            for line in block.first_line..=block.last_line {
+                state.replace_block_row(line, from, to, "")?;
+            }
+            Ok(from)
+        })
+    }
+
+    // Pastes `rows` as a rectangle at the top left of the block, one row
+    // per line. A single row is repeated on every line of the block.
+    pub(super) fn paste_in_block(&mut self, block: BlockRange, rows: &[String]) -> Result<()> {
+        if let [row] = rows {
+            return self.insert_in_block(block, row);
+        }
+        let width = rows.iter().map(|row| row.chars().count()).max();
+        self.edit_block(block, |state| {
+            for line in block.first_line..=block.last_line {
+                state.replace_block_row(line, block.from, block.to, "")?;
+            }
+            state.insert_rectangle(block.first_line, block.from, rows)?;
+            Ok(block.from + width.unwrap_or(0))
+        })
+    }
+
+    // Pastes `rows` as a rectangle with its top left corner at the cursor,
+    // adding lines at the end of the buffer when it runs out.
+    pub(super) fn paste_rectangle(&mut self, rows: &[String]) -> Result<()> {
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+
+        let cursor = *self.cursors.primary();
+        self.insert_rectangle(cursor.line, cursor.column, rows)?;
+        self.selection = None;
+
+        self.push_content_history(content_before, cursor_before, selection_before);
+        Ok(())
+    }
+
+    // Runs `edit` as one undo step and leaves a zero-width block at the
+    // column it returns, with the cursor still on its row.
+    fn edit_block<F>(&mut self, block: BlockRange, edit: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<usize>,
+    {
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+
+        let column = edit(self)?;
+        let line = self.cursors.primary().line;
+        let cursor = CursorPosition::new(line, column);
+        self.cursors.reset_to(cursor);
+        self.selection = (block.first_line < block.last_line).then(|| {
+            let anchor_line = if line == block.first_line {
+                block.last_line
+            } else {
+                block.first_line
+            };
+            Selection::new_block(CursorPosition::new(anchor_line, column), cursor)
+        });
+
+        self.push_content_history(content_before, cursor_before, selection_before);
+        Ok(())
+    }
+
+    // Inserts each of `rows` at `column` on consecutive lines from `line`.
+    // Rows going in before other text are padded so that text stays lined up.
+    fn insert_rectangle(&mut self, line: usize, column: usize, rows: &[String]) -> Result<()> {
+        let width = rows
+            .iter()
+            .map(|row| row.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (offset, row) in rows.iter().enumerate() {
+            let line = line + offset;
+            if line >= self.buffer().line_count() {
+                let last = self.buffer().line_count() - 1;
+                let last_len = self.buffer().line_len(last)?;
+                self.buffer_mut().insert_str(last, last_len, "\n")?;
+            }
+            let line_len = self.buffer().line_len(line)?;
+            let row = if line_len > column {
+                format!("{:width$}", row)
+            } else {
+                row.clone()
+            };
+            self.replace_block_row(line, column, column, &row)?;
+        }
+        Ok(())
+    }
+
+    // Replaces columns `from..to` of `line` with `text`, padding the line
+    // with spaces first if it ends before `from` and there is text to add.
+    fn replace_block_row(&mut self, line: usize, from: usize, to: usize, text: &str) -> Result<()> {
+        let line_len = self.buffer().line_len(line)?;
+        if line_len < from {
+            if !text.is_empty() {
+                let padding = " ".repeat(from - line_len);
+                self.buffer_mut()
+                    .insert_str(line, line_len, &(padding + text))?;
+            }
+            return Ok(());
+        }
+
+        let to = to.min(line_len);
+        if from < to {
+            self.buffer_mut().delete_range(line, from, line, to)?;
+        }
+        if !text.is_empty() {
+            self.buffer_mut().insert_str(line, from, text)?;
+        }
+        Ok(())
+    }
+}
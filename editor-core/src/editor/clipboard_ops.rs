@@ -1,10 +1,30 @@
+use super::block_ops::BlockRange;
 use super::state::EditorState;
+use crate::clipboard::ClipboardBackend;
+use crate::clipboard_history::ClipboardHistory;
 use crate::cursor::CursorPosition;
-use crate::error::Result;
+use crate::error::{EditorError, Result};
 use crate::selection::{Selection, SelectionMode};
 
 impl EditorState {
+    pub fn clipboard_history(&self) -> &ClipboardHistory {
+        &self.clipboard_history
+    }
+
+    // The clipboard backend copies go to, with `auto` resolved.
+    pub fn clipboard_backend(&self) -> ClipboardBackend {
+        self.clipboard.active_backend()
+    }
+
+    pub fn set_clipboard_history_size(&mut self, size: usize) {
+        self.clipboard_history.set_capacity(size);
+    }
+
     pub(super) fn selection_start(&mut self) -> Result<()> {
+        if self.cursors.positions().len() > 1 {
+            self.cursors.set_anchors();
+            return Ok(());
+        }
         let cursor_pos = *self.cursors.primary();
         let mode = if self.block_selection_mode {
             SelectionMode::Block
@@ -20,6 +40,10 @@ impl EditorState {
     }
 
     pub(super) fn selection_end(&mut self) -> Result<()> {
+        // Several cursors extend their selections as they move.
+        if self.cursors.positions().len() > 1 {
+            return Ok(());
+        }
         if let Some(selection) = &self.selection {
             let anchor = selection.anchor;
             let cursor_pos = *self.cursors.primary();
@@ -45,35 +69,18 @@ impl EditorState {
                     let end = selection.end();
                     self.get_text_range(start, end)
                 }
-                SelectionMode::Block => {
-                    let start = selection.start();
-                    let end = selection.end();
-                    let min_col = selection.anchor.column.min(selection.cursor.column);
-                    let max_col = selection.anchor.column.max(selection.cursor.column);
-
-                    let mut result = String::new();
-                    for line in start.line..=end.line {
-                        let line_len = self.buffer().line_len(line)?;
-                        let start_col = min_col.min(line_len);
-                        let end_col = max_col.min(line_len);
-
-                        let start_pos = CursorPosition::new(line, start_col);
-                        let end_pos = CursorPosition::new(line, end_col);
-
-                        if !result.is_empty() {
-                            result.push('\n');
-                        }
-                        result.push_str(&self.get_text_range(start_pos, end_pos)?);
-                    }
-                    Ok(result)
-                }
+                SelectionMode::Block => Ok(self.block_rows(BlockRange::of(selection))?.join("\n")),
             }
         } else {
             Ok(String::new())
         }
     }
 
-    fn get_text_range(&self, start: CursorPosition, end: CursorPosition) -> Result<String> {
+    pub(super) fn get_text_range(
+        &self,
+        start: CursorPosition,
+        end: CursorPosition,
+    ) -> Result<String> {
         let start_idx = self.buffer().char_index(start.line, start.column)?;
         let end_idx = self.buffer().char_index(end.line, end.column)?;
 
@@ -87,16 +94,64 @@ impl EditorState {
     }
 
     pub(super) fn copy(&mut self) -> Result<()> {
+        if self.cursors.positions().len() > 1 && self.cursors.has_selections() {
+            self.copy_pieces()?;
+            return Ok(());
+        }
         let text = self.get_selected_text()?;
-        self.clipboard.set_text(&text)?;
+        self.set_clipboard_text(&text)?;
+        self.clipboard_pieces.clear();
+        self.remember_rectangle(&text);
+        Ok(())
+    }
+
+    // Puts `text` on the system clipboard and at the top of the history.
+    pub(super) fn set_clipboard_text(&mut self, text: &str) -> Result<()> {
+        self.clipboard.set_text(text)?;
+        self.clipboard_history.push(text);
+        Ok(())
+    }
+
+    // Reads the system clipboard. Text copied outside the editor since the
+    // last copy here goes into the history as well.
+    pub(super) fn clipboard_text(&mut self) -> Result<String> {
+        let text = self.clipboard.get_text()?;
+        if self.clipboard_history.latest() != Some(text.as_str()) {
+            self.clipboard_history.push(text.as_str());
+        }
+        Ok(text)
+    }
+
+    // Block selections copy as rectangles, and paste back as one for as
+    // long as the clipboard still holds them.
+    fn remember_rectangle(&mut self, text: &str) {
+        let block = self.selection.is_some_and(|s| s.is_block());
+        self.clipboard_rectangle = block.then(|| text.to_string());
+    }
+
+    // Copies each cursor's selection, one per line of the clipboard.
+    fn copy_pieces(&mut self) -> Result<()> {
+        let pieces = self
+            .cursor_spans()
+            .into_iter()
+            .map(|(start, end)| self.get_text_range(start, end))
+            .collect::<Result<Vec<_>>>()?;
+        self.set_clipboard_text(&pieces.join("\n"))?;
+        self.clipboard_pieces = pieces;
         Ok(())
     }
 
     pub(super) fn cut(&mut self) -> Result<()> {
-        if let Some(selection) = &self.selection {
+        if self.cursors.positions().len() > 1 && self.cursors.has_selections() {
+            self.copy_pieces()?;
+            return self.replace_cursor_spans(|_, _| String::new());
+        }
+        if let Some(selection) = self.selection {
             if !selection.is_empty() {
                 let text = self.get_selected_text()?;
-                self.clipboard.set_text(&text)?;
+                self.set_clipboard_text(&text)?;
+                self.clipboard_pieces.clear();
+                self.remember_rectangle(&text);
 
                 match selection.mode {
                     SelectionMode::Normal => {
@@ -131,7 +186,73 @@ impl EditorState {
     }
 
     pub(super) fn paste(&mut self) -> Result<()> {
-        let text = self.clipboard.get_text()?;
+        let text = self.clipboard_text()?;
+        self.paste_text(text)
+    }
+
+    // Pastes an earlier clipboard entry, which also makes it the current
+    // clipboard contents.
+    pub(super) fn paste_from_history(&mut self, index: usize) -> Result<()> {
+        let text = self
+            .clipboard_history
+            .get(index)
+            .map(|entry| entry.text.clone())
+            .ok_or_else(|| {
+                EditorError::InvalidOperation(format!("No clipboard history entry {}", index))
+            })?;
+        self.set_clipboard_text(&text)?;
+        self.paste_text(text)
+    }
+
+    pub(super) fn toggle_clipboard_pin(&mut self, index: usize) -> Result<()> {
+        let pinned = self.clipboard_history.toggle_pin(index).ok_or_else(|| {
+            EditorError::InvalidOperation(format!("No clipboard history entry {}", index))
+        })?;
+        self.status_message = if pinned {
+            "Clipboard entry pinned".to_string()
+        } else {
+            "Clipboard entry unpinned".to_string()
+        };
+        Ok(())
+    }
+
+    // Pinned entries survive clearing the history.
+    pub(super) fn clear_clipboard_history(&mut self) -> Result<()> {
+        self.clipboard_history.clear();
+        self.status_message = "Clipboard history cleared".to_string();
+        Ok(())
+    }
+
+    // Inserts text exactly as it came, such as a bracketed paste from the
+    // terminal: no auto-indent or closing brackets, the same text at every
+    // cursor, and one undo step however long it is.
+    pub(super) fn insert_text(&mut self, text: &str) -> Result<()> {
+        let text = text.replace("\r\n", "\n").replace('\r', "\n");
+        if text.is_empty() {
+            return Ok(());
+        }
+        if let Some(block) = self.block_range() {
+            let rows: Vec<String> = text.split('\n').map(str::to_string).collect();
+            return self.paste_in_block(block, &rows);
+        }
+        self.replace_cursor_spans(|_, _| text.clone())
+    }
+
+    fn paste_text(&mut self, text: String) -> Result<()> {
+        if self.cursors.positions().len() > 1 && !text.is_empty() {
+            let pieces = self.paste_pieces(&text);
+            return self.replace_cursor_spans(|index, _| match &pieces {
+                Some(pieces) => pieces[index].clone(),
+                None => text.clone(),
+            });
+        }
+        let rows: Vec<String> = text.lines().map(str::to_string).collect();
+        if let Some(block) = self.block_range().filter(|_| !rows.is_empty()) {
+            return self.paste_in_block(block, &rows);
+        }
+        if self.clipboard_rectangle.as_deref() == Some(text.as_str()) {
+            return self.paste_rectangle(&rows);
+        }
         if !text.is_empty() {
             if let Some(selection) = &self.selection {
                 if !selection.is_empty() {
@@ -170,6 +291,100 @@ impl EditorState {
         Ok(())
     }
 
+    // Hands each cursor its own piece when the clipboard holds what as many
+    // cursors copied, or as many lines as there are cursors.
+    fn paste_pieces(&self, text: &str) -> Option<Vec<String>> {
+        let count = self.cursors.positions().len();
+        if self.clipboard_pieces.len() == count && self.clipboard_pieces.join("\n") == text {
+            return Some(self.clipboard_pieces.clone());
+        }
+        let lines: Vec<String> = text.lines().map(str::to_string).collect();
+        (lines.len() == count).then_some(lines)
+    }
+
+    // Deletes what every cursor has selected, leaving the cursors where the
+    // selections were. Only applies with several cursors; returns whether
+    // anything was deleted.
+    pub(super) fn delete_cursor_selections(&mut self) -> Result<bool> {
+        if self.cursors.positions().len() < 2 || !self.cursors.has_selections() {
+            return Ok(false);
+        }
+        let spans = self.cursor_spans();
+        let replaced = self.replace_spans(&spans, |_, _| String::new())?;
+        self.cursors
+            .set_positions(replaced.into_iter().map(|(_, end)| end).collect());
+        self.selection = None;
+        Ok(true)
+    }
+
+    // Replaces every cursor's span with what `replace` makes of its text and
+    // leaves each cursor after its replacement, as one undo step.
+    pub(super) fn replace_cursor_spans<F>(&mut self, replace: F) -> Result<()>
+    where
+        F: FnMut(usize, &str) -> String,
+    {
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+
+        let spans = self.cursor_spans();
+        let replaced = self.replace_spans(&spans, replace)?;
+        self.cursors
+            .set_positions(replaced.into_iter().map(|(_, end)| end).collect());
+        self.selection = None;
+
+        self.push_content_history(content_before, cursor_before, selection_before);
+        Ok(())
+    }
+
+    // Replaces each span with the text `replace` makes of it and returns
+    // where the replacements ended up. Spans must be in order and must not
+    // overlap.
+    pub(super) fn replace_spans<F>(
+        &mut self,
+        spans: &[(CursorPosition, CursorPosition)],
+        mut replace: F,
+    ) -> Result<Vec<(CursorPosition, CursorPosition)>>
+    where
+        F: FnMut(usize, &str) -> String,
+    {
+        // Working back from the end keeps the earlier spans where they are.
+        let mut replaced = Vec::with_capacity(spans.len());
+        for (index, &(start, end)) in spans.iter().enumerate().rev() {
+            let old_text = self.get_text_range(start, end)?;
+            let new_text = replace(index, &old_text);
+            if start != end {
+                self.buffer_mut()
+                    .delete_range(start.line, start.column, end.line, end.column)?;
+            }
+            if !new_text.is_empty() {
+                self.buffer_mut()
+                    .insert_str(start.line, start.column, &new_text)?;
+            }
+            let start_idx = self.buffer().char_index(start.line, start.column)?;
+            replaced.push((
+                start_idx,
+                old_text.chars().count(),
+                new_text.chars().count(),
+            ));
+        }
+
+        // Each replacement moves the ones after it by what it added.
+        let mut shift = 0isize;
+        let mut ranges = Vec::with_capacity(replaced.len());
+        for (start_idx, old_len, new_len) in replaced.into_iter().rev() {
+            let start_idx = start_idx.saturating_add_signed(shift);
+            let (line, column) = self.buffer().char_to_line_col(start_idx)?;
+            let (end_line, end_column) = self.buffer().char_to_line_col(start_idx + new_len)?;
+            ranges.push((
+                CursorPosition::new(line, column),
+                CursorPosition::new(end_line, end_column),
+            ));
+            shift += new_len as isize - old_len as isize;
+        }
+        Ok(ranges)
+    }
+
     fn delete_range(&mut self, start: CursorPosition, end: CursorPosition) -> Result<()> {
         let start_idx = self.buffer().char_index(start.line, start.column)?;
         let end_idx = self.buffer().char_index(end.line, end.column)?;
@@ -0,0 +1,284 @@
+use crate::completion::{CompletionContext, CompletionItem, CompletionMenu, CompletionSource};
+use crate::cursor::CursorPosition;
+use crate::error::{EditorError, Result};
+use crate::lsp::{completion_kind_name, from_lsp_position, LspCompletionItem, LspPosition};
+use ropey::Rope;
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::state::EditorState;
+use super::state_utils::is_word_char;
+
+impl EditorState {
+    pub fn completion_menu(&self) -> Option<&CompletionMenu> {
+        self.completion.as_ref().map(|(_, menu)| menu)
+    }
+
+    pub fn is_completion_open(&self) -> bool {
+        self.completion.is_some()
+    }
+
+    pub fn add_completion_source(&mut self, source: Box<dyn CompletionSource>) {
+        self.completion_sources.push(source);
+    }
+
+    pub fn completion_source_names(&self) -> Vec<&str> {
+        self.completion_sources
+            .iter()
+            .map(|source| source.name())
+            .collect()
+    }
+
+    pub(super) fn word_start_before(&self, cursor: CursorPosition) -> CursorPosition {
+        let chars: Vec<char> = self.buffer().rope().line(cursor.line).chars().collect();
+        let mut start = cursor.column.min(chars.len());
+        while start > 0 && is_word_char(chars[start - 1]) {
+            start -= 1;
+        }
+        CursorPosition::new(cursor.line, start)
+    }
+
+    fn word_start_before_cursor(&self) -> CursorPosition {
+        self.word_start_before(*self.cursor())
+    }
+
+    pub fn local_completions(&self) -> Vec<CompletionItem> {
+        let cursor = *self.cursor();
+        let start = self.word_start_before_cursor();
+        let line_before_cursor: String = self
+            .buffer()
+            .rope()
+            .line(cursor.line)
+            .chars()
+            .take(cursor.column)
+            .collect();
+        let prefix: String = line_before_cursor.chars().skip(start.column).collect();
+        let directory = self.buffer().file_path().and_then(|path| path.parent());
+        let buffers: Vec<&Rope> = std::iter::once(self.buffer().rope())
+            .chain(
+                self.buffers
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| *index != self.current_buffer_index)
+                    .map(|(_, buffer)| buffer.rope()),
+            )
+            .collect();
+
+        let context = CompletionContext {
+            language: &self.language().name,
+            prefix: &prefix,
+            line_before_cursor: &line_before_cursor,
+            cursor,
+            directory,
+            buffers: &buffers,
+            snippets: &self.snippets,
+        };
+        self.completion_sources
+            .iter()
+            .flat_map(|source| source.complete(&context))
+            .collect()
+    }
+
+    // Local sources answer straight away; a language server's items are
+    // merged in front of them when its response arrives.
+    pub(super) fn trigger_completion(&mut self) -> Result<()> {
+        let requested = self.has_current_language_server("completionProvider");
+        if requested {
+            self.request_lsp_completion()?;
+        }
+
+        let items = self.local_completions();
+        if !(requested && items.is_empty()) {
+            self.open_completion(items);
+        }
+        Ok(())
+    }
+
+    fn completion_query(&self, start: CursorPosition) -> Option<String> {
+        let cursor = *self.cursor();
+        if cursor.line != start.line || cursor.column < start.column {
+            return None;
+        }
+        let query: String = self
+            .buffer()
+            .rope()
+            .line(cursor.line)
+            .chars()
+            .skip(start.column)
+            .take(cursor.column - start.column)
+            .collect();
+        query.chars().all(is_word_char).then_some(query)
+    }
+
+    pub fn open_completion(&mut self, items: Vec<CompletionItem>) {
+        let start = self.word_start_before_cursor();
+        let query = self.completion_query(start).unwrap_or_default();
+        let menu = CompletionMenu::new(start, query, items);
+        if menu.is_empty() {
+            self.completion = None;
+            self.status_message = "No completions".to_string();
+        } else {
+            self.completion = Some((self.current_buffer_index, menu));
+        }
+    }
+
+    // Responses for a position the cursor has since left are stale and
+    // dropped rather than popping up somewhere unexpected.
+    pub(super) fn open_lsp_completion(
+        &mut self,
+        path: &Path,
+        position: LspPosition,
+        items: Vec<LspCompletionItem>,
+    ) {
+        if self.buffer().file_path().map(|p| p.as_path()) != Some(path) {
+            return;
+        }
+        let requested = from_lsp_position(self.buffer().rope(), position);
+        if requested.line != self.cursor().line {
+            return;
+        }
+
+        let mut seen = HashSet::new();
+        let items = items
+            .into_iter()
+            .map(|item| CompletionItem {
+                kind: item.kind.map(|kind| completion_kind_name(kind).to_string()),
+                detail: item.detail,
+                insert_text: item.insert_text,
+                is_snippet: item.is_snippet,
+                filter_text: item.filter_text,
+                sort_text: item.sort_text,
+                label: item.label,
+            })
+            .chain(self.local_completions())
+            .filter(|item| seen.insert(item.label.clone()))
+            .collect();
+        self.open_completion(items);
+    }
+
+    fn completion_required(&mut self) -> Result<&mut CompletionMenu> {
+        self.completion
+            .as_mut()
+            .map(|(_, menu)| menu)
+            .ok_or_else(|| EditorError::InvalidOperation("Completion menu is not open".to_string()))
+    }
+
+    pub(super) fn completion_next(&mut self) -> Result<()> {
+        self.completion_required()?.select_next();
+        Ok(())
+    }
+
+    pub(super) fn completion_previous(&mut self) -> Result<()> {
+        self.completion_required()?.select_previous();
+        Ok(())
+    }
+
+    pub(super) fn completion_select(&mut self, index: usize) -> Result<()> {
+        if self.completion_required()?.select_index(index) {
+            Ok(())
+        } else {
+            Err(EditorError::InvalidOperation(format!(
+                "Completion entry {} does not exist",
+                index
+            )))
+        }
+    }
+
+    pub(super) fn completion_cancel(&mut self) -> Result<()> {
+        self.completion = None;
+        Ok(())
+    }
+
+    // Every cursor gets the completion in place of the word before it, so
+    // typing a prefix at several cursors and accepting completes them all.
+    pub(super) fn completion_accept(&mut self) -> Result<()> {
+        let Some((_, menu)) = self.completion.take() else {
+            return Err(EditorError::InvalidOperation(
+                "Completion menu is not open".to_string(),
+            ));
+        };
+        let Some(item) = menu.selected_item() else {
+            return Ok(());
+        };
+
+        let primary = *self.cursor();
+        let query_length = primary.column.saturating_sub(menu.start().column);
+        let mut ranges = Vec::new();
+        for &cursor in self.cursors.positions() {
+            let start = if cursor == primary {
+                menu.start()
+            } else {
+                let word_start = self.word_start_before(cursor);
+                CursorPosition::new(
+                    cursor.line,
+                    word_start
+                        .column
+                        .max(cursor.column.saturating_sub(query_length)),
+                )
+            };
+            let start = self.buffer().char_index(start.line, start.column)?;
+            let end = self.buffer().char_index(cursor.line, cursor.column)?;
+            ranges.push((start.min(end), end));
+        }
+        if item.is_snippet {
+            return self.insert_snippet_at(ranges, &item.insert_text);
+        }
+        ranges.sort_unstable();
+        ranges.dedup();
+
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+        let inserted = item.insert_text.chars().count();
+
+        let mut shift = 0isize;
+        let mut after = Vec::with_capacity(ranges.len());
+        for &(start, end) in &ranges {
+            after.push((start as isize + shift) as usize + inserted);
+            shift += inserted as isize - (end - start) as isize;
+        }
+
+        for &(start, end) in ranges.iter().rev() {
+            let (start_line, start_column) = self.buffer().char_to_line_col(start)?;
+            let (end_line, end_column) = self.buffer().char_to_line_col(end)?;
+            self.buffer_mut()
+                .delete_range(start_line, start_column, end_line, end_column)?;
+            self.buffer_mut()
+                .insert_str(start_line, start_column, &item.insert_text)?;
+        }
+
+        let mut positions = Vec::with_capacity(after.len());
+        for index in after {
+            let (line, column) = self.buffer().char_to_line_col(index)?;
+            positions.push(CursorPosition::new(line, column));
+        }
+        self.cursors.set_positions(positions);
+        self.selection = None;
+        self.push_content_history(content_before, cursor_before, selection_before);
+        Ok(())
+    }
+
+    // Typing keeps the menu open and narrows it; moving off the word, to
+    // another line or to another buffer closes it.
+    pub(super) fn sync_completion(&mut self) {
+        let Some((buffer_index, menu)) = &self.completion else {
+            return;
+        };
+        if *buffer_index != self.current_buffer_index {
+            self.completion = None;
+            return;
+        }
+
+        match self.completion_query(menu.start()) {
+            Some(query) => {
+                if let Some((_, menu)) = &mut self.completion {
+                    menu.set_query(query);
+                    if menu.is_empty() {
+                        self.completion = None;
+                    }
+                }
+            }
+            None => self.completion = None,
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::clipboard::ClipboardManager;
+use crate::config::EditorConfig;
+use crate::editor::state::EditorState;
+use crate::error::Result;
+use crate::git_history::GitHistoryManager;
+use crate::keymap::KeymapProfile;
+
+impl EditorState {
+    pub fn apply_config(&mut self, config: &EditorConfig) -> Result<()> {
+        config.validate()?;
+
+        self.set_tab_size(config.editor.tab_size);
+        self.set_insert_spaces(config.editor.insert_spaces);
+        self.set_scroll_offset(config.editor.scroll_offset);
+        self.soft_wrap_width = config.soft_wrap_width();
+        self.set_auto_commit_enabled(config.editor.auto_commit);
+        self.set_vim_enabled(config.editor.vim_mode);
+        self.keymap_profile = config.editor.keymap;
+        if self.clipboard.backend() != config.editor.clipboard {
+            self.clipboard = ClipboardManager::with_backend(config.editor.clipboard);
+        }
+        self.set_clipboard_history_size(config.editor.clipboard_history);
+        self.persist_clipboard_history = config.editor.persist_clipboard_history;
+
+        let history = &config.history;
+        self.set_large_file_config(history.large_files.clone());
+        self.git_history.set_gc_config(history.gc.clone());
+        self.git_history
+            .set_retention_policy(history.retention.clone());
+        self.git_history
+            .set_auto_cleanup_enabled(history.auto_cleanup);
+
+        let storage_root = match history.resolved_storage_root() {
+            Some(root) => root,
+            None => GitHistoryManager::default_storage_root()?,
+        };
+        self.git_history.set_storage_root(storage_root);
+
+        Ok(())
+    }
+
+    pub fn keymap_profile(&self) -> KeymapProfile {
+        self.keymap_profile
+    }
+}
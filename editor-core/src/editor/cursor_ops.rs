@@ -6,8 +6,8 @@ use crate::error::{EditorError, Result};
 impl EditorState {
     pub(super) fn move_cursor_up(&mut self) -> Result<()> {
         self.map_cursors(|state, mut pos| {
-            if pos.line > 0 {
-                pos.line -= 1;
+            if let Some(line) = state.buffer().folds().previous_visible(pos.line) {
+                pos.line = line;
                 let line_len = state.buffer().line_len(pos.line)?;
                 if pos.column > line_len {
                     pos.column = line_len;
@@ -19,8 +19,9 @@ impl EditorState {
 
     pub(super) fn move_cursor_down(&mut self) -> Result<()> {
         self.map_cursors(|state, mut pos| {
-            if pos.line + 1 < state.buffer().line_count() {
-                pos.line += 1;
+            let line_count = state.buffer().line_count();
+            if let Some(line) = state.buffer().folds().next_visible(pos.line, line_count) {
+                pos.line = line;
                 let line_len = state.buffer().line_len(pos.line)?;
                 if pos.column > line_len {
                     pos.column = line_len;
@@ -34,8 +35,8 @@ impl EditorState {
         self.map_cursors(|state, mut pos| {
             if pos.column > 0 {
                 pos.column -= 1;
-            } else if pos.line > 0 {
-                pos.line -= 1;
+            } else if let Some(line) = state.buffer().folds().previous_visible(pos.line) {
+                pos.line = line;
                 pos.column = state.buffer().line_len(pos.line)?;
             }
             Ok(pos)
@@ -45,10 +46,11 @@ impl EditorState {
     pub(super) fn move_cursor_right(&mut self) -> Result<()> {
         self.map_cursors(|state, mut pos| {
             let line_len = state.buffer().line_len(pos.line)?;
+            let line_count = state.buffer().line_count();
             if pos.column < line_len {
                 pos.column += 1;
-            } else if pos.line + 1 < state.buffer().line_count() {
-                pos.line += 1;
+            } else if let Some(line) = state.buffer().folds().next_visible(pos.line, line_count) {
+                pos.line = line;
                 pos.column = 0;
             }
             Ok(pos)
@@ -155,11 +157,7 @@ impl EditorState {
 
     pub(super) fn page_up(&mut self, lines: usize) -> Result<()> {
         self.map_cursors(|state, mut pos| {
-            if pos.line >= lines {
-                pos.line -= lines;
-            } else {
-                pos.line = 0;
-            }
+            pos.line = state.buffer().folds().visible_line_above(pos.line, lines);
 
             let line_len = state.buffer().line_len(pos.line)?;
             if pos.column > line_len {
@@ -171,18 +169,13 @@ impl EditorState {
     }
 
     pub(super) fn page_down(&mut self, lines: usize) -> Result<()> {
-        let max_line = if self.buffer().line_count() > 0 {
-            self.buffer().line_count() - 1
-        } else {
-            0
-        };
+        let line_count = self.buffer().line_count();
 
         self.map_cursors(|state, mut pos| {
-            if pos.line + lines <= max_line {
-                pos.line += lines;
-            } else {
-                pos.line = max_line;
-            }
+            pos.line = state
+                .buffer()
+                .folds()
+                .visible_line_below(pos.line, lines, line_count);
 
             let line_len = state.buffer().line_len(pos.line)?;
             if pos.column > line_len {
@@ -204,6 +197,7 @@ impl EditorState {
 
     pub(super) fn add_cursor(&mut self, position: CursorPosition) -> Result<()> {
         self.validate_position(position)?;
+        self.adopt_primary_selection();
         self.cursors.add_cursor(position);
         Ok(())
     }
@@ -224,80 +218,42 @@ impl EditorState {
             return;
         }
 
-        let primary = self.cursors.primary();
+        let line = self.cursors.primary().line;
         let offset = self.scroll_offset.min(viewport_height / 2);
+        let folds = self.buffer().folds();
+        let rows_above = folds.count_visible(self.viewport_top, line);
 
-        if primary.line < self.viewport_top + offset {
-            self.viewport_top = primary.line.saturating_sub(offset);
-        } else if primary.line >= self.viewport_top + viewport_height - offset {
-            self.viewport_top = primary.line.saturating_sub(viewport_height - offset - 1);
+        if line < self.viewport_top || rows_above < offset {
+            self.viewport_top = folds.visible_line_above(line, offset);
+        } else if rows_above >= viewport_height - offset {
+            self.viewport_top = folds.visible_line_above(line, viewport_height - offset - 1);
         }
     }
 
     pub(super) fn jump_to_matching_bracket(&mut self) -> Result<()> {
+        let language = self.language().clone();
         self.map_cursors(|state, pos| {
             let char_idx = state.buffer().char_index(pos.line, pos.column)?;
-
-            if char_idx >= state.buffer().len_chars() {
+            let Some(current_char) = state.buffer().char_at(char_idx) else {
                 return Ok(pos);
-            }
-
-            let current_char = state.buffer().char_at(char_idx);
-            if current_char.is_none() {
+            };
+            let Some((open, close, is_open)) = language.bracket_pair(current_char) else {
                 return Ok(pos);
-            }
-            let current_char = current_char.unwrap();
-
-            let (opening, closing, direction) = match current_char {
-                '(' => ('(', ')', 1),
-                ')' => ('(', ')', -1),
-                '[' => ('[', ']', 1),
-                ']' => ('[', ']', -1),
-                '{' => ('{', '}', 1),
-                '}' => ('{', '}', -1),
-                '<' => ('<', '>', 1),
-                '>' => ('<', '>', -1),
-                _ => return Ok(pos),
             };
 
-            let mut depth = 0;
-            let mut idx = char_idx as isize;
-            let len = state.buffer().len_chars() as isize;
-
-            loop {
-                if direction == 1 {
-                    if idx >= len {
-                        break;
-                    }
-                } else if idx < 0 {
-                    break;
-                }
+            let target = if is_open {
+                state.find_unmatched_bracket(char_idx + 1, open, close, true)
+            } else {
+                state.find_unmatched_bracket(char_idx, open, close, false)
+            };
 
-                if let Some(ch) = state.buffer().char_at(idx as usize) {
-                    if ch == opening {
-                        if direction == 1 {
-                            depth += 1;
-                        } else {
-                            depth -= 1;
-                        }
-                    } else if ch == closing {
-                        if direction == 1 {
-                            depth -= 1;
-                        } else {
-                            depth += 1;
-                        }
-                    }
-
-                    if depth == 0 && idx != char_idx as isize {
-                        let (line, column) = state.buffer().char_to_line_col(idx as usize)?;
-                        return Ok(CursorPosition::new(line, column));
-                    }
+            match target {
+                Some(idx) => {
+                    let (line, column) = state.buffer().char_to_line_col(idx)?;
+                    Ok(CursorPosition::new(line, column))
                 }
-
-                idx += direction;
+                None => Ok(pos),
             }
-
-            Ok(pos)
         })
     }
 }
@@ -0,0 +1,350 @@
+use super::state::EditorState;
+use super::state_utils::is_word_char;
+use crate::cursor::CursorPosition;
+use crate::error::Result;
+use crate::selection::Selection;
+use std::ops::Range;
+use tree_sitter::Node;
+
+const QUOTES: [char; 3] = ['"', '\'', '`'];
+
+// What each cursor had selected before every expansion still in effect,
+// so that shrinking can step back through them.
+#[derive(Debug, Clone)]
+pub(super) struct SelectionExpansion {
+    previous: Vec<Vec<Selection>>,
+    current: Vec<(CursorPosition, CursorPosition)>,
+}
+
+impl EditorState {
+    // Grows every cursor's selection to the next enclosing word, string,
+    // bracket contents, brackets, line, paragraph or the whole buffer.
+    pub(super) fn expand_selection(&mut self) -> Result<()> {
+        let spans = self.cursor_spans();
+        let mut expanded = Vec::with_capacity(spans.len());
+        for &(start, end) in &spans {
+            let range = self.span_range(start, end)?;
+            let grown = self.expand_range(range.clone()).unwrap_or(range);
+            expanded.push(self.range_selection(grown)?);
+        }
+
+        let before: Vec<Selection> = spans
+            .iter()
+            .map(|&(start, end)| Selection::new(start, end))
+            .collect();
+        if expanded == before {
+            return Ok(());
+        }
+
+        let mut previous = match self.expansion.take() {
+            Some(expansion) if expansion.current == spans => expansion.previous,
+            _ => Vec::new(),
+        };
+        previous.push(before);
+        self.set_selections(expanded);
+        self.expansion = Some(SelectionExpansion {
+            previous,
+            current: self.cursor_spans(),
+        });
+        Ok(())
+    }
+
+    // Steps back to the selections from before the last expansion.
+    pub(super) fn shrink_selection(&mut self) -> Result<()> {
+        let Some(mut expansion) = self
+            .expansion
+            .take()
+            .filter(|expansion| expansion.current == self.cursor_spans())
+        else {
+            return Ok(());
+        };
+        let Some(selections) = expansion.previous.pop() else {
+            return Ok(());
+        };
+
+        let collapsed = selections.iter().all(Selection::is_empty);
+        self.set_selections(selections);
+        if collapsed {
+            self.cursors.clear_anchors();
+            self.selection = None;
+        }
+        if !expansion.previous.is_empty() {
+            expansion.current = self.cursor_spans();
+            self.expansion = Some(expansion);
+        }
+        Ok(())
+    }
+
+    // The smallest step that strictly contains `range`.
+    pub(super) fn expand_range(&self, range: Range<usize>) -> Option<Range<usize>> {
+        let mut candidates = Vec::new();
+        candidates.extend(self.word_range(range.clone()));
+        for (inner, outer) in self
+            .quote_ranges(range.clone())
+            .into_iter()
+            .chain(self.bracket_ranges(range.clone()))
+        {
+            candidates.push(inner);
+            candidates.push(outer);
+        }
+        candidates.extend(self.line_ranges(range.clone()));
+        candidates.extend(self.paragraph_range(range.clone()));
+        candidates.push(0..self.buffer().len_chars());
+
+        candidates
+            .into_iter()
+            .filter(|candidate| contains(candidate, &range) && candidate.len() > range.len())
+            .min_by_key(|candidate| candidate.len())
+    }
+
+    pub(super) fn word_range(&self, range: Range<usize>) -> Option<Range<usize>> {
+        let rope = self.buffer().rope();
+        let is_word = |idx: usize| idx < rope.len_chars() && is_word_char(rope.char(idx));
+        let mut start = range.start;
+        while start > 0 && is_word(start - 1) {
+            start -= 1;
+        }
+        let mut end = range.start;
+        while is_word(end) {
+            end += 1;
+        }
+        (start < end).then_some(start..end)
+    }
+
+    // The strings around `range`, innermost first, as their contents and
+    // the whole string.
+    pub(super) fn quote_ranges(&self, range: Range<usize>) -> Vec<(Range<usize>, Range<usize>)> {
+        if let Some(tree) = self.buffer().syntax_tree() {
+            return self.enclosing_nodes(tree.root_node(), &range, |node| {
+                self.string_node_ranges(node)
+            });
+        }
+
+        // Without a syntax tree, strings are found on the line by pairing
+        // up unescaped quotes.
+        let rope = self.buffer().rope();
+        let line = rope.char_to_line(range.start);
+        let line_start = rope.line_to_char(line);
+        let chars: Vec<char> = rope.line(line).chars().collect();
+        let mut ranges: Vec<(Range<usize>, Range<usize>)> = QUOTES
+            .iter()
+            .filter_map(|&quote| {
+                let quotes: Vec<usize> = (0..chars.len())
+                    .filter(|&i| chars[i] == quote && (i == 0 || chars[i - 1] != '\\'))
+                    .map(|i| line_start + i)
+                    .collect();
+                quotes
+                    .chunks_exact(2)
+                    .map(|pair| (pair[0] + 1..pair[1], pair[0]..pair[1] + 1))
+                    .find(|(_, outer)| contains(outer, &range))
+            })
+            .collect();
+        ranges.sort_by_key(|(_, outer)| outer.len());
+        ranges
+    }
+
+    // The bracket pairs around `range`, innermost first, as their contents
+    // and the contents with the brackets.
+    pub(super) fn bracket_ranges(&self, range: Range<usize>) -> Vec<(Range<usize>, Range<usize>)> {
+        if let Some(tree) = self.buffer().syntax_tree() {
+            return self.enclosing_nodes(tree.root_node(), &range, bracket_node_ranges);
+        }
+
+        let mut ranges = Vec::new();
+        for (open, close) in self.language().brackets.clone() {
+            // A cursor on an opening bracket is inside its pair.
+            let on_open = range.is_empty() && self.buffer().char_at(range.start) == Some(open);
+            let mut next = on_open
+                .then_some(range.start)
+                .or_else(|| self.find_unmatched_bracket(range.start, open, close, false));
+            while let Some(open_idx) = next {
+                let Some(close_idx) = self.find_unmatched_bracket(open_idx + 1, open, close, true)
+                else {
+                    break;
+                };
+                if close_idx >= range.end {
+                    ranges.push((open_idx + 1..close_idx, open_idx..close_idx + 1));
+                }
+                next = self.find_unmatched_bracket(open_idx, open, close, false);
+            }
+        }
+        ranges.sort_by_key(|(_, outer)| outer.len());
+        ranges
+    }
+
+    // The lines `range` covers, without and then with their indentation
+    // and line break.
+    pub(super) fn line_ranges(&self, range: Range<usize>) -> [Range<usize>; 2] {
+        let (first, last) = self.range_lines(&range);
+        let rope = self.buffer().rope();
+        let start = rope.line_to_char(first);
+        let indent = rope
+            .line(first)
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .count();
+        let content_end = start_of_line_break(rope, last);
+        let end = line_end(rope, last);
+        [(start + indent).min(content_end)..content_end, start..end]
+    }
+
+    // The run of non-blank lines around `range`, with its last line break.
+    pub(super) fn paragraph_range(&self, range: Range<usize>) -> Option<Range<usize>> {
+        let rope = self.buffer().rope();
+        let is_blank = |line: usize| rope.line(line).chars().all(char::is_whitespace);
+        let (mut first, mut last) = self.range_lines(&range);
+        if is_blank(first) {
+            return None;
+        }
+        while first > 0 && !is_blank(first - 1) {
+            first -= 1;
+        }
+        while last + 1 < rope.len_lines() && !is_blank(last + 1) {
+            last += 1;
+        }
+        Some(rope.line_to_char(first)..line_end(rope, last))
+    }
+
+    // The whole buffer less any blank lines at either end.
+    pub(super) fn entire_range(&self) -> Range<usize> {
+        let rope = self.buffer().rope();
+        let is_blank = |line: usize| rope.line(line).chars().all(char::is_whitespace);
+        let mut first = 0;
+        let mut last = rope.len_lines().saturating_sub(1);
+        while first < last && is_blank(first) {
+            first += 1;
+        }
+        while last > first && is_blank(last) {
+            last -= 1;
+        }
+        rope.line_to_char(first)..line_end(rope, last)
+    }
+
+    pub(super) fn span_range(
+        &self,
+        start: CursorPosition,
+        end: CursorPosition,
+    ) -> Result<Range<usize>> {
+        let buffer = self.buffer();
+        Ok(
+            buffer.char_index(start.line, start.column)?
+                ..buffer.char_index(end.line, end.column)?,
+        )
+    }
+
+    fn range_selection(&self, range: Range<usize>) -> Result<Selection> {
+        let (start_line, start_column) = self.buffer().char_to_line_col(range.start)?;
+        let (end_line, end_column) = self.buffer().char_to_line_col(range.end)?;
+        Ok(Selection::new(
+            CursorPosition::new(start_line, start_column),
+            CursorPosition::new(end_line, end_column),
+        ))
+    }
+
+    // The first and last line `range` covers. A range ending at the start
+    // of a line leaves that line out.
+    fn range_lines(&self, range: &Range<usize>) -> (usize, usize) {
+        let rope = self.buffer().rope();
+        let first = rope.char_to_line(range.start);
+        let mut last = rope.char_to_line(range.end);
+        if last > first && rope.line_to_char(last) == range.end {
+            last -= 1;
+        }
+        (first, last)
+    }
+
+    // Walks out from the smallest node around `range` and collects what
+    // `ranges` makes of each node, keeping those around `range`.
+    fn enclosing_nodes<F>(
+        &self,
+        root: Node,
+        range: &Range<usize>,
+        ranges: F,
+    ) -> Vec<(Range<usize>, Range<usize>)>
+    where
+        F: Fn(Node) -> Option<(Range<usize>, Range<usize>)>,
+    {
+        let rope = self.buffer().rope();
+        let start_byte = rope.char_to_byte(range.start);
+        let end_byte = rope.char_to_byte(range.end);
+        let mut node = root.descendant_for_byte_range(start_byte, end_byte);
+        let mut found = Vec::new();
+        while let Some(current) = node {
+            if let Some((inner, outer)) = ranges(current) {
+                let to_chars = |bytes: Range<usize>| {
+                    rope.byte_to_char(bytes.start)..rope.byte_to_char(bytes.end)
+                };
+                let (inner, outer) = (to_chars(inner), to_chars(outer));
+                if contains(&outer, range) {
+                    found.push((inner, outer));
+                }
+            }
+            node = current.parent();
+        }
+        found
+    }
+
+    fn string_node_ranges(&self, node: Node) -> Option<(Range<usize>, Range<usize>)> {
+        let kind = node.kind();
+        if !kind.contains("string") && kind != "char_literal" {
+            return None;
+        }
+        let outer = node.start_byte()..node.end_byte();
+        let count = node.child_count();
+        if count >= 2 {
+            let first = node.child(0)?;
+            let last = node.child(count - 1)?;
+            if first.is_named() && !first.kind().ends_with("start") {
+                return None;
+            }
+            return Some((first.end_byte()..last.start_byte(), outer));
+        }
+
+        // Strings the grammar doesn't split up are trimmed of their quotes.
+        let rope = self.buffer().rope();
+        let first = rope.char(rope.byte_to_char(outer.start));
+        (count == 0 && QUOTES.contains(&first) && outer.len() >= 2)
+            .then(|| (outer.start + 1..outer.end - 1, outer))
+    }
+}
+
+fn bracket_node_ranges(node: Node) -> Option<(Range<usize>, Range<usize>)> {
+    let count = node.child_count();
+    let first = node.child(0)?;
+    let last = node.child(count.checked_sub(1)?)?;
+    if count < 2 || first.is_named() || last.is_named() {
+        return None;
+    }
+    let pair = matches!(
+        (first.kind(), last.kind()),
+        ("(", ")") | ("[", "]") | ("{", "}")
+    );
+    pair.then(|| {
+        (
+            first.end_byte()..last.start_byte(),
+            first.start_byte()..last.end_byte(),
+        )
+    })
+}
+
+fn contains(outer: &Range<usize>, inner: &Range<usize>) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+fn start_of_line_break(rope: &ropey::Rope, line: usize) -> usize {
+    let start = rope.line_to_char(line);
+    let len = rope
+        .line(line)
+        .chars()
+        .take_while(|c| *c != '\n' && *c != '\r')
+        .count();
+    start + len
+}
+
+fn line_end(rope: &ropey::Rope, line: usize) -> usize {
+    if line + 1 < rope.len_lines() {
+        rope.line_to_char(line + 1)
+    } else {
+        rope.len_chars()
+    }
+}
@@ -1,12 +1,17 @@
 use super::state::EditorState;
 use crate::buffer::Buffer;
 use crate::cursor::CursorPosition;
+use crate::editorconfig::EditorConfigProperties;
 use crate::error::Result;
 use std::path::PathBuf;
 
 impl EditorState {
+    pub(super) fn load_buffer(&self, path: PathBuf) -> Result<Buffer> {
+        Buffer::from_file_with_languages(path, &self.languages)
+    }
+
     pub(super) fn open_file(&mut self, path: PathBuf) -> Result<()> {
-        let buffer = Buffer::from_file(path)?;
+        let buffer = self.load_buffer(path)?;
         self.buffers.push(buffer);
         self.current_buffer_index = self.buffers.len() - 1;
         self.cursors.reset_to(CursorPosition::zero());
@@ -14,12 +19,30 @@ impl EditorState {
         Ok(())
     }
 
+    // Opens the file, or switches to it, with the cursor at a zero-based
+    // position clamped to its contents, so that `file:line` from a compiler
+    // message past the end still opens the file.
+    pub fn open_at(&mut self, path: PathBuf, line: usize, column: usize) -> Result<()> {
+        self.open_or_switch_to(path)?;
+        let line = line.min(self.buffer().line_count().saturating_sub(1));
+        let column = column.min(self.buffer().line_len(line)?);
+        self.cursors.reset_to(CursorPosition::new(line, column));
+        self.selection = None;
+        Ok(())
+    }
+
     pub(super) fn save(&mut self) -> Result<()> {
         self.save_all()
     }
 
     pub(super) fn save_as(&mut self, path: PathBuf) -> Result<()> {
+        self.buffer_mut()
+            .set_editorconfig(EditorConfigProperties::resolve(&path));
+        self.normalize_before_save()?;
         self.buffer_mut().save_as(path.clone())?;
+        let buffer = &mut self.buffers[self.current_buffer_index];
+        buffer.detect_language(&self.languages);
+        self.clamp_cursors_after_edit()?;
 
         if self.auto_commit_enabled {
             if let Some(project_path) = path.parent() {
@@ -54,6 +77,19 @@ impl EditorState {
         Ok(())
     }
 
+    // What .editorconfig trims on save is undone like any other edit, so the
+    // undo history keeps matching the buffer.
+    fn normalize_before_save(&mut self) -> Result<()> {
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+        if self.buffer_mut().normalize_whitespace_for_save() {
+            self.clamp_cursors_after_edit()?;
+            self.push_content_history(content_before, cursor_before, selection_before);
+        }
+        Ok(())
+    }
+
     pub(super) fn new_buffer(&mut self) -> Result<()> {
         self.buffers.push(Buffer::new());
         self.current_buffer_index = self.buffers.len() - 1;
@@ -65,6 +101,9 @@ impl EditorState {
     pub(super) fn save_all(&mut self) -> Result<()> {
         let mut saved_files = Vec::new();
 
+        if self.buffer().is_modified() && self.buffer().file_path().is_some() {
+            self.normalize_before_save()?;
+        }
         for buffer in &mut self.buffers {
             if buffer.is_modified() && buffer.file_path().is_some() {
                 buffer.save()?;
@@ -74,6 +113,8 @@ impl EditorState {
             }
         }
 
+        self.clamp_cursors_after_edit()?;
+
         if !saved_files.is_empty() {
             let file_count = saved_files.len();
 
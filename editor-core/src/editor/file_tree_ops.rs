@@ -0,0 +1,248 @@
+use crate::cursor::CursorPosition;
+use crate::error::{EditorError, Result};
+use crate::file_tree::{FileStatus, FileTree};
+use std::path::{Path, PathBuf};
+
+use super::state::EditorState;
+
+impl EditorState {
+    pub fn open_file_tree(&mut self, root: PathBuf) -> Result<()> {
+        let mut tree = FileTree::new(root)?;
+        if let Some(path) = self.buffer().file_path().cloned() {
+            tree.reveal(&path)?;
+        }
+        self.file_tree = Some(tree);
+        self.file_tree_focused = true;
+        self.symbol_outline_focused = false;
+        Ok(())
+    }
+
+    pub fn close_file_tree(&mut self) -> Result<()> {
+        self.file_tree = None;
+        self.file_tree_focused = false;
+        Ok(())
+    }
+
+    pub(super) fn toggle_file_tree(&mut self) -> Result<()> {
+        if self.file_tree.is_some() {
+            return self.close_file_tree();
+        }
+
+        let root = match self.buffer().file_path().and_then(|p| p.parent()) {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => std::env::current_dir()?,
+        };
+        self.open_file_tree(root)
+    }
+
+    pub(super) fn focus_file_tree(&mut self) -> Result<()> {
+        if self.file_tree.is_none() {
+            return self.toggle_file_tree();
+        }
+        self.file_tree_focused = true;
+        self.symbol_outline_focused = false;
+        Ok(())
+    }
+
+    pub(super) fn focus_editor(&mut self) -> Result<()> {
+        self.file_tree_focused = false;
+        self.symbol_outline_focused = false;
+        Ok(())
+    }
+
+    fn file_tree_required(&mut self) -> Result<&mut FileTree> {
+        self.file_tree
+            .as_mut()
+            .ok_or_else(|| EditorError::InvalidOperation("File tree is not open".to_string()))
+    }
+
+    pub(super) fn file_tree_navigate_next(&mut self) -> Result<()> {
+        self.file_tree_required()?.select_next();
+        Ok(())
+    }
+
+    pub(super) fn file_tree_navigate_previous(&mut self) -> Result<()> {
+        self.file_tree_required()?.select_previous();
+        Ok(())
+    }
+
+    pub(super) fn file_tree_navigate_first(&mut self) -> Result<()> {
+        self.file_tree_required()?.select_first();
+        Ok(())
+    }
+
+    pub(super) fn file_tree_navigate_last(&mut self) -> Result<()> {
+        self.file_tree_required()?.select_last();
+        Ok(())
+    }
+
+    pub(super) fn file_tree_select(&mut self, index: usize) -> Result<()> {
+        if self.file_tree_required()?.select_index(index) {
+            Ok(())
+        } else {
+            Err(EditorError::InvalidOperation(format!(
+                "File tree entry {} does not exist",
+                index
+            )))
+        }
+    }
+
+    pub(super) fn file_tree_expand(&mut self) -> Result<()> {
+        self.file_tree_required()?.expand_selected()
+    }
+
+    pub(super) fn file_tree_collapse(&mut self) -> Result<()> {
+        self.file_tree_required()?.collapse_selected()
+    }
+
+    pub(super) fn file_tree_toggle_hidden(&mut self) -> Result<()> {
+        let tree = self.file_tree_required()?;
+        let show_hidden = !tree.show_hidden();
+        tree.set_show_hidden(show_hidden)
+    }
+
+    pub(super) fn file_tree_refresh(&mut self) -> Result<()> {
+        self.file_tree_required()?.refresh()
+    }
+
+    pub(super) fn file_tree_activate(&mut self) -> Result<()> {
+        let entry = self
+            .file_tree_required()?
+            .selected_entry()
+            .cloned()
+            .ok_or_else(|| {
+                EditorError::InvalidOperation("No file tree entry selected".to_string())
+            })?;
+
+        if entry.is_dir() {
+            return self.file_tree_required()?.toggle_selected();
+        }
+
+        self.open_or_switch_to(entry.path)?;
+        self.file_tree_focused = false;
+        Ok(())
+    }
+
+    pub(super) fn open_or_switch_to(&mut self, path: PathBuf) -> Result<()> {
+        if let Some(index) = self
+            .buffers
+            .iter()
+            .position(|b| b.file_path() == Some(&path))
+        {
+            if index != self.current_buffer_index {
+                self.current_buffer_index = index;
+                self.cursors.reset_to(CursorPosition::zero());
+                self.viewport_top = 0;
+            }
+            return Ok(());
+        }
+
+        self.open_file(path)
+    }
+
+    pub(super) fn file_tree_create_file(&mut self, name: &str) -> Result<()> {
+        let path = self.file_tree_required()?.create_file(name)?;
+        self.status_message = format!("Created {}", path.display());
+        Ok(())
+    }
+
+    pub(super) fn file_tree_create_directory(&mut self, name: &str) -> Result<()> {
+        let path = self.file_tree_required()?.create_directory(name)?;
+        self.status_message = format!("Created {}", path.display());
+        Ok(())
+    }
+
+    pub(super) fn file_tree_rename(&mut self, name: &str) -> Result<()> {
+        let (old_path, new_path) = self.file_tree_required()?.rename_selected(name)?;
+        if old_path == new_path {
+            return Ok(());
+        }
+
+        for buffer in &mut self.buffers {
+            let renamed = buffer
+                .file_path()
+                .and_then(|p| p.strip_prefix(&old_path).ok())
+                .map(|rest| {
+                    if rest.as_os_str().is_empty() {
+                        new_path.clone()
+                    } else {
+                        new_path.join(rest)
+                    }
+                });
+            if let Some(path) = renamed {
+                buffer.set_file_path(path);
+            }
+        }
+
+        self.status_message = format!("Renamed to {}", new_path.display());
+
+        if self.auto_commit_enabled {
+            let recorded = if new_path.is_dir() {
+                self.git_history
+                    .handle_directory_rename(&old_path, &new_path)
+                    .map(|_| ())
+            } else {
+                self.record_file_move(&old_path, &new_path)
+            };
+            if let Err(e) = recorded {
+                self.status_message = format!(
+                    "Renamed to {} (git history error: {})",
+                    new_path.display(),
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    fn record_file_move(&mut self, old_path: &Path, new_path: &Path) -> Result<()> {
+        let (old_dir, new_dir) = match (old_path.parent(), new_path.parent()) {
+            (Some(old_dir), Some(new_dir)) => (old_dir, new_dir),
+            _ => return Ok(()),
+        };
+
+        if let Some((old_root, new_root)) = self.git_history.handle_file_move(old_dir, new_dir)? {
+            self.status_message = format!(
+                "Moved {} from {} to {}",
+                new_path.display(),
+                old_root.display(),
+                new_root.display()
+            );
+        }
+
+        self.git_history
+            .commit_file_move(old_dir, new_dir, old_path, new_path)?;
+        Ok(())
+    }
+
+    pub(super) fn file_tree_delete(&mut self) -> Result<()> {
+        let path = self.file_tree_required()?.delete_selected()?;
+        let still_open = self
+            .buffers
+            .iter()
+            .any(|b| b.file_path().is_some_and(|p| p.starts_with(&path)));
+
+        self.status_message = if still_open {
+            format!("Deleted {} (still open in editor)", path.display())
+        } else {
+            format!("Deleted {}", path.display())
+        };
+        Ok(())
+    }
+
+    pub fn file_status(&self, path: &Path) -> FileStatus {
+        let mut status = FileStatus::Clean;
+        for buffer in &self.buffers {
+            if let Some(file_path) = buffer.file_path() {
+                if file_path.starts_with(path) {
+                    if buffer.is_modified() {
+                        return FileStatus::Modified;
+                    }
+                    status = FileStatus::Open;
+                }
+            }
+        }
+        status
+    }
+}
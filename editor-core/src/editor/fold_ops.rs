@@ -0,0 +1,133 @@
+use super::state::EditorState;
+use crate::cursor::CursorPosition;
+use crate::error::{EditorError, Result};
+use crate::folding::{fold_depths, FoldRange};
+
+impl EditorState {
+    pub fn fold_ranges(&self) -> Vec<FoldRange> {
+        self.buffer().fold_ranges(self.tab_size())
+    }
+
+    pub fn is_line_foldable(&self, line: usize) -> bool {
+        self.buffer().fold_range_at(line, self.tab_size()).is_some()
+    }
+
+    pub fn is_line_folded(&self, line: usize) -> bool {
+        self.buffer().folds().is_folded(line)
+    }
+
+    pub fn is_line_hidden(&self, line: usize) -> bool {
+        self.buffer().folds().is_hidden(line)
+    }
+
+    pub(super) fn fold_code(&mut self) -> Result<()> {
+        let line = self.cursors.primary().line;
+        let folds = self.buffer().folds();
+        let innermost = self
+            .fold_ranges()
+            .into_iter()
+            .filter(|range| range.contains(line) && !folds.is_folded(range.start_line))
+            .max_by_key(|range| range.start_line);
+
+        match innermost {
+            Some(range) => {
+                self.buffer_mut().folds_mut().fold(range);
+                self.move_cursors_out_of_folds()
+            }
+            None => {
+                self.status_message = "Nothing to fold".to_string();
+                Ok(())
+            }
+        }
+    }
+
+    pub(super) fn unfold_code(&mut self) -> Result<()> {
+        let line = self.cursors.primary().line;
+        if self.buffer_mut().folds_mut().unfold_at(line).is_none() {
+            self.status_message = "Nothing to unfold".to_string();
+        }
+        Ok(())
+    }
+
+    pub(super) fn toggle_fold(&mut self) -> Result<()> {
+        if self.is_line_folded(self.cursors.primary().line) {
+            self.unfold_code()
+        } else {
+            self.fold_code()
+        }
+    }
+
+    pub(super) fn fold_all(&mut self) -> Result<()> {
+        let ranges = self.fold_ranges();
+        self.buffer_mut().folds_mut().set_folds(&ranges);
+        self.move_cursors_out_of_folds()
+    }
+
+    pub(super) fn unfold_all(&mut self) -> Result<()> {
+        self.buffer_mut().folds_mut().clear();
+        Ok(())
+    }
+
+    pub(super) fn fold_level(&mut self, level: usize) -> Result<()> {
+        if level == 0 {
+            return Err(EditorError::InvalidOperation(
+                "Fold level must be at least 1".to_string(),
+            ));
+        }
+
+        let ranges = self.fold_ranges();
+        let depths = fold_depths(&ranges);
+        for (range, depth) in ranges.into_iter().zip(depths) {
+            if depth == level {
+                self.buffer_mut().folds_mut().fold(range);
+            }
+        }
+        self.move_cursors_out_of_folds()
+    }
+
+    pub(super) fn move_cursors_out_of_folds(&mut self) -> Result<()> {
+        let folds = self.buffer().folds().clone();
+        if folds.is_empty() {
+            return Ok(());
+        }
+
+        let mut positions = Vec::with_capacity(self.cursors.positions().len());
+        for pos in self.cursors.positions().to_vec() {
+            let line = folds.visible_line(pos.line);
+            let column = pos.column.min(self.buffer().line_len(line)?);
+            positions.push(CursorPosition::new(line, column));
+        }
+        self.cursors.set_positions(positions);
+
+        if let Some(selection) = self.selection {
+            if folds.is_hidden(selection.start().line) || folds.is_hidden(selection.end().line) {
+                self.selection = None;
+            }
+        }
+        Ok(())
+    }
+
+    // Commands that land a cursor inside a folded region (goto line, search,
+    // undo) open the folds around it instead of leaving it invisible.
+    pub(super) fn reveal_cursors(&mut self) {
+        if self.buffer().folds().is_empty() {
+            return;
+        }
+
+        let lines: Vec<usize> = self
+            .cursors
+            .positions()
+            .iter()
+            .map(|pos| pos.line)
+            .collect();
+        for line in lines {
+            loop {
+                let header = self.buffer().folds().visible_line(line);
+                if header == line {
+                    break;
+                }
+                self.buffer_mut().folds_mut().unfold_at(header);
+            }
+        }
+    }
+}
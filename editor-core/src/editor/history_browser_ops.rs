@@ -1,4 +1,3 @@
-use crate::buffer::Buffer;
 use crate::error::Result;
 use crate::history_browser::HistoryBrowser;
 
@@ -251,7 +250,7 @@ impl EditorState {
 
         self.git_history.restore_commit(project_path, commit_id)?;
 
-        let buffer = Buffer::from_file(file_path.to_path_buf())?;
+        let buffer = self.load_buffer(file_path.to_path_buf())?;
         self.buffers[self.current_buffer_index] = buffer;
 
         self.set_status_message(format!("Restored from commit {}", commit_id));
@@ -284,7 +283,7 @@ impl EditorState {
         std::fs::write(&target_path, content).map_err(EditorError::Io)?;
 
         if &target_path == current_file_path {
-            let buffer = Buffer::from_file(target_path)?;
+            let buffer = self.load_buffer(target_path)?;
             self.buffers[self.current_buffer_index] = buffer;
         }
 
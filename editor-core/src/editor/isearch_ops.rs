@@ -0,0 +1,181 @@
+use super::state::EditorState;
+use crate::command::Command;
+use crate::cursor::CursorPosition;
+use crate::error::Result;
+use crate::selection::Selection;
+
+#[derive(Debug, Clone)]
+pub(super) struct IncrementalSearch {
+    forward: bool,
+    query: String,
+    origin: CursorPosition,
+    // Start of the current match; a longer query keeps searching from here.
+    found: Option<usize>,
+    failing: bool,
+}
+
+// Searches fold case unless the query has an uppercase letter in it.
+fn find_match(text: &[char], query: &[char], from: usize, forward: bool) -> Option<usize> {
+    if query.is_empty() || query.len() > text.len() {
+        return None;
+    }
+    let fold = !query.iter().any(|c| c.is_uppercase());
+    let matches_at = |start: usize| {
+        text[start..start + query.len()]
+            .iter()
+            .zip(query)
+            .all(|(a, b)| a == b || (fold && a.to_lowercase().eq(b.to_lowercase())))
+    };
+    let last = text.len() - query.len();
+    if forward {
+        (from.min(last + 1)..=last).find(|&start| matches_at(start))
+    } else {
+        (0..=from.min(last)).rev().find(|&start| matches_at(start))
+    }
+}
+
+impl EditorState {
+    pub fn is_isearch_active(&self) -> bool {
+        self.isearch.is_some()
+    }
+
+    pub fn isearch_query(&self) -> Option<&str> {
+        self.isearch.as_ref().map(|search| search.query.as_str())
+    }
+
+    pub(super) fn start_isearch(&mut self, forward: bool) -> Result<()> {
+        self.isearch = Some(IncrementalSearch {
+            forward,
+            query: String::new(),
+            origin: *self.cursors.primary(),
+            found: None,
+            failing: false,
+        });
+        self.mark_active = false;
+        self.show_isearch_prompt();
+        Ok(())
+    }
+
+    // While a search is running, typing edits the query, the search keys
+    // step through matches, Enter ends the search and C-g goes back to where
+    // it started. Any other command ends the search and then runs as usual.
+    // Returns whether the command was consumed.
+    pub(super) fn isearch_command(&mut self, command: &Command) -> Result<bool> {
+        let Some(mut search) = self.isearch.take() else {
+            return Ok(false);
+        };
+        let advance = match command {
+            Command::InsertChar(c) | Command::InsertCharWithAutoClose(c) => {
+                search.query.push(*c);
+                false
+            }
+            Command::Backspace => {
+                search.query.pop();
+                search.found = None;
+                false
+            }
+            Command::IncrementalSearch { forward } => {
+                if search.query.is_empty() {
+                    search.query = self.last_search_query.clone().unwrap_or_default();
+                }
+                let turned = search.forward != *forward;
+                search.forward = *forward;
+                !turned || search.found.is_none()
+            }
+            Command::NewLine => {
+                self.finish_isearch(search);
+                return Ok(true);
+            }
+            Command::KeyboardQuit => {
+                self.cursors.reset_to(search.origin);
+                self.selection = None;
+                self.status_message = "Quit".to_string();
+                return Ok(true);
+            }
+            _ => {
+                self.finish_isearch(search);
+                return Ok(false);
+            }
+        };
+
+        self.isearch_update(&mut search, advance)?;
+        self.isearch = Some(search);
+        self.show_isearch_prompt();
+        Ok(true)
+    }
+
+    fn isearch_update(&mut self, search: &mut IncrementalSearch, advance: bool) -> Result<()> {
+        let query: Vec<char> = search.query.chars().collect();
+        if query.is_empty() {
+            search.found = None;
+            search.failing = false;
+            self.cursors.reset_to(search.origin);
+            self.selection = None;
+            return Ok(());
+        }
+
+        let text: Vec<char> = self.buffer().rope().chars().collect();
+        let origin = self
+            .buffer()
+            .char_index(search.origin.line, search.origin.column)?;
+        let from = match (search.found, advance, search.forward) {
+            (Some(found), true, true) => found + 1,
+            (Some(found), true, false) => match found.checked_sub(1) {
+                Some(from) => from,
+                None => text.len(),
+            },
+            (Some(found), false, _) => found,
+            (None, _, true) => origin,
+            (None, _, false) => origin.saturating_sub(1),
+        };
+        // A step past the last match wraps around to the other end.
+        let found = find_match(&text, &query, from, search.forward).or_else(|| {
+            advance
+                .then(|| {
+                    let wrap = if search.forward { 0 } else { text.len() };
+                    find_match(&text, &query, wrap, search.forward)
+                })
+                .flatten()
+        });
+
+        match found {
+            Some(start) => {
+                search.found = Some(start);
+                search.failing = false;
+                let end = start + query.len();
+                let (line, column) = self.buffer().char_to_line_col(start)?;
+                let (end_line, end_column) = self.buffer().char_to_line_col(end)?;
+                let start = CursorPosition::new(line, column);
+                let end = CursorPosition::new(end_line, end_column);
+                self.cursors
+                    .reset_to(if search.forward { end } else { start });
+                self.selection = Some(Selection::new(start, end));
+            }
+            None => search.failing = true,
+        }
+        Ok(())
+    }
+
+    fn finish_isearch(&mut self, search: IncrementalSearch) {
+        if !search.query.is_empty() {
+            if self.search_history.last() != Some(&search.query) {
+                self.search_history.push(search.query.clone());
+            }
+            self.last_search_query = Some(search.query);
+        }
+        self.selection = None;
+        self.status_message.clear();
+    }
+
+    fn show_isearch_prompt(&mut self) {
+        let Some(search) = &self.isearch else {
+            return;
+        };
+        self.status_message = format!(
+            "{}I-search{}: {}",
+            if search.failing { "Failing " } else { "" },
+            if search.forward { "" } else { " backward" },
+            search.query
+        );
+    }
+}
@@ -0,0 +1,248 @@
+use super::state::EditorState;
+use super::state_utils::is_word_char;
+use crate::cursor::CursorPosition;
+use crate::error::{EditorError, Result};
+use crate::selection::Selection;
+
+// What the previous command left behind for the next one to build on:
+// kills that follow a kill grow the same kill ring entry, and a yank-pop
+// replaces the text the previous yank inserted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum KillChain {
+    Kill,
+    Yank { start: usize, end: usize },
+}
+
+impl EditorState {
+    pub fn mark(&self) -> Option<CursorPosition> {
+        self.mark
+    }
+
+    pub fn is_mark_active(&self) -> bool {
+        self.mark.is_some() && self.mark_active
+    }
+
+    pub fn kill_ring(&self) -> &crate::kill_ring::KillRing {
+        &self.kill_ring
+    }
+
+    pub(super) fn set_mark(&mut self) -> Result<()> {
+        self.mark = Some(*self.cursors.primary());
+        self.mark_active = true;
+        self.status_message = "Mark set".to_string();
+        Ok(())
+    }
+
+    pub(super) fn exchange_point_and_mark(&mut self) -> Result<()> {
+        let mark = self.mark.ok_or_else(no_mark)?;
+        self.mark = Some(*self.cursors.primary());
+        self.mark_active = true;
+        self.cursors.reset_to(mark);
+        Ok(())
+    }
+
+    pub(super) fn keyboard_quit(&mut self) -> Result<()> {
+        self.mark_active = false;
+        self.selection = None;
+        self.cursors.reset_to(*self.cursors.primary());
+        self.status_message = "Quit".to_string();
+        Ok(())
+    }
+
+    // Keeps the selection on the region while the mark is active; an edit
+    // deactivates it.
+    pub(super) fn sync_mark(&mut self, edited: bool) {
+        if !self.mark_active {
+            return;
+        }
+        match self.mark {
+            Some(mark) if !edited => {
+                self.selection = Some(Selection::new(mark, *self.cursors.primary()));
+            }
+            _ => {
+                self.mark_active = false;
+                self.selection = None;
+            }
+        }
+    }
+
+    pub(super) fn kill_line(&mut self, chain: Option<KillChain>) -> Result<()> {
+        let cursor = *self.cursors.primary();
+        let line_len = self.buffer().line_len(cursor.line)?;
+        let rest: String = self
+            .buffer()
+            .rope()
+            .line(cursor.line)
+            .chars()
+            .skip(cursor.column)
+            .take(line_len.saturating_sub(cursor.column))
+            .collect();
+        let start = self.buffer().char_index(cursor.line, cursor.column)?;
+        // Only blanks left on the line: the newline goes with them.
+        let end = if rest.trim().is_empty() {
+            (start + rest.chars().count() + 1).min(self.buffer().len_chars())
+        } else {
+            start + rest.chars().count()
+        };
+        self.kill_chars(start, end, false, chain)
+    }
+
+    pub(super) fn kill_region(&mut self, chain: Option<KillChain>) -> Result<()> {
+        let (start, end) = self.region()?;
+        self.kill_chars(start, end, false, chain)?;
+        self.mark_active = false;
+        Ok(())
+    }
+
+    pub(super) fn copy_region(&mut self, chain: Option<KillChain>) -> Result<()> {
+        let (start, end) = self.region()?;
+        let text = self.buffer().rope().slice(start..end).to_string();
+        self.record_kill(&text, false, chain)?;
+        self.kill_chain = Some(KillChain::Kill);
+        self.mark_active = false;
+        self.selection = None;
+        Ok(())
+    }
+
+    pub(super) fn kill_word(&mut self, forward: bool, chain: Option<KillChain>) -> Result<()> {
+        let cursor = *self.cursors.primary();
+        let from = self.buffer().char_index(cursor.line, cursor.column)?;
+        let len = self.buffer().len_chars();
+        let is_word = |idx: usize| self.buffer().char_at(idx).is_some_and(is_word_char);
+
+        let mut to = from;
+        if forward {
+            while to < len && !is_word(to) {
+                to += 1;
+            }
+            while to < len && is_word(to) {
+                to += 1;
+            }
+            self.kill_chars(from, to, false, chain)
+        } else {
+            while to > 0 && !is_word(to - 1) {
+                to -= 1;
+            }
+            while to > 0 && is_word(to - 1) {
+                to -= 1;
+            }
+            self.kill_chars(to, from, true, chain)
+        }
+    }
+
+    pub(super) fn yank(&mut self) -> Result<()> {
+        let text = match self.kill_ring.reset_yank() {
+            Some(text) => text.to_string(),
+            None => self.clipboard_text()?,
+        };
+        if text.is_empty() {
+            return Err(EditorError::InvalidOperation(
+                "Kill ring is empty".to_string(),
+            ));
+        }
+
+        let cursor = *self.cursors.primary();
+        let start = self.buffer().char_index(cursor.line, cursor.column)?;
+        self.replace_killed_chars(start, start, &text)?;
+        // Yanking sets the mark at the start of the text without activating it.
+        self.mark = Some(cursor);
+        self.kill_chain = Some(KillChain::Yank {
+            start,
+            end: start + text.chars().count(),
+        });
+        Ok(())
+    }
+
+    pub(super) fn yank_pop(&mut self, chain: Option<KillChain>) -> Result<()> {
+        let Some(KillChain::Yank { start, end }) = chain else {
+            return Err(EditorError::InvalidOperation(
+                "Previous command was not a yank".to_string(),
+            ));
+        };
+        let Some(text) = self.kill_ring.rotate().map(str::to_string) else {
+            return Err(EditorError::InvalidOperation(
+                "Kill ring is empty".to_string(),
+            ));
+        };
+        self.replace_killed_chars(start, end, &text)?;
+        self.kill_chain = Some(KillChain::Yank {
+            start,
+            end: start + text.chars().count(),
+        });
+        Ok(())
+    }
+
+    fn region(&self) -> Result<(usize, usize)> {
+        let mark = self.mark.ok_or_else(no_mark)?;
+        let cursor = *self.cursors.primary();
+        let mark = self.buffer().char_index(mark.line, mark.column)?;
+        let cursor = self.buffer().char_index(cursor.line, cursor.column)?;
+        Ok((mark.min(cursor), mark.max(cursor)))
+    }
+
+    fn kill_chars(
+        &mut self,
+        start: usize,
+        end: usize,
+        before: bool,
+        chain: Option<KillChain>,
+    ) -> Result<()> {
+        if start >= end {
+            // Nothing to kill still keeps a run of kills together.
+            self.kill_chain = chain.filter(|chain| *chain == KillChain::Kill);
+            return Ok(());
+        }
+        let text = self.buffer().rope().slice(start..end).to_string();
+        self.replace_killed_chars(start, end, "")?;
+        self.record_kill(&text, before, chain)?;
+        self.kill_chain = Some(KillChain::Kill);
+        Ok(())
+    }
+
+    fn record_kill(&mut self, text: &str, before: bool, chain: Option<KillChain>) -> Result<()> {
+        if chain == Some(KillChain::Kill) {
+            self.kill_ring.append(text, before);
+        } else {
+            self.kill_ring.push(text);
+        }
+        if let Some(killed) = self.kill_ring.current().map(str::to_string) {
+            self.clipboard.set_text(&killed)?;
+            if chain == Some(KillChain::Kill) {
+                self.clipboard_history.replace_latest(killed);
+            } else {
+                self.clipboard_history.push(killed);
+            }
+        }
+        Ok(())
+    }
+
+    // Replaces a char range as one undo step and leaves the cursor after
+    // the new text.
+    fn replace_killed_chars(&mut self, start: usize, end: usize, text: &str) -> Result<()> {
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+
+        let (line, column) = self.buffer().char_to_line_col(start)?;
+        if end > start {
+            let (end_line, end_column) = self.buffer().char_to_line_col(end)?;
+            self.buffer_mut()
+                .delete_range(line, column, end_line, end_column)?;
+        }
+        if !text.is_empty() {
+            self.buffer_mut().insert_str(line, column, text)?;
+        }
+        let (line, column) = self
+            .buffer()
+            .char_to_line_col(start + text.chars().count())?;
+        self.cursors.reset_to(CursorPosition::new(line, column));
+        self.selection = None;
+
+        self.push_content_history(content_before, cursor_before, selection_before);
+        Ok(())
+    }
+}
+
+fn no_mark() -> EditorError {
+    EditorError::InvalidOperation("The mark is not set now, so there is no region".to_string())
+}
@@ -0,0 +1,577 @@
+use crate::error::{EditorError, Result};
+use crate::lsp::{
+    from_lsp_position, to_char_index, to_lsp_position, Diagnostic, DiagnosticSeverity, Location,
+    LspClient, LspEvent, LspPosition, TextEdit, WorkspaceEdit,
+};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::state::EditorState;
+
+impl EditorState {
+    pub fn language_server(&self, language: &str) -> Option<&LspClient> {
+        self.language_servers.get(language)
+    }
+
+    pub fn has_language_servers(&self) -> bool {
+        !self.language_servers.is_empty()
+    }
+
+    // Frontends call this from their event loop; it returns true when a
+    // server message changed something worth redrawing.
+    pub fn poll_language_servers(&mut self) -> bool {
+        let mut events = Vec::new();
+        for (language, client) in &mut self.language_servers {
+            events.extend(
+                client
+                    .poll()
+                    .into_iter()
+                    .map(|event| (language.clone(), event)),
+            );
+        }
+        self.handle_lsp_events(events)
+    }
+
+    pub fn wait_for_language_servers(&mut self, timeout: Duration) -> bool {
+        let mut events = Vec::new();
+        let mut waited = false;
+        for (language, client) in &mut self.language_servers {
+            let received = if waited {
+                client.poll()
+            } else {
+                waited = true;
+                client.wait(timeout)
+            };
+            events.extend(received.into_iter().map(|event| (language.clone(), event)));
+        }
+        self.handle_lsp_events(events)
+    }
+
+    fn handle_lsp_events(&mut self, events: Vec<(String, LspEvent)>) -> bool {
+        if events.is_empty() {
+            return false;
+        }
+
+        for (language, event) in events {
+            if let Err(e) = self.handle_lsp_event(&language, event) {
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+        self.sync_language_servers();
+        self.reveal_cursors();
+        self.sync_symbol_outline();
+        true
+    }
+
+    fn handle_lsp_event(&mut self, language: &str, event: LspEvent) -> Result<()> {
+        match event {
+            LspEvent::Initialized => {}
+            LspEvent::Diagnostics { path, diagnostics } => {
+                if diagnostics.is_empty() {
+                    self.diagnostics.remove(&path);
+                } else {
+                    self.diagnostics.insert(path, diagnostics);
+                }
+            }
+            LspEvent::Completion {
+                path,
+                position,
+                items,
+            } => self.open_lsp_completion(&path, position, items),
+            LspEvent::Hover { path, contents } => {
+                if self.buffer().file_path() == Some(&path) {
+                    match contents {
+                        Some(contents) => self.hover = Some(contents),
+                        None => self.status_message = "No hover information".to_string(),
+                    }
+                }
+            }
+            LspEvent::Definition { locations } => match locations.first() {
+                Some(location) => self.jump_to_location(location)?,
+                None => self.goto_symbol_definition()?,
+            },
+            LspEvent::References { locations } => self.show_references(locations)?,
+            LspEvent::Rename { edit } => self.apply_workspace_edit(&edit)?,
+            LspEvent::Formatting {
+                path,
+                revision,
+                edits,
+            } => self.apply_formatting(&path, revision, &edits)?,
+            LspEvent::Message(message) => self.status_message = message,
+            LspEvent::Exited => {
+                self.language_servers.remove(language);
+                self.failed_language_servers.insert(language.to_string());
+                self.status_message = format!("Language server for {} exited", language);
+            }
+        }
+        Ok(())
+    }
+
+    // Opens every file-backed buffer with its language's server, then
+    // forwards whatever the buffer's change log recorded since the last sync.
+    pub(super) fn sync_language_servers(&mut self) {
+        let mut wanted: Vec<(String, PathBuf)> = Vec::new();
+        for buffer in &self.buffers {
+            let (Some(path), Some(language)) = (
+                buffer.file_path(),
+                buffer.language().and_then(|name| self.languages.get(name)),
+            ) else {
+                continue;
+            };
+            if language.language_server.is_some() {
+                wanted.push((language.name.clone(), path.clone()));
+            }
+        }
+
+        for (language, _) in &wanted {
+            self.ensure_language_server(language);
+        }
+
+        for (language, path) in &wanted {
+            if let Err(e) = self.sync_document(language, path) {
+                self.status_message = format!("Error: {}", e);
+            }
+        }
+
+        let wanted: HashSet<(String, PathBuf)> = wanted.into_iter().collect();
+        for (language, client) in &mut self.language_servers {
+            if !client.is_initialized() {
+                continue;
+            }
+            for path in client.document_paths() {
+                if !wanted.contains(&(language.clone(), path.clone())) {
+                    let _ = client.close_document(&path);
+                    self.diagnostics.remove(&path);
+                }
+            }
+        }
+    }
+
+    fn ensure_language_server(&mut self, language: &str) {
+        if self.language_servers.contains_key(language)
+            || self.failed_language_servers.contains(language)
+        {
+            return;
+        }
+        let Some(config) = self
+            .languages
+            .get(language)
+            .and_then(|definition| definition.language_server.clone())
+        else {
+            return;
+        };
+
+        let started = self
+            .project_root()
+            .and_then(|root| LspClient::start(language, &config, &root));
+        match started {
+            Ok(client) => {
+                self.language_servers.insert(language.to_string(), client);
+            }
+            Err(e) => {
+                self.failed_language_servers.insert(language.to_string());
+                self.status_message = e.to_string();
+            }
+        }
+    }
+
+    fn sync_document(&mut self, language: &str, path: &Path) -> Result<()> {
+        let Some(client) = self.language_servers.get_mut(language) else {
+            return Ok(());
+        };
+        if !client.is_initialized() {
+            return Ok(());
+        }
+        let Some(buffer) = self
+            .buffers
+            .iter()
+            .find(|buffer| buffer.file_path().map(PathBuf::as_path) == Some(path))
+        else {
+            return Ok(());
+        };
+
+        let Some(document) = client.document(path).copied() else {
+            let language_id = self
+                .languages
+                .get(language)
+                .map_or(language, |definition| definition.language_id());
+            return client.open_document(
+                path,
+                language_id,
+                buffer.rope(),
+                buffer.revision(),
+                buffer.last_saved(),
+            );
+        };
+
+        if document.revision != buffer.revision() {
+            client.change_document(
+                path,
+                buffer.rope(),
+                buffer.revision(),
+                buffer.changes_since(document.revision),
+            )?;
+        }
+        if document.saved != buffer.last_saved() {
+            client.save_document(path, buffer.rope(), buffer.last_saved())?;
+        }
+        Ok(())
+    }
+
+    fn current_language_server(&mut self) -> Result<(&mut LspClient, PathBuf, LspPosition)> {
+        let path = self.buffer().file_path().cloned().ok_or_else(|| {
+            EditorError::InvalidOperation("Buffer has no file for the language server".to_string())
+        })?;
+        let language = self.language().name.clone();
+        let position = to_lsp_position(self.buffer().rope(), *self.cursor());
+        let client = self.language_servers.get_mut(&language).ok_or_else(|| {
+            EditorError::InvalidOperation(format!("No language server running for {}", language))
+        })?;
+        Ok((client, path, position))
+    }
+
+    pub(super) fn has_current_language_server(&self, capability: &str) -> bool {
+        self.language_servers
+            .get(&self.language().name)
+            .is_some_and(|client| client.is_initialized() && client.supports(capability))
+    }
+
+    pub(super) fn request_lsp_completion(&mut self) -> Result<()> {
+        let (client, path, position) = self.current_language_server()?;
+        client.completion(&path, position)
+    }
+
+    pub(super) fn show_hover(&mut self) -> Result<()> {
+        let (client, path, position) = self.current_language_server()?;
+        client.hover(&path, position)
+    }
+
+    pub(super) fn lsp_goto_definition(&mut self) -> Result<()> {
+        let (client, path, position) = self.current_language_server()?;
+        client.definition(&path, position)
+    }
+
+    pub(super) fn find_references(&mut self) -> Result<()> {
+        let (client, path, position) = self.current_language_server()?;
+        client.references(&path, position)
+    }
+
+    pub(super) fn rename_symbol(&mut self, new_name: &str) -> Result<()> {
+        if new_name.trim().is_empty() {
+            return Err(EditorError::InvalidOperation(
+                "New name cannot be empty".to_string(),
+            ));
+        }
+        let (client, path, position) = self.current_language_server()?;
+        client.rename(&path, position, new_name.trim())
+    }
+
+    pub(super) fn format_document(&mut self) -> Result<()> {
+        let revision = self.buffer().revision();
+        let (tab_size, insert_spaces) = (self.tab_size, self.insert_spaces);
+        let (client, path, _) = self.current_language_server()?;
+        client.formatting(&path, revision, tab_size, insert_spaces)
+    }
+
+    pub(super) fn restart_language_server(&mut self) -> Result<()> {
+        let language = self.language().name.clone();
+        if self
+            .languages
+            .get(&language)
+            .and_then(|l| l.language_server.as_ref())
+            .is_none()
+        {
+            return Err(EditorError::InvalidOperation(format!(
+                "No language server configured for {}",
+                language
+            )));
+        }
+
+        if let Some(mut client) = self.language_servers.remove(&language) {
+            for path in client.document_paths() {
+                self.diagnostics.remove(&path);
+            }
+            client.shutdown();
+        }
+        self.failed_language_servers.remove(&language);
+        self.status_message = format!("Restarting language server for {}", language);
+        Ok(())
+    }
+
+    fn jump_to_location(&mut self, location: &Location) -> Result<()> {
+        if self.buffer().file_path() != Some(&location.path) {
+            self.open_or_switch_to(location.path.clone())?;
+        }
+        let position = from_lsp_position(self.buffer().rope(), location.range.start);
+        self.jump_to_symbol(None, position.line, position.column)
+    }
+
+    fn show_references(&mut self, mut locations: Vec<Location>) -> Result<()> {
+        if locations.is_empty() {
+            self.references.clear();
+            self.status_message = "No references found".to_string();
+            return Ok(());
+        }
+
+        locations.sort_by(|a, b| (&a.path, a.range.start).cmp(&(&b.path, b.range.start)));
+        locations.dedup();
+        let current = self.buffer().file_path().cloned();
+        let cursor = to_lsp_position(self.buffer().rope(), *self.cursor());
+        let at_cursor = locations.iter().position(|location| {
+            Some(&location.path) == current.as_ref()
+                && location.range.start <= cursor
+                && cursor <= location.range.end
+        });
+
+        self.references = locations;
+        self.reference_index = at_cursor.unwrap_or(0);
+        if at_cursor.is_none() {
+            let location = self.references[0].clone();
+            self.jump_to_location(&location)?;
+        }
+        self.status_message = format!(
+            "Reference {} of {}",
+            self.reference_index + 1,
+            self.references.len()
+        );
+        Ok(())
+    }
+
+    pub(super) fn next_reference(&mut self) -> Result<()> {
+        self.step_reference(1)
+    }
+
+    pub(super) fn previous_reference(&mut self) -> Result<()> {
+        self.step_reference(self.references.len().saturating_sub(1))
+    }
+
+    fn step_reference(&mut self, step: usize) -> Result<()> {
+        if self.references.is_empty() {
+            self.status_message = "No references".to_string();
+            return Ok(());
+        }
+        self.reference_index = (self.reference_index + step) % self.references.len();
+        let location = self.references[self.reference_index].clone();
+        self.jump_to_location(&location)?;
+        self.status_message = format!(
+            "Reference {} of {}",
+            self.reference_index + 1,
+            self.references.len()
+        );
+        Ok(())
+    }
+
+    pub(super) fn next_diagnostic(&mut self) -> Result<()> {
+        self.step_diagnostic(true)
+    }
+
+    pub(super) fn previous_diagnostic(&mut self) -> Result<()> {
+        self.step_diagnostic(false)
+    }
+
+    fn step_diagnostic(&mut self, forward: bool) -> Result<()> {
+        let mut diagnostics: Vec<Diagnostic> = self.diagnostics().to_vec();
+        if diagnostics.is_empty() {
+            self.status_message = "No diagnostics".to_string();
+            return Ok(());
+        }
+        diagnostics.sort_by_key(|diagnostic| diagnostic.range.start);
+
+        let cursor = to_lsp_position(self.buffer().rope(), *self.cursor());
+        let target = if forward {
+            diagnostics
+                .iter()
+                .find(|diagnostic| diagnostic.range.start > cursor)
+                .or_else(|| diagnostics.first())
+        } else {
+            diagnostics
+                .iter()
+                .rev()
+                .find(|diagnostic| diagnostic.range.start < cursor)
+                .or_else(|| diagnostics.last())
+        };
+
+        if let Some(diagnostic) = target.cloned() {
+            let position = from_lsp_position(self.buffer().rope(), diagnostic.range.start);
+            self.jump_to_symbol(None, position.line, position.column)?;
+            self.status_message = diagnostic.summary();
+        }
+        Ok(())
+    }
+
+    pub fn diagnostics(&self) -> &[Diagnostic] {
+        self.buffer()
+            .file_path()
+            .and_then(|path| self.diagnostics.get(path))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    pub fn diagnostic_counts(&self) -> (usize, usize) {
+        let diagnostics = self.diagnostics();
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == severity)
+                .count()
+        };
+        (
+            count(DiagnosticSeverity::Error),
+            count(DiagnosticSeverity::Warning),
+        )
+    }
+
+    pub fn line_diagnostic_severity(&self, line: usize) -> Option<DiagnosticSeverity> {
+        self.diagnostics()
+            .iter()
+            .filter(|diagnostic| diagnostic.range.contains_line(line as u32))
+            .map(|diagnostic| diagnostic.severity)
+            .min()
+    }
+
+    // Column ranges (in characters) of diagnostics touching `line`, for
+    // underlining; a range that continues past the line ends at its end.
+    pub fn line_diagnostics(&self, line: usize) -> Vec<(usize, usize, DiagnosticSeverity)> {
+        let rope = self.buffer().rope();
+        let line_len = self.buffer().line_len(line).unwrap_or(0);
+        self.diagnostics()
+            .iter()
+            .filter(|diagnostic| diagnostic.range.contains_line(line as u32))
+            .map(|diagnostic| {
+                let start = from_lsp_position(rope, diagnostic.range.start);
+                let end = from_lsp_position(rope, diagnostic.range.end);
+                let start_column = if start.line < line { 0 } else { start.column };
+                let end_column = if end.line > line {
+                    line_len
+                } else {
+                    end.column
+                };
+                // Zero-width diagnostics still mark one character.
+                let end_column = end_column.max((start_column + 1).min(line_len.max(1)));
+                (start_column, end_column, diagnostic.severity)
+            })
+            .collect()
+    }
+
+    pub fn diagnostic_at_cursor(&self) -> Option<&Diagnostic> {
+        let line = self.cursor().line as u32;
+        self.diagnostics()
+            .iter()
+            .filter(|diagnostic| diagnostic.range.contains_line(line))
+            .min_by_key(|diagnostic| diagnostic.severity)
+    }
+
+    pub fn hover_text(&self) -> Option<&str> {
+        self.hover.as_deref()
+    }
+
+    pub fn references(&self) -> &[Location] {
+        &self.references
+    }
+
+    pub(super) fn buffer_index_for(&mut self, path: &Path) -> Result<usize> {
+        if let Some(index) = self
+            .buffers
+            .iter()
+            .position(|buffer| buffer.file_path().map(PathBuf::as_path) == Some(path))
+        {
+            return Ok(index);
+        }
+        let buffer = self.load_buffer(path.to_path_buf())?;
+        self.buffers.push(buffer);
+        Ok(self.buffers.len() - 1)
+    }
+
+    // Edits arrive against the server's view of the document; applying them
+    // from the end backwards keeps the earlier ranges valid.
+    fn apply_text_edits(&mut self, index: usize, edits: &[TextEdit]) -> Result<()> {
+        let buffer = &mut self.buffers[index];
+        if buffer.is_read_only() {
+            return Err(EditorError::ReadOnlyFile(
+                buffer
+                    .file_path()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "buffer".to_string()),
+            ));
+        }
+
+        let rope = buffer.rope();
+        let mut ranges: Vec<(usize, usize, usize, &str)> = edits
+            .iter()
+            .enumerate()
+            .map(|(order, edit)| {
+                let start = to_char_index(rope, edit.range.start);
+                let end = to_char_index(rope, edit.range.end).max(start);
+                (start, order, end, edit.new_text.as_str())
+            })
+            .collect();
+        ranges.sort_by_key(|&(start, order, _, _)| std::cmp::Reverse((start, order)));
+
+        for (start, _, end, text) in ranges {
+            let (start_line, start_column) = buffer.char_to_line_col(start)?;
+            if end > start {
+                let (end_line, end_column) = buffer.char_to_line_col(end)?;
+                buffer.delete_range(start_line, start_column, end_line, end_column)?;
+            }
+            if !text.is_empty() {
+                buffer.insert_str(start_line, start_column, text)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_edits_to_current(&mut self, edits: &[TextEdit]) -> Result<()> {
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+
+        self.apply_text_edits(self.current_buffer_index, edits)?;
+        self.clamp_cursors_after_edit()?;
+        self.push_content_history(content_before, cursor_before, selection_before);
+        Ok(())
+    }
+
+    fn apply_formatting(&mut self, path: &Path, revision: u64, edits: &[TextEdit]) -> Result<()> {
+        if self.buffer().file_path().map(PathBuf::as_path) != Some(path) {
+            return Ok(());
+        }
+        if self.buffer().revision() != revision {
+            self.status_message = "Document changed while formatting; try again".to_string();
+            return Ok(());
+        }
+        if edits.is_empty() {
+            self.status_message = "Document is already formatted".to_string();
+            return Ok(());
+        }
+        self.apply_edits_to_current(edits)?;
+        self.status_message = "Formatted document".to_string();
+        Ok(())
+    }
+
+    // Files touched by a rename that aren't open yet are loaded into new
+    // buffers so the result can be reviewed before saving.
+    fn apply_workspace_edit(&mut self, edit: &WorkspaceEdit) -> Result<()> {
+        if edit.changes.is_empty() {
+            self.status_message = "Nothing to rename".to_string();
+            return Ok(());
+        }
+
+        for (path, edits) in &edit.changes {
+            let index = self.buffer_index_for(path)?;
+            if index == self.current_buffer_index {
+                self.apply_edits_to_current(edits)?;
+            } else {
+                self.apply_text_edits(index, edits)?;
+            }
+        }
+
+        let files = edit.changes.len();
+        self.status_message = format!(
+            "Renamed {} occurrences in {} {}",
+            edit.edit_count(),
+            files,
+            if files == 1 { "file" } else { "files" }
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+use super::state::EditorState;
+use crate::command::Command;
+use crate::cursor::CursorPosition;
+use crate::error::{EditorError, Result};
+use crate::keyboard_macro::{KeyboardMacro, MacroRegisters};
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct MacroRecording {
+    name: String,
+    commands: Vec<Command>,
+}
+
+impl EditorState {
+    pub fn is_recording_macro(&self) -> bool {
+        self.macro_recording.is_some()
+    }
+
+    pub fn recording_macro_name(&self) -> Option<&str> {
+        self.macro_recording
+            .as_ref()
+            .map(|recording| recording.name.as_str())
+    }
+
+    pub fn macro_registers(&self) -> &MacroRegisters {
+        &self.macros
+    }
+
+    pub fn set_macro_registers(&mut self, macros: MacroRegisters) {
+        self.macros = macros;
+    }
+
+    // Only commands issued from outside are recorded: the ones a macro or a
+    // vim repeat runs on its behalf are replayed by the outer command.
+    pub(super) fn macro_should_record(&self, command: &Command) -> bool {
+        self.macro_recording.is_some()
+            && self.command_depth == 0
+            && !matches!(
+                command,
+                Command::StartMacroRecording(_)
+                    | Command::StopMacroRecording
+                    | Command::ToggleMacroRecording
+                    | Command::SaveMacros
+            )
+    }
+
+    pub(super) fn record_macro_command(&mut self, command: Command) {
+        if let Some(recording) = self.macro_recording.as_mut() {
+            recording.commands.push(command);
+        }
+    }
+
+    pub(super) fn start_macro_recording(&mut self, name: &str) -> Result<()> {
+        if self.macro_recording.is_some() {
+            return Err(EditorError::InvalidOperation(
+                "Already recording a macro".to_string(),
+            ));
+        }
+        let name = name.trim();
+        self.macro_recording = Some(MacroRecording {
+            name: name.to_string(),
+            commands: Vec::new(),
+        });
+        self.status_message = if name.is_empty() {
+            "Recording macro".to_string()
+        } else {
+            format!("Recording macro '{}'", name)
+        };
+        Ok(())
+    }
+
+    pub(super) fn stop_macro_recording(&mut self) -> Result<()> {
+        let Some(recording) = self.macro_recording.take() else {
+            return Err(EditorError::InvalidOperation(
+                "Not recording a macro".to_string(),
+            ));
+        };
+        if recording.commands.is_empty() {
+            self.status_message = "Macro is empty, nothing recorded".to_string();
+            return Ok(());
+        }
+
+        let count = recording.commands.len();
+        self.macros
+            .set(&recording.name, KeyboardMacro::new(recording.commands));
+        self.status_message = if recording.name.is_empty() {
+            format!("Recorded macro ({} commands)", count)
+        } else {
+            format!("Recorded macro '{}' ({} commands)", recording.name, count)
+        };
+        Ok(())
+    }
+
+    pub(super) fn toggle_macro_recording(&mut self) -> Result<()> {
+        if self.macro_recording.is_some() {
+            self.stop_macro_recording()
+        } else {
+            self.start_macro_recording("")
+        }
+    }
+
+    pub(super) fn play_macro(&mut self, name: &str, count: usize) -> Result<()> {
+        // As in Emacs, asking for the last macro while recording one ends
+        // the recording instead.
+        if name.trim().is_empty() && self.macro_recording.is_some() {
+            return self.stop_macro_recording();
+        }
+        let recorded = self.macro_to_play(name)?;
+        self.run_macro(name, |state| {
+            (0..count).try_for_each(|_| state.run_macro_commands(&recorded))
+        })
+    }
+
+    // Runs the macro once at each cursor, starting from the bottom so that
+    // edits made at one cursor don't move the cursors still to come.
+    pub(super) fn play_macro_at_cursors(&mut self, name: &str) -> Result<()> {
+        let recorded = self.macro_to_play(name)?;
+        let mut positions: Vec<CursorPosition> = self.cursors.positions().to_vec();
+        positions.sort_by_key(|position| std::cmp::Reverse((position.line, position.column)));
+
+        self.run_macro(name, |state| {
+            positions.iter().try_for_each(|position| {
+                state.cursors.reset_to(*position);
+                state.selection = None;
+                state.run_macro_commands(&recorded)
+            })
+        })
+    }
+
+    pub(super) fn save_macros(&mut self) -> Result<()> {
+        let path = MacroRegisters::user_macros_path()?;
+        self.macros.save_to_file(&path)?;
+        self.status_message = format!(
+            "Saved {} macros to {}",
+            self.macros.names().count(),
+            path.display()
+        );
+        Ok(())
+    }
+
+    fn macro_to_play(&self, name: &str) -> Result<KeyboardMacro> {
+        let name = name.trim();
+        if self.playing_macros.iter().any(|playing| playing == name)
+            || self
+                .recording_macro_name()
+                .is_some_and(|recording| recording == name)
+        {
+            return Err(EditorError::InvalidOperation(format!(
+                "Macro '{}' can't call itself",
+                name
+            )));
+        }
+        match self.macros.get(name) {
+            Some(recorded) => Ok(recorded.clone()),
+            None if name.is_empty() => Err(EditorError::InvalidOperation(
+                "No macro has been recorded".to_string(),
+            )),
+            None => Err(EditorError::InvalidOperation(format!(
+                "No macro named '{}'",
+                name
+            ))),
+        }
+    }
+
+    // A whole playback undoes as one step.
+    fn run_macro(&mut self, name: &str, run: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        self.history.end_group();
+        let history_start = self.history.undo_stack_len();
+        self.playing_macros.push(name.trim().to_string());
+        let result = run(self);
+        self.playing_macros.pop();
+        self.history.merge_from(history_start);
+        result
+    }
+
+    fn run_macro_commands(&mut self, recorded: &KeyboardMacro) -> Result<()> {
+        for (index, command) in recorded.commands.iter().enumerate() {
+            self.execute_command(command.clone()).map_err(|e| {
+                EditorError::InvalidOperation(format!(
+                    "Macro stopped at command {} of {}: {}",
+                    index + 1,
+                    recorded.len(),
+                    e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
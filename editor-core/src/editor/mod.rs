@@ -1,22 +1,38 @@
+mod block_ops;
 mod bookmark_ops;
 mod clipboard_ops;
 mod code_intelligence_ops;
+mod completion_ops;
+mod config_ops;
 mod cursor_ops;
+mod expand_ops;
 mod file_ops;
+mod file_tree_ops;
+mod fold_ops;
 mod git_history_ops;
 mod history_browser_ops;
+mod isearch_ops;
+mod kill_ring_ops;
+mod lsp_ops;
+mod macro_ops;
 mod mode;
 mod mouse_ops;
+mod occurrence_ops;
 mod replace_ops;
 mod search_ops;
 mod search_types;
 mod session_ops;
+mod snippet_ops;
+mod split_ops;
 mod state;
 mod state_accessors;
 mod state_utils;
+mod symbol_ops;
 mod text_ops;
 mod undo_ops;
 mod view_ops;
+mod vim_motion_ops;
+mod vim_ops;
 
 pub use mode::EditorMode;
 pub use search_types::SearchOptions;
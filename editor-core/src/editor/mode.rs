@@ -1,7 +1,9 @@
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum EditorMode {
     #[default]
-    Normal, // Standard modeless editing
-    Insert, // Vim-style insert
-    Visual, // Vim-style visual
+    Normal, // Modeless editing, or vim normal mode when vim is enabled
+    Insert,      // Vim-style insert
+    Visual,      // Vim-style visual
+    VisualLine,  // Vim-style visual line
+    VisualBlock, // Vim-style visual block
 }
@@ -0,0 +1,219 @@
+use super::state::EditorState;
+use super::state_utils::is_word_char;
+use crate::cursor::CursorPosition;
+use crate::error::Result;
+use crate::selection::{Selection, SelectionMode};
+
+// What add-next-occurrence looks for. Starting from the word under the
+// cursor matches whole words only; starting from a selection matches its
+// text anywhere.
+#[derive(Debug, Clone)]
+pub(super) struct OccurrenceQuery {
+    text: String,
+    whole_word: bool,
+    // Start of the selection added last; the next search goes on from here.
+    last: CursorPosition,
+}
+
+impl EditorState {
+    pub(super) fn add_next_occurrence(&mut self) -> Result<()> {
+        let Some(mut query) = self.occurrence_query()? else {
+            return self.select_words_at_cursors();
+        };
+        let Some(next) = self.next_occurrence(&query)? else {
+            self.status_message = format!("No more occurrences of '{}'", query.text);
+            return Ok(());
+        };
+
+        self.adopt_primary_selection();
+        self.cursors.add_selection(next);
+        query.last = next.start();
+        self.occurrence = Some(query);
+        Ok(())
+    }
+
+    // Moves the selection added last on to the next occurrence.
+    pub(super) fn skip_occurrence(&mut self) -> Result<()> {
+        let Some(mut query) = self.occurrence_query()? else {
+            return self.select_words_at_cursors();
+        };
+        let Some(next) = self.next_occurrence(&query)? else {
+            self.status_message = format!("No more occurrences of '{}'", query.text);
+            return Ok(());
+        };
+
+        let mut selections = self.selections();
+        selections.retain(|selection| selection.start() != query.last);
+        selections.push(next);
+        self.set_selections(selections);
+        query.last = next.start();
+        self.occurrence = Some(query);
+        Ok(())
+    }
+
+    pub(super) fn select_all_occurrences(&mut self) -> Result<()> {
+        if self.occurrence_query()?.is_none() {
+            self.select_words_at_cursors()?;
+        }
+        let Some(mut query) = self.occurrence_query()? else {
+            return Ok(());
+        };
+
+        let selections = self.occurrences(&query)?;
+        self.status_message = format!("{} occurrences of '{}'", selections.len(), query.text);
+        if let Some(last) = selections.last() {
+            query.last = last.start();
+        }
+        self.set_selections(selections);
+        self.occurrence = Some(query);
+        Ok(())
+    }
+
+    // Gives every line of a selection that spans several lines its own
+    // cursor, with the part of the line that was selected.
+    pub(super) fn split_selection_into_lines(&mut self) -> Result<()> {
+        let selections = self.selections();
+        if selections.is_empty() {
+            return Ok(());
+        }
+
+        let mut split = Vec::new();
+        for selection in selections {
+            let start = selection.start();
+            let end = selection.end();
+            if start.line == end.line {
+                split.push(selection);
+                continue;
+            }
+
+            // A selection ending at the start of a line leaves that line out.
+            let last = if end.column == 0 && selection.mode == SelectionMode::Normal {
+                end.line - 1
+            } else {
+                end.line
+            };
+            for line in start.line..=last {
+                let line_len = self.buffer().line_len(line)?;
+                if let Some((from, to)) = selection.columns_on_line(line, line_len) {
+                    split.push(Selection::new(
+                        CursorPosition::new(line, from),
+                        CursorPosition::new(line, to),
+                    ));
+                }
+            }
+        }
+
+        self.block_selection_mode = false;
+        self.set_selections(split);
+        Ok(())
+    }
+
+    // Carries on with the last search while its selection is still there,
+    // and otherwise starts one from the last selection.
+    fn occurrence_query(&self) -> Result<Option<OccurrenceQuery>> {
+        let selections = self.selections();
+        if let Some(query) = &self.occurrence {
+            for selection in &selections {
+                if selection.start() == query.last
+                    && self.get_text_range(selection.start(), selection.end())? == query.text
+                {
+                    return Ok(Some(query.clone()));
+                }
+            }
+        }
+
+        let Some(selection) = selections.last() else {
+            return Ok(None);
+        };
+        Ok(Some(OccurrenceQuery {
+            text: self.get_text_range(selection.start(), selection.end())?,
+            whole_word: false,
+            last: selection.start(),
+        }))
+    }
+
+    // Selects the word at each cursor, so that the next add-next-occurrence
+    // looks for the primary cursor's word.
+    fn select_words_at_cursors(&mut self) -> Result<()> {
+        let mut selections = Vec::new();
+        for &position in self.cursors.positions() {
+            let (start, end) = self.word_span_at(position)?;
+            selections.push(Selection::new(start, end));
+        }
+        let Some(&primary) = selections.first() else {
+            return Ok(());
+        };
+        if primary.is_empty() {
+            self.status_message = "No word at the cursor".to_string();
+            return Ok(());
+        }
+
+        self.occurrence = Some(OccurrenceQuery {
+            text: self.get_text_range(primary.start(), primary.end())?,
+            whole_word: true,
+            last: primary.start(),
+        });
+        self.set_selections(selections);
+        Ok(())
+    }
+
+    fn word_span_at(&self, position: CursorPosition) -> Result<(CursorPosition, CursorPosition)> {
+        let chars: Vec<char> = self.buffer().rope().line(position.line).chars().collect();
+        let column = position.column.min(chars.len());
+
+        let mut start = column;
+        while start > 0 && is_word_char(chars[start - 1]) {
+            start -= 1;
+        }
+        let mut end = column;
+        while end < chars.len() && is_word_char(chars[end]) {
+            end += 1;
+        }
+        Ok((
+            CursorPosition::new(position.line, start),
+            CursorPosition::new(position.line, end),
+        ))
+    }
+
+    // Every visible match of the query as a selection, in document order.
+    // The selected text is matched literally, following the search options
+    // for case and whole words.
+    fn occurrences(&self, query: &OccurrenceQuery) -> Result<Vec<Selection>> {
+        let buffer = self.buffer();
+        let options = self.search_options;
+        let length = query.text.chars().count();
+        let mut selections = Vec::new();
+        for idx in buffer.find_all_advanced(
+            &query.text,
+            options.case_sensitive,
+            false,
+            query.whole_word || options.whole_word,
+        ) {
+            let (line, column) = buffer.char_to_line_col(idx)?;
+            if buffer.folds().is_hidden(line) {
+                continue;
+            }
+            let (end_line, end_column) = buffer.char_to_line_col(idx + length)?;
+            selections.push(Selection::new(
+                CursorPosition::new(line, column),
+                CursorPosition::new(end_line, end_column),
+            ));
+        }
+        Ok(selections)
+    }
+
+    // The first match after the last one added that isn't selected yet,
+    // wrapping around at the end of the buffer.
+    fn next_occurrence(&self, query: &OccurrenceQuery) -> Result<Option<Selection>> {
+        let selected: Vec<CursorPosition> =
+            self.selections().iter().map(Selection::start).collect();
+        let occurrences = self.occurrences(query)?;
+        let (before, after): (Vec<Selection>, Vec<Selection>) = occurrences
+            .into_iter()
+            .partition(|occurrence| occurrence.start() <= query.last);
+        Ok(after
+            .into_iter()
+            .chain(before)
+            .find(|occurrence| !selected.contains(&occurrence.start())))
+    }
+}
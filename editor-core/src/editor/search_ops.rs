@@ -28,14 +28,7 @@ impl EditorState {
 
         // Simple heuristic: Search from current position. If found at current position, maybe that's fine.
 
-        let opts = self.search_options;
-        if let Some(idx) = self.buffer().find_next_advanced(
-            &query,
-            start_idx,
-            opts.case_sensitive,
-            opts.use_regex,
-            opts.whole_word,
-        ) {
+        if let Some(idx) = self.find_next_visible(&query, start_idx) {
             let match_len = self.get_match_length(&query, idx)?;
             self.move_to_match(idx, match_len)?;
         }
@@ -47,7 +40,6 @@ impl EditorState {
         if let Some(query) = self.last_search_query.clone() {
             let start_pos = self.cursors.primary();
             let mut start_idx = self.buffer().char_index(start_pos.line, start_pos.column)?;
-            let opts = self.search_options;
 
             // If we are currently sitting on a match, we don't want to find the same match again.
             // We should advance by 1 char? Or length of match?
@@ -56,26 +48,14 @@ impl EditorState {
             // Let's advance by 1 char to ensure we find "next" one.
             start_idx += 1;
 
-            if let Some(idx) = self.buffer().find_next_advanced(
-                &query,
-                start_idx,
-                opts.case_sensitive,
-                opts.use_regex,
-                opts.whole_word,
-            ) {
+            if let Some(idx) = self.find_next_visible(&query, start_idx) {
                 let match_len = self.get_match_length(&query, idx)?;
                 self.move_to_match(idx, match_len)?;
             } else {
                 // Wrap around?
                 // Roadmap doesn't explicitly splitting 'wrap' but "BASIC search" implies basic navigation.
                 // Let's implement wrap.
-                if let Some(idx) = self.buffer().find_next_advanced(
-                    &query,
-                    0,
-                    opts.case_sensitive,
-                    opts.use_regex,
-                    opts.whole_word,
-                ) {
+                if let Some(idx) = self.find_next_visible(&query, 0) {
                     let match_len = self.get_match_length(&query, idx)?;
                     self.move_to_match(idx, match_len)?;
                 }
@@ -89,7 +69,6 @@ impl EditorState {
             let start_pos = self.cursors.primary();
             let original_idx = self.buffer().char_index(start_pos.line, start_pos.column)?;
             let mut search_idx = original_idx;
-            let opts = self.search_options;
 
             // Search backwards
             // If we find a match that ENDS at original_idx, we should verify it's the one we want to skip?
@@ -100,13 +79,7 @@ impl EditorState {
                     break;
                 }
 
-                if let Some(idx) = self.buffer().find_previous_advanced(
-                    &query,
-                    search_idx - 1,
-                    opts.case_sensitive,
-                    opts.use_regex,
-                    opts.whole_word,
-                ) {
+                if let Some(idx) = self.find_previous_visible(&query, search_idx - 1) {
                     let match_len = self.get_match_length(&query, idx)?;
                     if idx + match_len == original_idx {
                         // This is the match we are currently at.
@@ -125,13 +98,7 @@ impl EditorState {
             // Wrap around (search from end)
             let len = self.buffer().len_chars();
 
-            if let Some(idx) = self.buffer().find_previous_advanced(
-                &query,
-                len,
-                opts.case_sensitive,
-                opts.use_regex,
-                opts.whole_word,
-            ) {
+            if let Some(idx) = self.find_previous_visible(&query, len) {
                 let match_len = self.get_match_length(&query, idx)?;
                 // Check if it is same as original (single match case)
                 if idx + match_len == original_idx {
@@ -147,6 +114,50 @@ impl EditorState {
         Ok(())
     }
 
+    // Matches inside folded regions are skipped; the search resumes after
+    // (or before) the whole fold rather than match by match.
+    fn find_next_visible(&self, query: &str, from: usize) -> Option<usize> {
+        let opts = self.search_options;
+        let buffer = self.buffer();
+        let mut start = from;
+        loop {
+            let idx = buffer.find_next_advanced(
+                query,
+                start,
+                opts.case_sensitive,
+                opts.use_regex,
+                opts.whole_word,
+            )?;
+            let line = buffer.rope().char_to_line(idx);
+            if !buffer.folds().is_hidden(line) {
+                return Some(idx);
+            }
+            let next_line = buffer.folds().next_visible(line, buffer.line_count())?;
+            start = buffer.rope().line_to_char(next_line);
+        }
+    }
+
+    fn find_previous_visible(&self, query: &str, from: usize) -> Option<usize> {
+        let opts = self.search_options;
+        let buffer = self.buffer();
+        let mut start = from;
+        loop {
+            let idx = buffer.find_previous_advanced(
+                query,
+                start,
+                opts.case_sensitive,
+                opts.use_regex,
+                opts.whole_word,
+            )?;
+            let line = buffer.rope().char_to_line(idx);
+            if !buffer.folds().is_hidden(line) {
+                return Some(idx);
+            }
+            let header = buffer.folds().visible_line(line);
+            start = buffer.rope().line_to_char(header + 1).checked_sub(1)?;
+        }
+    }
+
     pub(super) fn move_to_match(&mut self, char_idx: usize, len: usize) -> Result<()> {
         let (line, col) = self.buffer().char_to_line_col(char_idx)?;
         // We might want to select the match?
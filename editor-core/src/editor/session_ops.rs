@@ -1,8 +1,10 @@
 use super::state::EditorState;
-use crate::buffer::Buffer;
-use crate::cursor::CursorPosition;
+use crate::cursor::{CursorPosition, MultiCursor};
 use crate::error::Result;
-use crate::session::{OpenFileState, Session};
+use crate::folding::FileFolds;
+use crate::session::{OpenFileState, Session, SessionLayout, SplitPaneState};
+use crate::split::{SplitLayout, SplitView};
+use std::collections::HashMap;
 
 impl EditorState {
     pub fn capture_file_state(&self) -> Option<OpenFileState> {
@@ -16,7 +18,7 @@ impl EditorState {
     }
 
     pub fn restore_from_file_state(&mut self, file_state: &OpenFileState) -> Result<()> {
-        let buffer = Buffer::from_file(file_state.path.clone())?;
+        let buffer = self.load_buffer(file_state.path.clone())?;
         self.buffers.push(buffer);
         self.current_buffer_index = self.buffers.len() - 1;
 
@@ -42,7 +44,122 @@ impl EditorState {
                 file_state.viewport_top,
             );
             session.set_active_file(&file_state.path);
+            session.save_folds(
+                file_state.path.clone(),
+                FileFolds::from_state(file_state.path.clone(), self.buffer().folds()),
+            );
             session.add_to_recent_files(file_state.path);
         }
+        session.layout = self.capture_split_layout();
+        session.clipboard_history = if self.persist_clipboard_history {
+            self.clipboard_history.entries().cloned().collect()
+        } else {
+            Vec::new()
+        };
+    }
+
+    pub fn capture_split_layout(&self) -> Option<SessionLayout> {
+        if !self.splits.is_split() {
+            return None;
+        }
+        let focused = self.focused_view();
+        let panes = self
+            .splits
+            .pane_ids()
+            .into_iter()
+            .filter_map(|pane| {
+                let view = self.split_views.get(&pane).unwrap_or(&focused);
+                let path = self.buffers[view.buffer_index].file_path()?;
+                let cursor = view.cursors.primary();
+                Some(SplitPaneState {
+                    pane,
+                    path: path.clone(),
+                    cursor_line: cursor.line,
+                    cursor_column: cursor.column,
+                    viewport_top: view.viewport_top,
+                })
+            })
+            .collect();
+        Some(SessionLayout {
+            root: self.splits.root().clone(),
+            focused: self.splits.focused(),
+            panes,
+        })
+    }
+
+    // Rebuilds the saved splits, opening each pane's file unless it is
+    // already open. Panes whose file is gone show the focused pane's buffer.
+    pub fn restore_split_layout(&mut self, layout: &SessionLayout) -> Result<()> {
+        let splits = SplitLayout::from_root(layout.root.clone(), layout.focused);
+        let mut views = HashMap::new();
+        for pane in &layout.panes {
+            let Ok(buffer_index) = self.buffer_index_for(&pane.path) else {
+                continue;
+            };
+            let buffer = &self.buffers[buffer_index];
+            let line = pane.cursor_line.min(buffer.line_count().saturating_sub(1));
+            let column = pane
+                .cursor_column
+                .min(buffer.line_len(line).unwrap_or_default());
+            let mut cursors = MultiCursor::new();
+            cursors.reset_to(CursorPosition::new(line, column));
+            views.insert(
+                pane.pane,
+                SplitView {
+                    buffer_index,
+                    cursors,
+                    selection: None,
+                    viewport_top: pane.viewport_top.min(line),
+                    revision: buffer.revision(),
+                },
+            );
+        }
+
+        let fallback = self.focused_view();
+        self.splits = splits;
+        self.split_views = self
+            .splits
+            .pane_ids()
+            .into_iter()
+            .map(|pane| {
+                (
+                    pane,
+                    views.remove(&pane).unwrap_or_else(|| fallback.clone()),
+                )
+            })
+            .collect();
+        let mut focused = self
+            .split_views
+            .remove(&self.splits.focused())
+            .unwrap_or(fallback);
+        self.current_buffer_index = focused.buffer_index;
+        self.cursors = std::mem::take(&mut focused.cursors);
+        self.selection = None;
+        self.viewport_top = focused.viewport_top;
+        Ok(())
+    }
+
+    pub fn restore_clipboard_history_from_session(&mut self, session: &Session) {
+        if self.persist_clipboard_history {
+            self.clipboard_history
+                .restore(session.clipboard_history.iter().cloned());
+        }
+    }
+
+    pub fn restore_folds_from_session(&mut self, session: &Session) -> Result<()> {
+        let Some(path) = self.buffer().file_path().cloned() else {
+            return Ok(());
+        };
+        let line_count = self.buffer().line_count();
+        let folds: Vec<_> = session
+            .load_folds(&path)
+            .map(|file_folds| file_folds.folds.clone())
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|fold| fold.end_line < line_count)
+            .collect();
+
+        self.buffer_mut().folds_mut().set_folds(&folds);
+        self.move_cursors_out_of_folds()
     }
 }
@@ -0,0 +1,382 @@
+use crate::command::Command;
+use crate::completion::{snippet_items, CompletionItem, CompletionMenu};
+use crate::cursor::CursorPosition;
+use crate::error::{EditorError, Result};
+use crate::selection::Selection;
+use crate::snippet::{
+    expand_snippet, SnippetOptions, SnippetRegistry, SnippetSession, SnippetTabstop,
+};
+use std::collections::HashMap;
+
+use super::state::EditorState;
+
+impl EditorState {
+    pub fn snippets(&self) -> &SnippetRegistry {
+        &self.snippets
+    }
+
+    pub fn set_snippet_registry(&mut self, snippets: SnippetRegistry) {
+        self.snippets = snippets;
+    }
+
+    pub fn snippet_session(&self) -> Option<&SnippetSession> {
+        self.snippet_session.as_ref().map(|(_, session)| session)
+    }
+
+    pub fn is_snippet_active(&self) -> bool {
+        self.snippet_session.is_some()
+    }
+
+    fn snippet_variables(&self) -> HashMap<&'static str, String> {
+        let mut variables = HashMap::new();
+        let line = self.cursor().line;
+        variables.insert("TM_LINE_INDEX", line.to_string());
+        variables.insert("TM_LINE_NUMBER", (line + 1).to_string());
+
+        if let Some(path) = self.buffer().file_path() {
+            let text = |value: Option<&std::ffi::OsStr>| {
+                value.map(|value| value.to_string_lossy().to_string())
+            };
+            variables.insert("TM_FILEPATH", path.to_string_lossy().to_string());
+            if let Some(name) = text(path.file_name()) {
+                variables.insert("TM_FILENAME", name);
+            }
+            if let Some(base) = text(path.file_stem()) {
+                variables.insert("TM_FILENAME_BASE", base);
+            }
+            if let Some(directory) = path.parent() {
+                variables.insert("TM_DIRECTORY", directory.to_string_lossy().to_string());
+            }
+        }
+        variables
+    }
+
+    // Replaces each char range with the snippet, indented to match its line,
+    // and starts a session whose tabstops span every copy. The insertion is a
+    // single undo entry.
+    pub(super) fn insert_snippet_at(
+        &mut self,
+        mut ranges: Vec<(usize, usize)>,
+        body: &str,
+    ) -> Result<()> {
+        ranges.sort_unstable();
+        ranges.dedup();
+
+        let variables = self.snippet_variables();
+        let resolve = |name: &str| variables.get(name).cloned();
+        let tab = self.indent_unit();
+        let mut expansions = Vec::with_capacity(ranges.len());
+        for &(start, _) in &ranges {
+            let (line, _) = self.buffer().char_to_line_col(start)?;
+            let indent = self.indentation_for_line(line)?;
+            expansions.push(expand_snippet(
+                body,
+                &SnippetOptions {
+                    indent: &indent,
+                    tab: &tab,
+                    variable: &resolve,
+                },
+            ));
+        }
+        let Some(first) = expansions.first() else {
+            return Ok(());
+        };
+
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+
+        let mut tabstops: Vec<SnippetTabstop> = first
+            .tabstops
+            .iter()
+            .map(|tabstop| SnippetTabstop {
+                ranges: Vec::new(),
+                ..tabstop.clone()
+            })
+            .collect();
+        let mut shift = 0isize;
+        for (&(start, end), expansion) in ranges.iter().zip(&expansions) {
+            let base = (start as isize + shift) as usize;
+            for (merged, tabstop) in tabstops.iter_mut().zip(&expansion.tabstops) {
+                merged.ranges.extend(
+                    tabstop
+                        .ranges
+                        .iter()
+                        .map(|&(from, to)| (base + from, base + to)),
+                );
+            }
+            shift += expansion.text.chars().count() as isize - (end - start) as isize;
+        }
+
+        for (&(start, end), expansion) in ranges.iter().zip(&expansions).rev() {
+            let (start_line, start_column) = self.buffer().char_to_line_col(start)?;
+            let (end_line, end_column) = self.buffer().char_to_line_col(end)?;
+            self.buffer_mut()
+                .delete_range(start_line, start_column, end_line, end_column)?;
+            self.buffer_mut()
+                .insert_str(start_line, start_column, &expansion.text)?;
+        }
+
+        self.completion = None;
+        let session = SnippetSession::new(tabstops, self.buffer().revision());
+        self.snippet_session = Some((self.current_buffer_index, session));
+        self.select_snippet_tabstop()?;
+        self.push_content_history(content_before, cursor_before, selection_before);
+        Ok(())
+    }
+
+    // Puts a cursor on every copy of the current tabstop and selects the
+    // primary placeholder; choices open in the completion popup. Reaching
+    // the final tabstop ends the session.
+    fn select_snippet_tabstop(&mut self) -> Result<()> {
+        let Some((_, session)) = &self.snippet_session else {
+            return Ok(());
+        };
+        let Some(tabstop) = session.current().cloned() else {
+            self.snippet_session = None;
+            return Ok(());
+        };
+        let is_final = session.is_at_final_tabstop();
+
+        let mut positions = Vec::with_capacity(tabstop.ranges.len());
+        for &(start, end) in &tabstop.ranges {
+            let index = if tabstop.choices.is_empty() {
+                end
+            } else {
+                start
+            };
+            let (line, column) = self.buffer().char_to_line_col(index)?;
+            positions.push(CursorPosition::new(line, column));
+        }
+        self.cursors.set_positions(positions);
+        self.selection = None;
+
+        if is_final {
+            self.snippet_session = None;
+            return Ok(());
+        }
+
+        if let Some(&(start, end)) = tabstop.ranges.first().filter(|(start, end)| start < end) {
+            let (start_line, start_column) = self.buffer().char_to_line_col(start)?;
+            let (end_line, end_column) = self.buffer().char_to_line_col(end)?;
+            let start = CursorPosition::new(start_line, start_column);
+            let end = CursorPosition::new(end_line, end_column);
+            self.selection = Some(if tabstop.choices.is_empty() {
+                Selection::new(start, end)
+            } else {
+                Selection::new(end, start)
+            });
+            if !tabstop.choices.is_empty() {
+                let items = tabstop
+                    .choices
+                    .iter()
+                    .enumerate()
+                    .map(|(index, choice)| {
+                        let mut item = CompletionItem::new(choice.clone()).with_kind("choice");
+                        item.sort_text = Some(format!("{:04}", index));
+                        item
+                    })
+                    .collect();
+                self.completion = Some((
+                    self.current_buffer_index,
+                    CompletionMenu::new(start, "", items),
+                ));
+            }
+        }
+
+        let has_placeholder = tabstop.ranges.iter().any(|(start, end)| start < end);
+        if let Some((_, session)) = &mut self.snippet_session {
+            session.set_placeholder_selected(has_placeholder);
+        }
+        Ok(())
+    }
+
+    fn snippet_session_required(&mut self) -> Result<&mut SnippetSession> {
+        self.snippet_session
+            .as_mut()
+            .map(|(_, session)| session)
+            .ok_or_else(|| EditorError::InvalidOperation("No snippet is active".to_string()))
+    }
+
+    pub(super) fn next_tabstop(&mut self) -> Result<()> {
+        let session = self.snippet_session_required()?;
+        if !session.select_next() {
+            self.snippet_session = None;
+            return Ok(());
+        }
+        self.completion = None;
+        self.select_snippet_tabstop()
+    }
+
+    pub(super) fn previous_tabstop(&mut self) -> Result<()> {
+        let session = self.snippet_session_required()?;
+        if session.select_previous() {
+            self.completion = None;
+            self.select_snippet_tabstop()?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn exit_snippet(&mut self) -> Result<()> {
+        self.snippet_session_required()?;
+        self.snippet_session = None;
+        self.selection = None;
+        Ok(())
+    }
+
+    pub(super) fn expand_snippet_at_cursor(&mut self) -> Result<()> {
+        let cursor = *self.cursor();
+        let start = self.word_start_before(cursor);
+        let prefix = self.line_text(cursor.line, start.column, cursor.column);
+        let language = self.language().name.clone();
+        let Some(snippet) = self.snippets.find_by_prefix(&language, &prefix).cloned() else {
+            self.status_message = if prefix.is_empty() {
+                "No snippet prefix before cursor".to_string()
+            } else {
+                format!("No snippet for '{}'", prefix)
+            };
+            return Ok(());
+        };
+
+        let mut ranges = Vec::with_capacity(self.cursors.positions().len());
+        for &position in self.cursors.positions() {
+            let end = self.buffer().char_index(position.line, position.column)?;
+            let word_start = self.word_start_before(position);
+            let start =
+                if self.line_text(position.line, word_start.column, position.column) == prefix {
+                    self.buffer()
+                        .char_index(word_start.line, word_start.column)?
+                } else {
+                    end
+                };
+            ranges.push((start, end));
+        }
+        self.insert_snippet_at(ranges, &snippet.body)
+    }
+
+    fn line_text(&self, line: usize, start: usize, end: usize) -> String {
+        self.buffer()
+            .rope()
+            .line(line)
+            .chars()
+            .skip(start)
+            .take(end.saturating_sub(start))
+            .collect()
+    }
+
+    // The snippet picker is the completion popup listing every snippet for
+    // the buffer's language, filtered by what is typed after opening it.
+    pub(super) fn open_snippet_picker(&mut self) -> Result<()> {
+        let language = self.language().name.clone();
+        let items: Vec<CompletionItem> = self
+            .snippets
+            .for_language(&language)
+            .flat_map(snippet_items)
+            .collect();
+        if items.is_empty() {
+            self.status_message = format!("No snippets for {}", language);
+            return Ok(());
+        }
+        self.completion = Some((
+            self.current_buffer_index,
+            CompletionMenu::new(*self.cursor(), "", items),
+        ));
+        Ok(())
+    }
+
+    // Typing, deleting or accepting a choice over a freshly selected
+    // placeholder first removes it from every copy of the tabstop; a delete
+    // stops there. Returns whether the command was consumed.
+    pub(super) fn replace_snippet_placeholder(&mut self, command: &Command) -> Result<bool> {
+        let Some((_, session)) = &mut self.snippet_session else {
+            return Ok(false);
+        };
+        if matches!(command, Command::Undo | Command::Redo) {
+            self.snippet_session = None;
+            return Ok(false);
+        }
+        if !session.is_placeholder_selected() {
+            return Ok(false);
+        }
+
+        let consumes = matches!(command, Command::Backspace | Command::DeleteChar);
+        let replaces = consumes
+            || matches!(
+                command,
+                Command::InsertChar(_)
+                    | Command::InsertCharWithAutoClose(_)
+                    | Command::NewLine
+                    | Command::Paste
+                    | Command::CompletionAccept
+            );
+        if !replaces {
+            if command.is_navigation_command()
+                || matches!(
+                    command,
+                    Command::MouseClick(_) | Command::MouseDragStart(_) | Command::MouseDrag(_)
+                )
+            {
+                session.set_placeholder_selected(false);
+            }
+            return Ok(false);
+        }
+
+        session.set_placeholder_selected(false);
+        let ranges = session
+            .current()
+            .map(|tabstop| tabstop.ranges.clone())
+            .unwrap_or_default();
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+
+        for &(start, end) in ranges.iter().rev() {
+            let (start_line, start_column) = self.buffer().char_to_line_col(start)?;
+            let (end_line, end_column) = self.buffer().char_to_line_col(end)?;
+            self.buffer_mut()
+                .delete_range(start_line, start_column, end_line, end_column)?;
+        }
+
+        let mut removed = 0;
+        let mut positions = Vec::with_capacity(ranges.len());
+        for &(start, end) in &ranges {
+            let (line, column) = self.buffer().char_to_line_col(start - removed)?;
+            positions.push(CursorPosition::new(line, column));
+            removed += end - start;
+        }
+        self.cursors.set_positions(positions);
+        self.selection = None;
+        self.push_content_history(content_before, cursor_before, selection_before);
+        Ok(consumes)
+    }
+
+    // Edits move the tabstops along; the session ends when the primary
+    // cursor leaves the current tabstop or the buffer changes.
+    pub(super) fn sync_snippet_session(&mut self) {
+        let Some((buffer_index, session)) = &mut self.snippet_session else {
+            return;
+        };
+        if *buffer_index != self.current_buffer_index {
+            self.snippet_session = None;
+            return;
+        }
+
+        let buffer = &self.buffers[self.current_buffer_index];
+        let revision = buffer.revision();
+        match buffer.changes_since(session.revision()) {
+            Some(changes) => session.apply_changes(changes, revision),
+            None => {
+                self.snippet_session = None;
+                return;
+            }
+        }
+
+        let cursor = *self.cursors.primary();
+        let inside = buffer
+            .char_index(cursor.line, cursor.column)
+            .is_ok_and(|index| session.contains(index));
+        if !inside {
+            self.snippet_session = None;
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use super::state::EditorState;
+use crate::buffer::Buffer;
+use crate::cursor::CursorPosition;
+use crate::error::Result;
+use crate::split::{PaneDirection, SplitDirection, SplitLayout, SplitView};
+
+impl EditorState {
+    pub fn split_layout(&self) -> &SplitLayout {
+        &self.splits
+    }
+
+    pub fn focused_split(&self) -> usize {
+        self.splits.focused()
+    }
+
+    pub fn split_buffer(&self, pane: usize) -> Option<&Buffer> {
+        if pane == self.splits.focused() {
+            return Some(self.buffer());
+        }
+        self.split_views
+            .get(&pane)
+            .and_then(|view| self.buffers.get(view.buffer_index))
+    }
+
+    // Runs `f` with the pane's buffer, cursors and viewport in place of the
+    // focused pane's, so frontends can draw every pane the same way.
+    pub fn with_split_view<R>(&mut self, pane: usize, f: impl FnOnce(&Self) -> R) -> R {
+        self.sync_split_views();
+        let Some(mut view) = self.split_views.remove(&pane) else {
+            return f(self);
+        };
+        self.swap_split_view(&mut view);
+        let result = f(self);
+        self.swap_split_view(&mut view);
+        self.split_views.insert(pane, view);
+        result
+    }
+
+    fn swap_split_view(&mut self, view: &mut SplitView) {
+        std::mem::swap(&mut self.current_buffer_index, &mut view.buffer_index);
+        std::mem::swap(&mut self.cursors, &mut view.cursors);
+        std::mem::swap(&mut self.selection, &mut view.selection);
+        std::mem::swap(&mut self.viewport_top, &mut view.viewport_top);
+    }
+
+    pub(super) fn focused_view(&self) -> SplitView {
+        SplitView {
+            buffer_index: self.current_buffer_index,
+            cursors: self.cursors.clone(),
+            selection: self.selection,
+            viewport_top: self.viewport_top,
+            revision: self.buffer().revision(),
+        }
+    }
+
+    pub(super) fn split(&mut self, direction: SplitDirection) -> Result<()> {
+        let view = self.focused_view();
+        let focused = self.splits.focused();
+        let pane = self.splits.split(direction);
+        self.split_views.insert(focused, view);
+        self.splits.focus(pane)
+    }
+
+    pub(super) fn focus_split_pane(&mut self, pane: usize) -> Result<()> {
+        let focused = self.splits.focused();
+        if pane == focused {
+            return Ok(());
+        }
+        self.splits.focus(pane)?;
+        let view = self.load_split_view(pane);
+        self.split_views.insert(focused, view);
+        Ok(())
+    }
+
+    // Makes `pane`'s view the focused one and returns the view it replaced.
+    fn load_split_view(&mut self, pane: usize) -> SplitView {
+        self.sync_split_views();
+        let mut view = self
+            .split_views
+            .remove(&pane)
+            .unwrap_or_else(|| self.focused_view());
+        self.swap_split_view(&mut view);
+        view.revision = self.buffers[view.buffer_index].revision();
+
+        self.completion = None;
+        self.snippet_session = None;
+        self.file_tree_focused = false;
+        self.symbol_outline_focused = false;
+        view
+    }
+
+    pub(super) fn focus_split(&mut self, direction: PaneDirection) -> Result<()> {
+        match self.splits.neighbor(direction) {
+            Some(pane) => self.focus_split_pane(pane),
+            None => Ok(()),
+        }
+    }
+
+    pub(super) fn focus_next_split(&mut self) -> Result<()> {
+        self.focus_split_pane(self.splits.next_pane())
+    }
+
+    pub(super) fn close_split(&mut self) -> Result<()> {
+        let focus = self.splits.close(self.splits.focused())?;
+        self.load_split_view(focus);
+        Ok(())
+    }
+
+    pub(super) fn resize_split(&mut self, direction: SplitDirection, delta: i32) -> Result<()> {
+        if !self.splits.resize(direction, delta as f32 / 100.0) {
+            self.status_message = "No split to resize that way".to_string();
+        }
+        Ok(())
+    }
+
+    // Exchanges the focused pane with the next one; focus moves with it.
+    pub(super) fn swap_split(&mut self) -> Result<()> {
+        let focused = self.splits.focused();
+        self.splits.swap(focused, self.splits.next_pane());
+        Ok(())
+    }
+
+    // Moves the cursors of unfocused panes along with edits made elsewhere
+    // in their buffers.
+    pub(super) fn sync_split_views(&mut self) {
+        for view in self.split_views.values_mut() {
+            let buffer = &self.buffers[view.buffer_index];
+            let revision = buffer.revision();
+            if view.revision == revision {
+                continue;
+            }
+            match buffer.changes_since(view.revision) {
+                Some(changes) => view.apply_changes(changes, revision),
+                None => {
+                    let positions = view
+                        .cursors
+                        .positions()
+                        .iter()
+                        .map(|position| clamp_to_buffer(buffer, *position))
+                        .collect();
+                    view.cursors.set_positions(positions);
+                    view.selection = None;
+                    view.viewport_top =
+                        view.viewport_top.min(buffer.line_count().saturating_sub(1));
+                    view.revision = revision;
+                }
+            }
+        }
+    }
+}
+
+fn clamp_to_buffer(buffer: &Buffer, position: CursorPosition) -> CursorPosition {
+    let line = position.line.min(buffer.line_count().saturating_sub(1));
+    let column = position
+        .column
+        .min(buffer.line_len(line).unwrap_or_default());
+    CursorPosition::new(line, column)
+}
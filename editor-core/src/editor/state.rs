@@ -1,17 +1,39 @@
 use crate::bookmark::BookmarkManager;
 use crate::buffer::Buffer;
 use crate::clipboard::ClipboardManager;
+use crate::clipboard_history::ClipboardHistory;
 use crate::command::Command;
-use crate::cursor::MultiCursor;
+use crate::completion::{
+    BufferWordSource, CompletionMenu, CompletionSource, PathSource, SnippetSource,
+};
+use crate::cursor::{CursorPosition, MultiCursor};
 use crate::error::Result;
+use crate::file_tree::FileTree;
 use crate::git_history::{CleanupStats, GitHistoryManager, HistoryStats, LargeFileConfig};
 use crate::history::History;
 use crate::history_browser::HistoryBrowser;
+use crate::keyboard_macro::MacroRegisters;
+use crate::keymap::KeymapProfile;
+use crate::kill_ring::KillRing;
+use crate::language::LanguageRegistry;
+use crate::lsp::{Diagnostic, Location, LspClient};
 use crate::selection::Selection;
+use crate::snippet::{SnippetRegistry, SnippetSession};
+use crate::split::{SplitLayout, SplitView};
+use crate::symbol_index::SymbolIndex;
+use crate::symbols::SymbolOutline;
+use crate::vim::VimState;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 
+use super::expand_ops::SelectionExpansion;
+use super::isearch_ops::IncrementalSearch;
+use super::kill_ring_ops::KillChain;
+use super::macro_ops::MacroRecording;
 use super::mode::EditorMode;
+use super::occurrence_ops::OccurrenceQuery;
 use super::search_types::SearchOptions;
+use super::symbol_ops::SymbolOutlineSource;
 
 pub struct EditorState {
     pub(super) buffers: Vec<Buffer>,
@@ -19,6 +41,8 @@ pub struct EditorState {
     pub(super) cursors: MultiCursor,
     pub(super) viewport_top: usize,
     pub(super) scroll_offset: usize,
+    pub(super) tab_size: usize,
+    pub(super) insert_spaces: bool,
     pub(super) status_message: String,
     pub(super) overwrite_mode: bool,
     pub(super) soft_wrap_width: Option<usize>,
@@ -26,6 +50,12 @@ pub struct EditorState {
     pub(super) block_selection_mode: bool,
     pub(super) bookmarks: BookmarkManager,
     pub(super) clipboard: ClipboardManager,
+    // What each cursor copied last, so that pasting with as many cursors
+    // hands every cursor its own piece.
+    pub(super) clipboard_pieces: Vec<String>,
+    pub(super) clipboard_rectangle: Option<String>,
+    pub(super) clipboard_history: ClipboardHistory,
+    pub(super) persist_clipboard_history: bool,
     pub(super) mode: EditorMode,
     pub(super) last_search_query: Option<String>,
     pub(super) search_options: SearchOptions,
@@ -38,6 +68,40 @@ pub struct EditorState {
     pub(super) history_browser: Option<HistoryBrowser>,
     pub(super) history_stats: Option<HistoryStats>,
     pub(super) cleanup_stats: Option<CleanupStats>,
+    pub(super) file_tree: Option<FileTree>,
+    pub(super) file_tree_focused: bool,
+    pub(super) symbol_outline: Option<SymbolOutline>,
+    pub(super) symbol_outline_focused: bool,
+    pub(super) symbol_outline_source: Option<SymbolOutlineSource>,
+    pub(super) symbol_index: Option<SymbolIndex>,
+    pub(super) symbol_index_path: Option<PathBuf>,
+    pub(super) language_servers: HashMap<String, LspClient>,
+    pub(super) failed_language_servers: HashSet<String>,
+    pub(super) diagnostics: HashMap<PathBuf, Vec<Diagnostic>>,
+    pub(super) hover: Option<String>,
+    pub(super) references: Vec<Location>,
+    pub(super) reference_index: usize,
+    pub(super) completion: Option<(usize, CompletionMenu)>,
+    pub(super) completion_sources: Vec<Box<dyn CompletionSource>>,
+    pub(super) snippets: SnippetRegistry,
+    pub(super) snippet_session: Option<(usize, SnippetSession)>,
+    pub(super) splits: SplitLayout,
+    pub(super) split_views: HashMap<usize, SplitView>,
+    pub(super) languages: LanguageRegistry,
+    pub(super) vim: Option<VimState>,
+    pub(super) quit_requested: bool,
+    pub(super) keymap_profile: KeymapProfile,
+    pub(super) kill_ring: KillRing,
+    pub(super) kill_chain: Option<KillChain>,
+    pub(super) mark: Option<CursorPosition>,
+    pub(super) mark_active: bool,
+    pub(super) isearch: Option<IncrementalSearch>,
+    pub(super) occurrence: Option<OccurrenceQuery>,
+    pub(super) expansion: Option<SelectionExpansion>,
+    pub(super) macros: MacroRegisters,
+    pub(super) macro_recording: Option<MacroRecording>,
+    pub(super) playing_macros: Vec<String>,
+    pub(super) command_depth: usize,
 }
 
 impl EditorState {
@@ -51,13 +115,19 @@ impl EditorState {
             cursors: MultiCursor::new(),
             viewport_top: 0,
             scroll_offset: 5,
+            tab_size: 4,
+            insert_spaces: true,
             status_message: String::new(),
             overwrite_mode: false,
             soft_wrap_width: None,
             selection: None,
             block_selection_mode: false,
             bookmarks: BookmarkManager::new(),
-            clipboard: ClipboardManager,
+            clipboard: ClipboardManager::default(),
+            clipboard_pieces: Vec::new(),
+            clipboard_rectangle: None,
+            clipboard_history: ClipboardHistory::new(),
+            persist_clipboard_history: false,
             mode: EditorMode::default(),
             last_search_query: None,
             search_options: SearchOptions::default(),
@@ -70,6 +140,40 @@ impl EditorState {
             history_browser: None,
             history_stats: None,
             cleanup_stats: None,
+            file_tree: None,
+            file_tree_focused: false,
+            symbol_outline: None,
+            symbol_outline_focused: false,
+            symbol_outline_source: None,
+            symbol_index: None,
+            symbol_index_path: None,
+            language_servers: HashMap::new(),
+            failed_language_servers: HashSet::new(),
+            diagnostics: HashMap::new(),
+            hover: None,
+            references: Vec::new(),
+            reference_index: 0,
+            completion: None,
+            completion_sources: default_completion_sources(),
+            snippets: SnippetRegistry::builtin().clone(),
+            snippet_session: None,
+            splits: SplitLayout::new(),
+            split_views: HashMap::new(),
+            languages: LanguageRegistry::builtin().clone(),
+            vim: None,
+            quit_requested: false,
+            keymap_profile: KeymapProfile::default(),
+            kill_ring: KillRing::new(),
+            kill_chain: None,
+            mark: None,
+            mark_active: false,
+            isearch: None,
+            occurrence: None,
+            expansion: None,
+            macros: MacroRegisters::new(),
+            macro_recording: None,
+            playing_macros: Vec::new(),
+            command_depth: 0,
         }
     }
 
@@ -84,13 +188,19 @@ impl EditorState {
             cursors: MultiCursor::new(),
             viewport_top: 0,
             scroll_offset: 5,
+            tab_size: 4,
+            insert_spaces: true,
             status_message: String::new(),
             overwrite_mode: false,
             soft_wrap_width: None,
             selection: None,
             block_selection_mode: false,
             bookmarks: BookmarkManager::new(),
-            clipboard: ClipboardManager,
+            clipboard: ClipboardManager::default(),
+            clipboard_pieces: Vec::new(),
+            clipboard_rectangle: None,
+            clipboard_history: ClipboardHistory::new(),
+            persist_clipboard_history: false,
             mode: EditorMode::default(),
             last_search_query: None,
             search_options: SearchOptions::default(),
@@ -103,12 +213,58 @@ impl EditorState {
             history_browser: None,
             history_stats: None,
             cleanup_stats: None,
+            file_tree: None,
+            file_tree_focused: false,
+            symbol_outline: None,
+            symbol_outline_focused: false,
+            symbol_outline_source: None,
+            symbol_index: None,
+            symbol_index_path: None,
+            language_servers: HashMap::new(),
+            failed_language_servers: HashSet::new(),
+            diagnostics: HashMap::new(),
+            hover: None,
+            references: Vec::new(),
+            reference_index: 0,
+            completion: None,
+            completion_sources: default_completion_sources(),
+            snippets: SnippetRegistry::builtin().clone(),
+            snippet_session: None,
+            splits: SplitLayout::new(),
+            split_views: HashMap::new(),
+            languages: LanguageRegistry::builtin().clone(),
+            vim: None,
+            quit_requested: false,
+            keymap_profile: KeymapProfile::default(),
+            kill_ring: KillRing::new(),
+            kill_chain: None,
+            mark: None,
+            mark_active: false,
+            isearch: None,
+            occurrence: None,
+            expansion: None,
+            macros: MacroRegisters::new(),
+            macro_recording: None,
+            playing_macros: Vec::new(),
+            command_depth: 0,
         })
     }
 
     pub fn execute_command(&mut self, command: Command) -> Result<()> {
+        let recorded = self.macro_should_record(&command).then(|| command.clone());
+        self.command_depth += 1;
+        let result = self.run_command(command);
+        self.command_depth -= 1;
+        if let (Some(command), Ok(())) = (recorded, &result) {
+            self.record_macro_command(command);
+        }
+        result
+    }
+
+    fn run_command(&mut self, command: Command) -> Result<()> {
         use crate::error::EditorError;
-        if self.buffer().is_read_only() && command.is_editing_command() {
+        let searching = self.isearch_command(&command)?;
+        if !searching && self.buffer().is_read_only() && command.is_editing_command() {
             return Err(EditorError::ReadOnlyFile(
                 self.buffer()
                     .file_path()
@@ -117,7 +273,16 @@ impl EditorState {
             ));
         }
 
-        match command {
+        if !matches!(command, Command::ShowHover) {
+            self.hover = None;
+        }
+
+        let kill_chain = self.kill_chain.take();
+        let edited = command.is_editing_command();
+        self.record_vim_insert(&command);
+        let consumed = searching || self.replace_snippet_placeholder(&command)?;
+        let result = match command {
+            _ if consumed => Ok(()),
             Command::InsertChar(ch) => self.insert_char(ch),
             Command::DeleteChar => self.delete_char(),
             Command::Backspace => self.backspace(),
@@ -163,6 +328,12 @@ impl EditorState {
             Command::AddCursor(position) => self.add_cursor(position),
             Command::RemoveCursor(index) => self.remove_cursor(index),
             Command::ClearSecondaryCursors => self.clear_secondary_cursors(),
+            Command::AddNextOccurrence => self.add_next_occurrence(),
+            Command::SkipOccurrence => self.skip_occurrence(),
+            Command::SelectAllOccurrences => self.select_all_occurrences(),
+            Command::SplitSelectionIntoLines => self.split_selection_into_lines(),
+            Command::ExpandSelection => self.expand_selection(),
+            Command::ShrinkSelection => self.shrink_selection(),
 
             Command::MouseClick(position) => self.mouse_click(position),
             Command::MouseDragStart(position) => self.mouse_drag_start(position),
@@ -186,10 +357,19 @@ impl EditorState {
             Command::Copy => self.copy(),
             Command::Cut => self.cut(),
             Command::Paste => self.paste(),
+            Command::PasteFromHistory(index) => self.paste_from_history(index),
+            Command::InsertText(text) => self.insert_text(&text),
+            Command::ToggleClipboardPin(index) => self.toggle_clipboard_pin(index),
+            Command::ClearClipboardHistory => self.clear_clipboard_history(),
             Command::ToggleLineComment => self.toggle_line_comment(),
             Command::ToggleBlockComment => self.toggle_block_comment(),
+            Command::SetLanguage(name) => self.set_language(&name),
             Command::FoldCode => self.fold_code(),
             Command::UnfoldCode => self.unfold_code(),
+            Command::ToggleFold => self.toggle_fold(),
+            Command::FoldAll => self.fold_all(),
+            Command::UnfoldAll => self.unfold_all(),
+            Command::FoldLevel(level) => self.fold_level(level),
 
             Command::ToggleReadOnly => self.toggle_read_only(),
 
@@ -242,10 +422,104 @@ impl EditorState {
             Command::ShowHistoryStats => self.show_history_stats(),
             Command::CleanupHistory => self.cleanup_history(),
 
+            Command::ToggleFileTree => self.toggle_file_tree(),
+            Command::FocusFileTree => self.focus_file_tree(),
+            Command::FocusEditor => self.focus_editor(),
+            Command::FileTreeNavigateNext => self.file_tree_navigate_next(),
+            Command::FileTreeNavigatePrevious => self.file_tree_navigate_previous(),
+            Command::FileTreeNavigateFirst => self.file_tree_navigate_first(),
+            Command::FileTreeNavigateLast => self.file_tree_navigate_last(),
+            Command::FileTreeSelect(index) => self.file_tree_select(index),
+            Command::FileTreeExpand => self.file_tree_expand(),
+            Command::FileTreeCollapse => self.file_tree_collapse(),
+            Command::FileTreeActivate => self.file_tree_activate(),
+            Command::FileTreeToggleHidden => self.file_tree_toggle_hidden(),
+            Command::FileTreeRefresh => self.file_tree_refresh(),
+            Command::FileTreeCreateFile(name) => self.file_tree_create_file(&name),
+            Command::FileTreeCreateDirectory(name) => self.file_tree_create_directory(&name),
+            Command::FileTreeRename(name) => self.file_tree_rename(&name),
+            Command::FileTreeDelete => self.file_tree_delete(),
+
+            Command::ToggleSymbolOutline => self.toggle_symbol_outline(),
+            Command::FocusSymbolOutline => self.focus_symbol_outline(),
+            Command::SymbolOutlineNavigateNext => self.symbol_outline_navigate_next(),
+            Command::SymbolOutlineNavigatePrevious => self.symbol_outline_navigate_previous(),
+            Command::SymbolOutlineNavigateFirst => self.symbol_outline_navigate_first(),
+            Command::SymbolOutlineNavigateLast => self.symbol_outline_navigate_last(),
+            Command::SymbolOutlineSelect(index) => self.symbol_outline_select(index),
+            Command::SymbolOutlineExpand => self.symbol_outline_expand(),
+            Command::SymbolOutlineCollapse => self.symbol_outline_collapse(),
+            Command::SymbolOutlineActivate => self.symbol_outline_activate(),
+            Command::JumpToSymbol { path, line, column } => self.jump_to_symbol(path, line, column),
+            Command::GotoDefinition => self.goto_definition(),
+            Command::RefreshSymbolIndex => self.rebuild_symbol_index(),
+
+            Command::TriggerCompletion => self.trigger_completion(),
+            Command::CompletionNext => self.completion_next(),
+            Command::CompletionPrevious => self.completion_previous(),
+            Command::CompletionSelect(index) => self.completion_select(index),
+            Command::CompletionAccept => self.completion_accept(),
+            Command::CompletionCancel => self.completion_cancel(),
+            Command::ShowHover => self.show_hover(),
+            Command::FindReferences => self.find_references(),
+            Command::NextReference => self.next_reference(),
+            Command::PreviousReference => self.previous_reference(),
+            Command::NextDiagnostic => self.next_diagnostic(),
+            Command::PreviousDiagnostic => self.previous_diagnostic(),
+            Command::RenameSymbol(name) => self.rename_symbol(&name),
+            Command::FormatDocument => self.format_document(),
+            Command::RestartLanguageServer => self.restart_language_server(),
+            Command::ExpandSnippet => self.expand_snippet_at_cursor(),
+            Command::InsertSnippet => self.open_snippet_picker(),
+            Command::NextTabstop => self.next_tabstop(),
+            Command::PreviousTabstop => self.previous_tabstop(),
+            Command::ExitSnippet => self.exit_snippet(),
+
+            Command::Split { direction } => self.split(direction),
+            Command::CloseSplit => self.close_split(),
+            Command::FocusSplit { direction } => self.focus_split(direction),
+            Command::FocusNextSplit => self.focus_next_split(),
+            Command::FocusSplitPane(pane) => self.focus_split_pane(pane),
+            Command::ResizeSplit { direction, delta } => self.resize_split(direction, delta),
+            Command::SwapSplit => self.swap_split(),
+
+            Command::VimKey(press) => self.vim_key(press),
+            Command::ToggleVimMode => self.toggle_vim_mode(),
+            Command::SetMark => self.set_mark(),
+            Command::ExchangePointAndMark => self.exchange_point_and_mark(),
+            Command::KeyboardQuit => self.keyboard_quit(),
+            Command::KillLine => self.kill_line(kill_chain),
+            Command::KillRegion => self.kill_region(kill_chain),
+            Command::CopyRegion => self.copy_region(kill_chain),
+            Command::KillWord { forward } => self.kill_word(forward, kill_chain),
+            Command::Yank => self.yank(),
+            Command::YankPop => self.yank_pop(kill_chain),
+            Command::IncrementalSearch { forward } => self.start_isearch(forward),
+            Command::StartMacroRecording(name) => self.start_macro_recording(&name),
+            Command::StopMacroRecording => self.stop_macro_recording(),
+            Command::ToggleMacroRecording => self.toggle_macro_recording(),
+            Command::PlayMacro { name, count } => self.play_macro(&name, count),
+            Command::PlayMacroAtCursors(name) => self.play_macro_at_cursors(&name),
+            Command::SaveMacros => self.save_macros(),
+            Command::Quit => {
+                self.quit_requested = true;
+                Ok(())
+            }
+
             _ => Err(EditorError::InvalidOperation(
                 "Command not yet implemented".to_string(),
             )),
-        }
+        };
+        self.sync_selections();
+        self.sync_vim();
+        self.sync_mark(edited);
+        self.reveal_cursors();
+        self.sync_symbol_outline();
+        self.sync_completion();
+        self.sync_snippet_session();
+        self.sync_split_views();
+        self.sync_language_servers();
+        result
     }
 
     pub fn toggle_read_only(&mut self) -> Result<()> {
@@ -260,3 +534,11 @@ impl Default for EditorState {
         Self::new()
     }
 }
+
+fn default_completion_sources() -> Vec<Box<dyn CompletionSource>> {
+    vec![
+        Box::new(SnippetSource),
+        Box::new(PathSource),
+        Box::new(BufferWordSource),
+    ]
+}
@@ -1,7 +1,10 @@
 use crate::buffer::Buffer;
 use crate::cursor::CursorPosition;
-use crate::git_history::{CleanupStats, HistoryStats, LargeFileConfig};
+use crate::file_tree::FileTree;
+use crate::git_history::{CleanupStats, GitHistoryManager, HistoryStats, LargeFileConfig};
 use crate::history_browser::HistoryBrowser;
+use crate::language::{LanguageDefinition, LanguageRegistry};
+use crate::symbols::SymbolOutline;
 use std::path::Path;
 
 use super::mode::EditorMode;
@@ -65,6 +68,46 @@ impl EditorState {
         self.scroll_offset = offset;
     }
 
+    pub fn tab_size(&self) -> usize {
+        self.buffer()
+            .editorconfig()
+            .indent_width()
+            .unwrap_or(self.tab_size)
+    }
+
+    pub fn set_tab_size(&mut self, tab_size: usize) {
+        self.tab_size = tab_size.max(1);
+    }
+
+    pub fn insert_spaces(&self) -> bool {
+        self.buffer()
+            .editorconfig()
+            .insert_spaces()
+            .unwrap_or(self.insert_spaces)
+    }
+
+    pub fn set_insert_spaces(&mut self, insert_spaces: bool) {
+        self.insert_spaces = insert_spaces;
+    }
+
+    pub fn languages(&self) -> &LanguageRegistry {
+        &self.languages
+    }
+
+    pub fn set_language_registry(&mut self, languages: LanguageRegistry) {
+        for buffer in &mut self.buffers {
+            buffer.detect_language(&languages);
+        }
+        self.languages = languages;
+    }
+
+    pub fn language(&self) -> &LanguageDefinition {
+        self.buffer()
+            .language()
+            .and_then(|name| self.languages.get(name))
+            .unwrap_or_else(|| self.languages.fallback())
+    }
+
     pub fn file_path(&self) -> Option<&Path> {
         self.buffer().file_path().map(|p| p.as_path())
     }
@@ -86,10 +129,27 @@ impl EditorState {
         self.git_history.set_large_file_config(config);
     }
 
+    pub fn git_history(&self) -> &GitHistoryManager {
+        &self.git_history
+    }
+
     pub fn selection(&self) -> Option<&crate::selection::Selection> {
         self.selection.as_ref()
     }
 
+    // Every cursor's selection in document order. Block selections only
+    // come from a lone cursor.
+    pub fn selections(&self) -> Vec<crate::selection::Selection> {
+        if self.cursors.positions().len() > 1 {
+            self.cursors.selections().into_iter().flatten().collect()
+        } else {
+            self.selection
+                .into_iter()
+                .filter(|selection| !selection.is_empty())
+                .collect()
+        }
+    }
+
     pub fn has_selection(&self) -> bool {
         self.selection.is_some()
     }
@@ -161,4 +221,44 @@ impl EditorState {
     pub fn close_cleanup_stats(&mut self) {
         self.cleanup_stats = None;
     }
+
+    pub fn is_file_tree_open(&self) -> bool {
+        self.file_tree.is_some()
+    }
+
+    pub fn is_file_tree_focused(&self) -> bool {
+        self.file_tree.is_some() && self.file_tree_focused
+    }
+
+    pub fn file_tree(&self) -> Option<&FileTree> {
+        self.file_tree.as_ref()
+    }
+
+    pub fn file_tree_mut(&mut self) -> Option<&mut FileTree> {
+        self.file_tree.as_mut()
+    }
+
+    pub fn is_symbol_outline_open(&self) -> bool {
+        self.symbol_outline.is_some()
+    }
+
+    pub fn is_symbol_outline_focused(&self) -> bool {
+        self.symbol_outline.is_some() && self.symbol_outline_focused
+    }
+
+    pub fn symbol_outline(&self) -> Option<&SymbolOutline> {
+        self.symbol_outline.as_ref()
+    }
+
+    pub fn buffer_count(&self) -> usize {
+        self.buffers.len()
+    }
+
+    pub fn buffers(&self) -> &[Buffer] {
+        &self.buffers
+    }
+
+    pub fn current_buffer_index(&self) -> usize {
+        self.current_buffer_index
+    }
 }
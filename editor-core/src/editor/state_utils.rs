@@ -1,5 +1,8 @@
 use crate::cursor::CursorPosition;
 use crate::error::Result;
+use crate::history::{Edit, HistoryEntry};
+use crate::selection::Selection;
+use std::collections::HashMap;
 
 use super::state::EditorState;
 
@@ -13,7 +16,7 @@ impl EditorState {
         for pos in positions {
             updated.push(f(self, pos)?);
         }
-        self.cursors.set_positions(updated);
+        self.cursors.move_positions(updated);
         Ok(())
     }
 
@@ -25,11 +28,27 @@ impl EditorState {
         positions.sort_by(|a, b| a.line.cmp(&b.line).then(a.column.cmp(&b.column)));
         positions.reverse();
 
-        let mut updated = Vec::with_capacity(positions.len());
+        // Each edit lands before the cursors already handled, so it moves
+        // them by however many characters it added or removed. Tracking
+        // them as char offsets covers cursors on the same line.
+        let mut offsets: Vec<usize> = Vec::with_capacity(positions.len());
         for pos in positions {
-            updated.push(f(self, pos)?);
+            let len_before = self.buffer().len_chars();
+            let moved = f(self, pos)?;
+            let delta = self.buffer().len_chars() as isize - len_before as isize;
+            for offset in &mut offsets {
+                *offset = offset.saturating_add_signed(delta);
+            }
+            offsets.push(self.buffer().char_index(moved.line, moved.column)?);
         }
 
+        let updated = offsets
+            .into_iter()
+            .map(|offset| {
+                let (line, column) = self.buffer().char_to_line_col(offset)?;
+                Ok(CursorPosition::new(line, column))
+            })
+            .collect::<Result<Vec<_>>>()?;
         self.cursors.set_positions(updated);
         Ok(())
     }
@@ -71,6 +90,57 @@ impl EditorState {
         Ok(indent)
     }
 
+    pub(super) fn indent_unit(&self) -> String {
+        if self.insert_spaces() {
+            " ".repeat(self.tab_size())
+        } else {
+            "\t".to_string()
+        }
+    }
+
+    pub(super) fn dedent_indentation(&self, indent: &str) -> String {
+        let unit = self.indent_unit();
+        if let Some(rest) = indent.strip_suffix(unit.as_str()) {
+            return rest.to_string();
+        }
+        if let Some(rest) = indent.strip_suffix('\t') {
+            return rest.to_string();
+        }
+        let spaces = indent.len() - indent.trim_end_matches(' ').len();
+        indent[..indent.len() - spaces.min(self.tab_size())].to_string()
+    }
+
+    pub(super) fn find_unmatched_bracket(
+        &self,
+        from: usize,
+        open: char,
+        close: char,
+        forward: bool,
+    ) -> Option<usize> {
+        let buffer = self.buffer();
+        let mut depth = 0usize;
+        let mut check = |idx: usize, nested: char, target: char| {
+            let ch = buffer.char_at(idx)?;
+            if ch == nested {
+                depth += 1;
+            } else if ch == target {
+                if depth == 0 {
+                    return Some(true);
+                }
+                depth -= 1;
+            }
+            Some(false)
+        };
+
+        if forward {
+            (from..buffer.len_chars()).find(|&idx| check(idx, open, close) == Some(true))
+        } else {
+            (0..from.min(buffer.len_chars()))
+                .rev()
+                .find(|&idx| check(idx, close, open) == Some(true))
+        }
+    }
+
     pub(super) fn wrap_line_to_width(&self, line: &str, width: usize) -> Vec<String> {
         if width == 0 {
             return vec![line.to_string()];
@@ -98,6 +168,123 @@ impl EditorState {
         chunks
     }
 
+    // Records everything since `content_before` as one undo step for edits
+    // that touch many places at once, such as server-provided text edits.
+    pub(super) fn push_content_history(
+        &mut self,
+        content_before: String,
+        cursor_before: Vec<CursorPosition>,
+        selection_before: Option<Selection>,
+    ) {
+        let content_after = self.buffer().content();
+        if content_before == content_after {
+            return;
+        }
+
+        let entry = HistoryEntry::new(
+            vec![Edit::Replace {
+                position: CursorPosition::new(0, 0),
+                old_text: content_before,
+                new_text: content_after,
+            }],
+            cursor_before,
+            self.cursors.positions().to_vec(),
+            selection_before,
+            self.selection,
+        );
+        self.history.push(entry);
+    }
+
+    // With several cursors each keeps its anchor in `cursors`, and the
+    // primary's selection is mirrored into `selection` for the features that
+    // only know about one. A lone cursor hands its anchor back to
+    // `selection`.
+    pub(super) fn sync_selections(&mut self) {
+        if self.cursors.positions().len() > 1 {
+            self.selection = self.cursors.selections()[0];
+        } else if self.cursors.anchors()[0].is_some() {
+            self.selection = self.cursors.selections()[0];
+            self.cursors.clear_anchors();
+        }
+    }
+
+    // A lone cursor's selection carries over to it as the first of many.
+    pub(super) fn adopt_primary_selection(&mut self) {
+        if self.cursors.positions().len() > 1 {
+            return;
+        }
+        if let Some(selection) = self.selection.filter(|s| !s.is_empty() && !s.is_block()) {
+            self.cursors.set_selections(vec![selection]);
+        }
+    }
+
+    // Selects each of `selections`, with one cursor at the end of each.
+    pub(super) fn set_selections(&mut self, selections: Vec<Selection>) {
+        if let [selection] = selections[..] {
+            self.cursors.reset_to(selection.cursor);
+            self.selection = (!selection.is_empty()).then_some(selection);
+        } else {
+            self.cursors.set_selections(selections);
+            self.sync_selections();
+        }
+    }
+
+    // The span each cursor works on, in document order: its selection, or an
+    // empty span where it stands.
+    pub(super) fn cursor_spans(&self) -> Vec<(CursorPosition, CursorPosition)> {
+        if self.cursors.positions().len() > 1 {
+            self.cursors
+                .positions()
+                .iter()
+                .zip(self.cursors.selections())
+                .map(|(&position, selection)| {
+                    selection.map_or((position, position), |s| (s.start(), s.end()))
+                })
+                .collect()
+        } else {
+            let position = *self.cursors.primary();
+            let selection = self.selection.filter(|selection| !selection.is_empty());
+            vec![selection.map_or((position, position), |s| (s.start(), s.end()))]
+        }
+    }
+
+    // The lines the cursors and their selections cover, in order. A
+    // selection that ends at the start of a line leaves that line out.
+    pub(super) fn cursor_lines(&self) -> Vec<usize> {
+        let mut lines: Vec<usize> = self
+            .cursor_spans()
+            .into_iter()
+            .flat_map(|(start, end)| {
+                let last = if end.column == 0 && end.line > start.line {
+                    end.line - 1
+                } else {
+                    end.line
+                };
+                start.line..=last
+            })
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+        lines
+    }
+
+    // Moves the cursors, their anchors and the selection along after text
+    // was added to or removed from the start of some lines.
+    pub(super) fn shift_line_starts(&mut self, shifts: &HashMap<usize, isize>) {
+        let shift = |position: CursorPosition| match shifts.get(&position.line) {
+            Some(&delta) => {
+                CursorPosition::new(position.line, position.column.saturating_add_signed(delta))
+            }
+            None => position,
+        };
+        self.cursors.map_positions(shift);
+        self.selection = self.selection.map(|selection| Selection {
+            anchor: shift(selection.anchor),
+            cursor: shift(selection.cursor),
+            ..selection
+        });
+    }
+
     pub(super) fn clamp_cursors_after_edit(&mut self) -> Result<()> {
         let mut positions = Vec::with_capacity(self.cursors.positions().len());
         for mut pos in self.cursors.positions().to_vec() {
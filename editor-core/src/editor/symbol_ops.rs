@@ -0,0 +1,336 @@
+use crate::buffer::Buffer;
+use crate::cursor::CursorPosition;
+use crate::error::{EditorError, Result};
+use crate::symbol_index::{SymbolIndex, SymbolLocation};
+use crate::symbols::{document_symbols, Symbol, SymbolKind, SymbolOutline};
+use std::path::PathBuf;
+
+use super::state::EditorState;
+use super::state_utils::is_word_char;
+
+pub(super) type SymbolOutlineSource = (usize, Option<PathBuf>, u64, Option<String>);
+
+impl EditorState {
+    pub fn document_symbols(&self) -> Vec<Symbol> {
+        self.buffer_symbols(self.buffer())
+    }
+
+    fn buffer_symbols(&self, buffer: &Buffer) -> Vec<Symbol> {
+        let language = buffer
+            .language()
+            .and_then(|name| self.languages.get(name))
+            .unwrap_or_else(|| self.languages.fallback());
+        document_symbols(
+            buffer.rope(),
+            buffer.syntax_tree(),
+            buffer.syntax_language(),
+            &language.symbols,
+            self.tab_size,
+        )
+    }
+
+    pub fn file_symbol_locations(&self) -> Vec<SymbolLocation> {
+        let path = self.buffer().file_path().cloned();
+        self.document_symbols()
+            .into_iter()
+            .map(|symbol| SymbolLocation::new(path.clone(), symbol))
+            .collect()
+    }
+
+    pub fn set_symbol_index_path(&mut self, path: PathBuf) {
+        self.symbol_index_path = Some(path);
+        self.symbol_index = None;
+    }
+
+    pub fn symbol_index(&self) -> Option<&SymbolIndex> {
+        self.symbol_index.as_ref()
+    }
+
+    pub fn project_root(&self) -> Result<PathBuf> {
+        if let Some(tree) = &self.file_tree {
+            return Ok(tree.root().to_path_buf());
+        }
+        if let Some(path) = self.buffer().file_path() {
+            if let Ok(root) = self.git_history.get_tracking_path(path) {
+                return Ok(root);
+            }
+        }
+        Ok(std::env::current_dir()?)
+    }
+
+    // Open buffers may hold unsaved edits, so their live symbols replace
+    // whatever the on-disk index recorded for the same file.
+    pub fn project_symbols(&mut self) -> Result<Vec<SymbolLocation>> {
+        self.refresh_symbol_index()?;
+        let Some(index) = &self.symbol_index else {
+            return Ok(Vec::new());
+        };
+
+        let open: Vec<(PathBuf, Vec<Symbol>)> = self
+            .buffers
+            .iter()
+            .filter_map(|buffer| {
+                let path = buffer.file_path()?;
+                path.starts_with(index.root())
+                    .then(|| (path.clone(), self.buffer_symbols(buffer)))
+            })
+            .collect();
+
+        let mut locations: Vec<SymbolLocation> = index
+            .locations()
+            .into_iter()
+            .filter(|location| {
+                !open
+                    .iter()
+                    .any(|(path, _)| location.path.as_ref() == Some(path))
+            })
+            .collect();
+        for (path, symbols) in open {
+            locations.extend(
+                symbols
+                    .into_iter()
+                    .map(|symbol| SymbolLocation::new(Some(path.clone()), symbol)),
+            );
+        }
+        Ok(locations)
+    }
+
+    pub(super) fn refresh_symbol_index(&mut self) -> Result<()> {
+        let root = self.project_root()?;
+        let index_path = match &self.symbol_index_path {
+            Some(path) => path.clone(),
+            None => SymbolIndex::default_index_path(&root)?,
+        };
+
+        let mut index = match self.symbol_index.take() {
+            Some(index) if index.root() == root => index,
+            _ => SymbolIndex::load(&index_path, &root),
+        };
+        let changed = index.refresh(&self.languages, self.tab_size);
+        self.symbol_index = Some(index);
+        if changed? {
+            if let Some(index) = &self.symbol_index {
+                index.save(&index_path)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn rebuild_symbol_index(&mut self) -> Result<()> {
+        self.refresh_symbol_index()?;
+        let count = self.symbol_index.as_ref().map_or(0, |i| i.file_count());
+        self.status_message = format!("Indexed symbols in {} files", count);
+        Ok(())
+    }
+
+    pub fn open_symbol_outline(&mut self) {
+        let mut outline = SymbolOutline::new(self.document_symbols());
+        outline.select_line(self.cursor().line);
+        self.symbol_outline = Some(outline);
+        self.symbol_outline_source = Some(self.symbol_outline_source_key());
+    }
+
+    pub fn close_symbol_outline(&mut self) {
+        self.symbol_outline = None;
+        self.symbol_outline_focused = false;
+        self.symbol_outline_source = None;
+    }
+
+    pub(super) fn toggle_symbol_outline(&mut self) -> Result<()> {
+        if self.symbol_outline.is_some() {
+            self.close_symbol_outline();
+        } else {
+            self.open_symbol_outline();
+        }
+        Ok(())
+    }
+
+    pub(super) fn focus_symbol_outline(&mut self) -> Result<()> {
+        if self.symbol_outline.is_none() {
+            self.open_symbol_outline();
+        }
+        self.symbol_outline_focused = true;
+        self.file_tree_focused = false;
+        Ok(())
+    }
+
+    fn symbol_outline_required(&mut self) -> Result<&mut SymbolOutline> {
+        self.symbol_outline
+            .as_mut()
+            .ok_or_else(|| EditorError::InvalidOperation("Symbol outline is not open".to_string()))
+    }
+
+    pub(super) fn symbol_outline_navigate_next(&mut self) -> Result<()> {
+        self.symbol_outline_required()?.select_next();
+        Ok(())
+    }
+
+    pub(super) fn symbol_outline_navigate_previous(&mut self) -> Result<()> {
+        self.symbol_outline_required()?.select_previous();
+        Ok(())
+    }
+
+    pub(super) fn symbol_outline_navigate_first(&mut self) -> Result<()> {
+        self.symbol_outline_required()?.select_first();
+        Ok(())
+    }
+
+    pub(super) fn symbol_outline_navigate_last(&mut self) -> Result<()> {
+        self.symbol_outline_required()?.select_last();
+        Ok(())
+    }
+
+    pub(super) fn symbol_outline_select(&mut self, index: usize) -> Result<()> {
+        if self.symbol_outline_required()?.select_index(index) {
+            Ok(())
+        } else {
+            Err(EditorError::InvalidOperation(format!(
+                "Symbol outline entry {} does not exist",
+                index
+            )))
+        }
+    }
+
+    pub(super) fn symbol_outline_expand(&mut self) -> Result<()> {
+        self.symbol_outline_required()?.expand_selected();
+        Ok(())
+    }
+
+    pub(super) fn symbol_outline_collapse(&mut self) -> Result<()> {
+        self.symbol_outline_required()?.collapse_selected();
+        Ok(())
+    }
+
+    pub(super) fn symbol_outline_activate(&mut self) -> Result<()> {
+        let symbol = self
+            .symbol_outline_required()?
+            .selected_entry()
+            .map(|entry| entry.symbol.clone())
+            .ok_or_else(|| {
+                EditorError::InvalidOperation("No symbol outline entry selected".to_string())
+            })?;
+
+        self.jump_to_symbol(None, symbol.line, symbol.column)?;
+        self.symbol_outline_focused = false;
+        Ok(())
+    }
+
+    pub(super) fn jump_to_symbol(
+        &mut self,
+        path: Option<PathBuf>,
+        line: usize,
+        column: usize,
+    ) -> Result<()> {
+        if let Some(path) = path {
+            if self.buffer().file_path() != Some(&path) {
+                self.open_or_switch_to(path)?;
+            }
+        }
+
+        let line_count = self.buffer().line_count();
+        if line >= line_count {
+            return Err(EditorError::InvalidPosition { line, column });
+        }
+        let column = column.min(self.buffer().line_len(line)?);
+        self.cursors
+            .set_positions(vec![CursorPosition::new(line, column)]);
+        self.selection = None;
+        Ok(())
+    }
+
+    pub(super) fn goto_definition(&mut self) -> Result<()> {
+        if self.has_current_language_server("definitionProvider") {
+            self.lsp_goto_definition()
+        } else {
+            self.goto_symbol_definition()
+        }
+    }
+
+    // Without a language server this resolves the identifier under the
+    // cursor by name: symbols in the current file win over the project index.
+    pub(super) fn goto_symbol_definition(&mut self) -> Result<()> {
+        let Some(word) = self.word_at_cursor() else {
+            self.status_message = "No identifier under cursor".to_string();
+            return Ok(());
+        };
+
+        let line = self.cursor().line;
+        let local = self
+            .document_symbols()
+            .into_iter()
+            .filter(|symbol| symbol.name == word && symbol.kind != SymbolKind::Impl)
+            .find(|symbol| symbol.line != line);
+        if let Some(symbol) = local {
+            return self.jump_to_symbol(None, symbol.line, symbol.column);
+        }
+
+        let current = self.buffer().file_path().cloned();
+        let target = self
+            .project_symbols()?
+            .into_iter()
+            .filter(|location| {
+                location.symbol.name == word && location.symbol.kind != SymbolKind::Impl
+            })
+            .find(|location| location.path != current);
+        match target {
+            Some(location) => {
+                self.jump_to_symbol(location.path, location.symbol.line, location.symbol.column)
+            }
+            None => {
+                self.status_message = format!("No definition found for '{}'", word);
+                Ok(())
+            }
+        }
+    }
+
+    fn word_at_cursor(&self) -> Option<String> {
+        let cursor = *self.cursor();
+        let chars: Vec<char> = self.buffer().rope().line(cursor.line).chars().collect();
+        let column = cursor.column.min(chars.len());
+
+        let mut start = column;
+        while start > 0 && is_word_char(chars[start - 1]) {
+            start -= 1;
+        }
+        let mut end = column;
+        while end < chars.len() && is_word_char(chars[end]) {
+            end += 1;
+        }
+        (start < end).then(|| chars[start..end].iter().collect())
+    }
+
+    fn symbol_outline_source_key(&self) -> SymbolOutlineSource {
+        let buffer = self.buffer();
+        (
+            self.current_buffer_index,
+            buffer.file_path().cloned(),
+            buffer.revision(),
+            buffer.language().map(str::to_string),
+        )
+    }
+
+    // The outline follows the active buffer: symbols are re-extracted only
+    // after an edit or a buffer switch, and the selection tracks the cursor
+    // while the editor has focus.
+    pub(super) fn sync_symbol_outline(&mut self) {
+        if self.symbol_outline.is_none() {
+            return;
+        }
+
+        let source = self.symbol_outline_source_key();
+        if self.symbol_outline_source.as_ref() != Some(&source) {
+            let symbols = self.document_symbols();
+            if let Some(outline) = &mut self.symbol_outline {
+                outline.set_symbols(symbols);
+            }
+            self.symbol_outline_source = Some(source);
+        }
+
+        if !self.symbol_outline_focused {
+            let line = self.cursor().line;
+            if let Some(outline) = &mut self.symbol_outline {
+                outline.select_line(line);
+            }
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::command::CaseMode;
 use crate::cursor::CursorPosition;
 use crate::error::Result;
 use crate::history::{Edit, HistoryEntry};
+use crate::selection::Selection;
 use std::collections::HashMap;
 
 impl EditorState {
@@ -10,12 +11,24 @@ impl EditorState {
         if ch == '\n' {
             return self.new_line();
         }
+        if let Some(block) = self.block_range() {
+            return self.insert_in_block(block, &ch.to_string());
+        }
 
         let content_before = self.buffer().content();
         let cursor_before = self.cursors.positions().to_vec();
         let selection_before = self.selection;
+        self.delete_cursor_selections()?;
+        let language = self.language();
+        let electric_pair = language
+            .bracket_pair(ch)
+            .filter(|(_, _, is_open)| !is_open && language.indent.is_electric(ch));
 
         self.map_cursors_descending(|state, mut pos| {
+            if let Some((open, close, _)) = electric_pair {
+                pos = state.align_closing_bracket(pos, open, close)?;
+            }
+
             let line_len = state.buffer_mut().line_len(pos.line)?;
             if state.overwrite_mode && pos.column < line_len {
                 state.buffer_mut().delete_char(pos.line, pos.column)?;
@@ -57,47 +70,74 @@ impl EditorState {
             return self.new_line();
         }
 
-        let closing_char = match ch {
-            '(' => Some(')'),
-            '[' => Some(']'),
-            '{' => Some('}'),
-            '"' => Some('"'),
-            '\'' => Some('\''),
-            _ => None,
+        let language = self.language().clone();
+        let Some(close) = language
+            .closing_for(ch)
+            .filter(|_| self.block_range().is_none())
+        else {
+            return self.insert_char(ch);
         };
+        self.delete_cursor_selections()?;
 
-        if let Some(close) = closing_char {
-            self.map_cursors_descending(|state, mut pos| {
-                let line_len = state.buffer_mut().line_len(pos.line)?;
-                let should_auto_close = if pos.column < line_len {
-                    let char_idx = state.buffer_mut().char_index(pos.line, pos.column)?;
-                    match state.buffer_mut().char_at(char_idx) {
-                        Some(c) => c.is_whitespace() || c == ')' || c == ']' || c == '}',
-                        None => true,
-                    }
-                } else {
-                    true
-                };
-
-                if should_auto_close {
-                    state.buffer_mut().insert_char(pos.line, pos.column, ch)?;
-                    state
-                        .buffer_mut()
-                        .insert_char(pos.line, pos.column + 1, close)?;
-                    pos.column += 1;
-                } else {
-                    state.buffer_mut().insert_char(pos.line, pos.column, ch)?;
-                    pos.column += 1;
+        self.map_cursors_descending(|state, mut pos| {
+            let line_len = state.buffer_mut().line_len(pos.line)?;
+            let should_auto_close = if pos.column < line_len {
+                let char_idx = state.buffer_mut().char_index(pos.line, pos.column)?;
+                match state.buffer_mut().char_at(char_idx) {
+                    Some(c) => c.is_whitespace() || language.is_closing_bracket(c),
+                    None => true,
                 }
+            } else {
+                true
+            };
 
-                Ok(pos)
-            })
-        } else {
-            self.insert_char(ch)
+            state.buffer_mut().insert_char(pos.line, pos.column, ch)?;
+            if should_auto_close {
+                state
+                    .buffer_mut()
+                    .insert_char(pos.line, pos.column + 1, close)?;
+            }
+            pos.column += 1;
+
+            Ok(pos)
+        })
+    }
+
+    fn align_closing_bracket(
+        &mut self,
+        pos: CursorPosition,
+        open: char,
+        close: char,
+    ) -> Result<CursorPosition> {
+        let line = self.buffer().line(pos.line)?;
+        let before: String = line.chars().take(pos.column).collect();
+        if before.is_empty() || !before.chars().all(|c| c == ' ' || c == '\t') {
+            return Ok(pos);
+        }
+
+        let char_idx = self.buffer().char_index(pos.line, pos.column)?;
+        let Some(open_idx) = self.find_unmatched_bracket(char_idx, open, close, false) else {
+            return Ok(pos);
+        };
+        let (open_line, _) = self.buffer().char_to_line_col(open_idx)?;
+        if open_line == pos.line {
+            return Ok(pos);
         }
+
+        let indent = self.indentation_for_line(open_line)?;
+        self.buffer_mut()
+            .delete_range(pos.line, 0, pos.line, pos.column)?;
+        self.buffer_mut().insert_str(pos.line, 0, &indent)?;
+        Ok(CursorPosition::new(pos.line, indent.chars().count()))
     }
 
     pub(super) fn delete_char(&mut self) -> Result<()> {
+        if let Some(block) = self.block_range() {
+            return self.delete_in_block(block, true);
+        }
+        if self.cursors.positions().len() > 1 && self.cursors.has_selections() {
+            return self.replace_cursor_spans(|_, _| String::new());
+        }
         self.map_cursors_descending(|state, pos| {
             state.buffer_mut().delete_char(pos.line, pos.column)?;
             Ok(pos)
@@ -105,6 +145,12 @@ impl EditorState {
     }
 
     pub(super) fn backspace(&mut self) -> Result<()> {
+        if let Some(block) = self.block_range() {
+            return self.delete_in_block(block, false);
+        }
+        if self.cursors.positions().len() > 1 && self.cursors.has_selections() {
+            return self.replace_cursor_spans(|_, _| String::new());
+        }
         self.map_cursors_descending(|state, mut pos| {
             if pos.column > 0 {
                 pos.column -= 1;
@@ -126,8 +172,25 @@ impl EditorState {
     }
 
     pub(super) fn new_line(&mut self) -> Result<()> {
+        let rules = self.language().indent.clone();
+        self.delete_cursor_selections()?;
+
         self.map_cursors_descending(|state, mut pos| {
-            let indent = state.indentation_for_line(pos.line)?;
+            let line = state.buffer().line(pos.line)?;
+            let line = line.trim_end_matches('\n');
+            let before: String = line.chars().take(pos.column).collect();
+            let after: String = line.chars().skip(pos.column).collect();
+            let base = state.indentation_for_line(pos.line)?;
+
+            let opens = rules.increases_after(&before);
+            let closes = !after.trim().is_empty() && rules.decreases_before(&after);
+            let indent = if opens {
+                base.clone() + &state.indent_unit()
+            } else if closes {
+                state.dedent_indentation(&base)
+            } else {
+                base.clone()
+            };
 
             state.buffer_mut().insert_char(pos.line, pos.column, '\n')?;
             pos.line += 1;
@@ -140,6 +203,12 @@ impl EditorState {
                 pos.column = indent.chars().count();
             }
 
+            if opens && closes {
+                state
+                    .buffer_mut()
+                    .insert_str(pos.line, pos.column, &format!("\n{}", base))?;
+            }
+
             Ok(pos)
         })
     }
@@ -177,31 +246,23 @@ impl EditorState {
     }
 
     pub(super) fn indent_line(&mut self) -> Result<()> {
-        let indent = "    ";
-        let mut lines: Vec<usize> = self.cursors.positions().iter().map(|p| p.line).collect();
-        lines.sort_unstable();
-        lines.dedup();
+        let indent = self.indent_unit();
+        let lines = self.cursor_lines();
 
-        for line in lines {
-            self.buffer_mut().insert_str(line, 0, indent)?;
+        for &line in &lines {
+            self.buffer_mut().insert_str(line, 0, &indent)?;
         }
 
-        self.map_cursors(|state, mut pos| {
-            pos.column += indent.len();
-            let line_len = state.buffer_mut().line_len(pos.line)?;
-            if pos.column > line_len {
-                pos.column = line_len;
-            }
-            Ok(pos)
-        })
+        let width = indent.chars().count() as isize;
+        let shifts = lines.into_iter().map(|line| (line, width)).collect();
+        self.shift_line_starts(&shifts);
+        Ok(())
     }
 
     pub(super) fn dedent_line(&mut self) -> Result<()> {
-        let mut lines: Vec<usize> = self.cursors.positions().iter().map(|p| p.line).collect();
-        lines.sort_unstable();
-        lines.dedup();
-
-        let mut removed_by_line = HashMap::new();
+        let lines = self.cursor_lines();
+        let tab_size = self.tab_size();
+        let mut shifts = HashMap::new();
 
         for line_idx in &lines {
             let line = self.buffer().line(*line_idx)?;
@@ -210,7 +271,7 @@ impl EditorState {
 
             for ch in trimmed.chars() {
                 match ch {
-                    ' ' if remove_count < 4 => remove_count += 1,
+                    ' ' if remove_count < tab_size => remove_count += 1,
                     '\t' => {
                         remove_count = 1;
                         break;
@@ -222,27 +283,12 @@ impl EditorState {
             if remove_count > 0 {
                 self.buffer_mut()
                     .delete_range(*line_idx, 0, *line_idx, remove_count)?;
-                removed_by_line.insert(*line_idx, remove_count);
+                shifts.insert(*line_idx, -(remove_count as isize));
             }
         }
 
-        self.map_cursors(|state, mut pos| {
-            let line_len = state.buffer_mut().line_len(pos.line)?;
-
-            if let Some(amount) = removed_by_line.get(&pos.line) {
-                if pos.column < *amount {
-                    pos.column = 0;
-                } else {
-                    pos.column -= *amount;
-                }
-            }
-
-            if pos.column > line_len {
-                pos.column = line_len;
-            }
-
-            Ok(pos)
-        })
+        self.shift_line_starts(&shifts);
+        Ok(())
     }
 
     pub(super) fn duplicate_line(&mut self) -> Result<()> {
@@ -440,32 +486,26 @@ impl EditorState {
     }
 
     pub(super) fn change_case(&mut self, mode: CaseMode) -> Result<()> {
+        // Selections change on their own; otherwise each cursor's line does.
+        let selections = self.selections();
+        if !selections.is_empty() && !selections.iter().any(Selection::is_block) {
+            let spans = self.cursor_spans();
+            let changed = self.replace_spans(&spans, |_, text| convert_case(text, &mode))?;
+            let selections = changed
+                .into_iter()
+                .map(|(start, end)| Selection::new(start, end))
+                .collect();
+            self.set_selections(selections);
+            return Ok(());
+        }
+
         self.map_cursors(|state, pos| {
             let line_content = state
                 .buffer()
                 .line(pos.line)?
                 .trim_end_matches('\n')
                 .to_string();
-            let transformed = match mode {
-                CaseMode::Upper => line_content.to_uppercase(),
-                CaseMode::Lower => line_content.to_lowercase(),
-                CaseMode::Title => {
-                    let mut result = String::new();
-                    let mut capitalize_next = true;
-                    for ch in line_content.chars() {
-                        if ch.is_whitespace() {
-                            result.push(ch);
-                            capitalize_next = true;
-                        } else if capitalize_next {
-                            result.push_str(&ch.to_uppercase().to_string());
-                            capitalize_next = false;
-                        } else {
-                            result.push_str(&ch.to_lowercase().to_string());
-                        }
-                    }
-                    result
-                }
-            };
+            let transformed = convert_case(&line_content, &mode);
 
             let line_len = state.buffer_mut().line_len(pos.line)?;
             state
@@ -533,3 +573,26 @@ impl EditorState {
         Ok(())
     }
 }
+
+fn convert_case(text: &str, mode: &CaseMode) -> String {
+    match mode {
+        CaseMode::Upper => text.to_uppercase(),
+        CaseMode::Lower => text.to_lowercase(),
+        CaseMode::Title => {
+            let mut result = String::new();
+            let mut capitalize_next = true;
+            for ch in text.chars() {
+                if ch.is_whitespace() {
+                    result.push(ch);
+                    capitalize_next = true;
+                } else if capitalize_next {
+                    result.push_str(&ch.to_uppercase().to_string());
+                    capitalize_next = false;
+                } else {
+                    result.push_str(&ch.to_lowercase().to_string());
+                }
+            }
+            result
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::state::EditorState;
+use crate::cursor::CursorPosition;
 use crate::error::Result;
 use crate::history::Edit;
 
@@ -39,8 +40,7 @@ impl EditorState {
                     .insert_str(position.line, position.column, text)?;
             }
             Edit::Delete { position, text } => {
-                let end_line = position.line;
-                let end_column = position.column + text.chars().count();
+                let (end_line, end_column) = text_end(*position, text);
                 self.buffer_mut().delete_range(
                     position.line,
                     position.column,
@@ -53,8 +53,7 @@ impl EditorState {
                 old_text,
                 new_text,
             } => {
-                let end_line = position.line;
-                let end_column = position.column + old_text.chars().count();
+                let (end_line, end_column) = text_end(*position, old_text);
                 self.buffer_mut().delete_range(
                     position.line,
                     position.column,
@@ -92,3 +91,15 @@ impl EditorState {
         self.history.redo_stack_len()
     }
 }
+
+// Where `text` ends when it starts at `position`; edits recorded for whole
+// buffers or pasted blocks span several lines.
+fn text_end(position: CursorPosition, text: &str) -> (usize, usize) {
+    let lines = text.split('\n').count() - 1;
+    let last = text.rsplit('\n').next().unwrap_or_default().chars().count();
+    if lines == 0 {
+        (position.line, position.column + last)
+    } else {
+        (position.line + lines, last)
+    }
+}
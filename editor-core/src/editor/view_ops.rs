@@ -5,16 +5,22 @@ pub struct VirtualViewport {
     pub start_line: usize,
     pub end_line: usize,
     pub visible_lines: Vec<String>,
+    pub line_numbers: Vec<usize>,
 }
 
 impl EditorState {
     pub fn get_virtual_viewport(&self, viewport_height: usize) -> VirtualViewport {
         let total_lines = self.buffer().line_count();
-        let start_line = self.viewport_top;
-        let end_line = (start_line + viewport_height).min(total_lines);
-
-        let mut visible_lines = Vec::with_capacity(viewport_height);
-        for line_idx in start_line..end_line {
+        let folds = self.buffer().folds();
+        let line_numbers = folds.visible_lines(self.viewport_top, total_lines, viewport_height);
+        let start_line = line_numbers
+            .first()
+            .copied()
+            .unwrap_or(self.viewport_top.min(total_lines));
+        let end_line = line_numbers.last().map_or(start_line, |line| line + 1);
+
+        let mut visible_lines = Vec::with_capacity(line_numbers.len());
+        for &line_idx in &line_numbers {
             if let Ok(line) = self.buffer().line(line_idx) {
                 visible_lines.push(line);
             }
@@ -24,19 +30,21 @@ impl EditorState {
             start_line,
             end_line,
             visible_lines,
+            line_numbers,
         }
     }
 
     pub fn adjust_viewport_to_cursor(&mut self, viewport_height: usize) {
         let cursor_line = self.cursor().line;
         let offset = self.scroll_offset;
-
-        if cursor_line < self.viewport_top + offset {
-            self.viewport_top = cursor_line.saturating_sub(offset);
-        } else if cursor_line >= self.viewport_top + viewport_height.saturating_sub(offset) {
-            self.viewport_top = cursor_line
-                .saturating_sub(viewport_height.saturating_sub(offset))
-                .saturating_add(1);
+        let folds = self.buffer().folds();
+        let rows_above = folds.count_visible(self.viewport_top, cursor_line);
+
+        if cursor_line < self.viewport_top || rows_above < offset {
+            self.viewport_top = folds.visible_line_above(cursor_line, offset);
+        } else if rows_above >= viewport_height.saturating_sub(offset) {
+            let rows = viewport_height.saturating_sub(offset).saturating_sub(1);
+            self.viewport_top = folds.visible_line_above(cursor_line, rows);
         }
     }
 
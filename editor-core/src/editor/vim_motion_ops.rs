@@ -0,0 +1,568 @@
+use super::state::EditorState;
+use super::state_utils::is_word_char;
+use crate::cursor::CursorPosition;
+use crate::vim::{FindState, MotionKind, VimMotion, VimOperator, VimRange, VimTextObject};
+use std::ops::Range;
+
+const BRACKETS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CharClass {
+    Newline,
+    Blank,
+    Word,
+    Punctuation,
+}
+
+fn char_class(ch: char, big: bool) -> CharClass {
+    if ch == '\n' {
+        CharClass::Newline
+    } else if ch.is_whitespace() {
+        CharClass::Blank
+    } else if big || is_word_char(ch) {
+        CharClass::Word
+    } else {
+        CharClass::Punctuation
+    }
+}
+
+impl EditorState {
+    pub(super) fn vim_index(&self, position: CursorPosition) -> usize {
+        self.buffer()
+            .char_index(position.line, position.column)
+            .unwrap_or_else(|_| self.buffer().len_chars())
+    }
+
+    pub(super) fn vim_position(&self, index: usize) -> CursorPosition {
+        let index = index.min(self.buffer().len_chars());
+        let (line, column) = self.buffer().char_to_line_col(index).unwrap_or((0, 0));
+        CursorPosition::new(line, column)
+    }
+
+    // The last line holding text; a file's trailing newline leaves an empty
+    // line after it that vim doesn't count.
+    pub(super) fn vim_last_line(&self) -> usize {
+        let count = self.buffer().line_count();
+        if count > 1 && self.vim_line_len(count - 1) == 0 {
+            count - 2
+        } else {
+            count.saturating_sub(1)
+        }
+    }
+
+    pub(super) fn vim_line_len(&self, line: usize) -> usize {
+        self.buffer().line_len(line).unwrap_or_default()
+    }
+
+    pub(super) fn vim_line_text(&self, line: usize) -> String {
+        self.buffer()
+            .line(line)
+            .map(|text| text.trim_end_matches(['\n', '\r']).to_string())
+            .unwrap_or_default()
+    }
+
+    pub(super) fn vim_first_non_blank(&self, line: usize) -> CursorPosition {
+        let text = self.vim_line_text(line);
+        let column = text
+            .chars()
+            .position(|ch| !ch.is_whitespace())
+            .unwrap_or_else(|| text.chars().count().saturating_sub(1));
+        CursorPosition::new(line, column)
+    }
+
+    fn vim_is_blank_line(&self, line: usize) -> bool {
+        self.vim_line_text(line).trim().is_empty()
+    }
+
+    fn vim_class_at(&self, index: usize, big: bool) -> Option<CharClass> {
+        self.buffer().char_at(index).map(|ch| char_class(ch, big))
+    }
+
+    pub(super) fn vim_motion_target(
+        &mut self,
+        from: CursorPosition,
+        motion: VimMotion,
+        count: Option<usize>,
+    ) -> Option<CursorPosition> {
+        let times = count.unwrap_or(1);
+        let last_line = self.vim_last_line();
+        let line_len = self.vim_line_len(from.line);
+        let target = match motion {
+            VimMotion::Left => {
+                if from.column == 0 {
+                    return None;
+                }
+                CursorPosition::new(from.line, from.column.saturating_sub(times))
+            }
+            VimMotion::Right => {
+                if from.column >= line_len {
+                    return None;
+                }
+                CursorPosition::new(from.line, (from.column + times).min(line_len))
+            }
+            VimMotion::Up => {
+                if from.line == 0 {
+                    return None;
+                }
+                let line = from.line.saturating_sub(times);
+                CursorPosition::new(line, from.column.min(self.vim_line_len(line)))
+            }
+            VimMotion::Down => {
+                if from.line >= last_line {
+                    return None;
+                }
+                let line = (from.line + times).min(last_line);
+                CursorPosition::new(line, from.column.min(self.vim_line_len(line)))
+            }
+            VimMotion::WordForward { big } => {
+                let index = (0..times).fold(self.vim_index(from), |index, _| {
+                    self.word_forward(index, big)
+                });
+                self.vim_position(index)
+            }
+            VimMotion::WordBackward { big } => {
+                let index = (0..times).fold(self.vim_index(from), |index, _| {
+                    self.word_backward(index, big)
+                });
+                self.vim_position(index)
+            }
+            VimMotion::WordEnd { big } => {
+                let index =
+                    (0..times).fold(self.vim_index(from), |index, _| self.word_end(index, big));
+                self.vim_position(index)
+            }
+            VimMotion::LineStart => CursorPosition::new(from.line, 0),
+            VimMotion::FirstNonBlank => self.vim_first_non_blank(from.line),
+            VimMotion::LineEnd => {
+                let line = (from.line + times - 1).min(last_line);
+                CursorPosition::new(line, self.vim_line_len(line).saturating_sub(1))
+            }
+            VimMotion::FileStart => {
+                self.vim_first_non_blank(count.map_or(0, |line| line - 1).min(last_line))
+            }
+            VimMotion::FileEnd => {
+                self.vim_first_non_blank(count.map_or(last_line, |line| line - 1).min(last_line))
+            }
+            VimMotion::FindChar {
+                target,
+                forward,
+                till,
+            } => {
+                let find = FindState {
+                    target,
+                    forward,
+                    till,
+                };
+                if let Some(vim) = self.vim.as_mut() {
+                    vim.last_find = Some(find);
+                }
+                self.find_in_line(from, find, times, false)?
+            }
+            VimMotion::RepeatFind { reverse } => {
+                let mut find = self.vim.as_ref()?.last_find?;
+                find.forward ^= reverse;
+                self.find_in_line(from, find, times, true)?
+            }
+            VimMotion::MatchingBracket => self.matching_bracket(from)?,
+            VimMotion::ParagraphForward => {
+                let mut line = from.line;
+                for _ in 0..times {
+                    while line <= last_line && self.vim_is_blank_line(line) {
+                        line += 1;
+                    }
+                    while line <= last_line && !self.vim_is_blank_line(line) {
+                        line += 1;
+                    }
+                }
+                if line > last_line {
+                    CursorPosition::new(last_line, self.vim_line_len(last_line))
+                } else {
+                    CursorPosition::new(line, 0)
+                }
+            }
+            VimMotion::ParagraphBackward => {
+                let mut line = from.line;
+                for _ in 0..times {
+                    while line > 0 && self.vim_is_blank_line(line) {
+                        line -= 1;
+                    }
+                    while line > 0 && !self.vim_is_blank_line(line) {
+                        line -= 1;
+                    }
+                }
+                CursorPosition::new(line, 0)
+            }
+        };
+        Some(target)
+    }
+
+    // The span an operator covers when given `motion`, following vim's rules
+    // for inclusive, exclusive and linewise motions.
+    pub(super) fn vim_motion_range(
+        &mut self,
+        from: CursorPosition,
+        motion: VimMotion,
+        count: Option<usize>,
+        operator: VimOperator,
+    ) -> Option<VimRange> {
+        let from_index = self.vim_index(from);
+        // `cw` on a word changes to the end of it, like `ce`.
+        if let VimMotion::WordForward { big } = motion {
+            let on_word = self
+                .vim_class_at(from_index, big)
+                .is_some_and(|class| matches!(class, CharClass::Word | CharClass::Punctuation));
+            if operator == VimOperator::Change && on_word {
+                let end = self.change_word_end(from_index, big, count.unwrap_or(1));
+                return Some(VimRange::Chars(from_index, end + 1));
+            }
+        }
+
+        let target = self.vim_motion_target(from, motion, count)?;
+        match motion.kind() {
+            MotionKind::Linewise => Some(VimRange::Lines(
+                from.line.min(target.line),
+                from.line.max(target.line),
+            )),
+            MotionKind::Inclusive => {
+                let target_index = self.vim_index(target);
+                let (start, end) = (from_index.min(target_index), from_index.max(target_index));
+                let end = if self.buffer().char_at(end).is_some_and(|ch| ch != '\n') {
+                    end + 1
+                } else {
+                    end
+                };
+                Some(VimRange::Chars(start, end))
+            }
+            MotionKind::Exclusive => {
+                let target_index = self.vim_index(target);
+                let (start, mut end) = (from_index.min(target_index), from_index.max(target_index));
+                // An exclusive motion that lands at the start of a later line
+                // stops at the end of the line before it instead.
+                let end_position = self.vim_position(end);
+                if end > start && end_position.column == 0 && end_position.line > from.line {
+                    end = self.vim_index(CursorPosition::new(
+                        end_position.line - 1,
+                        self.vim_line_len(end_position.line - 1),
+                    ));
+                }
+                Some(VimRange::Chars(start, end))
+            }
+        }
+    }
+
+    fn word_forward(&self, index: usize, big: bool) -> usize {
+        let len = self.buffer().len_chars();
+        let mut index = index;
+        if let Some(class) = self
+            .vim_class_at(index, big)
+            .filter(|class| matches!(class, CharClass::Word | CharClass::Punctuation))
+        {
+            while self.vim_class_at(index, big) == Some(class) {
+                index += 1;
+            }
+        }
+        while let Some(ch) = self.buffer().char_at(index) {
+            if !ch.is_whitespace() {
+                break;
+            }
+            // An empty line counts as a word of its own.
+            if ch == '\n' && self.buffer().char_at(index + 1) == Some('\n') {
+                return index + 1;
+            }
+            index += 1;
+        }
+        index.min(len)
+    }
+
+    fn word_backward(&self, index: usize, big: bool) -> usize {
+        let mut index = index;
+        loop {
+            if index == 0 {
+                return 0;
+            }
+            index -= 1;
+            let ch = self.buffer().char_at(index).unwrap_or(' ');
+            if ch == '\n' && (index == 0 || self.buffer().char_at(index - 1) == Some('\n')) {
+                return index;
+            }
+            if !ch.is_whitespace() {
+                break;
+            }
+        }
+        let class = self.vim_class_at(index, big);
+        while index > 0 && self.vim_class_at(index - 1, big) == class {
+            index -= 1;
+        }
+        index
+    }
+
+    fn word_end(&self, index: usize, big: bool) -> usize {
+        let len = self.buffer().len_chars();
+        let mut index = index + 1;
+        while self
+            .buffer()
+            .char_at(index)
+            .is_some_and(|ch| ch.is_whitespace())
+        {
+            index += 1;
+        }
+        if index >= len {
+            return len.saturating_sub(1);
+        }
+        let class = self.vim_class_at(index, big);
+        while self.vim_class_at(index + 1, big) == class {
+            index += 1;
+        }
+        index
+    }
+
+    fn change_word_end(&self, index: usize, big: bool, count: usize) -> usize {
+        let mut index = index;
+        for step in 0..count {
+            if step > 0 {
+                index += 1;
+                while self
+                    .buffer()
+                    .char_at(index)
+                    .is_some_and(|ch| ch.is_whitespace())
+                {
+                    index += 1;
+                }
+            }
+            let class = self.vim_class_at(index, big);
+            while self.vim_class_at(index + 1, big) == class {
+                index += 1;
+            }
+        }
+        index.min(self.buffer().len_chars().saturating_sub(1))
+    }
+
+    fn find_in_line(
+        &self,
+        from: CursorPosition,
+        find: FindState,
+        count: usize,
+        repeat: bool,
+    ) -> Option<CursorPosition> {
+        let chars: Vec<char> = self.vim_line_text(from.line).chars().collect();
+        // Repeating a `t` search skips the match right next to the cursor.
+        let skip = usize::from(repeat && find.till);
+        let column = if find.forward {
+            (from.column + 1 + skip..chars.len())
+                .filter(|&column| chars[column] == find.target)
+                .nth(count - 1)?
+        } else {
+            (0..from.column.saturating_sub(skip))
+                .rev()
+                .filter(|&column| chars[column] == find.target)
+                .nth(count - 1)?
+        };
+        let column = match (find.till, find.forward) {
+            (true, true) => column - 1,
+            (true, false) => column + 1,
+            (false, _) => column,
+        };
+        Some(CursorPosition::new(from.line, column))
+    }
+
+    fn matching_bracket(&self, from: CursorPosition) -> Option<CursorPosition> {
+        let chars: Vec<char> = self.vim_line_text(from.line).chars().collect();
+        let column = (from.column..chars.len()).find(|&column| {
+            BRACKETS
+                .iter()
+                .any(|(open, close)| chars[column] == *open || chars[column] == *close)
+        })?;
+        let index = self.vim_index(CursorPosition::new(from.line, column));
+        let ch = chars[column];
+        let matched = BRACKETS.iter().find_map(|&(open, close)| {
+            if ch == open {
+                self.find_unmatched_bracket(index + 1, open, close, true)
+            } else if ch == close {
+                self.find_unmatched_bracket(index, open, close, false)
+            } else {
+                None
+            }
+        })?;
+        Some(self.vim_position(matched))
+    }
+
+    pub(super) fn vim_object_range(
+        &self,
+        from: CursorPosition,
+        object: VimTextObject,
+        around: bool,
+        count: usize,
+    ) -> Option<VimRange> {
+        match object {
+            VimTextObject::Word { big } => self.word_object(from, big, around),
+            VimTextObject::Quote(quote) => self.quote_object(from, quote, around),
+            VimTextObject::Bracket { open, close } => {
+                self.bracket_object(from, open, close, around, count)
+            }
+            VimTextObject::Paragraph => Some(self.paragraph_object(from, around)),
+            VimTextObject::AnyQuote => {
+                let index = self.vim_index(from);
+                let quotes = self.quote_ranges(index..index);
+                pick_pair(quotes, around, count)
+            }
+            VimTextObject::AnyBracket => {
+                let index = self.vim_index(from);
+                let brackets = self.bracket_ranges(index..index);
+                pick_pair(brackets, around, count)
+            }
+            VimTextObject::Line if around => Some(VimRange::Lines(from.line, from.line)),
+            VimTextObject::Line => {
+                let index = self.vim_index(from);
+                let [inner, _] = self.line_ranges(index..index);
+                Some(VimRange::Chars(inner.start, inner.end))
+            }
+            VimTextObject::Entire if around => Some(VimRange::Lines(0, self.vim_last_line())),
+            VimTextObject::Entire => {
+                let range = self.entire_range();
+                Some(VimRange::Chars(range.start, range.end))
+            }
+        }
+    }
+
+    fn word_object(&self, from: CursorPosition, big: bool, around: bool) -> Option<VimRange> {
+        let index = self.vim_index(from);
+        let class = self.vim_class_at(index, big)?;
+        if class == CharClass::Newline {
+            return None;
+        }
+        let mut start = index;
+        while start > 0 && self.vim_class_at(start - 1, big) == Some(class) {
+            start -= 1;
+        }
+        let mut end = index + 1;
+        while self.vim_class_at(end, big) == Some(class) {
+            end += 1;
+        }
+        if !around {
+            return Some(VimRange::Chars(start, end));
+        }
+
+        if class == CharClass::Blank {
+            // Around blanks takes the word that follows too.
+            if let Some(next) = self
+                .vim_class_at(end, big)
+                .filter(|next| *next != CharClass::Newline)
+            {
+                while self.vim_class_at(end, big) == Some(next) {
+                    end += 1;
+                }
+            }
+        } else if self.vim_class_at(end, big) == Some(CharClass::Blank) {
+            while self.vim_class_at(end, big) == Some(CharClass::Blank) {
+                end += 1;
+            }
+        } else {
+            while start > 0 && self.vim_class_at(start - 1, big) == Some(CharClass::Blank) {
+                start -= 1;
+            }
+        }
+        Some(VimRange::Chars(start, end))
+    }
+
+    fn quote_object(&self, from: CursorPosition, quote: char, around: bool) -> Option<VimRange> {
+        let chars: Vec<char> = self.vim_line_text(from.line).chars().collect();
+        let quotes: Vec<usize> = (0..chars.len())
+            .filter(|&column| chars[column] == quote && (column == 0 || chars[column - 1] != '\\'))
+            .collect();
+        let (open, close) = quotes
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .find(|&(open, close)| open <= from.column && from.column <= close)
+            .or_else(|| {
+                quotes
+                    .chunks_exact(2)
+                    .map(|pair| (pair[0], pair[1]))
+                    .find(|&(open, _)| open > from.column)
+            })?;
+        let line_start = self.vim_index(CursorPosition::new(from.line, 0));
+        if !around {
+            return Some(VimRange::Chars(line_start + open + 1, line_start + close));
+        }
+        let mut start = open;
+        let mut end = close + 1;
+        if chars.get(end).is_some_and(|ch| ch.is_whitespace()) {
+            while chars.get(end).is_some_and(|ch| ch.is_whitespace()) {
+                end += 1;
+            }
+        } else {
+            while start > 0 && chars[start - 1].is_whitespace() {
+                start -= 1;
+            }
+        }
+        Some(VimRange::Chars(line_start + start, line_start + end))
+    }
+
+    fn bracket_object(
+        &self,
+        from: CursorPosition,
+        open: char,
+        close: char,
+        around: bool,
+        count: usize,
+    ) -> Option<VimRange> {
+        let index = self.vim_index(from);
+        let mut open_index = match self.buffer().char_at(index) {
+            Some(ch) if ch == open => index,
+            _ => self.find_unmatched_bracket(index, open, close, false)?,
+        };
+        for _ in 1..count {
+            open_index = self.find_unmatched_bracket(open_index, open, close, false)?;
+        }
+        let close_index = self.find_unmatched_bracket(open_index + 1, open, close, true)?;
+        if around {
+            return Some(VimRange::Chars(open_index, close_index + 1));
+        }
+
+        // A block whose brackets sit on their own lines keeps them there.
+        let mut start = open_index + 1;
+        let mut end = close_index;
+        if self.buffer().char_at(start) == Some('\n') {
+            start += 1;
+            let close_position = self.vim_position(close_index);
+            let before_close: String = self
+                .vim_line_text(close_position.line)
+                .chars()
+                .take(close_position.column)
+                .collect();
+            if before_close.trim().is_empty() {
+                end = self.vim_index(CursorPosition::new(close_position.line, 0));
+            }
+        }
+        Some(VimRange::Chars(start, end.max(start)))
+    }
+
+    fn paragraph_object(&self, from: CursorPosition, around: bool) -> VimRange {
+        let last_line = self.vim_last_line();
+        let blank = self.vim_is_blank_line(from.line);
+        let mut start = from.line;
+        while start > 0 && self.vim_is_blank_line(start - 1) == blank {
+            start -= 1;
+        }
+        let mut end = from.line;
+        while end < last_line && self.vim_is_blank_line(end + 1) == blank {
+            end += 1;
+        }
+        if around {
+            while end < last_line && self.vim_is_blank_line(end + 1) != blank {
+                end += 1;
+            }
+        }
+        VimRange::Lines(start, end)
+    }
+}
+
+// The `count`th of the nested ranges, innermost first.
+fn pick_pair(
+    ranges: Vec<(Range<usize>, Range<usize>)>,
+    around: bool,
+    count: usize,
+) -> Option<VimRange> {
+    let (inner, outer) = ranges.into_iter().nth(count.max(1) - 1)?;
+    let range = if around { outer } else { inner };
+    Some(VimRange::Chars(range.start, range.end))
+}
@@ -0,0 +1,1143 @@
+use super::mode::EditorMode;
+use super::state::EditorState;
+use crate::command::Command;
+use crate::cursor::CursorPosition;
+use crate::error::{EditorError, Result};
+use crate::keymap::KeyPress;
+use crate::selection::Selection;
+use crate::vim::{
+    is_clipboard_register, parse_ex_command, parse_vim_keys, vim_key_char, ExCommand, ExContext,
+    InsertPosition, SortOptions, VimAction, VimChange, VimCommand, VimOperator, VimParse, VimRange,
+    VimRegister, VimState, VimTarget, VimTextObject, VisualKind, BACKSPACE, ENTER, ESCAPE,
+    UNNAMED_REGISTER,
+};
+use regex::RegexBuilder;
+
+fn visual_kind(mode: EditorMode) -> Option<VisualKind> {
+    match mode {
+        EditorMode::Visual => Some(VisualKind::Char),
+        EditorMode::VisualLine => Some(VisualKind::Line),
+        EditorMode::VisualBlock => Some(VisualKind::Block),
+        EditorMode::Normal | EditorMode::Insert => None,
+    }
+}
+
+fn visual_mode(kind: VisualKind) -> EditorMode {
+    match kind {
+        VisualKind::Char => EditorMode::Visual,
+        VisualKind::Line => EditorMode::VisualLine,
+        VisualKind::Block => EditorMode::VisualBlock,
+    }
+}
+
+fn ordered(a: CursorPosition, b: CursorPosition) -> (CursorPosition, CursorPosition) {
+    if (a.line, a.column) <= (b.line, b.column) {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+fn leading_number(line: &str) -> Option<i64> {
+    let start = line.find(|c: char| c.is_ascii_digit())?;
+    let digits: String = line[start..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    let value: i64 = digits.parse().ok()?;
+    Some(if line[..start].ends_with('-') {
+        -value
+    } else {
+        value
+    })
+}
+
+fn transform_case(text: &str, operator: VimOperator) -> String {
+    match operator {
+        VimOperator::Lowercase => text.to_lowercase(),
+        VimOperator::Uppercase => text.to_uppercase(),
+        _ => text
+            .chars()
+            .flat_map(|ch| {
+                if ch.is_uppercase() {
+                    ch.to_lowercase().collect::<Vec<_>>()
+                } else {
+                    ch.to_uppercase().collect::<Vec<_>>()
+                }
+            })
+            .collect(),
+    }
+}
+
+impl EditorState {
+    pub fn is_vim_enabled(&self) -> bool {
+        self.vim.is_some()
+    }
+
+    pub fn set_vim_enabled(&mut self, enabled: bool) {
+        if enabled == self.is_vim_enabled() {
+            return;
+        }
+        self.vim = enabled.then(VimState::default);
+        self.mode = EditorMode::Normal;
+        self.selection = None;
+    }
+
+    pub fn vim_mode_indicator(&self) -> Option<&'static str> {
+        self.vim.as_ref()?;
+        Some(match self.mode {
+            EditorMode::Normal => "NORMAL",
+            EditorMode::Insert => "INSERT",
+            EditorMode::Visual => "VISUAL",
+            EditorMode::VisualLine => "V-LINE",
+            EditorMode::VisualBlock => "V-BLOCK",
+        })
+    }
+
+    pub fn vim_command_line(&self) -> Option<&str> {
+        self.vim.as_ref()?.command_line.as_deref()
+    }
+
+    pub fn vim_pending_keys(&self) -> Option<String> {
+        let vim = self.vim.as_ref()?;
+        (!vim.pending.is_empty()).then(|| vim.pending.iter().collect())
+    }
+
+    pub fn vim_register(&self, name: char) -> Option<&VimRegister> {
+        self.vim.as_ref()?.registers.get(name)
+    }
+
+    // Frontends route a key to `Command::VimKey` when this says the vim
+    // engine takes it; everything else goes through the keymap as usual.
+    pub fn vim_wants_key(&self, press: &KeyPress) -> bool {
+        match vim_key_char(press) {
+            Some(key) => self.vim_wants_text() || (self.is_vim_enabled() && key == ESCAPE),
+            None => false,
+        }
+    }
+
+    // Outside insert mode, and while the command line is open, typed
+    // characters are vim keys rather than text.
+    pub fn vim_wants_text(&self) -> bool {
+        self.vim
+            .as_ref()
+            .is_some_and(|vim| vim.command_line.is_some() || self.mode != EditorMode::Insert)
+    }
+
+    pub fn take_quit_request(&mut self) -> bool {
+        std::mem::take(&mut self.quit_requested)
+    }
+
+    pub(super) fn toggle_vim_mode(&mut self) -> Result<()> {
+        let enabled = !self.is_vim_enabled();
+        self.set_vim_enabled(enabled);
+        self.status_message = if enabled {
+            "Vim mode on".to_string()
+        } else {
+            "Vim mode off".to_string()
+        };
+        Ok(())
+    }
+
+    pub(super) fn vim_key(&mut self, press: KeyPress) -> Result<()> {
+        let Some(key) = vim_key_char(&press) else {
+            return Ok(());
+        };
+        let Some(vim) = self.vim.as_mut() else {
+            return Ok(());
+        };
+        if vim.command_line.is_some() {
+            return self.vim_command_line_key(key);
+        }
+        if self.mode == EditorMode::Insert {
+            return if key == ESCAPE {
+                self.vim_leave_insert()
+            } else {
+                Ok(())
+            };
+        }
+
+        vim.pending.push(key);
+        let visual = visual_kind(self.mode).is_some();
+        match parse_vim_keys(&vim.pending, visual) {
+            VimParse::Pending => Ok(()),
+            VimParse::Invalid => {
+                vim.pending.clear();
+                Ok(())
+            }
+            VimParse::Complete(command) => {
+                vim.pending.clear();
+                self.run_vim_command(command)
+            }
+        }
+    }
+
+    // Remembers what is typed in insert mode so `.` can replay it.
+    pub(super) fn record_vim_insert(&mut self, command: &Command) {
+        if self.mode != EditorMode::Insert
+            || !command.is_editing_command()
+            || matches!(command, Command::Undo | Command::Redo)
+        {
+            return;
+        }
+        if let Some(vim) = self.vim.as_mut().filter(|vim| !vim.replaying) {
+            if let Some(recording) = vim.recording.as_mut() {
+                recording.inserted.push(command.clone());
+            }
+        }
+    }
+
+    // Keeps the cursor on a character outside insert mode and mirrors the
+    // visual selection into the editor's own selection so it is drawn.
+    pub(super) fn sync_vim(&mut self) {
+        let Some(vim) = &self.vim else {
+            return;
+        };
+        if self.mode == EditorMode::Insert {
+            return;
+        }
+        let anchor = vim.visual_anchor;
+        let last_line = self.vim_last_line();
+        let positions = self
+            .cursors
+            .positions()
+            .iter()
+            .map(|position| {
+                let line = position.line.min(last_line);
+                let column = position
+                    .column
+                    .min(self.vim_line_len(line).saturating_sub(1));
+                CursorPosition::new(line, column)
+            })
+            .collect();
+        self.cursors.set_positions(positions);
+
+        let cursor = *self.cursors.primary();
+        let (start, end) = ordered(anchor, cursor);
+        self.selection = match self.mode {
+            EditorMode::Visual => {
+                let end_column = (end.column + 1).min(self.vim_line_len(end.line).max(1));
+                Some(Selection::new(
+                    start,
+                    CursorPosition::new(end.line, end_column),
+                ))
+            }
+            EditorMode::VisualLine => Some(Selection::new(
+                CursorPosition::new(start.line, 0),
+                CursorPosition::new(end.line, self.vim_line_len(end.line)),
+            )),
+            EditorMode::VisualBlock => Some(Selection::new_block(
+                CursorPosition::new(start.line, anchor.column.min(cursor.column)),
+                CursorPosition::new(end.line, anchor.column.max(cursor.column) + 1),
+            )),
+            EditorMode::Normal | EditorMode::Insert => return,
+        };
+    }
+
+    fn run_vim_command(&mut self, command: VimCommand) -> Result<()> {
+        let count = command.count();
+        let history_start = self.history.undo_stack_len();
+        match command.action {
+            VimAction::Move(motion) => {
+                let from = *self.cursors.primary();
+                if let Some(target) = self.vim_motion_target(from, motion, command.count) {
+                    self.cursors.reset_to(target);
+                }
+            }
+            VimAction::Operate { operator, target } => {
+                self.vim_operate(operator, target, command)?
+            }
+            VimAction::SelectObject { object, around } => {
+                self.vim_select_object(object, around, count)
+            }
+            VimAction::Insert(position) => self.vim_insert(position, count)?,
+            VimAction::Paste { before } => self.vim_paste(before, command.register, count)?,
+            VimAction::ReplaceChar(ch) => self.vim_replace_char(ch, count)?,
+            VimAction::ToggleCaseChar => self.vim_toggle_case_char(count)?,
+            VimAction::JoinLines => self.vim_join_lines(count)?,
+            VimAction::Undo => self.vim_step_history(count, Self::undo)?,
+            VimAction::Redo => self.vim_step_history(count, Self::redo)?,
+            VimAction::Repeat => return self.vim_repeat(command.count),
+            VimAction::Visual(kind) => self.vim_visual(kind),
+            VimAction::SwapVisualEnds => {
+                let cursor = *self.cursors.primary();
+                if let Some(vim) = self.vim.as_mut() {
+                    let anchor = std::mem::replace(&mut vim.visual_anchor, cursor);
+                    self.cursors.reset_to(anchor);
+                }
+            }
+            VimAction::CommandLine(prefix) => self.vim_open_command_line(prefix),
+            VimAction::SearchNext { reverse } => {
+                for _ in 0..count {
+                    if reverse {
+                        self.previous_match()?;
+                    } else {
+                        self.next_match()?;
+                    }
+                }
+                self.vim_settle_search();
+            }
+            VimAction::Escape => self.vim_exit_visual(),
+        }
+
+        let entered_insert = self.mode == EditorMode::Insert;
+        if let Some(vim) = self.vim.as_mut() {
+            if entered_insert {
+                vim.insert_history_start = Some(history_start);
+            }
+            if command.is_change() {
+                let change = VimChange {
+                    command,
+                    inserted: Vec::new(),
+                };
+                if entered_insert {
+                    vim.recording = Some(change);
+                } else {
+                    vim.last_change = Some(change);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Runs `f` as a single undo step.
+    fn vim_edit(&mut self, f: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        let content_before = self.buffer().content();
+        let cursor_before = self.cursors.positions().to_vec();
+        let selection_before = self.selection;
+        let result = f(self);
+        self.push_content_history(content_before, cursor_before, selection_before);
+        result
+    }
+
+    fn vim_enter_insert(&mut self, count: usize) {
+        self.mode = EditorMode::Insert;
+        self.selection = None;
+        if let Some(vim) = self.vim.as_mut() {
+            vim.insert_count = count.max(1);
+        }
+    }
+
+    fn vim_leave_insert(&mut self) -> Result<()> {
+        let Some(vim) = self.vim.as_mut() else {
+            return Ok(());
+        };
+        let count = std::mem::replace(&mut vim.insert_count, 1);
+        let history_start = vim.insert_history_start.take();
+        let recording = vim.recording.take();
+
+        // A count on the insert types the text that many times.
+        if let Some(change) = &recording {
+            let reopen = matches!(
+                change.command.action,
+                VimAction::Insert(InsertPosition::LineBelow | InsertPosition::LineAbove)
+            );
+            vim.replaying = true;
+            let result = (1..count).try_for_each(|_| {
+                if reopen {
+                    self.new_line()?;
+                }
+                change
+                    .inserted
+                    .iter()
+                    .try_for_each(|command| self.execute_command(command.clone()))
+            });
+            if let Some(vim) = self.vim.as_mut() {
+                vim.replaying = false;
+            }
+            result?;
+        }
+
+        if let Some(start) = history_start {
+            self.history.merge_from(start);
+        }
+        if let Some(vim) = self.vim.as_mut() {
+            if recording.is_some() {
+                vim.last_change = recording;
+            }
+        }
+        self.mode = EditorMode::Normal;
+        let primary = *self.cursors.primary();
+        self.cursors.reset_to(CursorPosition::new(
+            primary.line,
+            primary.column.saturating_sub(1),
+        ));
+        Ok(())
+    }
+
+    fn vim_repeat(&mut self, count: Option<usize>) -> Result<()> {
+        let Some(mut change) = self.vim.as_ref().and_then(|vim| vim.last_change.clone()) else {
+            return Ok(());
+        };
+        if count.is_some() {
+            change.command.count = count;
+        }
+        if let Some(vim) = self.vim.as_mut() {
+            vim.replaying = true;
+        }
+        let result = self.vim_replay(&change);
+        if let Some(vim) = self.vim.as_mut() {
+            vim.replaying = false;
+        }
+        result
+    }
+
+    fn vim_replay(&mut self, change: &VimChange) -> Result<()> {
+        self.run_vim_command(change.command)?;
+        if self.mode != EditorMode::Insert {
+            return Ok(());
+        }
+        if let Some(recording) = self.vim.as_mut().and_then(|vim| vim.recording.as_mut()) {
+            recording.inserted = change.inserted.clone();
+        }
+        for command in &change.inserted {
+            self.execute_command(command.clone())?;
+        }
+        self.vim_leave_insert()
+    }
+
+    fn vim_step_history(&mut self, count: usize, step: fn(&mut Self) -> Result<()>) -> Result<()> {
+        for done in 0..count {
+            if let Err(error) = step(self) {
+                if done == 0 {
+                    return Err(error);
+                }
+                break;
+            }
+        }
+        self.selection = None;
+        Ok(())
+    }
+
+    fn vim_visual(&mut self, kind: VisualKind) {
+        let mode = visual_mode(kind);
+        if self.mode == mode {
+            self.vim_exit_visual();
+            return;
+        }
+        if visual_kind(self.mode).is_none() {
+            let cursor = *self.cursors.primary();
+            if let Some(vim) = self.vim.as_mut() {
+                vim.visual_anchor = cursor;
+            }
+        }
+        self.mode = mode;
+    }
+
+    fn vim_exit_visual(&mut self) {
+        if visual_kind(self.mode).is_none() {
+            return;
+        }
+        let cursor = *self.cursors.primary();
+        if let Some(vim) = self.vim.as_mut() {
+            let (start, end) = ordered(vim.visual_anchor, cursor);
+            vim.last_visual = Some((start.line, end.line));
+        }
+        self.mode = EditorMode::Normal;
+        self.selection = None;
+    }
+
+    fn vim_visual_range(&self) -> VimRange {
+        let anchor = self
+            .vim
+            .as_ref()
+            .map_or(*self.cursors.primary(), |vim| vim.visual_anchor);
+        let cursor = *self.cursors.primary();
+        let (start, end) = ordered(anchor, cursor);
+        match self.mode {
+            EditorMode::VisualLine => VimRange::Lines(start.line, end.line),
+            EditorMode::VisualBlock => VimRange::Block {
+                lines: (start.line, end.line),
+                columns: (
+                    anchor.column.min(cursor.column),
+                    anchor.column.max(cursor.column) + 1,
+                ),
+            },
+            _ => VimRange::Chars(
+                self.vim_index(start),
+                (self.vim_index(end) + 1).min(self.buffer().len_chars()),
+            ),
+        }
+    }
+
+    fn vim_select_object(&mut self, object: VimTextObject, around: bool, count: usize) {
+        let cursor = *self.cursors.primary();
+        let (mode, anchor, target) = match self.vim_object_range(cursor, object, around, count) {
+            Some(VimRange::Chars(start, end)) if end > start => (
+                EditorMode::Visual,
+                self.vim_position(start),
+                self.vim_position(end - 1),
+            ),
+            Some(VimRange::Lines(start, end)) => (
+                EditorMode::VisualLine,
+                CursorPosition::new(start, 0),
+                CursorPosition::new(end, 0),
+            ),
+            _ => return,
+        };
+        if let Some(vim) = self.vim.as_mut() {
+            vim.visual_anchor = anchor;
+        }
+        self.mode = mode;
+        self.cursors.reset_to(target);
+    }
+
+    fn vim_operate(
+        &mut self,
+        operator: VimOperator,
+        target: VimTarget,
+        command: VimCommand,
+    ) -> Result<()> {
+        let from = *self.cursors.primary();
+        let range = match target {
+            VimTarget::Motion(motion) => {
+                self.vim_motion_range(from, motion, command.count, operator)
+            }
+            VimTarget::Object { object, around } => {
+                self.vim_object_range(from, object, around, command.count())
+            }
+            VimTarget::Line => {
+                let end = (from.line + command.count() - 1).min(self.vim_last_line());
+                Some(VimRange::Lines(from.line, end))
+            }
+            VimTarget::Selection => {
+                let range = self.vim_visual_range();
+                self.vim_exit_visual();
+                Some(range)
+            }
+        };
+        let Some(range) = range else {
+            return Ok(());
+        };
+
+        match operator {
+            VimOperator::Yank => {
+                self.vim_store(command.register, range, false)?;
+                let start = match range {
+                    VimRange::Chars(start, _) => self.vim_position(start),
+                    VimRange::Lines(start, _) => {
+                        CursorPosition::new(start.min(from.line), from.column)
+                    }
+                    VimRange::Block { lines, columns } => CursorPosition::new(lines.0, columns.0),
+                };
+                self.cursors.reset_to(start);
+                Ok(())
+            }
+            VimOperator::Delete => {
+                self.vim_store(command.register, range, true)?;
+                self.vim_edit(|state| state.vim_delete_range(range))
+            }
+            VimOperator::Change => self.vim_change(range, command.register),
+            VimOperator::Indent | VimOperator::Dedent => {
+                let indent = operator == VimOperator::Indent;
+                self.vim_edit(|state| state.vim_shift_lines(range, indent))
+            }
+            VimOperator::Lowercase | VimOperator::Uppercase | VimOperator::ToggleCase => {
+                self.vim_edit(|state| state.vim_transform_range(range, operator))
+            }
+        }
+    }
+
+    fn vim_line_span(&self, first: usize, last: usize) -> (usize, usize) {
+        let start = self.vim_index(CursorPosition::new(first, 0));
+        let end = if last + 1 < self.buffer().line_count() {
+            self.vim_index(CursorPosition::new(last + 1, 0))
+        } else {
+            self.buffer().len_chars()
+        };
+        (start, end)
+    }
+
+    fn vim_range_text(&self, range: VimRange) -> (String, bool) {
+        let slice = |start: usize, end: usize| self.buffer().rope().slice(start..end).to_string();
+        match range {
+            VimRange::Chars(start, end) => (slice(start, end), false),
+            VimRange::Lines(first, last) => {
+                let (start, end) = self.vim_line_span(first, last);
+                let mut text = slice(start, end);
+                if !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                (text, true)
+            }
+            VimRange::Block { lines, columns } => {
+                let text = (lines.0..=lines.1)
+                    .map(|line| {
+                        self.vim_line_text(line)
+                            .chars()
+                            .skip(columns.0)
+                            .take(columns.1 - columns.0)
+                            .collect::<String>()
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                (text, false)
+            }
+        }
+    }
+
+    fn vim_store(&mut self, register: Option<char>, range: VimRange, delete: bool) -> Result<()> {
+        let (text, linewise) = self.vim_range_text(range);
+        let value = VimRegister::new(text, linewise);
+        if register.is_some_and(is_clipboard_register) {
+            self.set_clipboard_text(&value.text)?;
+        }
+        if let Some(vim) = self.vim.as_mut() {
+            if delete {
+                vim.registers.delete(register, value);
+            } else {
+                vim.registers.yank(register, value);
+            }
+        }
+        Ok(())
+    }
+
+    fn vim_replace_chars(&mut self, start: usize, end: usize, text: &str) -> Result<()> {
+        let from = self.vim_position(start);
+        if end > start {
+            let to = self.vim_position(end);
+            self.buffer_mut()
+                .delete_range(from.line, from.column, to.line, to.column)?;
+        }
+        if !text.is_empty() {
+            self.buffer_mut().insert_str(from.line, from.column, text)?;
+        }
+        Ok(())
+    }
+
+    fn vim_delete_range(&mut self, range: VimRange) -> Result<()> {
+        match range {
+            VimRange::Chars(start, end) => {
+                self.vim_replace_chars(start, end, "")?;
+                self.cursors.reset_to(self.vim_position(start));
+            }
+            VimRange::Lines(first, last) => {
+                let (mut start, end) = self.vim_line_span(first, last);
+                // Deleting the final lines of a file without a trailing
+                // newline takes the newline before them instead.
+                let len = self.buffer().len_chars();
+                if end == len
+                    && start > 0
+                    && self.buffer().char_at(len.saturating_sub(1)) != Some('\n')
+                {
+                    start -= 1;
+                }
+                self.vim_replace_chars(start, end, "")?;
+                let line = first.min(self.vim_last_line());
+                self.cursors.reset_to(self.vim_first_non_blank(line));
+            }
+            VimRange::Block { lines, columns } => {
+                for line in (lines.0..=lines.1).rev() {
+                    let len = self.vim_line_len(line);
+                    let (start, end) = (columns.0.min(len), columns.1.min(len));
+                    if start < end {
+                        self.buffer_mut().delete_range(line, start, line, end)?;
+                    }
+                }
+                let column = columns.0.min(self.vim_line_len(lines.0));
+                self.cursors.reset_to(CursorPosition::new(lines.0, column));
+            }
+        }
+        Ok(())
+    }
+
+    fn vim_change(&mut self, range: VimRange, register: Option<char>) -> Result<()> {
+        self.vim_store(register, range, true)?;
+        match range {
+            VimRange::Lines(first, last) => {
+                let indent = self.indentation_for_line(first)?;
+                let start = self.vim_index(CursorPosition::new(first, 0));
+                let end = self.vim_index(CursorPosition::new(last, self.vim_line_len(last)));
+                self.vim_edit(|state| state.vim_replace_chars(start, end, &indent))?;
+                self.cursors
+                    .reset_to(CursorPosition::new(first, indent.chars().count()));
+            }
+            VimRange::Block { lines, columns } => {
+                self.vim_edit(|state| state.vim_delete_range(range))?;
+                // Typing after a block change goes to every line of it.
+                let positions = (lines.0..=lines.1)
+                    .map(|line| CursorPosition::new(line, columns.0.min(self.vim_line_len(line))))
+                    .collect();
+                self.cursors.set_positions(positions);
+            }
+            VimRange::Chars(..) => self.vim_edit(|state| state.vim_delete_range(range))?,
+        }
+        self.vim_enter_insert(1);
+        Ok(())
+    }
+
+    fn vim_range_lines(&self, range: VimRange) -> (usize, usize) {
+        match range {
+            VimRange::Chars(start, end) => {
+                let last = self.vim_position(end.saturating_sub(1).max(start));
+                (self.vim_position(start).line, last.line)
+            }
+            VimRange::Lines(first, last) => (first, last),
+            VimRange::Block { lines, .. } => lines,
+        }
+    }
+
+    fn vim_shift_lines(&mut self, range: VimRange, indent: bool) -> Result<()> {
+        let (first, last) = self.vim_range_lines(range);
+        let unit = self.indent_unit();
+        for line in first..=last {
+            if self.vim_line_len(line) == 0 {
+                continue;
+            }
+            if indent {
+                self.buffer_mut().insert_str(line, 0, &unit)?;
+            } else {
+                let current = self.indentation_for_line(line)?;
+                let dedented = self.dedent_indentation(&current);
+                let width = current.chars().count();
+                self.buffer_mut().delete_range(line, 0, line, width)?;
+                self.buffer_mut().insert_str(line, 0, &dedented)?;
+            }
+        }
+        self.cursors.reset_to(self.vim_first_non_blank(first));
+        Ok(())
+    }
+
+    fn vim_transform_range(&mut self, range: VimRange, operator: VimOperator) -> Result<()> {
+        match range {
+            VimRange::Chars(start, end) => {
+                let (text, _) = self.vim_range_text(range);
+                self.vim_replace_chars(start, end, &transform_case(&text, operator))?;
+                self.cursors.reset_to(self.vim_position(start));
+            }
+            VimRange::Lines(first, last) => {
+                let start = self.vim_index(CursorPosition::new(first, 0));
+                let end = self.vim_index(CursorPosition::new(last, self.vim_line_len(last)));
+                self.vim_transform_range(VimRange::Chars(start, end), operator)?;
+            }
+            VimRange::Block { lines, columns } => {
+                for line in lines.0..=lines.1 {
+                    let len = self.vim_line_len(line);
+                    let start = self.vim_index(CursorPosition::new(line, columns.0.min(len)));
+                    let end = self.vim_index(CursorPosition::new(line, columns.1.min(len)));
+                    let (text, _) = self.vim_range_text(VimRange::Chars(start, end));
+                    self.vim_replace_chars(start, end, &transform_case(&text, operator))?;
+                }
+                self.cursors
+                    .reset_to(CursorPosition::new(lines.0, columns.0));
+            }
+        }
+        Ok(())
+    }
+
+    fn vim_insert(&mut self, position: InsertPosition, count: usize) -> Result<()> {
+        let cursor = *self.cursors.primary();
+        let line_len = self.vim_line_len(cursor.line);
+        match position {
+            InsertPosition::BeforeCursor => {}
+            InsertPosition::AfterCursor => {
+                self.cursors.reset_to(CursorPosition::new(
+                    cursor.line,
+                    (cursor.column + 1).min(line_len),
+                ));
+            }
+            InsertPosition::LineStart => {
+                let first = self.vim_first_non_blank(cursor.line);
+                let column = if self.vim_line_text(cursor.line).trim().is_empty() {
+                    line_len
+                } else {
+                    first.column
+                };
+                self.cursors
+                    .reset_to(CursorPosition::new(cursor.line, column));
+            }
+            InsertPosition::LineEnd => {
+                self.cursors
+                    .reset_to(CursorPosition::new(cursor.line, line_len));
+            }
+            InsertPosition::LineBelow | InsertPosition::LineAbove => {
+                let indent = self.indentation_for_line(cursor.line)?;
+                let below = position == InsertPosition::LineBelow;
+                let line = if below { cursor.line + 1 } else { cursor.line };
+                self.vim_edit(|state| {
+                    if below {
+                        state.buffer_mut().insert_str(
+                            cursor.line,
+                            line_len,
+                            &format!("\n{}", indent),
+                        )
+                    } else {
+                        state
+                            .buffer_mut()
+                            .insert_str(cursor.line, 0, &format!("{}\n", indent))
+                    }
+                })?;
+                self.cursors
+                    .reset_to(CursorPosition::new(line, indent.chars().count()));
+            }
+        }
+        self.vim_enter_insert(count);
+        Ok(())
+    }
+
+    fn vim_paste(&mut self, before: bool, register: Option<char>, count: usize) -> Result<()> {
+        let name = register.unwrap_or(UNNAMED_REGISTER);
+        let value = if is_clipboard_register(name) {
+            let text = self.clipboard_text()?;
+            let linewise = text.ends_with('\n');
+            VimRegister::new(text, linewise)
+        } else {
+            match self.vim_register(name) {
+                Some(value) => value.clone(),
+                None => {
+                    self.status_message = format!("Nothing in register {}", name);
+                    return Ok(());
+                }
+            }
+        };
+        if value.text.is_empty() {
+            return Ok(());
+        }
+
+        if visual_kind(self.mode).is_none() {
+            return self.vim_edit(|state| state.vim_put(&value, before, count));
+        }
+        // Pasting over a selection replaces it, and the replaced text takes
+        // the unnamed register's place.
+        let range = self.vim_visual_range();
+        let (replaced, linewise) = self.vim_range_text(range);
+        self.vim_exit_visual();
+        self.vim_edit(|state| {
+            state.vim_delete_range(range)?;
+            let before = match range {
+                VimRange::Lines(first, _) if first <= state.vim_last_line() => {
+                    state.cursors.reset_to(CursorPosition::new(first, 0));
+                    true
+                }
+                VimRange::Lines(..) => false,
+                _ => true,
+            };
+            state.vim_put(&value, before, count)
+        })?;
+        if let Some(vim) = self.vim.as_mut() {
+            vim.registers
+                .delete(None, VimRegister::new(replaced, linewise));
+        }
+        Ok(())
+    }
+
+    fn vim_put(&mut self, value: &VimRegister, before: bool, count: usize) -> Result<()> {
+        let cursor = *self.cursors.primary();
+        let text = value.text.repeat(count);
+        if value.linewise {
+            let mut text = text;
+            if !text.ends_with('\n') {
+                text.push('\n');
+            }
+            let line = if before { cursor.line } else { cursor.line + 1 };
+            if line < self.buffer().line_count() {
+                self.buffer_mut().insert_str(line, 0, &text)?;
+            } else {
+                let end = self.vim_line_len(cursor.line);
+                text.pop();
+                self.buffer_mut()
+                    .insert_str(cursor.line, end, &format!("\n{}", text))?;
+            }
+            self.cursors.reset_to(self.vim_first_non_blank(line));
+            return Ok(());
+        }
+
+        let column = if before || self.vim_line_len(cursor.line) == 0 {
+            cursor.column
+        } else {
+            cursor.column + 1
+        };
+        let index = self.vim_index(CursorPosition::new(cursor.line, column));
+        let position = self.vim_position(index);
+        self.buffer_mut()
+            .insert_str(position.line, position.column, &text)?;
+        let landing = if text.contains('\n') {
+            index
+        } else {
+            index + text.chars().count() - 1
+        };
+        self.cursors.reset_to(self.vim_position(landing));
+        Ok(())
+    }
+
+    fn vim_replace_char(&mut self, ch: char, count: usize) -> Result<()> {
+        let cursor = *self.cursors.primary();
+        if cursor.column + count > self.vim_line_len(cursor.line) {
+            return Ok(());
+        }
+        let start = self.vim_index(cursor);
+        self.vim_edit(|state| {
+            if ch == ENTER {
+                state.vim_replace_chars(start, start + count, "\n")?;
+                state
+                    .cursors
+                    .reset_to(CursorPosition::new(cursor.line + 1, 0));
+            } else {
+                let text: String = std::iter::repeat_n(ch, count).collect();
+                state.vim_replace_chars(start, start + count, &text)?;
+                state
+                    .cursors
+                    .reset_to(CursorPosition::new(cursor.line, cursor.column + count - 1));
+            }
+            Ok(())
+        })
+    }
+
+    fn vim_toggle_case_char(&mut self, count: usize) -> Result<()> {
+        let cursor = *self.cursors.primary();
+        let line_len = self.vim_line_len(cursor.line);
+        if line_len == 0 {
+            return Ok(());
+        }
+        let end_column = (cursor.column + count).min(line_len);
+        let start = self.vim_index(cursor);
+        let end = self.vim_index(CursorPosition::new(cursor.line, end_column));
+        self.vim_edit(|state| {
+            state.vim_transform_range(VimRange::Chars(start, end), VimOperator::ToggleCase)
+        })?;
+        self.cursors
+            .reset_to(CursorPosition::new(cursor.line, end_column));
+        Ok(())
+    }
+
+    fn vim_join_lines(&mut self, count: usize) -> Result<()> {
+        let cursor = *self.cursors.primary();
+        let (first, last) = if visual_kind(self.mode).is_some() {
+            let (first, last) = self.vim_range_lines(self.vim_visual_range());
+            self.vim_exit_visual();
+            (first, last.max(first + 1))
+        } else {
+            (cursor.line, cursor.line + count.max(2) - 1)
+        };
+        let last = last.min(self.vim_last_line());
+        if first >= last {
+            return Ok(());
+        }
+        self.vim_edit(|state| {
+            let mut column = 0;
+            for _ in first..last {
+                let current = state.vim_line_text(first);
+                let next = state.vim_line_text(first + 1);
+                let trimmed = next.trim_start();
+                let separator = if trimmed.is_empty()
+                    || trimmed.starts_with(')')
+                    || current.is_empty()
+                    || current.ends_with(' ')
+                {
+                    ""
+                } else {
+                    " "
+                };
+                column = current.chars().count();
+                let start = state.vim_index(CursorPosition::new(first, column));
+                let leading = next.chars().count() - trimmed.chars().count();
+                let end = state.vim_index(CursorPosition::new(first + 1, leading));
+                state.vim_replace_chars(start, end, separator)?;
+            }
+            state.cursors.reset_to(CursorPosition::new(first, column));
+            Ok(())
+        })
+    }
+
+    fn vim_open_command_line(&mut self, prefix: char) {
+        let visual = visual_kind(self.mode).is_some();
+        self.vim_exit_visual();
+        let text = if visual && prefix == ':' {
+            ":'<,'>".to_string()
+        } else {
+            prefix.to_string()
+        };
+        if let Some(vim) = self.vim.as_mut() {
+            vim.command_line = Some(text);
+        }
+    }
+
+    fn vim_command_line_key(&mut self, key: char) -> Result<()> {
+        let Some(vim) = self.vim.as_mut() else {
+            return Ok(());
+        };
+        let Some(line) = vim.command_line.as_mut() else {
+            return Ok(());
+        };
+        match key {
+            ESCAPE => vim.command_line = None,
+            BACKSPACE => {
+                line.pop();
+                if line.is_empty() {
+                    vim.command_line = None;
+                }
+            }
+            ENTER => {
+                let line = vim.command_line.take().unwrap_or_default();
+                return self.vim_run_command_line(&line);
+            }
+            ch if !ch.is_control() => line.push(ch),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn vim_run_command_line(&mut self, line: &str) -> Result<()> {
+        let mut chars = line.chars();
+        let prefix = chars.next();
+        let text = chars.as_str();
+        if prefix != Some('/') {
+            return self.vim_run_ex(text);
+        }
+        if text.is_empty() {
+            self.next_match()?;
+        } else {
+            self.search(text.to_string())?;
+        }
+        self.vim_settle_search();
+        Ok(())
+    }
+
+    // Searches select the match; vim just puts the cursor on its start.
+    fn vim_settle_search(&mut self) {
+        if let Some(selection) = self.selection.take() {
+            self.cursors.reset_to(selection.start());
+        }
+    }
+
+    fn vim_run_ex(&mut self, text: &str) -> Result<()> {
+        let context = ExContext {
+            current_line: self.cursors.primary().line,
+            last_line: self.vim_last_line(),
+            visual: self.vim.as_ref().and_then(|vim| vim.last_visual),
+        };
+        match parse_ex_command(text, &context)? {
+            ExCommand::Write { path, quit } => {
+                match path {
+                    Some(path) => self.save_as(path)?,
+                    None => self.save()?,
+                }
+                if quit {
+                    self.vim_quit(false)?;
+                }
+                Ok(())
+            }
+            ExCommand::Quit { force } => self.vim_quit(force),
+            ExCommand::Edit(path) => self.open_file(path),
+            ExCommand::GotoLine(line) => {
+                self.cursors.reset_to(self.vim_first_non_blank(line));
+                Ok(())
+            }
+            ExCommand::Substitute {
+                lines,
+                pattern,
+                replacement,
+                global,
+                ignore_case,
+            } => self.vim_substitute(lines, &pattern, &replacement, global, ignore_case),
+            ExCommand::Sort { lines, options } => self.vim_sort(lines, options),
+        }
+    }
+
+    // `:q` closes the focused split first, as vim closes the window.
+    fn vim_quit(&mut self, force: bool) -> Result<()> {
+        if self.splits.is_split() {
+            return self.close_split();
+        }
+        if !force && self.buffers.iter().any(|buffer| buffer.is_modified()) {
+            return Err(EditorError::InvalidOperation(
+                "No write since last change (add ! to override)".to_string(),
+            ));
+        }
+        self.quit_requested = true;
+        Ok(())
+    }
+
+    fn vim_substitute(
+        &mut self,
+        (first, last): (usize, usize),
+        pattern: &str,
+        replacement: &str,
+        global: bool,
+        ignore_case: bool,
+    ) -> Result<()> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|error| {
+                EditorError::InvalidOperation(format!("Invalid pattern: {}", error))
+            })?;
+
+        let mut substitutions = 0;
+        let mut changed = Vec::new();
+        for line in first..=last {
+            let text = self.vim_line_text(line);
+            let matches = regex.find_iter(&text).count();
+            if matches == 0 {
+                continue;
+            }
+            let replaced = if global {
+                substitutions += matches;
+                regex.replace_all(&text, replacement)
+            } else {
+                substitutions += 1;
+                regex.replace(&text, replacement)
+            };
+            changed.push((line, replaced.into_owned()));
+        }
+        let Some(&(last_changed, _)) = changed.last() else {
+            return Err(EditorError::InvalidOperation(format!(
+                "Pattern not found: {}",
+                pattern
+            )));
+        };
+
+        self.vim_edit(|state| {
+            for (line, text) in changed.iter().rev() {
+                let start = state.vim_index(CursorPosition::new(*line, 0));
+                let end = state.vim_index(CursorPosition::new(*line, state.vim_line_len(*line)));
+                state.vim_replace_chars(start, end, text)?;
+            }
+            Ok(())
+        })?;
+        self.cursors
+            .reset_to(self.vim_first_non_blank(last_changed.min(self.vim_last_line())));
+        self.status_message = format!(
+            "{} on {}",
+            plural(substitutions, "substitution"),
+            plural(changed.len(), "line")
+        );
+        Ok(())
+    }
+
+    fn vim_sort(&mut self, (first, last): (usize, usize), options: SortOptions) -> Result<()> {
+        let mut lines: Vec<String> = (first..=last)
+            .map(|line| self.vim_line_text(line))
+            .collect();
+        let (reverse, unique) = match options {
+            SortOptions::Text { reverse, unique } => {
+                lines.sort();
+                (reverse, unique)
+            }
+            SortOptions::Numeric { reverse, unique } => {
+                lines.sort_by_key(|line| leading_number(line));
+                (reverse, unique)
+            }
+        };
+        if reverse {
+            lines.reverse();
+        }
+        if unique {
+            lines.dedup();
+        }
+
+        let start = self.vim_index(CursorPosition::new(first, 0));
+        let end = self.vim_index(CursorPosition::new(last, self.vim_line_len(last)));
+        self.vim_edit(|state| state.vim_replace_chars(start, end, &lines.join("\n")))?;
+        self.cursors.reset_to(CursorPosition::new(first, 0));
+        Ok(())
+    }
+}
@@ -0,0 +1,377 @@
+use crate::buffer::{Encoding, LineEnding};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const EDITORCONFIG_FILE: &str = ".editorconfig";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentSize {
+    Tab,
+    Columns(usize),
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditorConfigProperties {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<IndentSize>,
+    pub tab_width: Option<usize>,
+    pub end_of_line: Option<LineEnding>,
+    pub charset: Option<Encoding>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+}
+
+impl EditorConfigProperties {
+    pub fn resolve(path: &Path) -> Self {
+        let path = absolute_path(path);
+        let mut files = Vec::new();
+
+        for dir in path.ancestors().skip(1) {
+            let config_path = dir.join(EDITORCONFIG_FILE);
+            let Ok(content) = fs::read_to_string(&config_path) else {
+                continue;
+            };
+            let file = EditorConfigFile::parse(&content, dir);
+            let is_root = file.root;
+            files.push(file);
+            if is_root {
+                break;
+            }
+        }
+
+        let mut values = HashMap::new();
+        for file in files.iter().rev() {
+            file.collect_properties(&path, &mut values);
+        }
+        Self::from_values(&values)
+    }
+
+    pub fn from_values(values: &HashMap<String, String>) -> Self {
+        let get = |key: &str| values.get(key).map(String::as_str);
+        let mut properties = Self {
+            indent_style: match get("indent_style") {
+                Some("tab") => Some(IndentStyle::Tab),
+                Some("space") => Some(IndentStyle::Space),
+                _ => None,
+            },
+            indent_size: match get("indent_size") {
+                Some("tab") => Some(IndentSize::Tab),
+                Some(value) => parse_width(value).map(IndentSize::Columns),
+                None => None,
+            },
+            tab_width: get("tab_width").and_then(parse_width),
+            end_of_line: match get("end_of_line") {
+                Some("lf") => Some(LineEnding::Lf),
+                Some("crlf") => Some(LineEnding::Crlf),
+                _ => None,
+            },
+            charset: match get("charset") {
+                Some("utf-8") => Some(Encoding::Utf8),
+                Some("utf-8-bom") => Some(Encoding::Utf8Bom),
+                _ => None,
+            },
+            trim_trailing_whitespace: get("trim_trailing_whitespace").and_then(parse_bool),
+            insert_final_newline: get("insert_final_newline").and_then(parse_bool),
+        };
+
+        if properties.indent_style == Some(IndentStyle::Tab) && properties.indent_size.is_none() {
+            properties.indent_size = Some(IndentSize::Tab);
+        }
+        if properties.tab_width.is_none() {
+            if let Some(IndentSize::Columns(columns)) = properties.indent_size {
+                properties.tab_width = Some(columns);
+            }
+        }
+        properties
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn indent_width(&self) -> Option<usize> {
+        match self.indent_size {
+            Some(IndentSize::Columns(columns)) => Some(columns),
+            Some(IndentSize::Tab) | None => self.tab_width,
+        }
+    }
+
+    pub fn insert_spaces(&self) -> Option<bool> {
+        self.indent_style.map(|style| style == IndentStyle::Space)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EditorConfigFile {
+    pub root: bool,
+    directory: PathBuf,
+    sections: Vec<EditorConfigSection>,
+}
+
+#[derive(Debug, Clone)]
+struct EditorConfigSection {
+    glob: Option<SectionGlob>,
+    properties: Vec<(String, String)>,
+}
+
+impl EditorConfigFile {
+    pub fn parse(content: &str, directory: &Path) -> Self {
+        let mut root = false;
+        let mut sections: Vec<EditorConfigSection> = Vec::new();
+
+        for line in content.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[') {
+                if let Some(end) = header.rfind(']') {
+                    sections.push(EditorConfigSection {
+                        glob: SectionGlob::new(&header[..end], directory),
+                        properties: Vec::new(),
+                    });
+                }
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let key = key.trim().to_lowercase();
+            let value = value.trim().to_lowercase();
+
+            match sections.last_mut() {
+                Some(section) => section.properties.push((key, value)),
+                None if key == "root" => root = value == "true",
+                None => {}
+            }
+        }
+
+        Self {
+            root,
+            directory: directory.to_path_buf(),
+            sections,
+        }
+    }
+
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    pub fn collect_properties(&self, path: &Path, values: &mut HashMap<String, String>) {
+        let path = path.to_string_lossy().replace('\\', "/");
+
+        for section in &self.sections {
+            let Some(glob) = &section.glob else {
+                continue;
+            };
+            if !glob.is_match(&path) {
+                continue;
+            }
+            for (key, value) in &section.properties {
+                if value == "unset" {
+                    values.remove(key);
+                } else {
+                    values.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SectionGlob {
+    regex: Regex,
+    ranges: Vec<(i64, i64)>,
+}
+
+impl SectionGlob {
+    fn new(pattern: &str, directory: &Path) -> Option<Self> {
+        let mut prefix = directory.to_string_lossy().replace('\\', "/");
+        if !prefix.ends_with('/') {
+            prefix.push('/');
+        }
+
+        let anchored = contains_separator(pattern);
+        let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+
+        let mut ranges = Vec::new();
+        let body = translate_glob(pattern, &mut ranges);
+        let source = if anchored {
+            format!("^{}{}$", regex::escape(&prefix), body)
+        } else {
+            format!("^{}(?:.*/)?{}$", regex::escape(&prefix), body)
+        };
+
+        Regex::new(&source).ok().map(|regex| Self { regex, ranges })
+    }
+
+    fn is_match(&self, path: &str) -> bool {
+        let Some(captures) = self.regex.captures(path) else {
+            return false;
+        };
+
+        self.ranges.iter().enumerate().all(|(index, (start, end))| {
+            captures
+                .get(index + 1)
+                .and_then(|number| number.as_str().parse::<i64>().ok())
+                .is_some_and(|number| number >= *start && number <= *end)
+        })
+    }
+}
+
+fn contains_separator(pattern: &str) -> bool {
+    let mut in_brackets = false;
+    for c in pattern.chars() {
+        match c {
+            '[' => in_brackets = true,
+            ']' => in_brackets = false,
+            '/' if !in_brackets => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn translate_glob(pattern: &str, ranges: &mut Vec<(i64, i64)>) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut regex = String::new();
+    let mut brace_depth = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' if i + 1 < chars.len() => {
+                regex.push_str(&regex::escape(&chars[i + 1].to_string()));
+                i += 1;
+            }
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                regex.push_str(".*");
+                i += 1;
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            '[' => match chars[i + 1..].iter().position(|&c| c == ']') {
+                Some(length) => {
+                    let class: String = chars[i + 1..i + 1 + length].iter().collect();
+                    regex.push_str(&translate_class(&class));
+                    i += length + 1;
+                }
+                None => regex.push_str("\\["),
+            },
+            '{' => match matching_brace(&chars, i) {
+                Some(end) => {
+                    let inner: String = chars[i + 1..end].iter().collect();
+                    if let Some(range) = parse_range(&inner) {
+                        ranges.push(range);
+                        regex.push_str("([+-]?\\d+)");
+                        i = end;
+                    } else if has_top_level_comma(&inner) {
+                        regex.push_str("(?:");
+                        brace_depth += 1;
+                    } else {
+                        regex.push_str("\\{");
+                    }
+                }
+                None => regex.push_str("\\{"),
+            },
+            ',' if brace_depth > 0 => regex.push('|'),
+            '}' if brace_depth > 0 => {
+                regex.push(')');
+                brace_depth -= 1;
+            }
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+        i += 1;
+    }
+
+    regex
+}
+
+fn translate_class(class: &str) -> String {
+    let (negated, body) = match class.strip_prefix('!') {
+        Some(body) => (true, body),
+        None => (false, class),
+    };
+    let body = body.replace('\\', "\\\\").replace('[', "\\[");
+    if negated {
+        format!("[^/{}]", body)
+    } else {
+        format!("[{}]", body)
+    }
+}
+
+fn matching_brace(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = open;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+fn has_top_level_comma(inner: &str) -> bool {
+    let mut depth = 0;
+    let mut escaped = false;
+    for c in inner.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+fn parse_range(inner: &str) -> Option<(i64, i64)> {
+    let (start, end) = inner.split_once("..")?;
+    let start: i64 = start.parse().ok()?;
+    let end: i64 = end.parse().ok()?;
+    Some((start.min(end), start.max(end)))
+}
+
+fn parse_width(value: &str) -> Option<usize> {
+    value.parse().ok().filter(|width| *width > 0)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn absolute_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+}
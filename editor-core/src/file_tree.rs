@@ -0,0 +1,388 @@
+use crate::error::{EditorError, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileTreeEntryKind {
+    File,
+    Directory,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    Clean,
+    Open,
+    Modified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTreeEntry {
+    pub path: PathBuf,
+    pub name: String,
+    pub kind: FileTreeEntryKind,
+    pub depth: usize,
+    pub expanded: bool,
+}
+
+impl FileTreeEntry {
+    pub fn is_dir(&self) -> bool {
+        self.kind == FileTreeEntryKind::Directory
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FileTree {
+    root: PathBuf,
+    expanded: HashSet<PathBuf>,
+    entries: Vec<FileTreeEntry>,
+    selected_index: usize,
+    show_hidden: bool,
+}
+
+impl FileTree {
+    pub fn new(root: PathBuf) -> Result<Self> {
+        if !root.is_dir() {
+            return Err(EditorError::FileNotFound(root.display().to_string()));
+        }
+
+        let mut tree = Self {
+            root,
+            expanded: HashSet::new(),
+            entries: Vec::new(),
+            selected_index: 0,
+            show_hidden: false,
+        };
+        tree.refresh()?;
+        Ok(tree)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn entries(&self) -> &[FileTreeEntry] {
+        &self.entries
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    pub fn selected_entry(&self) -> Option<&FileTreeEntry> {
+        self.entries.get(self.selected_index)
+    }
+
+    pub fn show_hidden(&self) -> bool {
+        self.show_hidden
+    }
+
+    pub fn set_show_hidden(&mut self, show_hidden: bool) -> Result<()> {
+        self.show_hidden = show_hidden;
+        self.refresh()
+    }
+
+    pub fn is_expanded(&self, path: &Path) -> bool {
+        self.expanded.contains(path)
+    }
+
+    pub fn refresh(&mut self) -> Result<()> {
+        let selected_path = self.selected_entry().map(|e| e.path.clone());
+        self.expanded.retain(|p| p.is_dir());
+
+        let mut entries = Vec::new();
+        self.collect_entries(&self.root, 0, &mut entries)?;
+        self.entries = entries;
+
+        self.selected_index = selected_path
+            .and_then(|path| self.entries.iter().position(|e| e.path == path))
+            .unwrap_or_else(|| {
+                self.selected_index
+                    .min(self.entries.len().saturating_sub(1))
+            });
+        Ok(())
+    }
+
+    fn collect_entries(
+        &self,
+        dir: &Path,
+        depth: usize,
+        entries: &mut Vec<FileTreeEntry>,
+    ) -> Result<()> {
+        let mut children = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name == ".git" || (!self.show_hidden && name.starts_with('.')) {
+                continue;
+            }
+
+            let kind = if entry.file_type()?.is_dir() {
+                FileTreeEntryKind::Directory
+            } else {
+                FileTreeEntryKind::File
+            };
+            children.push((name, entry.path(), kind));
+        }
+
+        children.sort_by(|a, b| {
+            let a_is_file = a.2 == FileTreeEntryKind::File;
+            let b_is_file = b.2 == FileTreeEntryKind::File;
+            a_is_file
+                .cmp(&b_is_file)
+                .then_with(|| a.0.to_lowercase().cmp(&b.0.to_lowercase()))
+        });
+
+        for (name, path, kind) in children {
+            let expanded = kind == FileTreeEntryKind::Directory && self.expanded.contains(&path);
+            entries.push(FileTreeEntry {
+                path: path.clone(),
+                name,
+                kind,
+                depth,
+                expanded,
+            });
+
+            if expanded {
+                self.collect_entries(&path, depth + 1, entries)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn select_next(&mut self) -> bool {
+        if self.selected_index + 1 < self.entries.len() {
+            self.selected_index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn select_previous(&mut self) -> bool {
+        if self.selected_index > 0 {
+            self.selected_index -= 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn select_first(&mut self) {
+        self.selected_index = 0;
+    }
+
+    pub fn select_last(&mut self) {
+        self.selected_index = self.entries.len().saturating_sub(1);
+    }
+
+    pub fn select_index(&mut self, index: usize) -> bool {
+        if index < self.entries.len() {
+            self.selected_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn select_path(&mut self, path: &Path) -> bool {
+        if let Some(index) = self.entries.iter().position(|e| e.path == path) {
+            self.selected_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn expand_selected(&mut self) -> Result<()> {
+        if let Some(entry) = self.selected_entry() {
+            if entry.is_dir() && !entry.expanded {
+                let path = entry.path.clone();
+                self.expanded.insert(path);
+                self.refresh()?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn collapse_selected(&mut self) -> Result<()> {
+        let entry = match self.selected_entry() {
+            Some(entry) => entry.clone(),
+            None => return Ok(()),
+        };
+
+        if entry.is_dir() && entry.expanded {
+            self.expanded.remove(&entry.path);
+            self.refresh()?;
+        } else if let Some(parent) = entry.path.parent() {
+            if parent != self.root {
+                self.select_path(parent);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn toggle_selected(&mut self) -> Result<()> {
+        match self.selected_entry() {
+            Some(entry) if entry.is_dir() && entry.expanded => self.collapse_selected(),
+            Some(entry) if entry.is_dir() => self.expand_selected(),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn reveal(&mut self, path: &Path) -> Result<bool> {
+        if !path.starts_with(&self.root) {
+            return Ok(false);
+        }
+
+        let mut ancestor = path.parent();
+        while let Some(dir) = ancestor {
+            if dir == self.root {
+                break;
+            }
+            self.expanded.insert(dir.to_path_buf());
+            ancestor = dir.parent();
+        }
+
+        self.refresh()?;
+        Ok(self.select_path(path))
+    }
+
+    fn target_directory(&self) -> PathBuf {
+        match self.selected_entry() {
+            Some(entry) if entry.is_dir() => entry.path.clone(),
+            Some(entry) => entry
+                .path
+                .parent()
+                .map(|p| p.to_path_buf())
+                .unwrap_or_else(|| self.root.clone()),
+            None => self.root.clone(),
+        }
+    }
+
+    fn validate_name(name: &str) -> Result<()> {
+        let trimmed = name.trim();
+        if trimmed.is_empty() {
+            return Err(EditorError::InvalidOperation(
+                "Name must not be empty".to_string(),
+            ));
+        }
+
+        if Path::new(trimmed)
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        {
+            return Err(EditorError::InvalidOperation(format!(
+                "Invalid name: {}",
+                name
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub fn create_file(&mut self, name: &str) -> Result<PathBuf> {
+        Self::validate_name(name)?;
+        let dir = self.target_directory();
+        let path = dir.join(name.trim());
+
+        if path.exists() {
+            return Err(EditorError::InvalidOperation(format!(
+                "{} already exists",
+                path.display()
+            )));
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::File::create(&path)?;
+
+        self.reveal(&path)?;
+        Ok(path)
+    }
+
+    pub fn create_directory(&mut self, name: &str) -> Result<PathBuf> {
+        Self::validate_name(name)?;
+        let dir = self.target_directory();
+        let path = dir.join(name.trim());
+
+        if path.exists() {
+            return Err(EditorError::InvalidOperation(format!(
+                "{} already exists",
+                path.display()
+            )));
+        }
+
+        fs::create_dir_all(&path)?;
+
+        self.reveal(&path)?;
+        Ok(path)
+    }
+
+    pub fn rename_selected(&mut self, new_name: &str) -> Result<(PathBuf, PathBuf)> {
+        Self::validate_name(new_name)?;
+        let entry = self.selected_entry().cloned().ok_or_else(|| {
+            EditorError::InvalidOperation("No file tree entry selected".to_string())
+        })?;
+
+        let parent = entry
+            .path
+            .parent()
+            .map(|p| p.to_path_buf())
+            .unwrap_or_else(|| self.root.clone());
+        let new_path = parent.join(new_name.trim());
+
+        if new_path == entry.path {
+            return Ok((entry.path.clone(), new_path));
+        }
+
+        if new_path.exists() {
+            return Err(EditorError::InvalidOperation(format!(
+                "{} already exists",
+                new_path.display()
+            )));
+        }
+
+        if let Some(new_parent) = new_path.parent() {
+            fs::create_dir_all(new_parent)?;
+        }
+        fs::rename(&entry.path, &new_path)?;
+
+        if self.expanded.remove(&entry.path) {
+            self.expanded.insert(new_path.clone());
+        }
+        let moved: Vec<PathBuf> = self
+            .expanded
+            .iter()
+            .filter(|p| p.starts_with(&entry.path))
+            .cloned()
+            .collect();
+        for old in moved {
+            self.expanded.remove(&old);
+            if let Ok(rest) = old.strip_prefix(&entry.path) {
+                self.expanded.insert(new_path.join(rest));
+            }
+        }
+
+        self.reveal(&new_path)?;
+        Ok((entry.path, new_path))
+    }
+
+    pub fn delete_selected(&mut self) -> Result<PathBuf> {
+        let entry = self.selected_entry().cloned().ok_or_else(|| {
+            EditorError::InvalidOperation("No file tree entry selected".to_string())
+        })?;
+
+        if entry.is_dir() {
+            fs::remove_dir_all(&entry.path)?;
+            self.expanded.retain(|p| !p.starts_with(&entry.path));
+        } else {
+            fs::remove_file(&entry.path)?;
+        }
+
+        self.refresh()?;
+        Ok(entry.path)
+    }
+}
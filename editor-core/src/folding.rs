@@ -0,0 +1,381 @@
+use crate::text_change::TextChange;
+use ropey::Rope;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use tree_sitter::{Node, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct FoldRange {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl FoldRange {
+    pub fn new(start_line: usize, end_line: usize) -> Self {
+        Self {
+            start_line,
+            end_line,
+        }
+    }
+
+    pub fn contains(&self, line: usize) -> bool {
+        line >= self.start_line && line <= self.end_line
+    }
+
+    pub fn hides(&self, line: usize) -> bool {
+        line > self.start_line && line <= self.end_line
+    }
+
+    pub fn hidden_lines(&self) -> usize {
+        self.end_line - self.start_line
+    }
+}
+
+// A line starts a range when the following non-blank lines are indented
+// deeper than it; the range ends at the last of those lines, so trailing
+// blank lines and a dedented closing line stay visible.
+pub fn indentation_fold_ranges(rope: &Rope, tab_size: usize) -> Vec<FoldRange> {
+    let mut ranges = Vec::new();
+    let mut open: Vec<(usize, usize)> = Vec::new();
+    let mut last_content_line = 0;
+
+    for line_idx in 0..rope.len_lines() {
+        let Some(indent) = indent_width(rope, line_idx, tab_size) else {
+            continue;
+        };
+        while let Some(&(start, start_indent)) = open.last() {
+            if start_indent < indent {
+                break;
+            }
+            open.pop();
+            if last_content_line > start {
+                ranges.push(FoldRange::new(start, last_content_line));
+            }
+        }
+        open.push((line_idx, indent));
+        last_content_line = line_idx;
+    }
+
+    while let Some((start, _)) = open.pop() {
+        if last_content_line > start {
+            ranges.push(FoldRange::new(start, last_content_line));
+        }
+    }
+
+    ranges.sort();
+    ranges
+}
+
+// Every multi-line node below the root whose body is indented under its
+// first line is a candidate. When a node ends with an anonymous token on a
+// line of its own (`}`, `end`, `]`), that line stays visible below the
+// folded header.
+pub fn syntax_fold_ranges(tree: &Tree, rope: &Rope, tab_size: usize) -> Vec<FoldRange> {
+    let mut ends: BTreeMap<usize, usize> = BTreeMap::new();
+    let mut cursor = tree.walk();
+
+    'walk: loop {
+        let node = cursor.node();
+        let start_row = node.start_position().row;
+        let multi_line = node.end_position().row > start_row;
+
+        if multi_line && cursor.depth() > 0 {
+            let end_row = fold_end_row(&node, rope);
+            if end_row > start_row && body_is_indented(rope, start_row, end_row, tab_size) {
+                let end = ends.entry(start_row).or_insert(end_row);
+                *end = (*end).max(end_row);
+            }
+        }
+
+        if multi_line && cursor.goto_first_child() {
+            continue;
+        }
+        loop {
+            if cursor.goto_next_sibling() {
+                continue 'walk;
+            }
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
+        }
+    }
+
+    ends.into_iter()
+        .map(|(start, end)| FoldRange::new(start, end))
+        .collect()
+}
+
+fn fold_end_row(node: &Node, rope: &Rope) -> usize {
+    let start_row = node.start_position().row;
+    let end = node.end_position();
+    let mut end_row = end.row;
+    if end.column == 0 {
+        end_row -= 1;
+    }
+
+    let mut last = *node;
+    while let Some(child) = last
+        .child_count()
+        .checked_sub(1)
+        .and_then(|i| last.child(i))
+    {
+        last = child;
+    }
+    let row = last.start_position().row;
+    if !last.is_named() && row == end_row && row > start_row {
+        let line_start = rope.line_to_byte(row);
+        let prefix = rope.byte_slice(line_start..line_start + last.start_position().column);
+        if prefix.chars().all(char::is_whitespace) {
+            end_row -= 1;
+        }
+    }
+    end_row
+}
+
+fn body_is_indented(rope: &Rope, start_row: usize, end_row: usize, tab_size: usize) -> bool {
+    let Some(header) = indent_width(rope, start_row, tab_size) else {
+        return false;
+    };
+    (start_row + 1..=end_row)
+        .find_map(|row| indent_width(rope, row, tab_size))
+        .is_some_and(|body| body > header)
+}
+
+fn indent_width(rope: &Rope, line_idx: usize, tab_size: usize) -> Option<usize> {
+    let mut width = 0;
+    for ch in rope.line(line_idx).chars() {
+        match ch {
+            ' ' => width += 1,
+            '\t' => width += tab_size.max(1) - width % tab_size.max(1),
+            '\n' | '\r' => return None,
+            _ => return Some(width),
+        }
+    }
+    None
+}
+
+pub fn fold_depths(ranges: &[FoldRange]) -> Vec<usize> {
+    let mut depths = Vec::with_capacity(ranges.len());
+    let mut enclosing: Vec<usize> = Vec::new();
+    for range in ranges {
+        while enclosing.last().is_some_and(|&end| end < range.start_line) {
+            enclosing.pop();
+        }
+        depths.push(enclosing.len() + 1);
+        enclosing.push(range.end_line);
+    }
+    depths
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FoldState {
+    folds: Vec<FoldRange>,
+}
+
+impl FoldState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fold(&mut self, range: FoldRange) {
+        if range.end_line <= range.start_line {
+            return;
+        }
+        match self
+            .folds
+            .binary_search_by_key(&range.start_line, |fold| fold.start_line)
+        {
+            Ok(index) => self.folds[index] = range,
+            Err(index) => self.folds.insert(index, range),
+        }
+    }
+
+    pub fn unfold_at(&mut self, line: usize) -> Option<FoldRange> {
+        let index = self
+            .folds
+            .binary_search_by_key(&line, |fold| fold.start_line)
+            .ok()?;
+        Some(self.folds.remove(index))
+    }
+
+    pub fn folded_at(&self, line: usize) -> Option<FoldRange> {
+        self.folds
+            .binary_search_by_key(&line, |fold| fold.start_line)
+            .ok()
+            .map(|index| self.folds[index])
+    }
+
+    pub fn is_folded(&self, line: usize) -> bool {
+        self.folded_at(line).is_some()
+    }
+
+    pub fn is_hidden(&self, line: usize) -> bool {
+        self.hiding_fold(line).is_some()
+    }
+
+    pub fn folds(&self) -> &[FoldRange] {
+        &self.folds
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.folds.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.folds.clear();
+    }
+
+    pub fn set_folds(&mut self, folds: &[FoldRange]) {
+        self.folds.clear();
+        for fold in folds {
+            self.fold(*fold);
+        }
+    }
+
+    // The outermost fold wins, so a hidden line resolves to the header that
+    // is actually on screen.
+    pub fn visible_line(&self, line: usize) -> usize {
+        let mut line = line;
+        while let Some(fold) = self.hiding_fold(line) {
+            line = fold.start_line;
+        }
+        line
+    }
+
+    pub fn next_visible(&self, line: usize, line_count: usize) -> Option<usize> {
+        let mut candidate = line + 1;
+        while candidate < line_count {
+            match self.hiding_fold(candidate) {
+                Some(fold) => candidate = fold.end_line + 1,
+                None => return Some(candidate),
+            }
+        }
+        None
+    }
+
+    pub fn previous_visible(&self, line: usize) -> Option<usize> {
+        line.checked_sub(1).map(|line| self.visible_line(line))
+    }
+
+    pub fn visible_line_above(&self, line: usize, count: usize) -> usize {
+        if self.folds.is_empty() {
+            return line.saturating_sub(count);
+        }
+        let mut line = self.visible_line(line);
+        for _ in 0..count {
+            match self.previous_visible(line) {
+                Some(previous) => line = previous,
+                None => break,
+            }
+        }
+        line
+    }
+
+    pub fn visible_line_below(&self, line: usize, count: usize, line_count: usize) -> usize {
+        if self.folds.is_empty() {
+            return (line + count).min(line_count.saturating_sub(1)).max(line);
+        }
+        let mut line = self.visible_line(line);
+        for _ in 0..count {
+            match self.next_visible(line, line_count) {
+                Some(next) => line = next,
+                None => break,
+            }
+        }
+        line
+    }
+
+    pub fn count_visible(&self, from: usize, to: usize) -> usize {
+        if self.folds.is_empty() {
+            return to.saturating_sub(from);
+        }
+        let mut count = 0;
+        let mut line = self.visible_line(from);
+        if line < from {
+            line = match self.next_visible(line, to) {
+                Some(next) => next,
+                None => return 0,
+            };
+        }
+        while line < to {
+            count += 1;
+            match self.next_visible(line, to) {
+                Some(next) => line = next,
+                None => break,
+            }
+        }
+        count
+    }
+
+    pub fn visible_lines(&self, start: usize, line_count: usize, limit: usize) -> Vec<usize> {
+        let mut lines = Vec::with_capacity(limit.min(line_count));
+        let mut line = self.visible_line(start);
+        while line < line_count && lines.len() < limit {
+            lines.push(line);
+            match self.next_visible(line, line_count) {
+                Some(next) => line = next,
+                None => break,
+            }
+        }
+        lines
+    }
+
+    pub fn apply_change(&mut self, change: &TextChange) {
+        let start = change.start.line;
+        let old_end = change.old_end.line;
+        let delta = change.new_end.line as isize - old_end as isize;
+
+        self.folds.retain_mut(|fold| {
+            if fold.end_line < start {
+                true
+            } else if fold.start_line > old_end {
+                fold.start_line = fold.start_line.saturating_add_signed(delta);
+                fold.end_line = fold.end_line.saturating_add_signed(delta);
+                true
+            } else if start > fold.start_line && old_end <= fold.end_line {
+                fold.end_line = fold.end_line.saturating_add_signed(delta);
+                fold.end_line > fold.start_line
+            } else {
+                start == fold.start_line && old_end == start && delta == 0
+            }
+        });
+    }
+
+    fn hiding_fold(&self, line: usize) -> Option<FoldRange> {
+        self.folds
+            .iter()
+            .take_while(|fold| fold.start_line < line)
+            .filter(|fold| fold.hides(line))
+            .max_by_key(|fold| fold.end_line)
+            .copied()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileFolds {
+    pub file_path: PathBuf,
+    pub folds: Vec<FoldRange>,
+}
+
+impl FileFolds {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self {
+            file_path,
+            folds: Vec::new(),
+        }
+    }
+
+    pub fn from_state(file_path: PathBuf, state: &FoldState) -> Self {
+        Self {
+            file_path,
+            folds: state.folds().to_vec(),
+        }
+    }
+
+    pub fn to_state(&self) -> FoldState {
+        let mut state = FoldState::new();
+        state.set_folds(&self.folds);
+        state
+    }
+}
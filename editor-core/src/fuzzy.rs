@@ -0,0 +1,129 @@
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    pub score: i64,
+    pub indices: Vec<usize>,
+}
+
+const MATCH_SCORE: i64 = 16;
+const CONSECUTIVE_BONUS: i64 = 24;
+const WORD_START_BONUS: i64 = 20;
+const PREFIX_BONUS: i64 = 32;
+const CASE_BONUS: i64 = 2;
+const GAP_PENALTY: i64 = 3;
+const LENGTH_PENALTY_DIVISOR: i64 = 4;
+
+fn is_word_start(chars: &[char], index: usize) -> bool {
+    if index == 0 {
+        return true;
+    }
+
+    let previous = chars[index - 1];
+    let current = chars[index];
+    !previous.is_alphanumeric() || (previous.is_lowercase() && current.is_uppercase())
+}
+
+pub fn fuzzy_match(pattern: &str, text: &str) -> Option<FuzzyMatch> {
+    let pattern: Vec<char> = pattern.chars().filter(|c| !c.is_whitespace()).collect();
+    if pattern.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            indices: Vec::new(),
+        });
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+
+    let mut best: Option<FuzzyMatch> = None;
+
+    for start in 0..chars.len() {
+        if lower[start] != pattern[0].to_lowercase().next().unwrap_or(pattern[0]) {
+            continue;
+        }
+
+        if let Some(candidate) = match_from(&pattern, &chars, &lower, start) {
+            if best.as_ref().is_none_or(|b| candidate.score > b.score) {
+                best = Some(candidate);
+            }
+        }
+    }
+
+    best
+}
+
+fn match_from(
+    pattern: &[char],
+    chars: &[char],
+    lower: &[char],
+    start: usize,
+) -> Option<FuzzyMatch> {
+    let mut indices: Vec<usize> = Vec::with_capacity(pattern.len());
+    let mut score = 0;
+    let mut text_index = start;
+
+    for (pattern_index, &pattern_char) in pattern.iter().enumerate() {
+        let needle = pattern_char.to_lowercase().next().unwrap_or(pattern_char);
+
+        let first = (text_index..lower.len()).find(|&i| lower[i] == needle)?;
+        let consecutive = indices.last().is_some_and(|&last| first == last + 1);
+        let index = if pattern_index == 0 || consecutive {
+            first
+        } else {
+            (first..lower.len())
+                .find(|&i| {
+                    lower[i] == needle
+                        && is_word_start(chars, i)
+                        && is_subsequence(&pattern[pattern_index + 1..], &lower[i + 1..])
+                })
+                .unwrap_or(first)
+        };
+
+        score += MATCH_SCORE;
+        if chars[index] == pattern_char {
+            score += CASE_BONUS;
+        }
+        if is_word_start(chars, index) {
+            score += WORD_START_BONUS;
+        }
+        if let Some(&last) = indices.last() {
+            if index == last + 1 {
+                score += CONSECUTIVE_BONUS;
+            } else {
+                score -= GAP_PENALTY * (index - last - 1).min(10) as i64;
+            }
+        } else if index == 0 {
+            score += PREFIX_BONUS;
+        }
+
+        indices.push(index);
+        text_index = index + 1;
+    }
+
+    score -= chars.len() as i64 / LENGTH_PENALTY_DIVISOR;
+
+    Some(FuzzyMatch { score, indices })
+}
+
+fn is_subsequence(pattern: &[char], lower: &[char]) -> bool {
+    let mut remaining = lower.iter();
+    pattern.iter().all(|p| {
+        let needle = p.to_lowercase().next().unwrap_or(*p);
+        remaining.any(|&c| c == needle)
+    })
+}
+
+pub fn fuzzy_filter<'a, T, F>(pattern: &str, items: &'a [T], key: F) -> Vec<(&'a T, FuzzyMatch)>
+where
+    F: Fn(&T) -> &str,
+{
+    let mut matches: Vec<(&T, FuzzyMatch)> = items
+        .iter()
+        .filter_map(|item| fuzzy_match(pattern, key(item)).map(|m| (item, m)))
+        .collect();
+
+    matches.sort_by_key(|(_, m)| std::cmp::Reverse(m.score));
+    matches
+}
@@ -215,6 +215,221 @@ impl GitHistoryManager {
         Ok(CommitResult { skipped_files })
     }
 
+    pub fn commit_file_move(
+        &self,
+        old_project_path: &Path,
+        new_project_path: &Path,
+        old_file_path: &Path,
+        new_file_path: &Path,
+    ) -> Result<bool> {
+        let old_repo = self.open_repository(old_project_path)?;
+        let old_relative = Self::moved_relative_path(old_project_path, old_file_path)?;
+        let new_relative = Self::moved_relative_path(new_project_path, new_file_path)?;
+
+        let mut old_index = old_repo
+            .index()
+            .map_err(|e| EditorError::Git(e.to_string()))?;
+        if old_index.get_path(&old_relative, 0).is_none() {
+            return Ok(false);
+        }
+
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let same_repo = self.repo_path(old_project_path)? == self.repo_path(new_project_path)?;
+
+        Self::remove_from_index(&old_repo, &mut old_index, &old_relative)?;
+
+        if same_repo {
+            Self::add_to_index(&old_repo, &mut old_index, new_file_path, &new_relative)?;
+            Self::commit_index(
+                &old_repo,
+                &mut old_index,
+                &format!(
+                    "Rename: {} -> {} at {}",
+                    old_relative.display(),
+                    new_relative.display(),
+                    timestamp
+                ),
+            )?;
+            return Ok(true);
+        }
+
+        Self::commit_index(
+            &old_repo,
+            &mut old_index,
+            &format!(
+                "Moved out: {} -> {} at {}",
+                old_relative.display(),
+                new_file_path.display(),
+                timestamp
+            ),
+        )?;
+
+        let new_repo = self.open_repository(new_project_path)?;
+        let mut new_index = new_repo
+            .index()
+            .map_err(|e| EditorError::Git(e.to_string()))?;
+        Self::add_to_index(&new_repo, &mut new_index, new_file_path, &new_relative)?;
+        Self::commit_index(
+            &new_repo,
+            &mut new_index,
+            &format!(
+                "Moved in: {} -> {} at {}",
+                old_file_path.display(),
+                new_relative.display(),
+                timestamp
+            ),
+        )?;
+
+        Ok(true)
+    }
+
+    // Moves every tracked file under `old_relative` to the same place under
+    // `new_relative`, keeping the committed content, and records it as one
+    // rename. Returns how many files were moved.
+    pub fn commit_directory_move(
+        &self,
+        project_path: &Path,
+        old_relative: &Path,
+        new_relative: &Path,
+    ) -> Result<usize> {
+        let repo = self.open_repository(project_path)?;
+        let mut index = repo.index().map_err(|e| EditorError::Git(e.to_string()))?;
+
+        let entries: Vec<(git2::IndexEntry, PathBuf)> = index
+            .iter()
+            .filter_map(|entry| {
+                let path = PathBuf::from(String::from_utf8_lossy(&entry.path).into_owned());
+                let rest = path.strip_prefix(old_relative).ok()?.to_path_buf();
+                Some((entry, rest))
+            })
+            .collect();
+        if entries.is_empty() {
+            return Ok(0);
+        }
+
+        let workdir = repo.workdir().map(Path::to_path_buf);
+        let moved = entries.len();
+        for (mut entry, rest) in entries {
+            let old_path = old_relative.join(&rest);
+            let new_path = new_relative.join(&rest);
+            index
+                .remove_path(&old_path)
+                .map_err(|e| EditorError::Git(e.to_string()))?;
+            if let Some(workdir) = &workdir {
+                let target = workdir.join(&new_path);
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).map_err(EditorError::Io)?;
+                }
+                let _ = fs::rename(workdir.join(&old_path), target);
+            }
+            entry.path = new_path.to_string_lossy().into_owned().into_bytes();
+            index
+                .add(&entry)
+                .map_err(|e| EditorError::Git(e.to_string()))?;
+        }
+
+        let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        Self::commit_index(
+            &repo,
+            &mut index,
+            &format!(
+                "Rename: {}/ -> {}/ at {}",
+                old_relative.display(),
+                new_relative.display(),
+                timestamp
+            ),
+        )?;
+        Ok(moved)
+    }
+
+    fn moved_relative_path(project_path: &Path, file_path: &Path) -> Result<PathBuf> {
+        let canonical_project = project_path.canonicalize().map_err(EditorError::Io)?;
+        let file_name = file_path.file_name().ok_or_else(|| {
+            EditorError::InvalidOperation(format!("Invalid file path: {}", file_path.display()))
+        })?;
+        let parent = file_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let canonical_file = parent
+            .canonicalize()
+            .map_err(EditorError::Io)?
+            .join(file_name);
+
+        canonical_file
+            .strip_prefix(&canonical_project)
+            .map(|p| p.to_path_buf())
+            .map_err(|_| {
+                EditorError::InvalidOperation(format!(
+                    "File {} is outside project directory {}",
+                    canonical_file.display(),
+                    canonical_project.display()
+                ))
+            })
+    }
+
+    fn remove_from_index(
+        repo: &git2::Repository,
+        index: &mut git2::Index,
+        relative_path: &Path,
+    ) -> Result<()> {
+        if let Some(workdir) = repo.workdir() {
+            let _ = fs::remove_file(workdir.join(relative_path));
+        }
+        index
+            .remove_path(relative_path)
+            .map_err(|e| EditorError::Git(e.to_string()))
+    }
+
+    fn add_to_index(
+        repo: &git2::Repository,
+        index: &mut git2::Index,
+        file_path: &Path,
+        relative_path: &Path,
+    ) -> Result<()> {
+        let repo_file_path = repo
+            .workdir()
+            .ok_or_else(|| EditorError::Git("Repository has no working directory".to_string()))?
+            .join(relative_path);
+
+        if let Some(parent) = repo_file_path.parent() {
+            fs::create_dir_all(parent).map_err(EditorError::Io)?;
+        }
+
+        fs::copy(file_path, &repo_file_path).map_err(EditorError::Io)?;
+
+        index
+            .add_path(relative_path)
+            .map_err(|e| EditorError::Git(e.to_string()))
+    }
+
+    fn commit_index(repo: &git2::Repository, index: &mut git2::Index, message: &str) -> Result<()> {
+        index.write().map_err(|e| EditorError::Git(e.to_string()))?;
+
+        let signature = create_signature()?;
+        let tree_id = index
+            .write_tree()
+            .map_err(|e| EditorError::Git(e.to_string()))?;
+        let tree = repo
+            .find_tree(tree_id)
+            .map_err(|e| EditorError::Git(e.to_string()))?;
+
+        let parent_commit = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<_> = parent_commit.iter().collect();
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .map_err(|e| EditorError::Git(e.to_string()))?;
+
+        Ok(())
+    }
+
     pub fn list_commits(&self, project_path: &Path) -> Result<Vec<CommitInfo>> {
         let repo = self.open_repository(project_path)?;
 
@@ -153,6 +153,78 @@ impl GitHistoryManager {
         Ok(())
     }
 
+    // Moves the history of every directory under a renamed directory to its
+    // new path. The old directories are gone by then, so their repositories
+    // are found from where they used to be. A project repository above the
+    // directory has its tracked paths renamed instead. Returns how many
+    // repositories were updated.
+    pub fn handle_directory_rename(&self, old_dir: &Path, new_dir: &Path) -> Result<usize> {
+        let old_name = old_dir.file_name().ok_or_else(|| {
+            EditorError::InvalidOperation(format!("Invalid directory: {}", old_dir.display()))
+        })?;
+        let old_parent = old_dir
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        let old_root = old_parent
+            .canonicalize()
+            .map_err(EditorError::Io)?
+            .join(old_name);
+        let new_root = new_dir.canonicalize().map_err(EditorError::Io)?;
+
+        let mut directories = vec![new_root.clone()];
+        let mut moved = 0;
+        while let Some(dir) = directories.pop() {
+            for entry in fs::read_dir(&dir).map_err(EditorError::Io)? {
+                let entry = entry.map_err(EditorError::Io)?;
+                if entry.file_type().map_err(EditorError::Io)?.is_dir() {
+                    directories.push(entry.path());
+                }
+            }
+
+            let old_path = match dir.strip_prefix(&new_root) {
+                Ok(rest) if !rest.as_os_str().is_empty() => old_root.join(rest),
+                _ => old_root.clone(),
+            };
+            let old_repo_path = self
+                .storage_root()
+                .join(Self::canonical_path_hash(&old_path));
+            if !old_repo_path.exists() {
+                continue;
+            }
+
+            let new_repo_path = self.repo_path(&dir)?;
+            if new_repo_path.exists() {
+                return Err(EditorError::InvalidOperation(format!(
+                    "Repository for {} already exists",
+                    dir.display()
+                )));
+            }
+            fs::rename(&old_repo_path, &new_repo_path).map_err(EditorError::Io)?;
+
+            let repo = git2::Repository::open(&new_repo_path)
+                .map_err(|e| EditorError::Git(e.to_string()))?;
+            self.write_project_metadata(&repo, &dir)?;
+            moved += 1;
+        }
+
+        if let TrackingMode::Project(project) = self.detect_tracking_mode(&new_root)? {
+            if let (Ok(old_relative), Ok(new_relative)) = (
+                old_root.strip_prefix(&project),
+                new_root.strip_prefix(&project),
+            ) {
+                if !new_relative.as_os_str().is_empty()
+                    && self.repo_path(&project)?.exists()
+                    && self.commit_directory_move(&project, old_relative, new_relative)? > 0
+                {
+                    moved += 1;
+                }
+            }
+        }
+
+        Ok(moved)
+    }
+
     pub fn list_tracked_projects(&self) -> Result<Vec<PathBuf>> {
         let storage_root = self.storage_root();
 
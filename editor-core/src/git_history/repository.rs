@@ -75,7 +75,19 @@ impl GitHistoryManager {
         self.large_file_config = large_file_config;
     }
 
-    fn default_storage_root() -> Result<PathBuf> {
+    pub fn set_gc_config(&mut self, gc_config: GcConfig) {
+        self.gc_config = gc_config;
+    }
+
+    pub fn set_retention_policy(&mut self, retention_policy: RetentionPolicy) {
+        self.retention_policy = retention_policy;
+    }
+
+    pub fn set_storage_root(&mut self, storage_root: PathBuf) {
+        self.storage_root = storage_root;
+    }
+
+    pub fn default_storage_root() -> Result<PathBuf> {
         let home = dirs::home_dir().ok_or_else(|| {
             EditorError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -87,12 +99,17 @@ impl GitHistoryManager {
 
     pub fn project_hash(project_path: &Path) -> Result<String> {
         let canonical_path = project_path.canonicalize().map_err(EditorError::Io)?;
+        Ok(Self::canonical_path_hash(&canonical_path))
+    }
 
+    // The hash of a path already in canonical form, which also works for a
+    // path that no longer exists.
+    pub(crate) fn canonical_path_hash(canonical_path: &Path) -> String {
         let path_str = canonical_path.to_string_lossy();
         let mut hasher = Sha256::new();
         hasher.update(path_str.as_bytes());
         let result = hasher.finalize();
-        Ok(format!("{:x}", result))
+        format!("{:x}", result)
     }
 
     pub fn repo_path(&self, project_path: &Path) -> Result<PathBuf> {
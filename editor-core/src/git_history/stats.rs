@@ -94,7 +94,7 @@ impl GitHistoryManager {
             })
             .collect();
 
-        stats.sort_by(|a, b| b.commit_count.cmp(&a.commit_count));
+        stats.sort_by_key(|b| std::cmp::Reverse(b.commit_count));
 
         Ok(stats)
     }
@@ -26,6 +26,7 @@ pub enum ChangeStatus {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct GcConfig {
     pub enabled: bool,
     pub commits_threshold: usize,
@@ -63,6 +64,7 @@ impl Default for GcConfig {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
 pub enum RetentionPolicy {
     #[default]
     Forever,
@@ -72,6 +74,7 @@ pub enum RetentionPolicy {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum LargeFileStrategy {
     Warn,
     Skip,
@@ -80,6 +83,7 @@ pub enum LargeFileStrategy {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct LargeFileConfig {
     pub threshold_mb: u64,
     pub strategy: LargeFileStrategy,
@@ -137,6 +137,29 @@ impl History {
         self.last_edit_time = Some(now);
     }
 
+    // Keeps the next edit from being grouped with the ones before it.
+    pub fn end_group(&mut self) {
+        self.last_edit_time = None;
+    }
+
+    // Folds every entry from `index` on into one closed entry, so a run of
+    // edits undoes as a single step.
+    pub fn merge_from(&mut self, index: usize) {
+        if index >= self.undo_stack.len() {
+            return;
+        }
+        let mut entries = self.undo_stack.split_off(index).into_iter();
+        if let Some(mut merged) = entries.next() {
+            for entry in entries {
+                merged.edits.extend(entry.edits);
+                merged.cursor_after = entry.cursor_after;
+                merged.selection_after = entry.selection_after;
+            }
+            merged.grouped = false;
+            self.undo_stack.push(merged);
+        }
+    }
+
     pub fn undo(&mut self) -> Result<HistoryEntry> {
         if let Some(entry) = self.undo_stack.pop() {
             self.redo_stack.push(entry.clone());
@@ -164,8 +164,8 @@ impl HistoryBrowser {
     }
 
     fn update_current_page(&mut self) {
-        if self.page_size > 0 {
-            self.current_page = self.selected_index / self.page_size;
+        if let Some(page) = self.selected_index.checked_div(self.page_size) {
+            self.current_page = page;
         }
     }
 
@@ -0,0 +1,121 @@
+use crate::command::Command;
+use crate::error::{EditorError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MACRO_DIR: &str = ".config/editor-rs";
+const MACRO_FILE: &str = "macros.toml";
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct KeyboardMacro {
+    pub commands: Vec<Command>,
+}
+
+impl KeyboardMacro {
+    pub fn new(commands: Vec<Command>) -> Self {
+        Self { commands }
+    }
+
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+// Named macros plus the most recently recorded one, which is what an empty
+// register name refers to.
+#[derive(Debug, Clone, Default)]
+pub struct MacroRegisters {
+    named: BTreeMap<String, KeyboardMacro>,
+    last: Option<KeyboardMacro>,
+}
+
+impl MacroRegisters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&KeyboardMacro> {
+        if name.is_empty() {
+            self.last.as_ref()
+        } else {
+            self.named.get(name)
+        }
+    }
+
+    pub fn set(&mut self, name: &str, recorded: KeyboardMacro) {
+        if !name.is_empty() {
+            self.named.insert(name.to_string(), recorded.clone());
+        }
+        self.last = Some(recorded);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<KeyboardMacro> {
+        self.named.remove(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.named.keys().map(String::as_str)
+    }
+
+    pub fn last(&self) -> Option<&KeyboardMacro> {
+        self.last.as_ref()
+    }
+
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let named: BTreeMap<String, KeyboardMacro> =
+            toml::from_str(content).map_err(|e| EditorError::Parse(e.to_string()))?;
+        if let Some(name) = named.keys().find(|name| name.is_empty()) {
+            return Err(EditorError::Parse(format!(
+                "Macro name '{}' must not be empty",
+                name
+            )));
+        }
+        Ok(Self { named, last: None })
+    }
+
+    pub fn to_toml_string(&self) -> Result<String> {
+        toml::to_string_pretty(&self.named).map_err(|e| EditorError::Parse(e.to_string()))
+    }
+
+    pub fn user_macros_path() -> Result<PathBuf> {
+        let home_dir = std::env::var("HOME").map_err(|_| {
+            EditorError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "HOME environment variable not set",
+            ))
+        })?;
+
+        Ok(PathBuf::from(home_dir).join(MACRO_DIR).join(MACRO_FILE))
+    }
+
+    pub fn load_user() -> Result<Self> {
+        let path = Self::user_macros_path()?;
+        if path.exists() {
+            Self::load_from_file(&path)
+        } else {
+            Ok(Self::new())
+        }
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Self::from_toml_str(&content)
+            .map_err(|e| EditorError::Parse(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn save_to_file(&self, path: &Path) -> Result<()> {
+        let content = self.to_toml_string()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, content)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,801 @@
+use crate::command_registry::CommandRegistry;
+use crate::error::{EditorError, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const KEYMAP_DIR: &str = ".config/editor-rs";
+const KEYMAP_FILE: &str = "keymap.toml";
+const DEFAULT_KEYMAP: &str = include_str!("../keymaps/default.toml");
+const EMACS_KEYMAP: &str = include_str!("../keymaps/emacs.toml");
+
+static DEFAULT_KEYMAPS: Lazy<Keymaps> = Lazy::new(|| {
+    Keymaps::from_toml_str(DEFAULT_KEYMAP, CommandRegistry::builtin())
+        .expect("built-in keymap must be valid")
+});
+
+// The Emacs profile is an overlay on top of the default bindings.
+static EMACS_KEYMAPS: Lazy<Keymaps> = Lazy::new(|| {
+    let mut keymaps = DEFAULT_KEYMAPS.clone();
+    keymaps
+        .merge_toml_str(EMACS_KEYMAP, CommandRegistry::builtin())
+        .expect("built-in emacs keymap must be valid");
+    keymaps
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    F(u8),
+    Enter,
+    Escape,
+    Tab,
+    Backspace,
+    Delete,
+    Insert,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Key {
+    fn parse(name: &str) -> Option<(Self, bool)> {
+        let lower = name.to_ascii_lowercase();
+        let key = match lower.as_str() {
+            "enter" | "return" => Key::Enter,
+            "esc" | "escape" => Key::Escape,
+            "tab" => Key::Tab,
+            "backtab" => return Some((Key::Tab, true)),
+            "backspace" => Key::Backspace,
+            "delete" | "del" => Key::Delete,
+            "insert" | "ins" => Key::Insert,
+            "home" => Key::Home,
+            "end" => Key::End,
+            "pageup" | "pgup" => Key::PageUp,
+            "pagedown" | "pgdown" | "pgdn" => Key::PageDown,
+            "up" => Key::Up,
+            "down" => Key::Down,
+            "left" => Key::Left,
+            "right" => Key::Right,
+            "space" => Key::Char(' '),
+            "plus" => Key::Char('+'),
+            _ => {
+                let mut chars = name.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Key::Char(c.to_ascii_lowercase()),
+                    _ => {
+                        let number = lower.strip_prefix('f')?.parse::<u8>().ok()?;
+                        if !(1..=24).contains(&number) {
+                            return None;
+                        }
+                        Key::F(number)
+                    }
+                }
+            }
+        };
+        Some((key, false))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Char(' ') => write!(f, "Space"),
+            Key::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            Key::F(n) => write!(f, "F{}", n),
+            Key::Enter => write!(f, "Enter"),
+            Key::Escape => write!(f, "Esc"),
+            Key::Tab => write!(f, "Tab"),
+            Key::Backspace => write!(f, "Backspace"),
+            Key::Delete => write!(f, "Delete"),
+            Key::Insert => write!(f, "Insert"),
+            Key::Home => write!(f, "Home"),
+            Key::End => write!(f, "End"),
+            Key::PageUp => write!(f, "PageUp"),
+            Key::PageDown => write!(f, "PageDown"),
+            Key::Up => write!(f, "Up"),
+            Key::Down => write!(f, "Down"),
+            Key::Left => write!(f, "Left"),
+            Key::Right => write!(f, "Right"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct KeyModifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+}
+
+impl KeyModifiers {
+    pub const NONE: Self = Self {
+        ctrl: false,
+        alt: false,
+        shift: false,
+        meta: false,
+    };
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    pub fn is_text_input(&self) -> bool {
+        !self.ctrl && !self.alt && !self.meta
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct KeyPress {
+    pub key: Key,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyPress {
+    pub fn new(key: Key, modifiers: KeyModifiers) -> Self {
+        match key {
+            Key::Char(c) if c.is_ascii_uppercase() => Self {
+                key: Key::Char(c.to_ascii_lowercase()),
+                modifiers: KeyModifiers {
+                    shift: true,
+                    ..modifiers
+                },
+            },
+            _ => Self { key, modifiers },
+        }
+    }
+
+    pub fn plain(key: Key) -> Self {
+        Self::new(key, KeyModifiers::NONE)
+    }
+
+    pub fn ctrl(key: Key) -> Self {
+        Self::new(
+            key,
+            KeyModifiers {
+                ctrl: true,
+                ..KeyModifiers::NONE
+            },
+        )
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let text = text.trim();
+        let (modifier_part, key_part) = if text == "+" {
+            ("", "+")
+        } else if let Some(prefix) = text.strip_suffix("++") {
+            (prefix, "+")
+        } else {
+            match text.rsplit_once('+') {
+                Some((prefix, key)) => (prefix, key),
+                None => ("", text),
+            }
+        };
+
+        let (key, implied_shift) = Key::parse(key_part).ok_or_else(|| {
+            EditorError::Parse(format!("Unknown key '{}' in '{}'", key_part, text))
+        })?;
+
+        let mut modifiers = KeyModifiers {
+            shift: implied_shift,
+            ..KeyModifiers::NONE
+        };
+        for modifier in modifier_part.split('+').filter(|m| !m.is_empty()) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => modifiers.ctrl = true,
+                "alt" | "option" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "meta" | "cmd" | "command" | "super" => modifiers.meta = true,
+                _ => {
+                    return Err(EditorError::Parse(format!(
+                        "Unknown modifier '{}' in '{}'",
+                        modifier, text
+                    )))
+                }
+            }
+        }
+
+        Ok(Self { key, modifiers })
+    }
+}
+
+impl FromStr for KeyPress {
+    type Err = EditorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for KeyPress {
+    type Error = EditorError;
+
+    fn try_from(text: String) -> Result<Self> {
+        Self::parse(&text)
+    }
+}
+
+impl From<KeyPress> for String {
+    fn from(press: KeyPress) -> Self {
+        press.to_string()
+    }
+}
+
+impl fmt::Display for KeyPress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.shift {
+            write!(f, "Shift+")?;
+        }
+        if self.modifiers.meta {
+            write!(f, "Meta+")?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KeySequence(Vec<KeyPress>);
+
+impl KeySequence {
+    pub fn new(presses: Vec<KeyPress>) -> Self {
+        Self(presses)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let presses = text
+            .split_whitespace()
+            .map(KeyPress::parse)
+            .collect::<Result<Vec<_>>>()?;
+        if presses.is_empty() {
+            return Err(EditorError::Parse("Empty key sequence".to_string()));
+        }
+        Ok(Self(presses))
+    }
+
+    pub fn presses(&self) -> &[KeyPress] {
+        &self.0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn is_chord(&self) -> bool {
+        self.0.len() > 1
+    }
+
+    pub fn starts_with(&self, prefix: &[KeyPress]) -> bool {
+        self.0.starts_with(prefix)
+    }
+}
+
+impl FromStr for KeySequence {
+    type Err = EditorError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl fmt::Display for KeySequence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, press) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", press)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeymapMode {
+    Editor,
+    FileTree,
+    SymbolOutline,
+    Completion,
+    Snippet,
+    HistoryBrowser,
+    HistoryStats,
+    Dialog,
+    Menu,
+    Palette,
+}
+
+impl KeymapMode {
+    pub fn all() -> &'static [KeymapMode] {
+        &[
+            KeymapMode::Editor,
+            KeymapMode::FileTree,
+            KeymapMode::SymbolOutline,
+            KeymapMode::Completion,
+            KeymapMode::Snippet,
+            KeymapMode::HistoryBrowser,
+            KeymapMode::HistoryStats,
+            KeymapMode::Dialog,
+            KeymapMode::Menu,
+            KeymapMode::Palette,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeymapMode::Editor => "editor",
+            KeymapMode::FileTree => "file_tree",
+            KeymapMode::SymbolOutline => "symbol_outline",
+            KeymapMode::Completion => "completion",
+            KeymapMode::Snippet => "snippet",
+            KeymapMode::HistoryBrowser => "history_browser",
+            KeymapMode::HistoryStats => "history_stats",
+            KeymapMode::Dialog => "dialog",
+            KeymapMode::Menu => "menu",
+            KeymapMode::Palette => "palette",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|mode| mode.name() == name)
+    }
+}
+
+impl fmt::Display for KeymapMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapProfile {
+    #[default]
+    Default,
+    Emacs,
+}
+
+impl KeymapProfile {
+    pub fn all() -> &'static [KeymapProfile] {
+        &[KeymapProfile::Default, KeymapProfile::Emacs]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeymapProfile::Default => "default",
+            KeymapProfile::Emacs => "emacs",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|profile| profile.name() == name)
+    }
+}
+
+impl fmt::Display for KeymapProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyMatch {
+    Command(&'static str),
+    Prefix,
+    None,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: Vec<(KeySequence, &'static str)>,
+    // Arguments passed to a command bound with them, such as the macro name
+    // in "tools.play_macro tidy".
+    arguments: HashMap<KeySequence, Vec<String>>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bind(&mut self, sequence: KeySequence, command: &'static str) {
+        self.unbind(&sequence);
+        self.bindings.push((sequence, command));
+    }
+
+    pub fn bind_with_arguments(
+        &mut self,
+        sequence: KeySequence,
+        command: &'static str,
+        arguments: Vec<String>,
+    ) {
+        self.bind(sequence.clone(), command);
+        if !arguments.is_empty() {
+            self.arguments.insert(sequence, arguments);
+        }
+    }
+
+    pub fn unbind(&mut self, sequence: &KeySequence) -> bool {
+        self.arguments.remove(sequence);
+        let before = self.bindings.len();
+        self.bindings.retain(|(existing, _)| existing != sequence);
+        self.bindings.len() != before
+    }
+
+    pub fn unbind_command(&mut self, command: &str) {
+        let arguments = &mut self.arguments;
+        self.bindings.retain(|(sequence, existing)| {
+            if *existing == command {
+                arguments.remove(sequence);
+            }
+            *existing != command
+        });
+    }
+
+    pub fn arguments(&self, presses: &[KeyPress]) -> &[String] {
+        self.arguments
+            .get(&KeySequence::new(presses.to_vec()))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    pub fn bindings(&self) -> &[(KeySequence, &'static str)] {
+        &self.bindings
+    }
+
+    pub fn sequences_for(&self, command: &str) -> Vec<&KeySequence> {
+        self.bindings
+            .iter()
+            .filter(|(_, existing)| *existing == command)
+            .map(|(sequence, _)| sequence)
+            .collect()
+    }
+
+    pub fn lookup(&self, presses: &[KeyPress]) -> KeyMatch {
+        if let Some((_, command)) = self
+            .bindings
+            .iter()
+            .find(|(sequence, _)| sequence.presses() == presses)
+        {
+            return KeyMatch::Command(command);
+        }
+
+        if self
+            .bindings
+            .iter()
+            .any(|(sequence, _)| sequence.len() > presses.len() && sequence.starts_with(presses))
+        {
+            KeyMatch::Prefix
+        } else {
+            KeyMatch::None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeymapConflictKind {
+    Duplicate,
+    Prefix,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeymapConflict {
+    pub mode: KeymapMode,
+    pub kind: KeymapConflictKind,
+    pub sequence: KeySequence,
+    pub command: &'static str,
+    pub other_sequence: KeySequence,
+    pub other_command: &'static str,
+}
+
+impl fmt::Display for KeymapConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            KeymapConflictKind::Duplicate => write!(
+                f,
+                "[{}] '{}' is bound to both {} and {}",
+                self.mode, self.sequence, self.command, self.other_command
+            ),
+            KeymapConflictKind::Prefix => write!(
+                f,
+                "[{}] '{}' ({}) shadows '{}' ({})",
+                self.mode, self.sequence, self.command, self.other_sequence, self.other_command
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymaps {
+    keymaps: HashMap<KeymapMode, Keymap>,
+}
+
+impl Keymaps {
+    pub fn empty() -> Self {
+        Self {
+            keymaps: HashMap::new(),
+        }
+    }
+
+    pub fn builtin() -> &'static Keymaps {
+        &DEFAULT_KEYMAPS
+    }
+
+    pub fn builtin_profile(profile: KeymapProfile) -> &'static Keymaps {
+        match profile {
+            KeymapProfile::Default => &DEFAULT_KEYMAPS,
+            KeymapProfile::Emacs => &EMACS_KEYMAPS,
+        }
+    }
+
+    pub fn default_toml() -> &'static str {
+        DEFAULT_KEYMAP
+    }
+
+    pub fn from_toml_str(content: &str, registry: &CommandRegistry) -> Result<Self> {
+        let mut keymaps = Self::empty();
+        keymaps.merge_toml_str(content, registry)?;
+        Ok(keymaps)
+    }
+
+    pub fn user_keymap_path() -> Result<PathBuf> {
+        let home_dir = std::env::var("HOME").map_err(|_| {
+            EditorError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "HOME environment variable not set",
+            ))
+        })?;
+
+        Ok(PathBuf::from(home_dir).join(KEYMAP_DIR).join(KEYMAP_FILE))
+    }
+
+    pub fn load_user(registry: &CommandRegistry) -> Result<Self> {
+        Self::load_user_profile(KeymapProfile::Default, registry)
+    }
+
+    pub fn load_user_profile(profile: KeymapProfile, registry: &CommandRegistry) -> Result<Self> {
+        let mut keymaps = Self::builtin_profile(profile).clone();
+        let path = Self::user_keymap_path()?;
+        if path.exists() {
+            keymaps.merge_file(&path, registry)?;
+        }
+        Ok(keymaps)
+    }
+
+    pub fn merge_file(&mut self, path: &Path, registry: &CommandRegistry) -> Result<()> {
+        let content = fs::read_to_string(path)?;
+        self.merge_toml_str(&content, registry)
+            .map_err(|e| EditorError::Parse(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn merge_toml_str(&mut self, content: &str, registry: &CommandRegistry) -> Result<()> {
+        let document: toml::Table =
+            toml::from_str(content).map_err(|e| EditorError::Parse(e.to_string()))?;
+
+        let mut parsed = Vec::new();
+        for (mode_name, table) in &document {
+            let mode = KeymapMode::from_name(mode_name).ok_or_else(|| {
+                EditorError::Parse(format!("Unknown keymap mode '{}'", mode_name))
+            })?;
+            let table = table.as_table().ok_or_else(|| {
+                EditorError::Parse(format!("Keymap mode '{}' must be a table", mode_name))
+            })?;
+
+            let mut entries = Vec::new();
+            for (sequence, command) in table {
+                let sequence = KeySequence::parse(sequence)?;
+                let command = command.as_str().ok_or_else(|| {
+                    EditorError::Parse(format!(
+                        "Binding for '{}' must be a command id string",
+                        sequence
+                    ))
+                })?;
+                // Anything after the command id is passed to it as its
+                // whitespace separated arguments.
+                let mut words = command.split_whitespace();
+                let command = match words.next() {
+                    None => None,
+                    Some(command) => {
+                        let spec = registry.get(command).ok_or_else(|| {
+                            EditorError::Parse(format!("Unknown command '{}'", command))
+                        })?;
+                        let arguments: Vec<String> = words.map(str::to_string).collect();
+                        if !arguments.is_empty() {
+                            spec.resolve(&arguments).map_err(|e| {
+                                EditorError::Parse(format!("Binding for '{}': {}", sequence, e))
+                            })?;
+                        }
+                        Some((spec.id, arguments))
+                    }
+                };
+                entries.push((sequence, command));
+            }
+            parsed.push((mode, entries));
+        }
+
+        for (mode, entries) in parsed {
+            let keymap = self.keymaps.entry(mode).or_default();
+            for (sequence, _) in &entries {
+                keymap.unbind(sequence);
+            }
+            for (sequence, command) in entries {
+                if let Some((command, arguments)) = command {
+                    if !arguments.is_empty() {
+                        keymap.arguments.insert(sequence.clone(), arguments);
+                    }
+                    keymap.bindings.push((sequence, command));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn keymap(&self, mode: KeymapMode) -> Option<&Keymap> {
+        self.keymaps.get(&mode)
+    }
+
+    pub fn keymap_mut(&mut self, mode: KeymapMode) -> &mut Keymap {
+        self.keymaps.entry(mode).or_default()
+    }
+
+    pub fn lookup(&self, mode: KeymapMode, presses: &[KeyPress]) -> KeyMatch {
+        self.keymap(mode)
+            .map(|keymap| keymap.lookup(presses))
+            .unwrap_or(KeyMatch::None)
+    }
+
+    pub fn bind_in_all_modes(&mut self, sequence: KeySequence, command: &'static str) {
+        for mode in KeymapMode::all() {
+            self.keymap_mut(*mode).bind(sequence.clone(), command);
+        }
+    }
+
+    pub fn rebind_command(&mut self, command: &'static str, sequence: KeySequence) {
+        for keymap in self.keymaps.values_mut() {
+            if keymap
+                .bindings
+                .iter()
+                .any(|(_, existing)| *existing == command)
+            {
+                keymap.unbind_command(command);
+                keymap.bind(sequence.clone(), command);
+            }
+        }
+    }
+
+    pub fn conflicts(&self) -> Vec<KeymapConflict> {
+        let mut conflicts = Vec::new();
+
+        for mode in KeymapMode::all() {
+            let Some(keymap) = self.keymap(*mode) else {
+                continue;
+            };
+            let bindings = keymap.bindings();
+
+            for (i, (sequence, command)) in bindings.iter().enumerate() {
+                for (j, (other_sequence, other_command)) in bindings.iter().enumerate() {
+                    let kind = if i < j && sequence == other_sequence {
+                        KeymapConflictKind::Duplicate
+                    } else if sequence.len() < other_sequence.len()
+                        && other_sequence.starts_with(sequence.presses())
+                    {
+                        KeymapConflictKind::Prefix
+                    } else {
+                        continue;
+                    };
+
+                    conflicts.push(KeymapConflict {
+                        mode: *mode,
+                        kind,
+                        sequence: sequence.clone(),
+                        command,
+                        other_sequence: other_sequence.clone(),
+                        other_command,
+                    });
+                }
+            }
+        }
+
+        conflicts
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyResolution {
+    Command(&'static str),
+    Pending,
+    Cancelled,
+    Unbound,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct KeySequenceResolver {
+    pending: Vec<KeyPress>,
+    mode: Option<KeymapMode>,
+    arguments: Vec<String>,
+}
+
+impl KeySequenceResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    pub fn pending(&self) -> &[KeyPress] {
+        &self.pending
+    }
+
+    pub fn pending_display(&self) -> Option<String> {
+        if self.pending.is_empty() {
+            None
+        } else {
+            Some(KeySequence::new(self.pending.clone()).to_string())
+        }
+    }
+
+    // The arguments bound along with the command the last key resolved to.
+    pub fn take_arguments(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.arguments)
+    }
+
+    pub fn reset(&mut self) {
+        self.pending.clear();
+        self.mode = None;
+    }
+
+    pub fn feed(&mut self, keymaps: &Keymaps, mode: KeymapMode, press: KeyPress) -> KeyResolution {
+        if self.mode != Some(mode) {
+            self.pending.clear();
+            self.mode = Some(mode);
+        }
+
+        let was_pending = self.is_pending();
+        self.pending.push(press);
+
+        match keymaps.lookup(mode, &self.pending) {
+            KeyMatch::Command(command) => {
+                self.arguments = keymaps
+                    .keymap(mode)
+                    .map(|keymap| keymap.arguments(&self.pending).to_vec())
+                    .unwrap_or_default();
+                self.pending.clear();
+                KeyResolution::Command(command)
+            }
+            KeyMatch::Prefix => KeyResolution::Pending,
+            KeyMatch::None => {
+                self.pending.clear();
+                if was_pending {
+                    KeyResolution::Cancelled
+                } else {
+                    KeyResolution::Unbound
+                }
+            }
+        }
+    }
+}
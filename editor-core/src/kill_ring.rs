@@ -0,0 +1,80 @@
+use std::collections::VecDeque;
+
+const DEFAULT_CAPACITY: usize = 60;
+
+#[derive(Debug, Clone)]
+pub struct KillRing {
+    entries: VecDeque<String>,
+    capacity: usize,
+    yank_index: usize,
+}
+
+impl KillRing {
+    pub fn new() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            yank_index: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+
+    pub fn push(&mut self, text: impl Into<String>) {
+        let text = text.into();
+        if text.is_empty() {
+            return;
+        }
+        self.entries.push_front(text);
+        self.entries.truncate(self.capacity);
+        self.yank_index = 0;
+    }
+
+    // Successive kills grow the newest entry instead of adding new ones;
+    // kills going backwards put their text in front of it.
+    pub fn append(&mut self, text: &str, before: bool) {
+        match self.entries.front_mut() {
+            Some(entry) if before => entry.insert_str(0, text),
+            Some(entry) => entry.push_str(text),
+            None => self.push(text),
+        }
+        self.yank_index = 0;
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.entries.get(self.yank_index).map(String::as_str)
+    }
+
+    pub fn reset_yank(&mut self) -> Option<&str> {
+        self.yank_index = 0;
+        self.current()
+    }
+
+    pub fn rotate(&mut self) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        self.yank_index = (self.yank_index + 1) % self.entries.len();
+        self.current()
+    }
+}
+
+impl Default for KillRing {
+    fn default() -> Self {
+        Self::new()
+    }
+}
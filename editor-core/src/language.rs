@@ -0,0 +1,494 @@
+use crate::error::{EditorError, Result};
+use crate::symbols::{SymbolKind, SymbolPattern};
+use crate::syntax::SyntaxLanguage;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const LANGUAGES_DIR: &str = ".config/editor-rs";
+const LANGUAGES_FILE: &str = "languages.toml";
+const DEFAULT_LANGUAGES: &str = include_str!("../languages/default.toml");
+const MODELINE_LINES: usize = 5;
+pub const PLAIN_TEXT: &str = "text";
+
+static DEFAULT_REGISTRY: Lazy<LanguageRegistry> = Lazy::new(|| {
+    LanguageRegistry::from_toml_str(DEFAULT_LANGUAGES).expect("built-in languages must be valid")
+});
+
+static PLAIN_TEXT_LANGUAGE: Lazy<LanguageDefinition> = Lazy::new(|| {
+    LanguageRegistry::builtin()
+        .get(PLAIN_TEXT)
+        .cloned()
+        .unwrap_or_else(|| LanguageDefinition::new(PLAIN_TEXT))
+});
+
+static VIM_MODELINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:^|\s)(?:vim?|ex):.*?\b(?:ft|filetype|syntax)=([\w+.-]+)")
+        .expect("vim modeline pattern must compile")
+});
+
+static EMACS_MODELINE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"-\*-\s*(.*?)\s*-\*-").expect("emacs modeline pattern must compile"));
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndentRules {
+    pub indent_after: Vec<String>,
+    pub dedent_before: Vec<String>,
+}
+
+impl IndentRules {
+    pub fn increases_after(&self, line: &str) -> bool {
+        let line = line.trim_end();
+        self.indent_after.iter().any(|token| {
+            line.strip_suffix(token.as_str()).is_some_and(|rest| {
+                !is_word(token) || !rest.chars().next_back().is_some_and(is_word_char)
+            })
+        })
+    }
+
+    pub fn decreases_before(&self, line: &str) -> bool {
+        let line = line.trim_start();
+        self.dedent_before.iter().any(|token| {
+            line.strip_prefix(token.as_str()).is_some_and(|rest| {
+                !is_word(token) || !rest.chars().next().is_some_and(is_word_char)
+            })
+        })
+    }
+
+    pub fn is_electric(&self, ch: char) -> bool {
+        self.dedent_before
+            .iter()
+            .any(|token| token.chars().eq(std::iter::once(ch)))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageServerConfig {
+    pub command: String,
+    pub args: Vec<String>,
+    pub language_id: Option<String>,
+}
+
+impl LanguageServerConfig {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            args: Vec::new(),
+            language_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageDefinition {
+    pub name: String,
+    pub extensions: Vec<String>,
+    pub filenames: Vec<String>,
+    pub shebangs: Vec<String>,
+    pub syntax: Option<SyntaxLanguage>,
+    pub line_comment: Option<String>,
+    pub block_comment: Option<(String, String)>,
+    pub brackets: Vec<(char, char)>,
+    pub auto_close: Vec<(char, char)>,
+    pub indent: IndentRules,
+    pub symbols: Vec<SymbolPattern>,
+    pub language_server: Option<LanguageServerConfig>,
+}
+
+impl LanguageDefinition {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            extensions: Vec::new(),
+            filenames: Vec::new(),
+            shebangs: Vec::new(),
+            syntax: None,
+            line_comment: None,
+            block_comment: None,
+            brackets: Vec::new(),
+            auto_close: Vec::new(),
+            indent: IndentRules::default(),
+            symbols: Vec::new(),
+            language_server: None,
+        }
+    }
+
+    pub fn bracket_pair(&self, ch: char) -> Option<(char, char, bool)> {
+        self.brackets.iter().find_map(|&(open, close)| {
+            if ch == open {
+                Some((open, close, true))
+            } else if ch == close {
+                Some((open, close, false))
+            } else {
+                None
+            }
+        })
+    }
+
+    pub fn closing_for(&self, ch: char) -> Option<char> {
+        self.auto_close
+            .iter()
+            .find(|(open, _)| *open == ch)
+            .map(|(_, close)| *close)
+    }
+
+    pub fn is_closing_bracket(&self, ch: char) -> bool {
+        self.brackets.iter().any(|(_, close)| *close == ch)
+    }
+
+    pub fn language_id(&self) -> &str {
+        self.language_server
+            .as_ref()
+            .and_then(|server| server.language_id.as_deref())
+            .unwrap_or(&self.name)
+    }
+
+    fn matches_name(&self, name: &str) -> bool {
+        self.name.eq_ignore_ascii_case(name)
+            || self
+                .extensions
+                .iter()
+                .any(|extension| extension.eq_ignore_ascii_case(name))
+            || self.shebangs.iter().any(|shebang| shebang == name)
+    }
+
+    fn apply(&mut self, entry: LanguageEntry) -> Result<()> {
+        if let Some(extensions) = entry.extensions {
+            self.extensions = extensions;
+        }
+        if let Some(filenames) = entry.filenames {
+            self.filenames = filenames;
+        }
+        if let Some(shebangs) = entry.shebangs {
+            self.shebangs = shebangs;
+        }
+        if let Some(syntax) = entry.syntax {
+            self.syntax = if syntax.is_empty() {
+                None
+            } else {
+                Some(SyntaxLanguage::from_name(&syntax).ok_or_else(|| {
+                    EditorError::Parse(format!(
+                        "Language '{}' uses unknown syntax '{}'",
+                        self.name, syntax
+                    ))
+                })?)
+            };
+        }
+        if let Some(line_comment) = entry.line_comment {
+            self.line_comment = (!line_comment.is_empty()).then_some(line_comment);
+        }
+        if let Some([start, end]) = entry.block_comment {
+            self.block_comment = (!start.is_empty() && !end.is_empty()).then_some((start, end));
+        }
+        if let Some(brackets) = entry.brackets {
+            self.brackets = parse_pairs(&self.name, "brackets", &brackets)?;
+        }
+        if let Some(auto_close) = entry.auto_close {
+            self.auto_close = parse_pairs(&self.name, "auto_close", &auto_close)?;
+        }
+        if let Some(indent_after) = entry.indent_after {
+            self.indent.indent_after = indent_after;
+        }
+        if let Some(dedent_before) = entry.dedent_before {
+            self.indent.dedent_before = dedent_before;
+        }
+        if let Some(symbols) = entry.symbols {
+            self.symbols = parse_symbol_patterns(&self.name, symbols)?;
+        }
+        if let Some(server) = entry.language_server {
+            self.language_server = (!server.command.is_empty()).then(|| LanguageServerConfig {
+                command: server.command,
+                args: server.args.unwrap_or_default(),
+                language_id: server.language_id,
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LanguageEntry {
+    name: String,
+    extensions: Option<Vec<String>>,
+    filenames: Option<Vec<String>>,
+    shebangs: Option<Vec<String>>,
+    syntax: Option<String>,
+    line_comment: Option<String>,
+    block_comment: Option<[String; 2]>,
+    brackets: Option<Vec<String>>,
+    auto_close: Option<Vec<String>>,
+    indent_after: Option<Vec<String>>,
+    dedent_before: Option<Vec<String>>,
+    symbols: Option<Vec<SymbolEntry>>,
+    language_server: Option<LanguageServerEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SymbolEntry {
+    kind: String,
+    pattern: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LanguageServerEntry {
+    command: String,
+    args: Option<Vec<String>>,
+    language_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LanguagesFile {
+    #[serde(default)]
+    language: Vec<LanguageEntry>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LanguageRegistry {
+    languages: Vec<LanguageDefinition>,
+}
+
+impl LanguageRegistry {
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub fn builtin() -> &'static LanguageRegistry {
+        &DEFAULT_REGISTRY
+    }
+
+    pub fn default_toml() -> &'static str {
+        DEFAULT_LANGUAGES
+    }
+
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let mut registry = Self::empty();
+        registry.merge_toml_str(content)?;
+        Ok(registry)
+    }
+
+    pub fn user_languages_path() -> Result<PathBuf> {
+        let home_dir = std::env::var("HOME").map_err(|_| {
+            EditorError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "HOME environment variable not set",
+            ))
+        })?;
+
+        Ok(PathBuf::from(home_dir)
+            .join(LANGUAGES_DIR)
+            .join(LANGUAGES_FILE))
+    }
+
+    pub fn load_user() -> Result<Self> {
+        let mut registry = Self::builtin().clone();
+        let path = Self::user_languages_path()?;
+        if path.exists() {
+            registry.merge_file(&path)?;
+        }
+        Ok(registry)
+    }
+
+    pub fn merge_file(&mut self, path: &Path) -> Result<()> {
+        let content = fs::read_to_string(path)?;
+        self.merge_toml_str(&content)
+            .map_err(|e| EditorError::Parse(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn merge_toml_str(&mut self, content: &str) -> Result<()> {
+        let file: LanguagesFile =
+            toml::from_str(content).map_err(|e| EditorError::Parse(e.to_string()))?;
+
+        let mut merged = self.languages.clone();
+        for entry in file.language {
+            let name = entry.name.trim().to_string();
+            if name.is_empty() {
+                return Err(EditorError::Parse(
+                    "Language name cannot be empty".to_string(),
+                ));
+            }
+
+            match merged
+                .iter_mut()
+                .find(|language| language.name.eq_ignore_ascii_case(&name))
+            {
+                Some(language) => language.apply(entry)?,
+                None => {
+                    let mut language = LanguageDefinition::new(name);
+                    language.apply(entry)?;
+                    merged.push(language);
+                }
+            }
+        }
+
+        self.languages = merged;
+        Ok(())
+    }
+
+    pub fn languages(&self) -> &[LanguageDefinition] {
+        &self.languages
+    }
+
+    pub fn get(&self, name: &str) -> Option<&LanguageDefinition> {
+        self.languages
+            .iter()
+            .find(|language| language.name.eq_ignore_ascii_case(name))
+    }
+
+    pub fn fallback(&self) -> &LanguageDefinition {
+        self.get(PLAIN_TEXT).unwrap_or(&PLAIN_TEXT_LANGUAGE)
+    }
+
+    pub fn detect(&self, path: Option<&Path>, content: &str) -> Option<&LanguageDefinition> {
+        if let Some(language) = modeline_language(content).and_then(|name| self.for_name(&name)) {
+            return Some(language);
+        }
+        if let Some(language) = path.and_then(|path| self.for_path(path)) {
+            return Some(language);
+        }
+        shebang_interpreter(content).and_then(|interpreter| self.for_interpreter(&interpreter))
+    }
+
+    pub fn for_name(&self, name: &str) -> Option<&LanguageDefinition> {
+        self.get(name).or_else(|| {
+            self.languages
+                .iter()
+                .rev()
+                .find(|language| language.matches_name(name))
+        })
+    }
+
+    pub fn for_path(&self, path: &Path) -> Option<&LanguageDefinition> {
+        let file_name = path.file_name()?.to_str()?;
+        if let Some(language) = self
+            .languages
+            .iter()
+            .rev()
+            .find(|language| language.filenames.iter().any(|name| name == file_name))
+        {
+            return Some(language);
+        }
+
+        let extension = path.extension()?.to_str()?;
+        self.languages.iter().rev().find(|language| {
+            language
+                .extensions
+                .iter()
+                .any(|candidate| candidate.eq_ignore_ascii_case(extension))
+        })
+    }
+
+    pub fn for_interpreter(&self, interpreter: &str) -> Option<&LanguageDefinition> {
+        let find = |name: &str| {
+            self.languages
+                .iter()
+                .rev()
+                .find(|language| language.shebangs.iter().any(|shebang| shebang == name))
+        };
+        find(interpreter).or_else(|| {
+            let unversioned =
+                interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+            find(unversioned)
+        })
+    }
+}
+
+pub fn modeline_language(content: &str) -> Option<String> {
+    let lines: Vec<&str> = content.lines().collect();
+    let tail_start = lines
+        .len()
+        .saturating_sub(MODELINE_LINES)
+        .max(MODELINE_LINES);
+    let head = lines.iter().take(MODELINE_LINES);
+    let tail = lines.iter().skip(tail_start);
+
+    for line in head.chain(tail) {
+        if let Some(captures) = VIM_MODELINE.captures(line) {
+            return Some(captures[1].to_string());
+        }
+        if let Some(captures) = EMACS_MODELINE.captures(line) {
+            if let Some(mode) = emacs_mode(&captures[1]) {
+                return Some(mode);
+            }
+        }
+    }
+    None
+}
+
+pub fn shebang_interpreter(content: &str) -> Option<String> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let program = base_name(words.next()?);
+    if program != "env" {
+        return Some(program.to_string());
+    }
+    words
+        .find(|word| !word.starts_with('-') && !word.contains('='))
+        .map(|word| base_name(word).to_string())
+}
+
+fn emacs_mode(variables: &str) -> Option<String> {
+    if !variables.contains(':') {
+        let mode = variables.trim();
+        return (!mode.is_empty()).then(|| mode.to_string());
+    }
+    variables.split(';').find_map(|variable| {
+        let (key, value) = variable.split_once(':')?;
+        key.trim()
+            .eq_ignore_ascii_case("mode")
+            .then(|| value.trim().to_string())
+    })
+}
+
+fn base_name(program: &str) -> &str {
+    program.rsplit('/').next().unwrap_or(program)
+}
+
+fn parse_pairs(language: &str, field: &str, pairs: &[String]) -> Result<Vec<(char, char)>> {
+    pairs
+        .iter()
+        .map(|pair| {
+            let mut chars = pair.chars();
+            match (chars.next(), chars.next(), chars.next()) {
+                (Some(open), Some(close), None) => Ok((open, close)),
+                _ => Err(EditorError::Parse(format!(
+                    "Language '{}' {} entry '{}' must be exactly two characters",
+                    language, field, pair
+                ))),
+            }
+        })
+        .collect()
+}
+
+fn parse_symbol_patterns(language: &str, entries: Vec<SymbolEntry>) -> Result<Vec<SymbolPattern>> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let kind = SymbolKind::from_name(&entry.kind).ok_or_else(|| {
+                EditorError::Parse(format!(
+                    "Language '{}' uses unknown symbol kind '{}'",
+                    language, entry.kind
+                ))
+            })?;
+            SymbolPattern::new(kind, entry.pattern).map_err(|e| match e {
+                EditorError::Parse(message) => {
+                    EditorError::Parse(format!("Language '{}': {}", language, message))
+                }
+                other => other,
+            })
+        })
+        .collect()
+}
+
+fn is_word(token: &str) -> bool {
+    token.chars().all(is_word_char)
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
@@ -1,26 +1,75 @@
 pub mod bookmark;
 pub mod buffer;
 pub mod clipboard;
+pub mod clipboard_history;
 pub mod command;
+pub mod command_palette;
+pub mod command_registry;
+pub mod completion;
+pub mod config;
 pub mod cursor;
 pub mod diff_parser;
 pub mod editor;
+pub mod editorconfig;
 pub mod error;
+pub mod file_tree;
+pub mod folding;
+pub mod fuzzy;
 pub mod git_history;
 pub mod history;
 pub mod history_browser;
+pub mod keyboard_macro;
+pub mod keymap;
+pub mod kill_ring;
+pub mod language;
+pub mod lsp;
+#[cfg(unix)]
+pub mod remote;
+pub mod script;
 pub mod selection;
 pub mod session;
+pub mod snippet;
+pub mod split;
+pub mod symbol_index;
+pub mod symbol_picker;
+pub mod symbols;
+pub mod syntax;
+pub mod text_change;
 pub mod view;
+pub mod vim;
 
 pub use bookmark::{Bookmark, BookmarkManager, FileBookmarks};
 pub use buffer::{Buffer, Encoding, LineEnding};
-pub use clipboard::ClipboardManager;
+pub use clipboard::{ClipboardBackend, ClipboardEnvironment, ClipboardManager};
+pub use clipboard_history::{
+    ClipboardEntry, ClipboardHistory, ClipboardPicker, ClipboardPickerItem,
+    DEFAULT_CLIPBOARD_HISTORY_SIZE,
+};
 pub use command::{CaseMode, Command};
+pub use command_palette::{CommandPalette, PaletteItem, PaletteOutcome};
+pub use command_registry::{
+    ArgumentKind, ArgumentSpec, CommandAction, CommandCategory, CommandRegistry, CommandSpec,
+};
+pub use completion::{
+    BufferWordSource, CompletionContext, CompletionItem, CompletionMenu, CompletionMenuItem,
+    CompletionSource, PathSource, SnippetSource,
+};
+pub use config::{
+    ConfigLayer, ConfigLoader, ConfigWatcher, EditorConfig, EditorSettings, HistorySettings,
+    ViewSettings, PROJECT_CONFIG_FILE,
+};
 pub use cursor::{CursorPosition, MultiCursor};
 pub use diff_parser::{DiffLine, DiffLineType, SideBySideDiff};
 pub use editor::{EditorState, VirtualViewport};
+pub use editorconfig::{
+    EditorConfigFile, EditorConfigProperties, IndentSize, IndentStyle, EDITORCONFIG_FILE,
+};
 pub use error::{EditorError, Result};
+pub use file_tree::{FileStatus, FileTree, FileTreeEntry, FileTreeEntryKind};
+pub use folding::{
+    fold_depths, indentation_fold_ranges, syntax_fold_ranges, FileFolds, FoldRange, FoldState,
+};
+pub use fuzzy::{fuzzy_filter, fuzzy_match, FuzzyMatch};
 pub use git_history::{
     create_signature, format_graph_line, generate_commit_graph, ChangeStatus, CleanupStats,
     CommitAnnotations, CommitGraphNode, CommitInfo, CommitResult, FileChange, FileSizeInfo,
@@ -29,6 +78,42 @@ pub use git_history::{
 };
 pub use history::{Edit, History, HistoryEntry};
 pub use history_browser::{DiffViewMode, HistoryBrowser};
+pub use keyboard_macro::{KeyboardMacro, MacroRegisters};
+pub use keymap::{
+    Key, KeyMatch, KeyModifiers, KeyPress, KeyResolution, KeySequence, KeySequenceResolver, Keymap,
+    KeymapConflict, KeymapConflictKind, KeymapMode, KeymapProfile, Keymaps,
+};
+pub use kill_ring::KillRing;
+pub use language::{
+    modeline_language, shebang_interpreter, IndentRules, LanguageDefinition, LanguageRegistry,
+    LanguageServerConfig, PLAIN_TEXT,
+};
+pub use lsp::{
+    Diagnostic, DiagnosticSeverity, LspClient, LspCompletionItem, LspEvent, LspPosition, LspRange,
+};
+#[cfg(unix)]
+pub use remote::{RemoteClient, RemoteServer};
+pub use script::BatchScript;
 pub use selection::{Selection, SelectionMode};
-pub use session::{OpenFileState, Session, SessionManager};
+pub use session::{OpenFileState, Session, SessionLayout, SessionManager, SplitPaneState};
+pub use snippet::{
+    expand_snippet, parse_snippet, ExpandedSnippet, Snippet, SnippetOptions, SnippetRegistry,
+    SnippetSession, SnippetTabstop,
+};
+pub use split::{PaneDirection, SplitDirection, SplitLayout, SplitNode, SplitRect};
+pub use symbol_index::{SymbolIndex, SymbolLocation};
+pub use symbol_picker::{SymbolPicker, SymbolPickerItem, SymbolScope};
+pub use symbols::{
+    document_symbols, pattern_symbols, syntax_symbols, OutlineEntry, Symbol, SymbolKind,
+    SymbolOutline, SymbolPattern,
+};
+pub use syntax::{
+    highlight_segments, HighlightKind, HighlightSpan, SyntaxHighlighter, SyntaxLanguage,
+};
+pub use text_change::{ChangeLog, TextChange, TextPosition};
 pub use view::EditorView;
+pub use vim::{
+    parse_ex_command, parse_vim_keys, vim_key_char, ExCommand, ExContext, InsertPosition,
+    MotionKind, SortOptions, VimAction, VimCommand, VimMotion, VimOperator, VimParse, VimRegister,
+    VimRegisters, VimTarget, VimTextObject, VisualKind,
+};
@@ -0,0 +1,16 @@
+mod client;
+mod position;
+mod protocol;
+mod types;
+
+pub use client::{DocumentState, DocumentSyncKind, LspClient, LspEvent};
+pub use position::{
+    from_lsp_position, path_to_uri, to_char_index, to_lsp_position, uri_to_path, LspPosition,
+    LspRange,
+};
+pub use protocol::{read_message, write_message, LspMessage, ResponseError};
+pub use types::{
+    completion_kind_name, parse_completion_items, parse_diagnostics, parse_hover, parse_locations,
+    parse_text_edits, parse_workspace_edit, snippet_text, Diagnostic, DiagnosticSeverity, Location,
+    LspCompletionItem, TextEdit, WorkspaceEdit,
+};
@@ -0,0 +1,630 @@
+use super::position::{path_to_uri, LspPosition};
+use super::protocol::{read_message, write_message, LspMessage};
+use super::types::{
+    parse_completion_items, parse_diagnostics, parse_hover, parse_locations, parse_text_edits,
+    parse_workspace_edit, Diagnostic, Location, LspCompletionItem, TextEdit, WorkspaceEdit,
+};
+use crate::error::{EditorError, Result};
+use crate::language::LanguageServerConfig;
+use crate::text_change::TextChange;
+use ropey::Rope;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_millis(500);
+const REQUEST_CANCELLED: i64 = -32800;
+const CONTENT_MODIFIED: i64 = -32801;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LspEvent {
+    Initialized,
+    Diagnostics {
+        path: PathBuf,
+        diagnostics: Vec<Diagnostic>,
+    },
+    Completion {
+        path: PathBuf,
+        position: LspPosition,
+        items: Vec<LspCompletionItem>,
+    },
+    Hover {
+        path: PathBuf,
+        contents: Option<String>,
+    },
+    Definition {
+        locations: Vec<Location>,
+    },
+    References {
+        locations: Vec<Location>,
+    },
+    Rename {
+        edit: WorkspaceEdit,
+    },
+    Formatting {
+        path: PathBuf,
+        revision: u64,
+        edits: Vec<TextEdit>,
+    },
+    Message(String),
+    Exited,
+}
+
+#[derive(Debug, Clone)]
+enum PendingRequest {
+    Initialize,
+    Shutdown,
+    Completion {
+        path: PathBuf,
+        position: LspPosition,
+    },
+    Hover {
+        path: PathBuf,
+    },
+    Definition,
+    References,
+    Rename,
+    Formatting {
+        path: PathBuf,
+        revision: u64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentSyncKind {
+    None,
+    Full,
+    Incremental,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentState {
+    pub version: i64,
+    pub revision: u64,
+    pub saved: Option<SystemTime>,
+}
+
+pub struct LspClient {
+    language: String,
+    child: Option<Child>,
+    writer: Box<dyn Write + Send>,
+    incoming: Receiver<LspMessage>,
+    next_id: i64,
+    pending: HashMap<i64, PendingRequest>,
+    capabilities: Value,
+    initialized: bool,
+    exited: bool,
+    documents: HashMap<PathBuf, DocumentState>,
+}
+
+impl LspClient {
+    pub fn start(language: &str, config: &LanguageServerConfig, root: &Path) -> Result<Self> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .current_dir(root)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| {
+                EditorError::InvalidOperation(format!(
+                    "Failed to start language server '{}': {}",
+                    config.command, e
+                ))
+            })?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err(EditorError::InvalidOperation(format!(
+                "Language server '{}' has no stdio pipes",
+                config.command
+            )));
+        };
+
+        let (sender, incoming) = mpsc::channel();
+        thread::spawn(move || {
+            let mut reader = BufReader::new(stdout);
+            while let Ok(Some(message)) = read_message(&mut reader) {
+                if sender.send(message).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut client = Self {
+            language: language.to_string(),
+            child: Some(child),
+            writer: Box::new(stdin),
+            incoming,
+            next_id: 1,
+            pending: HashMap::new(),
+            capabilities: Value::Null,
+            initialized: false,
+            exited: false,
+            documents: HashMap::new(),
+        };
+        client.initialize(root)?;
+        Ok(client)
+    }
+
+    fn initialize(&mut self, root: &Path) -> Result<()> {
+        let root_uri = path_to_uri(root);
+        let name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let params = json!({
+            "processId": std::process::id(),
+            "clientInfo": { "name": "editor-rs", "version": env!("CARGO_PKG_VERSION") },
+            "rootPath": root.to_string_lossy(),
+            "rootUri": root_uri,
+            "workspaceFolders": [{ "uri": root_uri, "name": name }],
+            "capabilities": {
+                "general": { "positionEncodings": ["utf-16"] },
+                "textDocument": {
+                    "synchronization": { "didSave": true },
+                    "completion": {
+                        "completionItem": { "snippetSupport": true },
+                    },
+                    "hover": { "contentFormat": ["plaintext", "markdown"] },
+                    "definition": { "linkSupport": true },
+                    "references": {},
+                    "rename": { "prepareSupport": false },
+                    "formatting": {},
+                    "publishDiagnostics": { "relatedInformation": false },
+                },
+                "workspace": {
+                    "workspaceEdit": { "documentChanges": true },
+                    "workspaceFolders": true,
+                },
+            },
+        });
+        self.request("initialize", params, PendingRequest::Initialize)
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn is_initialized(&self) -> bool {
+        self.initialized && !self.exited
+    }
+
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    pub fn capabilities(&self) -> &Value {
+        &self.capabilities
+    }
+
+    pub fn supports(&self, capability: &str) -> bool {
+        match self.capabilities.get(capability) {
+            Some(Value::Bool(enabled)) => *enabled,
+            Some(Value::Null) | None => false,
+            Some(_) => true,
+        }
+    }
+
+    pub fn sync_kind(&self) -> DocumentSyncKind {
+        let sync = self.capabilities.get("textDocumentSync");
+        let kind = match sync {
+            Some(Value::Object(options)) => options.get("change").and_then(Value::as_u64),
+            Some(value) => value.as_u64(),
+            None => None,
+        };
+        match kind {
+            Some(1) => DocumentSyncKind::Full,
+            Some(2) => DocumentSyncKind::Incremental,
+            _ => DocumentSyncKind::None,
+        }
+    }
+
+    fn save_includes_text(&self) -> bool {
+        self.capabilities
+            .get("textDocumentSync")
+            .and_then(|sync| sync.get("save"))
+            .and_then(|save| save.get("includeText"))
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    }
+
+    pub fn document(&self, path: &Path) -> Option<&DocumentState> {
+        self.documents.get(path)
+    }
+
+    pub fn document_paths(&self) -> Vec<PathBuf> {
+        self.documents.keys().cloned().collect()
+    }
+
+    pub fn open_document(
+        &mut self,
+        path: &Path,
+        language_id: &str,
+        text: &Rope,
+        revision: u64,
+        saved: Option<SystemTime>,
+    ) -> Result<()> {
+        let state = DocumentState {
+            version: 1,
+            revision,
+            saved,
+        };
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": path_to_uri(path),
+                    "languageId": language_id,
+                    "version": state.version,
+                    "text": text.to_string(),
+                },
+            }),
+        )?;
+        self.documents.insert(path.to_path_buf(), state);
+        Ok(())
+    }
+
+    // `changes` is the buffer's change log since the last synced revision;
+    // `None` means the log no longer reaches back that far and the whole
+    // text is sent instead.
+    pub fn change_document(
+        &mut self,
+        path: &Path,
+        text: &Rope,
+        revision: u64,
+        changes: Option<&[TextChange]>,
+    ) -> Result<()> {
+        let Some(state) = self.documents.get(path).copied() else {
+            return Ok(());
+        };
+        if state.revision == revision {
+            return Ok(());
+        }
+
+        let content_changes: Vec<Value> = match (self.sync_kind(), changes) {
+            (DocumentSyncKind::None, _) => Vec::new(),
+            (DocumentSyncKind::Incremental, Some(changes)) => changes
+                .iter()
+                .map(|change| {
+                    json!({
+                        "range": {
+                            "start": LspPosition::from(&change.start),
+                            "end": LspPosition::from(&change.old_end),
+                        },
+                        "text": change.text,
+                    })
+                })
+                .collect(),
+            _ => vec![json!({ "text": text.to_string() })],
+        };
+
+        let version = state.version + 1;
+        if !content_changes.is_empty() {
+            self.notify(
+                "textDocument/didChange",
+                json!({
+                    "textDocument": { "uri": path_to_uri(path), "version": version },
+                    "contentChanges": content_changes,
+                }),
+            )?;
+        }
+        self.documents.insert(
+            path.to_path_buf(),
+            DocumentState {
+                version,
+                revision,
+                ..state
+            },
+        );
+        Ok(())
+    }
+
+    pub fn save_document(
+        &mut self,
+        path: &Path,
+        text: &Rope,
+        saved: Option<SystemTime>,
+    ) -> Result<()> {
+        let Some(state) = self.documents.get_mut(path) else {
+            return Ok(());
+        };
+        state.saved = saved;
+
+        let mut params = json!({ "textDocument": { "uri": path_to_uri(path) } });
+        if self.save_includes_text() {
+            params["text"] = json!(text.to_string());
+        }
+        self.notify("textDocument/didSave", params)
+    }
+
+    pub fn close_document(&mut self, path: &Path) -> Result<()> {
+        if self.documents.remove(path).is_none() {
+            return Ok(());
+        }
+        self.notify(
+            "textDocument/didClose",
+            json!({ "textDocument": { "uri": path_to_uri(path) } }),
+        )
+    }
+
+    fn position_params(path: &Path, position: LspPosition) -> Value {
+        json!({
+            "textDocument": { "uri": path_to_uri(path) },
+            "position": position,
+        })
+    }
+
+    pub fn completion(&mut self, path: &Path, position: LspPosition) -> Result<()> {
+        self.request(
+            "textDocument/completion",
+            Self::position_params(path, position),
+            PendingRequest::Completion {
+                path: path.to_path_buf(),
+                position,
+            },
+        )
+    }
+
+    pub fn hover(&mut self, path: &Path, position: LspPosition) -> Result<()> {
+        self.request(
+            "textDocument/hover",
+            Self::position_params(path, position),
+            PendingRequest::Hover {
+                path: path.to_path_buf(),
+            },
+        )
+    }
+
+    pub fn definition(&mut self, path: &Path, position: LspPosition) -> Result<()> {
+        self.request(
+            "textDocument/definition",
+            Self::position_params(path, position),
+            PendingRequest::Definition,
+        )
+    }
+
+    pub fn references(&mut self, path: &Path, position: LspPosition) -> Result<()> {
+        let mut params = Self::position_params(path, position);
+        params["context"] = json!({ "includeDeclaration": true });
+        self.request(
+            "textDocument/references",
+            params,
+            PendingRequest::References,
+        )
+    }
+
+    pub fn rename(&mut self, path: &Path, position: LspPosition, new_name: &str) -> Result<()> {
+        let mut params = Self::position_params(path, position);
+        params["newName"] = json!(new_name);
+        self.request("textDocument/rename", params, PendingRequest::Rename)
+    }
+
+    pub fn formatting(
+        &mut self,
+        path: &Path,
+        revision: u64,
+        tab_size: usize,
+        insert_spaces: bool,
+    ) -> Result<()> {
+        self.request(
+            "textDocument/formatting",
+            json!({
+                "textDocument": { "uri": path_to_uri(path) },
+                "options": { "tabSize": tab_size, "insertSpaces": insert_spaces },
+            }),
+            PendingRequest::Formatting {
+                path: path.to_path_buf(),
+                revision,
+            },
+        )
+    }
+
+    fn request(&mut self, method: &str, params: Value, pending: PendingRequest) -> Result<()> {
+        if !self.initialized && !matches!(pending, PendingRequest::Initialize) {
+            return Err(EditorError::InvalidOperation(format!(
+                "Language server for {} is still starting",
+                self.language
+            )));
+        }
+        let id = self.next_id;
+        self.next_id += 1;
+        self.write(&LspMessage::request(id, method, params))?;
+        self.pending.insert(id, pending);
+        Ok(())
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> Result<()> {
+        self.write(&LspMessage::notification(method, params))
+    }
+
+    fn write(&mut self, message: &LspMessage) -> Result<()> {
+        if self.exited {
+            return Err(EditorError::InvalidOperation(format!(
+                "Language server for {} has exited",
+                self.language
+            )));
+        }
+        let result = write_message(&mut self.writer, message);
+        if result.is_err() {
+            self.exited = true;
+        }
+        result
+    }
+
+    pub fn poll(&mut self) -> Vec<LspEvent> {
+        let mut events = Vec::new();
+        loop {
+            match self.incoming.try_recv() {
+                Ok(message) => self.handle_message(message, &mut events),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.mark_exited(&mut events);
+                    break;
+                }
+            }
+        }
+        events
+    }
+
+    // Blocks until at least one message arrives or the timeout passes, then
+    // drains whatever else is already queued.
+    pub fn wait(&mut self, timeout: Duration) -> Vec<LspEvent> {
+        let mut events = Vec::new();
+        match self.incoming.recv_timeout(timeout) {
+            Ok(message) => self.handle_message(message, &mut events),
+            Err(RecvTimeoutError::Timeout) => return events,
+            Err(RecvTimeoutError::Disconnected) => {
+                self.mark_exited(&mut events);
+                return events;
+            }
+        }
+        events.extend(self.poll());
+        events
+    }
+
+    fn mark_exited(&mut self, events: &mut Vec<LspEvent>) {
+        if !self.exited {
+            self.exited = true;
+            self.pending.clear();
+            events.push(LspEvent::Exited);
+        }
+    }
+
+    fn handle_message(&mut self, message: LspMessage, events: &mut Vec<LspEvent>) {
+        match message {
+            LspMessage::Response { id, result, error } => {
+                let Some(pending) = id.as_i64().and_then(|id| self.pending.remove(&id)) else {
+                    return;
+                };
+                if let Some(error) = error {
+                    if error.code != REQUEST_CANCELLED && error.code != CONTENT_MODIFIED {
+                        events.push(LspEvent::Message(format!(
+                            "{} language server: {}",
+                            self.language, error.message
+                        )));
+                    }
+                    return;
+                }
+                self.handle_response(pending, result, events);
+            }
+            LspMessage::Request { id, method, params } => {
+                // Server requests we don't act on still need an answer.
+                let result = match method.as_str() {
+                    "workspace/configuration" => {
+                        let count = params
+                            .get("items")
+                            .and_then(Value::as_array)
+                            .map_or(0, Vec::len);
+                        Value::Array(vec![Value::Null; count])
+                    }
+                    "workspace/applyEdit" => json!({ "applied": false }),
+                    _ => Value::Null,
+                };
+                let _ = self.write(&LspMessage::response(id, result));
+            }
+            LspMessage::Notification { method, params } => match method.as_str() {
+                "textDocument/publishDiagnostics" => {
+                    if let Some((path, diagnostics)) = parse_diagnostics(&params) {
+                        events.push(LspEvent::Diagnostics { path, diagnostics });
+                    }
+                }
+                "window/showMessage" => {
+                    if let Some(message) = params.get("message").and_then(Value::as_str) {
+                        events.push(LspEvent::Message(message.to_string()));
+                    }
+                }
+                _ => {}
+            },
+        }
+    }
+
+    fn handle_response(
+        &mut self,
+        pending: PendingRequest,
+        result: Value,
+        events: &mut Vec<LspEvent>,
+    ) {
+        let event = match pending {
+            PendingRequest::Initialize => {
+                self.capabilities = result.get("capabilities").cloned().unwrap_or(Value::Null);
+                self.initialized = true;
+                if self.notify("initialized", json!({})).is_err() {
+                    return;
+                }
+                LspEvent::Initialized
+            }
+            PendingRequest::Shutdown => {
+                let _ = self.notify("exit", Value::Null);
+                return;
+            }
+            PendingRequest::Completion { path, position } => LspEvent::Completion {
+                path,
+                position,
+                items: parse_completion_items(&result),
+            },
+            PendingRequest::Hover { path } => LspEvent::Hover {
+                path,
+                contents: parse_hover(&result),
+            },
+            PendingRequest::Definition => LspEvent::Definition {
+                locations: parse_locations(&result),
+            },
+            PendingRequest::References => LspEvent::References {
+                locations: parse_locations(&result),
+            },
+            PendingRequest::Rename => LspEvent::Rename {
+                edit: parse_workspace_edit(&result),
+            },
+            PendingRequest::Formatting { path, revision } => LspEvent::Formatting {
+                path,
+                revision,
+                edits: parse_text_edits(&result),
+            },
+        };
+        events.push(event);
+    }
+
+    pub fn shutdown(&mut self) {
+        if self.initialized && !self.exited {
+            let id = self.next_id;
+            if self
+                .request("shutdown", Value::Null, PendingRequest::Shutdown)
+                .is_ok()
+            {
+                let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+                while self.pending.contains_key(&id) && !self.exited {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        break;
+                    }
+                    self.wait(remaining);
+                }
+            }
+        }
+        self.exited = true;
+
+        if let Some(mut child) = self.child.take() {
+            let deadline = Instant::now() + SHUTDOWN_TIMEOUT;
+            while Instant::now() < deadline {
+                if let Ok(Some(_)) = child.try_wait() {
+                    return;
+                }
+                thread::sleep(Duration::from_millis(10));
+            }
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
@@ -0,0 +1,132 @@
+use crate::cursor::CursorPosition;
+use crate::text_change::TextPosition;
+use ropey::Rope;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+pub struct LspPosition {
+    pub line: u32,
+    pub character: u32,
+}
+
+impl LspPosition {
+    pub fn new(line: u32, character: u32) -> Self {
+        Self { line, character }
+    }
+}
+
+impl From<&TextPosition> for LspPosition {
+    fn from(position: &TextPosition) -> Self {
+        Self::new(position.line as u32, position.utf16_column as u32)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct LspRange {
+    pub start: LspPosition,
+    pub end: LspPosition,
+}
+
+impl LspRange {
+    pub fn new(start: LspPosition, end: LspPosition) -> Self {
+        Self { start, end }
+    }
+
+    pub fn contains_line(&self, line: u32) -> bool {
+        self.start.line <= line && line <= self.end.line
+    }
+}
+
+fn line_text_len(rope: &Rope, line: usize) -> usize {
+    let slice = rope.line(line);
+    let mut len = slice.len_chars();
+    if len > 0 && slice.char(len - 1) == '\n' {
+        len -= 1;
+        if len > 0 && slice.char(len - 1) == '\r' {
+            len -= 1;
+        }
+    }
+    len
+}
+
+// LSP columns count UTF-16 code units, so characters outside the Basic
+// Multilingual Plane take two columns on the wire but one in the buffer.
+pub fn to_lsp_position(rope: &Rope, position: CursorPosition) -> LspPosition {
+    let line = position.line.min(rope.len_lines().saturating_sub(1));
+    let column = position.column.min(line_text_len(rope, line));
+    let line_start = rope.line_to_char(line);
+    let character = rope.char_to_utf16_cu(line_start + column) - rope.char_to_utf16_cu(line_start);
+    LspPosition::new(line as u32, character as u32)
+}
+
+pub fn from_lsp_position(rope: &Rope, position: LspPosition) -> CursorPosition {
+    let last_line = rope.len_lines().saturating_sub(1);
+    let line = position.line as usize;
+    if line > last_line {
+        return CursorPosition::new(last_line, line_text_len(rope, last_line));
+    }
+
+    let line_start = rope.line_to_char(line);
+    let line_len = line_text_len(rope, line);
+    let target = rope.char_to_utf16_cu(line_start) + position.character as usize;
+    let line_end_utf16 = rope.char_to_utf16_cu(line_start + line_len);
+    let column = if target >= line_end_utf16 {
+        line_len
+    } else {
+        rope.utf16_cu_to_char(target) - line_start
+    };
+    CursorPosition::new(line, column)
+}
+
+pub fn to_char_index(rope: &Rope, position: LspPosition) -> usize {
+    let position = from_lsp_position(rope, position);
+    rope.line_to_char(position.line) + position.column
+}
+
+pub fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            b':' if uri.len() == "file:///".len() + 1 => uri.push(':'),
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?;
+    let path = path.strip_prefix("localhost").unwrap_or(path);
+
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    let path = String::from_utf8(decoded).ok()?;
+
+    // file:///C:/dir on Windows carries a leading slash before the drive.
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if drive.is_ascii_alphabetic() && cfg!(windows) => {
+            path[1..].to_string()
+        }
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
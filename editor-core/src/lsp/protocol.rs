@@ -0,0 +1,178 @@
+use crate::error::{EditorError, Result};
+use serde_json::{json, Map, Value};
+use std::io::{BufRead, Write};
+
+// Larger bodies are refused rather than allocated, so a misbehaving server
+// can't take the editor down with one header.
+const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseError {
+    pub code: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LspMessage {
+    Request {
+        id: Value,
+        method: String,
+        params: Value,
+    },
+    Response {
+        id: Value,
+        result: Value,
+        error: Option<ResponseError>,
+    },
+    Notification {
+        method: String,
+        params: Value,
+    },
+}
+
+impl LspMessage {
+    pub fn request(id: i64, method: &str, params: Value) -> Self {
+        LspMessage::Request {
+            id: json!(id),
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    pub fn notification(method: &str, params: Value) -> Self {
+        LspMessage::Notification {
+            method: method.to_string(),
+            params,
+        }
+    }
+
+    pub fn response(id: Value, result: Value) -> Self {
+        LspMessage::Response {
+            id,
+            result,
+            error: None,
+        }
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            LspMessage::Request { id, method, params } => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": method,
+                "params": params,
+            }),
+            LspMessage::Response { id, result, error } => match error {
+                Some(error) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": error.code, "message": error.message },
+                }),
+                None => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            },
+            LspMessage::Notification { method, params } => json!({
+                "jsonrpc": "2.0",
+                "method": method,
+                "params": params,
+            }),
+        }
+    }
+
+    pub fn from_value(value: Value) -> Result<Self> {
+        let Value::Object(mut object) = value else {
+            return Err(EditorError::Parse(
+                "LSP message is not a JSON object".to_string(),
+            ));
+        };
+
+        let method = match object.remove("method") {
+            Some(Value::String(method)) => Some(method),
+            Some(_) => {
+                return Err(EditorError::Parse(
+                    "LSP message method is not a string".to_string(),
+                ))
+            }
+            None => None,
+        };
+        let params = object.remove("params").unwrap_or(Value::Null);
+        let id = object.remove("id").filter(|id| !id.is_null());
+
+        match (method, id) {
+            (Some(method), Some(id)) => Ok(LspMessage::Request { id, method, params }),
+            (Some(method), None) => Ok(LspMessage::Notification { method, params }),
+            (None, Some(id)) => Ok(LspMessage::Response {
+                id,
+                result: object.remove("result").unwrap_or(Value::Null),
+                error: object.remove("error").map(response_error),
+            }),
+            (None, None) => Err(EditorError::Parse(
+                "LSP message has neither a method nor an id".to_string(),
+            )),
+        }
+    }
+}
+
+fn response_error(value: Value) -> ResponseError {
+    let object = value.as_object().cloned().unwrap_or_else(Map::new);
+    ResponseError {
+        code: object.get("code").and_then(Value::as_i64).unwrap_or(0),
+        message: object
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown error")
+            .to_string(),
+    }
+}
+
+pub fn write_message<W: Write>(writer: &mut W, message: &LspMessage) -> Result<()> {
+    let body = serde_json::to_string(&message.to_value())
+        .map_err(|e| EditorError::Parse(format!("Failed to encode LSP message: {}", e)))?;
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()?;
+    Ok(())
+}
+
+// Returns `None` when the stream ends cleanly between messages.
+pub fn read_message<R: BufRead>(reader: &mut R) -> Result<Option<LspMessage>> {
+    let mut content_length = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return match content_length {
+                None => Ok(None),
+                Some(_) => Err(EditorError::Parse(
+                    "LSP stream ended inside a message header".to_string(),
+                )),
+            };
+        }
+
+        let header = line.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("Content-Length") {
+                let length = value.trim().parse::<usize>().map_err(|_| {
+                    EditorError::Parse(format!("Invalid LSP Content-Length '{}'", value.trim()))
+                })?;
+                if length > MAX_CONTENT_LENGTH {
+                    return Err(EditorError::Parse(format!(
+                        "LSP Content-Length {} is over the {} byte limit",
+                        length, MAX_CONTENT_LENGTH
+                    )));
+                }
+                content_length = Some(length);
+            }
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap_or(0)];
+    reader.read_exact(&mut body)?;
+    let value: Value = serde_json::from_slice(&body)
+        .map_err(|e| EditorError::Parse(format!("Invalid LSP message: {}", e)))?;
+    LspMessage::from_value(value).map(Some)
+}
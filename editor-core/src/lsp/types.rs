@@ -0,0 +1,301 @@
+use super::position::{uri_to_path, LspRange};
+use crate::snippet::parse_snippet;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+impl DiagnosticSeverity {
+    pub fn from_lsp(value: u64) -> Self {
+        match value {
+            1 => DiagnosticSeverity::Error,
+            2 => DiagnosticSeverity::Warning,
+            3 => DiagnosticSeverity::Information,
+            _ => DiagnosticSeverity::Hint,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+            DiagnosticSeverity::Information => "info",
+            DiagnosticSeverity::Hint => "hint",
+        }
+    }
+
+    pub fn marker(&self) -> char {
+        match self {
+            DiagnosticSeverity::Error => 'E',
+            DiagnosticSeverity::Warning => 'W',
+            DiagnosticSeverity::Information => 'I',
+            DiagnosticSeverity::Hint => 'H',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub range: LspRange,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+    pub source: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn summary(&self) -> String {
+        let message = self.message.lines().next().unwrap_or_default();
+        match &self.source {
+            Some(source) => format!("{}: {} [{}]", self.severity.name(), message, source),
+            None => format!("{}: {}", self.severity.name(), message),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range: LspRange,
+    pub new_text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub path: PathBuf,
+    pub range: LspRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WorkspaceEdit {
+    pub changes: Vec<(PathBuf, Vec<TextEdit>)>,
+}
+
+impl WorkspaceEdit {
+    pub fn edit_count(&self) -> usize {
+        self.changes.iter().map(|(_, edits)| edits.len()).sum()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspCompletionItem {
+    pub label: String,
+    pub kind: Option<u64>,
+    pub detail: Option<String>,
+    pub insert_text: String,
+    pub is_snippet: bool,
+    pub filter_text: Option<String>,
+    pub sort_text: Option<String>,
+    pub edit: Option<TextEdit>,
+}
+
+pub fn completion_kind_name(kind: u64) -> &'static str {
+    match kind {
+        2 => "method",
+        3 => "fn",
+        4 => "ctor",
+        5 => "field",
+        6 => "var",
+        7 => "class",
+        8 => "iface",
+        9 => "mod",
+        10 => "prop",
+        11 => "unit",
+        12 => "value",
+        13 => "enum",
+        14 => "keyword",
+        15 => "snippet",
+        16 => "color",
+        17 => "file",
+        18 => "ref",
+        19 => "folder",
+        20 => "variant",
+        21 => "const",
+        22 => "struct",
+        23 => "event",
+        24 => "op",
+        25 => "type",
+        _ => "text",
+    }
+}
+
+fn parse_range(value: &Value) -> Option<LspRange> {
+    serde_json::from_value(value.clone()).ok()
+}
+
+pub fn parse_diagnostics(params: &Value) -> Option<(PathBuf, Vec<Diagnostic>)> {
+    let path = uri_to_path(params.get("uri")?.as_str()?)?;
+    let diagnostics = params
+        .get("diagnostics")?
+        .as_array()?
+        .iter()
+        .filter_map(|diagnostic| {
+            Some(Diagnostic {
+                range: parse_range(diagnostic.get("range")?)?,
+                severity: diagnostic
+                    .get("severity")
+                    .and_then(Value::as_u64)
+                    .map_or(DiagnosticSeverity::Error, DiagnosticSeverity::from_lsp),
+                message: diagnostic.get("message")?.as_str()?.to_string(),
+                source: diagnostic
+                    .get("source")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            })
+        })
+        .collect();
+    Some((path, diagnostics))
+}
+
+// Completion results are either a bare array or a CompletionList.
+pub fn parse_completion_items(result: &Value) -> Vec<LspCompletionItem> {
+    let items = match result {
+        Value::Array(items) => items,
+        Value::Object(list) => match list.get("items").and_then(Value::as_array) {
+            Some(items) => items,
+            None => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let label = item.get("label")?.as_str()?.to_string();
+            let is_snippet = item.get("insertTextFormat").and_then(Value::as_u64) == Some(2);
+            let body = |text: &str| {
+                if is_snippet {
+                    snippet_text(text)
+                } else {
+                    text.to_string()
+                }
+            };
+            let edit = item.get("textEdit").and_then(|edit| {
+                // InsertReplaceEdit carries `insert` and `replace` ranges.
+                let range = edit.get("range").or_else(|| edit.get("replace"))?;
+                Some(TextEdit {
+                    range: parse_range(range)?,
+                    new_text: body(edit.get("newText")?.as_str()?),
+                })
+            });
+            // Snippet bodies are kept as they are and expanded on insertion.
+            let insert_text = item
+                .get("insertText")
+                .or_else(|| item.get("textEdit").and_then(|edit| edit.get("newText")))
+                .and_then(Value::as_str)
+                .map_or_else(|| label.clone(), str::to_string);
+            let text = |key: &str| item.get(key).and_then(Value::as_str).map(str::to_string);
+
+            Some(LspCompletionItem {
+                kind: item.get("kind").and_then(Value::as_u64),
+                detail: text("detail"),
+                filter_text: text("filterText"),
+                sort_text: text("sortText"),
+                insert_text,
+                is_snippet,
+                edit,
+                label,
+            })
+        })
+        .collect()
+}
+
+// Plain-text form of a snippet: placeholders keep their default text and
+// bare tabstops are dropped.
+pub fn snippet_text(snippet: &str) -> String {
+    parse_snippet(snippet).text
+}
+
+fn marked_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Object(object) => object
+            .get("value")
+            .and_then(Value::as_str)
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+pub fn parse_hover(result: &Value) -> Option<String> {
+    let contents = result.get("contents")?;
+    let text = match contents {
+        Value::Array(parts) => parts
+            .iter()
+            .filter_map(marked_string)
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        other => marked_string(other)?,
+    };
+    let text = text.trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+fn parse_location(value: &Value) -> Option<Location> {
+    if let Some(uri) = value.get("targetUri") {
+        let range = value
+            .get("targetSelectionRange")
+            .or_else(|| value.get("targetRange"))?;
+        return Some(Location {
+            path: uri_to_path(uri.as_str()?)?,
+            range: parse_range(range)?,
+        });
+    }
+    Some(Location {
+        path: uri_to_path(value.get("uri")?.as_str()?)?,
+        range: parse_range(value.get("range")?)?,
+    })
+}
+
+// Definition and references answer with a Location, an array of Locations
+// or an array of LocationLinks.
+pub fn parse_locations(result: &Value) -> Vec<Location> {
+    match result {
+        Value::Array(values) => values.iter().filter_map(parse_location).collect(),
+        Value::Null => Vec::new(),
+        value => parse_location(value).into_iter().collect(),
+    }
+}
+
+pub fn parse_text_edits(result: &Value) -> Vec<TextEdit> {
+    serde_json::from_value(result.clone()).unwrap_or_default()
+}
+
+pub fn parse_workspace_edit(result: &Value) -> WorkspaceEdit {
+    let mut edit = WorkspaceEdit::default();
+
+    if let Some(changes) = result.get("changes").and_then(Value::as_object) {
+        for (uri, edits) in changes {
+            if let Some(path) = uri_to_path(uri) {
+                edit.changes.push((path, parse_text_edits(edits)));
+            }
+        }
+    }
+
+    if let Some(document_changes) = result.get("documentChanges").and_then(Value::as_array) {
+        for change in document_changes {
+            let Some(path) = change
+                .get("textDocument")
+                .and_then(|document| document.get("uri"))
+                .and_then(Value::as_str)
+                .and_then(uri_to_path)
+            else {
+                continue;
+            };
+            let edits = change
+                .get("edits")
+                .map(parse_text_edits)
+                .unwrap_or_default();
+            edit.changes.push((path, edits));
+        }
+    }
+
+    edit.changes.sort_by(|a, b| a.0.cmp(&b.0));
+    edit
+}
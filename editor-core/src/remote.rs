@@ -0,0 +1,528 @@
+use crate::command::Command;
+use crate::editor::EditorState;
+use crate::error::{EditorError, Result};
+use crate::lsp::{read_message, write_message, LspMessage, ResponseError};
+use crate::session::SessionManager;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fs;
+use std::io::{BufReader, ErrorKind};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+pub const REQUEST_FAILED: i64 = -32000;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+enum Incoming {
+    Connected(u64, UnixStream),
+    Message(u64, LspMessage),
+    Disconnected(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct BufferVersion {
+    revision: u64,
+    modified: bool,
+    saved: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct BufferSnapshot {
+    buffers: BTreeMap<PathBuf, BufferVersion>,
+    active: Option<PathBuf>,
+}
+
+impl BufferSnapshot {
+    fn capture(editor: &EditorState) -> Self {
+        Self {
+            buffers: editor
+                .buffers()
+                .iter()
+                .filter_map(|buffer| {
+                    let version = BufferVersion {
+                        revision: buffer.revision(),
+                        modified: buffer.is_modified(),
+                        saved: buffer.last_saved(),
+                    };
+                    Some((buffer.file_path()?.clone(), version))
+                })
+                .collect(),
+            active: editor.current_buffer().file_path().cloned(),
+        }
+    }
+
+    // The notifications that take a subscriber from `self` to `current`.
+    fn events(&self, current: &Self) -> Vec<(&'static str, Value)> {
+        let mut events = Vec::new();
+        for path in self.buffers.keys() {
+            if !current.buffers.contains_key(path) {
+                events.push(("buffer_closed", json!({ "path": path })));
+            }
+        }
+        for (path, version) in &current.buffers {
+            let Some(old) = self.buffers.get(path) else {
+                events.push(("buffer_opened", json!({ "path": path })));
+                continue;
+            };
+            if old.revision != version.revision {
+                events.push((
+                    "buffer_changed",
+                    json!({
+                        "path": path,
+                        "revision": version.revision,
+                        "modified": version.modified,
+                    }),
+                ));
+            }
+            if old.saved != version.saved {
+                events.push(("buffer_saved", json!({ "path": path })));
+            }
+        }
+        if self.active != current.active {
+            events.push(("active_buffer_changed", json!({ "path": current.active })));
+        }
+        events
+    }
+}
+
+// Listens on a Unix socket for JSON-RPC requests from other processes,
+// framed like LSP messages. Requests are queued by background threads and
+// answered on the frontend's thread when it calls `poll`.
+pub struct RemoteServer {
+    socket_path: PathBuf,
+    incoming: Receiver<Incoming>,
+    connections: HashMap<u64, UnixStream>,
+    subscribers: HashSet<u64>,
+    snapshot: BufferSnapshot,
+    stopped: Arc<AtomicBool>,
+}
+
+impl RemoteServer {
+    pub fn bind(socket_path: PathBuf) -> Result<Self> {
+        if let Some(parent) = socket_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let listen_error = |e: std::io::Error| {
+            EditorError::InvalidOperation(format!(
+                "Failed to listen on {}: {}",
+                socket_path.display(),
+                e
+            ))
+        };
+        // A socket left behind by an instance that crashed would block bind.
+        if socket_path.exists() {
+            if UnixStream::connect(&socket_path).is_ok() {
+                return Err(listen_error(ErrorKind::AddrInUse.into()));
+            }
+            fs::remove_file(&socket_path)?;
+        }
+        let listener = bind_private(&socket_path).map_err(listen_error)?;
+
+        let (sender, incoming) = mpsc::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let accept_stopped = Arc::clone(&stopped);
+        thread::spawn(move || {
+            for (connection, stream) in (1..).zip(listener.incoming()) {
+                if accept_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let Ok(writer) = stream.try_clone() else {
+                    continue;
+                };
+                if sender
+                    .send(Incoming::Connected(connection, writer))
+                    .is_err()
+                {
+                    break;
+                }
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    let mut reader = BufReader::new(stream);
+                    while let Ok(Some(message)) = read_message(&mut reader) {
+                        if sender.send(Incoming::Message(connection, message)).is_err() {
+                            return;
+                        }
+                    }
+                    let _ = sender.send(Incoming::Disconnected(connection));
+                });
+            }
+        });
+
+        Ok(Self {
+            socket_path,
+            incoming,
+            connections: HashMap::new(),
+            subscribers: HashSet::new(),
+            snapshot: BufferSnapshot::default(),
+            stopped,
+        })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        &self.socket_path
+    }
+
+    pub fn connection_count(&self) -> usize {
+        self.connections.len()
+    }
+
+    // Frontends call this from their event loop; it returns true when a
+    // request may have changed something worth redrawing.
+    pub fn poll(&mut self, editor: &mut EditorState) -> bool {
+        let mut handled = false;
+        loop {
+            match self.incoming.try_recv() {
+                Ok(Incoming::Connected(connection, stream)) => {
+                    self.connections.insert(connection, stream);
+                }
+                Ok(Incoming::Disconnected(connection)) => self.disconnect(connection),
+                Ok(Incoming::Message(connection, message)) => {
+                    handled |= self.handle_message(editor, connection, message);
+                }
+                Err(TryRecvError::Empty) | Err(TryRecvError::Disconnected) => break,
+            }
+        }
+        self.publish_events(editor);
+        handled
+    }
+
+    fn handle_message(
+        &mut self,
+        editor: &mut EditorState,
+        connection: u64,
+        message: LspMessage,
+    ) -> bool {
+        let (id, method, params) = match message {
+            LspMessage::Request { id, method, params } => (Some(id), method, params),
+            LspMessage::Notification { method, params } => (None, method, params),
+            LspMessage::Response { .. } => return false,
+        };
+
+        let result = match method.as_str() {
+            "subscribe" => {
+                // Subscribers hear about changes from now on, not about the
+                // buffers that were already open.
+                if self.subscribers.is_empty() {
+                    self.snapshot = BufferSnapshot::capture(editor);
+                }
+                self.subscribers.insert(connection);
+                Ok(Value::Bool(true))
+            }
+            "unsubscribe" => Ok(Value::Bool(self.subscribers.remove(&connection))),
+            _ => handle_request(editor, &method, params),
+        };
+
+        if let Some(id) = id {
+            let response = LspMessage::Response {
+                id,
+                result: result.as_ref().cloned().unwrap_or(Value::Null),
+                error: result.err(),
+            };
+            self.send(connection, &response);
+        }
+        true
+    }
+
+    fn publish_events(&mut self, editor: &EditorState) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+        let current = BufferSnapshot::capture(editor);
+        if current == self.snapshot {
+            return;
+        }
+        for (method, params) in self.snapshot.events(&current) {
+            let notification = LspMessage::notification(method, params);
+            let subscribers: Vec<u64> = self.subscribers.iter().copied().collect();
+            for connection in subscribers {
+                self.send(connection, &notification);
+            }
+        }
+        self.snapshot = current;
+    }
+
+    fn send(&mut self, connection: u64, message: &LspMessage) {
+        let Some(stream) = self.connections.get_mut(&connection) else {
+            return;
+        };
+        if write_message(stream, message).is_err() {
+            self.disconnect(connection);
+        }
+    }
+
+    fn disconnect(&mut self, connection: u64) {
+        if let Some(stream) = self.connections.remove(&connection) {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+        }
+        self.subscribers.remove(&connection);
+    }
+}
+
+impl Drop for RemoteServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wakes the accept loop so that it sees the flag and exits.
+        let _ = UnixStream::connect(&self.socket_path);
+        let _ = fs::remove_file(&self.socket_path);
+        for (_, stream) in self.connections.drain() {
+            let _ = stream.shutdown(std::net::Shutdown::Both);
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OpenParams {
+    path: PathBuf,
+    #[serde(default)]
+    line: usize,
+    #[serde(default)]
+    column: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BufferParams {
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ExecuteParams {
+    command: Command,
+}
+
+fn handle_request(
+    editor: &mut EditorState,
+    method: &str,
+    params: Value,
+) -> std::result::Result<Value, ResponseError> {
+    match method {
+        "open" => {
+            let params: OpenParams = parse_params(params)?;
+            // The editor's working directory means nothing to the client.
+            if !params.path.is_absolute() {
+                return Err(ResponseError {
+                    code: INVALID_PARAMS,
+                    message: format!("Invalid params: {} is not absolute", params.path.display()),
+                });
+            }
+            open_at(editor, params).map_err(request_failed)
+        }
+        "buffers" => {
+            let active = editor.current_buffer_index();
+            Ok(Value::Array(
+                editor
+                    .buffers()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, buffer)| {
+                        json!({
+                            "path": buffer.file_path(),
+                            "modified": buffer.is_modified(),
+                            "revision": buffer.revision(),
+                            "line_count": buffer.line_count(),
+                            "active": index == active,
+                        })
+                    })
+                    .collect(),
+            ))
+        }
+        "buffer" => {
+            let params: BufferParams = if params.is_null() {
+                BufferParams::default()
+            } else {
+                parse_params(params)?
+            };
+            buffer_contents(editor, params.path.as_deref())
+        }
+        "execute" => {
+            let params: ExecuteParams = parse_params(params)?;
+            editor
+                .execute_command(params.command)
+                .map(|()| Value::Null)
+                .map_err(request_failed)
+        }
+        _ => Err(ResponseError {
+            code: METHOD_NOT_FOUND,
+            message: format!("Unknown method '{}'", method),
+        }),
+    }
+}
+
+// Binds the socket inside a directory only the user can enter and moves it
+// into place once it is 0600, so no one else can connect in between.
+fn bind_private(socket_path: &Path) -> std::io::Result<UnixListener> {
+    let parent = socket_path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let private_dir = parent.join(format!(".editor-rs-{}", std::process::id()));
+    fs::DirBuilder::new().mode(0o700).create(&private_dir)?;
+
+    let staged = private_dir.join("s");
+    let bound = UnixListener::bind(&staged).and_then(|listener| {
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o600))?;
+        fs::rename(&staged, socket_path)?;
+        Ok(listener)
+    });
+    let _ = fs::remove_file(&staged);
+    let _ = fs::remove_dir(&private_dir);
+    bound
+}
+
+fn open_at(editor: &mut EditorState, params: OpenParams) -> Result<Value> {
+    editor.open_at(params.path.clone(), params.line, params.column)?;
+    Ok(json!({ "path": params.path, "cursor": editor.cursor() }))
+}
+
+fn buffer_contents(
+    editor: &EditorState,
+    path: Option<&Path>,
+) -> std::result::Result<Value, ResponseError> {
+    let buffer = match path {
+        None => editor.current_buffer(),
+        Some(path) => editor
+            .buffers()
+            .iter()
+            .find(|buffer| buffer.file_path().map(PathBuf::as_path) == Some(path))
+            .ok_or_else(|| ResponseError {
+                code: REQUEST_FAILED,
+                message: format!("{} is not open", path.display()),
+            })?,
+    };
+    // Only the buffer being edited has a cursor.
+    let cursor = std::ptr::eq(buffer, editor.current_buffer()).then(|| editor.cursor());
+    Ok(json!({
+        "path": buffer.file_path(),
+        "content": buffer.content(),
+        "revision": buffer.revision(),
+        "modified": buffer.is_modified(),
+        "cursor": cursor,
+    }))
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(
+    params: Value,
+) -> std::result::Result<T, ResponseError> {
+    serde_json::from_value(params).map_err(|e| ResponseError {
+        code: INVALID_PARAMS,
+        message: format!("Invalid params: {}", e),
+    })
+}
+
+fn request_failed(error: EditorError) -> ResponseError {
+    ResponseError {
+        code: REQUEST_FAILED,
+        message: error.to_string(),
+    }
+}
+
+pub struct RemoteClient {
+    writer: UnixStream,
+    reader: BufReader<UnixStream>,
+    next_id: i64,
+    notifications: VecDeque<(String, Value)>,
+}
+
+impl RemoteClient {
+    pub fn connect(socket_path: &Path) -> Result<Self> {
+        let writer = UnixStream::connect(socket_path)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        Ok(Self {
+            writer,
+            reader,
+            next_id: 1,
+            notifications: VecDeque::new(),
+        })
+    }
+
+    // Connects to the newest instance that answers, tidying away the
+    // sockets that crashed instances left behind.
+    pub fn connect_to_running() -> Result<Option<Self>> {
+        for socket_path in SessionManager::running_instances()? {
+            match Self::connect(&socket_path) {
+                Ok(client) => return Ok(Some(client)),
+                Err(EditorError::Io(e)) if e.kind() == ErrorKind::ConnectionRefused => {
+                    let _ = fs::remove_file(&socket_path);
+                }
+                Err(_) => {}
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn request(&mut self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_message(&mut self.writer, &LspMessage::request(id, method, params))?;
+
+        self.reader
+            .get_ref()
+            .set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        loop {
+            match read_message(&mut self.reader)? {
+                Some(LspMessage::Response {
+                    id: reply,
+                    result,
+                    error,
+                }) if reply == json!(id) => {
+                    return match error {
+                        Some(error) => Err(EditorError::InvalidOperation(error.message)),
+                        None => Ok(result),
+                    };
+                }
+                Some(LspMessage::Notification { method, params }) => {
+                    self.notifications.push_back((method, params));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(EditorError::InvalidOperation(
+                        "Editor closed the connection".to_string(),
+                    ))
+                }
+            }
+        }
+    }
+
+    // A relative path is taken from this process's working directory.
+    pub fn open(&mut self, path: &Path, line: usize, column: usize) -> Result<()> {
+        let path = std::env::current_dir()?.join(path);
+        self.request(
+            "open",
+            json!({ "path": path, "line": line, "column": column }),
+        )
+        .map(|_| ())
+    }
+
+    // Waits up to `timeout` for the next event a subscription delivers.
+    pub fn next_notification(&mut self, timeout: Duration) -> Result<Option<(String, Value)>> {
+        if let Some(notification) = self.notifications.pop_front() {
+            return Ok(Some(notification));
+        }
+        self.reader.get_ref().set_read_timeout(Some(timeout))?;
+        match read_message(&mut self.reader) {
+            Ok(Some(LspMessage::Notification { method, params })) => Ok(Some((method, params))),
+            Ok(_) => Ok(None),
+            Err(EditorError::Io(e))
+                if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+            {
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use crate::command::Command;
+use crate::editor::EditorState;
+use crate::error::{EditorError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// A list of commands to run without a user interface. Given files, the
+// commands run once in each of them; otherwise they run once and open and
+// save whatever they need themselves.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BatchScript {
+    #[serde(default)]
+    pub files: Vec<PathBuf>,
+    pub commands: Vec<Command>,
+    #[serde(default = "default_save")]
+    pub save: bool,
+}
+
+fn default_save() -> bool {
+    true
+}
+
+impl BatchScript {
+    pub fn new(commands: Vec<Command>) -> Self {
+        Self {
+            files: Vec::new(),
+            commands,
+            save: true,
+        }
+    }
+
+    pub fn with_files(mut self, files: Vec<PathBuf>) -> Self {
+        self.files = files;
+        self
+    }
+
+    // A script is either the full object or just its list of commands.
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        let parse_error = |e: serde_json::Error| EditorError::Parse(e.to_string());
+        if content.trim_start().starts_with('[') {
+            Ok(Self::new(
+                serde_json::from_str(content).map_err(parse_error)?,
+            ))
+        } else {
+            serde_json::from_str(content).map_err(parse_error)
+        }
+    }
+
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        Self::from_json_str(&content)
+            .map_err(|e| EditorError::Parse(format!("{}: {}", path.display(), e)))
+    }
+
+    pub fn to_json_string(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|e| EditorError::Parse(e.to_string()))
+    }
+
+    // Stops at the first command that fails, naming the file and command.
+    pub fn run(&self, editor: &mut EditorState) -> Result<()> {
+        if self.files.is_empty() {
+            self.run_commands(editor, None)?;
+        }
+        for file in &self.files {
+            editor
+                .execute_command(Command::Open(file.clone()))
+                .map_err(|e| EditorError::InvalidOperation(format!("{}: {}", file.display(), e)))?;
+            self.run_commands(editor, Some(file))?;
+        }
+        if self.save {
+            editor.execute_command(Command::Save)?;
+        }
+        Ok(())
+    }
+
+    fn run_commands(&self, editor: &mut EditorState, file: Option<&Path>) -> Result<()> {
+        for (index, command) in self.commands.iter().enumerate() {
+            editor.execute_command(command.clone()).map_err(|e| {
+                let location = match file {
+                    Some(file) => format!("{}: ", file.display()),
+                    None => String::new(),
+                };
+                let command =
+                    serde_json::to_string(command).unwrap_or_else(|_| format!("{:?}", command));
+                EditorError::InvalidOperation(format!(
+                    "{}command {} of {} ({}) failed: {}",
+                    location,
+                    index + 1,
+                    self.commands.len(),
+                    command,
+                    e
+                ))
+            })?;
+        }
+        Ok(())
+    }
+}
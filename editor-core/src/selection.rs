@@ -93,4 +93,30 @@ impl Selection {
             }
         }
     }
+
+    // The columns the selection covers on `line`, if any, for a line
+    // `line_len` characters long.
+    pub fn columns_on_line(&self, line: usize, line_len: usize) -> Option<(usize, usize)> {
+        let start = self.start();
+        let end = self.end();
+        if self.is_empty() || line < start.line || line > end.line {
+            return None;
+        }
+
+        let (from, to) = match self.mode {
+            SelectionMode::Normal => (
+                if line == start.line { start.column } else { 0 },
+                if line == end.line {
+                    end.column
+                } else {
+                    line_len
+                },
+            ),
+            SelectionMode::Block => (
+                self.anchor.column.min(self.cursor.column),
+                self.anchor.column.max(self.cursor.column),
+            ),
+        };
+        Some((from.min(line_len), to.min(line_len)))
+    }
 }
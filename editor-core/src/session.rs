@@ -1,6 +1,9 @@
 use crate::bookmark::FileBookmarks;
+use crate::clipboard_history::ClipboardEntry;
 use crate::cursor::CursorPosition;
 use crate::error::{EditorError, Result};
+use crate::folding::FileFolds;
+use crate::split::SplitNode;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
 use std::fs;
@@ -10,6 +13,7 @@ use std::time::SystemTime;
 const DEFAULT_RECENT_FILES_LIMIT: usize = 20;
 const SESSION_DIR: &str = ".config/editor-rs";
 const SESSION_FILE_PREFIX: &str = "session";
+const SOCKET_EXTENSION: &str = "sock";
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct OpenFileState {
@@ -20,6 +24,22 @@ pub struct OpenFileState {
     pub active: bool,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SplitPaneState {
+    pub pane: usize,
+    pub path: PathBuf,
+    pub cursor_line: usize,
+    pub cursor_column: usize,
+    pub viewport_top: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionLayout {
+    pub root: SplitNode,
+    pub focused: usize,
+    pub panes: Vec<SplitPaneState>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Session {
     pub open_files: Vec<OpenFileState>,
@@ -28,6 +48,12 @@ pub struct Session {
     pub created_at: SystemTime,
     pub last_accessed: SystemTime,
     pub bookmarks: Vec<FileBookmarks>,
+    #[serde(default)]
+    pub folds: Vec<FileFolds>,
+    #[serde(default)]
+    pub layout: Option<SessionLayout>,
+    #[serde(default)]
+    pub clipboard_history: Vec<ClipboardEntry>,
 }
 
 impl Session {
@@ -40,6 +66,9 @@ impl Session {
             created_at: now,
             last_accessed: now,
             bookmarks: Vec::new(),
+            folds: Vec::new(),
+            layout: None,
+            clipboard_history: Vec::new(),
         }
     }
 
@@ -127,6 +156,23 @@ impl Session {
         self.last_accessed = SystemTime::now();
     }
 
+    pub fn save_folds(&mut self, file_path: PathBuf, folds: FileFolds) {
+        self.folds.retain(|ff| ff.file_path != file_path);
+        if !folds.folds.is_empty() {
+            self.folds.push(folds);
+        }
+        self.last_accessed = SystemTime::now();
+    }
+
+    pub fn load_folds(&self, file_path: &Path) -> Option<&FileFolds> {
+        self.folds.iter().find(|ff| ff.file_path == file_path)
+    }
+
+    pub fn remove_folds(&mut self, file_path: &Path) {
+        self.folds.retain(|ff| ff.file_path != file_path);
+        self.last_accessed = SystemTime::now();
+    }
+
     pub fn save_to_file(&self, path: &Path) -> Result<()> {
         let toml_string =
             toml::to_string_pretty(self).map_err(|e| EditorError::Io(std::io::Error::other(e)))?;
@@ -146,6 +192,9 @@ impl Session {
 
         session.open_files.retain(|f| f.path.exists());
         session.recent_files.retain(|p| p.exists());
+        if let Some(layout) = &mut session.layout {
+            layout.panes.retain(|pane| pane.path.exists());
+        }
 
         session.last_accessed = SystemTime::now();
         Ok(session)
@@ -173,6 +222,13 @@ impl SessionManager {
     }
 
     fn get_session_path() -> Result<PathBuf> {
+        let pid = std::process::id();
+        let session_file = format!("{}-{}.toml", SESSION_FILE_PREFIX, pid);
+
+        Ok(Self::session_dir()?.join(session_file))
+    }
+
+    fn session_dir() -> Result<PathBuf> {
         let home_dir = std::env::var("HOME").map_err(|_| {
             EditorError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -180,16 +236,47 @@ impl SessionManager {
             ))
         })?;
 
-        let pid = std::process::id();
-        let session_file = format!("{}-{}.toml", SESSION_FILE_PREFIX, pid);
-
-        Ok(PathBuf::from(home_dir).join(SESSION_DIR).join(session_file))
+        Ok(PathBuf::from(home_dir).join(SESSION_DIR))
     }
 
     pub fn session_path(&self) -> &Path {
         &self.session_path
     }
 
+    // Each instance listens for remote control next to its session file.
+    pub fn socket_path(&self) -> PathBuf {
+        self.session_path.with_extension(SOCKET_EXTENSION)
+    }
+
+    // Control sockets of the instances that may be running, newest first.
+    // A socket can outlive a crashed instance, so connecting may still fail.
+    pub fn running_instances() -> Result<Vec<PathBuf>> {
+        let session_dir = Self::session_dir()?;
+        if !session_dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut sockets = Vec::new();
+        for entry in fs::read_dir(session_dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let is_socket = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(SESSION_FILE_PREFIX))
+                && path.extension().is_some_and(|ext| ext == SOCKET_EXTENSION);
+            if is_socket {
+                let modified = entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .unwrap_or(SystemTime::UNIX_EPOCH);
+                sockets.push((modified, path));
+            }
+        }
+        sockets.sort_by(|a, b| b.cmp(a));
+        Ok(sockets.into_iter().map(|(_, path)| path).collect())
+    }
+
     pub fn save_session(&self, session: &Session) -> Result<()> {
         session.save_to_file(&self.session_path)
     }
@@ -210,14 +297,7 @@ impl SessionManager {
     }
 
     pub fn cleanup_stale_sessions(max_age_secs: u64) -> Result<()> {
-        let home_dir = std::env::var("HOME").map_err(|_| {
-            EditorError::Io(std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "HOME environment variable not set",
-            ))
-        })?;
-
-        let session_dir = PathBuf::from(home_dir).join(SESSION_DIR);
+        let session_dir = Self::session_dir()?;
         if !session_dir.exists() {
             return Ok(());
         }
@@ -0,0 +1,649 @@
+use crate::error::{EditorError, Result};
+use crate::text_change::TextChange;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_SNIPPETS: &str = include_str!("../snippets/default.json");
+const SNIPPETS_DIR: &str = ".config/editor-rs/snippets";
+const GLOBAL_SNIPPETS_EXTENSION: &str = "code-snippets";
+
+static DEFAULT_REGISTRY: Lazy<SnippetRegistry> = Lazy::new(|| {
+    SnippetRegistry::from_json_str(DEFAULT_SNIPPETS).expect("built-in snippets must parse")
+});
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    pub name: String,
+    pub prefixes: Vec<String>,
+    pub body: String,
+    pub description: Option<String>,
+    pub scopes: Vec<String>,
+}
+
+impl Snippet {
+    // Snippets without a scope apply to every language.
+    pub fn applies_to(&self, language: &str) -> bool {
+        self.scopes.is_empty()
+            || self
+                .scopes
+                .iter()
+                .any(|scope| scope.eq_ignore_ascii_case(language))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            OneOrMany::One(value) => vec![value],
+            OneOrMany::Many(values) => values,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SnippetEntry {
+    prefix: Option<OneOrMany>,
+    body: OneOrMany,
+    description: Option<String>,
+    scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SnippetRegistry {
+    snippets: Vec<Snippet>,
+}
+
+impl SnippetRegistry {
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    pub fn builtin() -> &'static SnippetRegistry {
+        &DEFAULT_REGISTRY
+    }
+
+    pub fn from_json_str(content: &str) -> Result<Self> {
+        let mut registry = Self::empty();
+        registry.merge_json_str(content, None)?;
+        Ok(registry)
+    }
+
+    pub fn user_snippets_dir() -> Result<PathBuf> {
+        let home_dir = std::env::var("HOME").map_err(|_| {
+            EditorError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "HOME environment variable not set",
+            ))
+        })?;
+
+        Ok(PathBuf::from(home_dir).join(SNIPPETS_DIR))
+    }
+
+    pub fn load_user() -> Result<Self> {
+        let mut registry = Self::builtin().clone();
+        let dir = Self::user_snippets_dir()?;
+        if dir.is_dir() {
+            registry.merge_dir(&dir)?;
+        }
+        Ok(registry)
+    }
+
+    // `rust.json` holds Rust snippets; `*.code-snippets` files are global
+    // and scope each entry themselves, as in VS Code.
+    pub fn merge_dir(&mut self, dir: &Path) -> Result<()> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file())
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let language = match path.extension().and_then(|ext| ext.to_str()) {
+                Some("json") => path.file_stem().and_then(|stem| stem.to_str()),
+                Some(GLOBAL_SNIPPETS_EXTENSION) => None,
+                _ => continue,
+            };
+            let content = fs::read_to_string(&path)?;
+            self.merge_json_str(&content, language)
+                .map_err(|e| EditorError::Parse(format!("{}: {}", path.display(), e)))?;
+        }
+        Ok(())
+    }
+
+    // Reads a VS Code snippet file. `language` scopes every entry of a
+    // per-language file such as `rust.json`; entries of global files carry
+    // their own comma-separated `scope`. A snippet with the same name as an
+    // existing one replaces it.
+    pub fn merge_json_str(&mut self, content: &str, language: Option<&str>) -> Result<()> {
+        let entries: BTreeMap<String, SnippetEntry> =
+            serde_json::from_str(content).map_err(|e| EditorError::Parse(e.to_string()))?;
+
+        for (name, entry) in entries {
+            let prefixes: Vec<String> = entry
+                .prefix
+                .map(OneOrMany::into_vec)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|prefix| prefix.trim().to_string())
+                .filter(|prefix| !prefix.is_empty())
+                .collect();
+            let scopes = match language {
+                Some(language) => vec![language.to_string()],
+                None => entry
+                    .scope
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(|scope| scope.trim().to_string())
+                    .filter(|scope| !scope.is_empty())
+                    .collect(),
+            };
+            let snippet = Snippet {
+                body: entry.body.into_vec().join("\n"),
+                description: entry.description,
+                name,
+                prefixes,
+                scopes,
+            };
+
+            match self
+                .snippets
+                .iter_mut()
+                .find(|existing| existing.name == snippet.name && existing.scopes == snippet.scopes)
+            {
+                Some(existing) => *existing = snippet,
+                None => self.snippets.push(snippet),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn all(&self) -> &[Snippet] {
+        &self.snippets
+    }
+
+    pub fn for_language<'a>(&'a self, language: &'a str) -> impl Iterator<Item = &'a Snippet> {
+        self.snippets
+            .iter()
+            .filter(move |snippet| snippet.applies_to(language))
+    }
+
+    pub fn find_by_prefix(&self, language: &str, prefix: &str) -> Option<&Snippet> {
+        self.snippets.iter().find(|snippet| {
+            snippet.applies_to(language) && snippet.prefixes.iter().any(|p| p == prefix)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetTabstop {
+    pub index: usize,
+    pub ranges: Vec<(usize, usize)>,
+    pub choices: Vec<String>,
+}
+
+// The text a snippet body expands to, with its tabstops as char ranges into
+// that text in visiting order: $1, $2, ... and finally $0.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ExpandedSnippet {
+    pub text: String,
+    pub tabstops: Vec<SnippetTabstop>,
+}
+
+pub struct SnippetOptions<'a> {
+    pub indent: &'a str,
+    pub tab: &'a str,
+    pub variable: &'a dyn Fn(&str) -> Option<String>,
+}
+
+impl Default for SnippetOptions<'_> {
+    fn default() -> Self {
+        Self {
+            indent: "",
+            tab: "\t",
+            variable: &|_| None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SnippetNode {
+    Text(String),
+    Tabstop {
+        index: usize,
+        children: Vec<SnippetNode>,
+        choices: Vec<String>,
+    },
+    Variable {
+        name: String,
+        default: Vec<SnippetNode>,
+    },
+}
+
+impl SnippetNode {
+    fn has_content(&self) -> bool {
+        matches!(self, SnippetNode::Tabstop { children, choices, .. }
+            if !children.is_empty() || !choices.is_empty())
+    }
+}
+
+pub fn parse_snippet(body: &str) -> ExpandedSnippet {
+    expand_snippet(body, &SnippetOptions::default())
+}
+
+// Expands a VS Code snippet body: `$1`, `${1}`, `${1:default}` (nestable),
+// `${1|one,two|}`, `$0`, and `$NAME`/`${NAME:default}` variables. A tabstop
+// used more than once mirrors the first occurrence that gives it a default.
+// Lines after the first are prefixed with `indent` and tabs become `tab`.
+pub fn expand_snippet(body: &str, options: &SnippetOptions) -> ExpandedSnippet {
+    let chars: Vec<char> = body.chars().collect();
+    let mut position = 0;
+    let nodes = parse_nodes(&chars, &mut position, false);
+
+    let mut defaults = HashMap::new();
+    collect_defaults(&nodes, &mut defaults);
+
+    let mut renderer = SnippetRenderer {
+        options,
+        defaults: &defaults,
+        text: String::new(),
+        length: 0,
+        ranges: BTreeMap::new(),
+        choices: HashMap::new(),
+        expanding: HashSet::new(),
+    };
+    renderer.render(&nodes);
+
+    let SnippetRenderer {
+        text,
+        length,
+        mut ranges,
+        mut choices,
+        ..
+    } = renderer;
+    let last = ranges.remove(&0).unwrap_or_else(|| vec![(length, length)]);
+    let mut tabstops: Vec<SnippetTabstop> = ranges
+        .into_iter()
+        .chain(std::iter::once((0, last)))
+        .map(|(index, mut ranges)| {
+            ranges.sort_unstable();
+            SnippetTabstop {
+                index,
+                ranges,
+                choices: choices.remove(&index).unwrap_or_default(),
+            }
+        })
+        .collect();
+    if let Some(final_stop) = tabstops.last_mut() {
+        final_stop.ranges.truncate(1);
+    }
+
+    ExpandedSnippet { text, tabstops }
+}
+
+fn parse_nodes(chars: &[char], i: &mut usize, nested: bool) -> Vec<SnippetNode> {
+    let mut nodes = Vec::new();
+    let mut text = String::new();
+    while *i < chars.len() {
+        match chars[*i] {
+            '\\' if matches!(chars.get(*i + 1), Some('$' | '}' | '\\')) => {
+                text.push(chars[*i + 1]);
+                *i += 2;
+            }
+            '}' if nested => break,
+            '$' => {
+                let start = *i;
+                match parse_dollar(chars, i) {
+                    Some(node) => {
+                        if !text.is_empty() {
+                            nodes.push(SnippetNode::Text(std::mem::take(&mut text)));
+                        }
+                        nodes.push(node);
+                    }
+                    None => {
+                        *i = start + 1;
+                        text.push('$');
+                    }
+                }
+            }
+            c => {
+                text.push(c);
+                *i += 1;
+            }
+        }
+    }
+    if !text.is_empty() {
+        nodes.push(SnippetNode::Text(text));
+    }
+    nodes
+}
+
+fn take_while(chars: &[char], i: &mut usize, predicate: impl Fn(char) -> bool) -> String {
+    let start = *i;
+    while chars.get(*i).is_some_and(|&c| predicate(c)) {
+        *i += 1;
+    }
+    chars[start..*i].iter().collect()
+}
+
+fn is_variable_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_variable_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn parse_dollar(chars: &[char], i: &mut usize) -> Option<SnippetNode> {
+    *i += 1;
+    match chars.get(*i)? {
+        c if c.is_ascii_digit() => Some(SnippetNode::Tabstop {
+            index: take_while(chars, i, |c| c.is_ascii_digit()).parse().ok()?,
+            children: Vec::new(),
+            choices: Vec::new(),
+        }),
+        &c if is_variable_start(c) => Some(SnippetNode::Variable {
+            name: take_while(chars, i, is_variable_char),
+            default: Vec::new(),
+        }),
+        '{' => {
+            *i += 1;
+            let first = *chars.get(*i)?;
+            if first.is_ascii_digit() {
+                let index = take_while(chars, i, |c| c.is_ascii_digit()).parse().ok()?;
+                match chars.get(*i)? {
+                    '}' => {
+                        *i += 1;
+                        Some(SnippetNode::Tabstop {
+                            index,
+                            children: Vec::new(),
+                            choices: Vec::new(),
+                        })
+                    }
+                    ':' => {
+                        *i += 1;
+                        let children = parse_nodes(chars, i, true);
+                        (chars.get(*i) == Some(&'}')).then(|| {
+                            *i += 1;
+                            SnippetNode::Tabstop {
+                                index,
+                                children,
+                                choices: Vec::new(),
+                            }
+                        })
+                    }
+                    '|' => {
+                        *i += 1;
+                        let choices = parse_choices(chars, i)?;
+                        Some(SnippetNode::Tabstop {
+                            index,
+                            children: Vec::new(),
+                            choices,
+                        })
+                    }
+                    _ => None,
+                }
+            } else if is_variable_start(first) {
+                let name = take_while(chars, i, is_variable_char);
+                match chars.get(*i)? {
+                    '}' => {
+                        *i += 1;
+                        Some(SnippetNode::Variable {
+                            name,
+                            default: Vec::new(),
+                        })
+                    }
+                    ':' => {
+                        *i += 1;
+                        let default = parse_nodes(chars, i, true);
+                        (chars.get(*i) == Some(&'}')).then(|| {
+                            *i += 1;
+                            SnippetNode::Variable { name, default }
+                        })
+                    }
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+fn parse_choices(chars: &[char], i: &mut usize) -> Option<Vec<String>> {
+    let mut choices = Vec::new();
+    let mut choice = String::new();
+    loop {
+        match *chars.get(*i)? {
+            '\\' if matches!(chars.get(*i + 1), Some(',' | '|' | '\\' | '$' | '}')) => {
+                choice.push(chars[*i + 1]);
+                *i += 2;
+            }
+            ',' => {
+                choices.push(std::mem::take(&mut choice));
+                *i += 1;
+            }
+            '|' if chars.get(*i + 1) == Some(&'}') => {
+                choices.push(choice);
+                *i += 2;
+                return Some(choices);
+            }
+            c => {
+                choice.push(c);
+                *i += 1;
+            }
+        }
+    }
+}
+
+fn collect_defaults<'a>(nodes: &'a [SnippetNode], defaults: &mut HashMap<usize, &'a SnippetNode>) {
+    for node in nodes {
+        match node {
+            SnippetNode::Tabstop {
+                index, children, ..
+            } => {
+                if node.has_content() {
+                    defaults.entry(*index).or_insert(node);
+                }
+                collect_defaults(children, defaults);
+            }
+            SnippetNode::Variable { default, .. } => collect_defaults(default, defaults),
+            SnippetNode::Text(_) => {}
+        }
+    }
+}
+
+struct SnippetRenderer<'a> {
+    options: &'a SnippetOptions<'a>,
+    defaults: &'a HashMap<usize, &'a SnippetNode>,
+    text: String,
+    length: usize,
+    ranges: BTreeMap<usize, Vec<(usize, usize)>>,
+    choices: HashMap<usize, Vec<String>>,
+    expanding: HashSet<usize>,
+}
+
+impl SnippetRenderer<'_> {
+    fn push(&mut self, text: &str) {
+        for c in text.chars() {
+            let replacement = match c {
+                '\n' => {
+                    self.text.push('\n');
+                    self.length += 1;
+                    self.options.indent
+                }
+                '\t' => self.options.tab,
+                _ => {
+                    self.text.push(c);
+                    self.length += 1;
+                    continue;
+                }
+            };
+            self.text.push_str(replacement);
+            self.length += replacement.chars().count();
+        }
+    }
+
+    fn render(&mut self, nodes: &[SnippetNode]) {
+        for node in nodes {
+            match node {
+                SnippetNode::Text(text) => self.push(text),
+                SnippetNode::Tabstop { index, .. } => {
+                    let start = self.length;
+                    let source = if node.has_content() {
+                        Some(node)
+                    } else {
+                        self.defaults.get(index).copied()
+                    };
+                    // A placeholder that mirrors itself would never finish.
+                    if let Some(source) = source.filter(|_| self.expanding.insert(*index)) {
+                        if let SnippetNode::Tabstop {
+                            children, choices, ..
+                        } = source
+                        {
+                            match choices.first() {
+                                Some(first) => {
+                                    self.push(first);
+                                    self.choices.entry(*index).or_insert(choices.clone());
+                                }
+                                None => self.render(children),
+                            }
+                        }
+                        self.expanding.remove(index);
+                    }
+                    self.ranges
+                        .entry(*index)
+                        .or_default()
+                        .push((start, self.length));
+                }
+                SnippetNode::Variable { name, default } => match (self.options.variable)(name) {
+                    Some(value) => self.push(&value),
+                    None => self.render(default),
+                },
+            }
+        }
+    }
+}
+
+// Tracks the tabstops of an inserted snippet through later edits. Ranges of
+// the active tabstop grow with text typed at either edge; the others keep
+// their extent and move out of the way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetSession {
+    tabstops: Vec<SnippetTabstop>,
+    current: usize,
+    revision: u64,
+    placeholder_selected: bool,
+}
+
+impl SnippetSession {
+    pub fn new(tabstops: Vec<SnippetTabstop>, revision: u64) -> Self {
+        Self {
+            tabstops,
+            current: 0,
+            revision,
+            placeholder_selected: false,
+        }
+    }
+
+    pub fn tabstops(&self) -> &[SnippetTabstop] {
+        &self.tabstops
+    }
+
+    pub fn current_index(&self) -> usize {
+        self.current
+    }
+
+    pub fn current(&self) -> Option<&SnippetTabstop> {
+        self.tabstops.get(self.current)
+    }
+
+    pub fn is_at_final_tabstop(&self) -> bool {
+        self.current + 1 >= self.tabstops.len()
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn is_placeholder_selected(&self) -> bool {
+        self.placeholder_selected
+    }
+
+    pub fn set_placeholder_selected(&mut self, selected: bool) {
+        self.placeholder_selected = selected;
+    }
+
+    pub fn select_next(&mut self) -> bool {
+        if self.is_at_final_tabstop() {
+            return false;
+        }
+        self.current += 1;
+        true
+    }
+
+    pub fn select_previous(&mut self) -> bool {
+        if self.current == 0 {
+            return false;
+        }
+        self.current -= 1;
+        true
+    }
+
+    pub fn contains(&self, char_index: usize) -> bool {
+        self.current().is_some_and(|tabstop| {
+            tabstop
+                .ranges
+                .iter()
+                .any(|&(start, end)| start <= char_index && char_index <= end)
+        })
+    }
+
+    pub fn apply_changes(&mut self, changes: &[TextChange], revision: u64) {
+        for change in changes {
+            self.apply_change(change);
+        }
+        self.revision = revision;
+    }
+
+    pub fn apply_change(&mut self, change: &TextChange) {
+        let start = change.start.char_index;
+        let end = change.old_end.char_index;
+        let inserted = change.new_end.char_index - start;
+        let shift = |position: usize| position - (end - start) + inserted;
+
+        for (index, tabstop) in self.tabstops.iter_mut().enumerate() {
+            let active = index == self.current;
+            for range in &mut tabstop.ranges {
+                let (from, to) = *range;
+                let new_from = if from < start || (active && from == start) {
+                    from
+                } else if from >= end {
+                    shift(from)
+                } else if active {
+                    start
+                } else {
+                    start + inserted
+                };
+                let new_to = if to < start || (!active && to == start && from < to) {
+                    to
+                } else if to >= end {
+                    shift(to)
+                } else {
+                    start + inserted
+                };
+                *range = (new_from, new_to.max(new_from));
+            }
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use crate::cursor::{CursorPosition, MultiCursor};
+use crate::error::{EditorError, Result};
+use crate::selection::Selection;
+use crate::text_change::TextChange;
+use serde::{Deserialize, Serialize};
+
+const MIN_RATIO: f32 = 0.1;
+const MAX_RATIO: f32 = 0.9;
+const EPSILON: f32 = 1e-4;
+
+// Vertical splits put panes side by side, horizontal splits stack them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitDirection {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PaneDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SplitNode {
+    Pane {
+        id: usize,
+    },
+    Split {
+        direction: SplitDirection,
+        ratio: f32,
+        first: Box<SplitNode>,
+        second: Box<SplitNode>,
+    },
+}
+
+impl SplitNode {
+    fn pane_ids(&self, ids: &mut Vec<usize>) {
+        match self {
+            SplitNode::Pane { id } => ids.push(*id),
+            SplitNode::Split { first, second, .. } => {
+                first.pane_ids(ids);
+                second.pane_ids(ids);
+            }
+        }
+    }
+
+    fn contains(&self, pane: usize) -> bool {
+        match self {
+            SplitNode::Pane { id } => *id == pane,
+            SplitNode::Split { first, second, .. } => first.contains(pane) || second.contains(pane),
+        }
+    }
+
+    fn first_pane(&self) -> usize {
+        match self {
+            SplitNode::Pane { id } => *id,
+            SplitNode::Split { first, .. } => first.first_pane(),
+        }
+    }
+
+    fn rects(&self, rect: SplitRect, rects: &mut Vec<(usize, SplitRect)>) {
+        match self {
+            SplitNode::Pane { id } => rects.push((*id, rect)),
+            SplitNode::Split {
+                direction,
+                ratio,
+                first,
+                second,
+            } => {
+                let (a, b) = rect.divide(*direction, *ratio);
+                first.rects(a, rects);
+                second.rects(b, rects);
+            }
+        }
+    }
+
+    fn split(&mut self, pane: usize, direction: SplitDirection, new_pane: usize) -> bool {
+        match self {
+            SplitNode::Pane { id } if *id == pane => {
+                *self = SplitNode::Split {
+                    direction,
+                    ratio: 0.5,
+                    first: Box::new(SplitNode::Pane { id: pane }),
+                    second: Box::new(SplitNode::Pane { id: new_pane }),
+                };
+                true
+            }
+            SplitNode::Pane { .. } => false,
+            SplitNode::Split { first, second, .. } => {
+                first.split(pane, direction, new_pane) || second.split(pane, direction, new_pane)
+            }
+        }
+    }
+
+    // Replaces the split holding `pane` with its other child and returns the
+    // pane that should take focus.
+    fn remove(&mut self, pane: usize) -> Option<usize> {
+        let SplitNode::Split { first, second, .. } = self else {
+            return None;
+        };
+        let sibling = if matches!(**first, SplitNode::Pane { id } if id == pane) {
+            std::mem::replace(second.as_mut(), SplitNode::Pane { id: pane })
+        } else if matches!(**second, SplitNode::Pane { id } if id == pane) {
+            std::mem::replace(first.as_mut(), SplitNode::Pane { id: pane })
+        } else {
+            return first.remove(pane).or_else(|| second.remove(pane));
+        };
+        let focus = sibling.first_pane();
+        *self = sibling;
+        Some(focus)
+    }
+
+    // Moves the divider of the innermost split that is laid out along
+    // `direction` and contains `pane`, growing the pane by `delta`.
+    fn resize(&mut self, pane: usize, direction: SplitDirection, delta: f32) -> bool {
+        let SplitNode::Split {
+            direction: split_direction,
+            ratio,
+            first,
+            second,
+        } = self
+        else {
+            return false;
+        };
+        let in_first = first.contains(pane);
+        if !in_first && !second.contains(pane) {
+            return false;
+        }
+        let child = if in_first { first } else { second };
+        if child.resize(pane, direction, delta) {
+            return true;
+        }
+        if *split_direction != direction {
+            return false;
+        }
+        let delta = if in_first { delta } else { -delta };
+        *ratio = (*ratio + delta).clamp(MIN_RATIO, MAX_RATIO);
+        true
+    }
+
+    fn replace_pane(&mut self, from: usize, to: usize) {
+        match self {
+            SplitNode::Pane { id } if *id == from => *id = to,
+            SplitNode::Pane { .. } => {}
+            SplitNode::Split { first, second, .. } => {
+                first.replace_pane(from, to);
+                second.replace_pane(from, to);
+            }
+        }
+    }
+
+    fn max_id(&self) -> usize {
+        match self {
+            SplitNode::Pane { id } => *id,
+            SplitNode::Split { first, second, .. } => first.max_id().max(second.max_id()),
+        }
+    }
+}
+
+// A pane's share of the editor area, in fractions of its width and height.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SplitRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl SplitRect {
+    pub fn full() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            width: 1.0,
+            height: 1.0,
+        }
+    }
+
+    fn divide(self, direction: SplitDirection, ratio: f32) -> (Self, Self) {
+        match direction {
+            SplitDirection::Vertical => {
+                let width = self.width * ratio;
+                (
+                    Self { width, ..self },
+                    Self {
+                        x: self.x + width,
+                        width: self.width - width,
+                        ..self
+                    },
+                )
+            }
+            SplitDirection::Horizontal => {
+                let height = self.height * ratio;
+                (
+                    Self { height, ..self },
+                    Self {
+                        y: self.y + height,
+                        height: self.height - height,
+                        ..self
+                    },
+                )
+            }
+        }
+    }
+
+    fn right(&self) -> f32 {
+        self.x + self.width
+    }
+
+    fn bottom(&self) -> f32 {
+        self.y + self.height
+    }
+
+    // Distance from this rect to `other` in `direction`, with how much the
+    // two overlap on the other axis, or None when `other` isn't that way.
+    fn distance_to(&self, other: &SplitRect, direction: PaneDirection) -> Option<(f32, f32)> {
+        let (gap, overlap) = match direction {
+            PaneDirection::Left => (
+                self.x - other.right(),
+                self.bottom().min(other.bottom()) - self.y.max(other.y),
+            ),
+            PaneDirection::Right => (
+                other.x - self.right(),
+                self.bottom().min(other.bottom()) - self.y.max(other.y),
+            ),
+            PaneDirection::Up => (
+                self.y - other.bottom(),
+                self.right().min(other.right()) - self.x.max(other.x),
+            ),
+            PaneDirection::Down => (
+                other.y - self.bottom(),
+                self.right().min(other.right()) - self.x.max(other.x),
+            ),
+        };
+        (gap > -EPSILON && overlap > EPSILON).then_some((gap, overlap))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SplitLayout {
+    root: SplitNode,
+    focused: usize,
+}
+
+impl SplitLayout {
+    pub fn new() -> Self {
+        Self {
+            root: SplitNode::Pane { id: 0 },
+            focused: 0,
+        }
+    }
+
+    pub fn from_root(root: SplitNode, focused: usize) -> Self {
+        let focused = if root.contains(focused) {
+            focused
+        } else {
+            root.first_pane()
+        };
+        Self { root, focused }
+    }
+
+    pub fn root(&self) -> &SplitNode {
+        &self.root
+    }
+
+    pub fn focused(&self) -> usize {
+        self.focused
+    }
+
+    pub fn pane_count(&self) -> usize {
+        self.pane_ids().len()
+    }
+
+    pub fn is_split(&self) -> bool {
+        matches!(self.root, SplitNode::Split { .. })
+    }
+
+    pub fn pane_ids(&self) -> Vec<usize> {
+        let mut ids = Vec::new();
+        self.root.pane_ids(&mut ids);
+        ids
+    }
+
+    pub fn pane_rects(&self) -> Vec<(usize, SplitRect)> {
+        let mut rects = Vec::new();
+        self.root.rects(SplitRect::full(), &mut rects);
+        rects
+    }
+
+    pub fn focus(&mut self, pane: usize) -> Result<()> {
+        if !self.root.contains(pane) {
+            return Err(EditorError::InvalidOperation(format!(
+                "No split with id {}",
+                pane
+            )));
+        }
+        self.focused = pane;
+        Ok(())
+    }
+
+    // Splits the focused pane in two and returns the new pane, which takes
+    // the right or bottom half.
+    pub fn split(&mut self, direction: SplitDirection) -> usize {
+        let new_pane = self.root.max_id() + 1;
+        self.root.split(self.focused, direction, new_pane);
+        new_pane
+    }
+
+    pub fn close(&mut self, pane: usize) -> Result<usize> {
+        if !self.is_split() {
+            return Err(EditorError::InvalidOperation(
+                "Cannot close the last split".to_string(),
+            ));
+        }
+        let focus = self
+            .root
+            .remove(pane)
+            .ok_or_else(|| EditorError::InvalidOperation(format!("No split with id {}", pane)))?;
+        if self.focused == pane {
+            self.focused = focus;
+        }
+        Ok(self.focused)
+    }
+
+    pub fn neighbor(&self, direction: PaneDirection) -> Option<usize> {
+        let rects = self.pane_rects();
+        let (_, current) = rects.iter().find(|(id, _)| *id == self.focused)?;
+        rects
+            .iter()
+            .filter(|(id, _)| *id != self.focused)
+            .filter_map(|(id, rect)| {
+                current
+                    .distance_to(rect, direction)
+                    .map(|(gap, overlap)| (*id, gap, overlap))
+            })
+            .min_by(|a, b| {
+                a.1.total_cmp(&b.1)
+                    .then_with(|| b.2.total_cmp(&a.2))
+                    .then_with(|| a.0.cmp(&b.0))
+            })
+            .map(|(id, _, _)| id)
+    }
+
+    pub fn next_pane(&self) -> usize {
+        let ids = self.pane_ids();
+        let index = ids.iter().position(|id| *id == self.focused).unwrap_or(0);
+        ids[(index + 1) % ids.len()]
+    }
+
+    pub fn resize(&mut self, direction: SplitDirection, delta: f32) -> bool {
+        self.root.resize(self.focused, direction, delta)
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if a == b {
+            return;
+        }
+        let placeholder = self.root.max_id() + 1;
+        self.root.replace_pane(a, placeholder);
+        self.root.replace_pane(b, a);
+        self.root.replace_pane(placeholder, b);
+    }
+}
+
+impl Default for SplitLayout {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// What a pane shows while another pane has focus. The focused pane's view
+// lives in the editor's own cursor, selection and viewport fields.
+#[derive(Debug, Clone)]
+pub(crate) struct SplitView {
+    pub(crate) buffer_index: usize,
+    pub(crate) cursors: MultiCursor,
+    pub(crate) selection: Option<Selection>,
+    pub(crate) viewport_top: usize,
+    pub(crate) revision: u64,
+}
+
+impl SplitView {
+    // Keeps the view's cursors on the same text while another pane edits
+    // the buffer.
+    pub(crate) fn apply_changes(&mut self, changes: &[TextChange], revision: u64) {
+        for change in changes {
+            let positions = self
+                .cursors
+                .positions()
+                .iter()
+                .map(|&position| shift_position(position, change))
+                .collect();
+            self.cursors.set_positions(positions);
+            self.selection = self.selection.map(|selection| Selection {
+                anchor: shift_position(selection.anchor, change),
+                cursor: shift_position(selection.cursor, change),
+                ..selection
+            });
+            if change.old_end.line < self.viewport_top {
+                self.viewport_top = self.viewport_top + change.new_end.line - change.old_end.line;
+            } else if change.start.line < self.viewport_top {
+                self.viewport_top = change.start.line;
+            }
+        }
+        self.revision = revision;
+    }
+}
+
+fn shift_position(position: CursorPosition, change: &TextChange) -> CursorPosition {
+    let at = (position.line, position.column);
+    if at < (change.start.line, change.start.column) {
+        return position;
+    }
+    if at < (change.old_end.line, change.old_end.column) {
+        return CursorPosition::new(change.start.line, change.start.column);
+    }
+    let line = position.line - change.old_end.line + change.new_end.line;
+    let column = if position.line == change.old_end.line {
+        position.column - change.old_end.column + change.new_end.column
+    } else {
+        position.column
+    };
+    CursorPosition::new(line, column)
+}
@@ -0,0 +1,212 @@
+use crate::error::{EditorError, Result};
+use crate::git_history::GitHistoryManager;
+use crate::language::LanguageRegistry;
+use crate::symbols::{document_symbols, Symbol};
+use crate::syntax::SyntaxHighlighter;
+use ropey::Rope;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+const INDEX_DIR: &str = "symbols";
+const MAX_INDEXED_FILE_BYTES: u64 = 1024 * 1024;
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolLocation {
+    pub path: Option<PathBuf>,
+    pub symbol: Symbol,
+}
+
+impl SymbolLocation {
+    pub fn new(path: Option<PathBuf>, symbol: Symbol) -> Self {
+        Self { path, symbol }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct IndexedFile {
+    modified: u64,
+    size: u64,
+    symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SymbolIndex {
+    root: PathBuf,
+    files: BTreeMap<PathBuf, IndexedFile>,
+}
+
+impl SymbolIndex {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            files: BTreeMap::new(),
+        }
+    }
+
+    pub fn default_index_path(root: &Path) -> Result<PathBuf> {
+        let home = dirs::home_dir().ok_or_else(|| {
+            EditorError::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "Could not determine home directory",
+            ))
+        })?;
+        let hash = GitHistoryManager::project_hash(root)?;
+        Ok(home
+            .join(".editor-rs")
+            .join(INDEX_DIR)
+            .join(format!("{}.json", hash)))
+    }
+
+    // An index written for another root, or one that no longer parses, is
+    // discarded and rebuilt rather than reported as an error.
+    pub fn load(index_path: &Path, root: &Path) -> Self {
+        fs::read_to_string(index_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<SymbolIndex>(&content).ok())
+            .filter(|index| index.root == root)
+            .unwrap_or_else(|| Self::new(root.to_path_buf()))
+    }
+
+    pub fn save(&self, index_path: &Path) -> Result<()> {
+        if let Some(parent) = index_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(self)
+            .map_err(|e| EditorError::Parse(format!("Failed to serialize symbol index: {}", e)))?;
+        fs::write(index_path, content)?;
+        Ok(())
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn file_count(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    pub fn file_symbols(&self, path: &Path) -> Option<&[Symbol]> {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        self.files.get(relative).map(|file| file.symbols.as_slice())
+    }
+
+    // Only files whose size or modification time changed since the last
+    // refresh are parsed again. Returns whether anything changed.
+    pub fn refresh(&mut self, languages: &LanguageRegistry, tab_size: usize) -> Result<bool> {
+        let mut paths = Vec::new();
+        collect_files(&self.root, &mut paths)?;
+
+        let mut changed = false;
+        let mut seen = HashSet::new();
+        for path in paths {
+            let Some(language) = languages.for_path(&path) else {
+                continue;
+            };
+            if language.syntax.is_none() && language.symbols.is_empty() {
+                continue;
+            }
+            let Ok(metadata) = fs::metadata(&path) else {
+                continue;
+            };
+            if metadata.len() > MAX_INDEXED_FILE_BYTES {
+                continue;
+            }
+
+            let relative = path.strip_prefix(&self.root).unwrap_or(&path).to_path_buf();
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|duration| duration.as_nanos() as u64)
+                .unwrap_or(0);
+            seen.insert(relative.clone());
+
+            let fresh = self
+                .files
+                .get(&relative)
+                .is_some_and(|file| file.modified == modified && file.size == metadata.len());
+            if fresh {
+                continue;
+            }
+
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let rope = Rope::from_str(&content);
+            let highlighter = language
+                .syntax
+                .and_then(|syntax| SyntaxHighlighter::new(syntax, &rope));
+            let symbols = document_symbols(
+                &rope,
+                highlighter.as_ref().and_then(|h| h.tree()),
+                language.syntax,
+                &language.symbols,
+                tab_size,
+            );
+            self.files.insert(
+                relative,
+                IndexedFile {
+                    modified,
+                    size: metadata.len(),
+                    symbols,
+                },
+            );
+            changed = true;
+        }
+
+        let before = self.files.len();
+        self.files.retain(|path, _| seen.contains(path));
+        Ok(changed || self.files.len() != before)
+    }
+
+    pub fn locations(&self) -> Vec<SymbolLocation> {
+        self.files
+            .iter()
+            .flat_map(|(path, file)| {
+                let path = self.root.join(path);
+                file.symbols
+                    .iter()
+                    .map(move |symbol| SymbolLocation::new(Some(path.clone()), symbol.clone()))
+            })
+            .collect()
+    }
+
+    pub fn find(&self, name: &str) -> Vec<SymbolLocation> {
+        self.locations()
+            .into_iter()
+            .filter(|location| location.symbol.name == name)
+            .collect()
+    }
+}
+
+fn collect_files(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries: Vec<_> = fs::read_dir(dir)?.filter_map(|entry| entry.ok()).collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                collect_files(&entry.path(), paths)?;
+            }
+        } else if file_type.is_file() {
+            paths.push(entry.path());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use crate::command::Command;
+use crate::fuzzy::fuzzy_match;
+use crate::symbol_index::SymbolLocation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolScope {
+    File,
+    Project,
+}
+
+impl SymbolScope {
+    pub fn title(&self) -> &'static str {
+        match self {
+            SymbolScope::File => "Go to Symbol in File",
+            SymbolScope::Project => "Go to Symbol in Project",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolPickerItem {
+    pub location: SymbolLocation,
+    pub indices: Vec<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SymbolPicker {
+    scope: SymbolScope,
+    query: String,
+    candidates: Vec<SymbolLocation>,
+    items: Vec<SymbolPickerItem>,
+    selected_index: usize,
+}
+
+impl SymbolPicker {
+    pub fn new(scope: SymbolScope, candidates: Vec<SymbolLocation>) -> Self {
+        let mut picker = Self {
+            scope,
+            query: String::new(),
+            candidates,
+            items: Vec::new(),
+            selected_index: 0,
+        };
+        picker.update_items();
+        picker
+    }
+
+    pub fn scope(&self) -> SymbolScope {
+        self.scope
+    }
+
+    pub fn title(&self) -> &'static str {
+        self.scope.title()
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn items(&self) -> &[SymbolPickerItem] {
+        &self.items
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    pub fn selected_item(&self) -> Option<&SymbolPickerItem> {
+        self.items.get(self.selected_index)
+    }
+
+    pub fn set_query(&mut self, query: impl Into<String>) {
+        self.query = query.into();
+        self.update_items();
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        self.query.push(c);
+        self.update_items();
+    }
+
+    pub fn backspace(&mut self) {
+        self.query.pop();
+        self.update_items();
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected_index + 1 < self.items.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn select_index(&mut self, index: usize) -> bool {
+        if index < self.items.len() {
+            self.selected_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn confirm(&self) -> Option<Command> {
+        let location = &self.selected_item()?.location;
+        Some(Command::JumpToSymbol {
+            path: location.path.clone(),
+            line: location.symbol.line,
+            column: location.symbol.column,
+        })
+    }
+
+    // With an empty query the file picker keeps document order; otherwise
+    // results are ranked by match score.
+    fn update_items(&mut self) {
+        let mut matches: Vec<(i64, SymbolPickerItem)> = self
+            .candidates
+            .iter()
+            .filter_map(|location| {
+                fuzzy_match(&self.query, &location.symbol.name).map(|m| {
+                    (
+                        m.score,
+                        SymbolPickerItem {
+                            location: location.clone(),
+                            indices: m.indices,
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.items = matches.into_iter().map(|(_, item)| item).collect();
+        self.selected_index = 0;
+    }
+}
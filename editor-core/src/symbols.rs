@@ -0,0 +1,502 @@
+use crate::error::{EditorError, Result};
+use crate::folding::indentation_fold_ranges;
+use crate::syntax::SyntaxLanguage;
+use regex::Regex;
+use ropey::Rope;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use tree_sitter::{Node, Tree};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Struct,
+    Enum,
+    Trait,
+    Impl,
+    Class,
+    Interface,
+    Module,
+    Heading,
+}
+
+impl SymbolKind {
+    pub fn all() -> &'static [SymbolKind] {
+        &[
+            SymbolKind::Function,
+            SymbolKind::Method,
+            SymbolKind::Struct,
+            SymbolKind::Enum,
+            SymbolKind::Trait,
+            SymbolKind::Impl,
+            SymbolKind::Class,
+            SymbolKind::Interface,
+            SymbolKind::Module,
+            SymbolKind::Heading,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Method => "method",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Impl => "impl",
+            SymbolKind::Class => "class",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Module => "module",
+            SymbolKind::Heading => "heading",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|kind| kind.name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SymbolKind::Function => "fn",
+            SymbolKind::Method => "method",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Impl => "impl",
+            SymbolKind::Class => "class",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Module => "mod",
+            SymbolKind::Heading => "#",
+        }
+    }
+
+    pub fn is_container(&self) -> bool {
+        matches!(
+            self,
+            SymbolKind::Impl | SymbolKind::Trait | SymbolKind::Class | SymbolKind::Interface
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub line: usize,
+    pub column: usize,
+    pub end_line: usize,
+    #[serde(default)]
+    pub depth: usize,
+}
+
+impl Symbol {
+    pub fn new(name: impl Into<String>, kind: SymbolKind, line: usize, column: usize) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            line,
+            column,
+            end_line: line,
+            depth: 0,
+        }
+    }
+
+    pub fn contains_line(&self, line: usize) -> bool {
+        line >= self.line && line <= self.end_line
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolPattern {
+    pub kind: SymbolKind,
+    pub pattern: String,
+}
+
+impl SymbolPattern {
+    pub fn new(kind: SymbolKind, pattern: impl Into<String>) -> Result<Self> {
+        let pattern = pattern.into();
+        compile_pattern(&pattern)?;
+        Ok(Self { kind, pattern })
+    }
+}
+
+fn compile_pattern(pattern: &str) -> Result<Regex> {
+    let regex = Regex::new(pattern)
+        .map_err(|e| EditorError::Parse(format!("Invalid symbol pattern '{}': {}", pattern, e)))?;
+    if regex.captures_len() < 2 {
+        return Err(EditorError::Parse(format!(
+            "Symbol pattern '{}' must capture the symbol name",
+            pattern
+        )));
+    }
+    Ok(regex)
+}
+
+// Languages with a grammar use the syntax tree; everything else (and large
+// files where highlighting is off) falls back to the per-language patterns.
+pub fn document_symbols(
+    rope: &Rope,
+    tree: Option<&Tree>,
+    syntax: Option<SyntaxLanguage>,
+    patterns: &[SymbolPattern],
+    tab_size: usize,
+) -> Vec<Symbol> {
+    if let (Some(tree), Some(language)) = (tree, syntax) {
+        if let Some(symbols) = syntax_symbols(tree, rope, language) {
+            return symbols;
+        }
+    }
+    pattern_symbols(rope, patterns, tab_size)
+}
+
+pub fn syntax_symbols(tree: &Tree, rope: &Rope, language: SyntaxLanguage) -> Option<Vec<Symbol>> {
+    let classify: fn(&Node, &Rope) -> Option<(SymbolKind, String)> = match language {
+        SyntaxLanguage::Rust => rust_symbol,
+        SyntaxLanguage::Python => python_symbol,
+        SyntaxLanguage::Shell => shell_symbol,
+        SyntaxLanguage::Markdown => markdown_symbol,
+        SyntaxLanguage::Toml | SyntaxLanguage::Json => return None,
+    };
+
+    let mut symbols = Vec::new();
+    let mut cursor = tree.walk();
+    'walk: loop {
+        let node = cursor.node();
+        if let Some((kind, name)) = classify(&node, rope) {
+            let anchor = node.child_by_field_name("name").unwrap_or(node);
+            let mut symbol = Symbol::new(name, kind, anchor.start_position().row, 0);
+            symbol.column = char_column(rope, anchor.start_byte());
+            symbol.end_line = symbol_end_row(&node).max(symbol.line);
+            symbols.push(symbol);
+        }
+
+        if cursor.goto_first_child() {
+            continue;
+        }
+        loop {
+            if cursor.goto_next_sibling() {
+                continue 'walk;
+            }
+            if !cursor.goto_parent() {
+                break 'walk;
+            }
+        }
+    }
+
+    nest_symbols(&mut symbols);
+    Some(symbols)
+}
+
+fn rust_symbol(node: &Node, rope: &Rope) -> Option<(SymbolKind, String)> {
+    let kind = match node.kind() {
+        "function_item" | "function_signature_item" => SymbolKind::Function,
+        "struct_item" | "union_item" => SymbolKind::Struct,
+        "enum_item" => SymbolKind::Enum,
+        "trait_item" => SymbolKind::Trait,
+        "mod_item" => SymbolKind::Module,
+        "impl_item" => {
+            let type_name = node_text(&node.child_by_field_name("type")?, rope);
+            let name = match node.child_by_field_name("trait") {
+                Some(trait_node) => format!("{} for {}", node_text(&trait_node, rope), type_name),
+                None => type_name,
+            };
+            return Some((SymbolKind::Impl, name));
+        }
+        _ => return None,
+    };
+    Some((kind, node_text(&node.child_by_field_name("name")?, rope)))
+}
+
+fn python_symbol(node: &Node, rope: &Rope) -> Option<(SymbolKind, String)> {
+    let kind = match node.kind() {
+        "function_definition" => SymbolKind::Function,
+        "class_definition" => SymbolKind::Class,
+        _ => return None,
+    };
+    Some((kind, node_text(&node.child_by_field_name("name")?, rope)))
+}
+
+fn shell_symbol(node: &Node, rope: &Rope) -> Option<(SymbolKind, String)> {
+    (node.kind() == "function_definition").then_some(())?;
+    Some((
+        SymbolKind::Function,
+        node_text(&node.child_by_field_name("name")?, rope),
+    ))
+}
+
+fn markdown_symbol(node: &Node, rope: &Rope) -> Option<(SymbolKind, String)> {
+    if !matches!(node.kind(), "atx_heading" | "setext_heading") {
+        return None;
+    }
+    let content = node.child_by_field_name("heading_content")?;
+    let name = node_text(&content, rope).trim().to_string();
+    (!name.is_empty()).then_some((SymbolKind::Heading, name))
+}
+
+// A Markdown heading owns the section it opens, so nested headings end up
+// below it in the outline.
+fn symbol_end_row(node: &Node) -> usize {
+    let owner = match node.parent() {
+        Some(parent) if node.kind() == "atx_heading" && parent.kind() == "section" => parent,
+        _ => *node,
+    };
+    let end = owner.end_position();
+    if end.column == 0 && end.row > owner.start_position().row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+fn node_text(node: &Node, rope: &Rope) -> String {
+    let text = rope
+        .byte_slice(node.start_byte()..node.end_byte())
+        .to_string();
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn char_column(rope: &Rope, byte: usize) -> usize {
+    let char_idx = rope.byte_to_char(byte);
+    char_idx - rope.line_to_char(rope.char_to_line(char_idx))
+}
+
+pub fn pattern_symbols(rope: &Rope, patterns: &[SymbolPattern], tab_size: usize) -> Vec<Symbol> {
+    let compiled: Vec<(SymbolKind, Regex)> = patterns
+        .iter()
+        .filter_map(|pattern| {
+            compile_pattern(&pattern.pattern)
+                .ok()
+                .map(|regex| (pattern.kind, regex))
+        })
+        .collect();
+    if compiled.is_empty() {
+        return Vec::new();
+    }
+
+    let ranges = indentation_fold_ranges(rope, tab_size);
+    let mut symbols = Vec::new();
+    for (line_idx, line) in rope.lines().enumerate() {
+        let text = line.to_string();
+        let text = text.trim_end_matches(['\n', '\r']);
+        for (kind, regex) in &compiled {
+            let Some(captures) = regex.captures(text) else {
+                continue;
+            };
+            let Some(name) = captures.name("name").or_else(|| captures.get(1)) else {
+                continue;
+            };
+            let mut symbol = Symbol::new(
+                name.as_str().trim(),
+                *kind,
+                line_idx,
+                text[..name.start()].chars().count(),
+            );
+            if let Ok(index) = ranges.binary_search_by_key(&line_idx, |range| range.start_line) {
+                symbol.end_line = ranges[index].end_line;
+            }
+            symbols.push(symbol);
+            break;
+        }
+    }
+
+    nest_symbols(&mut symbols);
+    symbols
+}
+
+// Depth comes from line containment; functions directly inside an impl,
+// trait, class or interface are reported as methods.
+fn nest_symbols(symbols: &mut [Symbol]) {
+    symbols.sort_by_key(|symbol| (symbol.line, symbol.column));
+    let mut enclosing: Vec<(usize, SymbolKind)> = Vec::new();
+    for symbol in symbols.iter_mut() {
+        while enclosing
+            .last()
+            .is_some_and(|&(end_line, _)| end_line < symbol.line)
+        {
+            enclosing.pop();
+        }
+        symbol.depth = enclosing.len();
+        if symbol.kind == SymbolKind::Function
+            && enclosing
+                .last()
+                .is_some_and(|(_, kind)| kind.is_container())
+        {
+            symbol.kind = SymbolKind::Method;
+        }
+        if symbol.end_line > symbol.line {
+            enclosing.push((symbol.end_line, symbol.kind));
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutlineEntry {
+    pub symbol: Symbol,
+    pub has_children: bool,
+    pub expanded: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SymbolOutline {
+    symbols: Vec<Symbol>,
+    collapsed: HashSet<(SymbolKind, String, usize)>,
+    entries: Vec<OutlineEntry>,
+    selected_index: usize,
+}
+
+impl SymbolOutline {
+    pub fn new(symbols: Vec<Symbol>) -> Self {
+        let mut outline = Self::default();
+        outline.set_symbols(symbols);
+        outline
+    }
+
+    // Collapsed state and the selection are keyed by symbol identity, so
+    // they survive edits that move symbols around.
+    pub fn set_symbols(&mut self, symbols: Vec<Symbol>) {
+        let selected = self.selected_entry().map(|entry| key(&entry.symbol));
+        self.symbols = symbols;
+        let keys: HashSet<_> = self.symbols.iter().map(key).collect();
+        self.collapsed.retain(|collapsed| keys.contains(collapsed));
+        self.rebuild();
+
+        self.selected_index = selected
+            .and_then(|selected| {
+                self.entries
+                    .iter()
+                    .position(|entry| key(&entry.symbol) == selected)
+            })
+            .unwrap_or_else(|| {
+                self.selected_index
+                    .min(self.entries.len().saturating_sub(1))
+            });
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn entries(&self) -> &[OutlineEntry] {
+        &self.entries
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    pub fn selected_entry(&self) -> Option<&OutlineEntry> {
+        self.entries.get(self.selected_index)
+    }
+
+    pub fn select_next(&mut self) {
+        if self.selected_index + 1 < self.entries.len() {
+            self.selected_index += 1;
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected_index = self.selected_index.saturating_sub(1);
+    }
+
+    pub fn select_first(&mut self) {
+        self.selected_index = 0;
+    }
+
+    pub fn select_last(&mut self) {
+        self.selected_index = self.entries.len().saturating_sub(1);
+    }
+
+    pub fn select_index(&mut self, index: usize) -> bool {
+        if index < self.entries.len() {
+            self.selected_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn expand_selected(&mut self) {
+        if let Some(entry) = self.selected_entry() {
+            let key = key(&entry.symbol);
+            if self.collapsed.remove(&key) {
+                self.rebuild();
+            }
+        }
+    }
+
+    // Collapsing a leaf or an already collapsed entry moves the selection to
+    // its parent, like the file tree.
+    pub fn collapse_selected(&mut self) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        if entry.has_children && entry.expanded {
+            self.collapsed.insert(key(&entry.symbol));
+            self.rebuild();
+            return;
+        }
+
+        let depth = entry.symbol.depth;
+        if let Some(parent) = self.entries[..self.selected_index]
+            .iter()
+            .rposition(|candidate| candidate.symbol.depth < depth)
+        {
+            self.selected_index = parent;
+        }
+    }
+
+    pub fn toggle_selected(&mut self) {
+        match self.selected_entry() {
+            Some(entry) if entry.has_children && entry.expanded => self.collapse_selected(),
+            Some(entry) if entry.has_children => self.expand_selected(),
+            _ => {}
+        }
+    }
+
+    pub fn select_line(&mut self, line: usize) {
+        if let Some(index) = self
+            .entries
+            .iter()
+            .rposition(|entry| entry.symbol.contains_line(line))
+        {
+            self.selected_index = index;
+        }
+    }
+
+    fn rebuild(&mut self) {
+        let mut entries = Vec::with_capacity(self.symbols.len());
+        let mut hidden_below: Option<usize> = None;
+        for (index, symbol) in self.symbols.iter().enumerate() {
+            if let Some(depth) = hidden_below {
+                if symbol.depth > depth {
+                    continue;
+                }
+                hidden_below = None;
+            }
+
+            let has_children = self
+                .symbols
+                .get(index + 1)
+                .is_some_and(|next| next.depth > symbol.depth);
+            let expanded = !self.collapsed.contains(&key(symbol));
+            if has_children && !expanded {
+                hidden_below = Some(symbol.depth);
+            }
+            entries.push(OutlineEntry {
+                symbol: symbol.clone(),
+                has_children,
+                expanded,
+            });
+        }
+        self.entries = entries;
+    }
+}
+
+fn key(symbol: &Symbol) -> (SymbolKind, String, usize) {
+    (symbol.kind, symbol.name.clone(), symbol.depth)
+}
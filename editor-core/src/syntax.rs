@@ -0,0 +1,7 @@
+mod highlighter;
+mod language;
+mod types;
+
+pub use highlighter::{highlight_segments, SyntaxHighlighter};
+pub use language::SyntaxLanguage;
+pub use types::{HighlightKind, HighlightSpan};
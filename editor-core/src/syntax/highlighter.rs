@@ -0,0 +1,210 @@
+use super::language::SyntaxLanguage;
+use super::types::{HighlightKind, HighlightSpan};
+use crate::text_change::TextChange;
+use ropey::Rope;
+use std::cell::RefCell;
+use streaming_iterator::StreamingIterator;
+use tree_sitter::{InputEdit, Node, Parser, Point, QueryCursor, Tree};
+
+pub struct SyntaxHighlighter {
+    language: SyntaxLanguage,
+    parser: Parser,
+    tree: Option<Tree>,
+    line_cache: RefCell<Vec<Option<Vec<HighlightSpan>>>>,
+}
+
+impl SyntaxHighlighter {
+    pub fn new(language: SyntaxLanguage, rope: &Rope) -> Option<Self> {
+        let mut parser = Parser::new();
+        parser.set_language(&language.grammar()).ok()?;
+
+        let mut highlighter = Self {
+            language,
+            parser,
+            tree: None,
+            line_cache: RefCell::new(Vec::new()),
+        };
+        highlighter.reparse(rope);
+        Some(highlighter)
+    }
+
+    pub fn language(&self) -> SyntaxLanguage {
+        self.language
+    }
+
+    pub fn tree(&self) -> Option<&Tree> {
+        self.tree.as_ref()
+    }
+
+    pub fn reparse(&mut self, rope: &Rope) {
+        self.tree = parse_rope(&mut self.parser, rope, None);
+        *self.line_cache.borrow_mut() = vec![None; rope.len_lines()];
+    }
+
+    pub fn apply_change(&mut self, change: &TextChange, rope: &Rope) {
+        let Some(mut old_tree) = self.tree.take() else {
+            self.reparse(rope);
+            return;
+        };
+
+        old_tree.edit(&InputEdit {
+            start_byte: change.start.byte,
+            old_end_byte: change.old_end.byte,
+            new_end_byte: change.new_end.byte,
+            start_position: Point::new(change.start.line, change.start.byte_column),
+            old_end_position: Point::new(change.old_end.line, change.old_end.byte_column),
+            new_end_position: Point::new(change.new_end.line, change.new_end.byte_column),
+        });
+        let new_tree = parse_rope(&mut self.parser, rope, Some(&old_tree));
+
+        let mut cache = self.line_cache.borrow_mut();
+        let start = change.start.line.min(cache.len());
+        let end = (change.old_end.line + 1).min(cache.len());
+        let inserted = change.new_end.line - change.start.line + 1;
+        cache.splice(start..end, std::iter::repeat_n(None, inserted));
+        cache.resize(rope.len_lines(), None);
+
+        if let Some(new_tree) = &new_tree {
+            for range in old_tree.changed_ranges(new_tree) {
+                let last = range.end_point.row.min(cache.len().saturating_sub(1));
+                for line in range.start_point.row..=last {
+                    if let Some(entry) = cache.get_mut(line) {
+                        *entry = None;
+                    }
+                }
+            }
+        }
+        drop(cache);
+
+        self.tree = new_tree;
+    }
+
+    pub fn line_spans(&self, rope: &Rope, line: usize) -> Vec<HighlightSpan> {
+        if line >= rope.len_lines() {
+            return Vec::new();
+        }
+        if let Some(Some(spans)) = self.line_cache.borrow().get(line) {
+            return spans.clone();
+        }
+
+        let spans = self.compute_line_spans(rope, line);
+        let mut cache = self.line_cache.borrow_mut();
+        if cache.len() != rope.len_lines() {
+            cache.resize(rope.len_lines(), None);
+        }
+        cache[line] = Some(spans.clone());
+        spans
+    }
+
+    fn compute_line_spans(&self, rope: &Rope, line: usize) -> Vec<HighlightSpan> {
+        let (Some(tree), Some(query)) = (&self.tree, self.language.highlights_query()) else {
+            return Vec::new();
+        };
+
+        let line_start = rope.line_to_byte(line);
+        let line_text = rope.line(line).to_string();
+        let line_end = line_start + line_text.len();
+        let mut kinds: Vec<Option<(HighlightKind, usize)>> = vec![None; line_text.len()];
+
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(line_start..line_end);
+        let text_provider = |node: Node| {
+            rope.byte_slice(node.byte_range())
+                .chunks()
+                .map(str::as_bytes)
+        };
+        let mut captures = cursor.captures(query, tree.root_node(), text_provider);
+
+        while let Some((query_match, index)) = captures.next() {
+            let capture = query_match.captures[*index];
+            let name = query.capture_names()[capture.index as usize];
+            let Some(kind) = HighlightKind::from_capture_name(name) else {
+                continue;
+            };
+
+            let node = capture.node;
+            let start = node.start_byte().max(line_start) - line_start;
+            let end = node.end_byte().min(line_end).saturating_sub(line_start);
+            for slot in kinds.iter_mut().take(end).skip(start) {
+                match slot {
+                    Some((_, id)) if *id == node.id() => {}
+                    _ => *slot = Some((kind, node.id())),
+                }
+            }
+        }
+
+        let mut spans: Vec<HighlightSpan> = Vec::new();
+        for (column, (byte, ch)) in line_text.char_indices().enumerate() {
+            if ch == '\n' {
+                break;
+            }
+            let Some((kind, _)) = kinds[byte] else {
+                continue;
+            };
+            match spans.last_mut() {
+                Some(span) if span.kind == kind && span.end == column => span.end = column + 1,
+                _ => spans.push(HighlightSpan {
+                    start: column,
+                    end: column + 1,
+                    kind,
+                }),
+            }
+        }
+        spans
+    }
+}
+
+impl Clone for SyntaxHighlighter {
+    fn clone(&self) -> Self {
+        let mut parser = Parser::new();
+        let _ = parser.set_language(&self.language.grammar());
+        Self {
+            language: self.language,
+            parser,
+            tree: self.tree.clone(),
+            line_cache: RefCell::new(self.line_cache.borrow().clone()),
+        }
+    }
+}
+
+impl std::fmt::Debug for SyntaxHighlighter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SyntaxHighlighter")
+            .field("language", &self.language)
+            .field("parsed", &self.tree.is_some())
+            .finish()
+    }
+}
+
+pub fn highlight_segments(
+    text: &str,
+    spans: &[HighlightSpan],
+) -> Vec<(String, Option<HighlightKind>)> {
+    let mut segments: Vec<(String, Option<HighlightKind>)> = Vec::new();
+
+    for (column, ch) in text.chars().enumerate() {
+        let kind = spans
+            .iter()
+            .find(|span| span.start <= column && column < span.end)
+            .map(|span| span.kind);
+        match segments.last_mut() {
+            Some((segment, segment_kind)) if *segment_kind == kind => segment.push(ch),
+            _ => segments.push((ch.to_string(), kind)),
+        }
+    }
+    segments
+}
+
+fn parse_rope(parser: &mut Parser, rope: &Rope, old_tree: Option<&Tree>) -> Option<Tree> {
+    parser.parse_with_options(
+        &mut |byte, _| {
+            if byte >= rope.len_bytes() {
+                return &[][..];
+            }
+            let (chunk, chunk_start, _, _) = rope.chunk_at_byte(byte);
+            &chunk.as_bytes()[byte - chunk_start..]
+        },
+        old_tree,
+        None,
+    )
+}
@@ -0,0 +1,122 @@
+use once_cell::sync::Lazy;
+use std::path::Path;
+use tree_sitter::{Language, Query};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxLanguage {
+    Rust,
+    Toml,
+    Markdown,
+    Json,
+    Python,
+    Shell,
+}
+
+static RUST_QUERY: Lazy<Option<Query>> = Lazy::new(|| SyntaxLanguage::Rust.compile_query());
+static TOML_QUERY: Lazy<Option<Query>> = Lazy::new(|| SyntaxLanguage::Toml.compile_query());
+static MARKDOWN_QUERY: Lazy<Option<Query>> = Lazy::new(|| SyntaxLanguage::Markdown.compile_query());
+static JSON_QUERY: Lazy<Option<Query>> = Lazy::new(|| SyntaxLanguage::Json.compile_query());
+static PYTHON_QUERY: Lazy<Option<Query>> = Lazy::new(|| SyntaxLanguage::Python.compile_query());
+static SHELL_QUERY: Lazy<Option<Query>> = Lazy::new(|| SyntaxLanguage::Shell.compile_query());
+
+impl SyntaxLanguage {
+    pub fn all() -> &'static [SyntaxLanguage] {
+        &[
+            SyntaxLanguage::Rust,
+            SyntaxLanguage::Toml,
+            SyntaxLanguage::Markdown,
+            SyntaxLanguage::Json,
+            SyntaxLanguage::Python,
+            SyntaxLanguage::Shell,
+        ]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyntaxLanguage::Rust => "rust",
+            SyntaxLanguage::Toml => "toml",
+            SyntaxLanguage::Markdown => "markdown",
+            SyntaxLanguage::Json => "json",
+            SyntaxLanguage::Python => "python",
+            SyntaxLanguage::Shell => "shell",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        match name.as_str() {
+            "bash" | "sh" => Some(SyntaxLanguage::Shell),
+            "md" => Some(SyntaxLanguage::Markdown),
+            _ => Self::all()
+                .iter()
+                .copied()
+                .find(|language| language.name() == name),
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let file_name = path.file_name()?.to_str()?;
+        match file_name {
+            "Cargo.lock" | "Pipfile" => return Some(SyntaxLanguage::Toml),
+            ".bashrc" | ".bash_profile" | ".profile" | ".zshrc" | "PKGBUILD" => {
+                return Some(SyntaxLanguage::Shell)
+            }
+            _ => {}
+        }
+
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "rs" => Some(SyntaxLanguage::Rust),
+            "toml" => Some(SyntaxLanguage::Toml),
+            "md" | "markdown" => Some(SyntaxLanguage::Markdown),
+            "json" | "jsonc" => Some(SyntaxLanguage::Json),
+            "py" | "pyi" | "pyw" => Some(SyntaxLanguage::Python),
+            "sh" | "bash" | "zsh" => Some(SyntaxLanguage::Shell),
+            _ => None,
+        }
+    }
+
+    pub fn grammar(&self) -> Language {
+        match self {
+            SyntaxLanguage::Rust => tree_sitter_rust::LANGUAGE.into(),
+            SyntaxLanguage::Toml => tree_sitter_toml_ng::LANGUAGE.into(),
+            SyntaxLanguage::Markdown => tree_sitter_md::LANGUAGE.into(),
+            SyntaxLanguage::Json => tree_sitter_json::LANGUAGE.into(),
+            SyntaxLanguage::Python => tree_sitter_python::LANGUAGE.into(),
+            SyntaxLanguage::Shell => tree_sitter_bash::LANGUAGE.into(),
+        }
+    }
+
+    pub fn highlights_query(&self) -> Option<&'static Query> {
+        let query = match self {
+            SyntaxLanguage::Rust => &RUST_QUERY,
+            SyntaxLanguage::Toml => &TOML_QUERY,
+            SyntaxLanguage::Markdown => &MARKDOWN_QUERY,
+            SyntaxLanguage::Json => &JSON_QUERY,
+            SyntaxLanguage::Python => &PYTHON_QUERY,
+            SyntaxLanguage::Shell => &SHELL_QUERY,
+        };
+        query.as_ref()
+    }
+
+    fn highlights_source(&self) -> &'static str {
+        match self {
+            SyntaxLanguage::Rust => tree_sitter_rust::HIGHLIGHTS_QUERY,
+            SyntaxLanguage::Toml => tree_sitter_toml_ng::HIGHLIGHTS_QUERY,
+            SyntaxLanguage::Markdown => tree_sitter_md::HIGHLIGHT_QUERY_BLOCK,
+            SyntaxLanguage::Json => tree_sitter_json::HIGHLIGHTS_QUERY,
+            SyntaxLanguage::Python => tree_sitter_python::HIGHLIGHTS_QUERY,
+            SyntaxLanguage::Shell => tree_sitter_bash::HIGHLIGHT_QUERY,
+        }
+    }
+
+    fn compile_query(&self) -> Option<Query> {
+        Query::new(&self.grammar(), self.highlights_source()).ok()
+    }
+}
+
+impl std::fmt::Display for SyntaxLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
@@ -0,0 +1,76 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HighlightKind {
+    Comment,
+    Keyword,
+    String,
+    Escape,
+    Number,
+    Constant,
+    Function,
+    Type,
+    Variable,
+    Property,
+    Attribute,
+    Operator,
+    Punctuation,
+    Heading,
+    Literal,
+    Link,
+}
+
+impl HighlightKind {
+    pub fn from_capture_name(name: &str) -> Option<Self> {
+        let kind = match name {
+            "escape" | "string.escape" => Self::Escape,
+            "string.special.key" => Self::Property,
+            "constant.numeric" | "number" | "float" => Self::Number,
+            "variable.builtin" => Self::Keyword,
+            "text.title" => Self::Heading,
+            "text.literal" => Self::Literal,
+            "text.uri" | "text.reference" => Self::Link,
+            _ => match name.split('.').next().unwrap_or(name) {
+                "comment" => Self::Comment,
+                "keyword" | "conditional" | "repeat" | "include" | "exception" => Self::Keyword,
+                "string" | "character" => Self::String,
+                "boolean" | "constant" => Self::Constant,
+                "function" | "method" | "constructor" => Self::Function,
+                "type" | "namespace" | "module" => Self::Type,
+                "variable" | "parameter" => Self::Variable,
+                "property" | "field" => Self::Property,
+                "attribute" | "label" | "tag" => Self::Attribute,
+                "operator" => Self::Operator,
+                "punctuation" => Self::Punctuation,
+                _ => return None,
+            },
+        };
+        Some(kind)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Comment => "comment",
+            Self::Keyword => "keyword",
+            Self::String => "string",
+            Self::Escape => "escape",
+            Self::Number => "number",
+            Self::Constant => "constant",
+            Self::Function => "function",
+            Self::Type => "type",
+            Self::Variable => "variable",
+            Self::Property => "property",
+            Self::Attribute => "attribute",
+            Self::Operator => "operator",
+            Self::Punctuation => "punctuation",
+            Self::Heading => "heading",
+            Self::Literal => "literal",
+            Self::Link => "link",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HighlightSpan {
+    pub start: usize,
+    pub end: usize,
+    pub kind: HighlightKind,
+}
@@ -0,0 +1,105 @@
+use ropey::Rope;
+
+const MAX_LOGGED_CHANGES: usize = 1024;
+const MAX_LOGGED_BYTES: usize = 16 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextPosition {
+    pub char_index: usize,
+    pub byte: usize,
+    pub line: usize,
+    pub column: usize,
+    pub byte_column: usize,
+    pub utf16_column: usize,
+}
+
+impl TextPosition {
+    pub fn from_char_index(rope: &Rope, char_index: usize) -> Self {
+        let line = rope.char_to_line(char_index);
+        let line_char = rope.line_to_char(line);
+        let byte = rope.char_to_byte(char_index);
+        Self {
+            char_index,
+            byte,
+            line,
+            column: char_index - line_char,
+            byte_column: byte - rope.line_to_byte(line),
+            utf16_column: rope.char_to_utf16_cu(char_index) - rope.char_to_utf16_cu(line_char),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextChange {
+    pub revision: u64,
+    pub start: TextPosition,
+    pub old_end: TextPosition,
+    pub new_end: TextPosition,
+    pub text: String,
+}
+
+impl TextChange {
+    pub fn apply(rope: &mut Rope, revision: u64, start: usize, end: usize, text: &str) -> Self {
+        let start_position = TextPosition::from_char_index(rope, start);
+        let old_end = TextPosition::from_char_index(rope, end);
+
+        rope.remove(start..end);
+        rope.insert(start, text);
+
+        let new_end = TextPosition::from_char_index(rope, start + text.chars().count());
+        Self {
+            revision,
+            start: start_position,
+            old_end,
+            new_end,
+            text: text.to_string(),
+        }
+    }
+
+    pub fn removed_chars(&self) -> usize {
+        self.old_end.char_index - self.start.char_index
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ChangeLog {
+    revision: u64,
+    changes: Vec<TextChange>,
+    logged_bytes: usize,
+}
+
+impl ChangeLog {
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    pub fn next_revision(&self) -> u64 {
+        self.revision + 1
+    }
+
+    pub fn record(&mut self, change: TextChange) {
+        self.revision = change.revision;
+        self.logged_bytes += change.text.len();
+        self.changes.push(change);
+
+        let mut dropped = 0;
+        while self.changes.len() - dropped > MAX_LOGGED_CHANGES
+            || (self.logged_bytes > MAX_LOGGED_BYTES && self.changes.len() - dropped > 1)
+        {
+            self.logged_bytes -= self.changes[dropped].text.len();
+            dropped += 1;
+        }
+        self.changes.drain(..dropped);
+    }
+
+    pub fn changes_since(&self, revision: u64) -> Option<&[TextChange]> {
+        if revision == self.revision {
+            return Some(&[]);
+        }
+        let index = self
+            .changes
+            .iter()
+            .position(|change| change.revision == revision + 1)?;
+        Some(&self.changes[index..])
+    }
+}
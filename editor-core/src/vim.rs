@@ -0,0 +1,825 @@
+use crate::command::Command;
+use crate::cursor::CursorPosition;
+use crate::error::{EditorError, Result};
+use crate::keymap::{Key, KeyPress};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+// Special keys are fed to the parser as the control characters vim itself
+// uses for them.
+pub const ESCAPE: char = '\u{1b}';
+pub const ENTER: char = '\r';
+pub const BACKSPACE: char = '\u{8}';
+pub const CTRL_R: char = '\u{12}';
+pub const CTRL_V: char = '\u{16}';
+
+pub const UNNAMED_REGISTER: char = '"';
+const YANK_REGISTER: char = '0';
+const SMALL_DELETE_REGISTER: char = '-';
+const BLACK_HOLE_REGISTER: char = '_';
+
+pub fn vim_key_char(press: &KeyPress) -> Option<char> {
+    let modifiers = press.modifiers;
+    if modifiers.alt || modifiers.meta {
+        return None;
+    }
+    if modifiers.ctrl {
+        return match press.key {
+            Key::Char('r') => Some(CTRL_R),
+            Key::Char('v') => Some(CTRL_V),
+            Key::Char('[') => Some(ESCAPE),
+            _ => None,
+        };
+    }
+    match press.key {
+        Key::Char(c) if modifiers.shift => Some(c.to_ascii_uppercase()),
+        Key::Char(c) => Some(c),
+        Key::Escape => Some(ESCAPE),
+        Key::Enter => Some(ENTER),
+        Key::Backspace => Some(BACKSPACE),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimOperator {
+    Delete,
+    Change,
+    Yank,
+    Indent,
+    Dedent,
+    Lowercase,
+    Uppercase,
+    ToggleCase,
+}
+
+impl VimOperator {
+    fn from_key(key: char) -> Option<Self> {
+        match key {
+            'd' => Some(VimOperator::Delete),
+            'c' => Some(VimOperator::Change),
+            'y' => Some(VimOperator::Yank),
+            '>' => Some(VimOperator::Indent),
+            '<' => Some(VimOperator::Dedent),
+            _ => None,
+        }
+    }
+
+    pub fn is_change(&self) -> bool {
+        !matches!(self, VimOperator::Yank)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordForward {
+        big: bool,
+    },
+    WordBackward {
+        big: bool,
+    },
+    WordEnd {
+        big: bool,
+    },
+    LineStart,
+    FirstNonBlank,
+    LineEnd,
+    FileStart,
+    FileEnd,
+    FindChar {
+        target: char,
+        forward: bool,
+        till: bool,
+    },
+    RepeatFind {
+        reverse: bool,
+    },
+    MatchingBracket,
+    ParagraphForward,
+    ParagraphBackward,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MotionKind {
+    Exclusive,
+    Inclusive,
+    Linewise,
+}
+
+impl VimMotion {
+    pub fn kind(&self) -> MotionKind {
+        match self {
+            VimMotion::Up | VimMotion::Down | VimMotion::FileStart | VimMotion::FileEnd => {
+                MotionKind::Linewise
+            }
+            VimMotion::WordEnd { .. } | VimMotion::LineEnd | VimMotion::MatchingBracket => {
+                MotionKind::Inclusive
+            }
+            VimMotion::FindChar { forward, .. } if *forward => MotionKind::Inclusive,
+            _ => MotionKind::Exclusive,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimTextObject {
+    Word { big: bool },
+    Quote(char),
+    Bracket { open: char, close: char },
+    Paragraph,
+    // The nearest string or pair of brackets of any kind, the line and the
+    // whole buffer, as selection expansion finds them.
+    AnyQuote,
+    AnyBracket,
+    Line,
+    Entire,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimTarget {
+    Motion(VimMotion),
+    Object { object: VimTextObject, around: bool },
+    Line,
+    Selection,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsertPosition {
+    BeforeCursor,
+    AfterCursor,
+    LineStart,
+    LineEnd,
+    LineBelow,
+    LineAbove,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VisualKind {
+    Char,
+    Line,
+    Block,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimAction {
+    Move(VimMotion),
+    Operate {
+        operator: VimOperator,
+        target: VimTarget,
+    },
+    SelectObject {
+        object: VimTextObject,
+        around: bool,
+    },
+    Insert(InsertPosition),
+    Paste {
+        before: bool,
+    },
+    ReplaceChar(char),
+    ToggleCaseChar,
+    JoinLines,
+    Undo,
+    Redo,
+    Repeat,
+    Visual(VisualKind),
+    SwapVisualEnds,
+    CommandLine(char),
+    SearchNext {
+        reverse: bool,
+    },
+    Escape,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VimCommand {
+    pub count: Option<usize>,
+    pub register: Option<char>,
+    pub action: VimAction,
+}
+
+impl VimCommand {
+    pub fn count(&self) -> usize {
+        self.count.unwrap_or(1)
+    }
+
+    // Whether `.` should repeat this command.
+    pub fn is_change(&self) -> bool {
+        match self.action {
+            VimAction::Operate { operator, target } => {
+                operator.is_change() && target != VimTarget::Selection
+            }
+            VimAction::Insert(_)
+            | VimAction::Paste { .. }
+            | VimAction::ReplaceChar(_)
+            | VimAction::ToggleCaseChar
+            | VimAction::JoinLines => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VimParse {
+    Complete(VimCommand),
+    Pending,
+    Invalid,
+}
+
+type Parsed<T> = std::result::Result<T, VimParse>;
+
+struct KeyReader<'a> {
+    keys: &'a [char],
+    index: usize,
+}
+
+impl KeyReader<'_> {
+    fn peek(&self) -> Option<char> {
+        self.keys.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Parsed<char> {
+        let key = self.peek().ok_or(VimParse::Pending)?;
+        self.index += 1;
+        Ok(key)
+    }
+
+    fn count(&mut self) -> Option<usize> {
+        if !matches!(self.peek(), Some('1'..='9')) {
+            return None;
+        }
+        let mut count = 0usize;
+        while let Some(digit) = self.peek().and_then(|key| key.to_digit(10)) {
+            count = count.saturating_mul(10).saturating_add(digit as usize);
+            self.index += 1;
+        }
+        Some(count)
+    }
+}
+
+// Parses the keys typed so far in normal or visual mode into a command,
+// following vim's `["x][count]operator[count]motion` grammar.
+pub fn parse_vim_keys(keys: &[char], visual: bool) -> VimParse {
+    let mut reader = KeyReader { keys, index: 0 };
+    match parse_command(&mut reader, visual) {
+        Ok(command) => VimParse::Complete(command),
+        Err(parse) => parse,
+    }
+}
+
+fn parse_command(reader: &mut KeyReader, visual: bool) -> Parsed<VimCommand> {
+    let mut register = None;
+    if reader.peek() == Some('"') {
+        reader.next()?;
+        let name = reader.next()?;
+        if !is_register_name(name) {
+            return Err(VimParse::Invalid);
+        }
+        register = Some(name);
+    }
+    let count = reader.count();
+    let key = reader.next()?;
+
+    let operate = |operator| VimAction::Operate {
+        operator,
+        target: VimTarget::Selection,
+    };
+    let action = match key {
+        'd' | 'c' | 'y' | '>' | '<' => {
+            let operator = VimOperator::from_key(key).ok_or(VimParse::Invalid)?;
+            if visual {
+                operate(operator)
+            } else {
+                return parse_operator(reader, operator, key, count, register);
+            }
+        }
+        'g' => {
+            let (operator, doubled) = match reader.next()? {
+                'g' => return complete(count, register, VimAction::Move(VimMotion::FileStart)),
+                'u' => (VimOperator::Lowercase, 'u'),
+                'U' => (VimOperator::Uppercase, 'U'),
+                '~' => (VimOperator::ToggleCase, '~'),
+                _ => return Err(VimParse::Invalid),
+            };
+            if visual {
+                operate(operator)
+            } else {
+                return parse_operator(reader, operator, doubled, count, register);
+            }
+        }
+        'x' if visual => operate(VimOperator::Delete),
+        'x' => operate_on(VimOperator::Delete, VimMotion::Right),
+        'X' | 'D' if visual => VimAction::Operate {
+            operator: VimOperator::Delete,
+            target: VimTarget::Line,
+        },
+        'X' => operate_on(VimOperator::Delete, VimMotion::Left),
+        'D' => operate_on(VimOperator::Delete, VimMotion::LineEnd),
+        'C' => operate_on(VimOperator::Change, VimMotion::LineEnd),
+        's' if visual => operate(VimOperator::Change),
+        's' => operate_on(VimOperator::Change, VimMotion::Right),
+        'S' => VimAction::Operate {
+            operator: VimOperator::Change,
+            target: VimTarget::Line,
+        },
+        'Y' => VimAction::Operate {
+            operator: VimOperator::Yank,
+            target: VimTarget::Line,
+        },
+        'u' if visual => operate(VimOperator::Lowercase),
+        'U' if visual => operate(VimOperator::Uppercase),
+        '~' if visual => operate(VimOperator::ToggleCase),
+        'i' | 'a' if visual => {
+            let object = parse_object(reader.next()?)?;
+            VimAction::SelectObject {
+                object,
+                around: key == 'a',
+            }
+        }
+        'o' | 'O' if visual => VimAction::SwapVisualEnds,
+        'i' => VimAction::Insert(InsertPosition::BeforeCursor),
+        'a' => VimAction::Insert(InsertPosition::AfterCursor),
+        'I' => VimAction::Insert(InsertPosition::LineStart),
+        'A' => VimAction::Insert(InsertPosition::LineEnd),
+        'o' => VimAction::Insert(InsertPosition::LineBelow),
+        'O' => VimAction::Insert(InsertPosition::LineAbove),
+        'p' => VimAction::Paste { before: false },
+        'P' => VimAction::Paste { before: true },
+        'r' => match reader.next()? {
+            ESCAPE => return Err(VimParse::Invalid),
+            target => VimAction::ReplaceChar(target),
+        },
+        '~' => VimAction::ToggleCaseChar,
+        'J' => VimAction::JoinLines,
+        'u' => VimAction::Undo,
+        CTRL_R => VimAction::Redo,
+        '.' => VimAction::Repeat,
+        'v' => VimAction::Visual(VisualKind::Char),
+        'V' => VimAction::Visual(VisualKind::Line),
+        CTRL_V => VimAction::Visual(VisualKind::Block),
+        ':' | '/' => VimAction::CommandLine(key),
+        'n' => VimAction::SearchNext { reverse: false },
+        'N' => VimAction::SearchNext { reverse: true },
+        ESCAPE => VimAction::Escape,
+        _ => VimAction::Move(parse_motion(reader, key)?),
+    };
+    complete(count, register, action)
+}
+
+fn complete(count: Option<usize>, register: Option<char>, action: VimAction) -> Parsed<VimCommand> {
+    Ok(VimCommand {
+        count,
+        register,
+        action,
+    })
+}
+
+fn operate_on(operator: VimOperator, motion: VimMotion) -> VimAction {
+    VimAction::Operate {
+        operator,
+        target: VimTarget::Motion(motion),
+    }
+}
+
+fn parse_operator(
+    reader: &mut KeyReader,
+    operator: VimOperator,
+    doubled: char,
+    count: Option<usize>,
+    register: Option<char>,
+) -> Parsed<VimCommand> {
+    let motion_count = reader.count();
+    let key = reader.next()?;
+    let target = match key {
+        _ if key == doubled => VimTarget::Line,
+        'i' | 'a' => VimTarget::Object {
+            object: parse_object(reader.next()?)?,
+            around: key == 'a',
+        },
+        ESCAPE => return Err(VimParse::Invalid),
+        _ => VimTarget::Motion(parse_motion(reader, key)?),
+    };
+    let count = match (count, motion_count) {
+        (Some(a), Some(b)) => Some(a.saturating_mul(b)),
+        (a, b) => a.or(b),
+    };
+    complete(count, register, VimAction::Operate { operator, target })
+}
+
+fn parse_motion(reader: &mut KeyReader, key: char) -> Parsed<VimMotion> {
+    let motion = match key {
+        'h' | BACKSPACE => VimMotion::Left,
+        'l' | ' ' => VimMotion::Right,
+        'j' | ENTER => VimMotion::Down,
+        'k' => VimMotion::Up,
+        'w' | 'W' => VimMotion::WordForward { big: key == 'W' },
+        'b' | 'B' => VimMotion::WordBackward { big: key == 'B' },
+        'e' | 'E' => VimMotion::WordEnd { big: key == 'E' },
+        '0' => VimMotion::LineStart,
+        '^' => VimMotion::FirstNonBlank,
+        '$' => VimMotion::LineEnd,
+        'G' => VimMotion::FileEnd,
+        'g' => match reader.next()? {
+            'g' => VimMotion::FileStart,
+            _ => return Err(VimParse::Invalid),
+        },
+        'f' | 'F' | 't' | 'T' => match reader.next()? {
+            ESCAPE => return Err(VimParse::Invalid),
+            target => VimMotion::FindChar {
+                target,
+                forward: key.is_ascii_lowercase(),
+                till: key.eq_ignore_ascii_case(&'t'),
+            },
+        },
+        ';' => VimMotion::RepeatFind { reverse: false },
+        ',' => VimMotion::RepeatFind { reverse: true },
+        '%' => VimMotion::MatchingBracket,
+        '}' => VimMotion::ParagraphForward,
+        '{' => VimMotion::ParagraphBackward,
+        _ => return Err(VimParse::Invalid),
+    };
+    Ok(motion)
+}
+
+fn parse_object(key: char) -> Parsed<VimTextObject> {
+    let object = match key {
+        'w' | 'W' => VimTextObject::Word { big: key == 'W' },
+        '"' | '\'' | '`' => VimTextObject::Quote(key),
+        '(' | ')' | 'b' => VimTextObject::Bracket {
+            open: '(',
+            close: ')',
+        },
+        '{' | '}' | 'B' => VimTextObject::Bracket {
+            open: '{',
+            close: '}',
+        },
+        '[' | ']' => VimTextObject::Bracket {
+            open: '[',
+            close: ']',
+        },
+        '<' | '>' => VimTextObject::Bracket {
+            open: '<',
+            close: '>',
+        },
+        'p' => VimTextObject::Paragraph,
+        'q' => VimTextObject::AnyQuote,
+        'm' => VimTextObject::AnyBracket,
+        'l' => VimTextObject::Line,
+        'e' => VimTextObject::Entire,
+        _ => return Err(VimParse::Invalid),
+    };
+    Ok(object)
+}
+
+fn is_register_name(name: char) -> bool {
+    name.is_ascii_alphanumeric() || matches!(name, '"' | '-' | '_' | '+' | '*')
+}
+
+pub fn is_clipboard_register(name: char) -> bool {
+    matches!(name, '+' | '*')
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct VimRegister {
+    pub text: String,
+    pub linewise: bool,
+}
+
+impl VimRegister {
+    pub fn new(text: impl Into<String>, linewise: bool) -> Self {
+        Self {
+            text: text.into(),
+            linewise,
+        }
+    }
+}
+
+// Yanks go to `0`, deletes of a line or more shift through `1`-`9` and
+// smaller deletes go to `-`; every write also lands in the unnamed register
+// unless it was aimed at the black hole `_`.
+#[derive(Debug, Clone, Default)]
+pub struct VimRegisters {
+    registers: HashMap<char, VimRegister>,
+}
+
+impl VimRegisters {
+    pub fn get(&self, name: char) -> Option<&VimRegister> {
+        self.registers.get(&name.to_ascii_lowercase())
+    }
+
+    pub fn yank(&mut self, name: Option<char>, register: VimRegister) {
+        match name {
+            None | Some(UNNAMED_REGISTER) => {
+                self.registers.insert(YANK_REGISTER, register.clone());
+                self.registers.insert(UNNAMED_REGISTER, register);
+            }
+            Some(name) => self.store_named(name, register),
+        }
+    }
+
+    pub fn delete(&mut self, name: Option<char>, register: VimRegister) {
+        match name {
+            None | Some(UNNAMED_REGISTER) => {
+                if register.linewise || register.text.contains('\n') {
+                    for index in (1..9).rev() {
+                        let from = char::from_digit(index, 10).unwrap_or('1');
+                        let to = char::from_digit(index + 1, 10).unwrap_or('9');
+                        if let Some(previous) = self.registers.remove(&from) {
+                            self.registers.insert(to, previous);
+                        }
+                    }
+                    self.registers.insert('1', register.clone());
+                } else {
+                    self.registers
+                        .insert(SMALL_DELETE_REGISTER, register.clone());
+                }
+                self.registers.insert(UNNAMED_REGISTER, register);
+            }
+            Some(name) => self.store_named(name, register),
+        }
+    }
+
+    fn store_named(&mut self, name: char, register: VimRegister) {
+        if name == BLACK_HOLE_REGISTER {
+            return;
+        }
+        // Uppercase names append to the lowercase register.
+        let register = match self.registers.get(&name.to_ascii_lowercase()) {
+            Some(existing) if name.is_ascii_uppercase() => {
+                let mut text = existing.text.clone();
+                if existing.linewise && !text.ends_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str(&register.text);
+                VimRegister::new(text, existing.linewise || register.linewise)
+            }
+            _ => register,
+        };
+        self.registers
+            .insert(name.to_ascii_lowercase(), register.clone());
+        self.registers.insert(UNNAMED_REGISTER, register);
+    }
+}
+
+// Text an operator acts on: a half-open span of char indices, whole lines
+// (inclusive) or a visual block of columns across lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum VimRange {
+    Chars(usize, usize),
+    Lines(usize, usize),
+    Block {
+        lines: (usize, usize),
+        columns: (usize, usize),
+    },
+}
+
+// A change `.` can replay: the command itself plus whatever was typed in
+// insert mode when the command entered it.
+#[derive(Debug, Clone)]
+pub(crate) struct VimChange {
+    pub(crate) command: VimCommand,
+    pub(crate) inserted: Vec<Command>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct FindState {
+    pub(crate) target: char,
+    pub(crate) forward: bool,
+    pub(crate) till: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct VimState {
+    pub(crate) pending: Vec<char>,
+    pub(crate) registers: VimRegisters,
+    pub(crate) last_change: Option<VimChange>,
+    pub(crate) recording: Option<VimChange>,
+    pub(crate) insert_count: usize,
+    pub(crate) last_find: Option<FindState>,
+    pub(crate) visual_anchor: CursorPosition,
+    pub(crate) last_visual: Option<(usize, usize)>,
+    pub(crate) command_line: Option<String>,
+    pub(crate) insert_history_start: Option<usize>,
+    pub(crate) replaying: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOptions {
+    Text { reverse: bool, unique: bool },
+    Numeric { reverse: bool, unique: bool },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExCommand {
+    Write {
+        path: Option<PathBuf>,
+        quit: bool,
+    },
+    Quit {
+        force: bool,
+    },
+    Edit(PathBuf),
+    GotoLine(usize),
+    Substitute {
+        lines: (usize, usize),
+        pattern: String,
+        replacement: String,
+        global: bool,
+        ignore_case: bool,
+    },
+    Sort {
+        lines: (usize, usize),
+        options: SortOptions,
+    },
+}
+
+// Where an ex command runs: lines are zero-based and `visual` is the line
+// span of the last visual selection, used by the `'<,'>` range.
+#[derive(Debug, Clone, Copy)]
+pub struct ExContext {
+    pub current_line: usize,
+    pub last_line: usize,
+    pub visual: Option<(usize, usize)>,
+}
+
+pub fn parse_ex_command(text: &str, context: &ExContext) -> Result<ExCommand> {
+    let text = text.trim();
+    let (range, rest) = parse_ex_range(text, context)?;
+    let rest = rest.trim_start();
+
+    if rest.is_empty() {
+        let (_, line) = range.ok_or_else(|| ex_error("Empty command"))?;
+        return Ok(ExCommand::GotoLine(line));
+    }
+
+    let name_len = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    let (name, args) = rest.split_at(name_len);
+    let (force, args) = match args.strip_prefix('!') {
+        Some(args) => (true, args),
+        None => (false, args),
+    };
+    let path = || {
+        let path = args.trim();
+        (!path.is_empty()).then(|| PathBuf::from(path))
+    };
+
+    match name {
+        "w" | "write" => Ok(ExCommand::Write {
+            path: path(),
+            quit: false,
+        }),
+        "wq" | "x" | "xit" => Ok(ExCommand::Write {
+            path: path(),
+            quit: true,
+        }),
+        "q" | "quit" => Ok(ExCommand::Quit { force }),
+        "e" | "edit" => path()
+            .map(ExCommand::Edit)
+            .ok_or_else(|| ex_error("Argument required")),
+        "s" | "substitute" => {
+            let lines = range.unwrap_or((context.current_line, context.current_line));
+            parse_substitute(args, lines)
+        }
+        "sor" | "sort" => {
+            let lines = range.unwrap_or((0, context.last_line));
+            let flags = args.trim();
+            let reverse = force;
+            let unique = flags.contains('u');
+            let options = if flags.contains('n') {
+                SortOptions::Numeric { reverse, unique }
+            } else {
+                SortOptions::Text { reverse, unique }
+            };
+            Ok(ExCommand::Sort { lines, options })
+        }
+        _ => Err(ex_error(&format!("Not an editor command: {}", rest))),
+    }
+}
+
+fn ex_error(message: &str) -> EditorError {
+    EditorError::InvalidOperation(message.to_string())
+}
+
+fn parse_ex_range<'a>(
+    text: &'a str,
+    context: &ExContext,
+) -> Result<(Option<(usize, usize)>, &'a str)> {
+    if let Some(rest) = text.strip_prefix('%') {
+        return Ok((Some((0, context.last_line)), rest));
+    }
+    let Some((start, rest)) = parse_ex_address(text, context)? else {
+        return Ok((None, text));
+    };
+    let Some(rest) = rest.strip_prefix(',') else {
+        return Ok((Some((start, start)), rest));
+    };
+    let (end, rest) = parse_ex_address(rest, context)?.ok_or_else(|| ex_error("Invalid range"))?;
+    if start > end {
+        return Err(ex_error("Backwards range given"));
+    }
+    Ok((Some((start, end)), rest))
+}
+
+fn parse_ex_address<'a>(text: &'a str, context: &ExContext) -> Result<Option<(usize, &'a str)>> {
+    let visual = || context.visual.ok_or_else(|| ex_error("Mark not set"));
+    if let Some(rest) = text.strip_prefix('.') {
+        return Ok(Some((context.current_line, rest)));
+    }
+    if let Some(rest) = text.strip_prefix('$') {
+        return Ok(Some((context.last_line, rest)));
+    }
+    if let Some(rest) = text.strip_prefix("'<") {
+        return Ok(Some((visual()?.0, rest)));
+    }
+    if let Some(rest) = text.strip_prefix("'>") {
+        return Ok(Some((visual()?.1, rest)));
+    }
+    let digits = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    if digits == 0 {
+        return Ok(None);
+    }
+    let line: usize = text[..digits]
+        .parse()
+        .map_err(|_| ex_error("Invalid range"))?;
+    Ok(Some((
+        line.saturating_sub(1).min(context.last_line),
+        &text[digits..],
+    )))
+}
+
+fn parse_substitute(args: &str, lines: (usize, usize)) -> Result<ExCommand> {
+    let mut chars = args.chars();
+    let delimiter = chars
+        .next()
+        .filter(|c| !c.is_alphanumeric() && !c.is_whitespace() && *c != '\\')
+        .ok_or_else(|| ex_error("Usage: s/pattern/replacement/flags"))?;
+
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut escaped = false;
+    for c in chars {
+        if escaped {
+            if c != delimiter {
+                current.push('\\');
+            }
+            current.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == delimiter && parts.len() < 2 {
+            parts.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+    }
+    if escaped {
+        current.push('\\');
+    }
+    parts.push(current);
+
+    let pattern = parts[0].clone();
+    if pattern.is_empty() {
+        return Err(ex_error("Empty search pattern"));
+    }
+    let replacement = parts.get(1).cloned().unwrap_or_default();
+    let flags = parts.get(2).cloned().unwrap_or_default();
+    if let Some(flag) = flags.trim().chars().find(|c| !matches!(c, 'g' | 'i' | 'I')) {
+        return Err(ex_error(&format!("Trailing characters: {}", flag)));
+    }
+    Ok(ExCommand::Substitute {
+        lines,
+        pattern,
+        replacement: vim_replacement(&replacement),
+        global: flags.contains('g'),
+        ignore_case: flags.contains('i'),
+    })
+}
+
+// Turns vim's `&` and `\1` replacement references into the `${0}` and
+// `${1}` form the regex crate expands.
+fn vim_replacement(replacement: &str) -> String {
+    let mut result = String::new();
+    let mut chars = replacement.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '&' => result.push_str("${0}"),
+            '$' => result.push_str("$$"),
+            '\\' => match chars.next() {
+                Some(digit @ '0'..='9') => result.push_str(&format!("${{{}}}", digit)),
+                Some('n') => result.push('\n'),
+                Some('t') => result.push('\t'),
+                Some(other) => result.push(other),
+                None => result.push('\\'),
+            },
+            _ => result.push(c),
+        }
+    }
+    result
+}
@@ -0,0 +1,150 @@
+mod common;
+
+use common::{editor_with, mock_clipboard, type_text};
+use editor_core::{ClipboardManager, Command, CursorPosition, EditorState};
+
+// Turns block selection on, so that dragging selects a block.
+fn block_editor(content: &str) -> EditorState {
+    let mut editor = editor_with(content);
+    editor
+        .execute_command(Command::ToggleBlockSelection)
+        .unwrap();
+    editor
+}
+
+fn select_block(editor: &mut EditorState, anchor: CursorPosition, cursor: CursorPosition) {
+    editor
+        .execute_command(Command::MouseDragStart(anchor))
+        .unwrap();
+    editor.execute_command(Command::MouseDrag(cursor)).unwrap();
+    editor
+        .execute_command(Command::MouseDragEnd(cursor))
+        .unwrap();
+}
+
+#[test]
+fn test_typing_in_a_zero_width_block_inserts_on_every_row() {
+    let mut editor = block_editor("alpha\nbeta\ngamma\n");
+    select_block(
+        &mut editor,
+        CursorPosition::new(0, 2),
+        CursorPosition::new(2, 2),
+    );
+
+    type_text(&mut editor, "--");
+
+    assert_eq!(
+        editor.current_buffer().content(),
+        "al--pha\nbe--ta\nga--mma\n"
+    );
+    assert_eq!(editor.cursor(), &CursorPosition::new(2, 4));
+
+    editor.execute_command(Command::Backspace).unwrap();
+    assert_eq!(editor.current_buffer().content(), "al-pha\nbe-ta\nga-mma\n");
+}
+
+#[test]
+fn test_typing_replaces_the_block_and_pads_short_rows() {
+    let mut editor = block_editor("abcdef\nab\nabcdef\n");
+    select_block(
+        &mut editor,
+        CursorPosition::new(0, 3),
+        CursorPosition::new(2, 5),
+    );
+
+    editor.execute_command(Command::InsertChar('X')).unwrap();
+
+    assert_eq!(editor.current_buffer().content(), "abcXf\nab X\nabcXf\n");
+
+    // The whole column change is one undo step per keystroke.
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), "abcdef\nab\nabcdef\n");
+}
+
+#[test]
+fn test_delete_removes_the_column_range_on_every_row() {
+    let mut editor = block_editor("abcdef\nabc\nabcdef\n");
+    select_block(
+        &mut editor,
+        CursorPosition::new(0, 1),
+        CursorPosition::new(2, 4),
+    );
+
+    editor.execute_command(Command::DeleteChar).unwrap();
+
+    assert_eq!(editor.current_buffer().content(), "aef\na\naef\n");
+    assert_eq!(editor.cursor(), &CursorPosition::new(2, 1));
+
+    // What is left is a column cursor, so Delete carries on down it.
+    editor.execute_command(Command::DeleteChar).unwrap();
+    assert_eq!(editor.current_buffer().content(), "af\na\naf\n");
+}
+
+#[test]
+fn test_copying_a_block_gives_rectangular_text() {
+    let _lock = mock_clipboard();
+
+    let mut editor = block_editor("abcdef\nab\nabcdef\n");
+    select_block(
+        &mut editor,
+        CursorPosition::new(0, 1),
+        CursorPosition::new(2, 4),
+    );
+    editor.execute_command(Command::Copy).unwrap();
+
+    let clipboard = ClipboardManager::new().unwrap();
+    assert_eq!(clipboard.get_text().unwrap(), "bcd\nb  \nbcd");
+}
+
+#[test]
+fn test_pasting_a_copied_block_inserts_a_rectangle() {
+    let _lock = mock_clipboard();
+
+    let mut editor = block_editor("12\n34\n\nabcdef\nab\n");
+    select_block(
+        &mut editor,
+        CursorPosition::new(0, 0),
+        CursorPosition::new(1, 2),
+    );
+    editor.execute_command(Command::Copy).unwrap();
+
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(3, 3)))
+        .unwrap();
+    editor.execute_command(Command::Paste).unwrap();
+
+    assert_eq!(
+        editor.current_buffer().content(),
+        "12\n34\n\nabc12def\nab 34\n"
+    );
+
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), "12\n34\n\nabcdef\nab\n");
+}
+
+#[test]
+fn test_pasting_lines_over_a_block_fills_it_row_by_row() {
+    let _lock = mock_clipboard();
+
+    let clipboard = ClipboardManager::new().unwrap();
+    let mut editor = block_editor("a-b\nc-d\n");
+    select_block(
+        &mut editor,
+        CursorPosition::new(0, 1),
+        CursorPosition::new(1, 2),
+    );
+
+    clipboard.set_text("one\ntwo").unwrap();
+    editor.execute_command(Command::Paste).unwrap();
+    assert_eq!(editor.current_buffer().content(), "aoneb\nctwod\n");
+
+    // A single line goes on every row of the block.
+    select_block(
+        &mut editor,
+        CursorPosition::new(0, 0),
+        CursorPosition::new(1, 0),
+    );
+    clipboard.set_text("> ").unwrap();
+    editor.execute_command(Command::Paste).unwrap();
+    assert_eq!(editor.current_buffer().content(), "> aoneb\n> ctwod\n");
+}
@@ -0,0 +1,80 @@
+use editor_core::{
+    ClipboardBackend, ClipboardEnvironment, ClipboardManager, EditorConfig, EditorState,
+};
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn test_internal_backend_keeps_text_inside_the_editor() {
+    let clipboard = ClipboardManager::with_backend(ClipboardBackend::Internal);
+    clipboard.set_text("kept here").unwrap();
+
+    assert_eq!(clipboard.active_backend(), ClipboardBackend::Internal);
+    assert_eq!(clipboard.get_text().unwrap(), "kept here");
+}
+
+#[test]
+fn test_osc52_backend_sends_text_to_the_terminal() {
+    let sent = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&sent);
+    ClipboardManager::set_terminal_writer(move |text| {
+        sink.lock().unwrap().push(text.to_string());
+        Ok(())
+    });
+
+    let clipboard = ClipboardManager::with_backend(ClipboardBackend::Osc52);
+    clipboard.set_text("over ssh").unwrap();
+
+    assert_eq!(*sent.lock().unwrap(), vec!["over ssh".to_string()]);
+    // The terminal can't be read back, so pasting uses what was copied here.
+    assert_eq!(clipboard.get_text().unwrap(), "over ssh");
+}
+
+#[test]
+fn test_backend_is_chosen_in_the_config() {
+    let config = EditorConfig::from_toml_str("[editor]\nclipboard = \"tmux\"\n").unwrap();
+    assert_eq!(config.editor.clipboard, ClipboardBackend::Tmux);
+    assert!(EditorConfig::from_toml_str("[editor]\nclipboard = \"xclip\"\n").is_err());
+    assert_eq!(
+        ClipboardBackend::from_name("osc52"),
+        Some(ClipboardBackend::Osc52)
+    );
+
+    let mut editor = EditorState::new();
+    let config = EditorConfig::from_toml_str("[editor]\nclipboard = \"internal\"\n").unwrap();
+    editor.apply_config(&config).unwrap();
+    assert_eq!(editor.clipboard_backend(), ClipboardBackend::Internal);
+}
+
+#[test]
+fn test_auto_prefers_tmux_then_osc52_over_ssh() {
+    let detect = |tmux, ssh, terminal_writer, system_clipboard| {
+        ClipboardBackend::detect_in(&ClipboardEnvironment {
+            tmux,
+            ssh,
+            terminal_writer,
+            system_clipboard,
+        })
+    };
+
+    assert_eq!(detect(true, true, true, true), ClipboardBackend::Tmux);
+    assert_eq!(detect(false, true, true, true), ClipboardBackend::Osc52);
+    // Over SSH without a terminal to talk to, a forwarded display still helps.
+    assert_eq!(detect(false, true, false, true), ClipboardBackend::Arboard);
+    assert_eq!(detect(false, false, true, true), ClipboardBackend::Arboard);
+    assert_eq!(detect(false, false, true, false), ClipboardBackend::Osc52);
+    assert_eq!(
+        detect(false, false, false, false),
+        ClipboardBackend::Internal
+    );
+}
+
+#[test]
+fn test_auto_is_resolved_once() {
+    let clipboard = ClipboardManager::with_backend(ClipboardBackend::Auto);
+    let active = clipboard.active_backend();
+    assert_ne!(active, ClipboardBackend::Auto);
+
+    ClipboardManager::set_terminal_writer(|_| Ok(()));
+    assert_eq!(clipboard.active_backend(), active);
+    assert_eq!(clipboard.backend(), ClipboardBackend::Auto);
+}
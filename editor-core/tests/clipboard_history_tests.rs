@@ -0,0 +1,164 @@
+mod common;
+
+use common::{editor_with, mock_clipboard, select};
+use editor_core::{
+    ClipboardHistory, ClipboardManager, ClipboardPicker, Command, CursorPosition, EditorConfig,
+    EditorState, Session,
+};
+
+fn history_texts(history: &ClipboardHistory) -> Vec<&str> {
+    history.entries().map(|entry| entry.text.as_str()).collect()
+}
+
+#[test]
+fn test_history_is_bounded_but_keeps_pinned_entries() {
+    let mut history = ClipboardHistory::with_capacity(2);
+    history.push("one");
+    history.toggle_pin(0);
+    history.push("two");
+    history.push("three");
+    history.push("four");
+
+    assert_eq!(history_texts(&history), vec!["four", "three", "one"]);
+
+    // Copying something again moves it to the front, pin and all.
+    history.push("one");
+    assert_eq!(history_texts(&history), vec!["one", "four", "three"]);
+    assert!(history.get(0).unwrap().pinned);
+
+    history.clear();
+    assert_eq!(history_texts(&history), vec!["one"]);
+}
+
+#[test]
+fn test_copy_cut_and_external_changes_are_recorded() {
+    let _lock = mock_clipboard();
+    let mut editor = editor_with("alpha beta gamma\n");
+
+    select(
+        &mut editor,
+        CursorPosition::new(0, 0),
+        CursorPosition::new(0, 5),
+    );
+    editor.execute_command(Command::Copy).unwrap();
+    select(
+        &mut editor,
+        CursorPosition::new(0, 6),
+        CursorPosition::new(0, 10),
+    );
+    editor.execute_command(Command::Cut).unwrap();
+
+    // Text copied in another program shows up once it is pasted.
+    ClipboardManager::new()
+        .unwrap()
+        .set_text("outside")
+        .unwrap();
+    editor.execute_command(Command::Paste).unwrap();
+
+    assert_eq!(
+        history_texts(editor.clipboard_history()),
+        vec!["outside", "beta", "alpha"]
+    );
+    assert_eq!(editor.current_buffer().content(), "alpha outside gamma\n");
+}
+
+#[test]
+fn test_pasting_an_earlier_entry_makes_it_current() {
+    let _lock = mock_clipboard();
+    let mut editor = editor_with("one two\n");
+
+    select(
+        &mut editor,
+        CursorPosition::new(0, 0),
+        CursorPosition::new(0, 3),
+    );
+    editor.execute_command(Command::Copy).unwrap();
+    select(
+        &mut editor,
+        CursorPosition::new(0, 4),
+        CursorPosition::new(0, 7),
+    );
+    editor.execute_command(Command::Copy).unwrap();
+
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(0, 7)))
+        .unwrap();
+    editor
+        .execute_command(Command::PasteFromHistory(1))
+        .unwrap();
+
+    assert_eq!(editor.current_buffer().content(), "one twoone\n");
+    assert_eq!(
+        history_texts(editor.clipboard_history()),
+        vec!["one", "two"]
+    );
+    assert_eq!(ClipboardManager::new().unwrap().get_text().unwrap(), "one");
+    assert!(editor
+        .execute_command(Command::PasteFromHistory(5))
+        .is_err());
+}
+
+#[test]
+fn test_a_run_of_kills_is_one_entry() {
+    let _lock = mock_clipboard();
+    let mut editor = editor_with("first\nsecond\n");
+
+    editor.execute_command(Command::KillLine).unwrap();
+    editor.execute_command(Command::KillLine).unwrap();
+
+    assert_eq!(history_texts(editor.clipboard_history()), vec!["first\n"]);
+}
+
+#[test]
+fn test_picker_filters_entries_and_pastes_the_chosen_one() {
+    let mut history = ClipboardHistory::new();
+    history.push("fn main() {\n}");
+    history.push("let value = 1;");
+    history.push("println!");
+
+    let mut picker = ClipboardPicker::new(&history);
+    assert_eq!(picker.items().len(), 3);
+    assert_eq!(picker.items()[0].preview, "println!");
+    assert_eq!(picker.items()[2].preview, "fn main() {↵}");
+
+    picker.set_query("main");
+    assert_eq!(picker.items().len(), 1);
+    assert_eq!(picker.confirm(), Some(Command::PasteFromHistory(2)));
+    assert_eq!(picker.toggle_pin(), Some(Command::ToggleClipboardPin(2)));
+
+    history.toggle_pin(2);
+    picker.refresh(&history);
+    assert_eq!(picker.query(), "main");
+    assert!(picker.selected_item().unwrap().pinned);
+}
+
+#[test]
+fn test_history_persists_in_the_session_when_enabled() {
+    let _lock = mock_clipboard();
+    let mut editor = editor_with("keep this\n");
+    select(
+        &mut editor,
+        CursorPosition::new(0, 0),
+        CursorPosition::new(0, 4),
+    );
+    editor.execute_command(Command::Copy).unwrap();
+    editor
+        .execute_command(Command::ToggleClipboardPin(0))
+        .unwrap();
+
+    let mut session = Session::new();
+    editor.save_session_state(&mut session);
+    assert!(session.clipboard_history.is_empty());
+
+    let mut config = EditorConfig::default();
+    config.editor.persist_clipboard_history = true;
+    editor.apply_config(&config).unwrap();
+    editor.save_session_state(&mut session);
+
+    let mut restored = EditorState::new();
+    restored.apply_config(&config).unwrap();
+    restored.restore_clipboard_history_from_session(&session);
+    let entry = restored.clipboard_history().get(0).unwrap();
+    assert_eq!(entry.text, "keep");
+    assert!(entry.pinned);
+}
@@ -293,3 +293,53 @@ fn test_selection_with_mouse() {
 
     assert_eq!(editor.current_buffer().content(), "lo");
 }
+
+#[test]
+fn test_insert_text_is_verbatim_and_one_undo_step() {
+    let mut editor = EditorState::new();
+    editor
+        .current_buffer_mut()
+        .set_content("fn main() {\n    \n}\n".to_string())
+        .unwrap();
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(1, 4)))
+        .unwrap();
+
+    editor
+        .execute_command(Command::InsertText(
+            "if ready {\r\n\tgo(\"(\");\r\n}".to_string(),
+        ))
+        .unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "fn main() {\n    if ready {\n\tgo(\"(\");\n}\n}\n"
+    );
+    assert_eq!(editor.cursor(), &CursorPosition::new(3, 1));
+
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), "fn main() {\n    \n}\n");
+    assert_eq!(editor.cursor(), &CursorPosition::new(1, 4));
+}
+
+#[test]
+fn test_insert_text_goes_in_at_every_cursor() {
+    let mut editor = EditorState::new();
+    editor
+        .current_buffer_mut()
+        .set_content("a\nb\n".to_string())
+        .unwrap();
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(0, 1)))
+        .unwrap();
+    editor
+        .execute_command(Command::AddCursor(CursorPosition::new(1, 1)))
+        .unwrap();
+
+    editor
+        .execute_command(Command::InsertText("[x]\n".to_string()))
+        .unwrap();
+    assert_eq!(editor.current_buffer().content(), "a[x]\n\nb[x]\n\n");
+
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), "a\nb\n");
+}
@@ -0,0 +1,155 @@
+use editor_core::{
+    ArgumentKind, Command, CommandAction, CommandPalette, CommandRegistry, EditorState,
+    PaletteOutcome,
+};
+
+fn palette() -> (CommandRegistry, CommandPalette) {
+    let registry = CommandRegistry::new();
+    let editor = EditorState::new();
+    let palette = CommandPalette::new(&registry, &editor);
+    (registry, palette)
+}
+
+#[test]
+fn test_palette_lists_available_commands() {
+    let (registry, palette) = palette();
+    let editor = EditorState::new();
+
+    let visible = registry
+        .available(&editor)
+        .into_iter()
+        .filter(|spec| !spec.hidden)
+        .count();
+    assert_eq!(palette.items().len(), visible);
+    assert!(palette
+        .items()
+        .iter()
+        .all(|item| !item.id.starts_with("editor.")));
+    assert_eq!(palette.selected_index(), 0);
+    assert!(!palette.is_prompting());
+}
+
+#[test]
+fn test_palette_filters_by_fuzzy_query() {
+    let (_, mut palette) = palette();
+
+    palette.set_query("dupl");
+
+    assert_eq!(palette.selected_item().unwrap().id, "tools.duplicate_line");
+    assert!(!palette.selected_item().unwrap().indices.is_empty());
+}
+
+#[test]
+fn test_palette_matches_command_ids() {
+    let (_, mut palette) = palette();
+
+    palette.set_query("history.cleanup");
+
+    assert_eq!(palette.selected_item().unwrap().id, "history.cleanup");
+}
+
+#[test]
+fn test_palette_selection_bounds() {
+    let (_, mut palette) = palette();
+    palette.set_query("toggle");
+    let count = palette.items().len();
+    assert!(count > 1);
+
+    palette.select_previous();
+    assert_eq!(palette.selected_index(), 0);
+
+    for _ in 0..count + 5 {
+        palette.select_next();
+    }
+    assert_eq!(palette.selected_index(), count - 1);
+
+    palette.insert_char('x');
+    assert_eq!(palette.selected_index(), 0);
+}
+
+#[test]
+fn test_palette_runs_command_without_arguments() {
+    let (registry, mut palette) = palette();
+    palette.set_query("Join Lines");
+
+    match palette.confirm(&registry).unwrap() {
+        PaletteOutcome::Run(CommandAction::Execute(Command::JoinLines)) => {}
+        other => panic!("unexpected outcome {:?}", other),
+    }
+}
+
+#[test]
+fn test_palette_prompts_for_arguments() {
+    let (registry, mut palette) = palette();
+    palette.set_query("Replace All");
+
+    assert!(matches!(
+        palette.confirm(&registry).unwrap(),
+        PaletteOutcome::Continue
+    ));
+    assert!(palette.is_prompting());
+    assert_eq!(palette.pending_title(), Some("Replace All"));
+    assert_eq!(palette.prompt().unwrap().name, "find");
+    assert_eq!(palette.query(), "");
+
+    palette.set_query("old");
+    assert!(matches!(
+        palette.confirm(&registry).unwrap(),
+        PaletteOutcome::Continue
+    ));
+    assert_eq!(palette.prompt().unwrap().name, "replace");
+
+    palette.set_query("new");
+    match palette.confirm(&registry).unwrap() {
+        PaletteOutcome::Run(CommandAction::Execute(Command::ReplaceAll { find, replace })) => {
+            assert_eq!(find, "old");
+            assert_eq!(replace, "new");
+        }
+        other => panic!("unexpected outcome {:?}", other),
+    }
+}
+
+#[test]
+fn test_palette_for_command_starts_in_prompt_mode() {
+    let registry = CommandRegistry::new();
+    let mut palette = CommandPalette::for_command(&registry, "search.goto_line").unwrap();
+
+    assert!(palette.is_prompting());
+    assert_eq!(palette.prompt().unwrap().kind, ArgumentKind::LineNumber);
+
+    palette.set_query("12");
+    assert!(matches!(
+        palette.confirm(&registry).unwrap(),
+        PaletteOutcome::Run(CommandAction::Execute(Command::GotoLine(12)))
+    ));
+}
+
+#[test]
+fn test_palette_for_command_without_arguments() {
+    let registry = CommandRegistry::new();
+    let mut palette = CommandPalette::for_command(&registry, "file.save").unwrap();
+
+    assert!(matches!(
+        palette.confirm(&registry).unwrap(),
+        PaletteOutcome::Run(CommandAction::Execute(Command::Save))
+    ));
+    assert!(CommandPalette::for_command(&registry, "does.not_exist").is_err());
+}
+
+#[test]
+fn test_palette_reports_invalid_argument() {
+    let registry = CommandRegistry::new();
+    let mut palette = CommandPalette::for_command(&registry, "search.goto_line").unwrap();
+
+    palette.set_query("abc");
+    assert!(palette.confirm(&registry).is_err());
+}
+
+#[test]
+fn test_palette_confirm_without_matches() {
+    let (registry, mut palette) = palette();
+    palette.set_query("zzzzzz");
+
+    assert!(palette.items().is_empty());
+    assert!(palette.confirm(&registry).is_err());
+}
@@ -0,0 +1,182 @@
+use editor_core::{
+    ArgumentKind, ArgumentSpec, Command, CommandAction, CommandCategory, CommandRegistry,
+    CommandSpec, EditorError, EditorState,
+};
+use std::collections::HashSet;
+use tempfile::TempDir;
+
+#[test]
+fn test_builtin_registry_ids_are_unique() {
+    let registry = CommandRegistry::new();
+    let ids: HashSet<&str> = registry.all().iter().map(|spec| spec.id).collect();
+
+    assert_eq!(ids.len(), registry.len());
+    assert!(!registry.is_empty());
+}
+
+#[test]
+fn test_builtin_keybindings_are_unique() {
+    let registry = CommandRegistry::new();
+    let mut seen = HashSet::new();
+
+    for spec in registry.all() {
+        if let Some(keybinding) = spec.keybinding {
+            assert!(
+                seen.insert(keybinding),
+                "duplicate keybinding {}",
+                keybinding
+            );
+        }
+    }
+}
+
+#[test]
+fn test_registry_get_and_find_by_keybinding() {
+    let registry = CommandRegistry::builtin();
+
+    let save = registry.get("file.save").unwrap();
+    assert_eq!(save.title, "Save");
+    assert_eq!(save.category, CommandCategory::File);
+    assert_eq!(save.keybinding, Some("Ctrl+S"));
+
+    assert_eq!(
+        registry.find_by_keybinding("Ctrl+G").unwrap().id,
+        "search.goto_line"
+    );
+    assert!(registry.get("does.not_exist").is_none());
+}
+
+#[test]
+fn test_resolve_command_without_arguments() {
+    let registry = CommandRegistry::new();
+
+    match registry.resolve("tools.duplicate_line", &[]).unwrap() {
+        CommandAction::Execute(Command::DuplicateLine) => {}
+        other => panic!("unexpected action {:?}", other),
+    }
+}
+
+#[test]
+fn test_resolve_command_with_arguments() {
+    let registry = CommandRegistry::new();
+
+    match registry
+        .resolve("search.goto_line", &["42".to_string()])
+        .unwrap()
+    {
+        CommandAction::Execute(Command::GotoLine(42)) => {}
+        other => panic!("unexpected action {:?}", other),
+    }
+
+    match registry
+        .resolve("search.replace", &["foo".to_string(), "bar".to_string()])
+        .unwrap()
+    {
+        CommandAction::Execute(Command::ReplaceAll { find, replace }) => {
+            assert_eq!(find, "foo");
+            assert_eq!(replace, "bar");
+        }
+        other => panic!("unexpected action {:?}", other),
+    }
+}
+
+#[test]
+fn test_resolve_rejects_invalid_arguments() {
+    let registry = CommandRegistry::new();
+
+    assert!(matches!(
+        registry.resolve("search.goto_line", &["abc".to_string()]),
+        Err(EditorError::Parse(_))
+    ));
+    assert!(matches!(
+        registry.resolve("search.goto_line", &["0".to_string()]),
+        Err(EditorError::Parse(_))
+    ));
+    assert!(registry.resolve("search.goto_line", &[]).is_err());
+    assert!(registry.resolve("search.find", &[String::new()]).is_err());
+    assert!(registry.resolve("does.not_exist", &[]).is_err());
+}
+
+#[test]
+fn test_resolve_frontend_command() {
+    let registry = CommandRegistry::new();
+    let spec = registry.get("view.command_palette").unwrap();
+
+    assert!(spec.is_frontend());
+    match registry.resolve("view.command_palette", &[]).unwrap() {
+        CommandAction::Frontend(id) => assert_eq!(id, "view.command_palette"),
+        other => panic!("unexpected action {:?}", other),
+    }
+}
+
+#[test]
+fn test_register_custom_command() {
+    const ARGS: &[ArgumentSpec] = &[ArgumentSpec::new("width", "Width:", ArgumentKind::Number)];
+    let mut registry = CommandRegistry::empty();
+
+    registry
+        .register(
+            CommandSpec::new("tools.wrap_80", "Wrap", CommandCategory::Tools, |args| {
+                Ok(Command::HardWrap(args[0].parse().unwrap_or(80)))
+            })
+            .with_arguments(ARGS),
+        )
+        .unwrap();
+
+    assert_eq!(registry.len(), 1);
+    assert!(registry
+        .register(CommandSpec::frontend(
+            "tools.wrap_80",
+            "Wrap",
+            CommandCategory::Tools
+        ))
+        .is_err());
+    assert!(matches!(
+        registry
+            .resolve("tools.wrap_80", &["72".to_string()])
+            .unwrap(),
+        CommandAction::Execute(Command::HardWrap(72))
+    ));
+}
+
+#[test]
+fn test_menu_items_follow_menu_groups() {
+    let registry = CommandRegistry::new();
+    let items = registry.menu_items(CommandCategory::File);
+    let ids: Vec<&str> = items.iter().map(|spec| spec.id).collect();
+
+    assert_eq!(
+        ids,
+        vec![
+            "file.new",
+            "file.open",
+            "file.save",
+            "file.save_as",
+            "file.close",
+            "app.quit"
+        ]
+    );
+    assert!(registry.menu_items(CommandCategory::Bookmarks).is_empty());
+    assert!(!registry.by_category(CommandCategory::Bookmarks).is_empty());
+}
+
+#[test]
+fn test_availability_depends_on_editor_state() {
+    let project = TempDir::new().unwrap();
+    let registry = CommandRegistry::new();
+    let mut editor = EditorState::new();
+
+    let available = |editor: &EditorState| -> Vec<&str> {
+        registry
+            .available(editor)
+            .iter()
+            .map(|spec| spec.id)
+            .collect()
+    };
+
+    assert!(!available(&editor).contains(&"file_tree.refresh"));
+    assert!(available(&editor).contains(&"view.toggle_file_tree"));
+
+    editor.open_file_tree(project.path().to_path_buf()).unwrap();
+    assert!(available(&editor).contains(&"file_tree.refresh"));
+}
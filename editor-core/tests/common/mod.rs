@@ -0,0 +1,71 @@
+// Fixtures shared by the integration tests. Each test file uses only some
+// of them.
+#![allow(dead_code)]
+
+use editor_core::{ClipboardManager, Command, CursorPosition, EditorState};
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+static CLIPBOARD_LOCK: Mutex<()> = Mutex::new(());
+
+// Tests that copy or paste hold this guard, so they don't share the mock
+// clipboard with each other. The clipboard starts out empty.
+pub fn mock_clipboard() -> MutexGuard<'static, ()> {
+    let lock = CLIPBOARD_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    ClipboardManager::enable_mock_clipboard();
+    ClipboardManager::clear_test_clipboard();
+    lock
+}
+
+// An editor with an unsaved buffer holding `content`.
+pub fn editor_with(content: &str) -> EditorState {
+    let mut editor = EditorState::new();
+    editor
+        .current_buffer_mut()
+        .set_content(content.to_string())
+        .unwrap();
+    editor
+}
+
+// Writes `content` to `name` in `dir` and opens it, so the language and
+// settings are picked from the file name.
+pub fn open(dir: &Path, name: &str, content: &str) -> EditorState {
+    let path = dir.join(name);
+    fs::write(&path, content).unwrap();
+    let mut editor = EditorState::new();
+    editor.execute_command(Command::Open(path)).unwrap();
+    editor
+}
+
+// Types `text` as keystrokes; a line break presses Enter.
+pub fn type_text(editor: &mut EditorState, text: &str) {
+    for c in text.chars() {
+        let command = if c == '\n' {
+            Command::NewLine
+        } else {
+            Command::InsertChar(c)
+        };
+        editor.execute_command(command).unwrap();
+    }
+}
+
+pub fn run(editor: &mut EditorState, commands: &[Command]) {
+    for command in commands {
+        editor.execute_command(command.clone()).unwrap();
+    }
+}
+
+pub fn content(editor: &EditorState) -> String {
+    editor.current_buffer().content()
+}
+
+// Selects from `start` to `end` by dragging the mouse.
+pub fn select(editor: &mut EditorState, start: CursorPosition, end: CursorPosition) {
+    editor.execute_command(Command::MouseClick(start)).unwrap();
+    editor
+        .execute_command(Command::MouseDragStart(start))
+        .unwrap();
+    editor.execute_command(Command::MouseDrag(end)).unwrap();
+    editor.execute_command(Command::MouseDragEnd(end)).unwrap();
+}
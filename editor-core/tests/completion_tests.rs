@@ -0,0 +1,330 @@
+mod common;
+
+use common::{open, type_text};
+use editor_core::{
+    BufferWordSource, Command, CompletionContext, CompletionItem, CompletionMenu, CompletionSource,
+    CursorPosition, PathSource, SnippetRegistry, SnippetSource,
+};
+use ropey::Rope;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn context<'a>(
+    prefix: &'a str,
+    line_before_cursor: &'a str,
+    cursor: CursorPosition,
+    directory: Option<&'a Path>,
+    buffers: &'a [&'a Rope],
+) -> CompletionContext<'a> {
+    CompletionContext {
+        language: "rust",
+        prefix,
+        line_before_cursor,
+        cursor,
+        directory,
+        buffers,
+        snippets: SnippetRegistry::builtin(),
+    }
+}
+
+fn labels(menu: &CompletionMenu) -> Vec<&str> {
+    menu.items()
+        .iter()
+        .map(|entry| entry.item.label.as_str())
+        .collect()
+}
+
+#[test]
+fn test_menu_ranks_by_fuzzy_score() {
+    let items = ["format_document", "for_each", "from", "after"]
+        .into_iter()
+        .map(CompletionItem::new)
+        .collect();
+    let mut menu = CompletionMenu::new(CursorPosition::zero(), "fo", items);
+    assert_eq!(labels(&menu), vec!["for_each", "format_document", "from"]);
+    assert_eq!(menu.items()[0].indices, vec![0, 1]);
+
+    menu.select_previous();
+    assert_eq!(menu.selected_item().unwrap().label, "from");
+    menu.set_query("fmd");
+    assert_eq!(labels(&menu), vec!["format_document"]);
+    assert_eq!(menu.selected_index(), 0);
+    menu.set_query("xyz");
+    assert!(menu.is_empty());
+}
+
+#[test]
+fn test_buffer_words_rank_by_distance_from_cursor() {
+    let current = Rope::from_str("alpha_far\n\n\nalpha_near al\n");
+    let other = Rope::from_str("alpha_elsewhere alpha_near 42abc x1\n");
+    let buffers = [&current, &other];
+    let items = BufferWordSource.complete(&context(
+        "al",
+        "alpha_near al",
+        CursorPosition::new(3, 13),
+        None,
+        &buffers,
+    ));
+
+    let menu = CompletionMenu::new(CursorPosition::new(3, 11), "al", items);
+    assert_eq!(
+        labels(&menu),
+        vec!["alpha_near", "alpha_far", "alpha_elsewhere"]
+    );
+    assert!(menu
+        .items()
+        .iter()
+        .all(|entry| entry.item.kind.as_deref() == Some("word")));
+}
+
+#[test]
+fn test_path_source_lists_directory_being_typed() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join("src")).unwrap();
+    fs::write(temp_dir.path().join("src").join("main.rs"), "").unwrap();
+    fs::create_dir(temp_dir.path().join("src").join("editor")).unwrap();
+    fs::write(temp_dir.path().join("src").join(".hidden"), "").unwrap();
+    let buffers: [&Rope; 0] = [];
+
+    let items = PathSource.complete(&context(
+        "ma",
+        "include_str!(\"./src/ma",
+        CursorPosition::new(0, 22),
+        Some(temp_dir.path()),
+        &buffers,
+    ));
+    let menu = CompletionMenu::new(CursorPosition::new(0, 20), "", items);
+    assert_eq!(labels(&menu), vec!["editor/", "main.rs"]);
+    assert_eq!(menu.items()[0].item.kind.as_deref(), Some("folder"));
+    assert_eq!(menu.items()[1].item.kind.as_deref(), Some("file"));
+
+    let absolute = format!("cat {}/", temp_dir.path().display());
+    let items = PathSource.complete(&context(
+        "",
+        &absolute,
+        CursorPosition::new(0, absolute.chars().count()),
+        None,
+        &buffers,
+    ));
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].label, "src/");
+
+    let items = PathSource.complete(&context(
+        "main",
+        "fn main",
+        CursorPosition::new(0, 7),
+        Some(temp_dir.path()),
+        &buffers,
+    ));
+    assert!(items.is_empty());
+}
+
+#[test]
+fn test_snippet_registry_reads_vscode_json() {
+    let mut registry = SnippetRegistry::empty();
+    registry
+        .merge_json_str(
+            r#"{
+                "Print": {
+                    "prefix": ["pr", "print"],
+                    "body": ["println!(\"$1\");", "$0"],
+                    "description": "Print a line"
+                },
+                "Anywhere": { "prefix": "todo", "body": "TODO: $0" }
+            }"#,
+            Some("rust"),
+        )
+        .unwrap();
+    registry
+        .merge_json_str(
+            r#"{ "Shared": { "scope": "python, shell", "prefix": "sh", "body": "echo $1" } }"#,
+            None,
+        )
+        .unwrap();
+
+    let print = &registry.all()[1];
+    assert_eq!(print.name, "Print");
+    assert_eq!(print.prefixes, vec!["pr", "print"]);
+    assert_eq!(print.body, "println!(\"$1\");\n$0");
+    assert_eq!(registry.for_language("rust").count(), 2);
+    assert_eq!(registry.for_language("shell").count(), 1);
+    assert!(registry.merge_json_str("[1, 2]", None).is_err());
+
+    assert!(SnippetRegistry::builtin()
+        .for_language("rust")
+        .any(|snippet| snippet.prefixes.contains(&"test".to_string())));
+}
+
+#[test]
+fn test_snippet_source_offers_language_snippets() {
+    let buffers: [&Rope; 0] = [];
+    let items = SnippetSource.complete(&context(
+        "te",
+        "te",
+        CursorPosition::new(0, 2),
+        None,
+        &buffers,
+    ));
+    let test = items.iter().find(|item| item.label == "test").unwrap();
+    assert_eq!(test.kind.as_deref(), Some("snippet"));
+    assert_eq!(test.detail.as_deref(), Some("Unit test function"));
+    assert!(test.is_snippet);
+    assert_eq!(test.insert_text, "#[test]\nfn ${1:name}() {\n\t$0\n}");
+    assert!(items.iter().all(|item| item.label != "def"));
+}
+
+#[test]
+fn test_trigger_completion_without_language_server() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(temp_dir.path().join("other.txt"), "remote_value\n").unwrap();
+    let mut editor = open(temp_dir.path(), "other.txt", "remote_value\n");
+    let path = temp_dir.path().join("main.rs");
+    fs::write(&path, "let counter = 1;\nlet count_total = 2;\n").unwrap();
+    editor.execute_command(Command::Open(path)).unwrap();
+    editor.execute_command(Command::MoveToEndOfFile).unwrap();
+
+    type_text(&mut editor, "co");
+    editor.execute_command(Command::TriggerCompletion).unwrap();
+    let menu = editor.completion_menu().unwrap();
+    assert_eq!(menu.start(), CursorPosition::new(2, 0));
+    assert_eq!(labels(menu)[..2], ["counter", "count_total"]);
+
+    type_text(&mut editor, "un");
+    assert_eq!(editor.completion_menu().unwrap().query(), "coun");
+    editor.execute_command(Command::CompletionNext).unwrap();
+    assert_eq!(
+        editor
+            .completion_menu()
+            .unwrap()
+            .selected_item()
+            .unwrap()
+            .label,
+        "count_total"
+    );
+    editor.execute_command(Command::CompletionPrevious).unwrap();
+    editor.execute_command(Command::CompletionAccept).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "let counter = 1;\nlet count_total = 2;\ncounter"
+    );
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 7));
+
+    type_text(&mut editor, " rem");
+    editor.execute_command(Command::TriggerCompletion).unwrap();
+    assert_eq!(
+        labels(editor.completion_menu().unwrap()),
+        vec!["remote_value"]
+    );
+    editor.execute_command(Command::MoveCursorUp).unwrap();
+    assert!(!editor.is_completion_open());
+
+    editor.execute_command(Command::MoveToEndOfFile).unwrap();
+    type_text(&mut editor, " zz");
+    editor.execute_command(Command::TriggerCompletion).unwrap();
+    assert!(!editor.is_completion_open());
+    assert_eq!(editor.status_message(), "No completions");
+}
+
+#[test]
+fn test_accept_completes_at_every_cursor() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(
+        temp_dir.path(),
+        "main.rs",
+        "let value_one = 1;\nva\nx + va\n",
+    );
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(1, 2)))
+        .unwrap();
+    editor
+        .execute_command(Command::AddCursor(CursorPosition::new(2, 6)))
+        .unwrap();
+
+    editor.execute_command(Command::TriggerCompletion).unwrap();
+    assert_eq!(
+        editor
+            .completion_menu()
+            .unwrap()
+            .selected_item()
+            .unwrap()
+            .label,
+        "value_one"
+    );
+    editor.execute_command(Command::CompletionAccept).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "let value_one = 1;\nvalue_one\nx + value_one\n"
+    );
+    assert_eq!(
+        editor.cursors(),
+        &[CursorPosition::new(1, 9), CursorPosition::new(2, 13)]
+    );
+
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "let value_one = 1;\nva\nx + va\n"
+    );
+}
+
+#[test]
+fn test_accept_on_one_line_with_several_cursors() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "main.rs", "widget\nwi + wi\n");
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(1, 2)))
+        .unwrap();
+    editor
+        .execute_command(Command::AddCursor(CursorPosition::new(1, 7)))
+        .unwrap();
+
+    editor.execute_command(Command::TriggerCompletion).unwrap();
+    editor.execute_command(Command::CompletionAccept).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "widget\nwidget + widget\n"
+    );
+    assert_eq!(
+        editor.cursors(),
+        &[CursorPosition::new(1, 6), CursorPosition::new(1, 15)]
+    );
+}
+
+struct KeywordSource;
+
+impl CompletionSource for KeywordSource {
+    fn name(&self) -> &str {
+        "keywords"
+    }
+
+    fn complete(&self, context: &CompletionContext) -> Vec<CompletionItem> {
+        if context.language != "rust" {
+            return Vec::new();
+        }
+        vec![CompletionItem::new("unsafe")
+            .with_kind("keyword")
+            .with_detail("rust keyword")]
+    }
+}
+
+#[test]
+fn test_custom_completion_source() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "main.rs", "");
+    assert_eq!(
+        editor.completion_source_names(),
+        vec!["snippets", "paths", "buffer words"]
+    );
+    editor.add_completion_source(Box::new(KeywordSource));
+
+    type_text(&mut editor, "uns");
+    editor.execute_command(Command::TriggerCompletion).unwrap();
+    let menu = editor.completion_menu().unwrap();
+    assert_eq!(labels(menu), vec!["unsafe"]);
+    assert_eq!(menu.items()[0].item.detail.as_deref(), Some("rust keyword"));
+
+    editor.execute_command(Command::CompletionCancel).unwrap();
+    assert!(!editor.is_completion_open());
+    assert_eq!(editor.current_buffer().content(), "uns");
+}
@@ -0,0 +1,258 @@
+use editor_core::{
+    Command, ConfigLoader, ConfigWatcher, EditorConfig, EditorError, EditorState,
+    LargeFileStrategy, RetentionPolicy,
+};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+#[test]
+fn test_defaults_match_builtin_settings() {
+    let config = EditorConfig::from_toml_str("").unwrap();
+    assert_eq!(config, EditorConfig::default());
+    assert_eq!(config.editor.tab_size, 4);
+    assert_eq!(config.editor.scroll_offset, 5);
+    assert!(config.view.line_numbers);
+    assert_eq!(config.soft_wrap_width(), None);
+    assert_eq!(config.history.retention, RetentionPolicy::Forever);
+    assert_eq!(config.history.large_files.threshold_mb, 50);
+}
+
+#[test]
+fn test_parse_history_settings() {
+    let config = EditorConfig::from_toml_str(
+        r#"
+[history]
+storage_root = "/tmp/editor-history"
+retention = { days = 30 }
+
+[history.large_files]
+strategy = "skip"
+
+[history.gc]
+commits_threshold = 10
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        config.history.storage_root,
+        Some(PathBuf::from("/tmp/editor-history"))
+    );
+    assert_eq!(config.history.retention, RetentionPolicy::Days(30));
+    assert_eq!(config.history.large_files.strategy, LargeFileStrategy::Skip);
+    assert_eq!(config.history.large_files.threshold_mb, 50);
+    assert_eq!(config.history.gc.commits_threshold, 10);
+    assert!(config.history.gc.enabled);
+}
+
+#[test]
+fn test_unknown_keys_and_invalid_values_are_rejected() {
+    let error = EditorConfig::from_toml_str("[editor]\ntab_width = 2\n").unwrap_err();
+    assert!(matches!(error, EditorError::Parse(_)));
+    assert!(error.to_string().contains("tab_width"));
+
+    assert!(EditorConfig::from_toml_str("[colors]\ntheme = \"dark\"\n").is_err());
+    assert!(EditorConfig::from_toml_str("[editor]\ntab_size = \"four\"\n").is_err());
+    assert!(EditorConfig::from_toml_str("[history]\nretention = \"weekly\"\n").is_err());
+
+    let error = EditorConfig::from_toml_str("[editor]\ntab_size = 0\n").unwrap_err();
+    assert!(error.to_string().contains("tab_size"));
+}
+
+#[test]
+fn test_project_layer_overrides_user_layer() {
+    let temp_dir = TempDir::new().unwrap();
+    let user = temp_dir.path().join("config.toml");
+    let project = temp_dir.path().join(".editor-rs.toml");
+    fs::write(
+        &user,
+        "[editor]\ntab_size = 8\nscroll_offset = 2\n\n[history]\nretention = { days = 7 }\n",
+    )
+    .unwrap();
+    fs::write(
+        &project,
+        "[editor]\ntab_size = 2\n\n[history]\nretention = { commits = 100 }\n",
+    )
+    .unwrap();
+
+    let config = ConfigLoader::empty()
+        .with_user_path(&user)
+        .with_project_path(&project)
+        .load()
+        .unwrap();
+
+    assert_eq!(config.editor.tab_size, 2);
+    assert_eq!(config.editor.scroll_offset, 2);
+    assert!(config.editor.insert_spaces);
+    assert_eq!(config.history.retention, RetentionPolicy::Commits(100));
+}
+
+#[test]
+fn test_missing_layers_fall_back_to_defaults() {
+    let temp_dir = TempDir::new().unwrap();
+    let config = ConfigLoader::empty()
+        .with_user_path(temp_dir.path().join("missing.toml"))
+        .load()
+        .unwrap();
+    assert_eq!(config, EditorConfig::default());
+}
+
+#[test]
+fn test_layer_errors_name_the_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join(".editor-rs.toml");
+    fs::write(&project, "[view]\nminimap = true\n").unwrap();
+
+    let error = ConfigLoader::empty()
+        .with_project_path(&project)
+        .load()
+        .unwrap_err();
+    let message = error.to_string();
+    assert!(message.contains(".editor-rs.toml"), "{}", message);
+    assert!(message.contains("minimap"), "{}", message);
+}
+
+#[test]
+fn test_discover_project_config_searches_ancestors() {
+    let temp_dir = TempDir::new().unwrap();
+    let nested = temp_dir.path().join("src/module");
+    fs::create_dir_all(&nested).unwrap();
+    fs::write(temp_dir.path().join(".editor-rs.toml"), "").unwrap();
+
+    assert_eq!(
+        ConfigLoader::discover_project_config(&nested),
+        Some(temp_dir.path().join(".editor-rs.toml"))
+    );
+}
+
+#[test]
+fn test_save_round_trips() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("nested/config.toml");
+    let mut config = EditorConfig::default();
+    config.editor.soft_wrap = 80;
+    config.history.retention = RetentionPolicy::Size(500);
+
+    config.save(&path).unwrap();
+    let loaded = EditorConfig::from_toml_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(loaded, config);
+}
+
+#[test]
+fn test_apply_config_updates_editor_state() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut config = EditorConfig::from_toml_str(
+        r#"
+[editor]
+tab_size = 2
+scroll_offset = 1
+soft_wrap = 72
+auto_commit = false
+
+[history]
+auto_cleanup = true
+retention = { commits = 50 }
+
+[history.large_files]
+threshold_mb = 5
+"#,
+    )
+    .unwrap();
+    config.history.storage_root = Some(temp_dir.path().to_path_buf());
+
+    let mut editor = EditorState::new();
+    editor.apply_config(&config).unwrap();
+
+    assert_eq!(editor.tab_size(), 2);
+    assert_eq!(editor.scroll_offset(), 1);
+    assert_eq!(editor.soft_wrap_width(), Some(72));
+    assert!(!editor.auto_commit_enabled());
+    assert_eq!(editor.large_file_config().threshold_mb, 5);
+
+    let git_history = editor.git_history();
+    assert_eq!(git_history.storage_root(), temp_dir.path());
+    assert_eq!(
+        git_history.retention_policy(),
+        &RetentionPolicy::Commits(50)
+    );
+    assert_eq!(git_history.large_file_config().threshold_mb, 5);
+    assert!(git_history.auto_cleanup_enabled());
+}
+
+#[test]
+fn test_indent_follows_tab_settings() {
+    let mut editor = EditorState::new();
+    editor.execute_command(Command::InsertChar('x')).unwrap();
+
+    let mut config = EditorConfig::default();
+    config.editor.tab_size = 2;
+    editor.apply_config(&config).unwrap();
+    editor.execute_command(Command::Indent).unwrap();
+    assert_eq!(editor.current_buffer().content(), "  x");
+
+    config.editor.insert_spaces = false;
+    editor.apply_config(&config).unwrap();
+    editor.execute_command(Command::Indent).unwrap();
+    assert_eq!(editor.current_buffer().content(), "\t  x");
+
+    editor.execute_command(Command::Dedent).unwrap();
+    assert_eq!(editor.current_buffer().content(), "  x");
+    editor.execute_command(Command::Dedent).unwrap();
+    assert_eq!(editor.current_buffer().content(), "x");
+}
+
+#[test]
+fn test_watcher_reloads_changed_config() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join(".editor-rs.toml");
+    fs::write(&path, "[editor]\ntab_size = 4\n").unwrap();
+
+    let mut watcher = ConfigWatcher::new(ConfigLoader::empty().with_project_path(&path)).unwrap();
+    assert!(watcher.poll().is_none());
+
+    fs::write(&path, "[editor]\ntab_size = 3\n").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut reloaded = None;
+    while Instant::now() < deadline {
+        if let Some(Ok(config)) = watcher.poll() {
+            reloaded = Some(config);
+            if reloaded.as_ref().unwrap().editor.tab_size == 3 {
+                break;
+            }
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    assert_eq!(reloaded.map(|config| config.editor.tab_size), Some(3));
+}
+
+#[test]
+fn test_watcher_picks_up_config_created_later() {
+    let temp_dir = TempDir::new().unwrap();
+    let config_dir = temp_dir
+        .path()
+        .join("home")
+        .join(".config")
+        .join("editor-rs");
+    let path = config_dir.join("config.toml");
+
+    let mut watcher = ConfigWatcher::new(ConfigLoader::empty().with_user_path(&path)).unwrap();
+    assert!(watcher.poll().is_none());
+
+    fs::create_dir_all(&config_dir).unwrap();
+    fs::write(&path, "[editor]\ntab_size = 3\n").unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut tab_size = None;
+    while Instant::now() < deadline && tab_size != Some(3) {
+        if let Some(Ok(config)) = watcher.poll() {
+            tab_size = Some(config.editor.tab_size);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+
+    assert_eq!(tab_size, Some(3));
+}
@@ -0,0 +1,234 @@
+use editor_core::{
+    Buffer, Command, EditorConfigFile, EditorConfigProperties, EditorState, Encoding, IndentSize,
+    IndentStyle, LineEnding,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn properties_for(file: &EditorConfigFile, path: &Path) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    file.collect_properties(path, &mut values);
+    values
+}
+
+#[test]
+fn test_parse_root_and_sections() {
+    let dir = Path::new("/project");
+    let file = EditorConfigFile::parse(
+        "# comment\nroot = true\n\n[*]\nindent_style = space\nIndent_Size = 2\n\n[*.md]\ntrim_trailing_whitespace = false\n",
+        dir,
+    );
+
+    assert!(file.root);
+    assert_eq!(file.directory(), dir);
+
+    let values = properties_for(&file, Path::new("/project/README.md"));
+    assert_eq!(
+        values.get("indent_style").map(String::as_str),
+        Some("space")
+    );
+    assert_eq!(values.get("indent_size").map(String::as_str), Some("2"));
+    assert_eq!(
+        values.get("trim_trailing_whitespace").map(String::as_str),
+        Some("false")
+    );
+}
+
+#[test]
+fn test_glob_matching() {
+    let dir = Path::new("/project");
+    let file = EditorConfigFile::parse(
+        r#"
+[*.{js,ts}]
+kind = script
+
+[lib/**.rs]
+kind = library
+
+[/Makefile]
+kind = make
+
+[file{1..3}.txt]
+kind = numbered
+
+[[!a-c]*.py]
+kind = python
+"#,
+        dir,
+    );
+    let kind = |path: &str| properties_for(&file, Path::new(path)).get("kind").cloned();
+
+    assert_eq!(kind("/project/src/app.ts").as_deref(), Some("script"));
+    assert_eq!(kind("/project/app.js").as_deref(), Some("script"));
+    assert_eq!(kind("/project/app.json"), None);
+    assert_eq!(kind("/project/lib/a/b/mod.rs").as_deref(), Some("library"));
+    assert_eq!(kind("/project/src/lib/mod.rs"), None);
+    assert_eq!(kind("/project/Makefile").as_deref(), Some("make"));
+    assert_eq!(kind("/project/sub/Makefile"), None);
+    assert_eq!(kind("/project/file2.txt").as_deref(), Some("numbered"));
+    assert_eq!(kind("/project/file4.txt"), None);
+    assert_eq!(kind("/project/setup.py").as_deref(), Some("python"));
+    assert_eq!(kind("/project/build.py"), None);
+}
+
+#[test]
+fn test_from_values_applies_defaults_between_properties() {
+    let values: HashMap<String, String> = [("indent_style", "tab"), ("tab_width", "8")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let properties = EditorConfigProperties::from_values(&values);
+    assert_eq!(properties.indent_size, Some(IndentSize::Tab));
+    assert_eq!(properties.indent_width(), Some(8));
+    assert_eq!(properties.insert_spaces(), Some(false));
+
+    let values: HashMap<String, String> = [("indent_size", "3"), ("end_of_line", "crlf")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    let properties = EditorConfigProperties::from_values(&values);
+    assert_eq!(properties.tab_width, Some(3));
+    assert_eq!(properties.end_of_line, Some(LineEnding::Crlf));
+    assert_eq!(properties.indent_style, None);
+
+    let values: HashMap<String, String> = [("indent_size", "wide"), ("charset", "latin1")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    assert!(EditorConfigProperties::from_values(&values).is_empty());
+}
+
+#[test]
+fn test_resolve_walks_up_to_root() {
+    let temp_dir = TempDir::new().unwrap();
+    let project = temp_dir.path().join("project");
+    let nested = project.join("src");
+    fs::create_dir_all(&nested).unwrap();
+
+    fs::write(
+        temp_dir.path().join(".editorconfig"),
+        "[*]\ninsert_final_newline = true\n",
+    )
+    .unwrap();
+    fs::write(
+        project.join(".editorconfig"),
+        "root = true\n\n[*]\nindent_style = space\nindent_size = 4\ncharset = utf-8\n",
+    )
+    .unwrap();
+    fs::write(
+        nested.join(".editorconfig"),
+        "[*.rs]\nindent_size = 2\ncharset = unset\n",
+    )
+    .unwrap();
+
+    let properties = EditorConfigProperties::resolve(&nested.join("main.rs"));
+    assert_eq!(properties.indent_style, Some(IndentStyle::Space));
+    assert_eq!(properties.indent_size, Some(IndentSize::Columns(2)));
+    assert_eq!(properties.charset, None);
+    assert_eq!(properties.insert_final_newline, None);
+
+    let properties = EditorConfigProperties::resolve(&nested.join("notes.txt"));
+    assert_eq!(properties.indent_size, Some(IndentSize::Columns(4)));
+    assert_eq!(properties.charset, Some(Encoding::Utf8));
+}
+
+#[test]
+fn test_save_applies_editorconfig() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join(".editorconfig"),
+        "root = true\n\n[*.txt]\nend_of_line = crlf\ntrim_trailing_whitespace = true\ninsert_final_newline = true\ncharset = utf-8-bom\n",
+    )
+    .unwrap();
+    let path = temp_dir.path().join("notes.txt");
+    fs::write(&path, "one  \ntwo\t").unwrap();
+
+    let mut buffer = Buffer::from_file(path.clone()).unwrap();
+    assert_eq!(buffer.line_ending(), LineEnding::Lf);
+    buffer.save().unwrap();
+
+    assert_eq!(fs::read(&path).unwrap(), b"\xEF\xBB\xBFone\r\ntwo\r\n");
+    assert_eq!(buffer.line_ending(), LineEnding::Crlf);
+    assert_eq!(buffer.content(), "one\ntwo\n");
+    assert!(!buffer.is_modified());
+
+    let reopened = Buffer::from_file(path).unwrap();
+    assert_eq!(reopened.encoding(), Encoding::Utf8Bom);
+    assert_eq!(reopened.content(), "one\ntwo\n");
+}
+
+#[test]
+fn test_save_removes_final_newline_when_disabled() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join(".editorconfig"),
+        "root = true\n\n[*]\ninsert_final_newline = false\n",
+    )
+    .unwrap();
+    let path = temp_dir.path().join("data.csv");
+
+    let mut buffer = Buffer::from_string("a,b\n\n");
+    buffer.save_as(path.clone()).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "a,b");
+}
+
+#[test]
+fn test_save_normalization_is_one_undo_step() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join(".editorconfig"),
+        "root = true\n\n[*]\ntrim_trailing_whitespace = true\ninsert_final_newline = true\n",
+    )
+    .unwrap();
+    let path = temp_dir.path().join("notes.txt");
+    fs::write(&path, "one\n").unwrap();
+
+    let mut editor = EditorState::new();
+    editor.set_auto_commit_enabled(false);
+    editor.execute_command(Command::Open(path.clone())).unwrap();
+    editor.execute_command(Command::MoveToEndOfLine).unwrap();
+    editor.execute_command(Command::InsertChar(' ')).unwrap();
+    editor.execute_command(Command::InsertChar(' ')).unwrap();
+    editor.execute_command(Command::Save).unwrap();
+
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one\n");
+    assert_eq!(editor.current_buffer().content(), "one\n");
+
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), "one  \n");
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), "one\n");
+}
+
+#[test]
+fn test_editorconfig_overrides_global_indentation_per_buffer() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join(".editorconfig"),
+        "root = true\n\n[Makefile]\nindent_style = tab\n\n[*.py]\nindent_size = 2\n",
+    )
+    .unwrap();
+    let makefile = temp_dir.path().join("Makefile");
+    let script = temp_dir.path().join("main.py");
+    fs::write(&makefile, "all:\n").unwrap();
+    fs::write(&script, "pass\n").unwrap();
+
+    let mut editor = EditorState::new();
+    assert_eq!(editor.tab_size(), 4);
+
+    editor.execute_command(Command::Open(makefile)).unwrap();
+    assert!(!editor.insert_spaces());
+    editor.execute_command(Command::Indent).unwrap();
+    assert_eq!(editor.current_buffer().content(), "\tall:\n");
+
+    editor.execute_command(Command::Open(script)).unwrap();
+    assert!(editor.insert_spaces());
+    assert_eq!(editor.tab_size(), 2);
+    editor.execute_command(Command::Indent).unwrap();
+    assert_eq!(editor.current_buffer().content(), "  pass\n");
+
+    editor.execute_command(Command::New).unwrap();
+    assert_eq!(editor.tab_size(), 4);
+}
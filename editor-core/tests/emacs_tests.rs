@@ -0,0 +1,270 @@
+mod common;
+
+use common::{content, open, run, type_text};
+use editor_core::{
+    Command, CommandRegistry, CursorPosition, EditorConfig, EditorState, Key, KeyMatch, KeyPress,
+    KeymapMode, KeymapProfile, Keymaps, KillRing, Selection,
+};
+use tempfile::TempDir;
+
+fn ctrl(c: char) -> KeyPress {
+    KeyPress::ctrl(Key::Char(c))
+}
+
+#[test]
+fn test_kill_ring_appends_and_rotates() {
+    let mut ring = KillRing::with_capacity(2);
+    assert_eq!(ring.current(), None);
+    assert_eq!(ring.rotate(), None);
+
+    ring.push("one");
+    ring.append(" two", false);
+    ring.append("zero ", true);
+    assert_eq!(ring.current(), Some("zero one two"));
+
+    ring.push("three");
+    ring.push("");
+    ring.push("four");
+    assert_eq!(ring.entries().collect::<Vec<_>>(), vec!["four", "three"]);
+    assert_eq!(ring.rotate(), Some("three"));
+    assert_eq!(ring.rotate(), Some("four"));
+    ring.rotate();
+    assert_eq!(ring.reset_yank(), Some("four"));
+}
+
+#[test]
+fn test_successive_kill_lines_append() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "one two\nthree\nfour\n");
+
+    run(&mut editor, &[Command::KillLine, Command::KillLine]);
+    assert_eq!(content(&editor), "three\nfour\n");
+    assert_eq!(editor.kill_ring().current(), Some("one two\n"));
+    assert_eq!(editor.kill_ring().len(), 1);
+
+    // Moving the cursor breaks the run, so the next kill is a new entry.
+    run(
+        &mut editor,
+        &[
+            Command::MoveCursorDown,
+            Command::KillLine,
+            Command::MoveToStartOfFile,
+            Command::Yank,
+        ],
+    );
+    assert_eq!(content(&editor), "fourthree\n\n");
+    assert_eq!(editor.kill_ring().len(), 2);
+
+    run(&mut editor, &[Command::Undo]);
+    assert_eq!(content(&editor), "three\n\n");
+}
+
+#[test]
+fn test_yank_pop_cycles_through_kills() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "alpha beta gamma\n");
+
+    run(
+        &mut editor,
+        &[
+            Command::KillWord { forward: true },
+            Command::MoveCursorRight,
+            Command::KillWord { forward: true },
+        ],
+    );
+    assert_eq!(content(&editor), "  gamma\n");
+
+    run(&mut editor, &[Command::MoveToEndOfLine, Command::Yank]);
+    assert_eq!(content(&editor), "  gammabeta\n");
+    run(&mut editor, &[Command::YankPop]);
+    assert_eq!(content(&editor), "  gammaalpha\n");
+    run(&mut editor, &[Command::YankPop]);
+    assert_eq!(content(&editor), "  gammabeta\n");
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 11));
+
+    run(&mut editor, &[Command::MoveCursorLeft]);
+    assert!(editor.execute_command(Command::YankPop).is_err());
+}
+
+#[test]
+fn test_backward_kill_word_prepends() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "one two three\n");
+
+    run(
+        &mut editor,
+        &[
+            Command::MoveToEndOfLine,
+            Command::KillWord { forward: false },
+            Command::KillWord { forward: false },
+        ],
+    );
+    assert_eq!(content(&editor), "one \n");
+    assert_eq!(editor.kill_ring().current(), Some("two three"));
+}
+
+#[test]
+fn test_mark_region_kill_and_copy() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "hello world\n");
+
+    assert!(editor.execute_command(Command::KillRegion).is_err());
+
+    run(
+        &mut editor,
+        &[Command::SetMark, Command::MoveCursorWordRight],
+    );
+    assert!(editor.is_mark_active());
+    assert_eq!(
+        editor.selection(),
+        Some(&Selection::new(
+            CursorPosition::new(0, 0),
+            CursorPosition::new(0, 6)
+        ))
+    );
+
+    run(&mut editor, &[Command::CopyRegion]);
+    assert!(!editor.is_mark_active());
+    assert_eq!(editor.selection(), None);
+    assert_eq!(editor.kill_ring().current(), Some("hello "));
+
+    run(
+        &mut editor,
+        &[Command::ExchangePointAndMark, Command::KillRegion],
+    );
+    assert_eq!(content(&editor), "world\n");
+    assert_eq!(editor.kill_ring().len(), 2);
+
+    run(&mut editor, &[Command::SetMark, Command::MoveCursorRight]);
+    type_text(&mut editor, "x");
+    assert!(!editor.is_mark_active());
+
+    run(&mut editor, &[Command::SetMark, Command::KeyboardQuit]);
+    assert!(!editor.is_mark_active());
+    assert_eq!(editor.selection(), None);
+    assert_eq!(editor.mark(), Some(*editor.cursor()));
+}
+
+#[test]
+fn test_incremental_search_forward_and_backward() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "foo bar\nFoo baz\nfood\n");
+
+    run(&mut editor, &[Command::IncrementalSearch { forward: true }]);
+    assert!(editor.is_isearch_active());
+    assert_eq!(editor.status_message(), "I-search: ");
+
+    type_text(&mut editor, "fo");
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 2));
+    type_text(&mut editor, "o");
+    assert_eq!(editor.isearch_query(), Some("foo"));
+    assert_eq!(content(&editor), "foo bar\nFoo baz\nfood\n");
+
+    run(&mut editor, &[Command::IncrementalSearch { forward: true }]);
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 3));
+    run(&mut editor, &[Command::IncrementalSearch { forward: true }]);
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 3));
+
+    run(
+        &mut editor,
+        &[Command::IncrementalSearch { forward: false }],
+    );
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 0));
+    assert_eq!(editor.status_message(), "I-search backward: foo");
+    run(
+        &mut editor,
+        &[Command::IncrementalSearch { forward: false }],
+    );
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 0));
+
+    run(&mut editor, &[Command::NewLine]);
+    assert!(!editor.is_isearch_active());
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 0));
+    assert_eq!(editor.selection(), None);
+    assert_eq!(content(&editor), "foo bar\nFoo baz\nfood\n");
+}
+
+#[test]
+fn test_incremental_search_cancel_and_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "abc abd\n");
+
+    run(&mut editor, &[Command::IncrementalSearch { forward: true }]);
+    type_text(&mut editor, "abd");
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 7));
+    type_text(&mut editor, "x");
+    assert_eq!(editor.status_message(), "Failing I-search: abdx");
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 7));
+
+    run(&mut editor, &[Command::KeyboardQuit]);
+    assert!(!editor.is_isearch_active());
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 0));
+
+    // An empty search repeats the last query, and other commands end the
+    // search before running.
+    run(&mut editor, &[Command::IncrementalSearch { forward: true }]);
+    type_text(&mut editor, "ab");
+    run(&mut editor, &[Command::NewLine, Command::MoveToEndOfLine]);
+    run(
+        &mut editor,
+        &[
+            Command::IncrementalSearch { forward: false },
+            Command::IncrementalSearch { forward: false },
+        ],
+    );
+    assert_eq!(editor.isearch_query(), Some("ab"));
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 4));
+    run(&mut editor, &[Command::MoveCursorRight]);
+    assert!(!editor.is_isearch_active());
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 5));
+}
+
+#[test]
+fn test_emacs_profile_binds_chords_without_conflicts() {
+    let keymaps = Keymaps::builtin_profile(KeymapProfile::Emacs);
+    assert!(keymaps.conflicts().is_empty(), "{:?}", keymaps.conflicts());
+
+    let lookup = |presses: &[KeyPress]| keymaps.lookup(KeymapMode::Editor, presses);
+    assert_eq!(lookup(&[ctrl('k')]), KeyMatch::Command("edit.kill_line"));
+    assert_eq!(
+        lookup(&[KeyPress::parse("Alt+Y").unwrap()]),
+        KeyMatch::Command("edit.yank_pop")
+    );
+    assert_eq!(
+        lookup(&[ctrl('s')]),
+        KeyMatch::Command("search.isearch_forward")
+    );
+    assert_eq!(lookup(&[ctrl('x')]), KeyMatch::Prefix);
+    assert_eq!(
+        lookup(&[ctrl('x'), ctrl('s')]),
+        KeyMatch::Command("file.save")
+    );
+    assert_eq!(
+        lookup(&[ctrl('x'), KeyPress::plain(Key::Char('2'))]),
+        KeyMatch::Command("view.split_down")
+    );
+    // Keys the profile leaves alone keep their default binding.
+    assert_eq!(lookup(&[ctrl('q')]), KeyMatch::Command("app.quit"));
+
+    assert_eq!(
+        Keymaps::builtin_profile(KeymapProfile::Default).lookup(KeymapMode::Editor, &[ctrl('k')]),
+        KeyMatch::Command("tools.delete_line")
+    );
+    assert!(Keymaps::load_user_profile(KeymapProfile::Emacs, CommandRegistry::builtin()).is_ok());
+}
+
+#[test]
+fn test_config_selects_keymap_profile() {
+    let config = EditorConfig::from_toml_str("[editor]\nkeymap = \"emacs\"\n").unwrap();
+    assert_eq!(config.editor.keymap, KeymapProfile::Emacs);
+    assert!(EditorConfig::from_toml_str("[editor]\nkeymap = \"nano\"\n").is_err());
+    assert_eq!(
+        KeymapProfile::from_name("default"),
+        Some(KeymapProfile::Default)
+    );
+
+    let mut editor = EditorState::new();
+    assert_eq!(editor.keymap_profile(), KeymapProfile::Default);
+    editor.apply_config(&config).unwrap();
+    assert_eq!(editor.keymap_profile(), KeymapProfile::Emacs);
+}
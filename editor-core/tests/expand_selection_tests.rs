@@ -0,0 +1,139 @@
+use editor_core::{Command, CursorPosition, EditorState};
+use std::fs;
+use tempfile::TempDir;
+
+fn selected(editor: &EditorState) -> String {
+    let selection = editor.selection().expect("a selection");
+    let content = editor.current_buffer().content();
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let (start, end) = (selection.start(), selection.end());
+    let mut text = String::new();
+    for (line, line_text) in lines.iter().enumerate() {
+        for (column, ch) in line_text.chars().enumerate() {
+            let position = CursorPosition::new(line, column);
+            if start <= position && position < end {
+                text.push(ch);
+            }
+        }
+    }
+    text
+}
+
+fn expand(editor: &mut EditorState) -> String {
+    editor.execute_command(Command::ExpandSelection).unwrap();
+    selected(editor)
+}
+
+fn place(editor: &mut EditorState, position: CursorPosition) {
+    editor
+        .execute_command(Command::MouseClick(position))
+        .unwrap();
+}
+
+const SOURCE: &str = "fn main() {\n    call(\"one (two)\", x);\n    next();\n}\n\nfn other() {}\n";
+
+#[test]
+fn test_expansion_steps_out_through_quotes_brackets_lines_and_paragraphs() {
+    let mut editor = EditorState::new();
+    editor
+        .current_buffer_mut()
+        .set_content(SOURCE.to_string())
+        .unwrap();
+    place(&mut editor, CursorPosition::new(1, 11));
+
+    assert_eq!(expand(&mut editor), "one");
+    assert_eq!(expand(&mut editor), "one (two)");
+    assert_eq!(expand(&mut editor), "\"one (two)\"");
+    assert_eq!(expand(&mut editor), "\"one (two)\", x");
+    assert_eq!(expand(&mut editor), "(\"one (two)\", x)");
+    assert_eq!(expand(&mut editor), "call(\"one (two)\", x);");
+    assert_eq!(expand(&mut editor), "    call(\"one (two)\", x);\n");
+    assert_eq!(
+        expand(&mut editor),
+        "\n    call(\"one (two)\", x);\n    next();\n"
+    );
+    assert_eq!(
+        expand(&mut editor),
+        "{\n    call(\"one (two)\", x);\n    next();\n}"
+    );
+    assert_eq!(
+        expand(&mut editor),
+        "fn main() {\n    call(\"one (two)\", x);\n    next();\n}"
+    );
+    assert_eq!(
+        expand(&mut editor),
+        "fn main() {\n    call(\"one (two)\", x);\n    next();\n}\n"
+    );
+    assert_eq!(expand(&mut editor), SOURCE);
+}
+
+#[test]
+fn test_shrink_steps_back_to_the_cursor() {
+    let mut editor = EditorState::new();
+    editor
+        .current_buffer_mut()
+        .set_content(SOURCE.to_string())
+        .unwrap();
+    place(&mut editor, CursorPosition::new(2, 6));
+
+    expand(&mut editor);
+    expand(&mut editor);
+    assert_eq!(selected(&editor), "next();");
+
+    editor.execute_command(Command::ShrinkSelection).unwrap();
+    assert_eq!(selected(&editor), "next");
+    editor.execute_command(Command::ShrinkSelection).unwrap();
+    assert!(editor.selection().is_none());
+    assert_eq!(editor.cursor(), &CursorPosition::new(2, 6));
+
+    // Nothing is left to shrink once the selection changed some other way.
+    expand(&mut editor);
+    editor
+        .execute_command(Command::MouseDoubleClick(CursorPosition::new(0, 4)))
+        .unwrap();
+    editor.execute_command(Command::ShrinkSelection).unwrap();
+    assert_eq!(selected(&editor), "main");
+}
+
+#[test]
+fn test_expansion_uses_the_syntax_tree_when_there_is_one() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("main.rs");
+    // The bracket inside the string would confuse plain scanning.
+    fs::write(&path, "fn f() {\n    g(\"a)\", b);\n}\n").unwrap();
+    let mut editor = EditorState::from_file(path).unwrap();
+    place(&mut editor, CursorPosition::new(1, 13));
+
+    assert_eq!(expand(&mut editor), "b");
+    assert_eq!(expand(&mut editor), "\"a)\", b");
+    assert_eq!(expand(&mut editor), "(\"a)\", b)");
+}
+
+#[test]
+fn test_every_cursor_expands_its_own_selection() {
+    let mut editor = EditorState::new();
+    editor
+        .current_buffer_mut()
+        .set_content("f(alpha)\ng(beta)\n".to_string())
+        .unwrap();
+    place(&mut editor, CursorPosition::new(0, 3));
+    editor
+        .execute_command(Command::AddCursor(CursorPosition::new(1, 3)))
+        .unwrap();
+
+    editor.execute_command(Command::ExpandSelection).unwrap();
+    editor.execute_command(Command::ExpandSelection).unwrap();
+
+    let spans: Vec<_> = editor
+        .selections()
+        .iter()
+        .map(|selection| (selection.start(), selection.end()))
+        .collect();
+    assert_eq!(
+        spans,
+        vec![
+            (CursorPosition::new(0, 1), CursorPosition::new(0, 8)),
+            (CursorPosition::new(1, 1), CursorPosition::new(1, 7)),
+        ]
+    );
+}
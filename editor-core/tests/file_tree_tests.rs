@@ -0,0 +1,373 @@
+use editor_core::{Command, EditorConfig, EditorState, FileStatus, FileTree, FileTreeEntryKind};
+use std::fs;
+use tempfile::TempDir;
+
+fn create_project() -> TempDir {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::create_dir(root.join("src")).unwrap();
+    fs::write(root.join("src").join("main.rs"), "fn main() {}\n").unwrap();
+    fs::write(root.join("src").join("lib.rs"), "").unwrap();
+    fs::create_dir(root.join("docs")).unwrap();
+    fs::write(root.join("README.md"), "# Readme\n").unwrap();
+    fs::write(root.join("Cargo.toml"), "[package]\n").unwrap();
+    fs::write(root.join(".hidden"), "").unwrap();
+    temp_dir
+}
+
+fn entry_names(tree: &FileTree) -> Vec<String> {
+    tree.entries().iter().map(|e| e.name.clone()).collect()
+}
+
+fn editor_with_tree(root: &std::path::Path) -> EditorState {
+    let mut editor = EditorState::new();
+    editor.set_auto_commit_enabled(false);
+    editor.open_file_tree(root.to_path_buf()).unwrap();
+    editor
+}
+
+#[test]
+fn test_file_tree_lists_directories_first() {
+    let project = create_project();
+    let tree = FileTree::new(project.path().to_path_buf()).unwrap();
+
+    assert_eq!(
+        entry_names(&tree),
+        vec!["docs", "src", "Cargo.toml", "README.md"]
+    );
+    assert_eq!(tree.entries()[0].kind, FileTreeEntryKind::Directory);
+    assert_eq!(tree.entries()[2].kind, FileTreeEntryKind::File);
+}
+
+#[test]
+fn test_file_tree_requires_directory() {
+    let project = create_project();
+    assert!(FileTree::new(project.path().join("README.md")).is_err());
+}
+
+#[test]
+fn test_file_tree_show_hidden() {
+    let project = create_project();
+    let mut tree = FileTree::new(project.path().to_path_buf()).unwrap();
+
+    tree.set_show_hidden(true).unwrap();
+
+    assert!(entry_names(&tree).contains(&".hidden".to_string()));
+}
+
+#[test]
+fn test_file_tree_expand_and_collapse() {
+    let project = create_project();
+    let mut tree = FileTree::new(project.path().to_path_buf()).unwrap();
+
+    tree.select_next();
+    tree.expand_selected().unwrap();
+    assert_eq!(
+        entry_names(&tree),
+        vec![
+            "docs",
+            "src",
+            "lib.rs",
+            "main.rs",
+            "Cargo.toml",
+            "README.md"
+        ]
+    );
+    assert_eq!(tree.entries()[2].depth, 1);
+
+    tree.select_next();
+    tree.collapse_selected().unwrap();
+    assert_eq!(tree.selected_entry().unwrap().name, "src");
+
+    tree.collapse_selected().unwrap();
+    assert_eq!(
+        entry_names(&tree),
+        vec!["docs", "src", "Cargo.toml", "README.md"]
+    );
+}
+
+#[test]
+fn test_file_tree_navigation_bounds() {
+    let project = create_project();
+    let mut tree = FileTree::new(project.path().to_path_buf()).unwrap();
+
+    assert!(!tree.select_previous());
+    tree.select_last();
+    assert_eq!(tree.selected_entry().unwrap().name, "README.md");
+    assert!(!tree.select_next());
+    tree.select_first();
+    assert_eq!(tree.selected_index(), 0);
+}
+
+#[test]
+fn test_file_tree_reveal_expands_parents() {
+    let project = create_project();
+    let mut tree = FileTree::new(project.path().to_path_buf()).unwrap();
+
+    let revealed = tree
+        .reveal(&project.path().join("src").join("main.rs"))
+        .unwrap();
+
+    assert!(revealed);
+    assert!(tree.is_expanded(&project.path().join("src")));
+    assert_eq!(tree.selected_entry().unwrap().name, "main.rs");
+}
+
+#[test]
+fn test_file_tree_create_file_in_selected_directory() {
+    let project = create_project();
+    let mut tree = FileTree::new(project.path().to_path_buf()).unwrap();
+
+    tree.select_next();
+    let path = tree.create_file("new.rs").unwrap();
+
+    assert_eq!(path, project.path().join("src").join("new.rs"));
+    assert!(path.exists());
+    assert_eq!(tree.selected_entry().unwrap().path, path);
+}
+
+#[test]
+fn test_file_tree_create_rejects_invalid_names() {
+    let project = create_project();
+    let mut tree = FileTree::new(project.path().to_path_buf()).unwrap();
+
+    assert!(tree.create_file("").is_err());
+    assert!(tree.create_file("../escape.txt").is_err());
+
+    tree.select_last();
+    assert!(tree.create_directory("docs").is_err());
+}
+
+#[test]
+fn test_file_tree_rename_keeps_expanded_state() {
+    let project = create_project();
+    let mut tree = FileTree::new(project.path().to_path_buf()).unwrap();
+
+    tree.select_next();
+    tree.expand_selected().unwrap();
+    let (old_path, new_path) = tree.rename_selected("source").unwrap();
+
+    assert!(!old_path.exists());
+    assert!(new_path.join("main.rs").exists());
+    assert!(tree.is_expanded(&new_path));
+    assert_eq!(tree.selected_entry().unwrap().name, "source");
+}
+
+#[test]
+fn test_file_tree_delete_directory() {
+    let project = create_project();
+    let mut tree = FileTree::new(project.path().to_path_buf()).unwrap();
+
+    tree.select_next();
+    let deleted = tree.delete_selected().unwrap();
+
+    assert!(!deleted.exists());
+    assert_eq!(entry_names(&tree), vec!["docs", "Cargo.toml", "README.md"]);
+}
+
+#[test]
+fn test_toggle_file_tree_uses_buffer_directory() {
+    let project = create_project();
+    let file_path = project.path().join("src").join("main.rs");
+    let mut editor = EditorState::from_file(file_path.clone()).unwrap();
+
+    editor.execute_command(Command::ToggleFileTree).unwrap();
+
+    assert!(editor.is_file_tree_open());
+    assert!(editor.is_file_tree_focused());
+    let tree = editor.file_tree().unwrap();
+    assert_eq!(tree.root(), project.path().join("src"));
+    assert_eq!(tree.selected_entry().unwrap().path, file_path);
+
+    editor.execute_command(Command::ToggleFileTree).unwrap();
+    assert!(!editor.is_file_tree_open());
+    assert!(!editor.is_file_tree_focused());
+}
+
+#[test]
+fn test_file_tree_commands_require_open_tree() {
+    let mut editor = EditorState::new();
+    assert!(editor
+        .execute_command(Command::FileTreeNavigateNext)
+        .is_err());
+}
+
+#[test]
+fn test_file_tree_focus_commands() {
+    let project = create_project();
+    let mut editor = editor_with_tree(project.path());
+
+    editor.execute_command(Command::FocusEditor).unwrap();
+    assert!(!editor.is_file_tree_focused());
+    assert!(editor.is_file_tree_open());
+
+    editor.execute_command(Command::FocusFileTree).unwrap();
+    assert!(editor.is_file_tree_focused());
+}
+
+#[test]
+fn test_file_tree_activate_opens_file() {
+    let project = create_project();
+    let mut editor = editor_with_tree(project.path());
+
+    editor
+        .execute_command(Command::FileTreeNavigateLast)
+        .unwrap();
+    editor.execute_command(Command::FileTreeActivate).unwrap();
+
+    assert_eq!(
+        editor.file_path(),
+        Some(project.path().join("README.md").as_path())
+    );
+    assert_eq!(editor.current_buffer().content(), "# Readme\n");
+    assert!(!editor.is_file_tree_focused());
+
+    editor.execute_command(Command::FocusFileTree).unwrap();
+    editor.execute_command(Command::FileTreeActivate).unwrap();
+    assert_eq!(editor.buffer_count(), 2);
+}
+
+#[test]
+fn test_file_tree_activate_toggles_directory() {
+    let project = create_project();
+    let mut editor = editor_with_tree(project.path());
+
+    editor.execute_command(Command::FileTreeSelect(1)).unwrap();
+    editor.execute_command(Command::FileTreeActivate).unwrap();
+
+    assert_eq!(editor.file_tree().unwrap().entries().len(), 6);
+    assert!(editor.is_file_tree_focused());
+}
+
+#[test]
+fn test_file_tree_rename_updates_open_buffer() {
+    let project = create_project();
+    let mut editor = editor_with_tree(project.path());
+
+    editor
+        .execute_command(Command::FileTreeNavigateLast)
+        .unwrap();
+    editor.execute_command(Command::FileTreeActivate).unwrap();
+    editor.execute_command(Command::FocusFileTree).unwrap();
+    editor
+        .execute_command(Command::FileTreeRename("NOTES.md".to_string()))
+        .unwrap();
+
+    let new_path = project.path().join("NOTES.md");
+    assert!(new_path.exists());
+    assert_eq!(editor.file_path(), Some(new_path.as_path()));
+}
+
+#[test]
+fn test_file_tree_rename_directory_updates_open_buffers() {
+    let project = create_project();
+    let mut editor = editor_with_tree(project.path());
+
+    editor
+        .execute_command(Command::Open(project.path().join("src").join("main.rs")))
+        .unwrap();
+    editor.execute_command(Command::FileTreeSelect(1)).unwrap();
+    editor
+        .execute_command(Command::FileTreeRename("source".to_string()))
+        .unwrap();
+
+    assert_eq!(
+        editor.file_path(),
+        Some(project.path().join("source").join("main.rs").as_path())
+    );
+}
+
+#[test]
+fn test_file_tree_create_and_delete_commands() {
+    let project = create_project();
+    let mut editor = editor_with_tree(project.path());
+
+    editor
+        .execute_command(Command::FileTreeNavigateLast)
+        .unwrap();
+    editor
+        .execute_command(Command::FileTreeCreateDirectory("tests".to_string()))
+        .unwrap();
+    editor
+        .execute_command(Command::FileTreeCreateFile("it.rs".to_string()))
+        .unwrap();
+
+    let created = project.path().join("tests").join("it.rs");
+    assert!(created.exists());
+
+    editor.execute_command(Command::FileTreeDelete).unwrap();
+    assert!(!created.exists());
+    assert!(editor.status_message().starts_with("Deleted"));
+}
+
+#[test]
+fn test_file_tree_commands_allowed_with_read_only_buffer() {
+    let project = create_project();
+    let mut editor = editor_with_tree(project.path());
+
+    editor.execute_command(Command::ToggleReadOnly).unwrap();
+    editor
+        .execute_command(Command::FileTreeNavigateLast)
+        .unwrap();
+    editor
+        .execute_command(Command::FileTreeCreateFile("new.txt".to_string()))
+        .unwrap();
+
+    assert!(project.path().join("new.txt").exists());
+}
+
+#[test]
+fn test_file_status_marks_modified_buffers() {
+    let project = create_project();
+    let file_path = project.path().join("src").join("main.rs");
+    let mut editor = EditorState::from_file(file_path.clone()).unwrap();
+
+    assert_eq!(editor.file_status(&file_path), FileStatus::Open);
+    assert_eq!(
+        editor.file_status(&project.path().join("README.md")),
+        FileStatus::Clean
+    );
+
+    editor.execute_command(Command::InsertChar('x')).unwrap();
+
+    assert_eq!(editor.file_status(&file_path), FileStatus::Modified);
+    assert_eq!(
+        editor.file_status(&project.path().join("src")),
+        FileStatus::Modified
+    );
+    assert_eq!(
+        editor.file_status(&project.path().join("docs")),
+        FileStatus::Clean
+    );
+}
+
+#[test]
+fn test_file_tree_rename_directory_keeps_history() {
+    let project = create_project();
+    let storage = TempDir::new().unwrap();
+    let mut config = EditorConfig::default();
+    config.history.storage_root = Some(storage.path().to_path_buf());
+
+    let mut editor = EditorState::new();
+    editor.apply_config(&config).unwrap();
+    editor.open_file_tree(project.path().to_path_buf()).unwrap();
+    editor
+        .execute_command(Command::Open(project.path().join("src").join("main.rs")))
+        .unwrap();
+    editor.execute_command(Command::InsertChar('x')).unwrap();
+    editor.execute_command(Command::Save).unwrap();
+
+    editor.execute_command(Command::FileTreeSelect(1)).unwrap();
+    editor
+        .execute_command(Command::FileTreeRename("source".to_string()))
+        .unwrap();
+
+    let new_dir = project.path().join("source");
+    let commits = editor.git_history().list_commits(&new_dir).unwrap();
+    assert_eq!(commits.len(), 1);
+    let files = editor
+        .git_history()
+        .get_files_changed(&new_dir, &commits[0].id)
+        .unwrap();
+    assert!(files.iter().any(|f| f.path == "main.rs"));
+}
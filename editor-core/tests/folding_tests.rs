@@ -0,0 +1,287 @@
+mod common;
+
+use common::open;
+use editor_core::{
+    indentation_fold_ranges, Buffer, Command, CommandAction, CommandRegistry, CursorPosition,
+    EditorState, FoldRange, FoldState, Session,
+};
+use ropey::Rope;
+use std::fs;
+use tempfile::TempDir;
+
+const PYTHON: &str = "def outer():\n    x = 1\n    if x:\n        y = x\n        z = 3\n\n    return x\n\nprint(outer())\n";
+
+const RUST: &str = "fn main() {\n    let v = vec![\n        1,\n        2,\n    ];\n    if v.is_empty() {\n        return;\n    }\n}\n";
+
+fn goto(editor: &mut EditorState, line: usize, column: usize) {
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(line, column)))
+        .unwrap();
+}
+
+#[test]
+fn test_indentation_fold_ranges() {
+    let ranges = indentation_fold_ranges(&Rope::from_str(PYTHON), 4);
+    assert_eq!(ranges, vec![FoldRange::new(0, 6), FoldRange::new(2, 4)]);
+
+    let tabs = indentation_fold_ranges(&Rope::from_str("a\n\tb\n    c\nd\n"), 4);
+    assert_eq!(tabs, vec![FoldRange::new(0, 2)]);
+
+    assert!(indentation_fold_ranges(&Rope::from_str("a\nb\n"), 4).is_empty());
+}
+
+#[test]
+fn test_syntax_fold_ranges_keep_closing_line_visible() {
+    let temp_dir = TempDir::new().unwrap();
+    let editor = open(temp_dir.path(), "main.rs", RUST);
+    assert!(editor.current_buffer().syntax_tree().is_some());
+
+    assert_eq!(
+        editor.fold_ranges(),
+        vec![
+            FoldRange::new(0, 7),
+            FoldRange::new(1, 4),
+            FoldRange::new(5, 6),
+        ]
+    );
+    assert!(editor.is_line_foldable(0));
+    assert!(!editor.is_line_foldable(2));
+}
+
+#[test]
+fn test_fold_unfold_and_toggle_leave_buffer_untouched() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "outer.py", PYTHON);
+
+    goto(&mut editor, 3, 8);
+    editor.execute_command(Command::FoldCode).unwrap();
+    assert!(editor.is_line_folded(2));
+    assert!(editor.is_line_hidden(3));
+    assert_eq!(editor.cursor().line, 2);
+    assert_eq!(editor.current_buffer().content(), PYTHON);
+    assert!(!editor.current_buffer().is_modified());
+
+    editor.execute_command(Command::FoldCode).unwrap();
+    assert!(editor.is_line_folded(0));
+    assert_eq!(editor.cursor().line, 0);
+
+    editor.execute_command(Command::UnfoldCode).unwrap();
+    assert!(!editor.is_line_folded(0));
+    assert!(editor.is_line_folded(2));
+
+    goto(&mut editor, 2, 0);
+    editor.execute_command(Command::ToggleFold).unwrap();
+    assert!(!editor.is_line_folded(2));
+    editor.execute_command(Command::ToggleFold).unwrap();
+    assert!(editor.is_line_folded(2));
+
+    goto(&mut editor, 8, 0);
+    editor.execute_command(Command::FoldCode).unwrap();
+    assert_eq!(editor.status_message(), "Nothing to fold");
+}
+
+#[test]
+fn test_fold_all_unfold_all_and_levels() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "main.rs", RUST);
+    goto(&mut editor, 6, 4);
+
+    editor.execute_command(Command::FoldAll).unwrap();
+    assert_eq!(editor.current_buffer().folds().folds().len(), 3);
+    assert_eq!(editor.cursor().line, 0);
+
+    editor.execute_command(Command::UnfoldAll).unwrap();
+    assert!(editor.current_buffer().folds().is_empty());
+
+    editor.execute_command(Command::FoldLevel(2)).unwrap();
+    assert!(!editor.is_line_folded(0));
+    assert!(editor.is_line_folded(1));
+    assert!(editor.is_line_folded(5));
+    assert!(editor.execute_command(Command::FoldLevel(0)).is_err());
+
+    let registry = CommandRegistry::new();
+    match registry.resolve("view.fold_level", &["2".to_string()]) {
+        Ok(CommandAction::Execute(Command::FoldLevel(2))) => {}
+        other => panic!("unexpected action {:?}", other),
+    }
+}
+
+#[test]
+fn test_cursor_movement_skips_folded_lines() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "outer.py", PYTHON);
+    goto(&mut editor, 2, 4);
+    editor.execute_command(Command::FoldCode).unwrap();
+
+    editor.execute_command(Command::MoveCursorDown).unwrap();
+    assert_eq!(editor.cursor().line, 5);
+    editor.execute_command(Command::MoveCursorUp).unwrap();
+    assert_eq!(editor.cursor().line, 2);
+
+    editor.execute_command(Command::MoveToEndOfLine).unwrap();
+    editor.execute_command(Command::MoveCursorRight).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(5, 0));
+    editor.execute_command(Command::MoveCursorLeft).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 9));
+
+    goto(&mut editor, 0, 0);
+    editor.execute_command(Command::PageDown).unwrap();
+    assert_eq!(editor.cursor().line, 9);
+    editor.execute_command(Command::PageUp).unwrap();
+    assert_eq!(editor.cursor().line, 0);
+}
+
+#[test]
+fn test_virtual_viewport_skips_folded_lines() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "outer.py", PYTHON);
+    goto(&mut editor, 2, 0);
+    editor.execute_command(Command::FoldCode).unwrap();
+
+    let viewport = editor.get_virtual_viewport(4);
+    assert_eq!(viewport.line_numbers, vec![0, 1, 2, 5]);
+    assert_eq!(viewport.visible_lines[2], "    if x:\n");
+    assert_eq!(viewport.start_line, 0);
+    assert_eq!(viewport.end_line, 6);
+
+    let content = (0..100)
+        .map(|i| {
+            if i % 10 == 0 {
+                format!("block {}\n", i)
+            } else {
+                format!("    line {}\n", i)
+            }
+        })
+        .collect::<String>();
+    let mut editor = EditorState::new();
+    editor.current_buffer_mut().set_content(content).unwrap();
+    editor.execute_command(Command::FoldAll).unwrap();
+    editor.execute_command(Command::MoveToEndOfFile).unwrap();
+    editor.set_scroll_offset(0);
+    editor.adjust_viewport_to_cursor(5);
+
+    let viewport = editor.get_virtual_viewport(5);
+    assert_eq!(viewport.line_numbers, vec![60, 70, 80, 90, 100]);
+}
+
+#[test]
+fn test_search_skips_folded_matches() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "outer.py", PYTHON);
+    goto(&mut editor, 2, 0);
+    editor.execute_command(Command::FoldCode).unwrap();
+    goto(&mut editor, 0, 0);
+
+    editor
+        .execute_command(Command::Search("x".to_string()))
+        .unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 5));
+    editor.execute_command(Command::NextMatch).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 8));
+    editor.execute_command(Command::NextMatch).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(6, 12));
+    editor.execute_command(Command::PreviousMatch).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 8));
+    assert!(editor.is_line_folded(2));
+
+    editor
+        .execute_command(Command::Search("y = x".to_string()))
+        .unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 8));
+}
+
+#[test]
+fn test_goto_hidden_line_reveals_fold() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "outer.py", PYTHON);
+    editor.execute_command(Command::FoldAll).unwrap();
+
+    editor.execute_command(Command::GotoLine(3)).unwrap();
+    assert_eq!(editor.cursor().line, 3);
+    assert!(!editor.is_line_hidden(3));
+    assert!(editor.current_buffer().folds().is_empty());
+}
+
+#[test]
+fn test_edits_shift_and_drop_folds() {
+    let mut buffer = Buffer::from_string(PYTHON);
+    buffer.folds_mut().fold(FoldRange::new(2, 4));
+
+    buffer.insert_str(0, 0, "import os\n\n").unwrap();
+    assert_eq!(buffer.folds().folds(), &[FoldRange::new(4, 6)]);
+
+    buffer.insert_char(4, 4, 'f').unwrap();
+    assert_eq!(buffer.folds().folds(), &[FoldRange::new(4, 6)]);
+
+    buffer.insert_str(6, 0, "        w = 4\n").unwrap();
+    assert_eq!(buffer.folds().folds(), &[FoldRange::new(4, 7)]);
+
+    buffer.delete_range(3, 0, 5, 0).unwrap();
+    assert!(buffer.folds().is_empty());
+}
+
+#[test]
+fn test_fold_state_navigation() {
+    let mut folds = FoldState::new();
+    folds.fold(FoldRange::new(2, 5));
+    folds.fold(FoldRange::new(3, 4));
+    folds.fold(FoldRange::new(8, 8));
+
+    assert_eq!(folds.folds().len(), 2);
+    assert_eq!(folds.visible_line(4), 2);
+    assert_eq!(folds.next_visible(2, 10), Some(6));
+    assert_eq!(folds.previous_visible(6), Some(2));
+    assert_eq!(folds.next_visible(9, 10), None);
+    assert_eq!(folds.visible_lines(0, 10, 5), vec![0, 1, 2, 6, 7]);
+    assert_eq!(folds.count_visible(0, 7), 4);
+    assert_eq!(folds.visible_line_above(7, 2), 2);
+    assert_eq!(folds.visible_line_below(1, 2, 10), 6);
+
+    assert_eq!(folds.unfold_at(2), Some(FoldRange::new(2, 5)));
+    assert!(folds.is_hidden(4));
+    assert!(!folds.is_hidden(5));
+}
+
+#[test]
+fn test_folds_saved_in_session() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "outer.py", PYTHON);
+    goto(&mut editor, 2, 0);
+    editor.execute_command(Command::FoldCode).unwrap();
+
+    let mut session = Session::new();
+    editor.save_session_state(&mut session);
+    let session_path = temp_dir.path().join("session.toml");
+    session.save_to_file(&session_path).unwrap();
+
+    let loaded = Session::load_from_file(&session_path).unwrap();
+    let path = temp_dir.path().join("outer.py");
+    assert_eq!(
+        loaded.load_folds(&path).unwrap().folds,
+        vec![FoldRange::new(2, 4)]
+    );
+
+    let mut restored = EditorState::new();
+    restored
+        .restore_from_file_state(loaded.get_active_file().unwrap())
+        .unwrap();
+    restored.restore_folds_from_session(&loaded).unwrap();
+    assert!(restored.is_line_folded(2));
+    assert_eq!(restored.cursor().line, 2);
+
+    editor.execute_command(Command::UnfoldAll).unwrap();
+    editor.save_session_state(&mut session);
+    assert!(session.load_folds(&path).is_none());
+
+    let legacy = fs::read_to_string(&session_path)
+        .unwrap()
+        .lines()
+        .take_while(|line| !line.starts_with("[[folds]]"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(&session_path, legacy).unwrap();
+    assert!(Session::load_from_file(&session_path)
+        .unwrap()
+        .folds
+        .is_empty());
+}
@@ -0,0 +1,60 @@
+use editor_core::{fuzzy_filter, fuzzy_match};
+
+#[test]
+fn test_fuzzy_match_empty_pattern_matches_everything() {
+    let result = fuzzy_match("", "Save File").unwrap();
+    assert_eq!(result.score, 0);
+    assert!(result.indices.is_empty());
+}
+
+#[test]
+fn test_fuzzy_match_subsequence() {
+    let result = fuzzy_match("svf", "Save File").unwrap();
+    assert_eq!(result.indices, vec![0, 2, 5]);
+}
+
+#[test]
+fn test_fuzzy_match_is_case_insensitive() {
+    assert!(fuzzy_match("GOTO", "Go to Line").is_some());
+    assert!(fuzzy_match("goto", "Go to Line").is_some());
+}
+
+#[test]
+fn test_fuzzy_match_rejects_missing_characters() {
+    assert!(fuzzy_match("xyz", "Save File").is_none());
+    assert!(fuzzy_match("fs", "Save File").is_none());
+}
+
+#[test]
+fn test_fuzzy_match_ignores_whitespace_in_pattern() {
+    let result = fuzzy_match("go line", "Go to Line").unwrap();
+    assert_eq!(result.indices, vec![0, 1, 6, 7, 8, 9]);
+}
+
+#[test]
+fn test_fuzzy_match_prefers_word_starts() {
+    let result = fuzzy_match("tl", "Toggle Line Numbers").unwrap();
+    assert_eq!(result.indices, vec![0, 7]);
+}
+
+#[test]
+fn test_fuzzy_match_falls_back_when_word_start_cannot_complete() {
+    let result = fuzzy_match("abc", "axbc_b").unwrap();
+    assert_eq!(result.indices, vec![0, 2, 3]);
+}
+
+#[test]
+fn test_fuzzy_match_scores_prefix_higher() {
+    let prefix = fuzzy_match("save", "Save").unwrap();
+    let inner = fuzzy_match("save", "Autosave").unwrap();
+    assert!(prefix.score > inner.score);
+}
+
+#[test]
+fn test_fuzzy_filter_sorts_by_score() {
+    let items = vec!["Close File", "Copy", "Toggle Comment"];
+    let results = fuzzy_filter("co", &items, |s| s);
+
+    assert_eq!(results.len(), 3);
+    assert_eq!(*results[0].0, "Copy");
+}
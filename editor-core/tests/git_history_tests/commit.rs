@@ -497,3 +497,91 @@ fn test_auto_commit_all_files_outside_project() {
     let result = manager.auto_commit_on_save_multiple(project_dir.path(), &file_paths);
     assert!(result.is_ok());
 }
+
+#[test]
+fn test_commit_file_move_within_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage_root = temp_dir.path().join("storage");
+    let project_dir = TempDir::new().unwrap();
+
+    let old_path = project_dir.path().join("old.txt");
+    fs::write(&old_path, "content").unwrap();
+
+    let manager = GitHistoryManager::with_storage_root(storage_root).unwrap();
+    manager
+        .auto_commit_on_save(project_dir.path(), &old_path)
+        .unwrap();
+
+    let new_path = project_dir.path().join("new.txt");
+    fs::rename(&old_path, &new_path).unwrap();
+
+    let moved = manager
+        .commit_file_move(project_dir.path(), project_dir.path(), &old_path, &new_path)
+        .unwrap();
+    assert!(moved);
+
+    let commits = manager.list_commits(project_dir.path()).unwrap();
+    assert_eq!(commits.len(), 2);
+    assert!(commits[0].message.starts_with("Rename: old.txt -> new.txt"));
+
+    let files = manager
+        .get_files_changed(project_dir.path(), &commits[0].id)
+        .unwrap();
+    assert!(files
+        .iter()
+        .any(|f| f.path == "old.txt" && f.status == ChangeStatus::Deleted));
+    assert!(files
+        .iter()
+        .any(|f| f.path == "new.txt" && f.status == ChangeStatus::Added));
+}
+
+#[test]
+fn test_commit_file_move_untracked_file() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage_root = temp_dir.path().join("storage");
+    let project_dir = TempDir::new().unwrap();
+
+    let old_path = project_dir.path().join("old.txt");
+    let new_path = project_dir.path().join("new.txt");
+    fs::write(&new_path, "content").unwrap();
+
+    let manager = GitHistoryManager::with_storage_root(storage_root).unwrap();
+    let moved = manager
+        .commit_file_move(project_dir.path(), project_dir.path(), &old_path, &new_path)
+        .unwrap();
+
+    assert!(!moved);
+    assert!(manager.list_commits(project_dir.path()).unwrap().is_empty());
+}
+
+#[test]
+fn test_commit_file_move_between_directories() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage_root = temp_dir.path().join("storage");
+    let project_dir = TempDir::new().unwrap();
+    let other_dir = project_dir.path().join("other");
+    fs::create_dir(&other_dir).unwrap();
+
+    let old_path = project_dir.path().join("file.txt");
+    fs::write(&old_path, "content").unwrap();
+
+    let manager = GitHistoryManager::with_storage_root(storage_root).unwrap();
+    manager
+        .auto_commit_on_save(project_dir.path(), &old_path)
+        .unwrap();
+
+    let new_path = other_dir.join("file.txt");
+    fs::rename(&old_path, &new_path).unwrap();
+
+    manager
+        .commit_file_move(project_dir.path(), &other_dir, &old_path, &new_path)
+        .unwrap();
+
+    let old_commits = manager.list_commits(project_dir.path()).unwrap();
+    assert_eq!(old_commits.len(), 2);
+    assert!(old_commits[0].message.starts_with("Moved out: file.txt"));
+
+    let new_commits = manager.list_commits(&other_dir).unwrap();
+    assert_eq!(new_commits.len(), 1);
+    assert!(new_commits[0].message.starts_with("Moved in:"));
+}
@@ -204,6 +204,47 @@ fn test_handle_project_rename() {
     assert_eq!(commits.len(), 1);
 }
 
+#[test]
+fn test_handle_directory_rename_in_project() {
+    let temp_dir = TempDir::new().unwrap();
+    let storage_root = temp_dir.path().join("storage");
+
+    let project = temp_dir.path().join("project");
+    let src = project.join("src");
+    fs::create_dir_all(&src).unwrap();
+    fs::write(project.join("Cargo.toml"), "[package]\n").unwrap();
+
+    let manager = GitHistoryManager::with_storage_root(storage_root).unwrap();
+
+    let file = src.join("main.rs");
+    fs::write(&file, "fn main() {}\n").unwrap();
+    manager.auto_commit_on_save(&project, &file).unwrap();
+    fs::write(&file, "fn main() { run(); }\n").unwrap();
+    manager.auto_commit_on_save(&project, &file).unwrap();
+
+    let source = project.join("source");
+    fs::rename(&src, &source).unwrap();
+    assert_eq!(manager.handle_directory_rename(&src, &source).unwrap(), 1);
+
+    let commits = manager.list_commits(&project).unwrap();
+    assert_eq!(commits.len(), 3);
+    assert_eq!(
+        manager
+            .get_file_content_at_commit(&project, "source/main.rs", &commits[0].id)
+            .unwrap(),
+        "fn main() { run(); }\n"
+    );
+    assert!(manager
+        .get_file_content_at_commit(&project, "src/main.rs", &commits[0].id)
+        .is_err());
+    assert_eq!(
+        manager
+            .get_file_content_at_commit(&project, "src/main.rs", &commits[2].id)
+            .unwrap(),
+        "fn main() {}\n"
+    );
+}
+
 #[test]
 fn test_handle_project_rename_nonexistent() {
     let temp_dir = TempDir::new().unwrap();
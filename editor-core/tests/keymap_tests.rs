@@ -0,0 +1,304 @@
+use editor_core::{
+    CommandRegistry, EditorError, Key, KeyMatch, KeyModifiers, KeyPress, KeyResolution,
+    KeySequence, KeySequenceResolver, KeymapConflictKind, KeymapMode, Keymaps,
+};
+use std::fs;
+use tempfile::TempDir;
+
+fn ctrl(c: char) -> KeyPress {
+    KeyPress::ctrl(Key::Char(c))
+}
+
+#[test]
+fn test_parse_key_press_with_modifiers() {
+    let press = KeyPress::parse("Ctrl+Shift+P").unwrap();
+    assert_eq!(press.key, Key::Char('p'));
+    assert!(press.modifiers.ctrl);
+    assert!(press.modifiers.shift);
+    assert!(!press.modifiers.alt);
+
+    assert_eq!(KeyPress::parse("ctrl+/").unwrap(), ctrl('/'));
+    assert_eq!(KeyPress::parse("Esc").unwrap().key, Key::Escape);
+    assert_eq!(KeyPress::parse("PgDn").unwrap().key, Key::PageDown);
+    assert_eq!(KeyPress::parse("F12").unwrap().key, Key::F(12));
+    assert_eq!(KeyPress::parse("Ctrl++").unwrap(), ctrl('+'));
+    assert_eq!(
+        KeyPress::parse("BackTab").unwrap(),
+        KeyPress::parse("Shift+Tab").unwrap()
+    );
+}
+
+#[test]
+fn test_parse_key_press_errors() {
+    assert!(matches!(
+        KeyPress::parse("Hyper+A"),
+        Err(EditorError::Parse(_))
+    ));
+    assert!(matches!(
+        KeyPress::parse("Ctrl+Foo"),
+        Err(EditorError::Parse(_))
+    ));
+    assert!(matches!(KeyPress::parse("F99"), Err(EditorError::Parse(_))));
+    assert!(matches!(
+        KeySequence::parse("   "),
+        Err(EditorError::Parse(_))
+    ));
+}
+
+#[test]
+fn test_uppercase_char_implies_shift() {
+    let press = KeyPress::new(Key::Char('A'), KeyModifiers::NONE);
+    assert_eq!(press.key, Key::Char('a'));
+    assert!(press.modifiers.shift);
+    assert_eq!(press, KeyPress::parse("Shift+A").unwrap());
+    assert_ne!(press, KeyPress::parse("A").unwrap());
+}
+
+#[test]
+fn test_key_sequence_display_round_trips() {
+    for text in [
+        "Ctrl+K Ctrl+C",
+        "Ctrl+Shift+Up",
+        "Alt+F",
+        "Space",
+        "Shift+F3",
+    ] {
+        let sequence = KeySequence::parse(text).unwrap();
+        assert_eq!(sequence.to_string(), text);
+        assert_eq!(KeySequence::parse(&sequence.to_string()).unwrap(), sequence);
+    }
+    assert!(KeySequence::parse("Ctrl+K Ctrl+C").unwrap().is_chord());
+}
+
+#[test]
+fn test_default_keymaps_cover_every_mode_without_conflicts() {
+    let keymaps = Keymaps::builtin();
+    for mode in KeymapMode::all() {
+        assert!(keymaps.keymap(*mode).is_some(), "missing {}", mode);
+        assert_eq!(
+            keymaps.lookup(*mode, &[ctrl('q')]),
+            KeyMatch::Command("app.quit")
+        );
+    }
+    assert!(keymaps.conflicts().is_empty(), "{:?}", keymaps.conflicts());
+}
+
+#[test]
+fn test_registry_keybindings_match_default_editor_keymap() {
+    let registry = CommandRegistry::builtin();
+    let keymaps = Keymaps::builtin();
+
+    for spec in registry.all() {
+        let Some(keybinding) = spec.keybinding else {
+            continue;
+        };
+        let sequence = KeySequence::parse(keybinding).unwrap();
+        let mode = if spec.id == "file_tree.focus_editor" {
+            KeymapMode::FileTree
+        } else {
+            KeymapMode::Editor
+        };
+        assert_eq!(
+            keymaps.lookup(mode, sequence.presses()),
+            KeyMatch::Command(spec.id),
+            "{} should be bound to {}",
+            keybinding,
+            spec.id
+        );
+    }
+}
+
+#[test]
+fn test_user_overrides_replace_and_unbind() {
+    let registry = CommandRegistry::builtin();
+    let mut keymaps = Keymaps::builtin().clone();
+
+    keymaps
+        .merge_toml_str(
+            r#"
+[editor]
+"Ctrl+K" = ""
+"Ctrl+K Ctrl+C" = "tools.toggle_comment"
+"Ctrl+D" = "edit.undo"
+"#,
+            registry,
+        )
+        .unwrap();
+
+    assert_eq!(
+        keymaps.lookup(KeymapMode::Editor, &[ctrl('k')]),
+        KeyMatch::Prefix
+    );
+    assert_eq!(
+        keymaps.lookup(KeymapMode::Editor, &[ctrl('k'), ctrl('c')]),
+        KeyMatch::Command("tools.toggle_comment")
+    );
+    assert_eq!(
+        keymaps.lookup(KeymapMode::Editor, &[ctrl('d')]),
+        KeyMatch::Command("edit.undo")
+    );
+    assert_eq!(
+        keymaps.lookup(KeymapMode::FileTree, &[ctrl('k')]),
+        KeyMatch::None
+    );
+    assert!(keymaps.conflicts().is_empty());
+}
+
+#[test]
+fn test_bindings_can_carry_arguments() {
+    let registry = CommandRegistry::builtin();
+    let mut keymaps = Keymaps::builtin().clone();
+    keymaps
+        .merge_toml_str(
+            "[editor]\n\"Ctrl+K\" = \"\"\n\"Ctrl+K Ctrl+T\" = \"tools.play_macro tidy\"\n",
+            registry,
+        )
+        .unwrap();
+
+    let keymap = keymaps.keymap(KeymapMode::Editor).unwrap();
+    assert_eq!(
+        keymap.lookup(&[ctrl('k'), ctrl('t')]),
+        KeyMatch::Command("tools.play_macro")
+    );
+    assert_eq!(keymap.arguments(&[ctrl('k'), ctrl('t')]), ["tidy"]);
+    assert!(keymap.arguments(&[ctrl('z')]).is_empty());
+
+    let mut resolver = KeySequenceResolver::new();
+    resolver.feed(&keymaps, KeymapMode::Editor, ctrl('k'));
+    assert_eq!(
+        resolver.feed(&keymaps, KeymapMode::Editor, ctrl('t')),
+        KeyResolution::Command("tools.play_macro")
+    );
+    assert_eq!(resolver.take_arguments(), ["tidy"]);
+    assert!(resolver.take_arguments().is_empty());
+
+    let wrong_count = Keymaps::from_toml_str(
+        "[editor]\n\"F5\" = \"tools.play_macro_times tidy\"\n",
+        registry,
+    );
+    assert!(matches!(wrong_count, Err(EditorError::Parse(_))));
+    let not_a_number = Keymaps::from_toml_str(
+        "[editor]\n\"F5\" = \"tools.play_macro_times tidy often\"\n",
+        registry,
+    );
+    assert!(matches!(not_a_number, Err(EditorError::Parse(_))));
+}
+
+#[test]
+fn test_unknown_command_and_mode_are_rejected() {
+    let registry = CommandRegistry::builtin();
+    let mut keymaps = Keymaps::empty();
+
+    assert!(matches!(
+        keymaps.merge_toml_str("[editor]\n\"Ctrl+K\" = \"no.such_command\"\n", registry),
+        Err(EditorError::Parse(_))
+    ));
+    assert!(matches!(
+        keymaps.merge_toml_str("[visual]\n\"Ctrl+K\" = \"edit.undo\"\n", registry),
+        Err(EditorError::Parse(_))
+    ));
+    assert!(matches!(
+        keymaps.merge_toml_str("[editor]\n\"Ctrl+K\" = 3\n", registry),
+        Err(EditorError::Parse(_))
+    ));
+}
+
+#[test]
+fn test_conflicts_detect_prefix_and_duplicate_bindings() {
+    let registry = CommandRegistry::builtin();
+    let mut keymaps = Keymaps::builtin().clone();
+
+    keymaps
+        .merge_toml_str(
+            r#"
+[editor]
+"Ctrl+K Ctrl+C" = "tools.toggle_comment"
+"ctrl+u" = "tools.title_case"
+"#,
+            registry,
+        )
+        .unwrap();
+
+    let conflicts = keymaps.conflicts();
+    assert!(conflicts.iter().any(|conflict| {
+        conflict.kind == KeymapConflictKind::Prefix
+            && conflict.command == "tools.delete_line"
+            && conflict.other_command == "tools.toggle_comment"
+    }));
+    assert!(conflicts
+        .iter()
+        .all(|conflict| conflict.mode == KeymapMode::Editor));
+    assert!(conflicts[0].to_string().contains("[editor]"));
+
+    let mut duplicates = Keymaps::empty();
+    duplicates
+        .merge_toml_str(
+            "[menu]\n\"Ctrl+K\" = \"menu.up\"\n\"ctrl+k\" = \"menu.down\"\n",
+            registry,
+        )
+        .unwrap();
+    assert_eq!(
+        duplicates.conflicts()[0].kind,
+        KeymapConflictKind::Duplicate
+    );
+}
+
+#[test]
+fn test_resolver_handles_chords() {
+    let registry = CommandRegistry::builtin();
+    let keymaps = Keymaps::from_toml_str(
+        "[editor]\n\"Ctrl+K Ctrl+C\" = \"tools.toggle_comment\"\n\"Ctrl+S\" = \"file.save\"\n",
+        registry,
+    )
+    .unwrap();
+    let mut resolver = KeySequenceResolver::new();
+
+    assert_eq!(
+        resolver.feed(&keymaps, KeymapMode::Editor, ctrl('k')),
+        KeyResolution::Pending
+    );
+    assert_eq!(resolver.pending_display().as_deref(), Some("Ctrl+K"));
+    assert_eq!(
+        resolver.feed(&keymaps, KeymapMode::Editor, ctrl('c')),
+        KeyResolution::Command("tools.toggle_comment")
+    );
+    assert!(!resolver.is_pending());
+
+    resolver.feed(&keymaps, KeymapMode::Editor, ctrl('k'));
+    assert_eq!(
+        resolver.feed(&keymaps, KeymapMode::Editor, ctrl('x')),
+        KeyResolution::Cancelled
+    );
+    assert_eq!(
+        resolver.feed(&keymaps, KeymapMode::Editor, ctrl('x')),
+        KeyResolution::Unbound
+    );
+
+    resolver.feed(&keymaps, KeymapMode::Editor, ctrl('k'));
+    assert_eq!(
+        resolver.feed(&keymaps, KeymapMode::Palette, ctrl('c')),
+        KeyResolution::Unbound
+    );
+}
+
+#[test]
+fn test_merge_file_reports_path_on_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("keymap.toml");
+    fs::write(&path, "[editor]\n\"Ctrl+Nope+K\" = \"edit.undo\"\n").unwrap();
+
+    let mut keymaps = Keymaps::builtin().clone();
+    let error = keymaps
+        .merge_file(&path, CommandRegistry::builtin())
+        .unwrap_err();
+    assert!(error.to_string().contains("keymap.toml"));
+
+    fs::write(&path, "[palette]\n\"Ctrl+J\" = \"palette.next\"\n").unwrap();
+    keymaps
+        .merge_file(&path, CommandRegistry::builtin())
+        .unwrap();
+    assert_eq!(
+        keymaps.lookup(KeymapMode::Palette, &[ctrl('j')]),
+        KeyMatch::Command("palette.next")
+    );
+}
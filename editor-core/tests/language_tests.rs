@@ -0,0 +1,253 @@
+mod common;
+
+use common::{open, type_text};
+use editor_core::{
+    modeline_language, shebang_interpreter, Command, EditorState, LanguageRegistry, SyntaxLanguage,
+    PLAIN_TEXT,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+#[test]
+fn test_detect_by_extension_and_file_name() {
+    let registry = LanguageRegistry::builtin();
+    let detect = |path: &str| {
+        registry
+            .detect(Some(Path::new(path)), "")
+            .map(|language| language.name.as_str())
+    };
+
+    assert_eq!(detect("src/lib.rs"), Some("rust"));
+    assert_eq!(detect("include/util.HPP"), Some("cpp"));
+    assert_eq!(detect("Makefile"), Some("makefile"));
+    assert_eq!(detect("Cargo.lock"), Some("toml"));
+    assert_eq!(detect("notes.unknown"), None);
+    assert_eq!(
+        registry.get("python").and_then(|language| language.syntax),
+        Some(SyntaxLanguage::Python)
+    );
+}
+
+#[test]
+fn test_detect_by_shebang() {
+    assert_eq!(
+        shebang_interpreter("#!/usr/bin/env -S python3.12 -u\n").as_deref(),
+        Some("python3.12")
+    );
+    assert_eq!(
+        shebang_interpreter("#!/bin/bash\n").as_deref(),
+        Some("bash")
+    );
+    assert_eq!(shebang_interpreter("echo hi\n"), None);
+
+    let registry = LanguageRegistry::builtin();
+    let language = registry
+        .detect(Some(Path::new("bin/tool")), "#!/usr/bin/env python3.12\n")
+        .unwrap();
+    assert_eq!(language.name, "python");
+    let language = registry.detect(None, "#!/bin/sh\nset -e\n").unwrap();
+    assert_eq!(language.name, "shell");
+}
+
+#[test]
+fn test_modeline_overrides_extension() {
+    assert_eq!(
+        modeline_language("# vim: set ts=4 ft=python :\n").as_deref(),
+        Some("python")
+    );
+    assert_eq!(
+        modeline_language("// -*- mode: rust; coding: utf-8 -*-\n").as_deref(),
+        Some("rust")
+    );
+    assert_eq!(modeline_language("/* -*- c -*- */\n").as_deref(), Some("c"));
+    assert_eq!(modeline_language("# -*- coding: utf-8 -*-\n"), None);
+
+    let mut content = "line\n".repeat(20);
+    content.push_str("# vim: ft=sh\n");
+    let language = LanguageRegistry::builtin()
+        .detect(Some(Path::new("script.txt")), &content)
+        .unwrap();
+    assert_eq!(language.name, "shell");
+}
+
+#[test]
+fn test_user_languages_extend_and_override() {
+    let mut registry = LanguageRegistry::builtin().clone();
+    registry
+        .merge_toml_str(
+            r##"
+[[language]]
+name = "rust"
+line_comment = "///"
+
+[[language]]
+name = "nim"
+extensions = ["nim"]
+shebangs = ["nim"]
+line_comment = "#"
+block_comment = ["#[", "]#"]
+brackets = ["()", "[]"]
+indent_after = ["="]
+"##,
+        )
+        .unwrap();
+
+    let rust = registry.get("rust").unwrap();
+    assert_eq!(rust.line_comment.as_deref(), Some("///"));
+    assert_eq!(
+        rust.block_comment,
+        Some(("/*".to_string(), "*/".to_string()))
+    );
+
+    let nim = registry.for_path(Path::new("main.nim")).unwrap();
+    assert_eq!(nim.name, "nim");
+    assert_eq!(nim.brackets, vec![('(', ')'), ('[', ']')]);
+    assert!(nim.indent.increases_after("proc main() ="));
+
+    assert!(registry
+        .merge_toml_str("[[language]]\nname = \"bad\"\nbrackets = [\"(\"]\n")
+        .is_err());
+    assert!(registry
+        .merge_toml_str("[[language]]\nname = \"bad\"\nsyntax = \"cobol\"\n")
+        .is_err());
+    assert!(registry.get("bad").is_none());
+}
+
+#[test]
+fn test_user_language_file_applies_to_open_buffers() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "query.prql", "from users\n");
+    assert_eq!(editor.language().name, PLAIN_TEXT);
+
+    let languages_path: PathBuf = temp_dir.path().join("languages.toml");
+    fs::write(
+        &languages_path,
+        "[[language]]\nname = \"prql\"\nextensions = [\"prql\"]\nline_comment = \"#\"\n",
+    )
+    .unwrap();
+    let mut registry = LanguageRegistry::builtin().clone();
+    registry.merge_file(&languages_path).unwrap();
+    editor.set_language_registry(registry);
+
+    assert_eq!(editor.current_buffer().language(), Some("prql"));
+    editor.execute_command(Command::ToggleLineComment).unwrap();
+    assert_eq!(editor.current_buffer().content(), "# from users\n");
+}
+
+#[test]
+fn test_line_comment_uses_language_token() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "main.py", "    x = 1\n    # y = 2\n");
+    assert_eq!(editor.language().name, "python");
+
+    editor.execute_command(Command::ToggleLineComment).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "#     x = 1\n    # y = 2\n"
+    );
+    editor.execute_command(Command::ToggleLineComment).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "    x = 1\n    # y = 2\n"
+    );
+
+    editor.execute_command(Command::MoveCursorDown).unwrap();
+    editor.execute_command(Command::ToggleLineComment).unwrap();
+    assert_eq!(editor.current_buffer().content(), "    x = 1\n    y = 2\n");
+
+    let mut editor = open(temp_dir.path(), "style.css", "a {}\n");
+    editor.execute_command(Command::ToggleLineComment).unwrap();
+    assert_eq!(editor.current_buffer().content(), "a {}\n");
+    assert!(editor.status_message().contains("no line comment"));
+}
+
+#[test]
+fn test_block_comment_toggles_language_markers() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "README.md", "hidden\n");
+
+    editor.execute_command(Command::SelectionStart).unwrap();
+    editor.execute_command(Command::MoveToEndOfLine).unwrap();
+    editor.execute_command(Command::SelectionEnd).unwrap();
+    editor.execute_command(Command::ToggleBlockComment).unwrap();
+    assert_eq!(editor.current_buffer().content(), "<!--hidden-->\n");
+
+    editor.execute_command(Command::MoveToStartOfLine).unwrap();
+    editor.execute_command(Command::SelectionStart).unwrap();
+    editor.execute_command(Command::MoveToEndOfLine).unwrap();
+    editor.execute_command(Command::SelectionEnd).unwrap();
+    editor.execute_command(Command::ToggleBlockComment).unwrap();
+    assert_eq!(editor.current_buffer().content(), "hidden\n");
+}
+
+#[test]
+fn test_auto_close_and_brackets_follow_language() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "lib.rs", "");
+
+    editor
+        .execute_command(Command::InsertCharWithAutoClose('\''))
+        .unwrap();
+    assert_eq!(editor.current_buffer().content(), "'");
+
+    let mut editor = open(temp_dir.path(), "data.json", "{\"a\": [1, 2]}");
+    editor
+        .execute_command(Command::JumpToMatchingBracket)
+        .unwrap();
+    assert_eq!(editor.cursor().column, 12);
+
+    let mut editor = open(temp_dir.path(), "cmp.rs", "a < b > c");
+    editor.execute_command(Command::MoveCursorRight).unwrap();
+    editor.execute_command(Command::MoveCursorRight).unwrap();
+    editor
+        .execute_command(Command::JumpToMatchingBracket)
+        .unwrap();
+    assert_eq!(editor.cursor().column, 2);
+}
+
+#[test]
+fn test_indent_rules_on_new_line() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "main.rs", "");
+
+    type_text(&mut editor, "fn main() {\nlet x = 1;\n}");
+    assert_eq!(
+        editor.current_buffer().content(),
+        "fn main() {\n    let x = 1;\n}"
+    );
+
+    let mut editor = open(temp_dir.path(), "split.rs", "fn f() {}");
+    editor.execute_command(Command::MoveToEndOfLine).unwrap();
+    editor.execute_command(Command::MoveCursorLeft).unwrap();
+    editor.execute_command(Command::NewLine).unwrap();
+    assert_eq!(editor.current_buffer().content(), "fn f() {\n    \n}");
+    assert_eq!(editor.cursor().line, 1);
+    assert_eq!(editor.cursor().column, 4);
+
+    let mut editor = open(temp_dir.path(), "main.py", "");
+    type_text(&mut editor, "def f():\nreturn 1\n");
+    assert_eq!(
+        editor.current_buffer().content(),
+        "def f():\n    return 1\n    "
+    );
+}
+
+#[test]
+fn test_set_language_command() {
+    let mut editor = EditorState::new();
+    assert_eq!(editor.language().name, PLAIN_TEXT);
+
+    editor
+        .execute_command(Command::SetLanguage("py".to_string()))
+        .unwrap();
+    assert_eq!(editor.language().name, "python");
+    assert_eq!(
+        editor.current_buffer().syntax_language(),
+        Some(SyntaxLanguage::Python)
+    );
+
+    assert!(editor
+        .execute_command(Command::SetLanguage("cobol".to_string()))
+        .is_err());
+}
@@ -0,0 +1,104 @@
+use editor_core::lsp::{
+    from_lsp_position, path_to_uri, read_message, snippet_text, to_lsp_position, uri_to_path,
+    write_message, LspMessage,
+};
+use editor_core::{CursorPosition, LanguageRegistry, LspPosition};
+use ropey::Rope;
+use serde_json::json;
+use std::io::Cursor;
+use std::path::Path;
+
+#[test]
+fn test_message_framing_round_trips() {
+    let mut bytes = Vec::new();
+    let request = LspMessage::request(7, "textDocument/hover", json!({ "x": "é" }));
+    let notification = LspMessage::notification("initialized", json!({}));
+    write_message(&mut bytes, &request).unwrap();
+    write_message(&mut bytes, &notification).unwrap();
+    assert!(bytes.starts_with(b"Content-Length: "));
+
+    let mut reader = Cursor::new(bytes);
+    assert_eq!(read_message(&mut reader).unwrap(), Some(request));
+    assert_eq!(read_message(&mut reader).unwrap(), Some(notification));
+    assert_eq!(read_message(&mut reader).unwrap(), None);
+
+    let response = LspMessage::from_value(json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "error": { "code": -32601, "message": "unknown" },
+    }))
+    .unwrap();
+    let LspMessage::Response { error, .. } = response else {
+        panic!("expected a response");
+    };
+    assert_eq!(error.unwrap().message, "unknown");
+}
+
+#[test]
+fn test_oversized_message_is_rejected() {
+    let mut reader = Cursor::new(b"Content-Length: 18446744073709551615\r\n\r\n{}".to_vec());
+    assert!(read_message(&mut reader).is_err());
+}
+
+#[test]
+fn test_positions_use_utf16_columns() {
+    let rope = Rope::from_str("a😀b\nxyz\n");
+
+    let after_emoji = to_lsp_position(&rope, CursorPosition::new(0, 2));
+    assert_eq!(after_emoji, LspPosition::new(0, 3));
+    assert_eq!(
+        from_lsp_position(&rope, LspPosition::new(0, 3)),
+        CursorPosition::new(0, 2)
+    );
+    assert_eq!(
+        from_lsp_position(&rope, LspPosition::new(0, 99)),
+        CursorPosition::new(0, 3)
+    );
+    assert_eq!(
+        from_lsp_position(&rope, LspPosition::new(9, 0)),
+        CursorPosition::new(2, 0)
+    );
+}
+
+#[test]
+fn test_file_uris_round_trip() {
+    let path = Path::new("/tmp/my project/src/main #1.rs");
+    let uri = path_to_uri(path);
+    assert_eq!(uri, "file:///tmp/my%20project/src/main%20%231.rs");
+    assert_eq!(uri_to_path(&uri).unwrap(), path);
+    assert!(uri_to_path("https://example.com/a.rs").is_none());
+}
+
+#[test]
+fn test_snippet_text_keeps_placeholder_defaults() {
+    assert_eq!(snippet_text("println!(\"$1\")$0"), "println!(\"\")");
+    assert_eq!(snippet_text("fn ${1:name}(${2:args})"), "fn name(args)");
+    assert_eq!(snippet_text("${1:outer ${2:inner}}"), "outer inner");
+    assert_eq!(snippet_text("cost \\$5"), "cost $5");
+}
+
+#[test]
+fn test_language_server_config() {
+    let mut registry = LanguageRegistry::builtin().clone();
+    assert!(registry.get("rust").unwrap().language_server.is_none());
+
+    registry
+        .merge_toml_str(
+            "[[language]]\nname = \"rust\"\nlanguage_server = { command = \"rust-analyzer\", args = [\"--log\"] }\n",
+        )
+        .unwrap();
+    let server = registry
+        .get("rust")
+        .unwrap()
+        .language_server
+        .clone()
+        .unwrap();
+    assert_eq!(server.command, "rust-analyzer");
+    assert_eq!(server.args, vec!["--log".to_string()]);
+    assert_eq!(registry.get("rust").unwrap().language_id(), "rust");
+
+    registry
+        .merge_toml_str("[[language]]\nname = \"rust\"\nlanguage_server = { command = \"\" }\n")
+        .unwrap();
+    assert!(registry.get("rust").unwrap().language_server.is_none());
+}
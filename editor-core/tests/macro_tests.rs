@@ -0,0 +1,270 @@
+mod common;
+
+use common::{content, open, run};
+use editor_core::{
+    Command, CursorPosition, EditorError, EditorState, Key, KeyPress, KeyboardMacro, MacroRegisters,
+};
+use std::fs;
+use tempfile::TempDir;
+
+fn play(name: &str, count: usize) -> Command {
+    Command::PlayMacro {
+        name: name.to_string(),
+        count,
+    }
+}
+
+// Comments out the current line and moves to the next one.
+fn record_comment_macro(editor: &mut EditorState, name: &str) {
+    run(
+        editor,
+        &[
+            Command::StartMacroRecording(name.to_string()),
+            Command::MoveToStartOfLine,
+            Command::InsertChar('#'),
+            Command::InsertChar(' '),
+            Command::MoveCursorDown,
+            Command::StopMacroRecording,
+        ],
+    );
+}
+
+#[test]
+fn test_record_and_replay_named_macro() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "a\nb\nc\nd\ne\n");
+
+    record_comment_macro(&mut editor, "comment");
+    assert!(!editor.is_recording_macro());
+    assert_eq!(content(&editor), "# a\nb\nc\nd\ne\n");
+    assert_eq!(
+        editor.status_message(),
+        "Recorded macro 'comment' (4 commands)"
+    );
+    assert_eq!(
+        editor.macro_registers().get("comment"),
+        Some(&KeyboardMacro::new(vec![
+            Command::MoveToStartOfLine,
+            Command::InsertChar('#'),
+            Command::InsertChar(' '),
+            Command::MoveCursorDown,
+        ]))
+    );
+    assert_eq!(
+        editor.macro_registers().get(""),
+        editor.macro_registers().get("comment")
+    );
+
+    run(&mut editor, &[play("comment", 3)]);
+    assert_eq!(content(&editor), "# a\n# b\n# c\n# d\ne\n");
+    assert_eq!(*editor.cursor(), CursorPosition::new(4, 1));
+
+    // The whole playback undoes as one step.
+    run(&mut editor, &[Command::Undo]);
+    assert_eq!(content(&editor), "# a\nb\nc\nd\ne\n");
+}
+
+#[test]
+fn test_toggle_records_last_macro_and_play_last_ends_recording() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "x\n");
+
+    run(&mut editor, &[Command::ToggleMacroRecording]);
+    assert_eq!(editor.recording_macro_name(), Some(""));
+    run(&mut editor, &[Command::InsertChar('!'), play("", 1)]);
+    assert!(!editor.is_recording_macro());
+    assert_eq!(
+        editor.macro_registers().last(),
+        Some(&KeyboardMacro::new(vec![Command::InsertChar('!')]))
+    );
+    assert_eq!(editor.macro_registers().names().count(), 0);
+
+    run(&mut editor, &[play("", 2)]);
+    assert_eq!(content(&editor), "!!!x\n");
+
+    run(
+        &mut editor,
+        &[Command::ToggleMacroRecording, Command::ToggleMacroRecording],
+    );
+    assert_eq!(editor.status_message(), "Macro is empty, nothing recorded");
+    assert_eq!(editor.macro_registers().last().unwrap().len(), 1);
+}
+
+#[test]
+fn test_playback_stops_on_first_error() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "one\ntwo\n");
+
+    // A command that fails while recording is left out of the macro.
+    run(
+        &mut editor,
+        &[
+            Command::StartMacroRecording("jump".to_string()),
+            Command::InsertChar('>'),
+        ],
+    );
+    assert!(editor
+        .execute_command(Command::JumpToNamedBookmark("mark".to_string()))
+        .is_err());
+    run(
+        &mut editor,
+        &[Command::InsertChar('<'), Command::StopMacroRecording],
+    );
+    let recorded = editor.macro_registers().get("jump").unwrap().clone();
+    assert_eq!(
+        recorded.commands,
+        vec![Command::InsertChar('>'), Command::InsertChar('<')]
+    );
+
+    let mut failing = MacroRegisters::new();
+    failing.set(
+        "broken",
+        KeyboardMacro::new(vec![
+            Command::InsertChar('a'),
+            Command::JumpToNamedBookmark("missing".to_string()),
+            Command::InsertChar('b'),
+        ]),
+    );
+    editor.set_macro_registers(failing);
+    let error = editor.execute_command(play("broken", 2)).unwrap_err();
+    assert!(matches!(error, EditorError::InvalidOperation(ref message)
+        if message.starts_with("Macro stopped at command 2 of 3")));
+    assert_eq!(content(&editor), "><aone\ntwo\n");
+
+    assert!(editor.execute_command(play("nothing", 1)).is_err());
+}
+
+#[test]
+fn test_play_macro_once_at_every_cursor() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(
+        temp_dir.path(),
+        "notes.txt",
+        "let a = 1\nlet b = 2\nlet c = 3\n",
+    );
+
+    let mut macros = MacroRegisters::new();
+    macros.set(
+        "mutable",
+        KeyboardMacro::new(vec![
+            Command::MoveToStartOfLine,
+            Command::MoveCursorWordRight,
+            Command::InsertChar('m'),
+            Command::InsertChar('u'),
+            Command::InsertChar('t'),
+            Command::InsertChar(' '),
+        ]),
+    );
+    editor.set_macro_registers(macros);
+    run(
+        &mut editor,
+        &[
+            Command::AddCursor(CursorPosition::new(1, 5)),
+            Command::AddCursor(CursorPosition::new(2, 2)),
+            Command::PlayMacroAtCursors("mutable".to_string()),
+        ],
+    );
+    assert_eq!(
+        content(&editor),
+        "let mut a = 1\nlet mut b = 2\nlet mut c = 3\n"
+    );
+
+    run(&mut editor, &[Command::Undo]);
+    assert_eq!(content(&editor), "let a = 1\nlet b = 2\nlet c = 3\n");
+}
+
+#[test]
+fn test_macros_cannot_call_themselves() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "text\n");
+
+    run(
+        &mut editor,
+        &[Command::StartMacroRecording("loop".to_string())],
+    );
+    assert!(editor.execute_command(play("loop", 1)).is_err());
+    run(&mut editor, &[Command::StopMacroRecording]);
+
+    let mut macros = MacroRegisters::new();
+    macros.set("outer", KeyboardMacro::new(vec![play("inner", 1)]));
+    macros.set("inner", KeyboardMacro::new(vec![play("outer", 1)]));
+    editor.set_macro_registers(macros);
+    let error = editor.execute_command(play("outer", 1)).unwrap_err();
+    assert!(error.to_string().contains("can't call itself"), "{}", error);
+}
+
+#[test]
+fn test_nested_commands_are_not_recorded_twice() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "\n");
+
+    let mut macros = MacroRegisters::new();
+    macros.set("dash", KeyboardMacro::new(vec![Command::InsertChar('-')]));
+    editor.set_macro_registers(macros);
+    run(
+        &mut editor,
+        &[
+            Command::StartMacroRecording("twice".to_string()),
+            play("dash", 2),
+            Command::StopMacroRecording,
+        ],
+    );
+    assert_eq!(
+        editor.macro_registers().get("twice").unwrap().commands,
+        vec![play("dash", 2)]
+    );
+    run(&mut editor, &[play("twice", 1)]);
+    assert_eq!(content(&editor), "----\n");
+}
+
+#[test]
+fn test_macros_round_trip_through_toml() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("config").join("macros.toml");
+
+    let mut macros = MacroRegisters::new();
+    macros.set(
+        "mixed",
+        KeyboardMacro::new(vec![
+            Command::MoveToEndOfLine,
+            Command::InsertChar(';'),
+            Command::KillWord { forward: false },
+            Command::Search("needle".to_string()),
+            Command::VimKey(KeyPress::ctrl(Key::Char('v'))),
+            Command::Open(temp_dir.path().join("other.txt")),
+        ]),
+    );
+    macros.set("empty", KeyboardMacro::default());
+    macros.save_to_file(&path).unwrap();
+
+    let saved = fs::read_to_string(&path).unwrap();
+    assert!(saved.contains("[mixed]"), "{}", saved);
+    assert!(saved.contains("\"move_to_end_of_line\""), "{}", saved);
+    assert!(saved.contains("\"Ctrl+V\""), "{}", saved);
+
+    let loaded = MacroRegisters::load_from_file(&path).unwrap();
+    assert_eq!(loaded.get("mixed"), macros.get("mixed"));
+    assert_eq!(loaded.names().collect::<Vec<_>>(), vec!["empty", "mixed"]);
+    assert_eq!(loaded.last(), None);
+
+    let written = MacroRegisters::from_toml_str(
+        r#"
+[indent]
+commands = ["move_to_start_of_line", { insert_char = " " }, { kill_word = { forward = true } }]
+"#,
+    )
+    .unwrap();
+    assert_eq!(
+        written.get("indent").unwrap().commands,
+        vec![
+            Command::MoveToStartOfLine,
+            Command::InsertChar(' '),
+            Command::KillWord { forward: true },
+        ]
+    );
+
+    assert!(MacroRegisters::from_toml_str("[bad]\ncommands = [\"fly\"]\n").is_err());
+    fs::write(&path, "[bad]\nkeys = []\n").unwrap();
+    let error = MacroRegisters::load_from_file(&path).unwrap_err();
+    assert!(error.to_string().contains("macros.toml"), "{}", error);
+}
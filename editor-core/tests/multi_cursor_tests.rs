@@ -1,4 +1,9 @@
-use editor_core::{Command, CursorPosition, EditorState};
+mod common;
+
+use common::{editor_with, mock_clipboard};
+use editor_core::{
+    CaseMode, ClipboardManager, Command, CursorPosition, EditorState, MultiCursor, Selection,
+};
 
 #[test]
 fn add_and_remove_cursors() {
@@ -35,7 +40,7 @@ fn multi_cursor_insert_inserts_at_all_positions() {
     let positions: Vec<_> = editor.cursors().to_vec();
     assert_eq!(
         positions,
-        vec![CursorPosition::new(0, 1), CursorPosition::new(0, 3)]
+        vec![CursorPosition::new(0, 1), CursorPosition::new(0, 4)]
     );
 }
 
@@ -92,3 +97,310 @@ fn add_cursor_rejects_invalid_positions() {
     let result = editor.execute_command(Command::AddCursor(CursorPosition::new(5, 0)));
     assert!(result.is_err());
 }
+
+// Selects the first `width` characters of each of `lines`, one cursor each.
+fn select_line_starts(editor: &mut EditorState, lines: &[usize], width: usize) {
+    for &line in lines.iter().skip(1) {
+        editor
+            .execute_command(Command::AddCursor(CursorPosition::new(line, 0)))
+            .unwrap();
+    }
+    editor.execute_command(Command::SelectionStart).unwrap();
+    for _ in 0..width {
+        editor.execute_command(Command::MoveCursorRight).unwrap();
+    }
+    editor.execute_command(Command::SelectionEnd).unwrap();
+}
+
+#[test]
+fn test_each_cursor_carries_its_own_selection() {
+    let mut editor = editor_with("foo one\nbar two\nbaz six");
+    select_line_starts(&mut editor, &[0, 1, 2], 3);
+
+    assert_eq!(
+        editor.selections(),
+        vec![
+            Selection::new(CursorPosition::new(0, 0), CursorPosition::new(0, 3)),
+            Selection::new(CursorPosition::new(1, 0), CursorPosition::new(1, 3)),
+            Selection::new(CursorPosition::new(2, 0), CursorPosition::new(2, 3)),
+        ]
+    );
+    assert_eq!(editor.selection(), editor.selections().first());
+
+    // Collapsing to the primary cursor keeps its selection.
+    editor
+        .execute_command(Command::ClearSecondaryCursors)
+        .unwrap();
+    assert_eq!(
+        editor.selections(),
+        vec![Selection::new(
+            CursorPosition::new(0, 0),
+            CursorPosition::new(0, 3)
+        )]
+    );
+}
+
+#[test]
+fn test_copy_and_paste_are_distributed_per_cursor() {
+    let _lock = mock_clipboard();
+    let mut editor = editor_with("foo one\nbar two\nbaz six");
+    select_line_starts(&mut editor, &[0, 1, 2], 3);
+
+    editor.execute_command(Command::Cut).unwrap();
+    assert_eq!(editor.current_buffer().content(), " one\n two\n six");
+    assert!(editor.selections().is_empty());
+    assert_eq!(
+        ClipboardManager::new().unwrap().get_text().unwrap(),
+        "foo\nbar\nbaz"
+    );
+
+    editor.execute_command(Command::MoveToEndOfLine).unwrap();
+    editor.execute_command(Command::Paste).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        " onefoo\n twobar\n sixbaz"
+    );
+    assert_eq!(
+        editor.cursors(),
+        &[
+            CursorPosition::new(0, 7),
+            CursorPosition::new(1, 7),
+            CursorPosition::new(2, 7)
+        ]
+    );
+
+    // The whole paste is one undo step.
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), " one\n two\n six");
+}
+
+#[test]
+fn test_paste_with_another_cursor_count_inserts_everything_at_each() {
+    let _lock = mock_clipboard();
+    ClipboardManager::new()
+        .unwrap()
+        .set_text("a\nb\nc")
+        .unwrap();
+    let mut editor = editor_with("x\ny");
+    select_line_starts(&mut editor, &[0, 1], 1);
+
+    editor.execute_command(Command::Paste).unwrap();
+    assert_eq!(editor.current_buffer().content(), "a\nb\nc\na\nb\nc");
+}
+
+#[test]
+fn test_change_case_comment_and_indent_apply_to_every_selection() {
+    let mut editor = editor_with("foo one\nbar two\nbaz six\nqux ten");
+    editor
+        .execute_command(Command::SetLanguage("rust".to_string()))
+        .unwrap();
+    select_line_starts(&mut editor, &[0, 2], 3);
+
+    editor
+        .execute_command(Command::ChangeCase {
+            mode: CaseMode::Upper,
+        })
+        .unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "FOO one\nbar two\nBAZ six\nqux ten"
+    );
+    assert_eq!(editor.selections().len(), 2);
+
+    editor.execute_command(Command::Indent).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "    FOO one\nbar two\n    BAZ six\nqux ten"
+    );
+    assert_eq!(
+        editor.selections()[1],
+        Selection::new(CursorPosition::new(2, 4), CursorPosition::new(2, 7))
+    );
+
+    editor.execute_command(Command::Dedent).unwrap();
+    editor.execute_command(Command::ToggleLineComment).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "// FOO one\nbar two\n// BAZ six\nqux ten"
+    );
+}
+
+#[test]
+fn test_line_comment_moves_selections_with_the_text() {
+    let mut editor = editor_with("foo\nfoo\n");
+    editor
+        .execute_command(Command::SetLanguage("rust".to_string()))
+        .unwrap();
+    editor
+        .execute_command(Command::SelectAllOccurrences)
+        .unwrap();
+
+    editor.execute_command(Command::ToggleLineComment).unwrap();
+    assert_eq!(editor.current_buffer().content(), "// foo\n// foo\n");
+    assert_eq!(
+        editor.selections(),
+        vec![
+            Selection::new(CursorPosition::new(0, 3), CursorPosition::new(0, 6)),
+            Selection::new(CursorPosition::new(1, 3), CursorPosition::new(1, 6)),
+        ]
+    );
+
+    editor.execute_command(Command::ToggleLineComment).unwrap();
+    assert_eq!(editor.current_buffer().content(), "foo\nfoo\n");
+    assert_eq!(
+        editor.selections(),
+        vec![
+            Selection::new(CursorPosition::new(0, 0), CursorPosition::new(0, 3)),
+            Selection::new(CursorPosition::new(1, 0), CursorPosition::new(1, 3)),
+        ]
+    );
+}
+
+#[test]
+fn test_overlapping_selections_are_merged() {
+    let mut cursors = MultiCursor::new();
+    cursors.set_selections(vec![
+        Selection::new(CursorPosition::new(0, 0), CursorPosition::new(0, 4)),
+        Selection::new(CursorPosition::new(0, 6), CursorPosition::new(0, 2)),
+        Selection::new(CursorPosition::new(0, 8), CursorPosition::new(0, 9)),
+    ]);
+    assert_eq!(
+        cursors.selections(),
+        vec![
+            Some(Selection::new(
+                CursorPosition::new(0, 0),
+                CursorPosition::new(0, 6)
+            )),
+            Some(Selection::new(
+                CursorPosition::new(0, 8),
+                CursorPosition::new(0, 9)
+            )),
+        ]
+    );
+
+    // A bare cursor inside a selection folds into it; one between them stays.
+    cursors.add_cursor(CursorPosition::new(0, 3));
+    cursors.add_cursor(CursorPosition::new(0, 7));
+    assert_eq!(
+        cursors.positions(),
+        &[
+            CursorPosition::new(0, 6),
+            CursorPosition::new(0, 7),
+            CursorPosition::new(0, 9)
+        ]
+    );
+}
+
+fn selected_texts(editor: &EditorState) -> Vec<String> {
+    let content = editor.current_buffer().content();
+    let lines: Vec<&str> = content.split('\n').collect();
+    editor
+        .selections()
+        .iter()
+        .map(|selection| {
+            let (start, end) = (selection.start(), selection.end());
+            assert_eq!(start.line, end.line);
+            lines[start.line]
+                .chars()
+                .skip(start.column)
+                .take(end.column - start.column)
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_add_next_occurrence_selects_word_then_following_matches() {
+    let mut editor = editor_with("foo bar\nfood foo\nfoo\n");
+
+    editor.execute_command(Command::AddNextOccurrence).unwrap();
+    assert_eq!(editor.cursor_count(), 1);
+    assert_eq!(selected_texts(&editor), vec!["foo"]);
+
+    // Starting from the word under the cursor matches whole words only.
+    editor.execute_command(Command::AddNextOccurrence).unwrap();
+    assert_eq!(editor.cursor_count(), 2);
+    assert_eq!(editor.cursors()[1], CursorPosition::new(1, 8));
+
+    editor.execute_command(Command::SkipOccurrence).unwrap();
+    assert_eq!(editor.cursor_count(), 2);
+    assert_eq!(editor.cursors()[1], CursorPosition::new(2, 3));
+
+    // Wraps around to the start and stops once every match is selected.
+    editor.execute_command(Command::AddNextOccurrence).unwrap();
+    editor.execute_command(Command::AddNextOccurrence).unwrap();
+    assert_eq!(editor.cursor_count(), 3);
+    assert!(editor.status_message().contains("No more occurrences"));
+}
+
+#[test]
+fn test_add_next_occurrence_from_selection_respects_case_sensitivity() {
+    let mut editor = editor_with("ab Ab ab\n");
+    editor.execute_command(Command::SelectionStart).unwrap();
+    editor.execute_command(Command::MoveCursorRight).unwrap();
+    editor.execute_command(Command::MoveCursorRight).unwrap();
+    editor.execute_command(Command::SelectionEnd).unwrap();
+
+    editor.execute_command(Command::AddNextOccurrence).unwrap();
+    assert_eq!(editor.cursors()[1], CursorPosition::new(0, 8));
+
+    editor
+        .execute_command(Command::ClearSecondaryCursors)
+        .unwrap();
+    let mut options = editor.search_options();
+    options.case_sensitive = false;
+    editor.set_search_options(options);
+    editor
+        .execute_command(Command::SelectAllOccurrences)
+        .unwrap();
+    assert_eq!(editor.cursor_count(), 3);
+    assert_eq!(selected_texts(&editor), vec!["ab", "Ab", "ab"]);
+}
+
+#[test]
+fn test_typing_replaces_all_occurrences_in_one_undo_step() {
+    let mut editor = editor_with("let x = x + x;\n");
+    for _ in 0..4 {
+        editor.execute_command(Command::MoveCursorRight).unwrap();
+    }
+
+    editor
+        .execute_command(Command::SelectAllOccurrences)
+        .unwrap();
+    assert_eq!(editor.cursor_count(), 3);
+
+    editor.execute_command(Command::InsertChar('y')).unwrap();
+    editor.execute_command(Command::InsertChar('z')).unwrap();
+    assert_eq!(editor.current_buffer().content(), "let yz = yz + yz;\n");
+    assert_eq!(
+        editor.cursors(),
+        &[
+            CursorPosition::new(0, 6),
+            CursorPosition::new(0, 11),
+            CursorPosition::new(0, 16),
+        ]
+    );
+    assert!(editor.selections().is_empty());
+
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), "let x = x + x;\n");
+}
+
+#[test]
+fn test_split_selection_into_lines_gives_each_line_a_cursor() {
+    let mut editor = editor_with("one\ntwo\nthree\nfour\n");
+    editor.execute_command(Command::SelectionStart).unwrap();
+    editor.execute_command(Command::MoveCursorDown).unwrap();
+    editor.execute_command(Command::MoveCursorDown).unwrap();
+    editor.execute_command(Command::MoveCursorRight).unwrap();
+    editor.execute_command(Command::SelectionEnd).unwrap();
+
+    editor
+        .execute_command(Command::SplitSelectionIntoLines)
+        .unwrap();
+    assert_eq!(editor.cursor_count(), 3);
+    assert_eq!(selected_texts(&editor), vec!["one", "two", "t"]);
+
+    editor.execute_command(Command::InsertChar('-')).unwrap();
+    assert_eq!(editor.current_buffer().content(), "-\n-\n-hree\nfour\n");
+}
@@ -0,0 +1,230 @@
+#![cfg(unix)]
+
+use editor_core::{
+    Command, CursorPosition, EditorState, RemoteClient, RemoteServer, SessionManager,
+};
+use serde_json::{json, Value};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::UnixListener;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+// Runs the client on its own thread while this one answers its requests, as
+// a frontend's event loop would.
+fn with_client<T: Send + 'static>(
+    server: &mut RemoteServer,
+    editor: &mut EditorState,
+    run: impl FnOnce(&mut RemoteClient) -> T + Send + 'static,
+) -> T {
+    let socket_path = server.socket_path().to_path_buf();
+    let handle = thread::spawn(move || run(&mut RemoteClient::connect(&socket_path).unwrap()));
+    while !handle.is_finished() {
+        server.poll(editor);
+        thread::sleep(Duration::from_millis(5));
+    }
+    server.poll(editor);
+    handle.join().unwrap()
+}
+
+fn start(dir: &Path) -> (RemoteServer, EditorState) {
+    let server = RemoteServer::bind(dir.join("session-1.sock")).unwrap();
+    (server, EditorState::new())
+}
+
+#[test]
+fn test_remote_open_query_and_execute() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("notes.txt");
+    fs::write(&path, "one\ntwo\nthree\n").unwrap();
+    let (mut server, mut editor) = start(temp_dir.path());
+
+    let request_path = path.clone();
+    let (opened, buffers, contents) = with_client(&mut server, &mut editor, move |client| {
+        let opened = client
+            .request(
+                "open",
+                json!({ "path": request_path, "line": 1, "column": 2 }),
+            )
+            .unwrap();
+        client
+            .request("execute", json!({ "command": { "insert_char": "X" } }))
+            .unwrap();
+        let buffers = client.request("buffers", Value::Null).unwrap();
+        let contents = client.request("buffer", json!({})).unwrap();
+        (opened, buffers, contents)
+    });
+
+    assert_eq!(opened["cursor"], json!({ "line": 1, "column": 2 }));
+    assert_eq!(editor.file_path(), Some(path.as_path()));
+    assert_eq!(editor.current_buffer().content(), "one\ntwXo\nthree\n");
+    let buffers = buffers.as_array().unwrap();
+    assert_eq!(buffers.last().unwrap()["path"], json!(path));
+    assert_eq!(buffers.last().unwrap()["active"], json!(true));
+    assert_eq!(buffers.last().unwrap()["modified"], json!(true));
+    assert_eq!(contents["content"], json!("one\ntwXo\nthree\n"));
+    assert_eq!(contents["cursor"], json!({ "line": 1, "column": 3 }));
+
+    // Opening a file that is already open switches to it, and lines past
+    // the end land on the last one.
+    editor.execute_command(Command::New).unwrap();
+    let request_path = path.clone();
+    with_client(&mut server, &mut editor, move |client| {
+        client.open(&request_path, 99, 0).unwrap();
+    });
+    assert_eq!(editor.file_path(), Some(path.as_path()));
+    assert_eq!(*editor.cursor(), CursorPosition::new(3, 0));
+    assert_eq!(editor.current_buffer().content(), "one\ntwXo\nthree\n");
+}
+
+#[test]
+fn test_remote_errors_are_reported() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut server, mut editor) = start(temp_dir.path());
+    let missing = temp_dir.path().join("missing.txt");
+
+    let errors = with_client(&mut server, &mut editor, move |client| {
+        vec![
+            client.request("frobnicate", Value::Null).unwrap_err(),
+            client.request("open", json!({ "file": "x" })).unwrap_err(),
+            client
+                .request("execute", json!({ "command": { "goto_line": 99 } }))
+                .unwrap_err(),
+            client
+                .request("buffer", json!({ "path": missing }))
+                .unwrap_err(),
+            client
+                .request("open", json!({ "path": "relative.txt" }))
+                .unwrap_err(),
+        ]
+        .into_iter()
+        .map(|error| error.to_string())
+        .collect::<Vec<_>>()
+    });
+
+    assert!(
+        errors[0].contains("Unknown method 'frobnicate'"),
+        "{}",
+        errors[0]
+    );
+    assert!(errors[1].contains("Invalid params"), "{}", errors[1]);
+    assert!(errors[2].contains("line 99"), "{}", errors[2]);
+    assert!(errors[3].contains("is not open"), "{}", errors[3]);
+    assert!(errors[4].contains("not absolute"), "{}", errors[4]);
+}
+
+#[test]
+fn test_remote_subscribers_receive_events() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.txt");
+    let second = temp_dir.path().join("second.txt");
+    fs::write(&first, "alpha\n").unwrap();
+    fs::write(&second, "beta\n").unwrap();
+    let (mut server, mut editor) = start(temp_dir.path());
+    editor
+        .execute_command(Command::Open(first.clone()))
+        .unwrap();
+
+    let mut client = RemoteClient::connect(server.socket_path()).unwrap();
+    let socket_path = server.socket_path().to_path_buf();
+    let handle = thread::spawn(move || {
+        let mut subscriber = RemoteClient::connect(&socket_path).unwrap();
+        subscriber.request("subscribe", Value::Null).unwrap();
+        subscriber
+    });
+    while !handle.is_finished() {
+        server.poll(&mut editor);
+        thread::sleep(Duration::from_millis(5));
+    }
+    let mut subscriber = handle.join().unwrap();
+
+    editor.execute_command(Command::InsertChar('>')).unwrap();
+    editor.execute_command(Command::Save).unwrap();
+    editor
+        .execute_command(Command::Open(second.clone()))
+        .unwrap();
+    server.poll(&mut editor);
+
+    let mut events = Vec::new();
+    while let Some((method, params)) = subscriber
+        .next_notification(Duration::from_millis(500))
+        .unwrap()
+    {
+        events.push((method, params["path"].clone()));
+        if events.len() == 4 {
+            break;
+        }
+    }
+    assert_eq!(
+        events,
+        vec![
+            ("buffer_changed".to_string(), json!(first)),
+            ("buffer_saved".to_string(), json!(first)),
+            ("buffer_opened".to_string(), json!(second)),
+            ("active_buffer_changed".to_string(), json!(second)),
+        ]
+    );
+
+    // Connections that didn't subscribe hear nothing.
+    assert_eq!(
+        client.next_notification(Duration::from_millis(50)).unwrap(),
+        None
+    );
+    assert_eq!(server.connection_count(), 2);
+    drop(subscriber);
+    thread::sleep(Duration::from_millis(50));
+    server.poll(&mut editor);
+    assert_eq!(server.connection_count(), 1);
+}
+
+#[test]
+fn test_socket_is_private_from_the_start() {
+    let temp_dir = TempDir::new().unwrap();
+    let (server, _) = start(temp_dir.path());
+
+    let mode = fs::metadata(server.socket_path())
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o600);
+    let entries: Vec<_> = fs::read_dir(temp_dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name())
+        .collect();
+    assert_eq!(entries, vec!["session-1.sock"]);
+
+    assert!(RemoteServer::bind(server.socket_path().to_path_buf()).is_err());
+    assert!(RemoteClient::connect(server.socket_path()).is_ok());
+}
+
+#[test]
+fn test_running_instances_are_found_through_session_files() {
+    let temp_dir = TempDir::new().unwrap();
+    std::env::set_var("HOME", temp_dir.path());
+    assert!(SessionManager::running_instances().unwrap().is_empty());
+
+    let sessions = SessionManager::new().unwrap();
+    let socket_path = sessions.socket_path();
+    assert_eq!(socket_path.extension().unwrap(), "sock");
+    assert_eq!(socket_path.parent(), sessions.session_path().parent());
+
+    // A socket nobody listens on any more is skipped and tidied away.
+    let session_dir = socket_path.parent().unwrap().to_path_buf();
+    fs::create_dir_all(&session_dir).unwrap();
+    let stale = session_dir.join("session-0.sock");
+    drop(UnixListener::bind(&stale).unwrap());
+    assert!(RemoteClient::connect_to_running().unwrap().is_none());
+    assert!(!stale.exists());
+
+    let server = RemoteServer::bind(socket_path.clone()).unwrap();
+    assert_eq!(
+        SessionManager::running_instances().unwrap(),
+        vec![socket_path.clone()]
+    );
+    assert!(RemoteClient::connect_to_running().unwrap().is_some());
+
+    drop(server);
+    assert!(!socket_path.exists());
+}
@@ -0,0 +1,103 @@
+use editor_core::{BatchScript, Command, EditorState};
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_script_parses_a_command_list_or_an_object() {
+    let script = BatchScript::from_json_str(
+        r#"["move_to_end_of_line", {"insert_char": ";"}, {"sort_lines": {"numerical": true}}]"#,
+    )
+    .unwrap();
+    assert_eq!(
+        script,
+        BatchScript::new(vec![
+            Command::MoveToEndOfLine,
+            Command::InsertChar(';'),
+            Command::SortLines { numerical: true },
+        ])
+    );
+    assert!(script.save);
+
+    let script = BatchScript::from_json_str(
+        r#"{"files": ["a.txt"], "commands": ["trim_trailing_whitespace"], "save": false}"#,
+    )
+    .unwrap();
+    assert_eq!(script.files, vec![std::path::PathBuf::from("a.txt")]);
+    assert!(!script.save);
+
+    let round_trip = BatchScript::from_json_str(&script.to_json_string().unwrap()).unwrap();
+    assert_eq!(round_trip, script);
+
+    assert!(BatchScript::from_json_str(r#"["no_such_command"]"#).is_err());
+    assert!(BatchScript::from_json_str(r#"{"commands": [], "extra": 1}"#).is_err());
+    assert!(BatchScript::from_json_str(r#"{"files": []}"#).is_err());
+}
+
+#[test]
+fn test_script_runs_over_each_file_and_saves() {
+    let temp_dir = TempDir::new().unwrap();
+    let first = temp_dir.path().join("first.txt");
+    let second = temp_dir.path().join("second.txt");
+    fs::write(&first, "pear  \napple\n").unwrap();
+    fs::write(&second, "apple\t\n").unwrap();
+
+    let script = BatchScript::new(vec![
+        Command::TrimTrailingWhitespace,
+        Command::InsertChar('#'),
+        Command::ReplaceAll {
+            find: "apple".to_string(),
+            replace: "plum".to_string(),
+        },
+    ])
+    .with_files(vec![first.clone(), second.clone()]);
+
+    let mut editor = EditorState::new();
+    script.run(&mut editor).unwrap();
+    assert_eq!(fs::read_to_string(&first).unwrap(), "#pear\nplum\n");
+    assert_eq!(fs::read_to_string(&second).unwrap(), "#plum\n");
+}
+
+#[test]
+fn test_script_without_save_leaves_files_alone() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("notes.txt");
+    fs::write(&path, "b\na\n").unwrap();
+
+    let mut script = BatchScript::new(vec![Command::JoinLines]).with_files(vec![path.clone()]);
+    script.save = false;
+
+    let mut editor = EditorState::new();
+    script.run(&mut editor).unwrap();
+    assert_eq!(editor.current_buffer().content(), "b a\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "b\na\n");
+}
+
+#[test]
+fn test_script_stops_at_the_first_failure() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("notes.txt");
+    fs::write(&path, "one\n").unwrap();
+
+    let script = BatchScript::new(vec![
+        Command::InsertChar('x'),
+        Command::GotoLine(99),
+        Command::InsertChar('y'),
+    ])
+    .with_files(vec![path.clone()]);
+
+    let mut editor = EditorState::new();
+    let error = script.run(&mut editor).unwrap_err().to_string();
+    assert!(error.contains("notes.txt"), "{}", error);
+    assert!(error.contains("command 2 of 3"), "{}", error);
+    assert!(error.contains(r#"{"goto_line":99}"#), "{}", error);
+    assert_eq!(editor.current_buffer().content(), "xone\n");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "one\n");
+
+    let missing =
+        BatchScript::new(Vec::new()).with_files(vec![temp_dir.path().join("missing.txt")]);
+    let error = missing
+        .run(&mut EditorState::new())
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("missing.txt"), "{}", error);
+}
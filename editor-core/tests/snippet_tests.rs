@@ -0,0 +1,347 @@
+mod common;
+
+use common::{open, type_text};
+use editor_core::{
+    expand_snippet, parse_snippet, Command, CursorPosition, EditorState, SnippetOptions,
+    SnippetRegistry, SnippetTabstop,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+// Opens the file with the cursor at its end, where snippets get typed.
+fn open_at_end(dir: &Path, name: &str, content: &str) -> EditorState {
+    let mut editor = open(dir, name, content);
+    editor.execute_command(Command::MoveToEndOfFile).unwrap();
+    editor
+}
+
+fn with_snippet(editor: &mut EditorState, prefix: &str, body: &str) {
+    let mut snippets = SnippetRegistry::empty();
+    let json = serde_json::json!({ "Custom": { "prefix": prefix, "body": body } });
+    snippets.merge_json_str(&json.to_string(), None).unwrap();
+    editor.set_snippet_registry(snippets);
+}
+
+fn tabstop(index: usize, ranges: &[(usize, usize)]) -> SnippetTabstop {
+    SnippetTabstop {
+        index,
+        ranges: ranges.to_vec(),
+        choices: Vec::new(),
+    }
+}
+
+#[test]
+fn test_parse_tabstops_placeholders_and_mirrors() {
+    let snippet = parse_snippet("fn ${1:name}(${2:args}) -> $1 {$0}");
+    assert_eq!(snippet.text, "fn name(args) -> name {}");
+    assert_eq!(
+        snippet.tabstops,
+        vec![
+            tabstop(1, &[(3, 7), (17, 21)]),
+            tabstop(2, &[(8, 12)]),
+            tabstop(0, &[(23, 23)]),
+        ]
+    );
+
+    let snippet = parse_snippet("${1:outer ${2:inner}}$3");
+    assert_eq!(snippet.text, "outer inner");
+    assert_eq!(
+        snippet.tabstops,
+        vec![
+            tabstop(1, &[(0, 11)]),
+            tabstop(2, &[(6, 11)]),
+            tabstop(3, &[(11, 11)]),
+            tabstop(0, &[(11, 11)]),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_choices_escapes_and_variables() {
+    let snippet = parse_snippet("${1|one,two\\,three|} $0");
+    assert_eq!(snippet.text, "one ");
+    assert_eq!(snippet.tabstops[0].choices, vec!["one", "two,three"]);
+    assert_eq!(snippet.tabstops[0].ranges, vec![(0, 3)]);
+
+    let variable = |name: &str| (name == "TM_FILENAME").then(|| "main.rs".to_string());
+    let snippet = expand_snippet(
+        "\\$HOME $TM_FILENAME ${UNKNOWN:${1:fallback}} $ ${x",
+        &SnippetOptions {
+            variable: &variable,
+            ..SnippetOptions::default()
+        },
+    );
+    assert_eq!(snippet.text, "$HOME main.rs fallback $ ${x");
+    assert_eq!(snippet.tabstops[0].ranges, vec![(14, 22)]);
+}
+
+#[test]
+fn test_expansion_indents_following_lines() {
+    let snippet = expand_snippet(
+        "if ${1:cond} {\n\t$0\n}",
+        &SnippetOptions {
+            indent: "  ",
+            tab: "    ",
+            ..SnippetOptions::default()
+        },
+    );
+    assert_eq!(snippet.text, "if cond {\n      \n  }");
+    assert_eq!(snippet.tabstops[1].ranges, vec![(16, 16)]);
+}
+
+#[test]
+fn test_expand_by_prefix_and_walk_tabstops() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open_at_end(temp_dir.path(), "lib.rs", "");
+    type_text(&mut editor, "test");
+    editor.execute_command(Command::ExpandSnippet).unwrap();
+
+    assert_eq!(
+        editor.current_buffer().content(),
+        "#[test]\nfn name() {\n    \n}"
+    );
+    assert!(editor.is_snippet_active());
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 7));
+    let selection = editor.selection().unwrap();
+    assert_eq!(selection.start(), CursorPosition::new(1, 3));
+    assert_eq!(selection.end(), CursorPosition::new(1, 7));
+
+    type_text(&mut editor, "adds");
+    assert_eq!(
+        editor.current_buffer().content(),
+        "#[test]\nfn adds() {\n    \n}"
+    );
+    editor.execute_command(Command::NextTabstop).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 4));
+    assert!(!editor.is_snippet_active());
+}
+
+#[test]
+fn test_expansion_is_a_single_undo_entry() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open_at_end(temp_dir.path(), "lib.rs", "fn main() {\n    ");
+    type_text(&mut editor, "match");
+    editor.execute_command(Command::ExpandSnippet).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "fn main() {\n    match value {\n        pattern => ,\n    }"
+    );
+
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), "fn main() {\n    match");
+    assert!(!editor.is_snippet_active());
+    editor.execute_command(Command::Redo).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "fn main() {\n    match value {\n        pattern => ,\n    }"
+    );
+}
+
+#[test]
+fn test_mirrors_follow_typing_and_tabstops_track_edits() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open_at_end(temp_dir.path(), "lib.rs", "");
+    with_snippet(
+        &mut editor,
+        "st",
+        "struct ${1:Name};\nimpl $1 {\n\t${2:// body}\n}$0",
+    );
+    type_text(&mut editor, "st");
+    editor.execute_command(Command::ExpandSnippet).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "struct Name;\nimpl Name {\n    // body\n}"
+    );
+    assert_eq!(
+        editor.cursors(),
+        &[CursorPosition::new(0, 11), CursorPosition::new(1, 9)]
+    );
+
+    type_text(&mut editor, "Parser");
+    editor.execute_command(Command::Backspace).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "struct Parse;\nimpl Parse {\n    // body\n}"
+    );
+
+    editor.execute_command(Command::NextTabstop).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 11));
+    assert_eq!(
+        editor.selection().unwrap().start(),
+        CursorPosition::new(2, 4)
+    );
+    editor.execute_command(Command::Backspace).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "struct Parse;\nimpl Parse {\n    \n}"
+    );
+
+    editor.execute_command(Command::PreviousTabstop).unwrap();
+    assert_eq!(
+        editor.cursors(),
+        &[CursorPosition::new(0, 12), CursorPosition::new(1, 10)]
+    );
+    editor.execute_command(Command::NextTabstop).unwrap();
+    editor.execute_command(Command::NextTabstop).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(3, 1));
+    assert!(!editor.is_snippet_active());
+}
+
+#[test]
+fn test_choice_tabstop_opens_completion_popup() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open_at_end(temp_dir.path(), "lib.rs", "");
+    type_text(&mut editor, "derive");
+    editor.execute_command(Command::ExpandSnippet).unwrap();
+    assert_eq!(editor.current_buffer().content(), "#[derive(Debug)]");
+
+    let menu = editor.completion_menu().unwrap();
+    assert_eq!(menu.items().len(), 7);
+    assert_eq!(menu.selected_item().unwrap().label, "Debug");
+    editor.execute_command(Command::CompletionNext).unwrap();
+    editor.execute_command(Command::CompletionAccept).unwrap();
+    assert_eq!(editor.current_buffer().content(), "#[derive(Clone)]");
+    assert!(editor.is_snippet_active());
+
+    editor.execute_command(Command::NextTabstop).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 16));
+    assert!(!editor.is_snippet_active());
+}
+
+#[test]
+fn test_leaving_the_tabstop_ends_the_session() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open_at_end(temp_dir.path(), "lib.rs", "");
+    type_text(&mut editor, "fn");
+    editor.execute_command(Command::ExpandSnippet).unwrap();
+    editor.execute_command(Command::MoveCursorLeft).unwrap();
+    assert!(editor.is_snippet_active());
+    type_text(&mut editor, "x");
+    assert_eq!(editor.current_buffer().content(), "fn namxe() {\n    \n}");
+
+    editor.execute_command(Command::MoveToEndOfFile).unwrap();
+    assert!(!editor.is_snippet_active());
+    assert!(editor.execute_command(Command::NextTabstop).is_err());
+
+    editor.execute_command(Command::MoveToStartOfFile).unwrap();
+    type_text(&mut editor, "zz ");
+    editor.execute_command(Command::ExpandSnippet).unwrap();
+    assert_eq!(editor.status_message(), "No snippet prefix before cursor");
+    editor.execute_command(Command::MoveCursorLeft).unwrap();
+    editor.execute_command(Command::ExpandSnippet).unwrap();
+    assert_eq!(editor.status_message(), "No snippet for 'zz'");
+}
+
+#[test]
+fn test_expand_at_every_cursor() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open_at_end(temp_dir.path(), "lib.rs", "fn\n\nfn");
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(0, 2)))
+        .unwrap();
+    editor
+        .execute_command(Command::AddCursor(CursorPosition::new(2, 2)))
+        .unwrap();
+    editor.execute_command(Command::ExpandSnippet).unwrap();
+
+    type_text(&mut editor, "run");
+    assert_eq!(
+        editor.current_buffer().content(),
+        "fn run() {\n    \n}\n\nfn run() {\n    \n}"
+    );
+    editor.execute_command(Command::NextTabstop).unwrap();
+    assert_eq!(
+        editor.cursors(),
+        &[CursorPosition::new(0, 7), CursorPosition::new(4, 7)]
+    );
+    type_text(&mut editor, "x");
+    assert_eq!(
+        editor.current_buffer().content(),
+        "fn run(x) {\n    \n}\n\nfn run(x) {\n    \n}"
+    );
+}
+
+#[test]
+fn test_snippet_picker_and_completion_expand_tabstops() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open_at_end(temp_dir.path(), "lib.rs", "");
+    editor.execute_command(Command::InsertSnippet).unwrap();
+    assert!(editor.completion_menu().unwrap().items().len() >= 7);
+    type_text(&mut editor, "impltr");
+    assert_eq!(
+        editor
+            .completion_menu()
+            .unwrap()
+            .selected_item()
+            .unwrap()
+            .label,
+        "impltrait"
+    );
+    editor.execute_command(Command::CompletionAccept).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "impl Trait for Type {\n    \n}"
+    );
+    assert_eq!(
+        editor.selection().unwrap().start(),
+        CursorPosition::new(0, 5)
+    );
+
+    editor.execute_command(Command::ExitSnippet).unwrap();
+    assert!(!editor.is_snippet_active());
+
+    let mut editor = open_at_end(temp_dir.path(), "main.py", "");
+    type_text(&mut editor, "de");
+    editor.execute_command(Command::TriggerCompletion).unwrap();
+    assert_eq!(
+        editor
+            .completion_menu()
+            .unwrap()
+            .selected_item()
+            .unwrap()
+            .label,
+        "def"
+    );
+    editor.execute_command(Command::CompletionAccept).unwrap();
+    assert_eq!(editor.current_buffer().content(), "def name():\n    pass");
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 8));
+
+    let mut editor = open_at_end(temp_dir.path(), "notes.txt", "");
+    editor.set_snippet_registry(SnippetRegistry::empty());
+    editor.execute_command(Command::InsertSnippet).unwrap();
+    assert!(!editor.is_completion_open());
+    assert_eq!(editor.status_message(), "No snippets for text");
+}
+
+#[test]
+fn test_user_snippet_directory() {
+    let temp_dir = TempDir::new().unwrap();
+    fs::write(
+        temp_dir.path().join("rust.json"),
+        r#"{ "Print": { "prefix": "pl", "body": "println!(\"$1\");" } }"#,
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("shared.code-snippets"),
+        r#"{ "Todo": { "scope": "rust,python", "prefix": "todo", "body": "TODO: $0" } }"#,
+    )
+    .unwrap();
+    fs::write(temp_dir.path().join("README.md"), "ignored").unwrap();
+
+    let mut snippets = SnippetRegistry::builtin().clone();
+    snippets.merge_dir(temp_dir.path()).unwrap();
+    assert_eq!(
+        snippets.find_by_prefix("rust", "pl").unwrap().body,
+        "println!(\"$1\");"
+    );
+    assert!(snippets.find_by_prefix("python", "pl").is_none());
+    assert!(snippets.find_by_prefix("python", "todo").is_some());
+    assert!(snippets.find_by_prefix("rust", "test").is_some());
+
+    fs::write(temp_dir.path().join("broken.json"), "{").unwrap();
+    let error = SnippetRegistry::empty()
+        .merge_dir(temp_dir.path())
+        .unwrap_err();
+    assert!(error.to_string().contains("broken.json"));
+}
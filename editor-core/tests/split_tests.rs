@@ -0,0 +1,199 @@
+mod common;
+
+use common::open;
+use editor_core::{
+    Command, CommandAction, CommandRegistry, CursorPosition, EditorState, PaneDirection, Session,
+    SplitDirection, SplitLayout, SplitRect,
+};
+use std::fs;
+use tempfile::TempDir;
+
+fn split(editor: &mut EditorState, direction: SplitDirection) {
+    editor
+        .execute_command(Command::Split { direction })
+        .unwrap();
+}
+
+fn focus(editor: &mut EditorState, direction: PaneDirection) {
+    editor
+        .execute_command(Command::FocusSplit { direction })
+        .unwrap();
+}
+
+fn pane_cursor(editor: &mut EditorState, pane: usize) -> CursorPosition {
+    editor.with_split_view(pane, |editor| *editor.cursor())
+}
+
+#[test]
+fn test_layout_splits_focus_and_closes() {
+    let mut layout = SplitLayout::new();
+    assert!(!layout.is_split());
+    let right = layout.split(SplitDirection::Vertical);
+    layout.focus(right).unwrap();
+    let bottom = layout.split(SplitDirection::Horizontal);
+    layout.focus(bottom).unwrap();
+
+    assert_eq!(layout.pane_ids(), vec![0, right, bottom]);
+    let rects = layout.pane_rects();
+    assert_eq!(
+        rects[2].1,
+        SplitRect {
+            x: 0.5,
+            y: 0.5,
+            width: 0.5,
+            height: 0.5
+        }
+    );
+    assert_eq!(layout.neighbor(PaneDirection::Left), Some(0));
+    assert_eq!(layout.neighbor(PaneDirection::Up), Some(right));
+    assert_eq!(layout.neighbor(PaneDirection::Right), None);
+    assert_eq!(layout.next_pane(), 0);
+
+    assert!(layout.resize(SplitDirection::Horizontal, 0.2));
+    assert_eq!(layout.pane_rects()[2].1.y, 0.3);
+    assert!(layout.resize(SplitDirection::Vertical, 0.6));
+    assert_eq!(layout.pane_rects()[0].1.width, 0.1);
+
+    layout.swap(bottom, 0);
+    assert_eq!(layout.pane_ids(), vec![bottom, right, 0]);
+
+    assert_eq!(layout.close(bottom).unwrap(), right);
+    assert_eq!(layout.pane_ids(), vec![right, 0]);
+    assert_eq!(layout.close(0).unwrap(), right);
+    assert!(layout.close(right).is_err());
+}
+
+#[test]
+fn test_splits_of_one_buffer_keep_their_own_cursors() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "notes.txt", "one\ntwo\nthree\n");
+    editor.execute_command(Command::GotoLine(2)).unwrap();
+    split(&mut editor, SplitDirection::Vertical);
+    assert_eq!(editor.split_layout().pane_count(), 2);
+    assert_eq!(editor.focused_split(), 1);
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 0));
+
+    editor.execute_command(Command::MoveToStartOfFile).unwrap();
+    for c in "zero\n".chars() {
+        editor.execute_command(Command::InsertChar(c)).unwrap();
+    }
+    assert_eq!(pane_cursor(&mut editor, 0), CursorPosition::new(3, 0));
+    assert_eq!(
+        editor.with_split_view(0, |editor| editor.current_buffer().content()),
+        "zero\none\ntwo\nthree\n"
+    );
+
+    focus(&mut editor, PaneDirection::Left);
+    assert_eq!(editor.focused_split(), 0);
+    assert_eq!(*editor.cursor(), CursorPosition::new(3, 0));
+    editor.execute_command(Command::InsertChar('>')).unwrap();
+    assert_eq!(pane_cursor(&mut editor, 1), CursorPosition::new(1, 0));
+    focus(&mut editor, PaneDirection::Left);
+    assert_eq!(editor.focused_split(), 0);
+
+    editor.execute_command(Command::FocusNextSplit).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 0));
+    assert_eq!(
+        editor.current_buffer().content(),
+        "zero\none\ntwo\n>three\n"
+    );
+}
+
+#[test]
+fn test_split_shows_other_buffer_and_closes() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "a.txt", "alpha\n");
+    fs::write(temp_dir.path().join("b.txt"), "beta\n").unwrap();
+    split(&mut editor, SplitDirection::Horizontal);
+    editor
+        .execute_command(Command::Open(temp_dir.path().join("b.txt")))
+        .unwrap();
+
+    let name = |editor: &EditorState, pane| {
+        editor
+            .split_buffer(pane)
+            .and_then(|buffer| buffer.file_path())
+            .map(|path| path.file_name().unwrap().to_string_lossy().to_string())
+    };
+    assert_eq!(name(&editor, 0).as_deref(), Some("a.txt"));
+    assert_eq!(name(&editor, 1).as_deref(), Some("b.txt"));
+    assert_eq!(editor.current_buffer().content(), "beta\n");
+
+    editor.execute_command(Command::SwapSplit).unwrap();
+    assert_eq!(editor.split_layout().pane_ids(), vec![1, 0]);
+    assert_eq!(editor.focused_split(), 1);
+
+    editor.execute_command(Command::CloseSplit).unwrap();
+    assert!(!editor.split_layout().is_split());
+    assert_eq!(editor.current_buffer().content(), "alpha\n");
+    assert!(editor.execute_command(Command::CloseSplit).is_err());
+
+    editor
+        .execute_command(Command::ResizeSplit {
+            direction: SplitDirection::Vertical,
+            delta: 5,
+        })
+        .unwrap();
+    assert_eq!(editor.status_message(), "No split to resize that way");
+}
+
+#[test]
+fn test_split_commands_are_available_only_with_splits() {
+    let registry = CommandRegistry::builtin();
+    let mut editor = EditorState::new();
+    let available = |editor: &EditorState, id: &str| {
+        registry.available(editor).iter().any(|spec| spec.id == id)
+    };
+    assert!(available(&editor, "view.split_right"));
+    assert!(!available(&editor, "view.close_split"));
+
+    split(&mut editor, SplitDirection::Vertical);
+    assert!(available(&editor, "view.close_split"));
+    assert!(matches!(
+        registry.get("view.focus_split_left").unwrap().resolve(&[]),
+        Ok(CommandAction::Execute(Command::FocusSplit {
+            direction: PaneDirection::Left
+        }))
+    ));
+}
+
+#[test]
+fn test_layout_persists_in_session() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open(temp_dir.path(), "a.txt", "a1\na2\na3\n");
+    let b_path = temp_dir.path().join("b.txt");
+    fs::write(&b_path, "b1\nb2\n").unwrap();
+    editor.execute_command(Command::GotoLine(1)).unwrap();
+    split(&mut editor, SplitDirection::Vertical);
+    editor.execute_command(Command::Open(b_path)).unwrap();
+    editor.execute_command(Command::MoveToEndOfFile).unwrap();
+    editor
+        .execute_command(Command::ResizeSplit {
+            direction: SplitDirection::Vertical,
+            delta: 10,
+        })
+        .unwrap();
+
+    let mut session = Session::new();
+    editor.save_session_state(&mut session);
+    let session_path = temp_dir.path().join("session.toml");
+    session.save_to_file(&session_path).unwrap();
+    let session = Session::load_from_file(&session_path).unwrap();
+    let layout = session.layout.clone().unwrap();
+    assert_eq!(layout.panes.len(), 2);
+
+    let mut restored = EditorState::new();
+    restored.restore_split_layout(&layout).unwrap();
+    assert_eq!(restored.split_layout(), editor.split_layout());
+    assert_eq!(restored.current_buffer().content(), "b1\nb2\n");
+    assert_eq!(*restored.cursor(), CursorPosition::new(2, 0));
+    assert_eq!(pane_cursor(&mut restored, 0), CursorPosition::new(1, 0));
+    assert_eq!(
+        restored.with_split_view(0, |editor| editor.current_buffer().content()),
+        "a1\na2\na3\n"
+    );
+
+    let mut single = Session::new();
+    EditorState::new().save_session_state(&mut single);
+    assert!(single.layout.is_none());
+}
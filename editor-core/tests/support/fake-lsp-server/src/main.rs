@@ -0,0 +1,319 @@
+// A tiny language server for the integration tests. It keeps its own copy of
+// every open document (applying incremental changes by their UTF-16 ranges),
+// mirrors that copy into the log directory given as its first argument, and
+// answers requests with simple word-based results:
+//
+// - diagnostics: a warning for every `TODO` and an error for every `FIXME`
+// - completion: every word in the document
+// - hover: the word under the cursor
+// - definition: the word's first occurrence
+// - references and rename: every occurrence of the word
+// - formatting: trailing whitespace removed
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+fn read_message(reader: &mut impl BufRead) -> Option<Value> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).ok()? == 0 {
+            return None;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let mut body = vec![0; length?];
+    reader.read_exact(&mut body).ok()?;
+    serde_json::from_slice(&body).ok()
+}
+
+fn write_message(value: &Value) {
+    let body = value.to_string();
+    let mut stdout = io::stdout().lock();
+    let _ = write!(stdout, "Content-Length: {}\r\n\r\n{}", body.len(), body);
+    let _ = stdout.flush();
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn utf16_to_char(line: &str, character: u64) -> usize {
+    let mut units = 0;
+    for (index, c) in line.chars().enumerate() {
+        if units >= character as usize {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+    line.chars().count()
+}
+
+fn char_to_utf16(line: &str, column: usize) -> usize {
+    line.chars().take(column).map(char::len_utf16).sum()
+}
+
+fn offset_of(text: &str, position: &Value) -> usize {
+    let line = position["line"].as_u64().unwrap_or(0) as usize;
+    let character = position["character"].as_u64().unwrap_or(0);
+    let mut offset = 0;
+    for (index, content) in text.split('\n').enumerate() {
+        if index == line {
+            let column = utf16_to_char(content, character);
+            return offset
+                + content
+                    .chars()
+                    .take(column)
+                    .map(char::len_utf8)
+                    .sum::<usize>();
+        }
+        offset += content.len() + 1;
+    }
+    text.len()
+}
+
+fn range(line: usize, text: &str, start: usize, end: usize) -> Value {
+    json!({
+        "start": { "line": line, "character": char_to_utf16(text, start) },
+        "end": { "line": line, "character": char_to_utf16(text, end) },
+    })
+}
+
+// Every whole-word occurrence of `word` as (line, start column, end column).
+fn occurrences(text: &str, word: &str) -> Vec<(usize, usize, usize)> {
+    let target: Vec<char> = word.chars().collect();
+    let mut found = Vec::new();
+    for (line_index, line) in text.split('\n').enumerate() {
+        let chars: Vec<char> = line.chars().collect();
+        let mut i = 0;
+        while i + target.len() <= chars.len() {
+            let before = i == 0 || !is_word_char(chars[i - 1]);
+            let after = chars
+                .get(i + target.len())
+                .is_none_or(|c| !is_word_char(*c));
+            if before && after && chars[i..i + target.len()] == target[..] {
+                found.push((line_index, i, i + target.len()));
+                i += target.len();
+            } else {
+                i += 1;
+            }
+        }
+    }
+    found
+}
+
+fn word_at(text: &str, position: &Value) -> Option<String> {
+    let line_index = position["line"].as_u64()? as usize;
+    let line = text.split('\n').nth(line_index)?;
+    let chars: Vec<char> = line.chars().collect();
+    let column = utf16_to_char(line, position["character"].as_u64()?);
+    let mut start = column.min(chars.len());
+    while start > 0 && is_word_char(chars[start - 1]) {
+        start -= 1;
+    }
+    let mut end = column.min(chars.len());
+    while end < chars.len() && is_word_char(chars[end]) {
+        end += 1;
+    }
+    (start < end).then(|| chars[start..end].iter().collect())
+}
+
+fn words(text: &str) -> Vec<String> {
+    let mut words: Vec<String> = text
+        .split(|c: char| !is_word_char(c))
+        .filter(|word| word.chars().count() > 1)
+        .map(str::to_string)
+        .collect();
+    words.sort();
+    words.dedup();
+    words
+}
+
+fn diagnostics(text: &str) -> Vec<Value> {
+    let mut diagnostics = Vec::new();
+    for (marker, severity, message) in [("TODO", 2, "unfinished work"), ("FIXME", 1, "broken code")]
+    {
+        for (line, start, end) in occurrences(text, marker) {
+            let content = text.split('\n').nth(line).unwrap_or_default();
+            diagnostics.push(json!({
+                "range": range(line, content, start, end),
+                "severity": severity,
+                "message": message,
+                "source": "fake",
+            }));
+        }
+    }
+    diagnostics
+}
+
+struct Server {
+    log_dir: PathBuf,
+    documents: HashMap<String, String>,
+}
+
+impl Server {
+    fn document_changed(&self, uri: &str) {
+        let text = &self.documents[uri];
+        if let Some(name) = uri.rsplit('/').next() {
+            let _ = std::fs::write(self.log_dir.join(name), text);
+        }
+        write_message(&json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics(text) },
+        }));
+    }
+
+    fn locations(&self, uri: &str, word: &str) -> Vec<Value> {
+        let text = &self.documents[uri];
+        occurrences(text, word)
+            .into_iter()
+            .map(|(line, start, end)| {
+                let content = text.split('\n').nth(line).unwrap_or_default();
+                json!({ "uri": uri, "range": range(line, content, start, end) })
+            })
+            .collect()
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) {
+        match method {
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                let uri = document["uri"].as_str().unwrap_or_default().to_string();
+                let text = document["text"].as_str().unwrap_or_default().to_string();
+                self.documents.insert(uri.clone(), text);
+                self.document_changed(&uri);
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                let Some(text) = self.documents.get_mut(uri) else {
+                    return;
+                };
+                for change in params["contentChanges"].as_array().into_iter().flatten() {
+                    let new_text = change["text"].as_str().unwrap_or_default();
+                    match change.get("range") {
+                        Some(range) => {
+                            let start = offset_of(text, &range["start"]);
+                            let end = offset_of(text, &range["end"]);
+                            text.replace_range(start..end, new_text);
+                        }
+                        None => *text = new_text.to_string(),
+                    }
+                }
+                let uri = uri.to_string();
+                self.document_changed(&uri);
+            }
+            "textDocument/didClose" => {
+                let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+                self.documents.remove(uri);
+            }
+            "exit" => std::process::exit(0),
+            _ => {}
+        }
+    }
+
+    fn handle_request(&mut self, method: &str, params: &Value) -> Value {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let text = self.documents.get(uri).cloned().unwrap_or_default();
+        let word = word_at(&text, &params["position"]);
+
+        match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": { "openClose": true, "change": 2, "save": {} },
+                    "completionProvider": {},
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "renameProvider": true,
+                    "documentFormattingProvider": true,
+                },
+            }),
+            "textDocument/completion" => json!({
+                "isIncomplete": false,
+                "items": words(&text)
+                    .into_iter()
+                    .map(|word| json!({ "label": word, "kind": 6, "detail": "word" }))
+                    .collect::<Vec<_>>(),
+            }),
+            "textDocument/hover" => match word {
+                Some(word) => {
+                    json!({ "contents": { "kind": "plaintext", "value": format!("word `{}`", word) } })
+                }
+                None => Value::Null,
+            },
+            "textDocument/definition" => match word {
+                Some(word) => self
+                    .locations(uri, &word)
+                    .into_iter()
+                    .next()
+                    .unwrap_or(Value::Null),
+                None => Value::Null,
+            },
+            "textDocument/references" => match word {
+                Some(word) => json!(self.locations(uri, &word)),
+                None => Value::Null,
+            },
+            "textDocument/rename" => {
+                let Some(word) = word else {
+                    return Value::Null;
+                };
+                let new_name = params["newName"].as_str().unwrap_or_default();
+                let edits: Vec<Value> = self
+                    .locations(uri, &word)
+                    .into_iter()
+                    .map(|location| json!({ "range": location["range"], "newText": new_name }))
+                    .collect();
+                json!({ "changes": { uri: edits } })
+            }
+            "textDocument/formatting" => {
+                let edits: Vec<Value> = text
+                    .split('\n')
+                    .enumerate()
+                    .filter_map(|(line, content)| {
+                        let trimmed = content.trim_end().chars().count();
+                        let length = content.chars().count();
+                        (trimmed < length).then(|| {
+                            json!({ "range": range(line, content, trimmed, length), "newText": "" })
+                        })
+                    })
+                    .collect();
+                json!(edits)
+            }
+            _ => Value::Null,
+        }
+    }
+}
+
+fn main() {
+    let log_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_default();
+    let mut server = Server {
+        log_dir,
+        documents: HashMap::new(),
+    };
+    let mut reader = BufReader::new(io::stdin().lock());
+
+    while let Some(message) = read_message(&mut reader) {
+        let method = message["method"].as_str().unwrap_or_default().to_string();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        match message.get("id") {
+            Some(id) if !method.is_empty() => {
+                let result = server.handle_request(&method, &params);
+                write_message(&json!({ "jsonrpc": "2.0", "id": id, "result": result }));
+            }
+            Some(_) => {}
+            None => server.handle_notification(&method, &params),
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use editor_core::{Command, CursorPosition, DiagnosticSeverity, EditorState, LanguageRegistry};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+const WAIT_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn fake_language(log_dir: &Path) -> LanguageRegistry {
+    let mut registry = LanguageRegistry::builtin().clone();
+    registry
+        .merge_toml_str(&format!(
+            "[[language]]\nname = \"fake\"\nextensions = [\"fake\"]\nlanguage_server = {{ command = {:?}, args = [{:?}] }}\n",
+            env!("CARGO_BIN_EXE_fake-lsp-server"),
+            log_dir.to_string_lossy(),
+        ))
+        .unwrap();
+    registry
+}
+
+fn wait_until(editor: &mut EditorState, condition: impl Fn(&EditorState) -> bool) {
+    let started = Instant::now();
+    while !condition(editor) {
+        assert!(
+            started.elapsed() < WAIT_TIMEOUT,
+            "timed out waiting for the language server ({})",
+            editor.status_message()
+        );
+        editor.wait_for_language_servers(Duration::from_millis(50));
+    }
+}
+
+fn open(dir: &Path, name: &str, content: &str) -> (EditorState, PathBuf) {
+    let log_dir = dir.join("server");
+    fs::create_dir_all(&log_dir).unwrap();
+    let path = dir.join(name);
+    fs::write(&path, content).unwrap();
+
+    let mut editor = EditorState::new();
+    editor.set_language_registry(fake_language(&log_dir));
+    editor.execute_command(Command::Open(path.clone())).unwrap();
+    wait_until(&mut editor, |editor| {
+        editor
+            .language_server("fake")
+            .is_some_and(|client| client.document(&path).is_some())
+    });
+    (editor, log_dir.join(name))
+}
+
+fn wait_for_server_copy(editor: &mut EditorState, server_copy: &Path) {
+    wait_until(editor, |editor| {
+        fs::read_to_string(server_copy).ok() == Some(editor.current_buffer().content())
+    });
+}
+
+fn click(editor: &mut EditorState, line: usize, column: usize) {
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(line, column)))
+        .unwrap();
+}
+
+#[test]
+fn test_incremental_changes_keep_server_in_sync() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut editor, server_copy) = open(temp_dir.path(), "notes.fake", "alpha 😀 beta\ngamma\n");
+    wait_for_server_copy(&mut editor, &server_copy);
+
+    click(&mut editor, 0, 8);
+    for c in "😀x".chars() {
+        editor.execute_command(Command::InsertChar(c)).unwrap();
+    }
+    editor.execute_command(Command::NewLine).unwrap();
+    editor.execute_command(Command::Backspace).unwrap();
+    editor.execute_command(Command::MoveToEndOfFile).unwrap();
+    editor.execute_command(Command::InsertChar('z')).unwrap();
+    wait_for_server_copy(&mut editor, &server_copy);
+    assert_eq!(
+        editor.current_buffer().content(),
+        "alpha 😀 😀xbeta\ngamma\nz"
+    );
+
+    editor.execute_command(Command::Undo).unwrap();
+    wait_for_server_copy(&mut editor, &server_copy);
+
+    click(&mut editor, 0, 0);
+    editor.execute_command(Command::DeleteLine).unwrap();
+    wait_for_server_copy(&mut editor, &server_copy);
+}
+
+#[test]
+fn test_diagnostics_are_shown_and_navigable() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut editor, _) = open(
+        temp_dir.path(),
+        "notes.fake",
+        "ok\n// TODO later\nfine\nFIXME now\n",
+    );
+    wait_until(&mut editor, |editor| editor.diagnostics().len() == 2);
+
+    assert_eq!(editor.diagnostic_counts(), (1, 1));
+    assert_eq!(
+        editor.line_diagnostic_severity(1),
+        Some(DiagnosticSeverity::Warning)
+    );
+    assert_eq!(editor.line_diagnostic_severity(2), None);
+    assert_eq!(
+        editor.line_diagnostics(3),
+        vec![(0, 5, DiagnosticSeverity::Error)]
+    );
+
+    editor.execute_command(Command::NextDiagnostic).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 3));
+    assert_eq!(
+        editor.diagnostic_at_cursor().unwrap().summary(),
+        "warning: unfinished work [fake]"
+    );
+    editor.execute_command(Command::NextDiagnostic).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(3, 0));
+    editor.execute_command(Command::NextDiagnostic).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 3));
+    editor.execute_command(Command::PreviousDiagnostic).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(3, 0));
+
+    editor.execute_command(Command::DeleteLine).unwrap();
+    wait_until(&mut editor, |editor| editor.diagnostics().len() == 1);
+    assert_eq!(editor.diagnostic_counts(), (0, 1));
+}
+
+#[test]
+fn test_completion_from_language_server() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut editor, server_copy) = open(temp_dir.path(), "notes.fake", "counter compute\nco");
+    editor.execute_command(Command::MoveToEndOfFile).unwrap();
+    editor.execute_command(Command::TriggerCompletion).unwrap();
+    // Buffer words open the menu straight away; the server's items follow.
+    wait_until(&mut editor, |editor| {
+        editor.completion_menu().is_some_and(|menu| {
+            menu.items()
+                .iter()
+                .any(|entry| entry.item.kind.as_deref() == Some("var"))
+        })
+    });
+
+    let labels: Vec<&str> = editor
+        .completion_menu()
+        .unwrap()
+        .items()
+        .iter()
+        .map(|entry| entry.item.label.as_str())
+        .collect();
+    assert_eq!(labels, vec!["co", "compute", "counter"]);
+
+    editor.execute_command(Command::InsertChar('u')).unwrap();
+    let menu = editor.completion_menu().unwrap();
+    assert_eq!(menu.query(), "cou");
+    assert_eq!(menu.selected_item().unwrap().label, "counter");
+    assert_eq!(menu.selected_item().unwrap().kind.as_deref(), Some("var"));
+
+    editor.execute_command(Command::CompletionAccept).unwrap();
+    assert!(!editor.is_completion_open());
+    assert_eq!(
+        editor.current_buffer().content(),
+        "counter compute\ncounter"
+    );
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 7));
+    wait_for_server_copy(&mut editor, &server_copy);
+
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(editor.current_buffer().content(), "counter compute\ncou");
+}
+
+#[test]
+fn test_hover_definition_and_references() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut editor, _) = open(
+        temp_dir.path(),
+        "notes.fake",
+        "let total = 1\nprint total\ntotal += 2\n",
+    );
+
+    click(&mut editor, 2, 1);
+    editor.execute_command(Command::ShowHover).unwrap();
+    wait_until(&mut editor, |editor| editor.hover_text().is_some());
+    assert_eq!(editor.hover_text(), Some("word `total`"));
+    editor.execute_command(Command::MoveCursorRight).unwrap();
+    assert_eq!(editor.hover_text(), None);
+
+    editor.execute_command(Command::GotoDefinition).unwrap();
+    wait_until(&mut editor, |editor| editor.cursor().line == 0);
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 4));
+
+    editor.execute_command(Command::FindReferences).unwrap();
+    wait_until(&mut editor, |editor| !editor.references().is_empty());
+    assert_eq!(editor.references().len(), 3);
+    assert_eq!(editor.status_message(), "Reference 1 of 3");
+
+    editor.execute_command(Command::NextReference).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 6));
+    editor.execute_command(Command::NextReference).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(2, 0));
+    editor.execute_command(Command::NextReference).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 4));
+    editor.execute_command(Command::PreviousReference).unwrap();
+    assert_eq!(editor.status_message(), "Reference 3 of 3");
+}
+
+#[test]
+fn test_rename_and_format_apply_server_edits() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut editor, server_copy) = open(
+        temp_dir.path(),
+        "notes.fake",
+        "let total = 1   \nprint total\t\ntotal += 2\n",
+    );
+
+    click(&mut editor, 1, 8);
+    editor
+        .execute_command(Command::RenameSymbol("sum".to_string()))
+        .unwrap();
+    wait_until(&mut editor, |editor| {
+        editor.current_buffer().content().contains("sum")
+    });
+    assert_eq!(
+        editor.current_buffer().content(),
+        "let sum = 1   \nprint sum\t\nsum += 2\n"
+    );
+    assert_eq!(editor.status_message(), "Renamed 3 occurrences in 1 file");
+    wait_for_server_copy(&mut editor, &server_copy);
+
+    editor.execute_command(Command::FormatDocument).unwrap();
+    wait_until(&mut editor, |editor| {
+        !editor.current_buffer().content().contains('\t')
+    });
+    assert_eq!(
+        editor.current_buffer().content(),
+        "let sum = 1\nprint sum\nsum += 2\n"
+    );
+
+    editor.execute_command(Command::Undo).unwrap();
+    assert_eq!(
+        editor.current_buffer().content(),
+        "let sum = 1   \nprint sum\t\nsum += 2\n"
+    );
+
+    assert!(editor
+        .execute_command(Command::RenameSymbol("  ".to_string()))
+        .is_err());
+}
+
+#[test]
+fn test_restart_and_missing_servers() {
+    let temp_dir = TempDir::new().unwrap();
+    let (mut editor, _) = open(temp_dir.path(), "notes.fake", "TODO\n");
+    wait_until(&mut editor, |editor| !editor.diagnostics().is_empty());
+
+    editor
+        .execute_command(Command::RestartLanguageServer)
+        .unwrap();
+    assert!(editor.diagnostics().is_empty());
+    let path = editor.current_buffer().file_path().cloned().unwrap();
+    wait_until(&mut editor, |editor| {
+        editor
+            .language_server("fake")
+            .is_some_and(|client| client.document(&path).is_some())
+            && !editor.diagnostics().is_empty()
+    });
+
+    let plain = temp_dir.path().join("plain.txt");
+    fs::write(&plain, "words\n").unwrap();
+    editor.execute_command(Command::Open(plain)).unwrap();
+    assert!(editor.execute_command(Command::ShowHover).is_err());
+    assert!(editor
+        .execute_command(Command::RestartLanguageServer)
+        .is_err());
+
+    let mut registry = LanguageRegistry::builtin().clone();
+    registry
+        .merge_toml_str(
+            "[[language]]\nname = \"fake\"\nextensions = [\"fake\"]\nlanguage_server = { command = \"/nonexistent/language-server\" }\n",
+        )
+        .unwrap();
+    let mut editor = EditorState::new();
+    editor.set_language_registry(registry);
+    let broken = temp_dir.path().join("broken.fake");
+    fs::write(&broken, "x\n").unwrap();
+    editor.execute_command(Command::Open(broken)).unwrap();
+    assert!(
+        editor
+            .status_message()
+            .contains("Failed to start language server"),
+        "{}",
+        editor.status_message()
+    );
+    assert!(!editor.has_language_servers());
+}
@@ -0,0 +1,356 @@
+mod common;
+
+use common::open;
+use editor_core::{
+    pattern_symbols, Command, CommandAction, CommandRegistry, CursorPosition, EditorState,
+    LanguageRegistry, SymbolIndex, SymbolKind, SymbolOutline, SymbolPattern, SymbolPicker,
+    SymbolScope,
+};
+use ropey::Rope;
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+const RUST: &str = "mod shapes {\n    pub struct Circle {\n        r: f64,\n    }\n\n    pub trait Area {\n        fn area(&self) -> f64;\n    }\n\n    impl Area for Circle {\n        fn area(&self) -> f64 {\n            3.14 * self.r * self.r\n        }\n    }\n}\n\nenum Color {\n    Red,\n}\n\nfn main() {\n    let c = make_circle();\n}\n";
+
+const PYTHON: &str = "class Greeter:\n    def __init__(self, name):\n        self.name = name\n\n    def greet(self):\n        return helper(self.name)\n\n\ndef helper(name):\n    return 'hi ' + name\n";
+
+const MARKDOWN: &str =
+    "# Guide\n\nIntro.\n\n## Install\n\nSteps.\n\n## Usage\n\nMore.\n\n# Appendix\n";
+
+// Opens the file with the project symbol index kept inside `dir`.
+fn open_indexed(dir: &Path, name: &str, content: &str) -> EditorState {
+    let mut editor = open(dir, name, content);
+    editor.set_symbol_index_path(dir.join(".index").join("symbols.json"));
+    editor
+}
+
+fn summary(editor: &EditorState) -> Vec<(SymbolKind, String, usize, usize)> {
+    editor
+        .document_symbols()
+        .into_iter()
+        .map(|symbol| (symbol.kind, symbol.name, symbol.line, symbol.depth))
+        .collect()
+}
+
+#[test]
+fn test_rust_symbols_from_syntax_tree() {
+    let temp_dir = TempDir::new().unwrap();
+    let editor = open_indexed(temp_dir.path(), "main.rs", RUST);
+
+    assert_eq!(
+        summary(&editor),
+        vec![
+            (SymbolKind::Module, "shapes".to_string(), 0, 0),
+            (SymbolKind::Struct, "Circle".to_string(), 1, 1),
+            (SymbolKind::Trait, "Area".to_string(), 5, 1),
+            (SymbolKind::Method, "area".to_string(), 6, 2),
+            (SymbolKind::Impl, "Area for Circle".to_string(), 9, 1),
+            (SymbolKind::Method, "area".to_string(), 10, 2),
+            (SymbolKind::Enum, "Color".to_string(), 16, 0),
+            (SymbolKind::Function, "main".to_string(), 20, 0),
+        ]
+    );
+
+    let symbols = editor.document_symbols();
+    assert_eq!(symbols[1].column, 15);
+    assert_eq!(symbols[4].end_line, 13);
+}
+
+#[test]
+fn test_python_and_markdown_symbols() {
+    let temp_dir = TempDir::new().unwrap();
+    let python = open_indexed(temp_dir.path(), "greet.py", PYTHON);
+    assert_eq!(
+        summary(&python),
+        vec![
+            (SymbolKind::Class, "Greeter".to_string(), 0, 0),
+            (SymbolKind::Method, "__init__".to_string(), 1, 1),
+            (SymbolKind::Method, "greet".to_string(), 4, 1),
+            (SymbolKind::Function, "helper".to_string(), 8, 0),
+        ]
+    );
+
+    let markdown = open_indexed(temp_dir.path(), "guide.md", MARKDOWN);
+    assert_eq!(
+        summary(&markdown),
+        vec![
+            (SymbolKind::Heading, "Guide".to_string(), 0, 0),
+            (SymbolKind::Heading, "Install".to_string(), 4, 1),
+            (SymbolKind::Heading, "Usage".to_string(), 8, 1),
+            (SymbolKind::Heading, "Appendix".to_string(), 12, 0),
+        ]
+    );
+}
+
+#[test]
+fn test_regex_fallback_for_languages_without_grammar() {
+    let temp_dir = TempDir::new().unwrap();
+    let go = open_indexed(
+        temp_dir.path(),
+        "main.go",
+        "package main\n\ntype Server struct {\n    port int\n}\n\nfunc (s *Server) Start() error {\n    return nil\n}\n\nfunc main() {\n}\n",
+    );
+    let names: Vec<_> = go
+        .document_symbols()
+        .into_iter()
+        .map(|symbol| (symbol.kind, symbol.name))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            (SymbolKind::Struct, "Server".to_string()),
+            (SymbolKind::Function, "Start".to_string()),
+            (SymbolKind::Function, "main".to_string()),
+        ]
+    );
+
+    let javascript = open_indexed(
+        temp_dir.path(),
+        "app.js",
+        "class Store {\n  constructor() {}\n}\n\nexport async function load(url) {\n  return fetch(url);\n}\n",
+    );
+    let symbols = javascript.document_symbols();
+    assert_eq!(symbols[0].kind, SymbolKind::Class);
+    assert_eq!(symbols[0].name, "Store");
+    assert_eq!(symbols.last().unwrap().name, "load");
+    assert_eq!(symbols.last().unwrap().column, 22);
+
+    let patterns = vec![SymbolPattern::new(SymbolKind::Function, r"^sub\s+(\w+)").unwrap()];
+    let symbols = pattern_symbols(&Rope::from_str("sub run {\n  1;\n}\n"), &patterns, 4);
+    assert_eq!(symbols[0].name, "run");
+    assert_eq!(symbols[0].end_line, 1);
+}
+
+#[test]
+fn test_language_symbol_patterns_are_validated() {
+    assert!(SymbolPattern::new(SymbolKind::Function, r"^fn \w+").is_err());
+    assert!(SymbolPattern::new(SymbolKind::Function, r"^fn (\w+").is_err());
+
+    let registry = LanguageRegistry::builtin();
+    assert!(!registry.get("go").unwrap().symbols.is_empty());
+
+    let unknown_kind = r#"
+[[language]]
+name = "perl"
+extensions = ["pl"]
+symbols = [{ kind = "subroutine", pattern = '^sub\s+(\w+)' }]
+"#;
+    let error = LanguageRegistry::from_toml_str(unknown_kind)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.contains("unknown symbol kind 'subroutine'"),
+        "{}",
+        error
+    );
+
+    let no_group = r#"
+[[language]]
+name = "perl"
+extensions = ["pl"]
+symbols = [{ kind = "function", pattern = '^sub\s+\w+' }]
+"#;
+    let error = LanguageRegistry::from_toml_str(no_group)
+        .unwrap_err()
+        .to_string();
+    assert!(error.contains("Language 'perl'"), "{}", error);
+}
+
+#[test]
+fn test_outline_collapse_expand_and_navigation() {
+    let temp_dir = TempDir::new().unwrap();
+    let editor = open_indexed(temp_dir.path(), "main.rs", RUST);
+    let mut outline = SymbolOutline::new(editor.document_symbols());
+    assert_eq!(outline.entries().len(), 8);
+
+    outline.select_first();
+    outline.collapse_selected();
+    assert_eq!(outline.entries().len(), 3);
+    assert!(!outline.entries()[0].expanded);
+
+    outline.expand_selected();
+    outline.select_line(11);
+    assert_eq!(outline.selected_entry().unwrap().symbol.line, 10);
+    outline.collapse_selected();
+    assert_eq!(
+        outline.selected_entry().unwrap().symbol.name,
+        "Area for Circle"
+    );
+    outline.collapse_selected();
+    assert_eq!(outline.entries().len(), 7);
+
+    let mut symbols = editor.document_symbols();
+    symbols.iter_mut().for_each(|symbol| symbol.line += 2);
+    outline.set_symbols(symbols);
+    assert_eq!(outline.entries().len(), 7);
+    assert_eq!(outline.selected_entry().unwrap().symbol.line, 11);
+
+    outline.select_last();
+    assert_eq!(outline.selected_entry().unwrap().symbol.name, "main");
+}
+
+#[test]
+fn test_outline_commands_follow_buffer_and_jump() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open_indexed(temp_dir.path(), "main.rs", RUST);
+
+    editor
+        .execute_command(Command::ToggleSymbolOutline)
+        .unwrap();
+    assert!(editor.is_symbol_outline_open());
+    assert!(!editor.is_symbol_outline_focused());
+
+    editor.execute_command(Command::GotoLine(17)).unwrap();
+    let outline = editor.symbol_outline().unwrap();
+    assert_eq!(outline.selected_entry().unwrap().symbol.name, "Color");
+
+    editor.execute_command(Command::FocusSymbolOutline).unwrap();
+    assert!(editor.is_symbol_outline_focused());
+    editor
+        .execute_command(Command::SymbolOutlineNavigateFirst)
+        .unwrap();
+    editor
+        .execute_command(Command::SymbolOutlineNavigateNext)
+        .unwrap();
+    editor
+        .execute_command(Command::SymbolOutlineActivate)
+        .unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 15));
+    assert!(!editor.is_symbol_outline_focused());
+
+    editor.execute_command(Command::MoveToStartOfFile).unwrap();
+    editor.execute_command(Command::InsertChar('\n')).unwrap();
+    let outline = editor.symbol_outline().unwrap();
+    assert_eq!(outline.symbols()[0].line, 1);
+
+    editor
+        .execute_command(Command::ToggleSymbolOutline)
+        .unwrap();
+    assert!(editor
+        .execute_command(Command::SymbolOutlineNavigateNext)
+        .is_err());
+}
+
+#[test]
+fn test_file_symbol_picker_fuzzy_confirm() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = open_indexed(temp_dir.path(), "main.rs", RUST);
+
+    let mut picker = SymbolPicker::new(SymbolScope::File, editor.file_symbol_locations());
+    assert_eq!(picker.title(), "Go to Symbol in File");
+    assert_eq!(picker.items().len(), 8);
+
+    picker.set_query("crc");
+    assert_eq!(picker.items()[0].location.symbol.name, "Circle");
+    assert_eq!(picker.items()[0].indices, vec![0, 2, 3]);
+
+    editor.execute_command(picker.confirm().unwrap()).unwrap();
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 15));
+
+    picker.set_query("zzz");
+    assert!(picker.items().is_empty());
+    assert!(picker.confirm().is_none());
+}
+
+#[test]
+fn test_project_index_refresh_save_and_reload() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("project");
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("src/lib.rs"), "pub fn alpha() {}\n").unwrap();
+    fs::write(root.join("src/util.py"), "def beta():\n    pass\n").unwrap();
+    fs::write(root.join("target/gen.rs"), "fn skipped() {}\n").unwrap();
+    fs::write(root.join("notes.txt"), "fn not_code() {}\n").unwrap();
+
+    let languages = LanguageRegistry::builtin();
+    let index_path = temp_dir.path().join("index.json");
+    let mut index = SymbolIndex::new(root.clone());
+    assert!(index.refresh(languages, 4).unwrap());
+    assert_eq!(index.file_count(), 2);
+    assert!(!index.refresh(languages, 4).unwrap());
+    index.save(&index_path).unwrap();
+
+    let names = |index: &SymbolIndex| {
+        let mut names: Vec<_> = index
+            .locations()
+            .into_iter()
+            .map(|location| location.symbol.name)
+            .collect();
+        names.sort();
+        names
+    };
+    assert_eq!(names(&index), vec!["alpha", "beta"]);
+
+    let mut reloaded = SymbolIndex::load(&index_path, &root);
+    assert_eq!(reloaded, index);
+    assert!(SymbolIndex::load(&index_path, temp_dir.path()).is_empty());
+
+    fs::write(
+        root.join("src/lib.rs"),
+        "pub fn alpha() {}\npub fn gamma() {}\n",
+    )
+    .unwrap();
+    fs::remove_file(root.join("src/util.py")).unwrap();
+    assert!(reloaded.refresh(languages, 4).unwrap());
+    assert_eq!(names(&reloaded), vec!["alpha", "gamma"]);
+    assert_eq!(
+        reloaded.find("gamma")[0].path.as_deref(),
+        Some(root.join("src/lib.rs").as_path())
+    );
+
+    fs::write(&index_path, "not json").unwrap();
+    assert!(SymbolIndex::load(&index_path, &root).is_empty());
+}
+
+#[test]
+fn test_project_symbols_and_goto_definition() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path();
+    fs::write(root.join("shapes.rs"), RUST).unwrap();
+    let mut editor = open_indexed(
+        root,
+        "app.rs",
+        "fn run() {\n    make_circle();\n    main();\n}\n",
+    );
+    editor.open_file_tree(root.to_path_buf()).unwrap();
+    editor.execute_command(Command::FocusEditor).unwrap();
+    fs::write(
+        root.join("factory.rs"),
+        "pub fn make_circle() -> Circle {\n    todo!()\n}\n",
+    )
+    .unwrap();
+
+    let picker = SymbolPicker::new(SymbolScope::Project, editor.project_symbols().unwrap());
+    assert_eq!(picker.title(), "Go to Symbol in Project");
+    assert!(picker
+        .items()
+        .iter()
+        .any(|item| item.location.symbol.name == "run"));
+    assert!(root.join(".index/symbols.json").exists());
+
+    editor
+        .execute_command(Command::MouseClick(CursorPosition::new(1, 8)))
+        .unwrap();
+    editor.execute_command(Command::GotoDefinition).unwrap();
+    assert_eq!(
+        editor.current_buffer().file_path(),
+        Some(&root.join("factory.rs"))
+    );
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 7));
+
+    editor.execute_command(Command::MoveCursorDown).unwrap();
+    editor.execute_command(Command::GotoDefinition).unwrap();
+    assert_eq!(editor.status_message(), "No definition found for 'todo'");
+
+    let registry = CommandRegistry::new();
+    match registry.resolve("navigation.goto_definition", &[]) {
+        Ok(CommandAction::Execute(Command::GotoDefinition)) => {}
+        other => panic!("unexpected action {:?}", other),
+    }
+    assert!(matches!(
+        registry.resolve("search.goto_symbol_in_project", &[]),
+        Ok(CommandAction::Frontend("search.goto_symbol_in_project"))
+    ));
+}
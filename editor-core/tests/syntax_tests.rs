@@ -0,0 +1,170 @@
+use editor_core::{highlight_segments, Buffer, HighlightKind, HighlightSpan, SyntaxLanguage};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn kind_at(buffer: &Buffer, line: usize, column: usize) -> Option<HighlightKind> {
+    buffer
+        .highlight_line(line)
+        .iter()
+        .find(|span| span.start <= column && column < span.end)
+        .map(|span| span.kind)
+}
+
+fn highlighted(language: SyntaxLanguage, content: &str) -> Buffer {
+    let mut buffer = Buffer::from_string(content);
+    buffer.set_syntax_language(Some(language));
+    buffer
+}
+
+#[test]
+fn test_language_detection() {
+    assert_eq!(
+        SyntaxLanguage::from_path(Path::new("src/main.rs")),
+        Some(SyntaxLanguage::Rust)
+    );
+    assert_eq!(
+        SyntaxLanguage::from_path(Path::new("Cargo.lock")),
+        Some(SyntaxLanguage::Toml)
+    );
+    assert_eq!(
+        SyntaxLanguage::from_path(Path::new("README.MD")),
+        Some(SyntaxLanguage::Markdown)
+    );
+    assert_eq!(
+        SyntaxLanguage::from_path(Path::new("scripts/build.sh")),
+        Some(SyntaxLanguage::Shell)
+    );
+    assert_eq!(SyntaxLanguage::from_path(Path::new("notes.txt")), None);
+    assert_eq!(
+        SyntaxLanguage::from_name("bash"),
+        Some(SyntaxLanguage::Shell)
+    );
+    assert_eq!(
+        SyntaxLanguage::from_name("Python"),
+        Some(SyntaxLanguage::Python)
+    );
+}
+
+#[test]
+fn test_every_grammar_loads_and_highlights() {
+    let samples = [
+        (SyntaxLanguage::Rust, "// hello\nfn main() {}\n", 0),
+        (SyntaxLanguage::Toml, "# hello\nname = \"x\"\n", 0),
+        (SyntaxLanguage::Markdown, "# Title\n\ntext\n", 0),
+        (SyntaxLanguage::Json, "{\"key\": true}\n", 0),
+        (SyntaxLanguage::Python, "# hello\ndef f():\n    pass\n", 0),
+        (SyntaxLanguage::Shell, "# hello\necho hi\n", 0),
+    ];
+
+    for (language, content, line) in samples {
+        assert!(
+            language.highlights_query().is_some(),
+            "{} query failed to compile",
+            language
+        );
+        let buffer = highlighted(language, content);
+        assert_eq!(buffer.syntax_language(), Some(language));
+        assert!(buffer.syntax_tree().is_some());
+        assert!(
+            !buffer.highlight_line(line).is_empty(),
+            "{} produced no spans",
+            language
+        );
+    }
+}
+
+#[test]
+fn test_rust_spans() {
+    let buffer = highlighted(
+        SyntaxLanguage::Rust,
+        "fn main() {\n    let s = \"hi\"; // note\n}\n",
+    );
+
+    assert_eq!(kind_at(&buffer, 0, 0), Some(HighlightKind::Keyword));
+    assert_eq!(kind_at(&buffer, 0, 3), Some(HighlightKind::Function));
+    assert_eq!(kind_at(&buffer, 1, 4), Some(HighlightKind::Keyword));
+    assert_eq!(kind_at(&buffer, 1, 13), Some(HighlightKind::String));
+    assert_eq!(kind_at(&buffer, 1, 19), Some(HighlightKind::Comment));
+    assert_eq!(kind_at(&buffer, 1, 8), None);
+}
+
+#[test]
+fn test_incremental_edits_update_later_lines() {
+    let mut buffer = highlighted(SyntaxLanguage::Rust, "let a = 1;\nlet b = 2;\nlet c = 3;\n");
+    assert_eq!(kind_at(&buffer, 2, 0), Some(HighlightKind::Keyword));
+
+    buffer.insert_str(0, 0, "/* ").unwrap();
+    assert_eq!(kind_at(&buffer, 0, 0), Some(HighlightKind::Comment));
+    assert_eq!(kind_at(&buffer, 2, 0), Some(HighlightKind::Comment));
+
+    buffer.insert_str(1, 0, "*/ ").unwrap();
+    assert_eq!(kind_at(&buffer, 1, 0), Some(HighlightKind::Comment));
+    assert_eq!(kind_at(&buffer, 2, 0), Some(HighlightKind::Keyword));
+
+    buffer.delete_range(0, 0, 1, 3).unwrap();
+    assert_eq!(buffer.content(), "let b = 2;\nlet c = 3;\n");
+    assert_eq!(kind_at(&buffer, 0, 0), Some(HighlightKind::Keyword));
+    assert_eq!(kind_at(&buffer, 1, 8), Some(HighlightKind::Constant));
+}
+
+#[test]
+fn test_change_log_records_edits() {
+    let mut buffer = Buffer::from_string("hello\nworld\n");
+    let start = buffer.revision();
+
+    buffer.insert_str(1, 0, "big ").unwrap();
+    buffer.delete_range(0, 0, 1, 0).unwrap();
+
+    let changes = buffer.changes_since(start).unwrap();
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[0].start.line, 1);
+    assert_eq!(changes[0].text, "big ");
+    assert_eq!(changes[0].new_end.column, 4);
+    assert_eq!(changes[1].old_end.line, 1);
+    assert_eq!(changes[1].removed_chars(), 6);
+    assert_eq!(buffer.changes_since(buffer.revision()).unwrap().len(), 0);
+    assert!(buffer.changes_since(start + 10).is_none());
+}
+
+#[test]
+fn test_files_are_highlighted_by_extension() {
+    let temp_dir = TempDir::new().unwrap();
+    let path = temp_dir.path().join("config.toml");
+    fs::write(&path, "[package]\nname = \"demo\"\n").unwrap();
+
+    let buffer = Buffer::from_file(path).unwrap();
+    assert_eq!(buffer.syntax_language(), Some(SyntaxLanguage::Toml));
+    assert_eq!(kind_at(&buffer, 1, 0), Some(HighlightKind::Property));
+    assert_eq!(kind_at(&buffer, 1, 8), Some(HighlightKind::String));
+
+    let mut plain = Buffer::from_string("fn main() {}\n");
+    assert!(plain.highlight_line(0).is_empty());
+    plain.save_as(temp_dir.path().join("main.rs")).unwrap();
+    assert_eq!(plain.syntax_language(), Some(SyntaxLanguage::Rust));
+}
+
+#[test]
+fn test_highlight_segments_split_text() {
+    let spans = [
+        HighlightSpan {
+            start: 0,
+            end: 2,
+            kind: HighlightKind::Keyword,
+        },
+        HighlightSpan {
+            start: 3,
+            end: 7,
+            kind: HighlightKind::Function,
+        },
+    ];
+    assert_eq!(
+        highlight_segments("fn main()\n", &spans),
+        vec![
+            ("fn".to_string(), Some(HighlightKind::Keyword)),
+            (" ".to_string(), None),
+            ("main".to_string(), Some(HighlightKind::Function)),
+            ("()\n".to_string(), None),
+        ]
+    );
+}
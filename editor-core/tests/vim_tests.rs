@@ -0,0 +1,351 @@
+mod common;
+
+use common::{content, open};
+use editor_core::{
+    parse_ex_command, parse_vim_keys, Command, CursorPosition, EditorState, ExCommand, ExContext,
+    Key, KeyPress, SortOptions, VimAction, VimCommand, VimMotion, VimOperator, VimParse,
+    VimRegister, VimTarget, VimTextObject,
+};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn vim_editor(dir: &Path, content: &str) -> EditorState {
+    let mut editor = open(dir, "notes.txt", content);
+    editor.set_vim_enabled(true);
+    editor
+}
+
+fn press(c: char) -> KeyPress {
+    match c {
+        '\u{1b}' => KeyPress::plain(Key::Escape),
+        '\r' => KeyPress::plain(Key::Enter),
+        c => KeyPress::plain(Key::Char(c)),
+    }
+}
+
+// Sends keys the way the frontends do: vim takes what it wants and the
+// rest is typed as text.
+fn keys(editor: &mut EditorState, text: &str) {
+    for c in text.chars() {
+        let press = press(c);
+        let command = if editor.vim_wants_key(&press) {
+            Command::VimKey(press)
+        } else if c == '\r' {
+            Command::NewLine
+        } else {
+            Command::InsertChar(c)
+        };
+        editor.execute_command(command).unwrap();
+    }
+}
+
+fn parse(text: &str, visual: bool) -> VimParse {
+    parse_vim_keys(&text.chars().collect::<Vec<_>>(), visual)
+}
+
+#[test]
+fn test_parse_counts_operators_and_objects() {
+    assert_eq!(
+        parse("2d3w", false),
+        VimParse::Complete(VimCommand {
+            count: Some(6),
+            register: None,
+            action: VimAction::Operate {
+                operator: VimOperator::Delete,
+                target: VimTarget::Motion(VimMotion::WordForward { big: false }),
+            },
+        })
+    );
+    assert_eq!(
+        parse("\"ayy", false),
+        VimParse::Complete(VimCommand {
+            count: None,
+            register: Some('a'),
+            action: VimAction::Operate {
+                operator: VimOperator::Yank,
+                target: VimTarget::Line,
+            },
+        })
+    );
+    assert_eq!(
+        parse("ci(", false),
+        VimParse::Complete(VimCommand {
+            count: None,
+            register: None,
+            action: VimAction::Operate {
+                operator: VimOperator::Change,
+                target: VimTarget::Object {
+                    object: VimTextObject::Bracket {
+                        open: '(',
+                        close: ')',
+                    },
+                    around: false,
+                },
+            },
+        })
+    );
+    assert_eq!(parse("d", false), VimParse::Pending);
+    assert_eq!(parse("g", false), VimParse::Pending);
+    assert_eq!(parse("dz", false), VimParse::Invalid);
+    assert!(matches!(
+        parse("d", true),
+        VimParse::Complete(VimCommand {
+            action: VimAction::Operate {
+                target: VimTarget::Selection,
+                ..
+            },
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_parse_ex_commands() {
+    let context = ExContext {
+        current_line: 2,
+        last_line: 9,
+        visual: Some((1, 3)),
+    };
+    assert_eq!(
+        parse_ex_command("%s/foo/bar/g", &context).unwrap(),
+        ExCommand::Substitute {
+            lines: (0, 9),
+            pattern: "foo".to_string(),
+            replacement: "bar".to_string(),
+            global: true,
+            ignore_case: false,
+        }
+    );
+    assert_eq!(
+        parse_ex_command("'<,'>sort! u", &context).unwrap(),
+        ExCommand::Sort {
+            lines: (1, 3),
+            options: SortOptions::Text {
+                reverse: true,
+                unique: true,
+            },
+        }
+    );
+    assert_eq!(
+        parse_ex_command("wq", &context).unwrap(),
+        ExCommand::Write {
+            path: None,
+            quit: true,
+        }
+    );
+    assert_eq!(
+        parse_ex_command("q!", &context).unwrap(),
+        ExCommand::Quit { force: true }
+    );
+    assert_eq!(
+        parse_ex_command("5", &context).unwrap(),
+        ExCommand::GotoLine(4)
+    );
+    assert!(parse_ex_command("frobnicate", &context).is_err());
+}
+
+#[test]
+fn test_operators_with_motions_and_objects() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = vim_editor(temp_dir.path(), "one two three\ncall(a, b)\n");
+    assert_eq!(editor.vim_mode_indicator(), Some("NORMAL"));
+
+    keys(&mut editor, "dw");
+    assert_eq!(content(&editor), "two three\ncall(a, b)\n");
+    assert_eq!(
+        editor.vim_register('"'),
+        Some(&VimRegister::new("one ", false))
+    );
+
+    keys(&mut editor, "cwsix\u{1b}");
+    assert_eq!(content(&editor), "six three\ncall(a, b)\n");
+    assert_eq!(editor.vim_mode_indicator(), Some("NORMAL"));
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 2));
+
+    keys(&mut editor, "jf(ci(x\u{1b}");
+    assert_eq!(content(&editor), "six three\ncall(x)\n");
+
+    keys(&mut editor, "0d$");
+    assert_eq!(content(&editor), "six three\n\n");
+}
+
+#[test]
+fn test_line_delete_put_and_dot_repeat() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = vim_editor(temp_dir.path(), "a\nb\nc\nd\n");
+
+    keys(&mut editor, "ddp");
+    assert_eq!(content(&editor), "b\na\nc\nd\n");
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 0));
+
+    keys(&mut editor, "j.");
+    assert_eq!(content(&editor), "b\na\nc\na\nd\n");
+
+    keys(&mut editor, "jdd.");
+    assert_eq!(content(&editor), "b\na\nc\n");
+
+    keys(&mut editor, "ggA;\u{1b}j.");
+    assert_eq!(content(&editor), "b;\na;\nc\n");
+
+    keys(&mut editor, "u");
+    assert_eq!(content(&editor), "b;\na\nc\n");
+}
+
+#[test]
+fn test_insert_with_count_undoes_as_one_step() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = vim_editor(temp_dir.path(), "x\n");
+
+    keys(&mut editor, "3ia-\u{1b}");
+    assert_eq!(content(&editor), "a-a-a-x\n");
+    assert_eq!(*editor.cursor(), CursorPosition::new(0, 5));
+
+    keys(&mut editor, "u");
+    assert_eq!(content(&editor), "x\n");
+}
+
+#[test]
+fn test_visual_modes() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = vim_editor(temp_dir.path(), "abcd\nefgh\nijkl\n");
+
+    keys(&mut editor, "lvly");
+    assert_eq!(editor.vim_mode_indicator(), Some("NORMAL"));
+    assert_eq!(
+        editor.vim_register('0'),
+        Some(&VimRegister::new("bc", false))
+    );
+
+    keys(&mut editor, "Vj");
+    assert_eq!(editor.vim_mode_indicator(), Some("V-LINE"));
+    keys(&mut editor, ">");
+    assert_eq!(content(&editor), "    abcd\n    efgh\nijkl\n");
+
+    keys(&mut editor, "gg0");
+    assert!(editor.vim_wants_key(&KeyPress::ctrl(Key::Char('v'))));
+    editor
+        .execute_command(Command::VimKey(KeyPress::ctrl(Key::Char('v'))))
+        .unwrap();
+    assert_eq!(editor.vim_mode_indicator(), Some("V-BLOCK"));
+    keys(&mut editor, "jjlld");
+    assert_eq!(content(&editor), " abcd\n efgh\nl\n");
+}
+
+#[test]
+fn test_registers_append_and_clipboard_free_paste() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = vim_editor(temp_dir.path(), "one\ntwo\n");
+
+    keys(&mut editor, "\"ayyj\"Ayy");
+    assert_eq!(
+        editor.vim_register('a'),
+        Some(&VimRegister::new("one\ntwo\n", true))
+    );
+
+    keys(&mut editor, "\"aP");
+    assert_eq!(content(&editor), "one\none\ntwo\ntwo\n");
+
+    keys(&mut editor, "\"_dd");
+    assert_eq!(content(&editor), "one\ntwo\ntwo\n");
+    assert_eq!(
+        editor.vim_register('"'),
+        Some(&VimRegister::new("one\ntwo\n", true))
+    );
+}
+
+#[test]
+fn test_ex_substitute_and_sort() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = vim_editor(temp_dir.path(), "pear foo foo\napple\nfoo\n");
+
+    keys(&mut editor, ":%s/foo/bar/g\r");
+    assert_eq!(content(&editor), "pear bar bar\napple\nbar\n");
+    assert_eq!(editor.status_message(), "3 substitutions on 2 lines");
+
+    keys(&mut editor, ":%sort\r");
+    assert_eq!(content(&editor), "apple\nbar\npear bar bar\n");
+
+    keys(&mut editor, "u");
+    assert_eq!(content(&editor), "pear bar bar\napple\nbar\n");
+
+    keys(&mut editor, ":s/nothing/x/");
+    assert_eq!(editor.vim_command_line(), Some(":s/nothing/x/"));
+    assert!(editor
+        .execute_command(Command::VimKey(press('\r')))
+        .is_err());
+    assert_eq!(editor.vim_command_line(), None);
+}
+
+#[test]
+fn test_ex_write_and_quit() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = vim_editor(temp_dir.path(), "draft\n");
+
+    keys(&mut editor, "x:q");
+    assert!(editor
+        .execute_command(Command::VimKey(press('\r')))
+        .is_err());
+    assert!(!editor.take_quit_request());
+
+    keys(&mut editor, ":w\r");
+    assert_eq!(
+        fs::read_to_string(temp_dir.path().join("notes.txt")).unwrap(),
+        "raft\n"
+    );
+    keys(&mut editor, ":q\r");
+    assert!(editor.take_quit_request());
+    assert!(!editor.take_quit_request());
+}
+
+#[test]
+fn test_toggle_and_insert_mode_keys() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = vim_editor(temp_dir.path(), "text\n");
+
+    keys(&mut editor, "i");
+    assert_eq!(editor.vim_mode_indicator(), Some("INSERT"));
+    assert!(!editor.vim_wants_key(&press('d')));
+    assert!(editor.vim_wants_key(&press('\u{1b}')));
+
+    editor.execute_command(Command::ToggleVimMode).unwrap();
+    assert!(!editor.is_vim_enabled());
+    assert_eq!(editor.vim_mode_indicator(), None);
+    assert!(!editor.vim_wants_key(&press('\u{1b}')));
+    assert_eq!(editor.status_message(), "Vim mode off");
+}
+
+#[test]
+fn test_any_quote_any_bracket_line_and_entire_objects() {
+    let temp_dir = TempDir::new().unwrap();
+    let mut editor = vim_editor(
+        temp_dir.path(),
+        "\nlet s = f(\"a b\", [1, 2]);\n  indented line  \n\n",
+    );
+
+    keys(&mut editor, "jfaciqx\u{1b}");
+    assert_eq!(
+        content(&editor),
+        "\nlet s = f(\"x\", [1, 2]);\n  indented line  \n\n"
+    );
+
+    // The nearest pair of any kind, then the one around it with a count.
+    keys(&mut editor, "f1dim");
+    assert_eq!(
+        content(&editor),
+        "\nlet s = f(\"x\", []);\n  indented line  \n\n"
+    );
+    keys(&mut editor, "d2am");
+    assert_eq!(content(&editor), "\nlet s = f;\n  indented line  \n\n");
+
+    keys(&mut editor, "jcilnew\u{1b}");
+    assert_eq!(content(&editor), "\nlet s = f;\n  new\n\n");
+
+    keys(&mut editor, "yie");
+    assert_eq!(
+        editor.vim_register('"'),
+        Some(&VimRegister::new("let s = f;\n  new\n", false))
+    );
+    keys(&mut editor, "dae");
+    assert_eq!(content(&editor), "");
+}
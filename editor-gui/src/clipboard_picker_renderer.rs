@@ -0,0 +1,158 @@
+use editor_core::{ClipboardHistory, ClipboardPicker, ClipboardPickerItem, Command};
+use eframe::egui;
+
+const PICKER_MAX_ITEMS: usize = 12;
+
+pub struct ClipboardPickerRenderer {
+    picker: Option<ClipboardPicker>,
+}
+
+impl ClipboardPickerRenderer {
+    pub fn new() -> Self {
+        Self { picker: None }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.picker.is_some()
+    }
+
+    pub fn open(&mut self, history: &ClipboardHistory) {
+        self.picker = Some(ClipboardPicker::new(history));
+    }
+
+    pub fn close(&mut self) {
+        self.picker = None;
+    }
+
+    pub fn refresh(&mut self, history: &ClipboardHistory) {
+        if let Some(picker) = self.picker.as_mut() {
+            picker.refresh(history);
+        }
+    }
+
+    // Returns the command to paste the chosen entry, which closes the
+    // picker, or to pin or unpin one, which leaves it open.
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<Command> {
+        let picker = self.picker.as_mut()?;
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+        let mut toggle_pin = false;
+        let mut clicked = None;
+        let mut pin_clicked = None;
+
+        ctx.input(|i| {
+            if i.key_pressed(egui::Key::ArrowDown) {
+                picker.select_next();
+            }
+            if i.key_pressed(egui::Key::ArrowUp) {
+                picker.select_previous();
+            }
+            if i.key_pressed(egui::Key::Enter) {
+                confirmed = true;
+            }
+            if i.key_pressed(egui::Key::Escape) {
+                cancelled = true;
+            }
+            if i.modifiers.command && i.key_pressed(egui::Key::T) {
+                toggle_pin = true;
+            }
+        });
+
+        egui::Window::new(picker.title())
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(true)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+            .fixed_size([480.0, 0.0])
+            .show(ctx, |ui| {
+                let mut query = picker.query().to_string();
+                ui.horizontal(|ui| {
+                    ui.label(">");
+                    let response =
+                        ui.add(egui::TextEdit::singleline(&mut query).desired_width(f32::INFINITY));
+                    response.request_focus();
+                });
+                if query != picker.query() {
+                    picker.set_query(query);
+                }
+
+                ui.separator();
+                if picker.items().is_empty() {
+                    ui.weak("No matching entries");
+                }
+
+                let start = picker.selected_index().saturating_sub(PICKER_MAX_ITEMS - 1);
+                for (index, item) in picker
+                    .items()
+                    .iter()
+                    .enumerate()
+                    .skip(start)
+                    .take(PICKER_MAX_ITEMS)
+                {
+                    let selected = index == picker.selected_index();
+                    ui.horizontal(|ui| {
+                        let pin_label = if item.pinned { "Unpin" } else { "Pin" };
+                        if ui.small_button(pin_label).clicked() {
+                            pin_clicked = Some(index);
+                        }
+                        if ui.selectable_label(selected, item_text(item)).clicked() {
+                            clicked = Some(index);
+                        }
+                    });
+                }
+                ui.separator();
+                ui.weak("Ctrl+T pins the selected entry so it never expires");
+            });
+
+        if let Some(index) = pin_clicked {
+            picker.select_index(index);
+            toggle_pin = true;
+        }
+        if let Some(index) = clicked {
+            picker.select_index(index);
+            confirmed = true;
+        }
+
+        if cancelled {
+            self.close();
+            None
+        } else if confirmed {
+            let command = picker.confirm();
+            self.close();
+            command
+        } else if toggle_pin {
+            picker.toggle_pin()
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for ClipboardPickerRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn item_text(item: &ClipboardPickerItem) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    let plain = egui::TextFormat {
+        color: egui::Color32::WHITE,
+        ..Default::default()
+    };
+    let highlight = egui::TextFormat {
+        color: egui::Color32::YELLOW,
+        ..Default::default()
+    };
+
+    for (index, c) in item.preview.chars().enumerate() {
+        let format = if item.indices.contains(&index) {
+            highlight.clone()
+        } else {
+            plain.clone()
+        };
+        job.append(&c.to_string(), 0.0, format);
+    }
+    job
+}
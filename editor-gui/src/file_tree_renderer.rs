@@ -0,0 +1,241 @@
+use editor_core::{Command, EditorState, FileStatus};
+use eframe::egui;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileTreePromptKind {
+    NewFile,
+    NewDirectory,
+    Rename,
+    ConfirmDelete,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileTreePrompt {
+    pub kind: FileTreePromptKind,
+    pub input: String,
+}
+
+pub struct FileTreeRenderer {
+    prompt: Option<FileTreePrompt>,
+}
+
+impl FileTreeRenderer {
+    pub fn new() -> Self {
+        Self { prompt: None }
+    }
+
+    pub fn is_prompt_open(&self) -> bool {
+        self.prompt.is_some()
+    }
+
+    #[allow(dead_code)]
+    pub fn prompt(&self) -> Option<&FileTreePrompt> {
+        self.prompt.as_ref()
+    }
+
+    pub fn open_prompt(&mut self, kind: FileTreePromptKind, editor_state: &EditorState) {
+        let selected_name = editor_state
+            .file_tree()
+            .and_then(|t| t.selected_entry())
+            .map(|e| e.name.clone());
+
+        let input = match kind {
+            FileTreePromptKind::NewFile | FileTreePromptKind::NewDirectory => String::new(),
+            FileTreePromptKind::Rename | FileTreePromptKind::ConfirmDelete => match selected_name {
+                Some(name) => name,
+                None => return,
+            },
+        };
+
+        self.prompt = Some(FileTreePrompt { kind, input });
+    }
+
+    pub fn close_prompt(&mut self) {
+        self.prompt = None;
+    }
+
+    pub fn confirm_prompt(&mut self) -> Option<Command> {
+        let prompt = self.prompt.take()?;
+        Some(match prompt.kind {
+            FileTreePromptKind::NewFile => Command::FileTreeCreateFile(prompt.input),
+            FileTreePromptKind::NewDirectory => Command::FileTreeCreateDirectory(prompt.input),
+            FileTreePromptKind::Rename => Command::FileTreeRename(prompt.input),
+            FileTreePromptKind::ConfirmDelete => Command::FileTreeDelete,
+        })
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context, editor_state: &EditorState) -> Vec<Command> {
+        let mut commands = Vec::new();
+
+        let tree = match editor_state.file_tree() {
+            Some(tree) => tree,
+            None => return commands,
+        };
+        let focused = editor_state.is_file_tree_focused();
+
+        egui::SidePanel::left("file_tree")
+            .resizable(true)
+            .default_width(240.0)
+            .show(ctx, |ui| {
+                ui.style_mut().visuals.override_text_color = Some(egui::Color32::WHITE);
+
+                let root_name = tree
+                    .root()
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or("Files");
+                ui.horizontal(|ui| {
+                    ui.strong(root_name);
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("⟳").on_hover_text("Refresh").clicked() {
+                            commands.push(Command::FileTreeRefresh);
+                        }
+                        if ui.small_button("🗀+").on_hover_text("New folder").clicked() {
+                            self.open_prompt(FileTreePromptKind::NewDirectory, editor_state);
+                        }
+                        if ui.small_button("+").on_hover_text("New file").clicked() {
+                            self.open_prompt(FileTreePromptKind::NewFile, editor_state);
+                        }
+                    });
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (index, entry) in tree.entries().iter().enumerate() {
+                        let icon = if !entry.is_dir() {
+                            "  "
+                        } else if entry.expanded {
+                            "▾ "
+                        } else {
+                            "▸ "
+                        };
+
+                        let status = editor_state.file_status(&entry.path);
+                        let marker = match status {
+                            FileStatus::Modified => " ●",
+                            FileStatus::Open if !entry.is_dir() => " ○",
+                            _ => "",
+                        };
+
+                        let color = match status {
+                            FileStatus::Modified => egui::Color32::YELLOW,
+                            _ if entry.is_dir() => egui::Color32::LIGHT_BLUE,
+                            _ => egui::Color32::WHITE,
+                        };
+
+                        let text = egui::RichText::new(format!(
+                            "{}{}{}{}",
+                            "  ".repeat(entry.depth),
+                            icon,
+                            entry.name,
+                            marker
+                        ))
+                        .monospace()
+                        .color(color);
+
+                        let selected = index == tree.selected_index();
+                        let response = ui.selectable_label(selected, text);
+                        if selected && focused {
+                            response.scroll_to_me(None);
+                        }
+
+                        if response.clicked() {
+                            commands.push(Command::FocusFileTree);
+                            commands.push(Command::FileTreeSelect(index));
+                        }
+                        if response.double_clicked() {
+                            commands.push(Command::FileTreeActivate);
+                        }
+
+                        response.context_menu(|ui| {
+                            if ui.button("Rename").clicked() {
+                                commands.push(Command::FileTreeSelect(index));
+                                self.prompt = Some(FileTreePrompt {
+                                    kind: FileTreePromptKind::Rename,
+                                    input: entry.name.clone(),
+                                });
+                                ui.close_menu();
+                            }
+                            if ui.button("Delete").clicked() {
+                                commands.push(Command::FileTreeSelect(index));
+                                self.prompt = Some(FileTreePrompt {
+                                    kind: FileTreePromptKind::ConfirmDelete,
+                                    input: entry.name.clone(),
+                                });
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                });
+            });
+
+        if let Some(command) = self.render_prompt(ctx) {
+            commands.push(command);
+        }
+
+        commands
+    }
+
+    fn render_prompt(&mut self, ctx: &egui::Context) -> Option<Command> {
+        let prompt = self.prompt.as_mut()?;
+
+        let title = match prompt.kind {
+            FileTreePromptKind::NewFile => "New File",
+            FileTreePromptKind::NewDirectory => "New Folder",
+            FileTreePromptKind::Rename => "Rename",
+            FileTreePromptKind::ConfirmDelete => "Delete",
+        };
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                if prompt.kind == FileTreePromptKind::ConfirmDelete {
+                    ui.label(format!("Delete {}?", prompt.input));
+                } else {
+                    let response = ui.text_edit_singleline(&mut prompt.input);
+                    response.request_focus();
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        confirmed = true;
+                    }
+                }
+
+                ui.horizontal(|ui| {
+                    if ui.button("OK").clicked() {
+                        confirmed = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancelled = true;
+                    }
+                });
+
+                if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                    cancelled = true;
+                }
+                if prompt.kind == FileTreePromptKind::ConfirmDelete
+                    && ui.input(|i| i.key_pressed(egui::Key::Enter))
+                {
+                    confirmed = true;
+                }
+            });
+
+        if cancelled {
+            self.close_prompt();
+            None
+        } else if confirmed {
+            self.confirm_prompt()
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for FileTreeRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
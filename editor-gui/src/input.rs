@@ -1,4 +1,7 @@
-use editor_core::{CaseMode, Command, CursorPosition};
+use editor_core::{
+    Command, CommandAction, CommandRegistry, CursorPosition, EditorState, Key, KeyModifiers,
+    KeyPress, KeyResolution, KeySequence, KeySequenceResolver, KeymapMode, Keymaps,
+};
 
 #[allow(dead_code)]
 pub struct KeyBindings {
@@ -16,238 +19,145 @@ impl Default for KeyBindings {
 }
 
 pub struct InputHandler {
-    key_bindings: KeyBindings,
+    keymaps: Keymaps,
+    resolver: KeySequenceResolver,
+    suppress_text: bool,
 }
 
 impl InputHandler {
     pub fn new() -> Self {
         Self {
-            key_bindings: KeyBindings::default(),
+            keymaps: Keymaps::builtin().clone(),
+            resolver: KeySequenceResolver::new(),
+            suppress_text: false,
         }
     }
 
     #[allow(dead_code)]
     pub fn with_key_bindings(mut self, key_bindings: KeyBindings) -> Self {
-        self.key_bindings = key_bindings;
+        let press = KeyPress::new(
+            Key::Char(key_bindings.quit_key),
+            KeyModifiers {
+                ctrl: key_bindings.quit_modifier,
+                ..KeyModifiers::NONE
+            },
+        );
+        self.keymaps
+            .rebind_command("app.quit", KeySequence::new(vec![press]));
         self
     }
 
+    pub fn with_keymaps(mut self, keymaps: Keymaps) -> Self {
+        self.set_keymaps(keymaps);
+        self
+    }
+
+    pub fn set_keymaps(&mut self, keymaps: Keymaps) {
+        self.keymaps = keymaps;
+        self.resolver.reset();
+    }
+
+    pub fn pending_keys(&self) -> Option<String> {
+        self.resolver.pending_display()
+    }
+
     pub fn handle_key_event(
         &mut self,
         key: egui::Key,
         modifiers: &egui::Modifiers,
     ) -> Option<InputAction> {
-        #[cfg(target_os = "macos")]
-        let ctrl = modifiers.command;
-        #[cfg(not(target_os = "macos"))]
-        let ctrl = modifiers.ctrl;
-
-        let alt = modifiers.alt;
-        let shift = modifiers.shift;
-
-        match key {
-            egui::Key::Q if ctrl && self.key_bindings.quit_key == 'q' => Some(InputAction::Quit),
-
-            egui::Key::Backspace if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::Backspace))
-            }
-            egui::Key::Delete if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::DeleteChar))
-            }
-            egui::Key::Enter if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::NewLine))
-            }
-            egui::Key::Tab if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::Indent))
-            }
-            egui::Key::Tab if !ctrl && !alt && shift => Some(InputAction::Command(Command::Dedent)),
-
-            egui::Key::ArrowUp if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveCursorUp))
-            }
-            egui::Key::ArrowDown if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveCursorDown))
-            }
-            egui::Key::ArrowLeft if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveCursorLeft))
-            }
-            egui::Key::ArrowRight if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveCursorRight))
-            }
-
-            egui::Key::ArrowLeft if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveCursorWordLeft))
-            }
-            egui::Key::ArrowRight if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveCursorWordRight))
-            }
-
-            egui::Key::Home if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveToStartOfLine))
-            }
-            egui::Key::End if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveToEndOfLine))
-            }
-
-            egui::Key::Home if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveToStartOfFile))
-            }
-            egui::Key::End if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::MoveToEndOfFile))
-            }
-
-            egui::Key::PageUp if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::PageUp))
-            }
-            egui::Key::PageDown if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::PageDown))
-            }
-
-            egui::Key::S if ctrl && !alt && !shift => Some(InputAction::Command(Command::Save)),
-            egui::Key::O if ctrl && !alt && !shift => Some(InputAction::OpenFile),
-            egui::Key::N if ctrl && !alt && !shift => Some(InputAction::Command(Command::New)),
-            egui::Key::W if ctrl && !alt && !shift => Some(InputAction::Command(Command::Close)),
-
-            egui::Key::Z if ctrl && !alt && !shift => Some(InputAction::Command(Command::Undo)),
-            egui::Key::Y if ctrl && !alt && !shift => Some(InputAction::Command(Command::Redo)),
-            egui::Key::Z if ctrl && !alt && shift => Some(InputAction::Command(Command::Redo)),
-
-            egui::Key::C if ctrl && !alt && !shift => Some(InputAction::Command(Command::Copy)),
-            egui::Key::X if ctrl && !alt && !shift => Some(InputAction::Command(Command::Cut)),
-            egui::Key::V if ctrl && !alt && !shift => Some(InputAction::Command(Command::Paste)),
-
-            egui::Key::A if ctrl && !alt && !shift => Some(InputAction::SelectAll),
-
-            egui::Key::F if ctrl && !alt && !shift => Some(InputAction::Search),
-            egui::Key::H if ctrl && !alt && !shift => Some(InputAction::Replace),
-            egui::Key::G if ctrl && !alt && !shift => Some(InputAction::GotoLine),
-
-            egui::Key::F3 if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::NextMatch))
-            }
-            egui::Key::F3 if !ctrl && !alt && shift => {
-                Some(InputAction::Command(Command::PreviousMatch))
-            }
-
-            egui::Key::D if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::DuplicateLine))
-            }
-            egui::Key::K if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::DeleteLine))
-            }
-            egui::Key::J if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::JoinLines))
-            }
-
-            egui::Key::ArrowUp if ctrl && !alt && shift => {
-                Some(InputAction::Command(Command::MoveLinesUp))
-            }
-            egui::Key::ArrowDown if ctrl && !alt && shift => {
-                Some(InputAction::Command(Command::MoveLinesDown))
-            }
-
-            egui::Key::Slash if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::ToggleLineComment))
-            }
-            egui::Key::Slash if ctrl && !alt && shift => {
-                Some(InputAction::Command(Command::ToggleBlockComment))
-            }
-
-            egui::Key::U if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::ChangeCase {
-                    mode: CaseMode::Upper,
-                }))
-            }
-            egui::Key::U if ctrl && !alt && shift => {
-                Some(InputAction::Command(Command::ChangeCase {
-                    mode: CaseMode::Lower,
-                }))
-            }
+        self.handle_key(key, modifiers, KeymapMode::Editor)
+    }
 
-            egui::Key::B if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::JumpToMatchingBracket))
-            }
+    pub fn handle_history_browser_key_event(
+        &mut self,
+        key: egui::Key,
+        modifiers: &egui::Modifiers,
+    ) -> Option<InputAction> {
+        self.handle_key(key, modifiers, KeymapMode::HistoryBrowser)
+    }
 
-            egui::Key::M if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::ToggleBookmark))
-            }
-            egui::Key::F2 if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::NextBookmark))
-            }
-            egui::Key::F2 if !ctrl && !alt && shift => {
-                Some(InputAction::Command(Command::PreviousBookmark))
-            }
+    pub fn handle_file_tree_key_event(
+        &mut self,
+        key: egui::Key,
+        modifiers: &egui::Modifiers,
+    ) -> Option<InputAction> {
+        self.handle_key(key, modifiers, KeymapMode::FileTree)
+    }
 
-            egui::Key::Insert if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::ToggleOverwriteMode))
-            }
+    pub fn handle_symbol_outline_key_event(
+        &mut self,
+        key: egui::Key,
+        modifiers: &egui::Modifiers,
+    ) -> Option<InputAction> {
+        self.handle_key(key, modifiers, KeymapMode::SymbolOutline)
+    }
 
-            egui::Key::R if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::ToggleReadOnly))
-            }
+    pub fn handle_completion_key_event(
+        &mut self,
+        key: egui::Key,
+        modifiers: &egui::Modifiers,
+    ) -> Option<InputAction> {
+        self.handle_overlay_key(key, modifiers, KeymapMode::Completion)
+    }
 
-            egui::Key::Escape if !ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::ClearSecondaryCursors))
-            }
+    pub fn handle_snippet_key_event(
+        &mut self,
+        key: egui::Key,
+        modifiers: &egui::Modifiers,
+    ) -> Option<InputAction> {
+        self.handle_overlay_key(key, modifiers, KeymapMode::Snippet)
+    }
 
-            egui::Key::T if ctrl && !alt && !shift => {
-                Some(InputAction::Command(Command::OpenHistoryBrowser))
+    // Keys the completion popup or snippet session doesn't claim fall
+    // through to the editor keymap, so typing and editing keep working.
+    fn handle_overlay_key(
+        &mut self,
+        key: egui::Key,
+        modifiers: &egui::Modifiers,
+        mode: KeymapMode,
+    ) -> Option<InputAction> {
+        if !self.resolver.is_pending() {
+            let press = key_press(key, modifiers)?;
+            if let KeyResolution::Command(id) = self.resolver.feed(&self.keymaps, mode, press) {
+                self.suppress_text =
+                    matches!(press.key, Key::Char(_)) && press.modifiers.is_text_input();
+                return action_for_binding(id, self.resolver.take_arguments());
             }
-
-            _ => None,
         }
+        self.handle_key(key, modifiers, KeymapMode::Editor)
     }
 
-    pub fn handle_history_browser_key_event(
+    pub fn handle_history_stats_key_event(
         &mut self,
         key: egui::Key,
         modifiers: &egui::Modifiers,
     ) -> Option<InputAction> {
-        #[cfg(target_os = "macos")]
-        let ctrl = modifiers.command;
-        #[cfg(not(target_os = "macos"))]
-        let ctrl = modifiers.ctrl;
-
-        match key {
-            egui::Key::Q if ctrl && self.key_bindings.quit_key == 'q' => Some(InputAction::Quit),
-            egui::Key::ArrowUp => Some(InputAction::Command(Command::HistoryNavigatePrevious)),
-            egui::Key::ArrowDown => Some(InputAction::Command(Command::HistoryNavigateNext)),
-            egui::Key::Home => Some(InputAction::Command(Command::HistoryNavigateFirst)),
-            egui::Key::End => Some(InputAction::Command(Command::HistoryNavigateLast)),
-            egui::Key::PageUp => Some(InputAction::Command(Command::HistoryPageUp)),
-            egui::Key::PageDown => Some(InputAction::Command(Command::HistoryPageDown)),
-            egui::Key::Enter => Some(InputAction::Command(Command::HistoryViewDiff)),
-            egui::Key::Tab => Some(InputAction::Command(Command::HistoryToggleFileList)),
-            egui::Key::S => Some(InputAction::Command(Command::HistoryToggleSideBySide)),
-            egui::Key::Escape => Some(InputAction::Command(Command::CloseHistoryBrowser)),
-            egui::Key::Q if !ctrl => Some(InputAction::Command(Command::CloseHistoryBrowser)),
-            egui::Key::F => Some(InputAction::Command(Command::HistoryToggleFileList)),
-            egui::Key::B => Some(InputAction::SetBaseCommit),
-            egui::Key::C => Some(InputAction::Command(Command::HistoryClearBaseCommit)),
-            _ => None,
-        }
+        self.handle_key(key, modifiers, KeymapMode::HistoryStats)
     }
 
-    pub fn handle_history_stats_key_event(
+    fn handle_key(
         &mut self,
         key: egui::Key,
         modifiers: &egui::Modifiers,
+        mode: KeymapMode,
     ) -> Option<InputAction> {
-        #[cfg(target_os = "macos")]
-        let ctrl = modifiers.command;
-        #[cfg(not(target_os = "macos"))]
-        let ctrl = modifiers.ctrl;
-
-        match key {
-            egui::Key::Q if ctrl && self.key_bindings.quit_key == 'q' => Some(InputAction::Quit),
-            egui::Key::Escape => Some(InputAction::CloseHistoryStats),
-            egui::Key::Q if !ctrl => Some(InputAction::CloseHistoryStats),
-            _ => None,
+        let press = key_press(key, modifiers)?;
+        let resolution = self.resolver.feed(&self.keymaps, mode, press);
+        self.suppress_text = matches!(press.key, Key::Char(_))
+            && press.modifiers.is_text_input()
+            && resolution != KeyResolution::Unbound;
+
+        match resolution {
+            KeyResolution::Command(id) => action_for_binding(id, self.resolver.take_arguments()),
+            KeyResolution::Pending | KeyResolution::Cancelled | KeyResolution::Unbound => None,
         }
     }
 
     pub fn handle_text_input(&mut self, text: &str) -> Option<InputAction> {
-        if text.len() == 1 {
+        if std::mem::take(&mut self.suppress_text) {
+            None
+        } else if text.len() == 1 {
             let c = text.chars().next().unwrap();
             Some(InputAction::Command(Command::InsertChar(c)))
         } else {
@@ -255,6 +165,44 @@ impl InputHandler {
         }
     }
 
+    pub fn vim_claims_key(
+        &self,
+        key: egui::Key,
+        modifiers: &egui::Modifiers,
+        editor_state: &EditorState,
+    ) -> bool {
+        !self.resolver.is_pending()
+            && key_press(key, modifiers).is_some_and(|press| editor_state.vim_wants_key(&press))
+    }
+
+    // Characters typed for vim arrive through `handle_vim_text_input`, so
+    // shifted symbols come through as typed; only the other keys go here.
+    pub fn handle_vim_key_event(
+        &mut self,
+        key: egui::Key,
+        modifiers: &egui::Modifiers,
+    ) -> Option<InputAction> {
+        let press = key_press(key, modifiers)?;
+        if matches!(press.key, Key::Char(_)) && press.modifiers.is_text_input() {
+            return None;
+        }
+        self.suppress_text = false;
+        Some(InputAction::Command(Command::VimKey(press)))
+    }
+
+    pub fn handle_vim_text_input(&mut self, text: &str) -> Option<InputAction> {
+        if std::mem::take(&mut self.suppress_text) {
+            return None;
+        }
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some(InputAction::Command(Command::VimKey(KeyPress::plain(
+                Key::Char(c),
+            )))),
+            _ => None,
+        }
+    }
+
     #[allow(dead_code)]
     pub fn handle_mouse_click(&mut self, pos: egui::Pos2, line_height: f32) -> Option<InputAction> {
         let line = (pos.y / line_height).floor() as usize;
@@ -304,4 +252,80 @@ pub enum InputAction {
     SelectAll,
     CloseHistoryStats,
     SetBaseCommit,
+    FileTreeNewFile,
+    FileTreeNewDirectory,
+    FileTreeRename,
+    FileTreeDelete,
+    OpenCommandPalette,
+    ShowHelp,
+    RunCommand(&'static str),
+}
+
+pub fn key_press(key: egui::Key, modifiers: &egui::Modifiers) -> Option<KeyPress> {
+    #[cfg(target_os = "macos")]
+    let ctrl = modifiers.command;
+    #[cfg(not(target_os = "macos"))]
+    let ctrl = modifiers.ctrl;
+
+    let key = match key {
+        egui::Key::Minus => Key::Char('-'),
+        egui::Key::Quote => Key::Char('\''),
+        _ => {
+            KeyPress::parse(key.name())
+                .or_else(|_| KeyPress::parse(key.symbol_or_name()))
+                .ok()?
+                .key
+        }
+    };
+
+    Some(KeyPress::new(
+        key,
+        KeyModifiers {
+            ctrl,
+            alt: modifiers.alt,
+            shift: modifiers.shift,
+            meta: false,
+        },
+    ))
+}
+
+// A binding that carries its arguments runs the command straight away
+// instead of prompting for them.
+pub fn action_for_binding(id: &'static str, arguments: Vec<String>) -> Option<InputAction> {
+    if arguments.is_empty() {
+        return action_for_command(id);
+    }
+    match CommandRegistry::builtin().resolve(id, &arguments) {
+        Ok(CommandAction::Execute(command)) => Some(InputAction::Command(command)),
+        _ => action_for_command(id),
+    }
+}
+
+pub fn action_for_command(id: &'static str) -> Option<InputAction> {
+    let action = match id {
+        "app.quit" => InputAction::Quit,
+        "file.open" => InputAction::OpenFile,
+        "search.find" => InputAction::Search,
+        "search.replace" => InputAction::Replace,
+        "search.goto_line" => InputAction::GotoLine,
+        "edit.select_all" => InputAction::SelectAll,
+        "view.command_palette" => InputAction::OpenCommandPalette,
+        "help.shortcuts" => InputAction::ShowHelp,
+        "history.set_base_commit" => InputAction::SetBaseCommit,
+        "history.close_stats" => InputAction::CloseHistoryStats,
+        "file_tree.new_file" => InputAction::FileTreeNewFile,
+        "file_tree.new_folder" => InputAction::FileTreeNewDirectory,
+        "file_tree.rename" => InputAction::FileTreeRename,
+        "file_tree.delete" => InputAction::FileTreeDelete,
+        _ => {
+            let spec = CommandRegistry::builtin().get(id)?;
+            match spec.resolve(&[]) {
+                Ok(CommandAction::Execute(command)) if spec.arguments.is_empty() => {
+                    InputAction::Command(command)
+                }
+                _ => InputAction::RunCommand(id),
+            }
+        }
+    };
+    Some(action)
 }
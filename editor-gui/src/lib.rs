@@ -1,3 +1,6 @@
+pub mod file_tree_renderer;
 pub mod history_renderer;
 pub mod input;
+pub mod menu_renderer;
+pub mod palette_renderer;
 pub mod renderer;
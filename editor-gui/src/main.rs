@@ -1,14 +1,32 @@
+mod clipboard_picker_renderer;
+mod file_tree_renderer;
 mod history_renderer;
 mod input;
+mod menu_renderer;
+mod palette_renderer;
 mod renderer;
 mod stats_renderer;
+mod symbol_outline_renderer;
+mod symbol_picker_renderer;
 
+use clipboard_picker_renderer::ClipboardPickerRenderer;
 use editor_core::editor::EditorState;
+use editor_core::{
+    CommandAction, CommandRegistry, ConfigLoader, ConfigWatcher, EditorConfig, Keymaps,
+    LanguageRegistry, MacroRegisters, SnippetRegistry, SymbolPicker, SymbolScope,
+};
+#[cfg(unix)]
+use editor_core::{RemoteServer, SessionManager};
 use eframe::egui;
+use file_tree_renderer::{FileTreePromptKind, FileTreeRenderer};
 use history_renderer::HistoryRenderer;
 use input::{InputAction, InputHandler};
+use menu_renderer::MenuRenderer;
+use palette_renderer::PaletteRenderer;
 use renderer::Renderer;
 use stats_renderer::StatsRenderer;
+use symbol_outline_renderer::SymbolOutlineRenderer;
+use symbol_picker_renderer::SymbolPickerRenderer;
 
 fn main() -> eframe::Result {
     let options = eframe::NativeOptions {
@@ -31,18 +49,166 @@ struct EditorApp {
     renderer: Renderer,
     history_renderer: HistoryRenderer,
     stats_renderer: StatsRenderer,
+    file_tree_renderer: FileTreeRenderer,
+    palette_renderer: PaletteRenderer,
+    symbol_outline_renderer: SymbolOutlineRenderer,
+    symbol_picker_renderer: SymbolPickerRenderer,
+    clipboard_picker_renderer: ClipboardPickerRenderer,
+    menu_renderer: MenuRenderer,
+    show_status_bar: bool,
+    config_watcher: Option<ConfigWatcher>,
+    #[cfg(unix)]
+    remote_server: Option<RemoteServer>,
     should_quit: bool,
 }
 
+const CONFIG_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+const LANGUAGE_SERVER_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(100);
+#[cfg(unix)]
+const REMOTE_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_millis(200);
+
 impl Default for EditorApp {
     fn default() -> Self {
-        Self {
-            editor_state: EditorState::new(),
-            input_handler: InputHandler::new(),
+        let mut editor_state = EditorState::new();
+        load_languages(&mut editor_state);
+        load_snippets(&mut editor_state);
+        load_macros(&mut editor_state);
+        let keymaps = load_keymaps(&mut editor_state);
+
+        let mut app = Self {
+            editor_state,
+            input_handler: InputHandler::new().with_keymaps(keymaps),
             renderer: Renderer::new(),
             history_renderer: HistoryRenderer::new(),
             stats_renderer: StatsRenderer::new(),
+            file_tree_renderer: FileTreeRenderer::new(),
+            palette_renderer: PaletteRenderer::new(),
+            symbol_outline_renderer: SymbolOutlineRenderer::new(),
+            symbol_picker_renderer: SymbolPickerRenderer::new(),
+            clipboard_picker_renderer: ClipboardPickerRenderer::new(),
+            menu_renderer: MenuRenderer::new(),
+            show_status_bar: true,
+            config_watcher: None,
+            #[cfg(unix)]
+            remote_server: None,
             should_quit: false,
+        };
+        app.load_config();
+        #[cfg(unix)]
+        app.start_remote_server();
+        app
+    }
+}
+
+impl EditorApp {
+    fn load_config(&mut self) {
+        let loader = ConfigLoader::new();
+        if let Err(e) = loader.load().and_then(|config| self.apply_config(&config)) {
+            self.editor_state
+                .set_status_message(format!("Config error: {}", e));
+        }
+
+        match ConfigWatcher::new(loader) {
+            Ok(watcher) => self.config_watcher = Some(watcher),
+            Err(e) => self
+                .editor_state
+                .set_status_message(format!("Config error: {}", e)),
+        }
+    }
+
+    fn apply_config(&mut self, config: &EditorConfig) -> editor_core::Result<()> {
+        let profile = self.editor_state.keymap_profile();
+        self.editor_state.apply_config(config)?;
+        if self.editor_state.keymap_profile() != profile {
+            self.input_handler
+                .set_keymaps(load_keymaps(&mut self.editor_state));
+        }
+        self.renderer
+            .set_show_line_numbers(config.view.line_numbers);
+        self.show_status_bar = config.view.status_bar;
+        Ok(())
+    }
+
+    fn poll_config(&mut self, ctx: &egui::Context) {
+        let Some(watcher) = self.config_watcher.as_mut() else {
+            return;
+        };
+        ctx.request_repaint_after(CONFIG_POLL_INTERVAL);
+
+        if let Some(reloaded) = watcher.poll() {
+            match reloaded.and_then(|config| self.apply_config(&config)) {
+                Ok(()) => self
+                    .editor_state
+                    .set_status_message("Configuration reloaded".to_string()),
+                Err(e) => self
+                    .editor_state
+                    .set_status_message(format!("Config error: {}", e)),
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    fn start_remote_server(&mut self) {
+        match SessionManager::new().and_then(|sessions| RemoteServer::bind(sessions.socket_path()))
+        {
+            Ok(server) => self.remote_server = Some(server),
+            Err(e) => self
+                .editor_state
+                .set_status_message(format!("Remote control error: {}", e)),
+        }
+    }
+
+    #[cfg(unix)]
+    fn poll_remote_server(&mut self, ctx: &egui::Context) {
+        if let Some(server) = self.remote_server.as_mut() {
+            if server.poll(&mut self.editor_state) {
+                ctx.request_repaint();
+            }
+            ctx.request_repaint_after(REMOTE_POLL_INTERVAL);
+        }
+    }
+
+    fn poll_language_servers(&mut self, ctx: &egui::Context) {
+        if self.editor_state.has_language_servers() {
+            self.editor_state.poll_language_servers();
+            ctx.request_repaint_after(LANGUAGE_SERVER_POLL_INTERVAL);
+        }
+    }
+}
+
+fn load_languages(editor_state: &mut EditorState) {
+    match LanguageRegistry::load_user() {
+        Ok(languages) => editor_state.set_language_registry(languages),
+        Err(e) => editor_state.set_status_message(format!("Language config error: {}", e)),
+    }
+}
+
+fn load_snippets(editor_state: &mut EditorState) {
+    match SnippetRegistry::load_user() {
+        Ok(snippets) => editor_state.set_snippet_registry(snippets),
+        Err(e) => editor_state.set_status_message(format!("Snippet error: {}", e)),
+    }
+}
+
+fn load_macros(editor_state: &mut EditorState) {
+    match MacroRegisters::load_user() {
+        Ok(macros) => editor_state.set_macro_registers(macros),
+        Err(e) => editor_state.set_status_message(format!("Macro error: {}", e)),
+    }
+}
+
+fn load_keymaps(editor_state: &mut EditorState) -> Keymaps {
+    let profile = editor_state.keymap_profile();
+    match Keymaps::load_user_profile(profile, CommandRegistry::builtin()) {
+        Ok(keymaps) => {
+            if let Some(conflict) = keymaps.conflicts().first() {
+                editor_state.set_status_message(format!("Keymap conflict: {}", conflict));
+            }
+            keymaps
+        }
+        Err(e) => {
+            editor_state.set_status_message(format!("Keymap error: {}", e));
+            Keymaps::builtin_profile(profile).clone()
         }
     }
 }
@@ -54,8 +220,20 @@ impl eframe::App for EditorApp {
             return;
         }
 
+        self.poll_config(ctx);
+        self.poll_language_servers(ctx);
+        #[cfg(unix)]
+        self.poll_remote_server(ctx);
+
         let is_history_browser_open = self.editor_state.is_history_browser_open();
         let is_history_stats_open = self.editor_state.is_history_stats_open();
+        let is_file_tree_prompt_open = self.file_tree_renderer.is_prompt_open();
+        let is_file_tree_focused = self.editor_state.is_file_tree_focused();
+        let is_symbol_outline_focused = self.editor_state.is_symbol_outline_focused();
+        let is_overlay_open = self.palette_renderer.is_open()
+            || self.symbol_picker_renderer.is_open()
+            || self.clipboard_picker_renderer.is_open()
+            || self.menu_renderer.is_help_open();
 
         ctx.input(|i| {
             for event in &i.events {
@@ -66,25 +244,60 @@ impl eframe::App for EditorApp {
                         modifiers,
                         ..
                     } => {
+                        if is_file_tree_prompt_open || is_overlay_open {
+                            continue;
+                        }
+
                         let action = if is_history_browser_open {
                             self.input_handler
                                 .handle_history_browser_key_event(*key, modifiers)
                         } else if is_history_stats_open {
                             self.input_handler
                                 .handle_history_stats_key_event(*key, modifiers)
+                        } else if is_file_tree_focused {
+                            self.input_handler
+                                .handle_file_tree_key_event(*key, modifiers)
+                        } else if is_symbol_outline_focused {
+                            self.input_handler
+                                .handle_symbol_outline_key_event(*key, modifiers)
+                        } else if self.editor_state.is_completion_open() {
+                            self.input_handler
+                                .handle_completion_key_event(*key, modifiers)
+                        } else if self.editor_state.is_snippet_active() {
+                            self.input_handler.handle_snippet_key_event(*key, modifiers)
+                        } else if self.input_handler.vim_claims_key(
+                            *key,
+                            modifiers,
+                            &self.editor_state,
+                        ) {
+                            self.input_handler.handle_vim_key_event(*key, modifiers)
                         } else {
                             self.input_handler.handle_key_event(*key, modifiers)
                         };
 
+                        if let Some(keys) = self.input_handler.pending_keys() {
+                            self.editor_state
+                                .set_status_message(format!("{} ...", keys));
+                        }
                         if let Some(action) = action {
                             self.handle_action(action);
                         }
                     }
-                    egui::Event::Text(text) => {
-                        if !is_history_browser_open && !is_history_stats_open {
-                            if let Some(action) = self.input_handler.handle_text_input(text) {
-                                self.handle_action(action);
-                            }
+                    egui::Event::Text(text)
+                        if !is_history_browser_open
+                            && !is_history_stats_open
+                            && !is_file_tree_focused
+                            && !is_symbol_outline_focused
+                            && !is_file_tree_prompt_open
+                            && !is_overlay_open =>
+                    {
+                        let action = if self.editor_state.vim_wants_text() {
+                            self.input_handler.handle_vim_text_input(text)
+                        } else {
+                            self.input_handler.handle_text_input(text)
+                        };
+                        if let Some(action) = action {
+                            self.handle_action(action);
                         }
                     }
                     _ => {}
@@ -92,9 +305,46 @@ impl eframe::App for EditorApp {
             }
         });
 
-        egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
-            self.renderer.render_status_bar(ui, &self.editor_state);
-        });
+        if let Some(id) = self.menu_renderer.render_menu_bar(ctx) {
+            self.run_registry_command(id);
+        }
+
+        if self.show_status_bar {
+            egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
+                self.renderer.render_status_bar(ui, &self.editor_state);
+            });
+        }
+
+        self.menu_renderer.render_help(ctx);
+
+        match self.palette_renderer.render(ctx) {
+            Some(Ok(action)) => self.run_command_action(action),
+            Some(Err(e)) => self
+                .editor_state
+                .set_status_message(format!("Error: {}", e)),
+            None => {}
+        }
+
+        if let Some(command) = self.symbol_picker_renderer.render(ctx) {
+            self.handle_action(InputAction::Command(command));
+        }
+
+        if let Some(command) = self.clipboard_picker_renderer.render(ctx) {
+            self.handle_action(InputAction::Command(command));
+            self.clipboard_picker_renderer
+                .refresh(self.editor_state.clipboard_history());
+        }
+
+        if !is_history_browser_open && !is_history_stats_open {
+            let mut commands = self.file_tree_renderer.render(ctx, &self.editor_state);
+            commands.extend(self.symbol_outline_renderer.render(ctx, &self.editor_state));
+            for command in commands {
+                if let Err(e) = self.editor_state.execute_command(command) {
+                    self.editor_state
+                        .set_status_message(format!("Error: {}", e));
+                }
+            }
+        }
 
         if is_history_browser_open {
             let diff_content = self.editor_state.get_history_diff().ok().flatten();
@@ -121,7 +371,13 @@ impl eframe::App for EditorApp {
                 ui.style_mut().visuals.extreme_bg_color = egui::Color32::from_rgb(30, 30, 30);
                 ui.style_mut().visuals.override_text_color = Some(egui::Color32::WHITE);
 
-                if let Some(scroll_delta) = self.renderer.render(ui, &self.editor_state, ctx) {
+                let response = self.renderer.render(ui, &mut self.editor_state, ctx);
+                if let Some(pane) = response.focus_split {
+                    self.handle_action(InputAction::Command(editor_core::Command::FocusSplitPane(
+                        pane,
+                    )));
+                }
+                if let Some(scroll_delta) = response.scroll_delta {
                     let lines_to_scroll = scroll_delta.abs();
                     for _ in 0..lines_to_scroll {
                         if scroll_delta > 0 {
@@ -152,22 +408,20 @@ impl EditorApp {
                     self.editor_state
                         .set_status_message(format!("Error: {}", e));
                 }
+                if self.editor_state.take_quit_request() {
+                    self.should_quit = true;
+                }
             }
-            InputAction::OpenFile => {
-                self.editor_state
-                    .set_status_message("Open file dialog not yet implemented".to_string());
-            }
-            InputAction::Search => {
-                self.editor_state
-                    .set_status_message("Search dialog not yet implemented".to_string());
-            }
-            InputAction::Replace => {
-                self.editor_state
-                    .set_status_message("Replace dialog not yet implemented".to_string());
+            InputAction::RunCommand(id) => self.run_registry_command(id),
+            InputAction::OpenFile => self.open_command_prompt("file.open"),
+            InputAction::Search => self.open_command_prompt("search.find"),
+            InputAction::Replace => self.open_command_prompt("search.replace"),
+            InputAction::GotoLine => self.open_command_prompt("search.goto_line"),
+            InputAction::OpenCommandPalette => {
+                self.palette_renderer.open(&self.editor_state);
             }
-            InputAction::GotoLine => {
-                self.editor_state
-                    .set_status_message("Goto line dialog not yet implemented".to_string());
+            InputAction::ShowHelp => {
+                self.menu_renderer.show_help();
             }
             InputAction::SelectAll => {
                 self.editor_state
@@ -176,6 +430,22 @@ impl EditorApp {
             InputAction::CloseHistoryStats => {
                 self.editor_state.close_history_stats();
             }
+            InputAction::FileTreeNewFile => {
+                self.file_tree_renderer
+                    .open_prompt(FileTreePromptKind::NewFile, &self.editor_state);
+            }
+            InputAction::FileTreeNewDirectory => {
+                self.file_tree_renderer
+                    .open_prompt(FileTreePromptKind::NewDirectory, &self.editor_state);
+            }
+            InputAction::FileTreeRename => {
+                self.file_tree_renderer
+                    .open_prompt(FileTreePromptKind::Rename, &self.editor_state);
+            }
+            InputAction::FileTreeDelete => {
+                self.file_tree_renderer
+                    .open_prompt(FileTreePromptKind::ConfirmDelete, &self.editor_state);
+            }
             InputAction::SetBaseCommit => {
                 if let Some(browser) = self.editor_state.history_browser() {
                     let index = browser.selected_index();
@@ -190,4 +460,83 @@ impl EditorApp {
             }
         }
     }
+
+    fn open_command_prompt(&mut self, id: &str) {
+        if let Some(error) = self.palette_renderer.open_command(id) {
+            self.editor_state
+                .set_status_message(format!("Error: {}", error));
+        }
+    }
+
+    fn run_registry_command(&mut self, id: &str) {
+        let Some(spec) = CommandRegistry::builtin().get(id) else {
+            return;
+        };
+
+        if !spec.arguments.is_empty() {
+            self.open_command_prompt(id);
+            return;
+        }
+
+        match spec.resolve(&[]) {
+            Ok(action) => self.run_command_action(action),
+            Err(e) => self
+                .editor_state
+                .set_status_message(format!("Error: {}", e)),
+        }
+    }
+
+    fn run_command_action(&mut self, action: CommandAction) {
+        match action {
+            CommandAction::Execute(cmd) => self.handle_action(InputAction::Command(cmd)),
+            CommandAction::Frontend(id) => match id {
+                "app.quit" => self.handle_action(InputAction::Quit),
+                "edit.select_all" => self.handle_action(InputAction::SelectAll),
+                "view.command_palette" => self.handle_action(InputAction::OpenCommandPalette),
+                "help.shortcuts" => self.handle_action(InputAction::ShowHelp),
+                "view.toggle_line_numbers" => {
+                    self.renderer.toggle_line_numbers();
+                    self.editor_state.set_status_message(format!(
+                        "Line numbers {}",
+                        if self.renderer.show_line_numbers() {
+                            "enabled"
+                        } else {
+                            "disabled"
+                        }
+                    ));
+                }
+                "view.toggle_status_bar" => {
+                    self.show_status_bar = !self.show_status_bar;
+                }
+                "search.goto_symbol" => {
+                    self.symbol_picker_renderer.open(SymbolPicker::new(
+                        SymbolScope::File,
+                        self.editor_state.file_symbol_locations(),
+                    ));
+                }
+                "search.goto_symbol_in_project" => match self.editor_state.project_symbols() {
+                    Ok(locations) => self
+                        .symbol_picker_renderer
+                        .open(SymbolPicker::new(SymbolScope::Project, locations)),
+                    Err(e) => self
+                        .editor_state
+                        .set_status_message(format!("Error: {}", e)),
+                },
+                "edit.paste_from_history" => {
+                    if self.editor_state.clipboard_history().is_empty() {
+                        self.editor_state
+                            .set_status_message("Clipboard history is empty".to_string());
+                    } else {
+                        self.clipboard_picker_renderer
+                            .open(self.editor_state.clipboard_history());
+                    }
+                }
+                "help.about" => {
+                    self.editor_state
+                        .set_status_message("About: Editor-rs v0.1.0".to_string());
+                }
+                _ => {}
+            },
+        }
+    }
 }
@@ -0,0 +1,110 @@
+use editor_core::{CommandCategory, CommandRegistry};
+use eframe::egui;
+
+pub const MENU_CATEGORIES: [CommandCategory; 6] = [
+    CommandCategory::File,
+    CommandCategory::Edit,
+    CommandCategory::View,
+    CommandCategory::Search,
+    CommandCategory::Tools,
+    CommandCategory::Help,
+];
+
+pub struct MenuRenderer {
+    help_open: bool,
+}
+
+impl MenuRenderer {
+    pub fn new() -> Self {
+        Self { help_open: false }
+    }
+
+    pub fn is_help_open(&self) -> bool {
+        self.help_open
+    }
+
+    pub fn show_help(&mut self) {
+        self.help_open = true;
+    }
+
+    pub fn close_help(&mut self) {
+        self.help_open = false;
+    }
+
+    pub fn render_menu_bar(&mut self, ctx: &egui::Context) -> Option<&'static str> {
+        let registry = CommandRegistry::builtin();
+        let mut selected = None;
+
+        egui::TopBottomPanel::top("menu_bar").show(ctx, |ui| {
+            egui::menu::bar(ui, |ui| {
+                for category in MENU_CATEGORIES {
+                    ui.menu_button(category.title(), |ui| {
+                        let mut current_group = None;
+                        for spec in registry.menu_items(category) {
+                            if current_group.is_some() && current_group != spec.menu_group {
+                                ui.separator();
+                            }
+                            current_group = spec.menu_group;
+
+                            let mut button = egui::Button::new(spec.title);
+                            if let Some(keybinding) = spec.keybinding {
+                                button = button.shortcut_text(keybinding);
+                            }
+                            if ui.add(button).clicked() {
+                                selected = Some(spec.id);
+                                ui.close_menu();
+                            }
+                        }
+                    });
+                }
+            });
+        });
+
+        selected
+    }
+
+    pub fn render_help(&mut self, ctx: &egui::Context) {
+        if !self.help_open {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Keyboard Shortcuts")
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(420.0)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(480.0)
+                    .show(ui, |ui| {
+                        for (category, specs) in
+                            CommandRegistry::builtin().keybindings_by_category()
+                        {
+                            ui.strong(category.title());
+                            egui::Grid::new(category.title())
+                                .num_columns(2)
+                                .spacing([24.0, 4.0])
+                                .show(ui, |ui| {
+                                    for spec in specs {
+                                        ui.monospace(spec.keybinding.unwrap_or_default());
+                                        ui.label(spec.title);
+                                        ui.end_row();
+                                    }
+                                });
+                            ui.add_space(8.0);
+                        }
+                    });
+            });
+
+        if !open || ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.close_help();
+        }
+    }
+}
+
+impl Default for MenuRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
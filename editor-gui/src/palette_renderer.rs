@@ -0,0 +1,200 @@
+use editor_core::{
+    CommandAction, CommandPalette, CommandRegistry, EditorState, PaletteItem, PaletteOutcome,
+};
+use eframe::egui;
+
+const PALETTE_MAX_ITEMS: usize = 12;
+
+pub struct PaletteRenderer {
+    palette: Option<CommandPalette>,
+}
+
+impl PaletteRenderer {
+    pub fn new() -> Self {
+        Self { palette: None }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.palette.is_some()
+    }
+
+    #[allow(dead_code)]
+    pub fn palette(&self) -> Option<&CommandPalette> {
+        self.palette.as_ref()
+    }
+
+    pub fn open(&mut self, editor_state: &EditorState) {
+        self.palette = Some(CommandPalette::new(
+            CommandRegistry::builtin(),
+            editor_state,
+        ));
+    }
+
+    pub fn open_command(&mut self, id: &str) -> Option<String> {
+        match CommandPalette::for_command(CommandRegistry::builtin(), id) {
+            Ok(palette) => {
+                self.palette = Some(palette);
+                None
+            }
+            Err(e) => Some(e.to_string()),
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.palette = None;
+    }
+
+    pub fn confirm(&mut self) -> Option<Result<CommandAction, String>> {
+        let palette = self.palette.as_mut()?;
+        match palette.confirm(CommandRegistry::builtin()) {
+            Ok(PaletteOutcome::Continue) => None,
+            Ok(PaletteOutcome::Run(action)) => {
+                self.palette = None;
+                Some(Ok(action))
+            }
+            Err(e) => {
+                self.palette = None;
+                Some(Err(e.to_string()))
+            }
+        }
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<Result<CommandAction, String>> {
+        let palette = self.palette.as_mut()?;
+
+        let title = palette
+            .pending_title()
+            .unwrap_or("Command Palette")
+            .to_string();
+        let prompt = palette
+            .prompt()
+            .map(|argument| argument.prompt)
+            .unwrap_or(">");
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+        let mut clicked = None;
+
+        ctx.input(|i| {
+            if i.key_pressed(egui::Key::ArrowDown) {
+                palette.select_next();
+            }
+            if i.key_pressed(egui::Key::ArrowUp) {
+                palette.select_previous();
+            }
+            if i.key_pressed(egui::Key::Enter) {
+                confirmed = true;
+            }
+            if i.key_pressed(egui::Key::Escape) {
+                cancelled = true;
+            }
+        });
+
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(true)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+            .fixed_size([480.0, 0.0])
+            .show(ctx, |ui| {
+                let mut query = palette.query().to_string();
+                ui.horizontal(|ui| {
+                    ui.label(prompt);
+                    let response =
+                        ui.add(egui::TextEdit::singleline(&mut query).desired_width(f32::INFINITY));
+                    response.request_focus();
+                });
+                if query != palette.query() {
+                    palette.set_query(query);
+                }
+
+                if palette.is_prompting() {
+                    ui.weak("Enter: Confirm  |  Esc: Cancel");
+                    return;
+                }
+
+                ui.separator();
+                if palette.items().is_empty() {
+                    ui.weak("No matching commands");
+                }
+
+                let start = palette
+                    .selected_index()
+                    .saturating_sub(PALETTE_MAX_ITEMS - 1);
+                for (index, item) in palette
+                    .items()
+                    .iter()
+                    .enumerate()
+                    .skip(start)
+                    .take(PALETTE_MAX_ITEMS)
+                {
+                    let selected = index == palette.selected_index();
+                    ui.horizontal(|ui| {
+                        if ui.selectable_label(selected, item_text(item)).clicked() {
+                            clicked = Some(index);
+                        }
+                        if let Some(keybinding) = item.keybinding {
+                            ui.with_layout(
+                                egui::Layout::right_to_left(egui::Align::Center),
+                                |ui| {
+                                    ui.weak(keybinding);
+                                },
+                            );
+                        }
+                    });
+                }
+            });
+
+        if let Some(index) = clicked {
+            while palette.selected_index() < index {
+                palette.select_next();
+            }
+            while palette.selected_index() > index {
+                palette.select_previous();
+            }
+            confirmed = true;
+        }
+
+        if cancelled {
+            self.close();
+            None
+        } else if confirmed {
+            self.confirm()
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for PaletteRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn item_text(item: &PaletteItem) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    let plain = egui::TextFormat {
+        color: egui::Color32::WHITE,
+        ..Default::default()
+    };
+    let highlight = egui::TextFormat {
+        color: egui::Color32::YELLOW,
+        ..Default::default()
+    };
+    let muted = egui::TextFormat {
+        color: egui::Color32::GRAY,
+        ..Default::default()
+    };
+
+    job.append(&format!("{}: ", item.category.title()), 0.0, muted);
+    for (index, c) in item.title.chars().enumerate() {
+        let format = if item.indices.contains(&index) {
+            highlight.clone()
+        } else {
+            plain.clone()
+        };
+        job.append(&c.to_string(), 0.0, format);
+    }
+    job
+}
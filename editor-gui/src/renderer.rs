@@ -1,7 +1,17 @@
-use editor_core::EditorState;
+use editor_core::{highlight_segments, DiagnosticSeverity, EditorState, HighlightKind};
 use eframe::egui;
 use std::time::{Duration, Instant};
 
+const COMPLETION_MAX_ROWS: usize = 10;
+const HOVER_MAX_ROWS: usize = 12;
+
+#[derive(Debug, Default)]
+pub struct EditorResponse {
+    pub scroll_delta: Option<i32>,
+    pub clicked: bool,
+    pub focus_split: Option<usize>,
+}
+
 pub struct Renderer {
     show_line_numbers: bool,
     font_size: f32,
@@ -31,29 +41,107 @@ impl Renderer {
         self
     }
 
+    pub fn set_show_line_numbers(&mut self, show: bool) {
+        self.show_line_numbers = show;
+    }
+
     pub fn render(
         &mut self,
         ui: &mut egui::Ui,
-        editor_state: &EditorState,
+        editor_state: &mut EditorState,
         ctx: &egui::Context,
-    ) -> Option<i32> {
+    ) -> EditorResponse {
         self.update_cursor_blink();
         ctx.request_repaint_after(self.blink_interval / 2);
+
+        let layout = editor_state.split_layout().clone();
+        if !layout.is_split() {
+            return self.render_pane(ui, editor_state, true);
+        }
+
+        // Each split gets a title row naming its file; clicking an unfocused
+        // split focuses it.
+        let area = ui.available_rect_before_wrap();
+        let mut result = EditorResponse::default();
+        for (pane, rect) in layout.pane_rects() {
+            let focused = pane == layout.focused();
+            let pane_rect = egui::Rect::from_min_size(
+                area.min + egui::vec2(rect.x * area.width(), rect.y * area.height()),
+                egui::vec2(rect.width * area.width(), rect.height * area.height()),
+            )
+            .shrink(1.0);
+            let (title_rect, body_rect) =
+                pane_rect.split_top_bottom_at_y(pane_rect.min.y + self.line_height);
+
+            let response = editor_state.with_split_view(pane, |editor_state| {
+                self.render_split_title(ui, editor_state, title_rect, focused);
+                ui.allocate_new_ui(egui::UiBuilder::new().max_rect(body_rect), |ui| {
+                    self.render_pane(ui, editor_state, focused)
+                })
+                .inner
+            });
+            if focused {
+                result.scroll_delta = response.scroll_delta;
+            } else if response.clicked {
+                result.focus_split = Some(pane);
+            }
+        }
+        result
+    }
+
+    fn render_split_title(
+        &self,
+        ui: &egui::Ui,
+        editor_state: &EditorState,
+        rect: egui::Rect,
+        focused: bool,
+    ) {
+        let buffer = editor_state.current_buffer();
+        let name = buffer
+            .file_path()
+            .and_then(|path| path.file_name())
+            .map_or("[No Name]".to_string(), |name| {
+                name.to_string_lossy().to_string()
+            });
+        let modified = if buffer.is_modified() { " ●" } else { "" };
+        let (background, color) = if focused {
+            (egui::Color32::from_rgb(60, 90, 140), egui::Color32::WHITE)
+        } else {
+            (egui::Color32::from_rgb(45, 45, 45), egui::Color32::GRAY)
+        };
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, background);
+        painter.text(
+            rect.min + egui::vec2(6.0, 0.0),
+            egui::Align2::LEFT_TOP,
+            format!("{}{}", name, modified),
+            egui::FontId::monospace(self.font_size),
+            color,
+        );
+    }
+
+    fn render_pane(
+        &self,
+        ui: &mut egui::Ui,
+        editor_state: &EditorState,
+        focused: bool,
+    ) -> EditorResponse {
         let available_size = ui.available_size();
-        let mut scroll_delta = None;
+        let mut result = EditorResponse::default();
 
         let (line_numbers_width, text_start_x) = if self.show_line_numbers {
             let buffer = editor_state.current_buffer();
             let line_count = buffer.line_count();
             let num_digits = line_count.to_string().len().max(3);
-            let width = (num_digits as f32 + 1.0) * self.char_width + 10.0;
+            let width = (num_digits as f32 + 2.0) * self.char_width + 10.0;
             (width, width)
         } else {
             (0.0, 0.0)
         };
 
-        let viewport_top = editor_state.viewport_top();
         let viewport_height = (available_size.y / self.line_height).ceil() as usize;
+        let viewport = editor_state.get_virtual_viewport(viewport_height);
+        let lines = &viewport.line_numbers;
 
         let (response, painter) = ui.allocate_painter(
             available_size,
@@ -61,37 +149,29 @@ impl Renderer {
         );
 
         if self.show_line_numbers {
-            self.render_line_numbers(
-                &painter,
-                editor_state,
-                line_numbers_width,
-                viewport_top,
-                viewport_height,
-            );
+            self.render_line_numbers(&painter, editor_state, line_numbers_width, lines);
         }
 
-        self.render_text_buffer(
-            &painter,
-            editor_state,
-            text_start_x,
-            viewport_top,
-            viewport_height,
-        );
+        self.render_text_buffer(&painter, editor_state, text_start_x, lines, viewport_height);
 
-        self.render_selection(
-            &painter,
-            editor_state,
-            text_start_x,
-            viewport_top,
-            viewport_height,
-        );
+        self.render_selection(&painter, editor_state, text_start_x, lines);
 
-        self.render_cursor(&painter, editor_state, text_start_x, viewport_top);
+        if focused {
+            self.render_cursor(&painter, editor_state, text_start_x, lines);
 
+            if editor_state.is_completion_open() {
+                self.render_completion_popup(&painter, editor_state, text_start_x, lines);
+            } else if editor_state.hover_text().is_some() {
+                self.render_hover_popup(&painter, editor_state, text_start_x, lines);
+            }
+        }
+
+        result.clicked = response.clicked();
         if response.clicked() {
             if let Some(pos) = response.interact_pointer_pos() {
                 let relative_pos = pos - response.rect.min;
-                let _line = ((relative_pos.y / self.line_height).floor() as usize) + viewport_top;
+                let row = (relative_pos.y / self.line_height).floor() as usize;
+                let _line = lines.get(row).copied();
                 let _column = ((relative_pos.x - text_start_x) / self.char_width).floor() as usize;
             }
         }
@@ -99,11 +179,11 @@ impl Renderer {
         ui.input(|i| {
             let scroll = i.smooth_scroll_delta.y;
             if scroll != 0.0 {
-                scroll_delta = Some((scroll / self.line_height) as i32);
+                result.scroll_delta = Some((scroll / self.line_height) as i32);
             }
         });
 
-        scroll_delta
+        result
     }
 
     fn render_line_numbers(
@@ -111,30 +191,35 @@ impl Renderer {
         painter: &egui::Painter,
         editor_state: &EditorState,
         width: f32,
-        viewport_top: usize,
-        viewport_height: usize,
+        lines: &[usize],
     ) {
-        let buffer = editor_state.current_buffer();
         let font_id = egui::FontId::monospace(self.font_size);
-        let color = egui::Color32::DARK_GRAY;
 
-        for i in 0..viewport_height {
-            let line_num = viewport_top + i;
-            if line_num < buffer.line_count() {
-                let line_text = format!(
-                    "{:>width$} ",
-                    line_num + 1,
-                    width = width as usize / self.char_width as usize - 2
-                );
-                let y = i as f32 * self.line_height;
-                painter.text(
-                    egui::pos2(5.0, y),
-                    egui::Align2::LEFT_TOP,
-                    line_text,
-                    font_id.clone(),
-                    color,
-                );
-            }
+        for (i, &line_num) in lines.iter().enumerate() {
+            let color = editor_state
+                .line_diagnostic_severity(line_num)
+                .map_or(egui::Color32::DARK_GRAY, diagnostic_color);
+            let marker = if editor_state.is_line_folded(line_num) {
+                '▸'
+            } else if editor_state.is_line_foldable(line_num) {
+                '▾'
+            } else {
+                ' '
+            };
+            let line_text = format!(
+                "{:>width$}{} ",
+                line_num + 1,
+                marker,
+                width = width as usize / self.char_width as usize - 3
+            );
+            let y = i as f32 * self.line_height;
+            painter.text(
+                painter.clip_rect().min + egui::vec2(5.0, y),
+                egui::Align2::LEFT_TOP,
+                line_text,
+                font_id.clone(),
+                color,
+            );
         }
     }
 
@@ -143,7 +228,7 @@ impl Renderer {
         painter: &egui::Painter,
         editor_state: &EditorState,
         x_offset: f32,
-        viewport_top: usize,
+        lines: &[usize],
         viewport_height: usize,
     ) {
         let buffer = editor_state.current_buffer();
@@ -153,31 +238,73 @@ impl Renderer {
         let current_line_bg = egui::Color32::from_rgb(40, 40, 40);
 
         for i in 0..viewport_height {
-            let line_num = viewport_top + i;
-            if line_num < buffer.line_count() {
+            if let Some(&line_num) = lines.get(i) {
                 let y = i as f32 * self.line_height;
 
                 if line_num == cursor.line {
                     let rect = egui::Rect::from_min_size(
-                        egui::pos2(x_offset, y),
+                        painter.clip_rect().min + egui::vec2(x_offset, y),
                         egui::vec2(painter.clip_rect().width() - x_offset, self.line_height),
                     );
                     painter.rect_filled(rect, 0.0, current_line_bg);
                 }
 
                 if let Ok(line_text) = buffer.line(line_num) {
-                    painter.text(
-                        egui::pos2(x_offset, y),
-                        egui::Align2::LEFT_TOP,
-                        line_text,
-                        font_id.clone(),
+                    let folded = editor_state.is_line_folded(line_num);
+                    let line_text = if folded {
+                        line_text.trim_end_matches('\n')
+                    } else {
+                        &line_text
+                    };
+                    let spans = buffer.highlight_line(line_num);
+                    let diagnostics = editor_state.line_diagnostics(line_num);
+                    let mut job = egui::text::LayoutJob::default();
+                    let mut column = 0;
+                    for (text, kind) in highlight_segments(line_text, &spans) {
+                        let color = kind.map_or(text_color, syntax_color);
+                        if diagnostics.is_empty() {
+                            job.append(
+                                &text,
+                                0.0,
+                                egui::TextFormat::simple(font_id.clone(), color),
+                            );
+                            continue;
+                        }
+                        for (offset, ch) in text.chars().enumerate() {
+                            let mut format = egui::TextFormat::simple(font_id.clone(), color);
+                            if let Some(severity) = diagnostics
+                                .iter()
+                                .filter(|(start, end, _)| {
+                                    *start <= column + offset && column + offset < *end
+                                })
+                                .map(|(_, _, severity)| *severity)
+                                .min()
+                            {
+                                format.underline =
+                                    egui::Stroke::new(1.0, diagnostic_color(severity));
+                            }
+                            job.append(ch.encode_utf8(&mut [0; 4]), 0.0, format);
+                        }
+                        column += text.chars().count();
+                    }
+                    if folded {
+                        job.append(
+                            " ⋯",
+                            0.0,
+                            egui::TextFormat::simple(font_id.clone(), egui::Color32::DARK_GRAY),
+                        );
+                    }
+                    let galley = painter.layout_job(job);
+                    painter.galley(
+                        painter.clip_rect().min + egui::vec2(x_offset, y),
+                        galley,
                         text_color,
                     );
                 }
             } else {
                 let y = i as f32 * self.line_height;
                 painter.text(
-                    egui::pos2(x_offset, y),
+                    painter.clip_rect().min + egui::vec2(x_offset, y),
                     egui::Align2::LEFT_TOP,
                     "~",
                     font_id.clone(),
@@ -192,16 +319,14 @@ impl Renderer {
         painter: &egui::Painter,
         editor_state: &EditorState,
         x_offset: f32,
-        viewport_top: usize,
-        viewport_height: usize,
+        lines: &[usize],
     ) {
-        if let Some(selection) = editor_state.selection() {
+        let selection_bg = egui::Color32::from_rgb(60, 90, 140);
+        for selection in editor_state.selections() {
             let start = selection.start();
             let end = selection.end();
-            let selection_bg = egui::Color32::from_rgb(60, 90, 140);
 
-            for i in 0..viewport_height {
-                let line_num = viewport_top + i;
+            for (i, &line_num) in lines.iter().enumerate() {
                 if line_num < start.line || line_num > end.line {
                     continue;
                 }
@@ -228,7 +353,7 @@ impl Renderer {
                 let x2 = x_offset + (end_col as f32 * self.char_width);
 
                 let rect = egui::Rect::from_min_size(
-                    egui::pos2(x1, y),
+                    painter.clip_rect().min + egui::vec2(x1, y),
                     egui::vec2(x2 - x1, self.line_height),
                 );
                 painter.rect_filled(rect, 0.0, selection_bg);
@@ -241,22 +366,187 @@ impl Renderer {
         painter: &egui::Painter,
         editor_state: &EditorState,
         x_offset: f32,
-        viewport_top: usize,
+        lines: &[usize],
     ) {
         if !self.cursor_blink_state {
             return;
         }
 
+        for cursor in editor_state.cursors() {
+            if let Some(screen_line) = lines.iter().position(|&line| line == cursor.line) {
+                let x = x_offset + (cursor.column as f32 * self.char_width);
+                let y = screen_line as f32 * self.line_height;
+
+                let cursor_rect = egui::Rect::from_min_size(
+                    painter.clip_rect().min + egui::vec2(x, y),
+                    egui::vec2(2.0, self.line_height),
+                );
+                painter.rect_filled(cursor_rect, 0.0, egui::Color32::WHITE);
+            }
+        }
+    }
+
+    fn popup_origin(
+        &self,
+        painter: &egui::Painter,
+        editor_state: &EditorState,
+        x_offset: f32,
+        lines: &[usize],
+        column: usize,
+        height: f32,
+    ) -> Option<egui::Pos2> {
         let cursor = editor_state.cursor();
+        let screen_line = lines.iter().position(|&line| line == cursor.line)?;
+        let x = x_offset + column as f32 * self.char_width;
+        let below = (screen_line + 1) as f32 * self.line_height;
+        let y = if below + height <= painter.clip_rect().height() {
+            below
+        } else {
+            (screen_line as f32 * self.line_height - height).max(0.0)
+        };
+        Some(painter.clip_rect().min + egui::vec2(x, y))
+    }
+
+    fn render_completion_popup(
+        &self,
+        painter: &egui::Painter,
+        editor_state: &EditorState,
+        x_offset: f32,
+        lines: &[usize],
+    ) {
+        let Some(menu) = editor_state.completion_menu() else {
+            return;
+        };
+        let font_id = egui::FontId::monospace(self.font_size);
+        let rows = menu.items().len().min(COMPLETION_MAX_ROWS);
+        let offset = menu.selected_index().saturating_sub(rows.saturating_sub(1));
+        let kind_width = menu
+            .items()
+            .iter()
+            .filter_map(|entry| entry.item.kind.as_ref())
+            .map(|kind| kind.chars().count())
+            .max()
+            .unwrap_or(0);
+        let width = menu
+            .items()
+            .iter()
+            .map(|entry| {
+                let detail = entry
+                    .item
+                    .detail
+                    .as_ref()
+                    .map_or(0, |d| d.chars().count() + 2);
+                kind_width + 1 + entry.item.label.chars().count() + detail
+            })
+            .max()
+            .unwrap_or(0) as f32
+            * self.char_width
+            + 8.0;
+        let height = rows as f32 * self.line_height + 4.0;
+        let Some(origin) = self.popup_origin(
+            painter,
+            editor_state,
+            x_offset,
+            lines,
+            menu.start().column.saturating_sub(kind_width + 1),
+            height,
+        ) else {
+            return;
+        };
 
-        if cursor.line >= viewport_top {
-            let screen_line = cursor.line - viewport_top;
-            let x = x_offset + (cursor.column as f32 * self.char_width);
-            let y = screen_line as f32 * self.line_height;
+        let rect = egui::Rect::from_min_size(origin, egui::vec2(width, height));
+        painter.rect_filled(rect, 2.0, egui::Color32::from_rgb(37, 37, 38));
+        painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+
+        for (row, (index, entry)) in menu
+            .items()
+            .iter()
+            .enumerate()
+            .skip(offset)
+            .take(rows)
+            .enumerate()
+        {
+            let y = origin.y + 2.0 + row as f32 * self.line_height;
+            if index == menu.selected_index() {
+                painter.rect_filled(
+                    egui::Rect::from_min_size(
+                        egui::pos2(origin.x, y),
+                        egui::vec2(width, self.line_height),
+                    ),
+                    0.0,
+                    egui::Color32::from_rgb(60, 90, 140),
+                );
+            }
 
-            let cursor_rect =
-                egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(2.0, self.line_height));
-            painter.rect_filled(cursor_rect, 0.0, egui::Color32::WHITE);
+            let mut job = egui::text::LayoutJob::default();
+            let kind = entry.item.kind.as_deref().unwrap_or("");
+            job.append(
+                &format!("{:>kind_width$} ", kind),
+                0.0,
+                egui::TextFormat::simple(font_id.clone(), egui::Color32::GRAY),
+            );
+            for (i, ch) in entry.item.label.chars().enumerate() {
+                let color = if entry.indices.contains(&i) {
+                    egui::Color32::from_rgb(229, 192, 123)
+                } else {
+                    egui::Color32::WHITE
+                };
+                job.append(
+                    ch.encode_utf8(&mut [0; 4]),
+                    0.0,
+                    egui::TextFormat::simple(font_id.clone(), color),
+                );
+            }
+            if let Some(detail) = &entry.item.detail {
+                job.append(
+                    &format!("  {}", detail),
+                    0.0,
+                    egui::TextFormat::simple(font_id.clone(), egui::Color32::DARK_GRAY),
+                );
+            }
+            let galley = painter.layout_job(job);
+            painter.galley(egui::pos2(origin.x + 4.0, y), galley, egui::Color32::WHITE);
+        }
+    }
+
+    fn render_hover_popup(
+        &self,
+        painter: &egui::Painter,
+        editor_state: &EditorState,
+        x_offset: f32,
+        lines: &[usize],
+    ) {
+        let Some(text) = editor_state.hover_text() else {
+            return;
+        };
+        let font_id = egui::FontId::monospace(self.font_size);
+        let text: Vec<&str> = text.lines().take(HOVER_MAX_ROWS).collect();
+        let width = text
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0) as f32
+            * self.char_width
+            + 12.0;
+        let height = text.len() as f32 * self.line_height + 8.0;
+        let column = editor_state.cursor().column;
+        let Some(origin) =
+            self.popup_origin(painter, editor_state, x_offset, lines, column, height)
+        else {
+            return;
+        };
+
+        let rect = egui::Rect::from_min_size(origin, egui::vec2(width, height));
+        painter.rect_filled(rect, 2.0, egui::Color32::from_rgb(37, 37, 38));
+        painter.rect_stroke(rect, 2.0, egui::Stroke::new(1.0, egui::Color32::DARK_GRAY));
+        for (row, line) in text.iter().enumerate() {
+            painter.text(
+                origin + egui::vec2(6.0, 4.0 + row as f32 * self.line_height),
+                egui::Align2::LEFT_TOP,
+                line,
+                font_id.clone(),
+                egui::Color32::WHITE,
+            );
         }
     }
 
@@ -268,6 +558,14 @@ impl Renderer {
         }
     }
 
+    pub fn toggle_line_numbers(&mut self) {
+        self.show_line_numbers = !self.show_line_numbers;
+    }
+
+    pub fn show_line_numbers(&self) -> bool {
+        self.show_line_numbers
+    }
+
     pub fn reset_cursor_blink(&mut self) {
         self.cursor_blink_state = true;
         self.last_blink_time = Instant::now();
@@ -292,13 +590,53 @@ impl Renderer {
         };
 
         let status_message = editor_state.status_message();
-        let left_text = if status_message.is_empty() {
-            format!("{}{}{}{}", file_name, modified, read_only, overwrite)
+        let left_text = if let Some(command_line) = editor_state.vim_command_line() {
+            command_line.to_string()
+        } else if status_message.is_empty() {
+            match editor_state.diagnostic_at_cursor() {
+                Some(diagnostic) => format!(
+                    "{}{}{}{} | {}",
+                    file_name,
+                    modified,
+                    read_only,
+                    overwrite,
+                    diagnostic.summary()
+                ),
+                None => format!("{}{}{}{}", file_name, modified, read_only, overwrite),
+            }
         } else {
             status_message.to_string()
         };
+        let left_text = match editor_state.recording_macro_name() {
+            Some("") => format!("REC | {}", left_text),
+            Some(name) => format!("REC {} | {}", name, left_text),
+            None => left_text,
+        };
+        let left_text = match editor_state.vim_mode_indicator() {
+            Some(mode) => format!("{} | {}", mode, left_text),
+            None => left_text,
+        };
 
-        let right_text = format!("{}:{}", cursor.line + 1, cursor.column + 1);
+        let (errors, warnings) = editor_state.diagnostic_counts();
+        let diagnostic_info = if errors + warnings > 0 {
+            format!("E:{} W:{} | ", errors, warnings)
+        } else {
+            String::new()
+        };
+
+        let pending_keys = editor_state
+            .vim_pending_keys()
+            .map(|keys| format!("{} | ", keys))
+            .unwrap_or_default();
+
+        let right_text = format!(
+            "{}{}{} | {}:{}",
+            pending_keys,
+            diagnostic_info,
+            editor_state.language().name,
+            cursor.line + 1,
+            cursor.column + 1
+        );
 
         ui.horizontal(|ui| {
             ui.label(&left_text);
@@ -314,3 +652,32 @@ impl Default for Renderer {
         Self::new()
     }
 }
+
+pub fn diagnostic_color(severity: DiagnosticSeverity) -> egui::Color32 {
+    match severity {
+        DiagnosticSeverity::Error => egui::Color32::from_rgb(224, 108, 117),
+        DiagnosticSeverity::Warning => egui::Color32::from_rgb(229, 192, 123),
+        DiagnosticSeverity::Information => egui::Color32::from_rgb(97, 175, 239),
+        DiagnosticSeverity::Hint => egui::Color32::from_rgb(86, 182, 194),
+    }
+}
+
+pub fn syntax_color(kind: HighlightKind) -> egui::Color32 {
+    match kind {
+        HighlightKind::Comment => egui::Color32::from_rgb(106, 115, 125),
+        HighlightKind::Keyword => egui::Color32::from_rgb(198, 120, 221),
+        HighlightKind::String | HighlightKind::Literal => egui::Color32::from_rgb(152, 195, 121),
+        HighlightKind::Escape => egui::Color32::from_rgb(86, 182, 194),
+        HighlightKind::Number | HighlightKind::Constant => egui::Color32::from_rgb(209, 154, 102),
+        HighlightKind::Function => egui::Color32::from_rgb(97, 175, 239),
+        HighlightKind::Type => egui::Color32::from_rgb(229, 192, 123),
+        HighlightKind::Variable => egui::Color32::from_rgb(224, 108, 117),
+        HighlightKind::Property => egui::Color32::from_rgb(86, 182, 194),
+        HighlightKind::Attribute => egui::Color32::from_rgb(209, 154, 102),
+        HighlightKind::Operator | HighlightKind::Punctuation => {
+            egui::Color32::from_rgb(171, 178, 191)
+        }
+        HighlightKind::Heading => egui::Color32::from_rgb(97, 175, 239),
+        HighlightKind::Link => egui::Color32::from_rgb(86, 182, 194),
+    }
+}
@@ -0,0 +1,99 @@
+use editor_core::{Command, EditorState};
+use eframe::egui;
+
+pub struct SymbolOutlineRenderer;
+
+impl SymbolOutlineRenderer {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context, editor_state: &EditorState) -> Vec<Command> {
+        let mut commands = Vec::new();
+
+        let outline = match editor_state.symbol_outline() {
+            Some(outline) => outline,
+            None => return commands,
+        };
+        let focused = editor_state.is_symbol_outline_focused();
+
+        egui::SidePanel::right("symbol_outline")
+            .resizable(true)
+            .default_width(240.0)
+            .show(ctx, |ui| {
+                ui.style_mut().visuals.override_text_color = Some(egui::Color32::WHITE);
+
+                ui.horizontal(|ui| {
+                    ui.strong("Outline");
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.small_button("×").on_hover_text("Close").clicked() {
+                            commands.push(Command::ToggleSymbolOutline);
+                        }
+                    });
+                });
+                ui.separator();
+
+                if outline.entries().is_empty() {
+                    ui.weak("No symbols");
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (index, entry) in outline.entries().iter().enumerate() {
+                        let icon = if !entry.has_children {
+                            "  "
+                        } else if entry.expanded {
+                            "▾ "
+                        } else {
+                            "▸ "
+                        };
+
+                        let mut job = egui::text::LayoutJob::default();
+                        let mono = egui::FontId::monospace(13.0);
+                        job.append(
+                            &format!("{}{}", "  ".repeat(entry.symbol.depth), icon),
+                            0.0,
+                            egui::TextFormat::simple(mono.clone(), egui::Color32::GRAY),
+                        );
+                        job.append(
+                            &format!("{} ", entry.symbol.kind.label()),
+                            0.0,
+                            egui::TextFormat::simple(mono.clone(), egui::Color32::LIGHT_BLUE),
+                        );
+                        job.append(
+                            &entry.symbol.name,
+                            0.0,
+                            egui::TextFormat::simple(mono, egui::Color32::WHITE),
+                        );
+
+                        let selected = index == outline.selected_index();
+                        let response = ui.selectable_label(selected, job);
+                        if selected && focused {
+                            response.scroll_to_me(None);
+                        }
+
+                        if response.clicked() {
+                            commands.push(Command::FocusSymbolOutline);
+                            commands.push(Command::SymbolOutlineSelect(index));
+                            commands.push(Command::SymbolOutlineActivate);
+                        }
+                        if response.double_clicked() && entry.has_children {
+                            commands.push(Command::SymbolOutlineSelect(index));
+                            commands.push(if entry.expanded {
+                                Command::SymbolOutlineCollapse
+                            } else {
+                                Command::SymbolOutlineExpand
+                            });
+                        }
+                    }
+                });
+            });
+
+        commands
+    }
+}
+
+impl Default for SymbolOutlineRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
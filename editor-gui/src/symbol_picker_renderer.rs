@@ -0,0 +1,160 @@
+use editor_core::{Command, SymbolPicker, SymbolPickerItem};
+use eframe::egui;
+
+const PICKER_MAX_ITEMS: usize = 12;
+
+pub struct SymbolPickerRenderer {
+    picker: Option<SymbolPicker>,
+}
+
+impl SymbolPickerRenderer {
+    pub fn new() -> Self {
+        Self { picker: None }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.picker.is_some()
+    }
+
+    #[allow(dead_code)]
+    pub fn picker(&self) -> Option<&SymbolPicker> {
+        self.picker.as_ref()
+    }
+
+    pub fn open(&mut self, picker: SymbolPicker) {
+        self.picker = Some(picker);
+    }
+
+    pub fn close(&mut self) {
+        self.picker = None;
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<Command> {
+        let picker = self.picker.as_mut()?;
+
+        let mut confirmed = false;
+        let mut cancelled = false;
+        let mut clicked = None;
+
+        ctx.input(|i| {
+            if i.key_pressed(egui::Key::ArrowDown) {
+                picker.select_next();
+            }
+            if i.key_pressed(egui::Key::ArrowUp) {
+                picker.select_previous();
+            }
+            if i.key_pressed(egui::Key::Enter) {
+                confirmed = true;
+            }
+            if i.key_pressed(egui::Key::Escape) {
+                cancelled = true;
+            }
+        });
+
+        egui::Window::new(picker.title())
+            .collapsible(false)
+            .resizable(false)
+            .title_bar(true)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 40.0])
+            .fixed_size([480.0, 0.0])
+            .show(ctx, |ui| {
+                let mut query = picker.query().to_string();
+                ui.horizontal(|ui| {
+                    ui.label("@");
+                    let response =
+                        ui.add(egui::TextEdit::singleline(&mut query).desired_width(f32::INFINITY));
+                    response.request_focus();
+                });
+                if query != picker.query() {
+                    picker.set_query(query);
+                }
+
+                ui.separator();
+                if picker.items().is_empty() {
+                    ui.weak("No matching symbols");
+                }
+
+                let start = picker.selected_index().saturating_sub(PICKER_MAX_ITEMS - 1);
+                for (index, item) in picker
+                    .items()
+                    .iter()
+                    .enumerate()
+                    .skip(start)
+                    .take(PICKER_MAX_ITEMS)
+                {
+                    let selected = index == picker.selected_index();
+                    ui.horizontal(|ui| {
+                        if ui.selectable_label(selected, item_text(item)).clicked() {
+                            clicked = Some(index);
+                        }
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            ui.weak(item_location(item));
+                        });
+                    });
+                }
+            });
+
+        if let Some(index) = clicked {
+            picker.select_index(index);
+            confirmed = true;
+        }
+
+        if cancelled {
+            self.close();
+            None
+        } else if confirmed {
+            let command = picker.confirm();
+            self.close();
+            command
+        } else {
+            None
+        }
+    }
+}
+
+impl Default for SymbolPickerRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn item_text(item: &SymbolPickerItem) -> egui::text::LayoutJob {
+    let mut job = egui::text::LayoutJob::default();
+    let plain = egui::TextFormat {
+        color: egui::Color32::WHITE,
+        ..Default::default()
+    };
+    let highlight = egui::TextFormat {
+        color: egui::Color32::YELLOW,
+        ..Default::default()
+    };
+    let muted = egui::TextFormat {
+        color: egui::Color32::GRAY,
+        ..Default::default()
+    };
+
+    let symbol = &item.location.symbol;
+    job.append(&format!("{} ", symbol.kind.label()), 0.0, muted);
+    for (index, c) in symbol.name.chars().enumerate() {
+        let format = if item.indices.contains(&index) {
+            highlight.clone()
+        } else {
+            plain.clone()
+        };
+        job.append(&c.to_string(), 0.0, format);
+    }
+    job
+}
+
+fn item_location(item: &SymbolPickerItem) -> String {
+    let line = item.location.symbol.line + 1;
+    match item
+        .location
+        .path
+        .as_ref()
+        .and_then(|path| path.file_name())
+    {
+        Some(name) => format!("{}:{}", name.to_string_lossy(), line),
+        None => format!(":{}", line),
+    }
+}
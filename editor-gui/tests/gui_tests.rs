@@ -468,3 +468,92 @@ fn test_gui_history_renderer_file_list_height() {
     let history_renderer = HistoryRenderer::new();
     assert_eq!(history_renderer.file_list_height(), 150.0);
 }
+
+#[test]
+fn test_file_tree_renderer_prompts() {
+    use editor_gui::file_tree_renderer::{FileTreePromptKind, FileTreeRenderer};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(temp_dir.path().join("notes.txt"), "").unwrap();
+
+    let mut editor_state = EditorState::new();
+    editor_state
+        .open_file_tree(temp_dir.path().to_path_buf())
+        .unwrap();
+
+    let mut renderer = FileTreeRenderer::new();
+    assert!(!renderer.is_prompt_open());
+
+    renderer.open_prompt(FileTreePromptKind::Rename, &editor_state);
+    assert_eq!(renderer.prompt().unwrap().input, "notes.txt");
+    assert!(matches!(
+        renderer.confirm_prompt(),
+        Some(Command::FileTreeRename(name)) if name == "notes.txt"
+    ));
+    assert!(!renderer.is_prompt_open());
+
+    renderer.open_prompt(FileTreePromptKind::NewFile, &editor_state);
+    assert_eq!(renderer.prompt().unwrap().input, "");
+    renderer.close_prompt();
+    assert!(renderer.confirm_prompt().is_none());
+}
+
+#[test]
+fn test_palette_renderer_prompts_for_arguments() {
+    use editor_core::CommandAction;
+    use editor_gui::palette_renderer::PaletteRenderer;
+
+    let editor_state = EditorState::new();
+    let mut renderer = PaletteRenderer::new();
+    assert!(!renderer.is_open());
+
+    renderer.open(&editor_state);
+    assert!(renderer.is_open());
+    assert!(!renderer.palette().unwrap().items().is_empty());
+    renderer.close();
+    assert!(!renderer.is_open());
+
+    assert!(renderer.open_command("search.goto_line").is_none());
+    assert!(renderer.palette().unwrap().is_prompting());
+    assert!(renderer.confirm().unwrap().is_err());
+    assert!(!renderer.is_open());
+
+    assert!(renderer.open_command("does.not_exist").is_some());
+    assert!(renderer.open_command("file.save").is_none());
+    assert!(matches!(
+        renderer.confirm(),
+        Some(Ok(CommandAction::Execute(Command::Save)))
+    ));
+}
+
+#[test]
+fn test_menu_renderer_categories_have_items() {
+    use editor_core::CommandRegistry;
+    use editor_gui::menu_renderer::{MenuRenderer, MENU_CATEGORIES};
+
+    for category in MENU_CATEGORIES {
+        assert!(!CommandRegistry::builtin().menu_items(category).is_empty());
+    }
+
+    let mut renderer = MenuRenderer::new();
+    assert!(!renderer.is_help_open());
+    renderer.show_help();
+    assert!(renderer.is_help_open());
+    renderer.close_help();
+    assert!(!renderer.is_help_open());
+}
+
+#[test]
+fn test_syntax_colors_distinguish_kinds() {
+    use editor_core::HighlightKind;
+    use editor_gui::renderer::syntax_color;
+
+    assert_ne!(
+        syntax_color(HighlightKind::Keyword),
+        syntax_color(HighlightKind::Comment)
+    );
+    assert_ne!(
+        syntax_color(HighlightKind::String),
+        syntax_color(HighlightKind::Function)
+    );
+}
@@ -1,4 +1,4 @@
-use editor_core::{CaseMode, Command};
+use editor_core::{CaseMode, Command, CommandRegistry, EditorState, Key, Keymaps};
 use editor_gui::input::{InputAction, InputHandler, KeyBindings};
 use eframe::egui;
 
@@ -635,3 +635,238 @@ fn test_handle_scroll_zero() {
     let action = handler.handle_scroll(0.0);
     assert!(action.is_none());
 }
+
+#[test]
+fn test_toggle_file_tree_shortcut() {
+    let mut handler = InputHandler::new();
+    let action = handler.handle_key_event(egui::Key::E, &cmd_modifiers());
+    assert!(matches!(
+        action,
+        Some(InputAction::Command(Command::ToggleFileTree))
+    ));
+
+    let action = handler.handle_key_event(egui::Key::E, &cmd_shift_modifiers());
+    assert!(matches!(
+        action,
+        Some(InputAction::Command(Command::FocusFileTree))
+    ));
+}
+
+#[test]
+fn test_file_tree_navigation_keys() {
+    let mut handler = InputHandler::new();
+    let none = egui::Modifiers::default();
+
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::ArrowDown, &none),
+        Some(InputAction::Command(Command::FileTreeNavigateNext))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::ArrowUp, &none),
+        Some(InputAction::Command(Command::FileTreeNavigatePrevious))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::ArrowRight, &none),
+        Some(InputAction::Command(Command::FileTreeExpand))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::ArrowLeft, &none),
+        Some(InputAction::Command(Command::FileTreeCollapse))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::Enter, &none),
+        Some(InputAction::Command(Command::FileTreeActivate))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::Escape, &none),
+        Some(InputAction::Command(Command::FocusEditor))
+    ));
+}
+
+#[test]
+fn test_file_tree_file_operation_keys() {
+    let mut handler = InputHandler::new();
+    let none = egui::Modifiers::default();
+    let shift = egui::Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::A, &none),
+        Some(InputAction::FileTreeNewFile)
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::A, &shift),
+        Some(InputAction::FileTreeNewDirectory)
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::F2, &none),
+        Some(InputAction::FileTreeRename)
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::Delete, &none),
+        Some(InputAction::FileTreeDelete)
+    ));
+}
+
+#[test]
+fn test_command_palette_and_help_shortcuts() {
+    let mut handler = InputHandler::new();
+
+    assert!(matches!(
+        handler.handle_key_event(egui::Key::P, &cmd_shift_modifiers()),
+        Some(InputAction::OpenCommandPalette)
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::P, &cmd_shift_modifiers()),
+        Some(InputAction::OpenCommandPalette)
+    ));
+    assert!(matches!(
+        handler.handle_key_event(egui::Key::F1, &egui::Modifiers::default()),
+        Some(InputAction::ShowHelp)
+    ));
+}
+
+#[test]
+fn test_custom_keymap_chord_suppresses_text() {
+    let keymaps = Keymaps::from_toml_str(
+        "[editor]\n\"Ctrl+K C\" = \"tools.toggle_comment\"\n",
+        CommandRegistry::builtin(),
+    )
+    .unwrap();
+    let mut handler = InputHandler::new().with_keymaps(keymaps);
+
+    assert!(handler
+        .handle_key_event(egui::Key::K, &cmd_modifiers())
+        .is_none());
+    assert_eq!(handler.pending_keys().as_deref(), Some("Ctrl+K"));
+    assert!(matches!(
+        handler.handle_key_event(egui::Key::C, &egui::Modifiers::default()),
+        Some(InputAction::Command(Command::ToggleLineComment))
+    ));
+    assert!(handler.handle_text_input("c").is_none());
+    assert!(matches!(
+        handler.handle_text_input("c"),
+        Some(InputAction::Command(Command::InsertChar('c')))
+    ));
+}
+
+#[test]
+fn test_custom_quit_key_binding() {
+    let mut handler = InputHandler::new().with_key_bindings(KeyBindings {
+        quit_modifier: true,
+        quit_key: 'x',
+    });
+
+    assert!(matches!(
+        handler.handle_key_event(egui::Key::X, &cmd_modifiers()),
+        Some(InputAction::Quit)
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::X, &cmd_modifiers()),
+        Some(InputAction::Quit)
+    ));
+    assert!(!matches!(
+        handler.handle_key_event(egui::Key::Q, &cmd_modifiers()),
+        Some(InputAction::Quit)
+    ));
+}
+
+#[test]
+fn test_punctuation_keys_resolve_through_keymap() {
+    let mut handler = InputHandler::new();
+
+    assert!(matches!(
+        handler.handle_file_tree_key_event(egui::Key::Period, &egui::Modifiers::default()),
+        Some(InputAction::Command(Command::FileTreeToggleHidden))
+    ));
+    assert!(matches!(
+        handler.handle_key_event(egui::Key::Slash, &cmd_shift_modifiers()),
+        Some(InputAction::Command(Command::ToggleBlockComment))
+    ));
+}
+
+#[test]
+fn test_snippet_keys_walk_tabstops() {
+    let mut handler = InputHandler::new();
+    let none = egui::Modifiers::default();
+    let shift = egui::Modifiers {
+        shift: true,
+        ..Default::default()
+    };
+
+    assert!(matches!(
+        handler.handle_snippet_key_event(egui::Key::Tab, &none),
+        Some(InputAction::Command(Command::NextTabstop))
+    ));
+    assert!(matches!(
+        handler.handle_snippet_key_event(egui::Key::Tab, &shift),
+        Some(InputAction::Command(Command::PreviousTabstop))
+    ));
+    assert!(matches!(
+        handler.handle_snippet_key_event(egui::Key::Escape, &none),
+        Some(InputAction::Command(Command::ExitSnippet))
+    ));
+    assert!(matches!(
+        handler.handle_snippet_key_event(egui::Key::ArrowLeft, &none),
+        Some(InputAction::Command(Command::MoveCursorLeft))
+    ));
+}
+
+#[test]
+fn test_completion_keys_fall_back_to_editor() {
+    let mut handler = InputHandler::new();
+    let none = egui::Modifiers::default();
+
+    assert!(matches!(
+        handler.handle_completion_key_event(egui::Key::ArrowDown, &none),
+        Some(InputAction::Command(Command::CompletionNext))
+    ));
+    assert!(matches!(
+        handler.handle_completion_key_event(egui::Key::Enter, &none),
+        Some(InputAction::Command(Command::CompletionAccept))
+    ));
+    assert!(matches!(
+        handler.handle_completion_key_event(egui::Key::Escape, &none),
+        Some(InputAction::Command(Command::CompletionCancel))
+    ));
+    assert!(matches!(
+        handler.handle_completion_key_event(egui::Key::ArrowLeft, &none),
+        Some(InputAction::Command(Command::MoveCursorLeft))
+    ));
+    assert!(handler
+        .handle_completion_key_event(egui::Key::A, &none)
+        .is_none());
+    assert!(matches!(
+        handler.handle_text_input("a"),
+        Some(InputAction::Command(Command::InsertChar('a')))
+    ));
+    assert!(matches!(
+        handler.handle_key_event(egui::Key::Space, &cmd_modifiers()),
+        Some(InputAction::Command(Command::TriggerCompletion))
+    ));
+}
+
+#[test]
+fn test_vim_takes_special_keys_and_typed_text() {
+    let mut handler = InputHandler::new();
+    let mut editor = EditorState::new();
+    let none = egui::Modifiers::default();
+    assert!(!handler.vim_claims_key(egui::Key::D, &none, &editor));
+    assert!(!editor.vim_wants_text());
+
+    editor.set_vim_enabled(true);
+    assert!(editor.vim_wants_text());
+    assert!(handler.vim_claims_key(egui::Key::D, &none, &editor));
+    assert!(handler.handle_vim_key_event(egui::Key::D, &none).is_none());
+    assert!(matches!(
+        handler.handle_vim_key_event(egui::Key::Escape, &none),
+        Some(InputAction::Command(Command::VimKey(_)))
+    ));
+    assert!(matches!(
+        handler.handle_vim_text_input("$"),
+        Some(InputAction::Command(Command::VimKey(press))) if press.key == Key::Char('$')
+    ));
+    assert!(handler.handle_vim_text_input("ab").is_none());
+}
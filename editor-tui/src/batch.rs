@@ -0,0 +1,91 @@
+use editor_core::{
+    BatchScript, ConfigLoader, EditorError, EditorState, LanguageRegistry, MacroRegisters, Result,
+};
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScriptSource {
+    File(PathBuf),
+    Stdin,
+}
+
+// `--script FILE` reads the script from a file and `--batch` from stdin; any
+// other arguments are files to run it over, after those the script lists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchArgs {
+    pub source: ScriptSource,
+    pub files: Vec<PathBuf>,
+}
+
+impl BatchArgs {
+    // Returns None when neither flag is given, leaving the arguments to the
+    // interactive editor.
+    pub fn parse<I>(args: I) -> Result<Option<Self>>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut source = None;
+        let mut files = Vec::new();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let next_source = match arg.as_str() {
+                "--script" => match args.next() {
+                    Some(path) => ScriptSource::File(PathBuf::from(path)),
+                    None => return Err(usage_error("--script needs a file")),
+                },
+                "--batch" => ScriptSource::Stdin,
+                "--" => {
+                    files.extend(args.by_ref().map(PathBuf::from));
+                    continue;
+                }
+                flag if flag.starts_with("--") => {
+                    return Err(usage_error(&format!("unknown option '{}'", flag)));
+                }
+                _ => {
+                    files.push(PathBuf::from(arg));
+                    continue;
+                }
+            };
+            if source.is_some() {
+                return Err(usage_error("only one of --script and --batch can be given"));
+            }
+            source = Some(next_source);
+        }
+
+        Ok(source.map(|source| Self { source, files }))
+    }
+
+    pub fn load_script(&self) -> Result<BatchScript> {
+        let mut script = match &self.source {
+            ScriptSource::File(path) => BatchScript::load_from_file(path)?,
+            ScriptSource::Stdin => {
+                let mut content = String::new();
+                io::stdin().read_to_string(&mut content)?;
+                BatchScript::from_json_str(&content)
+                    .map_err(|e| EditorError::Parse(format!("<stdin>: {}", e)))?
+            }
+        };
+        script.files.extend(self.files.iter().cloned());
+        Ok(script)
+    }
+
+    // Runs with the user's languages, macros and settings, as the
+    // interactive editor would, but any problem loading them is fatal.
+    pub fn run(&self) -> Result<()> {
+        let script = self.load_script()?;
+        let mut editor_state = EditorState::new();
+        editor_state.set_language_registry(LanguageRegistry::load_user()?);
+        editor_state.set_macro_registers(MacroRegisters::load_user()?);
+        editor_state.apply_config(&ConfigLoader::new().load()?)?;
+        script.run(&mut editor_state)
+    }
+}
+
+fn usage_error(message: &str) -> EditorError {
+    EditorError::InvalidOperation(format!(
+        "{}\nusage: editor-tui (--script FILE | --batch) [FILE...]",
+        message
+    ))
+}
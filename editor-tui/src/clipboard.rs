@@ -0,0 +1,50 @@
+use crossterm::{execute, Command};
+use editor_core::ClipboardManager;
+use std::fmt;
+use std::io;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Asks the terminal to put text on the system clipboard with an OSC 52
+// escape sequence. This reaches the local clipboard from a remote shell,
+// as long as the terminal allows it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CopyToClipboard<'a>(pub &'a str);
+
+impl Command for CopyToClipboard<'_> {
+    fn write_ansi(&self, f: &mut impl fmt::Write) -> fmt::Result {
+        write!(f, "\x1b]52;c;{}\x07", base64_encode(self.0.as_bytes()))
+    }
+
+    #[cfg(windows)]
+    fn execute_winapi(&self) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "OSC 52 needs a terminal that understands ANSI sequences",
+        ))
+    }
+}
+
+// Lets the editor's clipboard send OSC 52 sequences to this terminal.
+pub fn use_terminal_clipboard() {
+    ClipboardManager::set_terminal_writer(|text| execute!(io::stdout(), CopyToClipboard(text)));
+}
+
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let index = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[index as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
@@ -1,3 +1,4 @@
+use editor_core::CommandRegistry;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -12,6 +13,10 @@ pub enum DialogType {
     Replace,
     GotoLine,
     Help,
+    NewFile,
+    NewDirectory,
+    Rename,
+    ConfirmDelete,
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +26,7 @@ pub struct Dialog {
     pub replace_input: Option<String>,
     pub cursor_position: usize,
     pub editing_replace: bool,
+    pub scroll_offset: usize,
 }
 
 impl Dialog {
@@ -35,10 +41,28 @@ impl Dialog {
             },
             cursor_position: 0,
             editing_replace: false,
+            scroll_offset: 0,
         }
     }
 
+    pub fn with_input(dialog_type: DialogType, input: String) -> Self {
+        let mut dialog = Self::new(dialog_type);
+        dialog.cursor_position = input.len();
+        dialog.input = input;
+        dialog
+    }
+
+    fn is_read_only(&self) -> bool {
+        matches!(
+            self.dialog_type,
+            DialogType::ConfirmDelete | DialogType::Help
+        )
+    }
+
     pub fn insert_char(&mut self, c: char) {
+        if self.is_read_only() {
+            return;
+        }
         if self.editing_replace {
             if let Some(ref mut replace) = self.replace_input {
                 replace.insert(self.cursor_position, c);
@@ -51,6 +75,9 @@ impl Dialog {
     }
 
     pub fn delete_char(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
         if self.editing_replace {
             if let Some(ref mut replace) = self.replace_input {
                 if self.cursor_position < replace.len() {
@@ -63,6 +90,9 @@ impl Dialog {
     }
 
     pub fn backspace(&mut self) {
+        if self.is_read_only() {
+            return;
+        }
         if self.editing_replace {
             if let Some(ref mut replace) = self.replace_input {
                 if self.cursor_position > 0 {
@@ -109,22 +139,39 @@ impl Dialog {
     }
 
     pub fn switch_field(&mut self) {
-        if self.replace_input.is_some() {
+        if let Some(replace) = &self.replace_input {
             self.editing_replace = !self.editing_replace;
             self.cursor_position = if self.editing_replace {
-                self.replace_input.as_ref().unwrap().len()
+                replace.len()
             } else {
                 self.input.len()
             };
         }
     }
 
+    pub fn scroll_up(&mut self, lines: usize) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(lines);
+    }
+
+    pub fn scroll_down(&mut self, lines: usize) {
+        if self.dialog_type == DialogType::Help {
+            let max_offset = help_lines(CommandRegistry::builtin())
+                .len()
+                .saturating_sub(1);
+            self.scroll_offset = (self.scroll_offset + lines).min(max_offset);
+        }
+    }
+
     pub fn title(&self) -> &str {
         match self.dialog_type {
             DialogType::Search => "Search",
             DialogType::Replace => "Replace",
             DialogType::GotoLine => "Go to Line",
             DialogType::Help => "Keyboard Shortcuts",
+            DialogType::NewFile => "New File",
+            DialogType::NewDirectory => "New Folder",
+            DialogType::Rename => "Rename",
+            DialogType::ConfirmDelete => "Delete",
         }
     }
 
@@ -134,7 +181,7 @@ impl Dialog {
             _ => 60,
         };
         let dialog_height = match self.dialog_type {
-            DialogType::Help => 30,
+            DialogType::Help => 36,
             DialogType::Replace => 10,
             _ => 7,
         };
@@ -153,7 +200,12 @@ impl Dialog {
         frame.render_widget(block, dialog_area);
 
         match self.dialog_type {
-            DialogType::Search | DialogType::GotoLine => {
+            DialogType::Search
+            | DialogType::GotoLine
+            | DialogType::NewFile
+            | DialogType::NewDirectory
+            | DialogType::Rename
+            | DialogType::ConfirmDelete => {
                 self.render_single_input(frame, inner_area);
             }
             DialogType::Replace => {
@@ -178,6 +230,10 @@ impl Dialog {
         let label = match self.dialog_type {
             DialogType::Search => "Search for:",
             DialogType::GotoLine => "Line number:",
+            DialogType::NewFile => "File name:",
+            DialogType::NewDirectory => "Folder name:",
+            DialogType::Rename => "New name:",
+            DialogType::ConfirmDelete => "Delete this entry?",
             _ => "",
         };
 
@@ -191,13 +247,18 @@ impl Dialog {
         );
         frame.render_widget(input_paragraph, chunks[1]);
 
-        if chunks[1].x + (self.cursor_position as u16) < chunks[1].x + chunks[1].width {
+        if !self.is_read_only()
+            && chunks[1].x + (self.cursor_position as u16) < chunks[1].x + chunks[1].width
+        {
             frame.set_cursor(chunks[1].x + (self.cursor_position as u16), chunks[1].y);
         }
 
         let help_text = match self.dialog_type {
             DialogType::Search => "Enter: Search  |  Esc: Cancel",
             DialogType::GotoLine => "Enter: Go  |  Esc: Cancel",
+            DialogType::NewFile | DialogType::NewDirectory => "Enter: Create  |  Esc: Cancel",
+            DialogType::Rename => "Enter: Rename  |  Esc: Cancel",
+            DialogType::ConfirmDelete => "Enter: Delete  |  Esc: Cancel",
             _ => "",
         };
 
@@ -263,43 +324,8 @@ impl Dialog {
     }
 
     fn render_help(&self, frame: &mut Frame, area: Rect) {
-        let shortcuts = vec![
-            ("", "File Operations", true),
-            ("Ctrl+N", "New file", false),
-            ("Ctrl+O", "Open file", false),
-            ("Ctrl+S", "Save file", false),
-            ("Ctrl+W", "Close file", false),
-            ("Ctrl+Q", "Quit", false),
-            ("", "", false),
-            ("", "Editing", true),
-            ("Ctrl+Z", "Undo", false),
-            ("Ctrl+Y / Ctrl+Shift+Z", "Redo", false),
-            ("Ctrl+C", "Copy", false),
-            ("Ctrl+X", "Cut", false),
-            ("Ctrl+V", "Paste", false),
-            ("Ctrl+D", "Duplicate line", false),
-            ("Ctrl+K", "Delete line", false),
-            ("Ctrl+J", "Join lines", false),
-            ("", "", false),
-            ("", "Navigation", true),
-            ("Ctrl+F", "Search", false),
-            ("Ctrl+H", "Replace", false),
-            ("Ctrl+G", "Go to line", false),
-            ("F3 / Shift+F3", "Next/Previous match", false),
-            ("Ctrl+B", "Jump to matching bracket", false),
-            ("", "", false),
-            ("", "Menu", true),
-            (
-                "Alt+F/E/V/S/T/H",
-                "Open File/Edit/View/Search/Tools/Help menu",
-                false,
-            ),
-            ("", "", false),
-            ("Press Esc to close", "", false),
-        ];
-
         let mut lines = Vec::new();
-        for (key, desc, is_header) in shortcuts {
+        for (key, desc, is_header) in help_lines(CommandRegistry::builtin()) {
             if is_header {
                 lines.push(Line::from(Span::styled(
                     desc,
@@ -324,11 +350,69 @@ impl Dialog {
             }
         }
 
-        let paragraph = Paragraph::new(lines);
+        let paragraph = Paragraph::new(lines).scroll((self.scroll_offset as u16, 0));
         frame.render_widget(paragraph, area);
     }
 }
 
+pub fn help_lines(registry: &CommandRegistry) -> Vec<(String, String, bool)> {
+    let mut lines = Vec::new();
+
+    for (category, specs) in registry.keybindings_by_category() {
+        lines.push((String::new(), category.title().to_string(), true));
+        for spec in specs {
+            if let Some(keybinding) = spec.keybinding {
+                lines.push((keybinding.to_string(), spec.title.to_string(), false));
+            }
+        }
+        lines.push((String::new(), String::new(), false));
+    }
+
+    let panel_sections = [
+        (
+            "File Tree Panel",
+            vec![
+                ("Up/Down or k/j", "Move selection"),
+                ("Left/Right or h/l", "Collapse / Expand"),
+                ("Enter", "Open file or toggle folder"),
+                ("a / A", "New file / New folder"),
+                ("r / d", "Rename / Delete"),
+                ("Esc / Tab", "Return to editor"),
+            ],
+        ),
+        (
+            "Command Palette",
+            vec![
+                ("Up/Down", "Move selection"),
+                ("Enter", "Run command or confirm argument"),
+                ("Esc", "Close palette"),
+            ],
+        ),
+        (
+            "Menu",
+            vec![(
+                "Alt+F/E/V/S/T/H",
+                "Open File/Edit/View/Search/Tools/Help menu",
+            )],
+        ),
+    ];
+
+    for (title, entries) in panel_sections {
+        lines.push((String::new(), title.to_string(), true));
+        for (key, desc) in entries {
+            lines.push((key.to_string(), desc.to_string(), false));
+        }
+        lines.push((String::new(), String::new(), false));
+    }
+
+    lines.push((
+        String::new(),
+        "Up/Down/PageUp/PageDown to scroll, Esc to close".to_string(),
+        false,
+    ));
+    lines
+}
+
 fn centered_rect(width: u16, height: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
         .direction(Direction::Vertical)
@@ -1,7 +1,12 @@
 use crossterm::event::{
     Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
-use editor_core::{CaseMode, Command, CursorPosition};
+use editor_core::{
+    Command, CommandAction, CommandRegistry, CursorPosition, EditorState, Key, KeyPress,
+    KeyResolution, KeySequence, KeySequenceResolver, KeymapMode, Keymaps,
+};
+
+const DIALOG_PAGE_LINES: usize = 10;
 
 pub struct KeyBindings {
     pub quit_key: KeyCode,
@@ -16,7 +21,8 @@ impl Default for KeyBindings {
 }
 
 pub struct InputHandler {
-    key_bindings: KeyBindings,
+    keymaps: Keymaps,
+    resolver: KeySequenceResolver,
     mouse_enabled: bool,
     drag_start: Option<CursorPosition>,
 }
@@ -24,22 +30,44 @@ pub struct InputHandler {
 impl InputHandler {
     pub fn new() -> Self {
         Self {
-            key_bindings: KeyBindings::default(),
+            keymaps: Keymaps::builtin().clone(),
+            resolver: KeySequenceResolver::new(),
             mouse_enabled: true,
             drag_start: None,
         }
     }
 
     pub fn with_key_bindings(mut self, key_bindings: KeyBindings) -> Self {
-        self.key_bindings = key_bindings;
+        if let Some(press) = key_press(key_bindings.quit_key, KeyModifiers::CONTROL) {
+            self.keymaps
+                .rebind_command("app.quit", KeySequence::new(vec![press]));
+        }
+        self
+    }
+
+    pub fn with_keymaps(mut self, keymaps: Keymaps) -> Self {
+        self.set_keymaps(keymaps);
         self
     }
 
+    pub fn set_keymaps(&mut self, keymaps: Keymaps) {
+        self.keymaps = keymaps;
+        self.resolver.reset();
+    }
+
     pub fn with_mouse_enabled(mut self, enabled: bool) -> Self {
         self.mouse_enabled = enabled;
         self
     }
 
+    pub fn keymaps(&self) -> &Keymaps {
+        &self.keymaps
+    }
+
+    pub fn pending_keys(&self) -> Option<String> {
+        self.resolver.pending_display()
+    }
+
     pub fn handle_event(
         &mut self,
         event: Event,
@@ -50,185 +78,108 @@ impl InputHandler {
     ) -> Option<InputAction> {
         match event {
             Event::Key(key_event) => {
-                if is_dialog_open {
-                    self.handle_dialog_key_event(key_event)
+                let mode = if is_dialog_open {
+                    KeymapMode::Dialog
                 } else if is_history_browser_open {
-                    self.handle_history_browser_key_event(key_event)
+                    KeymapMode::HistoryBrowser
                 } else if is_history_stats_open {
-                    self.handle_history_stats_key_event(key_event)
+                    KeymapMode::HistoryStats
                 } else if is_menu_active {
-                    self.handle_menu_key_event(key_event)
+                    KeymapMode::Menu
                 } else {
-                    self.handle_key_event(key_event)
-                }
+                    KeymapMode::Editor
+                };
+                self.handle_key_event(key_event, mode)
             }
             Event::Mouse(mouse_event) if self.mouse_enabled => self.handle_mouse_event(mouse_event),
+            // A bracketed paste arrives whole, so it goes in verbatim rather
+            // than as keys that auto-indent and close brackets.
+            Event::Paste(text) if is_dialog_open => Some(InputAction::DialogPaste(text)),
+            Event::Paste(text)
+                if !is_history_browser_open && !is_history_stats_open && !is_menu_active =>
+            {
+                Some(InputAction::Command(Command::InsertText(text)))
+            }
             Event::Resize(_, _) => Some(InputAction::Resize),
             _ => None,
         }
     }
 
-    fn handle_key_event(&mut self, key_event: KeyEvent) -> Option<InputAction> {
-        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
-        let alt = key_event.modifiers.contains(KeyModifiers::ALT);
-        let shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
-
-        let has_any_ctrl_modifier = key_event.modifiers.contains(KeyModifiers::CONTROL)
-            || key_event.modifiers.contains(KeyModifiers::SUPER);
-
-        match (key_event.code, ctrl, alt, shift) {
-            (code, _, _, _) if code == self.key_bindings.quit_key && ctrl => {
-                Some(InputAction::Quit)
-            }
-
-            (KeyCode::Char('f'), false, true, false) => Some(InputAction::ActivateMenuBar),
-            (KeyCode::Char('e'), false, true, false) => Some(InputAction::ActivateMenuBar),
-            (KeyCode::Char('v'), false, true, false) => Some(InputAction::ActivateMenuBar),
-            (KeyCode::Char('s'), false, true, false) => Some(InputAction::ActivateMenuBar),
-            (KeyCode::Char('t'), false, true, false) => Some(InputAction::ActivateMenuBar),
-            (KeyCode::Char('h'), false, true, false) => Some(InputAction::ActivateMenuBar),
-
-            (KeyCode::Char(c), false, false, false) if !has_any_ctrl_modifier => {
-                Some(InputAction::Command(Command::InsertChar(c)))
-            }
-            (KeyCode::Char(c), false, false, true) if !has_any_ctrl_modifier => Some(
-                InputAction::Command(Command::InsertChar(c.to_ascii_uppercase())),
-            ),
-
-            (KeyCode::Backspace, false, false, false) => {
-                Some(InputAction::Command(Command::Backspace))
-            }
-            (KeyCode::Delete, false, false, false) => {
-                Some(InputAction::Command(Command::DeleteChar))
-            }
-            (KeyCode::Enter, false, false, false) => Some(InputAction::Command(Command::NewLine)),
-            (KeyCode::Tab, false, false, false) => Some(InputAction::Command(Command::Indent)),
-            (KeyCode::Tab, false, false, true) | (KeyCode::BackTab, false, false, false) => {
-                Some(InputAction::Command(Command::Dedent))
-            }
-
-            (KeyCode::Up, false, false, false) => Some(InputAction::Command(Command::MoveCursorUp)),
-            (KeyCode::Down, false, false, false) => {
-                Some(InputAction::Command(Command::MoveCursorDown))
-            }
-            (KeyCode::Left, false, false, false) => {
-                Some(InputAction::Command(Command::MoveCursorLeft))
-            }
-            (KeyCode::Right, false, false, false) => {
-                Some(InputAction::Command(Command::MoveCursorRight))
-            }
-
-            (KeyCode::Left, true, false, false) => {
-                Some(InputAction::Command(Command::MoveCursorWordLeft))
-            }
-            (KeyCode::Right, true, false, false) => {
-                Some(InputAction::Command(Command::MoveCursorWordRight))
-            }
-
-            (KeyCode::Home, false, false, false) => {
-                Some(InputAction::Command(Command::MoveToStartOfLine))
-            }
-            (KeyCode::End, false, false, false) => {
-                Some(InputAction::Command(Command::MoveToEndOfLine))
-            }
-
-            (KeyCode::Home, true, false, false) => {
-                Some(InputAction::Command(Command::MoveToStartOfFile))
-            }
-            (KeyCode::End, true, false, false) => {
-                Some(InputAction::Command(Command::MoveToEndOfFile))
-            }
-
-            (KeyCode::PageUp, false, false, false) => Some(InputAction::Command(Command::PageUp)),
-            (KeyCode::PageDown, false, false, false) => {
-                Some(InputAction::Command(Command::PageDown))
-            }
-
-            (KeyCode::Char('s'), true, false, false) => Some(InputAction::Command(Command::Save)),
-            (KeyCode::Char('o'), true, false, false) => Some(InputAction::OpenFile),
-            (KeyCode::Char('n'), true, false, false) => Some(InputAction::Command(Command::New)),
-            (KeyCode::Char('w'), true, false, false) => Some(InputAction::Command(Command::Close)),
-
-            (KeyCode::Char('z'), true, false, false) => Some(InputAction::Command(Command::Undo)),
-            (KeyCode::Char('y'), true, false, false) | (KeyCode::Char('z'), true, false, true) => {
-                Some(InputAction::Command(Command::Redo))
-            }
-
-            (KeyCode::Char('c'), true, false, false) => Some(InputAction::Command(Command::Copy)),
-            (KeyCode::Char('x'), true, false, false) => Some(InputAction::Command(Command::Cut)),
-            (KeyCode::Char('v'), true, false, false) => Some(InputAction::Command(Command::Paste)),
-
-            (KeyCode::Char('a'), true, false, false) => Some(InputAction::SelectAll),
-
-            (KeyCode::Char('f'), true, false, false) => Some(InputAction::Search),
-            (KeyCode::Char('h'), true, false, false) => Some(InputAction::Replace),
-            (KeyCode::Char('g'), true, false, false) => Some(InputAction::GotoLine),
-
-            (KeyCode::F(3), false, false, false) => Some(InputAction::Command(Command::NextMatch)),
-            (KeyCode::F(3), false, false, true) => {
-                Some(InputAction::Command(Command::PreviousMatch))
-            }
-
-            (KeyCode::Char('d'), true, false, false) => {
-                Some(InputAction::Command(Command::DuplicateLine))
-            }
-            (KeyCode::Char('k'), true, false, false) => {
-                Some(InputAction::Command(Command::DeleteLine))
-            }
-            (KeyCode::Char('j'), true, false, false) => {
-                Some(InputAction::Command(Command::JoinLines))
-            }
-
-            (KeyCode::Up, true, false, true) => Some(InputAction::Command(Command::MoveLinesUp)),
-            (KeyCode::Down, true, false, true) => {
-                Some(InputAction::Command(Command::MoveLinesDown))
-            }
+    // With vim mode on, the keys the vim engine claims skip the keymap; a
+    // chord already under way in the keymap is finished there first.
+    pub fn handle_vim_event(
+        &mut self,
+        event: &Event,
+        editor_state: &EditorState,
+    ) -> Option<InputAction> {
+        let Event::Key(key_event) = event else {
+            return None;
+        };
+        if self.resolver.is_pending() {
+            return None;
+        }
+        let press = key_press(key_event.code, key_event.modifiers)?;
+        editor_state
+            .vim_wants_key(&press)
+            .then_some(InputAction::Command(Command::VimKey(press)))
+    }
 
-            (KeyCode::Char('/'), true, false, false) => {
-                Some(InputAction::Command(Command::ToggleLineComment))
-            }
-            (KeyCode::Char('/'), true, false, true) => {
-                Some(InputAction::Command(Command::ToggleBlockComment))
-            }
+    pub fn handle_file_tree_event(&mut self, event: Event) -> Option<InputAction> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event, KeymapMode::FileTree),
+            Event::Paste(_) => None,
+            other => self.handle_event(other, false, false, false, false),
+        }
+    }
 
-            (KeyCode::Char('u'), true, false, false) => {
-                Some(InputAction::Command(Command::ChangeCase {
-                    mode: CaseMode::Upper,
-                }))
-            }
-            (KeyCode::Char('u'), true, false, true) => {
-                Some(InputAction::Command(Command::ChangeCase {
-                    mode: CaseMode::Lower,
-                }))
-            }
+    pub fn handle_symbol_outline_event(&mut self, event: Event) -> Option<InputAction> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event, KeymapMode::SymbolOutline),
+            Event::Paste(_) => None,
+            other => self.handle_event(other, false, false, false, false),
+        }
+    }
 
-            (KeyCode::Char('b'), true, false, false) => {
-                Some(InputAction::Command(Command::JumpToMatchingBracket))
-            }
+    pub fn handle_completion_event(&mut self, event: Event) -> Option<InputAction> {
+        self.handle_overlay_event(event, KeymapMode::Completion)
+    }
 
-            (KeyCode::Char('m'), true, false, false) => {
-                Some(InputAction::Command(Command::ToggleBookmark))
-            }
-            (KeyCode::F(2), false, false, false) => {
-                Some(InputAction::Command(Command::NextBookmark))
-            }
-            (KeyCode::F(2), false, false, true) => {
-                Some(InputAction::Command(Command::PreviousBookmark))
-            }
+    pub fn handle_snippet_event(&mut self, event: Event) -> Option<InputAction> {
+        self.handle_overlay_event(event, KeymapMode::Snippet)
+    }
 
-            (KeyCode::Insert, false, false, false) => {
-                Some(InputAction::Command(Command::ToggleOverwriteMode))
+    // Keys the completion popup or snippet session doesn't claim fall through
+    // to the editor so typing carries on; a chord already under way in the
+    // editor keymap is finished there.
+    fn handle_overlay_event(&mut self, event: Event, mode: KeymapMode) -> Option<InputAction> {
+        let Event::Key(key_event) = event else {
+            return self.handle_event(event, false, false, false, false);
+        };
+        if !self.resolver.is_pending() {
+            let press = key_press(key_event.code, key_event.modifiers)?;
+            if let KeyResolution::Command(id) = self.resolver.feed(&self.keymaps, mode, press) {
+                return action_for_binding(id, self.resolver.take_arguments());
             }
+        }
+        self.handle_key_event(key_event, KeymapMode::Editor)
+    }
 
-            (KeyCode::Char('r'), true, false, false) => {
-                Some(InputAction::Command(Command::ToggleReadOnly))
-            }
+    pub fn handle_palette_event(&mut self, event: Event) -> Option<InputAction> {
+        match event {
+            Event::Key(key_event) => self.handle_key_event(key_event, KeymapMode::Palette),
+            Event::Paste(text) => Some(InputAction::PalettePaste(text)),
+            Event::Resize(_, _) => Some(InputAction::Resize),
+            _ => None,
+        }
+    }
 
-            (KeyCode::Esc, false, false, false) => {
-                Some(InputAction::Command(Command::ClearSecondaryCursors))
-            }
+    fn handle_key_event(&mut self, key_event: KeyEvent, mode: KeymapMode) -> Option<InputAction> {
+        let press = key_press(key_event.code, key_event.modifiers)?;
 
-            _ => None,
+        match self.resolver.feed(&self.keymaps, mode, press) {
+            KeyResolution::Command(id) => action_for_binding(id, self.resolver.take_arguments()),
+            KeyResolution::Pending | KeyResolution::Cancelled => None,
+            KeyResolution::Unbound => text_input_action(key_event, mode),
         }
     }
 
@@ -263,75 +214,6 @@ impl InputHandler {
             _ => None,
         }
     }
-
-    fn handle_history_browser_key_event(&mut self, key_event: KeyEvent) -> Option<InputAction> {
-        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
-        let _alt = key_event.modifiers.contains(KeyModifiers::ALT);
-        let _shift = key_event.modifiers.contains(KeyModifiers::SHIFT);
-
-        match (key_event.code, ctrl) {
-            (KeyCode::Up, false) => Some(InputAction::Command(Command::HistoryNavigatePrevious)),
-            (KeyCode::Down, false) => Some(InputAction::Command(Command::HistoryNavigateNext)),
-            (KeyCode::Enter, false) => Some(InputAction::Command(Command::HistoryViewDiff)),
-            (KeyCode::Tab, false) => Some(InputAction::Command(Command::HistoryToggleFileList)),
-            (KeyCode::Esc, false) => Some(InputAction::Command(Command::CloseHistoryBrowser)),
-            (KeyCode::Char('q'), false) => Some(InputAction::Command(Command::CloseHistoryBrowser)),
-            (KeyCode::Char('f'), false) => {
-                Some(InputAction::Command(Command::HistoryToggleFileList))
-            }
-            (KeyCode::Char('b'), false) => Some(InputAction::SetBaseCommit),
-            (KeyCode::Char('c'), false) => {
-                Some(InputAction::Command(Command::HistoryClearBaseCommit))
-            }
-            (code, _) if code == self.key_bindings.quit_key && ctrl => Some(InputAction::Quit),
-            _ => None,
-        }
-    }
-
-    fn handle_history_stats_key_event(&mut self, key_event: KeyEvent) -> Option<InputAction> {
-        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
-
-        match (key_event.code, ctrl) {
-            (KeyCode::Esc, false) => Some(InputAction::CloseHistoryStats),
-            (KeyCode::Char('q'), false) => Some(InputAction::CloseHistoryStats),
-            (code, _) if code == self.key_bindings.quit_key && ctrl => Some(InputAction::Quit),
-            _ => None,
-        }
-    }
-
-    fn handle_menu_key_event(&mut self, key_event: KeyEvent) -> Option<InputAction> {
-        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
-
-        match (key_event.code, ctrl) {
-            (KeyCode::Up, false) => Some(InputAction::MenuUp),
-            (KeyCode::Down, false) => Some(InputAction::MenuDown),
-            (KeyCode::Left, false) => Some(InputAction::MenuLeft),
-            (KeyCode::Right, false) => Some(InputAction::MenuRight),
-            (KeyCode::Enter, false) => Some(InputAction::MenuSelect),
-            (KeyCode::Esc, false) => Some(InputAction::DeactivateMenuBar),
-            (code, _) if code == self.key_bindings.quit_key && ctrl => Some(InputAction::Quit),
-            _ => None,
-        }
-    }
-
-    fn handle_dialog_key_event(&mut self, key_event: KeyEvent) -> Option<InputAction> {
-        let ctrl = key_event.modifiers.contains(KeyModifiers::CONTROL);
-
-        match (key_event.code, ctrl) {
-            (KeyCode::Char(c), false) => Some(InputAction::DialogInsertChar(c)),
-            (KeyCode::Backspace, false) => Some(InputAction::DialogBackspace),
-            (KeyCode::Delete, false) => Some(InputAction::DialogDelete),
-            (KeyCode::Left, false) => Some(InputAction::DialogMoveCursorLeft),
-            (KeyCode::Right, false) => Some(InputAction::DialogMoveCursorRight),
-            (KeyCode::Home, false) => Some(InputAction::DialogMoveToStart),
-            (KeyCode::End, false) => Some(InputAction::DialogMoveToEnd),
-            (KeyCode::Enter, false) => Some(InputAction::DialogConfirm),
-            (KeyCode::Esc, false) => Some(InputAction::DialogCancel),
-            (KeyCode::Tab, false) => Some(InputAction::DialogSwitchField),
-            (code, _) if code == self.key_bindings.quit_key && ctrl => Some(InputAction::Quit),
-            _ => None,
-        }
-    }
 }
 
 impl Default for InputHandler {
@@ -361,6 +243,7 @@ pub enum InputAction {
     MenuRight,
     MenuSelect,
     DialogInsertChar(char),
+    DialogPaste(String),
     DialogBackspace,
     DialogDelete,
     DialogMoveCursorLeft,
@@ -370,4 +253,146 @@ pub enum InputAction {
     DialogConfirm,
     DialogCancel,
     DialogSwitchField,
+    DialogScrollUp(usize),
+    DialogScrollDown(usize),
+    FileTreeNewFile,
+    FileTreeNewDirectory,
+    FileTreeRename,
+    FileTreeDelete,
+    OpenCommandPalette,
+    ShowHelp,
+    PaletteInsertChar(char),
+    PalettePaste(String),
+    PaletteBackspace,
+    PaletteNext,
+    PalettePrevious,
+    PaletteConfirm,
+    PaletteCancel,
+    PaletteTogglePin,
+    RunCommand(&'static str),
+}
+
+pub fn key_press(code: KeyCode, modifiers: KeyModifiers) -> Option<KeyPress> {
+    let (key, back_tab) = match code {
+        KeyCode::Char(c) => (Key::Char(c), false),
+        KeyCode::F(n) => (Key::F(n), false),
+        KeyCode::Enter => (Key::Enter, false),
+        KeyCode::Esc => (Key::Escape, false),
+        KeyCode::Tab => (Key::Tab, false),
+        KeyCode::BackTab => (Key::Tab, true),
+        KeyCode::Backspace => (Key::Backspace, false),
+        KeyCode::Delete => (Key::Delete, false),
+        KeyCode::Insert => (Key::Insert, false),
+        KeyCode::Home => (Key::Home, false),
+        KeyCode::End => (Key::End, false),
+        KeyCode::PageUp => (Key::PageUp, false),
+        KeyCode::PageDown => (Key::PageDown, false),
+        KeyCode::Up => (Key::Up, false),
+        KeyCode::Down => (Key::Down, false),
+        KeyCode::Left => (Key::Left, false),
+        KeyCode::Right => (Key::Right, false),
+        _ => return None,
+    };
+
+    Some(KeyPress::new(
+        key,
+        editor_core::KeyModifiers {
+            ctrl: modifiers.contains(KeyModifiers::CONTROL),
+            alt: modifiers.contains(KeyModifiers::ALT),
+            shift: modifiers.contains(KeyModifiers::SHIFT) || back_tab,
+            meta: modifiers.intersects(KeyModifiers::SUPER | KeyModifiers::META),
+        },
+    ))
+}
+
+// A binding that carries its arguments runs the command straight away
+// instead of prompting for them.
+pub fn action_for_binding(id: &'static str, arguments: Vec<String>) -> Option<InputAction> {
+    if arguments.is_empty() {
+        return action_for_command(id);
+    }
+    match CommandRegistry::builtin().resolve(id, &arguments) {
+        Ok(CommandAction::Execute(command)) => Some(InputAction::Command(command)),
+        _ => action_for_command(id),
+    }
+}
+
+pub fn action_for_command(id: &'static str) -> Option<InputAction> {
+    let action = match id {
+        "app.quit" => InputAction::Quit,
+        "file.open" => InputAction::OpenFile,
+        "search.find" => InputAction::Search,
+        "search.replace" => InputAction::Replace,
+        "search.goto_line" => InputAction::GotoLine,
+        "edit.select_all" => InputAction::SelectAll,
+        "view.command_palette" => InputAction::OpenCommandPalette,
+        "help.shortcuts" => InputAction::ShowHelp,
+        "history.set_base_commit" => InputAction::SetBaseCommit,
+        "history.close_stats" => InputAction::CloseHistoryStats,
+        "file_tree.new_file" => InputAction::FileTreeNewFile,
+        "file_tree.new_folder" => InputAction::FileTreeNewDirectory,
+        "file_tree.rename" => InputAction::FileTreeRename,
+        "file_tree.delete" => InputAction::FileTreeDelete,
+        "menu.activate" => InputAction::ActivateMenuBar,
+        "menu.up" => InputAction::MenuUp,
+        "menu.down" => InputAction::MenuDown,
+        "menu.left" => InputAction::MenuLeft,
+        "menu.right" => InputAction::MenuRight,
+        "menu.select" => InputAction::MenuSelect,
+        "menu.close" => InputAction::DeactivateMenuBar,
+        "dialog.confirm" => InputAction::DialogConfirm,
+        "dialog.cancel" => InputAction::DialogCancel,
+        "dialog.switch_field" => InputAction::DialogSwitchField,
+        "dialog.backspace" => InputAction::DialogBackspace,
+        "dialog.delete" => InputAction::DialogDelete,
+        "dialog.cursor_left" => InputAction::DialogMoveCursorLeft,
+        "dialog.cursor_right" => InputAction::DialogMoveCursorRight,
+        "dialog.start" => InputAction::DialogMoveToStart,
+        "dialog.end" => InputAction::DialogMoveToEnd,
+        "dialog.scroll_up" => InputAction::DialogScrollUp(1),
+        "dialog.scroll_down" => InputAction::DialogScrollDown(1),
+        "dialog.page_up" => InputAction::DialogScrollUp(DIALOG_PAGE_LINES),
+        "dialog.page_down" => InputAction::DialogScrollDown(DIALOG_PAGE_LINES),
+        "palette.next" => InputAction::PaletteNext,
+        "palette.previous" => InputAction::PalettePrevious,
+        "palette.backspace" => InputAction::PaletteBackspace,
+        "palette.confirm" => InputAction::PaletteConfirm,
+        "palette.cancel" => InputAction::PaletteCancel,
+        "palette.toggle_pin" => InputAction::PaletteTogglePin,
+        _ => {
+            let spec = CommandRegistry::builtin().get(id)?;
+            match spec.resolve(&[]) {
+                Ok(CommandAction::Execute(command)) if spec.arguments.is_empty() => {
+                    InputAction::Command(command)
+                }
+                _ => InputAction::RunCommand(id),
+            }
+        }
+    };
+    Some(action)
+}
+
+fn text_input_action(key_event: KeyEvent, mode: KeymapMode) -> Option<InputAction> {
+    let KeyCode::Char(c) = key_event.code else {
+        return None;
+    };
+    if key_event.modifiers.intersects(
+        KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SUPER | KeyModifiers::META,
+    ) {
+        return None;
+    }
+
+    match mode {
+        KeymapMode::Editor => {
+            let c = if key_event.modifiers.contains(KeyModifiers::SHIFT) {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            };
+            Some(InputAction::Command(Command::InsertChar(c)))
+        }
+        KeymapMode::Dialog => Some(InputAction::DialogInsertChar(c)),
+        KeymapMode::Palette => Some(InputAction::PaletteInsertChar(c)),
+        _ => None,
+    }
 }
@@ -1,4 +1,9 @@
+pub mod batch;
+pub mod clipboard;
 pub mod dialog;
 pub mod input;
 pub mod menu;
+pub mod palette;
+#[cfg(unix)]
+pub mod remote;
 pub mod renderer;
@@ -1,23 +1,59 @@
 use crossterm::{
-    event::{self},
+    event::{self, DisableBracketedPaste, EnableBracketedPaste},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use editor_core::EditorState;
+use editor_core::{
+    ClipboardPicker, CommandAction, CommandPalette, CommandRegistry, ConfigLoader, ConfigWatcher,
+    EditorConfig, EditorState, Keymaps, LanguageRegistry, MacroRegisters, PaletteOutcome,
+    SnippetRegistry, SymbolPicker, SymbolScope,
+};
+#[cfg(unix)]
+use editor_core::{RemoteServer, SessionManager};
+use editor_tui::batch::BatchArgs;
+use editor_tui::clipboard::use_terminal_clipboard;
 use editor_tui::dialog::Dialog;
 use editor_tui::input::InputHandler;
-use editor_tui::menu::MenuState;
+use editor_tui::menu::{menu_action_for, MenuState};
+use editor_tui::palette::{render_clipboard_picker, render_command_palette, render_symbol_picker};
+#[cfg(unix)]
+use editor_tui::remote::{FileLocation, RemoteArgs};
 use editor_tui::renderer::Renderer;
 use ratatui::{backend::CrosstermBackend, Terminal};
 use std::io;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    #[cfg(unix)]
+    let remote_locations = hand_off_to_running_editor();
+
+    match BatchArgs::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => {
+            if let Err(e) = args.run() {
+                eprintln!("editor-tui: {}", e);
+                std::process::exit(1);
+            }
+            return Ok(());
+        }
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("editor-tui: {}", e);
+            std::process::exit(2);
+        }
+    }
+
     let mut terminal = setup_terminal()?;
+    use_terminal_clipboard();
     let mut editor_state = EditorState::new();
-    let mut input_handler = InputHandler::new();
+    load_languages(&mut editor_state);
+    load_snippets(&mut editor_state);
+    load_macros(&mut editor_state);
+    #[cfg(unix)]
+    open_locations(&mut editor_state, &remote_locations);
+    let mut input_handler = InputHandler::new().with_keymaps(load_keymaps(&mut editor_state));
     let mut renderer = Renderer::new();
     let mut menu_state = MenuState::new();
     let mut dialog: Option<Dialog> = None;
+    let mut palette: Option<CommandPalette> = None;
 
     let result = run_event_loop(
         &mut terminal,
@@ -26,6 +62,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         &mut renderer,
         &mut menu_state,
         &mut dialog,
+        &mut palette,
     );
 
     cleanup_terminal(terminal)?;
@@ -33,10 +70,127 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     result
 }
 
+// Exits once a running editor has taken the `--remote` files; if none is
+// running, they are left for this one to open.
+#[cfg(unix)]
+fn hand_off_to_running_editor() -> Vec<FileLocation> {
+    let args = match RemoteArgs::parse(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => return Vec::new(),
+        Err(e) => {
+            eprintln!("editor-tui: {}", e);
+            std::process::exit(2);
+        }
+    };
+    match args.resolve().and_then(|args| Ok((args.send()?, args))) {
+        Ok((true, _)) => std::process::exit(0),
+        Ok((false, args)) => args.locations,
+        Err(e) => {
+            eprintln!("editor-tui: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn open_locations(editor_state: &mut EditorState, locations: &[FileLocation]) {
+    for location in locations {
+        if let Err(e) = location.open_in(editor_state) {
+            editor_state.set_status_message(format!("Error: {}", e));
+        }
+    }
+}
+
+#[cfg(unix)]
+fn start_remote_server(editor_state: &mut EditorState) -> Option<RemoteServer> {
+    match SessionManager::new().and_then(|sessions| RemoteServer::bind(sessions.socket_path())) {
+        Ok(server) => Some(server),
+        Err(e) => {
+            editor_state.set_status_message(format!("Remote control error: {}", e));
+            None
+        }
+    }
+}
+
+fn load_languages(editor_state: &mut EditorState) {
+    match LanguageRegistry::load_user() {
+        Ok(languages) => editor_state.set_language_registry(languages),
+        Err(e) => editor_state.set_status_message(format!("Language config error: {}", e)),
+    }
+}
+
+fn load_snippets(editor_state: &mut EditorState) {
+    match SnippetRegistry::load_user() {
+        Ok(snippets) => editor_state.set_snippet_registry(snippets),
+        Err(e) => editor_state.set_status_message(format!("Snippet error: {}", e)),
+    }
+}
+
+fn load_macros(editor_state: &mut EditorState) {
+    match MacroRegisters::load_user() {
+        Ok(macros) => editor_state.set_macro_registers(macros),
+        Err(e) => editor_state.set_status_message(format!("Macro error: {}", e)),
+    }
+}
+
+fn load_keymaps(editor_state: &mut EditorState) -> Keymaps {
+    let profile = editor_state.keymap_profile();
+    match Keymaps::load_user_profile(profile, CommandRegistry::builtin()) {
+        Ok(keymaps) => {
+            if let Some(conflict) = keymaps.conflicts().first() {
+                editor_state.set_status_message(format!("Keymap conflict: {}", conflict));
+            }
+            keymaps
+        }
+        Err(e) => {
+            editor_state.set_status_message(format!("Keymap error: {}", e));
+            Keymaps::builtin_profile(profile).clone()
+        }
+    }
+}
+
+fn load_config(
+    editor_state: &mut EditorState,
+    input_handler: &mut InputHandler,
+    renderer: &mut Renderer,
+) -> Option<ConfigWatcher> {
+    let loader = ConfigLoader::new();
+    if let Err(e) = loader
+        .load()
+        .and_then(|config| apply_config(&config, editor_state, input_handler, renderer))
+    {
+        editor_state.set_status_message(format!("Config error: {}", e));
+    }
+
+    match ConfigWatcher::new(loader) {
+        Ok(watcher) => Some(watcher),
+        Err(e) => {
+            editor_state.set_status_message(format!("Config error: {}", e));
+            None
+        }
+    }
+}
+
+fn apply_config(
+    config: &EditorConfig,
+    editor_state: &mut EditorState,
+    input_handler: &mut InputHandler,
+    renderer: &mut Renderer,
+) -> editor_core::Result<()> {
+    let profile = editor_state.keymap_profile();
+    editor_state.apply_config(config)?;
+    if editor_state.keymap_profile() != profile {
+        input_handler.set_keymaps(load_keymaps(editor_state));
+    }
+    renderer.show_line_numbers = config.view.line_numbers;
+    renderer.show_status_bar = config.view.status_bar;
+    Ok(())
+}
+
 fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>, Box<dyn std::error::Error>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
     let terminal = Terminal::new(backend)?;
     Ok(terminal)
@@ -46,7 +200,11 @@ fn cleanup_terminal(
     mut terminal: Terminal<CrosstermBackend<io::Stdout>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(
+        terminal.backend_mut(),
+        DisableBracketedPaste,
+        LeaveAlternateScreen
+    )?;
     terminal.show_cursor()?;
     Ok(())
 }
@@ -58,10 +216,40 @@ fn run_event_loop(
     renderer: &mut Renderer,
     menu_state: &mut MenuState,
     dialog: &mut Option<Dialog>,
+    palette: &mut Option<CommandPalette>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let mut config_watcher = load_config(editor_state, input_handler, renderer);
+    let mut symbol_picker: Option<SymbolPicker> = None;
+    let mut clipboard_picker: Option<ClipboardPicker> = None;
+    #[cfg(unix)]
+    let mut remote_server = start_remote_server(editor_state);
+
     loop {
+        editor_state.poll_language_servers();
+        #[cfg(unix)]
+        if let Some(server) = remote_server.as_mut() {
+            server.poll(editor_state);
+        }
+        if let Some(reloaded) = config_watcher.as_mut().and_then(|watcher| watcher.poll()) {
+            match reloaded
+                .and_then(|config| apply_config(&config, editor_state, input_handler, renderer))
+            {
+                Ok(()) => editor_state.set_status_message("Configuration reloaded".to_string()),
+                Err(e) => editor_state.set_status_message(format!("Config error: {}", e)),
+            }
+        }
+
         terminal.draw(|frame| {
             renderer.render(frame, editor_state, menu_state, dialog.as_ref());
+            if let Some(palette) = palette.as_ref() {
+                render_command_palette(frame, palette, frame.size());
+            }
+            if let Some(picker) = symbol_picker.as_ref() {
+                render_symbol_picker(frame, picker, frame.size());
+            }
+            if let Some(picker) = clipboard_picker.as_ref() {
+                render_clipboard_picker(frame, picker, frame.size());
+            }
         })?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
@@ -70,23 +258,82 @@ fn run_event_loop(
             let is_history_stats_open = editor_state.is_history_stats_open();
             let is_menu_active = menu_state.active;
             let is_dialog_open = dialog.is_some();
-            if let Some(action) = input_handler.handle_event(
-                event,
-                is_history_browser_open,
-                is_history_stats_open,
-                is_menu_active,
-                is_dialog_open,
-            ) {
+            let is_file_tree_focused = editor_state.is_file_tree_focused()
+                && !is_history_browser_open
+                && !is_history_stats_open
+                && !is_menu_active
+                && !is_dialog_open;
+            let is_symbol_outline_focused = editor_state.is_symbol_outline_focused()
+                && !is_history_browser_open
+                && !is_history_stats_open
+                && !is_menu_active
+                && !is_dialog_open;
+            let action = if symbol_picker.is_some() {
+                input_handler
+                    .handle_palette_event(event)
+                    .and_then(|action| {
+                        handle_symbol_picker_action(action, &mut symbol_picker, editor_state)
+                    })
+            } else if clipboard_picker.is_some() {
+                input_handler
+                    .handle_palette_event(event)
+                    .and_then(|action| {
+                        handle_clipboard_picker_action(action, &mut clipboard_picker, editor_state)
+                    })
+            } else if palette.is_some() {
+                input_handler.handle_palette_event(event)
+            } else if is_file_tree_focused {
+                input_handler.handle_file_tree_event(event)
+            } else if is_symbol_outline_focused {
+                input_handler.handle_symbol_outline_event(event)
+            } else if editor_state.is_completion_open()
+                && !is_history_browser_open
+                && !is_history_stats_open
+                && !is_menu_active
+                && !is_dialog_open
+            {
+                input_handler.handle_completion_event(event)
+            } else if editor_state.is_snippet_active()
+                && !is_history_browser_open
+                && !is_history_stats_open
+                && !is_menu_active
+                && !is_dialog_open
+            {
+                input_handler.handle_snippet_event(event)
+            } else {
+                let is_editor_focused = !is_history_browser_open
+                    && !is_history_stats_open
+                    && !is_menu_active
+                    && !is_dialog_open;
+                is_editor_focused
+                    .then(|| input_handler.handle_vim_event(&event, editor_state))
+                    .flatten()
+                    .or_else(|| {
+                        input_handler.handle_event(
+                            event,
+                            is_history_browser_open,
+                            is_history_stats_open,
+                            is_menu_active,
+                            is_dialog_open,
+                        )
+                    })
+            };
+            if let Some(keys) = input_handler.pending_keys() {
+                editor_state.set_status_message(format!("{} ...", keys));
+            }
+            if let Some(action) = action {
                 match action {
                     editor_tui::input::InputAction::Quit => break,
                     editor_tui::input::InputAction::Command(cmd) => {
                         if let Err(e) = editor_state.execute_command(cmd) {
                             editor_state.set_status_message(format!("Error: {}", e));
                         }
+                        if editor_state.take_quit_request() {
+                            break;
+                        }
                     }
                     editor_tui::input::InputAction::OpenFile => {
-                        editor_state
-                            .set_status_message("Open file dialog not yet implemented".to_string());
+                        open_command_prompt("file.open", editor_state, palette);
                     }
                     editor_tui::input::InputAction::Search => {
                         use editor_tui::dialog::{Dialog, DialogType};
@@ -145,18 +392,45 @@ fn run_event_loop(
                     editor_tui::input::InputAction::MenuSelect => {
                         if let Some(menu_action) = menu_state.get_selected_action() {
                             menu_state.deactivate();
-                            handle_menu_action(menu_action, editor_state, renderer, dialog);
+                            if handle_menu_action(
+                                menu_action,
+                                editor_state,
+                                renderer,
+                                dialog,
+                                palette,
+                                &mut symbol_picker,
+                                &mut clipboard_picker,
+                            ) {
+                                break;
+                            }
                         }
                     }
                     editor_tui::input::InputAction::MenuAction(menu_action) => {
                         menu_state.deactivate();
-                        handle_menu_action(menu_action, editor_state, renderer, dialog);
+                        if handle_menu_action(
+                            menu_action,
+                            editor_state,
+                            renderer,
+                            dialog,
+                            palette,
+                            &mut symbol_picker,
+                            &mut clipboard_picker,
+                        ) {
+                            break;
+                        }
                     }
                     editor_tui::input::InputAction::DialogInsertChar(c) => {
                         if let Some(ref mut dlg) = dialog {
                             dlg.insert_char(c);
                         }
                     }
+                    editor_tui::input::InputAction::DialogPaste(text) => {
+                        if let Some(ref mut dlg) = dialog {
+                            text.chars()
+                                .filter(|c| !c.is_control())
+                                .for_each(|c| dlg.insert_char(c));
+                        }
+                    }
                     editor_tui::input::InputAction::DialogBackspace => {
                         if let Some(ref mut dlg) = dialog {
                             dlg.backspace();
@@ -198,6 +472,139 @@ fn run_event_loop(
                             dlg.switch_field();
                         }
                     }
+                    editor_tui::input::InputAction::DialogScrollUp(lines) => {
+                        if let Some(ref mut dlg) = dialog {
+                            dlg.scroll_up(lines);
+                        }
+                    }
+                    editor_tui::input::InputAction::DialogScrollDown(lines) => {
+                        if let Some(ref mut dlg) = dialog {
+                            dlg.scroll_down(lines);
+                        }
+                    }
+                    editor_tui::input::InputAction::OpenCommandPalette => {
+                        menu_state.deactivate();
+                        *palette = Some(CommandPalette::new(
+                            CommandRegistry::builtin(),
+                            editor_state,
+                        ));
+                    }
+                    editor_tui::input::InputAction::ShowHelp => {
+                        use editor_tui::dialog::DialogType;
+                        *dialog = Some(Dialog::new(DialogType::Help));
+                    }
+                    editor_tui::input::InputAction::PaletteInsertChar(c) => {
+                        if let Some(p) = palette {
+                            p.insert_char(c);
+                        }
+                    }
+                    editor_tui::input::InputAction::PalettePaste(text) => {
+                        if let Some(p) = palette {
+                            text.chars()
+                                .filter(|c| !c.is_control())
+                                .for_each(|c| p.insert_char(c));
+                        }
+                    }
+                    editor_tui::input::InputAction::PaletteBackspace => {
+                        if let Some(p) = palette {
+                            p.backspace();
+                        }
+                    }
+                    editor_tui::input::InputAction::PaletteNext => {
+                        if let Some(p) = palette {
+                            p.select_next();
+                        }
+                    }
+                    editor_tui::input::InputAction::PalettePrevious => {
+                        if let Some(p) = palette {
+                            p.select_previous();
+                        }
+                    }
+                    editor_tui::input::InputAction::PaletteCancel => {
+                        *palette = None;
+                    }
+                    editor_tui::input::InputAction::PaletteTogglePin => {}
+                    editor_tui::input::InputAction::PaletteConfirm => {
+                        if let Some(p) = palette {
+                            match p.confirm(CommandRegistry::builtin()) {
+                                Ok(PaletteOutcome::Continue) => {}
+                                Ok(PaletteOutcome::Run(command_action)) => {
+                                    *palette = None;
+                                    if run_command_action(
+                                        command_action,
+                                        editor_state,
+                                        renderer,
+                                        dialog,
+                                        palette,
+                                        &mut symbol_picker,
+                                        &mut clipboard_picker,
+                                    ) {
+                                        break;
+                                    }
+                                }
+                                Err(e) => {
+                                    *palette = None;
+                                    editor_state.set_status_message(format!("Error: {}", e));
+                                }
+                            }
+                        }
+                    }
+                    editor_tui::input::InputAction::RunCommand(id) => {
+                        let registry = CommandRegistry::builtin();
+                        if registry
+                            .get(id)
+                            .is_some_and(|spec| !spec.arguments.is_empty())
+                        {
+                            open_command_prompt(id, editor_state, palette);
+                        } else {
+                            match registry.resolve(id, &[]) {
+                                Ok(command_action) => {
+                                    if run_command_action(
+                                        command_action,
+                                        editor_state,
+                                        renderer,
+                                        dialog,
+                                        palette,
+                                        &mut symbol_picker,
+                                        &mut clipboard_picker,
+                                    ) {
+                                        break;
+                                    }
+                                }
+                                Err(e) => {
+                                    editor_state.set_status_message(format!("Error: {}", e));
+                                }
+                            }
+                        }
+                    }
+                    editor_tui::input::InputAction::FileTreeNewFile => {
+                        use editor_tui::dialog::{Dialog, DialogType};
+                        *dialog = Some(Dialog::new(DialogType::NewFile));
+                    }
+                    editor_tui::input::InputAction::FileTreeNewDirectory => {
+                        use editor_tui::dialog::{Dialog, DialogType};
+                        *dialog = Some(Dialog::new(DialogType::NewDirectory));
+                    }
+                    editor_tui::input::InputAction::FileTreeRename => {
+                        use editor_tui::dialog::{Dialog, DialogType};
+                        if let Some(entry) =
+                            editor_state.file_tree().and_then(|t| t.selected_entry())
+                        {
+                            *dialog =
+                                Some(Dialog::with_input(DialogType::Rename, entry.name.clone()));
+                        }
+                    }
+                    editor_tui::input::InputAction::FileTreeDelete => {
+                        use editor_tui::dialog::{Dialog, DialogType};
+                        if let Some(entry) =
+                            editor_state.file_tree().and_then(|t| t.selected_entry())
+                        {
+                            *dialog = Some(Dialog::with_input(
+                                DialogType::ConfirmDelete,
+                                entry.name.clone(),
+                            ));
+                        }
+                    }
                 }
             }
         }
@@ -241,6 +648,134 @@ fn handle_dialog_confirm(dialog: &mut Option<Dialog>, editor_state: &mut EditorS
                 }
             }
             DialogType::Help => {}
+            DialogType::NewFile
+            | DialogType::NewDirectory
+            | DialogType::Rename
+            | DialogType::ConfirmDelete => {
+                let command = match dlg.dialog_type {
+                    DialogType::NewFile => Command::FileTreeCreateFile(dlg.input),
+                    DialogType::NewDirectory => Command::FileTreeCreateDirectory(dlg.input),
+                    DialogType::Rename => Command::FileTreeRename(dlg.input),
+                    _ => Command::FileTreeDelete,
+                };
+                if let Err(e) = editor_state.execute_command(command) {
+                    editor_state.set_status_message(format!("Error: {}", e));
+                }
+            }
+        }
+    }
+}
+
+fn handle_symbol_picker_action(
+    action: editor_tui::input::InputAction,
+    symbol_picker: &mut Option<SymbolPicker>,
+    editor_state: &mut EditorState,
+) -> Option<editor_tui::input::InputAction> {
+    use editor_tui::input::InputAction;
+
+    let picker = symbol_picker.as_mut()?;
+    match action {
+        InputAction::PaletteInsertChar(c) => picker.insert_char(c),
+        InputAction::PalettePaste(text) => text
+            .chars()
+            .filter(|c| !c.is_control())
+            .for_each(|c| picker.insert_char(c)),
+        InputAction::PaletteBackspace => picker.backspace(),
+        InputAction::PaletteNext => picker.select_next(),
+        InputAction::PalettePrevious => picker.select_previous(),
+        InputAction::PaletteCancel => *symbol_picker = None,
+        InputAction::PaletteConfirm => {
+            if let Some(command) = picker.confirm() {
+                if let Err(e) = editor_state.execute_command(command) {
+                    editor_state.set_status_message(format!("Error: {}", e));
+                }
+            }
+            *symbol_picker = None;
+        }
+        other => return Some(other),
+    }
+    None
+}
+
+fn handle_clipboard_picker_action(
+    action: editor_tui::input::InputAction,
+    clipboard_picker: &mut Option<ClipboardPicker>,
+    editor_state: &mut EditorState,
+) -> Option<editor_tui::input::InputAction> {
+    use editor_tui::input::InputAction;
+
+    let picker = clipboard_picker.as_mut()?;
+    match action {
+        InputAction::PaletteInsertChar(c) => picker.insert_char(c),
+        InputAction::PalettePaste(text) => text
+            .chars()
+            .filter(|c| !c.is_control())
+            .for_each(|c| picker.insert_char(c)),
+        InputAction::PaletteBackspace => picker.backspace(),
+        InputAction::PaletteNext => picker.select_next(),
+        InputAction::PalettePrevious => picker.select_previous(),
+        InputAction::PaletteCancel => *clipboard_picker = None,
+        InputAction::PaletteTogglePin => {
+            if let Some(command) = picker.toggle_pin() {
+                if let Err(e) = editor_state.execute_command(command) {
+                    editor_state.set_status_message(format!("Error: {}", e));
+                }
+                picker.refresh(editor_state.clipboard_history());
+            }
+        }
+        InputAction::PaletteConfirm => {
+            if let Some(command) = picker.confirm() {
+                if let Err(e) = editor_state.execute_command(command) {
+                    editor_state.set_status_message(format!("Error: {}", e));
+                }
+            }
+            *clipboard_picker = None;
+        }
+        other => return Some(other),
+    }
+    None
+}
+
+fn open_command_prompt(
+    id: &str,
+    editor_state: &mut EditorState,
+    palette: &mut Option<CommandPalette>,
+) {
+    match CommandPalette::for_command(CommandRegistry::builtin(), id) {
+        Ok(p) => *palette = Some(p),
+        Err(e) => editor_state.set_status_message(format!("Error: {}", e)),
+    }
+}
+
+fn run_command_action(
+    action: CommandAction,
+    editor_state: &mut EditorState,
+    renderer: &mut Renderer,
+    dialog: &mut Option<Dialog>,
+    palette: &mut Option<CommandPalette>,
+    symbol_picker: &mut Option<SymbolPicker>,
+    clipboard_picker: &mut Option<ClipboardPicker>,
+) -> bool {
+    match action {
+        CommandAction::Execute(cmd) => {
+            if let Err(e) = editor_state.execute_command(cmd) {
+                editor_state.set_status_message(format!("Error: {}", e));
+            }
+            false
+        }
+        CommandAction::Frontend(id) => {
+            match CommandRegistry::builtin().get(id).and_then(menu_action_for) {
+                Some(menu_action) => handle_menu_action(
+                    menu_action,
+                    editor_state,
+                    renderer,
+                    dialog,
+                    palette,
+                    symbol_picker,
+                    clipboard_picker,
+                ),
+                None => false,
+            }
         }
     }
 }
@@ -250,7 +785,10 @@ fn handle_menu_action(
     editor_state: &mut EditorState,
     renderer: &mut Renderer,
     dialog: &mut Option<Dialog>,
-) {
+    palette: &mut Option<CommandPalette>,
+    symbol_picker: &mut Option<SymbolPicker>,
+    clipboard_picker: &mut Option<ClipboardPicker>,
+) -> bool {
     use editor_tui::dialog::DialogType;
     use editor_tui::menu::MenuAction;
 
@@ -260,14 +798,23 @@ fn handle_menu_action(
                 editor_state.set_status_message(format!("Error: {}", e));
             }
         }
+        MenuAction::PromptCommand(id) => {
+            open_command_prompt(id, editor_state, palette);
+        }
+        MenuAction::CommandPalette => {
+            *palette = Some(CommandPalette::new(
+                CommandRegistry::builtin(),
+                editor_state,
+            ));
+        }
         MenuAction::OpenFile => {
-            editor_state.set_status_message("Open file dialog not yet implemented".to_string());
+            open_command_prompt("file.open", editor_state, palette);
         }
         MenuAction::SaveAs => {
-            editor_state.set_status_message("Save As dialog not yet implemented".to_string());
+            open_command_prompt("file.save_as", editor_state, palette);
         }
         MenuAction::Quit => {
-            std::process::exit(0);
+            return true;
         }
         MenuAction::Search => {
             *dialog = Some(Dialog::new(DialogType::Search));
@@ -278,6 +825,25 @@ fn handle_menu_action(
         MenuAction::GotoLine => {
             *dialog = Some(Dialog::new(DialogType::GotoLine));
         }
+        MenuAction::GotoSymbol => {
+            *symbol_picker = Some(SymbolPicker::new(
+                SymbolScope::File,
+                editor_state.file_symbol_locations(),
+            ));
+        }
+        MenuAction::GotoSymbolInProject => match editor_state.project_symbols() {
+            Ok(locations) => {
+                *symbol_picker = Some(SymbolPicker::new(SymbolScope::Project, locations));
+            }
+            Err(e) => editor_state.set_status_message(format!("Error: {}", e)),
+        },
+        MenuAction::PasteFromHistory => {
+            if editor_state.clipboard_history().is_empty() {
+                editor_state.set_status_message("Clipboard history is empty".to_string());
+            } else {
+                *clipboard_picker = Some(ClipboardPicker::new(editor_state.clipboard_history()));
+            }
+        }
         MenuAction::SelectAll => {
             editor_state.set_status_message("Select all not yet implemented".to_string());
         }
@@ -310,4 +876,6 @@ fn handle_menu_action(
             ));
         }
     }
+
+    false
 }
@@ -1,14 +1,19 @@
-use editor_core::Command;
+use editor_core::{Command, CommandAction, CommandCategory, CommandRegistry, CommandSpec};
 
 #[derive(Debug, Clone)]
 pub enum MenuAction {
     ExecuteCommand(Command),
+    PromptCommand(&'static str),
+    CommandPalette,
     OpenFile,
     SaveAs,
     Quit,
     Search,
     Replace,
     GotoLine,
+    GotoSymbol,
+    GotoSymbolInProject,
+    PasteFromHistory,
     SelectAll,
     ShowHelp,
     ShowAbout,
@@ -128,6 +133,17 @@ impl MenuType {
         }
     }
 
+    pub fn category(&self) -> CommandCategory {
+        match self {
+            MenuType::File => CommandCategory::File,
+            MenuType::Edit => CommandCategory::Edit,
+            MenuType::View => CommandCategory::View,
+            MenuType::Search => CommandCategory::Search,
+            MenuType::Tools => CommandCategory::Tools,
+            MenuType::Help => CommandCategory::Help,
+        }
+    }
+
     pub fn alt_key(&self) -> char {
         match self {
             MenuType::File => 'f',
@@ -230,115 +246,55 @@ impl Default for MenuState {
     }
 }
 
-fn create_menu(menu_type: MenuType) -> Menu {
-    match menu_type {
-        MenuType::File => Menu::new(
-            "File",
-            vec![
-                MenuItem::new("New")
-                    .with_shortcut("Ctrl+N")
-                    .with_action(MenuAction::ExecuteCommand(Command::New)),
-                MenuItem::new("Open")
-                    .with_shortcut("Ctrl+O")
-                    .with_action(MenuAction::OpenFile),
-                MenuItem::new("-"),
-                MenuItem::new("Save")
-                    .with_shortcut("Ctrl+S")
-                    .with_action(MenuAction::ExecuteCommand(Command::Save)),
-                MenuItem::new("Save As").with_action(MenuAction::SaveAs),
-                MenuItem::new("-"),
-                MenuItem::new("Close")
-                    .with_shortcut("Ctrl+W")
-                    .with_action(MenuAction::ExecuteCommand(Command::Close)),
-                MenuItem::new("Quit")
-                    .with_shortcut("Ctrl+Q")
-                    .with_action(MenuAction::Quit),
-            ],
-        ),
-        MenuType::Edit => Menu::new(
-            "Edit",
-            vec![
-                MenuItem::new("Undo")
-                    .with_shortcut("Ctrl+Z")
-                    .with_action(MenuAction::ExecuteCommand(Command::Undo)),
-                MenuItem::new("Redo")
-                    .with_shortcut("Ctrl+Y")
-                    .with_action(MenuAction::ExecuteCommand(Command::Redo)),
-                MenuItem::new("-"),
-                MenuItem::new("Cut")
-                    .with_shortcut("Ctrl+X")
-                    .with_action(MenuAction::ExecuteCommand(Command::Cut)),
-                MenuItem::new("Copy")
-                    .with_shortcut("Ctrl+C")
-                    .with_action(MenuAction::ExecuteCommand(Command::Copy)),
-                MenuItem::new("Paste")
-                    .with_shortcut("Ctrl+V")
-                    .with_action(MenuAction::ExecuteCommand(Command::Paste)),
-                MenuItem::new("-"),
-                MenuItem::new("Select All")
-                    .with_shortcut("Ctrl+A")
-                    .with_action(MenuAction::SelectAll),
-            ],
-        ),
-        MenuType::View => Menu::new(
-            "View",
-            vec![
-                MenuItem::new("Toggle Line Numbers").with_action(MenuAction::ToggleLineNumbers),
-                MenuItem::new("Toggle Status Bar").with_action(MenuAction::ToggleStatusBar),
-            ],
-        ),
-        MenuType::Search => Menu::new(
-            "Search",
-            vec![
-                MenuItem::new("Find")
-                    .with_shortcut("Ctrl+F")
-                    .with_action(MenuAction::Search),
-                MenuItem::new("Replace")
-                    .with_shortcut("Ctrl+H")
-                    .with_action(MenuAction::Replace),
-                MenuItem::new("-"),
-                MenuItem::new("Go to Line")
-                    .with_shortcut("Ctrl+G")
-                    .with_action(MenuAction::GotoLine),
-                MenuItem::new("Next Match")
-                    .with_shortcut("F3")
-                    .with_action(MenuAction::ExecuteCommand(Command::NextMatch)),
-                MenuItem::new("Previous Match")
-                    .with_shortcut("Shift+F3")
-                    .with_action(MenuAction::ExecuteCommand(Command::PreviousMatch)),
-            ],
-        ),
-        MenuType::Tools => Menu::new(
-            "Tools",
-            vec![
-                MenuItem::new("Toggle Comment")
-                    .with_shortcut("Ctrl+/")
-                    .with_action(MenuAction::ExecuteCommand(Command::ToggleLineComment)),
-                MenuItem::new("Toggle Block Comment")
-                    .with_shortcut("Ctrl+Shift+/")
-                    .with_action(MenuAction::ExecuteCommand(Command::ToggleBlockComment)),
-                MenuItem::new("-"),
-                MenuItem::new("Sort Lines").with_action(MenuAction::ExecuteCommand(
-                    Command::SortLines { numerical: false },
-                )),
-                MenuItem::new("Duplicate Line")
-                    .with_shortcut("Ctrl+D")
-                    .with_action(MenuAction::ExecuteCommand(Command::DuplicateLine)),
-                MenuItem::new("Delete Line")
-                    .with_shortcut("Ctrl+K")
-                    .with_action(MenuAction::ExecuteCommand(Command::DeleteLine)),
-                MenuItem::new("Join Lines")
-                    .with_shortcut("Ctrl+J")
-                    .with_action(MenuAction::ExecuteCommand(Command::JoinLines)),
-            ],
-        ),
-        MenuType::Help => Menu::new(
-            "Help",
-            vec![
-                MenuItem::new("Keyboard Shortcuts").with_action(MenuAction::ShowHelp),
-                MenuItem::new("-"),
-                MenuItem::new("About").with_action(MenuAction::ShowAbout),
-            ],
-        ),
+pub fn create_menu(menu_type: MenuType) -> Menu {
+    create_menu_from_registry(menu_type, CommandRegistry::builtin())
+}
+
+pub fn create_menu_from_registry(menu_type: MenuType, registry: &CommandRegistry) -> Menu {
+    let mut items = Vec::new();
+    let mut current_group = None;
+
+    for spec in registry.menu_items(menu_type.category()) {
+        if current_group.is_some() && current_group != spec.menu_group {
+            items.push(MenuItem::new("-"));
+        }
+        current_group = spec.menu_group;
+
+        let mut item = MenuItem::new(spec.title);
+        if let Some(keybinding) = spec.keybinding {
+            item = item.with_shortcut(keybinding);
+        }
+        if let Some(action) = menu_action_for(spec) {
+            item = item.with_action(action);
+        }
+        items.push(item);
     }
+
+    Menu::new(menu_type.title(), items)
+}
+
+pub fn menu_action_for(spec: &CommandSpec) -> Option<MenuAction> {
+    let action = match spec.id {
+        "file.open" => MenuAction::OpenFile,
+        "file.save_as" => MenuAction::SaveAs,
+        "app.quit" => MenuAction::Quit,
+        "search.find" => MenuAction::Search,
+        "search.replace" => MenuAction::Replace,
+        "search.goto_line" => MenuAction::GotoLine,
+        "search.goto_symbol" => MenuAction::GotoSymbol,
+        "search.goto_symbol_in_project" => MenuAction::GotoSymbolInProject,
+        "edit.paste_from_history" => MenuAction::PasteFromHistory,
+        "edit.select_all" => MenuAction::SelectAll,
+        "help.shortcuts" => MenuAction::ShowHelp,
+        "help.about" => MenuAction::ShowAbout,
+        "view.toggle_line_numbers" => MenuAction::ToggleLineNumbers,
+        "view.toggle_status_bar" => MenuAction::ToggleStatusBar,
+        "view.command_palette" => MenuAction::CommandPalette,
+        _ if !spec.arguments.is_empty() => MenuAction::PromptCommand(spec.id),
+        _ => match spec.resolve(&[]).ok()? {
+            CommandAction::Execute(command) => MenuAction::ExecuteCommand(command),
+            CommandAction::Frontend(_) => return None,
+        },
+    };
+    Some(action)
 }
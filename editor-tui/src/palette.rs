@@ -0,0 +1,338 @@
+use editor_core::{ClipboardPicker, CommandPalette, SymbolPicker};
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+const PALETTE_WIDTH: u16 = 70;
+const PALETTE_MAX_ITEMS: usize = 12;
+
+pub fn render_command_palette(frame: &mut Frame, palette: &CommandPalette, area: Rect) {
+    let visible_items = if palette.is_prompting() {
+        0
+    } else {
+        palette.items().len().clamp(1, PALETTE_MAX_ITEMS)
+    };
+    let height = (visible_items as u16 + 4).min(area.height);
+    let width = PALETTE_WIDTH.min(area.width);
+    let palette_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + 1.min(area.height.saturating_sub(height)),
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, palette_area);
+
+    let title = match palette.pending_title() {
+        Some(title) => format!(" {} ", title),
+        None => " Command Palette ".to_string(),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+    let inner = block.inner(palette_area);
+    frame.render_widget(block, palette_area);
+
+    if inner.height == 0 {
+        return;
+    }
+
+    let prompt = palette.prompt().map(|arg| arg.prompt).unwrap_or(">");
+    let input_line = Line::from(vec![
+        Span::styled(format!("{} ", prompt), Style::default().fg(Color::White)),
+        Span::styled(palette.query(), Style::default().fg(Color::Yellow)),
+    ]);
+    frame.render_widget(
+        Paragraph::new(input_line).style(Style::default().bg(Color::Rgb(40, 40, 40))),
+        Rect { height: 1, ..inner },
+    );
+
+    let cursor_x = inner.x + (prompt.chars().count() + 1 + palette.query().chars().count()) as u16;
+    if cursor_x < inner.x + inner.width {
+        frame.set_cursor(cursor_x, inner.y);
+    }
+
+    if palette.is_prompting() {
+        let help = Paragraph::new("Enter: Confirm  |  Esc: Cancel")
+            .style(Style::default().fg(Color::DarkGray));
+        if inner.height > 1 {
+            frame.render_widget(
+                help,
+                Rect {
+                    y: inner.y + 1,
+                    height: 1,
+                    ..inner
+                },
+            );
+        }
+        return;
+    }
+
+    let list_area = Rect {
+        y: inner.y + 1,
+        height: inner.height.saturating_sub(1),
+        ..inner
+    };
+    let visible = list_area.height as usize;
+    let start = palette
+        .selected_index()
+        .saturating_sub(visible.saturating_sub(1));
+
+    let mut lines = Vec::new();
+    if palette.items().is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No matching commands",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    for (index, item) in palette.items().iter().enumerate().skip(start).take(visible) {
+        let selected = index == palette.selected_index();
+        let base = if selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let highlight = base.add_modifier(Modifier::BOLD).fg(if selected {
+            Color::Black
+        } else {
+            Color::Yellow
+        });
+
+        let mut spans = vec![Span::styled(format!("{}: ", item.category.title()), base)];
+        for (char_index, c) in item.title.chars().enumerate() {
+            let style = if item.indices.contains(&char_index) {
+                highlight
+            } else {
+                base
+            };
+            spans.push(Span::styled(c.to_string(), style));
+        }
+
+        if let Some(keybinding) = item.keybinding {
+            let used = item.category.title().len() + 2 + item.title.chars().count();
+            let padding = (list_area.width as usize).saturating_sub(used + keybinding.len());
+            spans.push(Span::styled(" ".repeat(padding), base));
+            spans.push(Span::styled(
+                keybinding,
+                if selected {
+                    base
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                },
+            ));
+        }
+
+        lines.push(Line::from(spans));
+    }
+
+    frame.render_widget(Paragraph::new(lines), list_area);
+}
+
+pub fn render_symbol_picker(frame: &mut Frame, picker: &SymbolPicker, area: Rect) {
+    let visible_items = picker.items().len().clamp(1, PALETTE_MAX_ITEMS);
+    let height = (visible_items as u16 + 4).min(area.height);
+    let width = PALETTE_WIDTH.min(area.width);
+    let picker_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + 1.min(area.height.saturating_sub(height)),
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, picker_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", picker.title()))
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+    let inner = block.inner(picker_area);
+    frame.render_widget(block, picker_area);
+
+    if inner.height == 0 {
+        return;
+    }
+
+    let input_line = Line::from(vec![
+        Span::styled("@ ", Style::default().fg(Color::White)),
+        Span::styled(picker.query(), Style::default().fg(Color::Yellow)),
+    ]);
+    frame.render_widget(
+        Paragraph::new(input_line).style(Style::default().bg(Color::Rgb(40, 40, 40))),
+        Rect { height: 1, ..inner },
+    );
+
+    let cursor_x = inner.x + (2 + picker.query().chars().count()) as u16;
+    if cursor_x < inner.x + inner.width {
+        frame.set_cursor(cursor_x, inner.y);
+    }
+
+    let list_area = Rect {
+        y: inner.y + 1,
+        height: inner.height.saturating_sub(1),
+        ..inner
+    };
+    let visible = list_area.height as usize;
+    let start = picker
+        .selected_index()
+        .saturating_sub(visible.saturating_sub(1));
+
+    let mut lines = Vec::new();
+    if picker.items().is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No matching symbols",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    for (index, item) in picker.items().iter().enumerate().skip(start).take(visible) {
+        let selected = index == picker.selected_index();
+        let base = if selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let highlight = base.add_modifier(Modifier::BOLD).fg(if selected {
+            Color::Black
+        } else {
+            Color::Yellow
+        });
+
+        let symbol = &item.location.symbol;
+        let label = format!("{:<7}", symbol.kind.label());
+        let mut spans = vec![Span::styled(label.clone(), base)];
+        for (char_index, c) in symbol.name.chars().enumerate() {
+            let style = if item.indices.contains(&char_index) {
+                highlight
+            } else {
+                base
+            };
+            spans.push(Span::styled(c.to_string(), style));
+        }
+
+        let location = match &item.location.path {
+            Some(path) => format!(
+                "{}:{}",
+                path.file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default(),
+                symbol.line + 1
+            ),
+            None => format!(":{}", symbol.line + 1),
+        };
+        let used = label.len() + symbol.name.chars().count();
+        let padding = (list_area.width as usize).saturating_sub(used + location.chars().count());
+        spans.push(Span::styled(" ".repeat(padding), base));
+        spans.push(Span::styled(
+            location,
+            if selected {
+                base
+            } else {
+                Style::default().fg(Color::DarkGray)
+            },
+        ));
+
+        lines.push(Line::from(spans));
+    }
+
+    frame.render_widget(Paragraph::new(lines), list_area);
+}
+
+pub fn render_clipboard_picker(frame: &mut Frame, picker: &ClipboardPicker, area: Rect) {
+    let visible_items = picker.items().len().clamp(1, PALETTE_MAX_ITEMS);
+    let height = (visible_items as u16 + 4).min(area.height);
+    let width = PALETTE_WIDTH.min(area.width);
+    let picker_area = Rect {
+        x: area.x + (area.width.saturating_sub(width)) / 2,
+        y: area.y + 1.min(area.height.saturating_sub(height)),
+        width,
+        height,
+    };
+
+    frame.render_widget(Clear, picker_area);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!(" {} ", picker.title()))
+        .title_bottom(" Ctrl+T pin ")
+        .border_style(Style::default().fg(Color::Cyan))
+        .style(Style::default().bg(Color::Rgb(30, 30, 30)));
+    let inner = block.inner(picker_area);
+    frame.render_widget(block, picker_area);
+
+    if inner.height == 0 {
+        return;
+    }
+
+    let input_line = Line::from(vec![
+        Span::styled("> ", Style::default().fg(Color::White)),
+        Span::styled(picker.query(), Style::default().fg(Color::Yellow)),
+    ]);
+    frame.render_widget(
+        Paragraph::new(input_line).style(Style::default().bg(Color::Rgb(40, 40, 40))),
+        Rect { height: 1, ..inner },
+    );
+
+    let cursor_x = inner.x + (2 + picker.query().chars().count()) as u16;
+    if cursor_x < inner.x + inner.width {
+        frame.set_cursor(cursor_x, inner.y);
+    }
+
+    let list_area = Rect {
+        y: inner.y + 1,
+        height: inner.height.saturating_sub(1),
+        ..inner
+    };
+    let visible = list_area.height as usize;
+    let start = picker
+        .selected_index()
+        .saturating_sub(visible.saturating_sub(1));
+
+    let mut lines = Vec::new();
+    if picker.items().is_empty() {
+        lines.push(Line::from(Span::styled(
+            "No matching entries",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    // Long entries are cut off at the edge of the picker.
+    let text_width = (list_area.width as usize).saturating_sub(2);
+    for (index, item) in picker.items().iter().enumerate().skip(start).take(visible) {
+        let selected = index == picker.selected_index();
+        let base = if selected {
+            Style::default().fg(Color::Black).bg(Color::Cyan)
+        } else {
+            Style::default().fg(Color::White)
+        };
+        let highlight = base.add_modifier(Modifier::BOLD).fg(if selected {
+            Color::Black
+        } else {
+            Color::Yellow
+        });
+
+        let marker = if item.pinned { "* " } else { "  " };
+        let mut spans = vec![Span::styled(marker, base)];
+        for (char_index, c) in item.preview.chars().enumerate().take(text_width) {
+            let style = if item.indices.contains(&char_index) {
+                highlight
+            } else {
+                base
+            };
+            spans.push(Span::styled(c.to_string(), style));
+        }
+        let used = item.preview.chars().count().min(text_width);
+        spans.push(Span::styled(" ".repeat(text_width - used), base));
+
+        lines.push(Line::from(spans));
+    }
+
+    frame.render_widget(Paragraph::new(lines), list_area);
+}
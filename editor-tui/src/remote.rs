@@ -0,0 +1,97 @@
+use editor_core::{EditorError, EditorState, RemoteClient, Result};
+use std::path::PathBuf;
+
+// A `file`, `file:line` or `file:line:column` argument. Lines and columns
+// are one-based on the command line, as compilers print them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileLocation {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl FileLocation {
+    pub fn parse(arg: &str) -> Self {
+        let mut path = arg;
+        let mut numbers = Vec::new();
+        while numbers.len() < 2 {
+            let Some((rest, number)) = path.rsplit_once(':') else {
+                break;
+            };
+            match number.parse::<usize>() {
+                Ok(number) if !rest.is_empty() => {
+                    numbers.insert(0, number);
+                    path = rest;
+                }
+                _ => break,
+            }
+        }
+        Self {
+            path: PathBuf::from(path),
+            line: numbers.first().copied().unwrap_or(1).saturating_sub(1),
+            column: numbers.get(1).copied().unwrap_or(1).saturating_sub(1),
+        }
+    }
+
+    pub fn open_in(&self, editor_state: &mut EditorState) -> Result<()> {
+        editor_state.open_at(self.path.clone(), self.line, self.column)
+    }
+}
+
+// `--remote FILE[:LINE[:COLUMN]]...` hands the files to a running editor
+// instead of starting another one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteArgs {
+    pub locations: Vec<FileLocation>,
+}
+
+impl RemoteArgs {
+    // Returns None when `--remote` isn't given.
+    pub fn parse<I>(args: I) -> Result<Option<Self>>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let args: Vec<String> = args.into_iter().collect();
+        if !args.iter().any(|arg| arg == "--remote") {
+            return Ok(None);
+        }
+
+        let mut locations = Vec::new();
+        let mut args = args.into_iter().filter(|arg| arg != "--remote");
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                locations.extend(args.by_ref().map(|arg| FileLocation::parse(&arg)));
+            } else if arg.starts_with("--") {
+                return Err(EditorError::InvalidOperation(format!(
+                    "unknown option '{}'\nusage: editor-tui --remote FILE[:LINE[:COLUMN]]...",
+                    arg
+                )));
+            } else {
+                locations.push(FileLocation::parse(&arg));
+            }
+        }
+        Ok(Some(Self { locations }))
+    }
+
+    // Makes the paths absolute, since the running editor may have been
+    // started somewhere else.
+    pub fn resolve(mut self) -> Result<Self> {
+        let current_dir = std::env::current_dir()?;
+        for location in &mut self.locations {
+            location.path = current_dir.join(&location.path);
+        }
+        Ok(self)
+    }
+
+    // Returns false when no editor is running, leaving the files for a new
+    // one to open.
+    pub fn send(&self) -> Result<bool> {
+        let Some(mut client) = RemoteClient::connect_to_running()? else {
+            return Ok(false);
+        };
+        for location in &self.locations {
+            client.open(&location.path, location.line, location.column)?;
+        }
+        Ok(true)
+    }
+}
@@ -1,6 +1,8 @@
 use crate::dialog::Dialog;
 use crate::menu::{MenuState, MenuType};
-use editor_core::EditorState;
+use editor_core::{
+    highlight_segments, DiagnosticSeverity, EditorState, FileStatus, HighlightKind, SplitRect,
+};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -9,6 +11,16 @@ use ratatui::{
     Frame,
 };
 
+const FILE_TREE_WIDTH: u16 = 30;
+const SYMBOL_OUTLINE_WIDTH: u16 = 32;
+const FOLD_MARKER_WIDTH: usize = 1;
+const FOLDED_SUFFIX: &str = " ⋯";
+const COMPLETION_MAX_ROWS: usize = 10;
+const COMPLETION_MAX_WIDTH: usize = 60;
+const HOVER_MAX_ROWS: usize = 12;
+const HOVER_MAX_WIDTH: usize = 72;
+const SELECTION_BACKGROUND: Color = Color::Rgb(38, 79, 120);
+
 pub struct Renderer {
     pub show_line_numbers: bool,
     pub show_status_bar: bool,
@@ -52,7 +64,7 @@ impl Renderer {
     pub fn render(
         &self,
         frame: &mut Frame,
-        editor_state: &EditorState,
+        editor_state: &mut EditorState,
         menu_state: &MenuState,
         dialog: Option<&Dialog>,
     ) {
@@ -85,7 +97,24 @@ impl Renderer {
         } else if editor_state.is_history_stats_open() {
             self.render_history_stats(frame, editor_state, editor_area);
         } else {
-            self.render_editor_area(frame, editor_state, editor_area);
+            let mut editor_area = editor_area;
+            if editor_state.is_file_tree_open() {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Length(FILE_TREE_WIDTH), Constraint::Min(1)])
+                    .split(editor_area);
+                self.render_file_tree(frame, editor_state, chunks[0]);
+                editor_area = chunks[1];
+            }
+            if editor_state.is_symbol_outline_open() {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(1), Constraint::Length(SYMBOL_OUTLINE_WIDTH)])
+                    .split(editor_area);
+                self.render_symbol_outline(frame, editor_state, chunks[1]);
+                editor_area = chunks[0];
+            }
+            self.render_splits(frame, editor_state, editor_area);
         }
 
         if let Some(status_area) = status_area {
@@ -101,40 +130,117 @@ impl Renderer {
         }
     }
 
-    fn render_editor_area(&self, frame: &mut Frame, editor_state: &EditorState, area: Rect) {
-        let (text_area, line_number_width) = if self.show_line_numbers {
+    // Each split gets a title row naming its file; only the focused split
+    // shows the terminal cursor and popups.
+    fn render_splits(&self, frame: &mut Frame, editor_state: &mut EditorState, area: Rect) {
+        let layout = editor_state.split_layout().clone();
+        if !layout.is_split() {
+            self.render_editor_area(frame, editor_state, area, true);
+            return;
+        }
+
+        for (pane, rect) in layout.pane_rects() {
+            let pane_area = split_area(area, rect);
+            if pane_area.width == 0 || pane_area.height < 2 {
+                continue;
+            }
+            let focused = pane == layout.focused();
+            let title_area = Rect::new(pane_area.x, pane_area.y, pane_area.width, 1);
+            let text_area = Rect::new(
+                pane_area.x,
+                pane_area.y + 1,
+                pane_area.width,
+                pane_area.height - 1,
+            );
+            editor_state.with_split_view(pane, |editor_state| {
+                let buffer = editor_state.current_buffer();
+                let name = buffer
+                    .file_path()
+                    .and_then(|path| path.file_name())
+                    .map_or("[No Name]".to_string(), |name| {
+                        name.to_string_lossy().to_string()
+                    });
+                let modified = if buffer.is_modified() { " ●" } else { "" };
+                let style = if focused {
+                    Style::default().fg(Color::Black).bg(Color::Cyan)
+                } else {
+                    Style::default().fg(Color::Gray).bg(Color::Rgb(50, 50, 50))
+                };
+                let title = format!(
+                    " {}{:width$}",
+                    name,
+                    modified,
+                    width = (pane_area.width as usize).saturating_sub(name.chars().count() + 1)
+                );
+                frame.render_widget(Paragraph::new(title).style(style), title_area);
+                self.render_editor_area(frame, editor_state, text_area, focused);
+            });
+        }
+    }
+
+    fn render_editor_area(
+        &self,
+        frame: &mut Frame,
+        editor_state: &EditorState,
+        area: Rect,
+        focused: bool,
+    ) {
+        let viewport = editor_state.get_virtual_viewport(area.height as usize);
+
+        let text_area = if self.show_line_numbers {
             let buffer = editor_state.current_buffer();
             let line_count = buffer.line_count();
-            let num_width = line_count.to_string().len().max(3) + 1;
+            let num_width = line_count.to_string().len().max(3) + FOLD_MARKER_WIDTH + 1;
 
             let chunks = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([Constraint::Length(num_width as u16), Constraint::Min(1)])
                 .split(area);
 
-            self.render_line_numbers(frame, editor_state, chunks[0]);
-            (chunks[1], num_width)
+            self.render_line_numbers(frame, editor_state, &viewport.line_numbers, chunks[0]);
+            chunks[1]
         } else {
-            (area, 0)
+            area
         };
 
-        self.render_text_buffer(frame, editor_state, text_area, line_number_width);
+        let cursor_position = self.render_text_buffer(
+            frame,
+            editor_state,
+            &viewport.line_numbers,
+            text_area,
+            focused,
+        );
+
+        if let Some((x, y)) = cursor_position.filter(|_| focused) {
+            if editor_state.is_completion_open() {
+                render_completion_popup(frame, editor_state, x, y);
+            } else if editor_state.hover_text().is_some() {
+                render_hover_popup(frame, editor_state, x, y);
+            }
+        }
     }
 
-    fn render_line_numbers(&self, frame: &mut Frame, editor_state: &EditorState, area: Rect) {
-        let buffer = editor_state.current_buffer();
-        let viewport_top = editor_state.viewport_top();
+    fn render_line_numbers(
+        &self,
+        frame: &mut Frame,
+        editor_state: &EditorState,
+        line_numbers: &[usize],
+        area: Rect,
+    ) {
         let viewport_height = area.height as usize;
+        let number_width = (area.width as usize).saturating_sub(FOLD_MARKER_WIDTH + 1);
 
         let mut lines = Vec::new();
         for i in 0..viewport_height {
-            let line_num = viewport_top + i;
-            if line_num < buffer.line_count() {
-                let line_text =
-                    format!("{:>width$} ", line_num + 1, width = area.width as usize - 1);
+            if let Some(&line_num) = line_numbers.get(i) {
+                let marker = fold_marker(editor_state, line_num);
+                let line_text = format!("{:>number_width$}{} ", line_num + 1, marker);
+                let color = editor_state
+                    .line_diagnostic_severity(line_num)
+                    .map_or(Color::DarkGray, diagnostic_color);
                 lines.push(Line::from(Span::styled(
                     line_text,
-                    Style::default().fg(Color::DarkGray),
+                    Style::default().fg(color),
                 )));
             } else {
                 lines.push(Line::from(Span::styled(
@@ -152,18 +258,17 @@ impl Renderer {
         &self,
         frame: &mut Frame,
         editor_state: &EditorState,
+        line_numbers: &[usize],
         area: Rect,
-        _line_number_width: usize,
-    ) {
+        focused: bool,
+    ) -> Option<(u16, u16)> {
         let buffer = editor_state.current_buffer();
-        let viewport_top = editor_state.viewport_top();
         let viewport_height = area.height as usize;
         let cursor = editor_state.cursor();
 
         let mut lines = Vec::new();
         for i in 0..viewport_height {
-            let line_num = viewport_top + i;
-            if line_num < buffer.line_count() {
+            if let Some(&line_num) = line_numbers.get(i) {
                 if let Ok(line_text) = buffer.line(line_num) {
                     let is_cursor_line = line_num == cursor.line;
                     let style = if is_cursor_line {
@@ -172,7 +277,29 @@ impl Renderer {
                         Style::default()
                     };
 
-                    lines.push(Line::from(Span::styled(line_text, style)));
+                    let folded = editor_state.is_line_folded(line_num);
+                    let text = if folded {
+                        line_text.trim_end_matches('\n')
+                    } else {
+                        &line_text
+                    };
+                    let spans = buffer.highlight_line(line_num);
+                    let diagnostics = editor_state.line_diagnostics(line_num);
+                    let mut segments: Vec<Span> = Vec::new();
+                    let mut column = 0;
+                    for (text, kind) in highlight_segments(text, &spans) {
+                        let style = kind.map_or(style, |kind| style.patch(syntax_style(kind)));
+                        let width = text.chars().count();
+                        segments.extend(underline_diagnostics(text, column, style, &diagnostics));
+                        column += width;
+                    }
+                    if folded {
+                        segments.push(Span::styled(
+                            FOLDED_SUFFIX,
+                            style.fg(Color::DarkGray).add_modifier(Modifier::DIM),
+                        ));
+                    }
+                    lines.push(Line::from(segments).style(style));
                 } else {
                     lines.push(Line::from(""));
                 }
@@ -183,14 +310,209 @@ impl Renderer {
 
         let paragraph = Paragraph::new(lines);
         frame.render_widget(paragraph, area);
+        paint_selections(frame, editor_state, line_numbers, area);
 
-        let cursor_screen_line = cursor.line.saturating_sub(viewport_top);
+        let cursor_screen_line = line_numbers
+            .iter()
+            .position(|&line| line == cursor.line)
+            .unwrap_or(viewport_height);
         let cursor_x = area.x + cursor.column as u16;
         let cursor_y = area.y + cursor_screen_line as u16;
 
         if cursor_screen_line < viewport_height && cursor_x < area.x + area.width {
-            frame.set_cursor(cursor_x, cursor_y);
+            if focused
+                && !editor_state.is_file_tree_focused()
+                && !editor_state.is_symbol_outline_focused()
+            {
+                frame.set_cursor(cursor_x, cursor_y);
+            }
+            Some((cursor_x, cursor_y))
+        } else {
+            None
+        }
+    }
+
+    fn render_file_tree(&self, frame: &mut Frame, editor_state: &EditorState, area: Rect) {
+        let tree = match editor_state.file_tree() {
+            Some(tree) => tree,
+            None => return,
+        };
+        let focused = editor_state.is_file_tree_focused();
+
+        let title = tree
+            .root()
+            .file_name()
+            .and_then(|n| n.to_str())
+            .map(|n| format!(" {} ", n))
+            .unwrap_or_else(|| " Files ".to_string());
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(title)
+            .border_style(Style::default().fg(if focused {
+                Color::Cyan
+            } else {
+                Color::DarkGray
+            }));
+
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+
+        let visible_height = inner_area.height as usize;
+        let selected = tree.selected_index();
+        let scroll = if visible_height > 0 && selected >= visible_height {
+            selected + 1 - visible_height
+        } else {
+            0
+        };
+
+        let mut lines = Vec::new();
+        for (index, entry) in tree
+            .entries()
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(visible_height)
+        {
+            let icon = if !entry.is_dir() {
+                "  "
+            } else if entry.expanded {
+                "▾ "
+            } else {
+                "▸ "
+            };
+
+            let status = editor_state.file_status(&entry.path);
+            let marker = match status {
+                FileStatus::Modified => " ●",
+                FileStatus::Open if !entry.is_dir() => " ○",
+                _ => "",
+            };
+
+            let mut style = match status {
+                FileStatus::Modified => Style::default().fg(Color::Yellow),
+                _ if entry.is_dir() => Style::default().fg(Color::Blue),
+                _ => Style::default().fg(Color::White),
+            };
+
+            if index == selected {
+                style = style.bg(if focused {
+                    Color::Rgb(60, 60, 90)
+                } else {
+                    Color::Rgb(40, 40, 40)
+                });
+                if focused {
+                    style = style.add_modifier(Modifier::BOLD);
+                }
+            }
+
+            let text = format!(
+                "{}{}{}{}",
+                "  ".repeat(entry.depth),
+                icon,
+                entry.name,
+                marker
+            );
+            let padded = format!("{:width$}", text, width = inner_area.width as usize);
+            lines.push(Line::from(Span::styled(padded, style)));
+        }
+
+        if lines.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "(empty)",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines);
+        frame.render_widget(paragraph, inner_area);
+    }
+
+    fn render_symbol_outline(&self, frame: &mut Frame, editor_state: &EditorState, area: Rect) {
+        let outline = match editor_state.symbol_outline() {
+            Some(outline) => outline,
+            None => return,
+        };
+        let focused = editor_state.is_symbol_outline_focused();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Outline ")
+            .border_style(Style::default().fg(if focused {
+                Color::Cyan
+            } else {
+                Color::DarkGray
+            }));
+
+        let inner_area = block.inner(area);
+        frame.render_widget(block, area);
+
+        let visible_height = inner_area.height as usize;
+        let selected = outline.selected_index();
+        let scroll = if visible_height > 0 && selected >= visible_height {
+            selected + 1 - visible_height
+        } else {
+            0
+        };
+
+        let mut lines = Vec::new();
+        for (index, entry) in outline
+            .entries()
+            .iter()
+            .enumerate()
+            .skip(scroll)
+            .take(visible_height)
+        {
+            let icon = if !entry.has_children {
+                "  "
+            } else if entry.expanded {
+                "▾ "
+            } else {
+                "▸ "
+            };
+
+            let mut label_style = Style::default().fg(Color::Magenta);
+            let mut name_style = Style::default().fg(Color::White);
+            if index == selected {
+                let bg = if focused {
+                    Color::Rgb(60, 60, 90)
+                } else {
+                    Color::Rgb(40, 40, 40)
+                };
+                label_style = label_style.bg(bg);
+                name_style = name_style.bg(bg);
+                if focused {
+                    name_style = name_style.add_modifier(Modifier::BOLD);
+                }
+            }
+
+            let prefix = format!(
+                "{}{}{} ",
+                "  ".repeat(entry.symbol.depth),
+                icon,
+                entry.symbol.kind.label()
+            );
+            let used = prefix.chars().count();
+            let name = format!(
+                "{:width$}",
+                entry.symbol.name,
+                width = (inner_area.width as usize).saturating_sub(used)
+            );
+            lines.push(Line::from(vec![
+                Span::styled(prefix, label_style),
+                Span::styled(name, name_style),
+            ]));
         }
+
+        if lines.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "(no symbols)",
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines);
+        frame.render_widget(paragraph, inner_area);
     }
 
     fn render_status_bar(&self, frame: &mut Frame, editor_state: &EditorState, area: Rect) {
@@ -213,14 +535,56 @@ impl Renderer {
             ""
         };
 
-        let cursor_info = format!(" {}:{} ", cursor.line + 1, cursor.column + 1);
+        let (errors, warnings) = editor_state.diagnostic_counts();
+        let diagnostic_info = if errors + warnings > 0 {
+            format!(" E:{} W:{} |", errors, warnings)
+        } else {
+            String::new()
+        };
+
+        let pending_keys = editor_state
+            .vim_pending_keys()
+            .map(|keys| format!(" {} |", keys))
+            .unwrap_or_default();
+
+        let cursor_info = format!(
+            "{}{} {} | {}:{} ",
+            pending_keys,
+            diagnostic_info,
+            editor_state.language().name,
+            cursor.line + 1,
+            cursor.column + 1
+        );
+
+        let vim_mode = editor_state
+            .vim_mode_indicator()
+            .map(|mode| format!(" {} |", mode))
+            .unwrap_or_default();
+        let recording = match editor_state.recording_macro_name() {
+            Some("") => " REC |".to_string(),
+            Some(name) => format!(" REC {} |", name),
+            None => String::new(),
+        };
 
         let status_message = editor_state.status_message();
-        let left_text = if status_message.is_empty() {
-            format!(" {}{}{}{}", file_name, modified, read_only, overwrite)
+        let left_text = if let Some(command_line) = editor_state.vim_command_line() {
+            format!(" {}", command_line)
+        } else if status_message.is_empty() {
+            match editor_state.diagnostic_at_cursor() {
+                Some(diagnostic) => format!(
+                    " {}{}{}{} | {}",
+                    file_name,
+                    modified,
+                    read_only,
+                    overwrite,
+                    diagnostic.summary()
+                ),
+                None => format!(" {}{}{}{}", file_name, modified, read_only, overwrite),
+            }
         } else {
             format!(" {} ", status_message)
         };
+        let left_text = format!("{}{}{}", vim_mode, recording, left_text);
 
         let left_width = left_text.len();
         let right_width = cursor_info.len();
@@ -865,3 +1229,246 @@ impl Default for Renderer {
         Self::new()
     }
 }
+
+fn split_area(area: Rect, rect: SplitRect) -> Rect {
+    let scale = |offset: u16, length: u16, fraction: f32| {
+        offset + (length as f32 * fraction).round() as u16
+    };
+    let x = scale(area.x, area.width, rect.x);
+    let y = scale(area.y, area.height, rect.y);
+    let right = scale(area.x, area.width, rect.x + rect.width);
+    let bottom = scale(area.y, area.height, rect.y + rect.height);
+    Rect::new(x, y, right - x, bottom - y)
+}
+
+fn fold_marker(editor_state: &EditorState, line: usize) -> char {
+    if editor_state.is_line_folded(line) {
+        '▸'
+    } else if editor_state.is_line_foldable(line) {
+        '▾'
+    } else {
+        ' '
+    }
+}
+
+pub fn diagnostic_color(severity: DiagnosticSeverity) -> Color {
+    match severity {
+        DiagnosticSeverity::Error => Color::Red,
+        DiagnosticSeverity::Warning => Color::Yellow,
+        DiagnosticSeverity::Information => Color::Blue,
+        DiagnosticSeverity::Hint => Color::Cyan,
+    }
+}
+
+// Splits a highlighted segment starting at `column` so the characters covered
+// by a diagnostic are underlined in its severity colour.
+// Shades every selection and shows the secondary cursors as reversed cells;
+// the terminal's own cursor marks the primary one.
+fn paint_selections(
+    frame: &mut Frame,
+    editor_state: &EditorState,
+    line_numbers: &[usize],
+    area: Rect,
+) {
+    let buffer = editor_state.current_buffer();
+    let selections = editor_state.selections();
+    let right = area.x + area.width;
+
+    for (row, &line) in line_numbers.iter().enumerate().take(area.height as usize) {
+        let y = area.y + row as u16;
+        let line_len = buffer.line_len(line).unwrap_or(0);
+        for selection in &selections {
+            let Some((from, to)) = selection.columns_on_line(line, line_len) else {
+                continue;
+            };
+            for column in from..to {
+                let x = area.x + column as u16;
+                if x >= right {
+                    break;
+                }
+                frame
+                    .buffer_mut()
+                    .get_mut(x, y)
+                    .set_bg(SELECTION_BACKGROUND);
+            }
+        }
+    }
+
+    for cursor in editor_state.cursors().iter().skip(1) {
+        let Some(row) = line_numbers.iter().position(|&line| line == cursor.line) else {
+            continue;
+        };
+        let x = area.x + cursor.column as u16;
+        if row < area.height as usize && x < right {
+            let cell = frame.buffer_mut().get_mut(x, area.y + row as u16);
+            cell.set_style(cell.style().add_modifier(Modifier::REVERSED));
+        }
+    }
+}
+
+fn underline_diagnostics(
+    text: String,
+    column: usize,
+    style: Style,
+    diagnostics: &[(usize, usize, DiagnosticSeverity)],
+) -> Vec<Span<'static>> {
+    if diagnostics.is_empty() {
+        return vec![Span::styled(text, style)];
+    }
+
+    let mut spans: Vec<(String, Option<DiagnosticSeverity>)> = Vec::new();
+    for (offset, ch) in text.chars().enumerate() {
+        let severity = diagnostics
+            .iter()
+            .filter(|(start, end, _)| *start <= column + offset && column + offset < *end)
+            .map(|(_, _, severity)| *severity)
+            .min();
+        match spans.last_mut() {
+            Some((segment, segment_severity)) if *segment_severity == severity => segment.push(ch),
+            _ => spans.push((ch.to_string(), severity)),
+        }
+    }
+
+    spans
+        .into_iter()
+        .map(|(text, severity)| match severity {
+            Some(severity) => Span::styled(
+                text,
+                style
+                    .fg(diagnostic_color(severity))
+                    .add_modifier(Modifier::UNDERLINED),
+            ),
+            None => Span::styled(text, style),
+        })
+        .collect()
+}
+
+// Popups open below the cursor, or above it when there is no room left.
+fn popup_area(frame: &Frame, x: u16, y: u16, width: usize, height: usize) -> Rect {
+    let screen = frame.size();
+    let width = (width as u16).min(screen.width);
+    let height = (height as u16).min(screen.height);
+    let x = x.min(screen.width.saturating_sub(width));
+    let y = if y + 1 + height <= screen.height {
+        y + 1
+    } else {
+        y.saturating_sub(height)
+    };
+    Rect::new(x, y, width, height)
+}
+
+fn render_completion_popup(frame: &mut Frame, editor_state: &EditorState, x: u16, y: u16) {
+    let Some(menu) = editor_state.completion_menu() else {
+        return;
+    };
+
+    let rows = menu.items().len().min(COMPLETION_MAX_ROWS);
+    let offset = menu.selected_index().saturating_sub(rows.saturating_sub(1));
+    let kind_width = menu
+        .items()
+        .iter()
+        .filter_map(|entry| entry.item.kind.as_ref())
+        .map(|kind| kind.chars().count())
+        .max()
+        .unwrap_or(0);
+    let content_width = menu
+        .items()
+        .iter()
+        .map(|entry| {
+            let detail = entry
+                .item
+                .detail
+                .as_ref()
+                .map_or(0, |d| d.chars().count() + 2);
+            kind_width + 1 + entry.item.label.chars().count() + detail
+        })
+        .max()
+        .unwrap_or(0)
+        .min(COMPLETION_MAX_WIDTH);
+
+    let start_column = x.saturating_sub((menu.query().chars().count() + kind_width + 2) as u16);
+    let area = popup_area(frame, start_column, y, content_width + 2, rows + 2);
+
+    let lines: Vec<Line> = menu
+        .items()
+        .iter()
+        .enumerate()
+        .skip(offset)
+        .take(rows)
+        .map(|(index, entry)| {
+            let base = if index == menu.selected_index() {
+                Style::default().bg(Color::Blue).fg(Color::White)
+            } else {
+                Style::default()
+            };
+            let kind = entry.item.kind.as_deref().unwrap_or("");
+            let mut spans = vec![Span::styled(
+                format!("{:>kind_width$} ", kind),
+                base.fg(Color::DarkGray),
+            )];
+            for (i, ch) in entry.item.label.chars().enumerate() {
+                let style = if entry.indices.contains(&i) {
+                    base.fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    base
+                };
+                spans.push(Span::styled(ch.to_string(), style));
+            }
+            if let Some(detail) = &entry.item.detail {
+                spans.push(Span::styled(
+                    format!("  {}", detail),
+                    base.fg(Color::Gray).add_modifier(Modifier::DIM),
+                ));
+            }
+            Line::from(spans).style(base)
+        })
+        .collect();
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL)),
+        area,
+    );
+}
+
+fn render_hover_popup(frame: &mut Frame, editor_state: &EditorState, x: u16, y: u16) {
+    let Some(text) = editor_state.hover_text() else {
+        return;
+    };
+
+    let lines: Vec<&str> = text.lines().take(HOVER_MAX_ROWS).collect();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(HOVER_MAX_WIDTH);
+    let area = popup_area(frame, x, y, width + 2, lines.len() + 2);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+            .block(Block::default().borders(Borders::ALL).title(" Hover ")),
+        area,
+    );
+}
+
+pub fn syntax_style(kind: HighlightKind) -> Style {
+    let style = Style::default();
+    match kind {
+        HighlightKind::Comment => style.fg(Color::DarkGray).add_modifier(Modifier::ITALIC),
+        HighlightKind::Keyword => style.fg(Color::Magenta),
+        HighlightKind::String => style.fg(Color::Green),
+        HighlightKind::Escape => style.fg(Color::LightCyan),
+        HighlightKind::Number | HighlightKind::Constant => style.fg(Color::LightYellow),
+        HighlightKind::Function => style.fg(Color::LightBlue),
+        HighlightKind::Type => style.fg(Color::Yellow),
+        HighlightKind::Variable => style.fg(Color::White),
+        HighlightKind::Property => style.fg(Color::Cyan),
+        HighlightKind::Attribute => style.fg(Color::LightMagenta),
+        HighlightKind::Operator | HighlightKind::Punctuation => style.fg(Color::Gray),
+        HighlightKind::Heading => style.fg(Color::LightBlue).add_modifier(Modifier::BOLD),
+        HighlightKind::Literal => style.fg(Color::Green),
+        HighlightKind::Link => style.fg(Color::Cyan).add_modifier(Modifier::UNDERLINED),
+    }
+}
@@ -0,0 +1,73 @@
+use editor_tui::batch::{BatchArgs, ScriptSource};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use tempfile::TempDir;
+
+fn parse(args: &[&str]) -> editor_core::Result<Option<BatchArgs>> {
+    BatchArgs::parse(args.iter().map(|arg| arg.to_string()))
+}
+
+fn editor_tui(home: &std::path::Path) -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_editor-tui"));
+    command.env("HOME", home).current_dir(home);
+    command
+}
+
+#[test]
+fn test_batch_args_parsing() {
+    assert_eq!(parse(&[]).unwrap(), None);
+    assert_eq!(parse(&["notes.txt"]).unwrap(), None);
+
+    assert_eq!(
+        parse(&["--script", "fix.json", "a.txt", "--", "--b.txt"]).unwrap(),
+        Some(BatchArgs {
+            source: ScriptSource::File(PathBuf::from("fix.json")),
+            files: vec![PathBuf::from("a.txt"), PathBuf::from("--b.txt")],
+        })
+    );
+    assert_eq!(
+        parse(&["a.txt", "--batch"]).unwrap(),
+        Some(BatchArgs {
+            source: ScriptSource::Stdin,
+            files: vec![PathBuf::from("a.txt")],
+        })
+    );
+
+    assert!(parse(&["--script"]).is_err());
+    assert!(parse(&["--batch", "--script", "fix.json"]).is_err());
+    assert!(parse(&["--batch", "--verbose"]).is_err());
+}
+
+#[test]
+fn test_batch_mode_exit_status() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path();
+    fs::write(home.join("notes.txt"), "b  \na\n").unwrap();
+    fs::write(
+        home.join("tidy.json"),
+        r#"["trim_trailing_whitespace", {"replace_all": {"find": "b", "replace": "c"}}]"#,
+    )
+    .unwrap();
+    fs::write(home.join("broken.json"), r#"[{"goto_line": 99}]"#).unwrap();
+
+    let status = editor_tui(home)
+        .args(["--script", "tidy.json", "notes.txt"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    assert_eq!(
+        fs::read_to_string(home.join("notes.txt")).unwrap(),
+        "c\na\n"
+    );
+
+    let output = editor_tui(home)
+        .args(["--script", "broken.json", "notes.txt"])
+        .output()
+        .unwrap();
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("command 1 of 1"));
+
+    let output = editor_tui(home).args(["--script"]).output().unwrap();
+    assert_eq!(output.status.code(), Some(2));
+}
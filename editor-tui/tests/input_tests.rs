@@ -1,8 +1,8 @@
 use crossterm::event::{
     Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
-use editor_core::Command;
-use editor_tui::input::{InputAction, InputHandler};
+use editor_core::{Command, CommandRegistry, EditorState, Key, KeyPress, Keymaps};
+use editor_tui::input::{InputAction, InputHandler, KeyBindings};
 
 #[test]
 fn test_basic_character_input() {
@@ -766,3 +766,423 @@ fn test_super_key_does_not_trigger_save() {
     let action = handler.handle_event(super_s, false, false, false, false);
     assert!(action.is_none());
 }
+
+#[test]
+fn test_toggle_file_tree_shortcut() {
+    let mut handler = InputHandler::new();
+    let event = Event::Key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::CONTROL));
+    let action = handler.handle_event(event, false, false, false, false);
+
+    assert!(matches!(
+        action,
+        Some(InputAction::Command(Command::ToggleFileTree))
+    ));
+}
+
+#[test]
+fn test_file_tree_navigation_keys() {
+    let mut handler = InputHandler::new();
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    assert!(matches!(
+        handler.handle_file_tree_event(key(KeyCode::Down)),
+        Some(InputAction::Command(Command::FileTreeNavigateNext))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_event(key(KeyCode::Up)),
+        Some(InputAction::Command(Command::FileTreeNavigatePrevious))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_event(key(KeyCode::Right)),
+        Some(InputAction::Command(Command::FileTreeExpand))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_event(key(KeyCode::Left)),
+        Some(InputAction::Command(Command::FileTreeCollapse))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_event(key(KeyCode::Enter)),
+        Some(InputAction::Command(Command::FileTreeActivate))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_event(key(KeyCode::Esc)),
+        Some(InputAction::Command(Command::FocusEditor))
+    ));
+}
+
+#[test]
+fn test_file_tree_file_operation_keys() {
+    let mut handler = InputHandler::new();
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    assert!(matches!(
+        handler.handle_file_tree_event(key(KeyCode::Char('a'))),
+        Some(InputAction::FileTreeNewFile)
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_event(Event::Key(KeyEvent::new(
+            KeyCode::Char('A'),
+            KeyModifiers::SHIFT
+        ))),
+        Some(InputAction::FileTreeNewDirectory)
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_event(key(KeyCode::Char('r'))),
+        Some(InputAction::FileTreeRename)
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_event(key(KeyCode::Delete)),
+        Some(InputAction::FileTreeDelete)
+    ));
+}
+
+#[test]
+fn test_file_tree_does_not_insert_text() {
+    let mut handler = InputHandler::new();
+    let event = Event::Key(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::NONE));
+
+    assert!(handler.handle_file_tree_event(event).is_none());
+}
+
+#[test]
+fn test_command_palette_shortcut() {
+    let mut handler = InputHandler::new();
+
+    let ctrl_shift_p = Event::Key(KeyEvent::new(
+        KeyCode::Char('P'),
+        KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+    ));
+    assert!(matches!(
+        handler.handle_event(ctrl_shift_p, false, false, false, false),
+        Some(InputAction::OpenCommandPalette)
+    ));
+
+    let f1 = Event::Key(KeyEvent::new(KeyCode::F(1), KeyModifiers::NONE));
+    assert!(matches!(
+        handler.handle_event(f1, false, false, false, false),
+        Some(InputAction::ShowHelp)
+    ));
+}
+
+#[test]
+fn test_command_palette_keys() {
+    let mut handler = InputHandler::new();
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    assert!(matches!(
+        handler.handle_palette_event(key(KeyCode::Char('g'))),
+        Some(InputAction::PaletteInsertChar('g'))
+    ));
+    assert!(matches!(
+        handler.handle_palette_event(key(KeyCode::Backspace)),
+        Some(InputAction::PaletteBackspace)
+    ));
+    assert!(matches!(
+        handler.handle_palette_event(key(KeyCode::Down)),
+        Some(InputAction::PaletteNext)
+    ));
+    assert!(matches!(
+        handler.handle_palette_event(key(KeyCode::Up)),
+        Some(InputAction::PalettePrevious)
+    ));
+    assert!(matches!(
+        handler.handle_palette_event(key(KeyCode::Enter)),
+        Some(InputAction::PaletteConfirm)
+    ));
+    assert!(matches!(
+        handler.handle_palette_event(key(KeyCode::Esc)),
+        Some(InputAction::PaletteCancel)
+    ));
+}
+
+#[test]
+fn test_completion_keys_fall_back_to_editor() {
+    let mut handler = InputHandler::new();
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    assert!(matches!(
+        handler.handle_completion_event(key(KeyCode::Down)),
+        Some(InputAction::Command(Command::CompletionNext))
+    ));
+    assert!(matches!(
+        handler.handle_completion_event(key(KeyCode::Up)),
+        Some(InputAction::Command(Command::CompletionPrevious))
+    ));
+    assert!(matches!(
+        handler.handle_completion_event(key(KeyCode::Tab)),
+        Some(InputAction::Command(Command::CompletionAccept))
+    ));
+    assert!(matches!(
+        handler.handle_completion_event(key(KeyCode::Esc)),
+        Some(InputAction::Command(Command::CompletionCancel))
+    ));
+    assert!(matches!(
+        handler.handle_completion_event(key(KeyCode::Char('x'))),
+        Some(InputAction::Command(Command::InsertChar('x')))
+    ));
+    assert!(matches!(
+        handler.handle_completion_event(key(KeyCode::Backspace)),
+        Some(InputAction::Command(Command::Backspace))
+    ));
+}
+
+#[test]
+fn test_snippet_keys_walk_tabstops() {
+    let mut handler = InputHandler::new();
+    let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+
+    assert!(matches!(
+        handler.handle_snippet_event(key(KeyCode::Tab, KeyModifiers::NONE)),
+        Some(InputAction::Command(Command::NextTabstop))
+    ));
+    assert!(matches!(
+        handler.handle_snippet_event(key(KeyCode::BackTab, KeyModifiers::SHIFT)),
+        Some(InputAction::Command(Command::PreviousTabstop))
+    ));
+    assert!(matches!(
+        handler.handle_snippet_event(key(KeyCode::Esc, KeyModifiers::NONE)),
+        Some(InputAction::Command(Command::ExitSnippet))
+    ));
+    assert!(matches!(
+        handler.handle_snippet_event(key(KeyCode::Char('x'), KeyModifiers::NONE)),
+        Some(InputAction::Command(Command::InsertChar('x')))
+    ));
+    assert!(matches!(
+        handler.handle_event(
+            key(KeyCode::Char('j'), KeyModifiers::ALT),
+            false,
+            false,
+            false,
+            false
+        ),
+        Some(InputAction::Command(Command::ExpandSnippet))
+    ));
+}
+
+#[test]
+fn test_language_server_shortcuts() {
+    let mut handler = InputHandler::new();
+    let ctrl_space = Event::Key(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::CONTROL));
+    assert!(matches!(
+        handler.handle_event(ctrl_space, false, false, false, false),
+        Some(InputAction::Command(Command::TriggerCompletion))
+    ));
+    let f8 = Event::Key(KeyEvent::new(KeyCode::F(8), KeyModifiers::NONE));
+    assert!(matches!(
+        handler.handle_event(f8, false, false, false, false),
+        Some(InputAction::Command(Command::NextDiagnostic))
+    ));
+    let rename = Event::Key(KeyEvent::new(
+        KeyCode::Char('r'),
+        KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+    ));
+    assert!(matches!(
+        handler.handle_event(rename, false, false, false, false),
+        Some(InputAction::RunCommand("tools.rename_symbol"))
+    ));
+}
+
+#[test]
+fn test_history_and_file_tree_focus_shortcuts() {
+    let mut handler = InputHandler::new();
+
+    let ctrl_t = Event::Key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL));
+    assert!(matches!(
+        handler.handle_event(ctrl_t, false, false, false, false),
+        Some(InputAction::Command(Command::OpenHistoryBrowser))
+    ));
+
+    let ctrl_shift_e = || {
+        Event::Key(KeyEvent::new(
+            KeyCode::Char('E'),
+            KeyModifiers::CONTROL | KeyModifiers::SHIFT,
+        ))
+    };
+    assert!(matches!(
+        handler.handle_event(ctrl_shift_e(), false, false, false, false),
+        Some(InputAction::Command(Command::FocusFileTree))
+    ));
+    assert!(matches!(
+        handler.handle_file_tree_event(ctrl_shift_e()),
+        Some(InputAction::Command(Command::FocusEditor))
+    ));
+}
+
+#[test]
+fn test_dialog_scroll_keys() {
+    let mut handler = InputHandler::new();
+    let key = |code| Event::Key(KeyEvent::new(code, KeyModifiers::NONE));
+
+    assert!(matches!(
+        handler.handle_event(key(KeyCode::Down), false, false, false, true),
+        Some(InputAction::DialogScrollDown(1))
+    ));
+    assert!(matches!(
+        handler.handle_event(key(KeyCode::PageUp), false, false, false, true),
+        Some(InputAction::DialogScrollUp(_))
+    ));
+}
+
+#[test]
+fn test_custom_keymap_chord() {
+    let keymaps = Keymaps::from_toml_str(
+        "[editor]\n\"Ctrl+K Ctrl+C\" = \"tools.toggle_comment\"\n",
+        CommandRegistry::builtin(),
+    )
+    .unwrap();
+    let mut handler = InputHandler::new().with_keymaps(keymaps);
+    let ctrl = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+
+    assert!(handler
+        .handle_event(ctrl('k'), false, false, false, false)
+        .is_none());
+    assert_eq!(handler.pending_keys().as_deref(), Some("Ctrl+K"));
+    assert!(matches!(
+        handler.handle_event(ctrl('c'), false, false, false, false),
+        Some(InputAction::Command(Command::ToggleLineComment))
+    ));
+    assert!(handler.pending_keys().is_none());
+
+    let plain = Event::Key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+    assert!(matches!(
+        handler.handle_event(plain, false, false, false, false),
+        Some(InputAction::Command(Command::InsertChar('x')))
+    ));
+}
+
+#[test]
+fn test_broken_chord_is_discarded() {
+    let keymaps = Keymaps::from_toml_str(
+        "[editor]\n\"Ctrl+K Ctrl+C\" = \"tools.toggle_comment\"\n",
+        CommandRegistry::builtin(),
+    )
+    .unwrap();
+    let mut handler = InputHandler::new().with_keymaps(keymaps);
+
+    let ctrl_k = Event::Key(KeyEvent::new(KeyCode::Char('k'), KeyModifiers::CONTROL));
+    let x = Event::Key(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+    assert!(handler
+        .handle_event(ctrl_k, false, false, false, false)
+        .is_none());
+    assert!(handler
+        .handle_event(x, false, false, false, false)
+        .is_none());
+}
+
+#[test]
+fn test_keymap_binding_to_command_with_arguments() {
+    let mut keymaps = Keymaps::builtin().clone();
+    keymaps
+        .merge_toml_str(
+            "[editor]\n\"Alt+W\" = \"tools.hard_wrap\"\n\"F4\" = \"help.about\"\n",
+            CommandRegistry::builtin(),
+        )
+        .unwrap();
+    let mut handler = InputHandler::new().with_keymaps(keymaps);
+
+    let alt_w = Event::Key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::ALT));
+    assert!(matches!(
+        handler.handle_event(alt_w, false, false, false, false),
+        Some(InputAction::RunCommand("tools.hard_wrap"))
+    ));
+    let f4 = Event::Key(KeyEvent::new(KeyCode::F(4), KeyModifiers::NONE));
+    assert!(matches!(
+        handler.handle_event(f4, false, false, false, false),
+        Some(InputAction::RunCommand("help.about"))
+    ));
+}
+
+#[test]
+fn test_keymap_binding_passes_its_arguments() {
+    let mut keymaps = Keymaps::builtin().clone();
+    keymaps
+        .merge_toml_str(
+            "[editor]\n\"Alt+1\" = \"tools.play_macro_times tidy 3\"\n",
+            CommandRegistry::builtin(),
+        )
+        .unwrap();
+    let mut handler = InputHandler::new().with_keymaps(keymaps);
+
+    let alt_1 = Event::Key(KeyEvent::new(KeyCode::Char('1'), KeyModifiers::ALT));
+    assert!(matches!(
+        handler.handle_event(alt_1, false, false, false, false),
+        Some(InputAction::Command(Command::PlayMacro { name, count: 3 })) if name == "tidy"
+    ));
+}
+
+#[test]
+fn test_custom_quit_key_rebinds_every_mode() {
+    let mut handler = InputHandler::new().with_key_bindings(KeyBindings {
+        quit_key: KeyCode::Char('x'),
+    });
+    let ctrl = |c| Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL));
+
+    assert!(matches!(
+        handler.handle_event(ctrl('x'), false, false, false, false),
+        Some(InputAction::Quit)
+    ));
+    assert!(matches!(
+        handler.handle_event(ctrl('x'), true, false, false, false),
+        Some(InputAction::Quit)
+    ));
+    assert!(!matches!(
+        handler.handle_event(ctrl('q'), false, false, false, false),
+        Some(InputAction::Quit)
+    ));
+}
+
+#[test]
+fn test_vim_keys_bypass_keymap_in_normal_mode() {
+    let mut handler = InputHandler::new();
+    let mut editor = EditorState::new();
+    let key = |code, modifiers| Event::Key(KeyEvent::new(code, modifiers));
+
+    assert!(handler
+        .handle_vim_event(&key(KeyCode::Char('d'), KeyModifiers::NONE), &editor)
+        .is_none());
+
+    editor.set_vim_enabled(true);
+    assert!(matches!(
+        handler.handle_vim_event(&key(KeyCode::Char('$'), KeyModifiers::SHIFT), &editor),
+        Some(InputAction::Command(Command::VimKey(_)))
+    ));
+    assert!(matches!(
+        handler.handle_vim_event(&key(KeyCode::Char('r'), KeyModifiers::CONTROL), &editor),
+        Some(InputAction::Command(Command::VimKey(_)))
+    ));
+    assert!(handler
+        .handle_vim_event(&key(KeyCode::Char('s'), KeyModifiers::CONTROL), &editor)
+        .is_none());
+
+    editor
+        .execute_command(Command::VimKey(KeyPress::plain(Key::Char('i'))))
+        .unwrap();
+    assert!(handler
+        .handle_vim_event(&key(KeyCode::Char('d'), KeyModifiers::NONE), &editor)
+        .is_none());
+    assert!(handler
+        .handle_vim_event(&key(KeyCode::Esc, KeyModifiers::NONE), &editor)
+        .is_some());
+}
+
+#[test]
+fn test_bracketed_paste_inserts_text() {
+    let mut handler = InputHandler::new();
+    let paste = || Event::Paste("if x {\n}".to_string());
+
+    assert!(matches!(
+        handler.handle_event(paste(), false, false, false, false),
+        Some(InputAction::Command(Command::InsertText(text))) if text == "if x {\n}"
+    ));
+    assert!(matches!(
+        handler.handle_event(paste(), false, false, false, true),
+        Some(InputAction::DialogPaste(_))
+    ));
+    assert!(handler
+        .handle_event(paste(), false, false, true, false)
+        .is_none());
+    assert!(matches!(
+        handler.handle_palette_event(paste()),
+        Some(InputAction::PalettePaste(_))
+    ));
+}
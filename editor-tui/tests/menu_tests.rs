@@ -199,3 +199,95 @@ fn test_menu_state_get_selected_action() {
     let action = state.get_selected_action();
     assert!(action.is_some());
 }
+
+#[test]
+fn test_menus_are_generated_from_registry() {
+    use editor_core::{CommandRegistry, CommandSpec};
+    use editor_tui::menu::create_menu;
+
+    let registry = CommandRegistry::builtin();
+
+    for menu_type in MenuType::all() {
+        let menu = create_menu(menu_type);
+        let labels: Vec<&str> = menu
+            .items
+            .iter()
+            .filter(|item| !item.is_separator())
+            .map(|item| item.label.as_str())
+            .collect();
+        let expected: Vec<&str> = registry
+            .menu_items(menu_type.category())
+            .iter()
+            .map(|spec: &&CommandSpec| spec.title)
+            .collect();
+
+        assert_eq!(labels, expected);
+        assert!(menu
+            .items
+            .iter()
+            .all(|item| item.is_separator() || item.action.is_some()));
+    }
+}
+
+#[test]
+fn test_menu_separators_between_groups() {
+    use editor_tui::menu::create_menu;
+
+    let menu = create_menu(MenuType::File);
+    let labels: Vec<&str> = menu.items.iter().map(|item| item.label.as_str()).collect();
+
+    assert_eq!(
+        labels,
+        vec![
+            "New File",
+            "Open File",
+            "-",
+            "Save",
+            "Save As",
+            "-",
+            "Close File",
+            "Quit"
+        ]
+    );
+    assert_eq!(menu.items[0].shortcut, Some("Ctrl+N".to_string()));
+}
+
+#[test]
+fn test_menu_actions_for_registry_commands() {
+    use editor_core::{Command, CommandRegistry};
+    use editor_tui::menu::menu_action_for;
+
+    let registry = CommandRegistry::builtin();
+
+    assert!(matches!(
+        menu_action_for(registry.get("tools.join_lines").unwrap()),
+        Some(MenuAction::ExecuteCommand(Command::JoinLines))
+    ));
+    assert!(matches!(
+        menu_action_for(registry.get("search.goto_line").unwrap()),
+        Some(MenuAction::GotoLine)
+    ));
+    assert!(matches!(
+        menu_action_for(registry.get("bookmarks.add_named").unwrap()),
+        Some(MenuAction::PromptCommand("bookmarks.add_named"))
+    ));
+    assert!(matches!(
+        menu_action_for(registry.get("view.command_palette").unwrap()),
+        Some(MenuAction::CommandPalette)
+    ));
+}
+
+#[test]
+fn test_help_lines_include_registry_keybindings() {
+    use editor_core::CommandRegistry;
+    use editor_tui::dialog::help_lines;
+
+    let lines = help_lines(CommandRegistry::builtin());
+
+    assert!(lines
+        .iter()
+        .any(|(key, desc, _)| key == "Ctrl+Shift+P" && desc == "Command Palette"));
+    assert!(lines
+        .iter()
+        .any(|(key, desc, header)| key.is_empty() && desc == "Bookmarks" && *header));
+}
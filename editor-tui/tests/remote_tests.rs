@@ -0,0 +1,90 @@
+#![cfg(unix)]
+
+use editor_core::{CursorPosition, EditorState, RemoteServer};
+use editor_tui::remote::{FileLocation, RemoteArgs};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+use tempfile::TempDir;
+
+fn location(path: &str, line: usize, column: usize) -> FileLocation {
+    FileLocation {
+        path: PathBuf::from(path),
+        line,
+        column,
+    }
+}
+
+#[test]
+fn test_file_locations_parse_lines_and_columns() {
+    assert_eq!(
+        FileLocation::parse("src/main.rs"),
+        location("src/main.rs", 0, 0)
+    );
+    assert_eq!(
+        FileLocation::parse("src/main.rs:12"),
+        location("src/main.rs", 11, 0)
+    );
+    assert_eq!(
+        FileLocation::parse("src/main.rs:12:5"),
+        location("src/main.rs", 11, 4)
+    );
+    assert_eq!(FileLocation::parse("a:b:3"), location("a:b", 2, 0));
+    assert_eq!(FileLocation::parse("notes:"), location("notes:", 0, 0));
+    assert_eq!(FileLocation::parse(":7"), location(":7", 0, 0));
+}
+
+#[test]
+fn test_remote_args_parsing() {
+    let parse = |args: &[&str]| RemoteArgs::parse(args.iter().map(|arg| arg.to_string()));
+
+    assert_eq!(parse(&["notes.txt"]).unwrap(), None);
+    assert_eq!(
+        parse(&["--remote", "a.rs:3", "b.rs", "--", "--c.rs"]).unwrap(),
+        Some(RemoteArgs {
+            locations: vec![
+                location("a.rs", 2, 0),
+                location("b.rs", 0, 0),
+                location("--c.rs", 0, 0)
+            ],
+        })
+    );
+    assert!(parse(&["--remote", "--batch"]).is_err());
+
+    let resolved = parse(&["--remote", "a.rs"])
+        .unwrap()
+        .unwrap()
+        .resolve()
+        .unwrap();
+    assert!(resolved.locations[0].path.is_absolute());
+}
+
+#[test]
+fn test_remote_hands_files_to_the_running_editor() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path();
+    let path = home.join("notes.txt");
+    fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+    let mut server = RemoteServer::bind(home.join(".config/editor-rs/session-1.sock")).unwrap();
+    let mut editor = EditorState::new();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_editor-tui"))
+        .env("HOME", home)
+        .current_dir(home)
+        .args(["--remote", "notes.txt:2:3"])
+        .spawn()
+        .unwrap();
+
+    let status = loop {
+        server.poll(&mut editor);
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        thread::sleep(Duration::from_millis(5));
+    };
+    assert!(status.success());
+    assert_eq!(editor.file_path(), Some(path.as_path()));
+    assert_eq!(*editor.cursor(), CursorPosition::new(1, 2));
+}
@@ -42,13 +42,13 @@ fn test_terminal_size() {
 fn test_renderer_basic_rendering() {
     let backend = TestBackend::new(80, 24);
     let mut terminal = Terminal::new(backend).unwrap();
-    let editor_state = EditorState::new();
+    let mut editor_state = EditorState::new();
     let renderer = Renderer::new();
 
     let result = terminal.draw(|frame| {
         renderer.render(
             frame,
-            &editor_state,
+            &mut editor_state,
             &editor_tui::menu::MenuState::new(),
             None,
         );
@@ -83,7 +83,7 @@ fn test_renderer_with_content() {
     let result = terminal.draw(|frame| {
         renderer.render(
             frame,
-            &editor_state,
+            &mut editor_state,
             &editor_tui::menu::MenuState::new(),
             None,
         );
@@ -96,13 +96,13 @@ fn test_renderer_with_content() {
 fn test_renderer_with_line_numbers() {
     let backend = TestBackend::new(80, 24);
     let mut terminal = Terminal::new(backend).unwrap();
-    let editor_state = EditorState::new();
+    let mut editor_state = EditorState::new();
     let renderer = Renderer::new().with_line_numbers(true);
 
     let result = terminal.draw(|frame| {
         renderer.render(
             frame,
-            &editor_state,
+            &mut editor_state,
             &editor_tui::menu::MenuState::new(),
             None,
         );
@@ -115,13 +115,13 @@ fn test_renderer_with_line_numbers() {
 fn test_renderer_without_line_numbers() {
     let backend = TestBackend::new(80, 24);
     let mut terminal = Terminal::new(backend).unwrap();
-    let editor_state = EditorState::new();
+    let mut editor_state = EditorState::new();
     let renderer = Renderer::new().with_line_numbers(false);
 
     let result = terminal.draw(|frame| {
         renderer.render(
             frame,
-            &editor_state,
+            &mut editor_state,
             &editor_tui::menu::MenuState::new(),
             None,
         );
@@ -142,7 +142,7 @@ fn test_renderer_status_bar() {
     let result = terminal.draw(|frame| {
         renderer.render(
             frame,
-            &editor_state,
+            &mut editor_state,
             &editor_tui::menu::MenuState::new(),
             None,
         );
@@ -168,7 +168,7 @@ fn test_renderer_cursor_position() {
     let result = terminal.draw(|frame| {
         renderer.render(
             frame,
-            &editor_state,
+            &mut editor_state,
             &editor_tui::menu::MenuState::new(),
             None,
         );
@@ -181,7 +181,7 @@ fn test_renderer_cursor_position() {
 fn test_renderer_history_browser_closed() {
     let backend = TestBackend::new(80, 24);
     let mut terminal = Terminal::new(backend).unwrap();
-    let editor_state = EditorState::new();
+    let mut editor_state = EditorState::new();
     let renderer = Renderer::new();
 
     assert!(!editor_state.is_history_browser_open());
@@ -189,7 +189,7 @@ fn test_renderer_history_browser_closed() {
     let result = terminal.draw(|frame| {
         renderer.render(
             frame,
-            &editor_state,
+            &mut editor_state,
             &editor_tui::menu::MenuState::new(),
             None,
         );
@@ -225,7 +225,7 @@ fn test_renderer_history_browser_render_empty() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -268,7 +268,7 @@ fn test_renderer_history_browser_layout_rendering() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -316,7 +316,7 @@ fn test_renderer_history_commit_display_timestamp_formats() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -360,7 +360,7 @@ fn test_renderer_history_commit_display_hash_short_form() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -404,7 +404,7 @@ fn test_renderer_history_commit_display_message_truncation() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -451,7 +451,7 @@ fn test_renderer_history_commit_display_selection_highlighting() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -495,7 +495,7 @@ fn test_renderer_history_commit_display_visual_indicators() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -560,7 +560,7 @@ fn test_renderer_history_diff_view_rendering() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -616,7 +616,7 @@ fn test_renderer_history_diff_view_line_indicators() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -665,7 +665,7 @@ fn test_renderer_history_diff_view_scrolling() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -707,7 +707,7 @@ fn test_renderer_history_diff_view_line_numbers() {
         let draw_result = terminal.draw(|frame| {
             renderer.render(
                 frame,
-                &editor_state,
+                &mut editor_state,
                 &editor_tui::menu::MenuState::new(),
                 None,
             );
@@ -716,3 +716,319 @@ fn test_renderer_history_diff_view_line_numbers() {
         assert!(draw_result.is_ok());
     }
 }
+
+#[test]
+fn test_renderer_with_file_tree() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    fs::create_dir(temp_dir.path().join("src")).unwrap();
+    let file_path = temp_dir.path().join("notes.txt");
+    fs::write(&file_path, "hello\n").unwrap();
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut editor_state = EditorState::from_file(file_path).unwrap();
+    editor_state
+        .open_file_tree(temp_dir.path().to_path_buf())
+        .unwrap();
+    editor_state
+        .execute_command(Command::InsertChar('x'))
+        .unwrap();
+    let renderer = Renderer::new();
+
+    terminal
+        .draw(|frame| {
+            renderer.render(
+                frame,
+                &mut editor_state,
+                &editor_tui::menu::MenuState::new(),
+                None,
+            );
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| -> String { (0..30).map(|x| buffer.get(x, y).symbol()).collect() };
+
+    assert!(row(2).contains("▸ src"));
+    assert!(row(3).contains("notes.txt ●"));
+}
+
+#[test]
+fn test_render_command_palette() {
+    use editor_core::{CommandPalette, CommandRegistry};
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut editor_state = EditorState::new();
+    let renderer = Renderer::new();
+    let mut palette = CommandPalette::new(CommandRegistry::builtin(), &editor_state);
+    palette.set_query("dupl");
+
+    terminal
+        .draw(|frame| {
+            renderer.render(
+                frame,
+                &mut editor_state,
+                &editor_tui::menu::MenuState::new(),
+                None,
+            );
+            editor_tui::palette::render_command_palette(frame, &palette, frame.size());
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| -> String { (0..80).map(|x| buffer.get(x, y).symbol()).collect() };
+
+    assert!(row(1).contains("Command Palette"));
+    assert!(row(2).contains("> dupl"));
+    assert!(row(3).contains("Tools: Duplicate Line"));
+    assert!(row(3).contains("Ctrl+D"));
+}
+
+#[test]
+fn test_render_command_palette_prompt() {
+    use editor_core::{CommandPalette, CommandRegistry};
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut palette =
+        CommandPalette::for_command(CommandRegistry::builtin(), "search.goto_line").unwrap();
+    palette.set_query("42");
+
+    terminal
+        .draw(|frame| {
+            editor_tui::palette::render_command_palette(frame, &palette, frame.size());
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| -> String { (0..80).map(|x| buffer.get(x, y).symbol()).collect() };
+
+    assert!(row(1).contains("Go to Line"));
+    assert!(row(2).contains("Line number: 42"));
+}
+
+#[test]
+fn test_renderer_applies_syntax_highlighting() {
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let path = temp_dir.path().join("main.rs");
+    fs::write(&path, "fn main() {}\n").unwrap();
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let mut editor_state = EditorState::from_file(path).unwrap();
+    let renderer = Renderer::new();
+
+    terminal
+        .draw(|frame| {
+            renderer.render(
+                frame,
+                &mut editor_state,
+                &editor_tui::menu::MenuState::new(),
+                None,
+            );
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let keyword_style = editor_tui::renderer::syntax_style(editor_core::HighlightKind::Keyword);
+    let found = (0..buffer.area.height).any(|y| {
+        (0..buffer.area.width.saturating_sub(1)).any(|x| {
+            buffer.get(x, y).symbol() == "f"
+                && buffer.get(x + 1, y).symbol() == "n"
+                && Some(buffer.get(x, y).fg) == keyword_style.fg
+        })
+    });
+    assert!(found);
+}
+
+#[test]
+fn test_renderer_shows_fold_markers_and_hides_folded_lines() {
+    let mut editor_state = EditorState::new();
+    editor_state
+        .current_buffer_mut()
+        .set_content("outer\n    inner a\n    inner b\nnext\n".to_string())
+        .unwrap();
+    editor_state.execute_command(Command::FoldCode).unwrap();
+
+    let backend = TestBackend::new(40, 8);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let renderer = Renderer::new();
+    terminal
+        .draw(|frame| {
+            renderer.render(
+                frame,
+                &mut editor_state,
+                &editor_tui::menu::MenuState::new(),
+                None,
+            );
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let rows: Vec<String> = (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer.get(x, y).symbol())
+                .collect()
+        })
+        .collect();
+
+    assert!(rows[1].starts_with("  1▸ outer ⋯"), "{:?}", rows);
+    assert!(rows[2].starts_with("  4  next"), "{:?}", rows);
+    assert!(!rows.iter().any(|row| row.contains("inner")));
+}
+
+#[test]
+fn test_renderer_with_symbol_outline_and_picker() {
+    use editor_core::{SymbolPicker, SymbolScope};
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let file_path = temp_dir.path().join("shapes.rs");
+    fs::write(
+        &file_path,
+        "struct Circle;\n\nimpl Circle {\n    fn area(&self) {}\n}\n",
+    )
+    .unwrap();
+    let mut editor_state = EditorState::from_file(file_path).unwrap();
+    editor_state
+        .execute_command(Command::ToggleSymbolOutline)
+        .unwrap();
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let renderer = Renderer::new();
+    let mut picker = SymbolPicker::new(SymbolScope::File, editor_state.file_symbol_locations());
+    picker.set_query("area");
+
+    terminal
+        .draw(|frame| {
+            renderer.render(
+                frame,
+                &mut editor_state,
+                &editor_tui::menu::MenuState::new(),
+                None,
+            );
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| -> String { (48..80).map(|x| buffer.get(x, y).symbol()).collect() };
+    assert!(row(1).contains("Outline"));
+    assert!(row(2).contains("struct Circle"));
+    assert!(row(3).contains("▾ impl Circle"));
+    assert!(row(4).contains("method area"));
+
+    terminal
+        .draw(|frame| {
+            editor_tui::palette::render_symbol_picker(frame, &picker, frame.size());
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16| -> String { (0..80).map(|x| buffer.get(x, y).symbol()).collect() };
+    assert!(row(1).contains("Go to Symbol in File"));
+    assert!(row(2).contains("@ area"));
+    assert!(row(3).contains("method area"));
+    assert!(row(3).contains("shapes.rs:4"));
+}
+
+#[test]
+fn test_renderer_draws_each_split() {
+    use editor_core::SplitDirection;
+
+    let temp_dir = tempfile::TempDir::new().unwrap();
+    let a_path = temp_dir.path().join("a.txt");
+    let b_path = temp_dir.path().join("b.txt");
+    fs::write(&a_path, "alpha\n").unwrap();
+    fs::write(&b_path, "beta\n").unwrap();
+    let mut editor_state = EditorState::from_file(a_path).unwrap();
+    editor_state
+        .execute_command(Command::Split {
+            direction: SplitDirection::Vertical,
+        })
+        .unwrap();
+    editor_state.execute_command(Command::Open(b_path)).unwrap();
+
+    let backend = TestBackend::new(80, 24);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let renderer = Renderer::new();
+    terminal
+        .draw(|frame| {
+            renderer.render(
+                frame,
+                &mut editor_state,
+                &editor_tui::menu::MenuState::new(),
+                None,
+            );
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let row = |y: u16, from: u16, to: u16| -> String {
+        (from..to).map(|x| buffer.get(x, y).symbol()).collect()
+    };
+    assert!(row(1, 0, 40).starts_with(" a.txt"));
+    assert!(row(1, 40, 80).starts_with(" b.txt"));
+    assert!(row(2, 0, 40).contains("alpha"));
+    assert!(row(2, 40, 80).contains("beta"));
+    assert_eq!(terminal.get_cursor().unwrap(), (45, 2));
+}
+
+#[test]
+fn test_renderer_shades_every_selection_and_shows_secondary_cursors() {
+    let mut editor_state = EditorState::new();
+    editor_state
+        .current_buffer_mut()
+        .set_content("foo bar\nfoo\n".to_string())
+        .unwrap();
+    editor_state
+        .execute_command(Command::SelectAllOccurrences)
+        .unwrap();
+
+    let backend = TestBackend::new(40, 8);
+    let mut terminal = Terminal::new(backend).unwrap();
+    let renderer = Renderer::new().with_line_numbers(false);
+    terminal
+        .draw(|frame| {
+            renderer.render(
+                frame,
+                &mut editor_state,
+                &editor_tui::menu::MenuState::new(),
+                None,
+            );
+        })
+        .unwrap();
+
+    let buffer = terminal.backend().buffer();
+    let y = (0..buffer.area.height)
+        .find(|&y| buffer.get(0, y).symbol() == "f")
+        .unwrap();
+    let selected = buffer.get(0, y).bg;
+    assert_eq!(buffer.get(2, y).bg, selected);
+    assert_ne!(buffer.get(4, y).bg, selected);
+    assert_eq!(buffer.get(0, y + 1).bg, selected);
+
+    // The secondary cursor sits after the second "foo".
+    assert!(buffer
+        .get(3, y + 1)
+        .modifier
+        .contains(ratatui::style::Modifier::REVERSED));
+}
+
+#[test]
+fn test_osc52_sequence_encodes_clipboard_text() {
+    use crossterm::Command as _;
+    use editor_tui::clipboard::{base64_encode, CopyToClipboard};
+
+    assert_eq!(base64_encode(b""), "");
+    assert_eq!(base64_encode(b"f"), "Zg==");
+    assert_eq!(base64_encode(b"fo"), "Zm8=");
+    assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+
+    let mut sequence = String::new();
+    CopyToClipboard("héllo\n")
+        .write_ansi(&mut sequence)
+        .unwrap();
+    assert_eq!(sequence, "\x1b]52;c;aMOpbGxvCg==\x07");
+}